@@ -0,0 +1,232 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Measures the time taken to write and read a request, to write a batch of
+//! requests into one buffer, to read a large reply with and without copying
+//! its data, and to write requests containing long lists.
+//!
+//! This is used to compare the overhead of the `tracing` feature, which should
+//! be none at all when the feature is disabled:
+//!
+//! ```sh
+//! cargo bench --bench serialization
+//! cargo bench --bench serialization --features tracing
+//! ```
+//!
+//! Lists of integers are written in bulk; the long list benchmarks also write
+//! the same list one element at a time for comparison.
+
+use std::{hint::black_box, time::Instant};
+
+use xrb::{
+	unit::Px,
+	x11::{
+		reply::{GetProperty, GetPropertyRef},
+		request::{
+			CoordinateMode,
+			DataFormat,
+			DataList,
+			DrawPoints,
+			GrabCursor,
+			ModifyProperty,
+			ModifyPropertyMode,
+			WarpCursor,
+			WarpSourceDimension,
+		},
+	},
+	Atom,
+	Coords,
+	CurrentableTime,
+	CursorAppearance,
+	CursorEventMask,
+	Drawable,
+	FreezeMode,
+	GraphicsContext,
+	Window,
+};
+use xrbk::{BytesMut, Readable, ReadableRef, Writable, X11Size};
+
+const ITERATIONS: u32 = 1_000_000;
+/// The number of requests in each batch of [`WarpCursor`] requests.
+const BATCH_SIZE: usize = 10_000;
+/// The number of iterations for the batch of [`WarpCursor`] requests.
+const BATCH_ITERATIONS: u32 = 100;
+/// The number of iterations for the 1 MiB [`GetProperty`] reply.
+const PROPERTY_ITERATIONS: u32 = 1_000;
+/// The number of iterations for the requests containing long lists.
+const LIST_ITERATIONS: u32 = 1_000;
+
+fn main() {
+	bench_request();
+	bench_batch();
+	bench_property();
+	bench_lists();
+}
+
+fn bench_request() {
+	let request = GrabCursor {
+		owner_events: true,
+		grab_window: Window::new(0x0040_0001),
+		event_mask: CursorEventMask::BUTTON_PRESS | CursorEventMask::ANY_MOTION,
+		cursor_freeze: FreezeMode::Unfrozen,
+		keyboard_freeze: FreezeMode::Frozen,
+		confine_to: None,
+		cursor_appearance: Some(CursorAppearance::new(0x0040_0002)),
+		time: CurrentableTime::CurrentTime,
+	};
+	let mut bytes = Vec::with_capacity(24);
+
+	let start = Instant::now();
+	for _ in 0..ITERATIONS {
+		bytes.clear();
+		black_box(&request).write_to(&mut bytes).unwrap();
+	}
+	let write = start.elapsed() / ITERATIONS;
+
+	let start = Instant::now();
+	for _ in 0..ITERATIONS {
+		// The major opcode is not read by `read_from`.
+		black_box(GrabCursor::read_from(&mut black_box(&bytes[1..])).unwrap());
+	}
+	let read = start.elapsed() / ITERATIONS;
+
+	println!("write GrabCursor: {write:?}");
+	println!("read GrabCursor: {read:?}");
+}
+
+fn bench_batch() {
+	let requests: Vec<_> = (0..BATCH_SIZE)
+		.map(|i| WarpCursor {
+			source: None,
+			destination: Some(Window::new(0x0040_0001)),
+			source_coords: Coords::new(Px(0), Px(0)),
+			source_width: WarpSourceDimension::FillRemaining,
+			source_height: WarpSourceDimension::FillRemaining,
+			coords: Coords::new(Px(i as i16), Px(-(i as i16))),
+		})
+		.collect();
+
+	// Writing each request to a new `Vec` and copying it into the batch.
+	let mut batch = BytesMut::new();
+	let start = Instant::now();
+	for _ in 0..BATCH_ITERATIONS {
+		batch = BytesMut::new();
+
+		for request in black_box(&requests) {
+			let mut bytes = vec![];
+			request.write_to(&mut bytes).unwrap();
+
+			batch.extend_from_slice(&bytes);
+		}
+	}
+	let copied = start.elapsed() / BATCH_ITERATIONS;
+	black_box(&batch);
+
+	let start = Instant::now();
+	for _ in 0..BATCH_ITERATIONS {
+		batch = BytesMut::new();
+
+		for request in black_box(&requests) {
+			request.write_into_exact(&mut batch).unwrap();
+		}
+	}
+	let direct = start.elapsed() / BATCH_ITERATIONS;
+	black_box(&batch);
+
+	// Reusing the buffer between batches.
+	let start = Instant::now();
+	for _ in 0..BATCH_ITERATIONS {
+		batch.clear();
+
+		for request in black_box(&requests) {
+			request.write_into_exact(&mut batch).unwrap();
+		}
+	}
+	let reused = start.elapsed() / BATCH_ITERATIONS;
+
+	println!("write {BATCH_SIZE} WarpCursor via Vec: {copied:?}");
+	println!("write {BATCH_SIZE} WarpCursor write_into_exact: {direct:?}");
+	println!("write {BATCH_SIZE} WarpCursor write_into_exact (reused buffer): {reused:?}");
+}
+
+fn bench_property() {
+	let reply = GetProperty {
+		sequence: 1,
+		format: Some(DataFormat::I8),
+		r#type: Some(Atom::new(31)),
+		bytes_remaining: 0,
+		value: DataList::I8((0..1 << 20).map(|i: i32| i as i8).collect()),
+	};
+	let mut bytes = vec![];
+	reply.write_to(&mut bytes).unwrap();
+
+	let start = Instant::now();
+	for _ in 0..PROPERTY_ITERATIONS {
+		// The first byte of a reply is not read by `read_from`.
+		black_box(GetProperty::read_from(&mut black_box(&bytes[1..])).unwrap());
+	}
+	let owned = start.elapsed() / PROPERTY_ITERATIONS;
+
+	let start = Instant::now();
+	for _ in 0..PROPERTY_ITERATIONS {
+		black_box(GetPropertyRef::read_ref(black_box(&bytes[1..])).unwrap());
+	}
+	let borrowed = start.elapsed() / PROPERTY_ITERATIONS;
+
+	println!("read 1 MiB GetProperty: {owned:?}");
+	println!("read 1 MiB GetPropertyRef: {borrowed:?}");
+}
+
+fn bench_lists() {
+	let request = ModifyProperty {
+		modify_mode: ModifyPropertyMode::Replace,
+		target: Window::new(0x0040_0001),
+		property: Atom::new(39),
+		r#type: Atom::new(31),
+		data: DataList::I8((0..1 << 16).map(|i: i32| i as i8).collect()),
+	};
+	let DataList::I8(data) = &request.data else {
+		unreachable!()
+	};
+	let mut bytes = Vec::with_capacity(request.x11_size());
+
+	let start = Instant::now();
+	for _ in 0..LIST_ITERATIONS {
+		bytes.clear();
+		black_box(&request).write_to(&mut bytes).unwrap();
+	}
+	let bulk = start.elapsed() / LIST_ITERATIONS;
+
+	let start = Instant::now();
+	for _ in 0..LIST_ITERATIONS {
+		bytes.clear();
+
+		for x in black_box(data) {
+			x.write_to(&mut bytes).unwrap();
+		}
+	}
+	let per_element = start.elapsed() / LIST_ITERATIONS;
+
+	println!("write 64 KiB ModifyProperty: {bulk:?}");
+	println!("write 64 KiB one element at a time: {per_element:?}");
+
+	let request = DrawPoints {
+		coordinate_mode: CoordinateMode::Drawable,
+		target: Drawable::new(0x0040_0001),
+		graphics_context: GraphicsContext::new(0x0040_0002),
+		points: (0..10_000)
+			.map(|i| Coords::new(Px(i as i16), Px(-(i as i16))))
+			.collect(),
+	};
+	let mut bytes = Vec::with_capacity(request.x11_size());
+
+	let start = Instant::now();
+	for _ in 0..LIST_ITERATIONS {
+		bytes.clear();
+		black_box(&request).write_to(&mut bytes).unwrap();
+	}
+	let points = start.elapsed() / LIST_ITERATIONS;
+
+	println!("write 10000-point DrawPoints: {points:?}");
+}
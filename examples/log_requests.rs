@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Prints the names of the requests in a capture file.
+//!
+//! The capture file must contain the bytes sent by an X client after its
+//! connection setup, such as those recorded by a proxy between the client and
+//! the X server.
+//!
+//! ```sh
+//! cargo run --example log_requests -- <capture file> [--little-endian]
+//! ```
+
+use std::{env, fs, process};
+
+use xrb::x11::registry::lookup_request;
+use xrbk::{ByteOrder, Ordered};
+
+fn main() {
+	let mut args = env::args().skip(1);
+
+	let Some(path) = args.next() else {
+		eprintln!("usage: log_requests <capture file> [--little-endian]");
+		process::exit(1);
+	};
+	let order = match args.next().as_deref() {
+		Some("--little-endian") => ByteOrder::LittleEndian,
+		_ => ByteOrder::BigEndian,
+	};
+
+	let capture = fs::read(&path).unwrap_or_else(|error| {
+		eprintln!("failed to read {path}: {error}");
+		process::exit(1);
+	});
+
+	let mut remaining = &capture[..];
+	let mut sequence: u16 = 0;
+
+	while remaining.len() >= 4 {
+		let major_opcode = remaining[0];
+
+		let length_bytes = [remaining[2], remaining[3]];
+		let length = match order {
+			ByteOrder::BigEndian => u16::from_be_bytes(length_bytes),
+			ByteOrder::LittleEndian => u16::from_le_bytes(length_bytes),
+		};
+
+		// A length of `0` is only used by the BIG-REQUESTS extension.
+		if length == 0 {
+			eprintln!("requests with extended lengths are not supported");
+			process::exit(1);
+		}
+
+		let size = usize::from(length) * 4;
+		if size > remaining.len() {
+			eprintln!("the capture file ends partway through a request");
+			process::exit(1);
+		}
+
+		let (request, rest) = remaining.split_at(size);
+		remaining = rest;
+		sequence = sequence.wrapping_add(1);
+
+		let Some(descriptor) = lookup_request(major_opcode, None) else {
+			println!("#{sequence}: unknown request (major opcode {major_opcode})");
+			continue;
+		};
+
+		let status = match descriptor.read {
+			_ if length < descriptor.min_length => "too short",
+
+			Some(read) => match read(&mut Ordered::new(&request[1..], order)) {
+				Ok(_) => "decoded",
+				Err(_) => "failed to decode",
+			},
+
+			None => "not decodable",
+		};
+
+		println!(
+			"#{sequence}: {} ({} bytes, {}{status})",
+			descriptor.name,
+			size,
+			if descriptor.has_reply {
+				"has reply, "
+			} else {
+				""
+			},
+		);
+	}
+}
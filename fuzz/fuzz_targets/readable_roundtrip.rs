@@ -0,0 +1,64 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Reads every message type listed by [`xrb::all_message_types`] from
+//! arbitrary bytes, and checks that any message which is read successfully
+//! can be written and read back as an equal message.
+//!
+//! Run with `cargo fuzz run readable_roundtrip`.
+
+#![no_main]
+
+use std::fmt::Debug;
+
+use libfuzzer_sys::fuzz_target;
+use xrbk::{Readable, Writable};
+
+/// Checks that writing what is read from `bytes[skip..]` and reading it back
+/// again reads an equal message.
+///
+/// `skip` is the number of bytes at the start of the message which are read
+/// before the type of the message is known.
+fn round_trip<T: Readable + Writable + PartialEq + Debug>(name: &str, bytes: &[u8], skip: usize) {
+	let Some(mut bytes) = bytes.get(skip..) else {
+		return;
+	};
+
+	let Ok(message) = T::read_from(&mut bytes) else {
+		return;
+	};
+
+	let mut written = vec![];
+	if message.write_to(&mut written).is_err() {
+		return;
+	}
+
+	let reread = T::read_from(&mut &written[skip..])
+		.unwrap_or_else(|error| panic!("{name} could not be read back: {error}"));
+
+	assert_eq!(message, reread, "{name} changed when read back");
+}
+
+macro_rules! round_trip_all {
+	(
+		requests: [$($Request:ident),+$(,)?],
+		replies: [$($Reply:ident),+$(,)?],
+		events: [$($Event:ident),+$(,)?],
+		errors: [$($Error:ident),+$(,)?]$(,)?
+	) => {
+		#[allow(deprecated)]
+		fn round_trip_all(bytes: &[u8]) {
+			use xrb::x11::{error, event, reply, request};
+
+			$(round_trip::<request::$Request>(stringify!($Request), bytes, 1);)+
+			$(round_trip::<reply::$Reply>(stringify!($Reply), bytes, 1);)+
+			$(round_trip::<event::$Event>(stringify!($Event), bytes, 1);)+
+			$(round_trip::<error::$Error>(stringify!($Error), bytes, 2);)+
+		}
+	};
+}
+
+xrb::all_message_types!(round_trip_all);
+
+fuzz_target!(|bytes: &[u8]| round_trip_all(bytes));
@@ -0,0 +1,591 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Property-based round-trip testing of messages.
+//!
+//! [`Arbitrary`] is implemented here for the leaf types whose values are
+//! restricted by the protocol; types without such restrictions derive it
+//! where they are defined, with
+//! `#[cfg_attr(test, derive(proptest_derive::Arbitrary))]`.
+//!
+//! The restrictions respected here are:
+//! - resource IDs and [atoms] are never `0`, nor do they use the top three
+//!   bits; resource IDs are additionally never `1`. Both of those values have
+//!   special meanings in wrappers such as [`FocusWindow`].
+//! - [`Keycode`]s are in the range `8..=255`.
+//! - [`Button`]s and [`Timestamp`]s are never `0`, which means [`Any`] and
+//!   [`CurrentTime`] respectively.
+//! - masks never contain bits which are not defined for them.
+//!
+//! Message types are registered for testing with [`round_trip_tests!`].
+//!
+//! [atoms]: Atom
+//! [`FocusWindow`]: crate::FocusWindow
+//! [`Any`]: crate::Any::Any
+//! [`CurrentTime`]: crate::CurrentableTime::CurrentTime
+
+use std::{fmt::Debug, ops::RangeInclusive};
+
+use proptest::{
+	arbitrary::{any, any_with, Arbitrary},
+	collection::vec,
+	prop_assert_eq,
+	prop_oneof,
+	strategy::{BoxedStrategy, Just, Strategy},
+	test_runner::TestCaseError,
+};
+use xrbk::{testing::FragmentedBuf, Buf, Readable, Writable, X11Size};
+
+use crate::{
+	set::{
+		DurationOrDefault,
+		KeyboardOptions,
+		KeyboardOptionsBuilder,
+		Led,
+		LedMode,
+		PercentOrDefault,
+		PitchOrDefault,
+	},
+	unit::{Hz, Ms, Percentage, Px, Sec, SignedPercentage},
+	visual::VisualId,
+	x11::{
+		reply::{GetProperty, KeyMapping, QueryExtension},
+		request::{BitPlane, DataList},
+	},
+	AnyModifierKeyMask,
+	AsciiString,
+	Atom,
+	Button,
+	Colormap,
+	CursorAppearance,
+	CursorEventMask,
+	DeviceEventMask,
+	Drawable,
+	EventMask,
+	Font,
+	Fontable,
+	GraphicsContext,
+	HostAddress,
+	HostFamily,
+	Keycode,
+	Keysym,
+	ModifierKeyMask,
+	ModifierMap,
+	ModifierMask,
+	Pixmap,
+	Rectangle,
+	Timestamp,
+	Window,
+};
+
+/// The maximum sizes of the fragments which written messages are split into
+/// by [`assert_round_trip`].
+const FRAGMENT_SIZES: [usize; 3] = [1, 3, 7];
+
+/// Asserts that the given `message` is read back unchanged after it has been
+/// written, and that its [`X11Size`] is the number of bytes written.
+///
+/// The first byte is skipped when reading, as it is not read by the
+/// [`Readable`] implementations of [requests] or [replies].
+///
+/// The [`PartialEq`] implementations of [replies], [events], and [errors]
+/// ignore their sequence numbers, so the message which was read is also
+/// written again and its bytes compared with those first written.
+///
+/// The message is also read back from a [`FragmentedBuf`] split into fragments
+/// of each of the [`FRAGMENT_SIZES`], so that every [`Readable`]
+/// implementation tested here is checked not to assume that a buffer's bytes
+/// are contiguous.
+///
+/// [requests]: crate::message::Request
+/// [replies]: crate::message::Reply
+/// [events]: crate::message::Event
+/// [errors]: crate::message::Error
+pub fn assert_round_trip<Message>(message: &Message) -> Result<(), TestCaseError>
+where
+	Message: X11Size + Readable + Writable + PartialEq + Debug,
+{
+	let mut bytes = vec![];
+	message
+		.write_to(&mut bytes)
+		.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	prop_assert_eq!(bytes.len(), message.x11_size());
+
+	let buf = &mut &bytes[1..];
+	let read = Message::read_from(buf).map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	prop_assert_eq!(&read, message);
+	prop_assert_eq!(buf.len(), 0, "not every byte written was read");
+	prop_assert_eq!(&rewrite(&read)?, &bytes, "not rewritten identically");
+
+	for max in FRAGMENT_SIZES {
+		let buf = &mut FragmentedBuf::new(&bytes[1..], max);
+		let read =
+			Message::read_from(buf).map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+		prop_assert_eq!(&read, message, "read from fragments of up to {} bytes", max);
+		prop_assert_eq!(buf.remaining(), 0, "not every byte written was read");
+		prop_assert_eq!(
+			&rewrite(&read)?,
+			&bytes,
+			"read from fragments of up to {} bytes and not rewritten identically",
+			max,
+		);
+	}
+
+	Ok(())
+}
+
+/// Writes the given `message` which was read back by [`assert_round_trip`].
+fn rewrite(message: &impl Writable) -> Result<Vec<u8>, TestCaseError> {
+	let mut bytes = vec![];
+	message
+		.write_to(&mut bytes)
+		.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	Ok(bytes)
+}
+
+/// Asserts that the message generated from the given `bytes` by its
+/// [`arbitrary::Arbitrary`] implementation is written identically after it has
+/// been written and read back.
+///
+/// As in [`assert_round_trip`], the first byte is skipped when reading.
+#[cfg(feature = "arbitrary")]
+pub fn assert_rewritten_identically<Message>(bytes: &[u8]) -> Result<(), TestCaseError>
+where
+	Message: for<'a> arbitrary::Arbitrary<'a> + Readable + Writable + Debug,
+{
+	let message = Message::arbitrary(&mut arbitrary::Unstructured::new(bytes))
+		.map_err(|error| TestCaseError::reject(error.to_string()))?;
+
+	let mut written = vec![];
+	message
+		.write_to(&mut written)
+		.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	let read = Message::read_from(&mut &written[1..])
+		.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	let mut rewritten = vec![];
+	read.write_to(&mut rewritten)
+		.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+	prop_assert_eq!(rewritten, written, "{:?} was not rewritten identically", message);
+
+	Ok(())
+}
+
+/// Generates a property-based test, using [`assert_round_trip`], for each of
+/// the given message types.
+///
+/// With the `arbitrary` feature, a test using [`assert_rewritten_identically`]
+/// is also generated for each message type, with the same name, in a module
+/// named `arbitrary_round_trip`.
+///
+/// Each message type is given with the name of its test:
+/// ```ignore
+/// round_trip_tests! {
+///     GrabCursor => test_grab_cursor_round_trip,
+///     UngrabCursor => test_ungrab_cursor_round_trip,
+/// }
+/// ```
+macro_rules! round_trip_tests {
+	($($Message:ty => $test:ident),+$(,)?) => {
+		::proptest::proptest! {
+			$(
+				#[test]
+				fn $test(message: $Message) {
+					$crate::arbitrary::assert_round_trip(&message)?;
+				}
+			)+
+		}
+
+		#[cfg(feature = "arbitrary")]
+		mod arbitrary_round_trip {
+			#[allow(clippy::wildcard_imports)]
+			use super::*;
+
+			::proptest::proptest! {
+				$(
+					#[test]
+					fn $test(
+						bytes in ::proptest::collection::vec(::proptest::arbitrary::any::<u8>(), 0..1024),
+					) {
+						$crate::arbitrary::assert_rewritten_identically::<$Message>(&bytes)?;
+					}
+				)+
+			}
+		}
+	};
+}
+
+pub(crate) use round_trip_tests;
+
+/// Implements [`Arbitrary`] for types constructed from the values of the given
+/// strategy with their `new` function.
+macro_rules! arbitrary_new {
+	($($Type:ty: $strategy:expr;)+) => {
+		$(
+			impl Arbitrary for $Type {
+				type Parameters = ();
+				type Strategy = BoxedStrategy<Self>;
+
+				fn arbitrary_with((): ()) -> Self::Strategy {
+					$strategy.prop_map(Self::new).boxed()
+				}
+			}
+		)+
+	};
+}
+
+/// Implements [`Arbitrary`] for masks, never setting bits which are not
+/// defined for them.
+macro_rules! arbitrary_masks {
+	($($Mask:ty: $Bits:ty),+$(,)?) => {
+		$(
+			impl Arbitrary for $Mask {
+				type Parameters = ();
+				type Strategy = BoxedStrategy<Self>;
+
+				fn arbitrary_with((): ()) -> Self::Strategy {
+					any::<$Bits>()
+						.prop_map(Self::from_bits_truncate)
+						.boxed()
+				}
+			}
+		)+
+	};
+}
+
+/// Implements [`Arbitrary`] for wrappers of a single numerical value.
+macro_rules! arbitrary_units {
+	($($Unit:ident),+$(,)?) => {
+		$(
+			impl<Num: Arbitrary + 'static> Arbitrary for $Unit<Num> {
+				type Parameters = Num::Parameters;
+				type Strategy = BoxedStrategy<Self>;
+
+				fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+					any_with::<Num>(params).prop_map($Unit).boxed()
+				}
+			}
+		)+
+	};
+}
+
+/// Generates non-empty ranges of [`Keycode`]s.
+pub fn keycode_ranges() -> impl Strategy<Value = RangeInclusive<Keycode>> {
+	(any::<Keycode>(), any::<Keycode>()).prop_map(|(first, second)| {
+		if first.unwrap() <= second.unwrap() {
+			first..=second
+		} else {
+			second..=first
+		}
+	})
+}
+
+/// Generates lists of [`KeyMapping`]s which each have the same number of
+/// [`Keysym`]s, as is required by the protocol.
+pub fn key_mappings() -> impl Strategy<Value = Vec<KeyMapping>> {
+	(1..=8_usize)
+		.prop_flat_map(|keysyms_per_keycode| vec(vec(any::<Keysym>(), keysyms_per_keycode), 0..16))
+}
+
+/// Generates lists of bytes with lengths that are multiples of `4`.
+///
+/// Padding cannot be told apart from the data it follows if the length of that
+/// data is not otherwise given.
+pub fn aligned_bytes() -> impl Strategy<Value = Vec<u8>> {
+	vec(any::<[u8; 4]>(), 0..16).prop_map(|words| words.concat())
+}
+
+/// Generates [`Rectangle`]s whose coordinates and dimensions are often at the
+/// limits of their ranges, where their sums overflow an [`i16`].
+pub fn rectangles() -> impl Strategy<Value = Rectangle> {
+	let coordinate = || prop_oneof![Just(i16::MIN), Just(i16::MAX), Just(0), any::<i16>()];
+	let dimension = || prop_oneof![Just(u16::MAX), Just(0), any::<u16>()];
+
+	(coordinate(), coordinate(), dimension(), dimension())
+		.prop_map(|(x, y, width, height)| Rectangle::new(Px(x), Px(y), Px(width), Px(height)))
+}
+
+/// The range of valid resource IDs.
+const RES_IDS: RangeInclusive<u32> = 2..=0x1fff_ffff;
+
+arbitrary_new! {
+	Drawable: RES_IDS;
+	Window: RES_IDS;
+	Pixmap: RES_IDS;
+	CursorAppearance: RES_IDS;
+	Fontable: RES_IDS;
+	Font: RES_IDS;
+	GraphicsContext: RES_IDS;
+	Colormap: RES_IDS;
+	VisualId: RES_IDS;
+
+	Atom: 1..=0x1fff_ffff_u32;
+
+	Keycode: 8..=u8::MAX;
+	Button: 1..=u8::MAX;
+	Keysym: any::<u32>();
+	Timestamp: 1..=u32::MAX;
+}
+
+arbitrary_masks!(
+	EventMask: u32,
+	CursorEventMask: u16,
+	DeviceEventMask: u32,
+	ModifierMask: u16,
+	ModifierKeyMask: u16,
+	AnyModifierKeyMask: u16,
+);
+
+arbitrary_units!(Px, Ms, Sec, Hz);
+
+impl Arbitrary for Percentage {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(0..=100_u8)
+			.prop_map(|percentage| Self::new(percentage).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for SignedPercentage {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(-100..=100_i8)
+			.prop_map(|percentage| Self::new(percentage).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for PercentOrDefault {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(-1..=100_i8)
+			.prop_map(|value| Self::new(value).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for PitchOrDefault {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(-1..=i16::from(u8::MAX))
+			.prop_map(|value| Self::new(value).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for DurationOrDefault {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(-1..=i16::from(u8::MAX))
+			.prop_map(|value| Self::new(value).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for BitPlane {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(0..32_u32)
+			.prop_map(|index| Self::nth(index).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for Led {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		(1..=32_u8).prop_map(|led| Self::new(led).unwrap()).boxed()
+	}
+}
+
+impl Arbitrary for LedMode {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		prop_oneof![Just(Self::Off), Just(Self::On)].boxed()
+	}
+}
+
+impl Arbitrary for AsciiString {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		// `0` is excluded, as it is used to separate the two `AsciiString`s in
+		// a `HostAddress::ServerInterpreted`.
+		vec(1..=0x7f_u8, 0..32)
+			.prop_map(|string| Self::new(string).unwrap())
+			.boxed()
+	}
+}
+
+impl Arbitrary for ModifierMap {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		std::array::from_fn::<_, 8, _>(|_| vec(any::<Keycode>(), 0..8))
+			.prop_map(Self::from)
+			.boxed()
+	}
+}
+
+impl Arbitrary for HostAddress {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		// An `Unknown` address must not use the code of a known family, or it
+		// would be read as an address of that family.
+		let unknown = (any::<u8>(), vec(any::<u8>(), 0..32))
+			.prop_filter("known family", |(family, _)| {
+				matches!(HostFamily::from(*family), HostFamily::Unknown(_))
+			})
+			.prop_map(|(family, address)| Self::Unknown { family, address });
+
+		prop_oneof![
+			any::<[u8; 4]>().prop_map(Self::Ipv4),
+			any::<[u8; 2]>().prop_map(Self::DecNet),
+			any::<[u8; 2]>().prop_map(Self::Chaos),
+			any::<(AsciiString, AsciiString)>().prop_map(|(address_type, address_value)| {
+				Self::ServerInterpreted {
+					address_type,
+					address_value,
+				}
+			}),
+			any::<[u8; 16]>().prop_map(Self::Ipv6),
+			unknown,
+		]
+		.boxed()
+	}
+}
+
+impl Arbitrary for KeyboardOptions {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		any::<(
+			Option<PercentOrDefault>,
+			Option<PercentOrDefault>,
+			Option<PitchOrDefault>,
+			Option<DurationOrDefault>,
+			Option<Led>,
+			Option<LedMode>,
+			Option<Keycode>,
+			Option<crate::ToggleOrDefault>,
+		)>()
+		.prop_map(
+			|(
+				key_click_volume,
+				bell_volume,
+				bell_pitch,
+				bell_duration,
+				led,
+				led_mode,
+				auto_repeated_key,
+				auto_repeat_mode,
+			)| {
+				let mut builder = KeyboardOptionsBuilder::new();
+
+				if let Some(key_click_volume) = key_click_volume {
+					builder.key_click_volume(key_click_volume);
+				}
+				if let Some(bell_volume) = bell_volume {
+					builder.bell_volume(bell_volume);
+				}
+				if let Some(bell_pitch) = bell_pitch {
+					builder.bell_pitch(bell_pitch);
+				}
+				if let Some(bell_duration) = bell_duration {
+					builder.bell_duration(bell_duration);
+				}
+				if let Some(led) = led {
+					builder.led(led);
+				}
+				if let Some(led_mode) = led_mode {
+					builder.led_mode(led_mode);
+				}
+				if let Some(auto_repeated_key) = auto_repeated_key {
+					builder.auto_repeated_key(auto_repeated_key);
+				}
+				if let Some(auto_repeat_mode) = auto_repeat_mode {
+					builder.auto_repeat_mode(auto_repeat_mode);
+				}
+
+				builder.build()
+			},
+		)
+		.boxed()
+	}
+}
+
+impl Arbitrary for QueryExtension {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		// The opcode and codes are only present if the extension is.
+		(any::<u16>(), any::<Option<(u8, u8, u8)>>())
+			.prop_map(|(sequence, codes)| Self {
+				sequence,
+				present: codes.is_some(),
+
+				major_opcode: codes.map(|(major_opcode, ..)| major_opcode),
+				first_event_code: codes.map(|(_, first_event_code, _)| first_event_code),
+				first_error_code: codes.map(|(.., first_error_code)| first_error_code),
+			})
+			.boxed()
+	}
+}
+
+impl Arbitrary for GetProperty {
+	type Parameters = ();
+	type Strategy = BoxedStrategy<Self>;
+
+	fn arbitrary_with((): ()) -> Self::Strategy {
+		// The `format` must match the `value`, and there is no `value` if the
+		// property does not exist.
+		let missing = (any::<u16>(), any::<u32>()).prop_map(|(sequence, bytes_remaining)| Self {
+			sequence,
+			format: None,
+			r#type: None,
+			bytes_remaining,
+			value: DataList::I8(vec![]),
+		});
+		let present = (any::<u16>(), any::<Atom>(), any::<u32>(), any::<DataList>()).prop_map(
+			|(sequence, r#type, bytes_remaining, value)| Self {
+				sequence,
+				format: Some(value.format()),
+				r#type: Some(r#type),
+				bytes_remaining,
+				value,
+			},
+		);
+
+		prop_oneof![missing, present].boxed()
+	}
+}
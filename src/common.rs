@@ -4,12 +4,16 @@
 
 extern crate self as xrb;
 
-use array_init::array_init;
 use derive_more::{From, Into};
+use std::{cmp::Ordering, time::Duration};
 use thiserror::Error;
 
-pub use atom::Atom;
+pub use atom::{Atom, AtomCache, AtomLookup};
+pub use key_states::KeyStates;
+pub use keysym::Keysym;
+pub use known_extensions::ExtensionName;
 pub use mask::*;
+pub use modifier_map::{Modifier, ModifierMap, ModifierMapBuilder};
 pub use res_id::*;
 pub use wrapper::*;
 
@@ -21,26 +25,35 @@ use xrbk::{
 	ReadError,
 	ReadError::FailedConversion,
 	ReadResult,
+	Readable,
 	ReadableWithContext,
 	Wrap,
 	Writable,
 	WriteResult,
 	X11Size,
+	ZeroIsNone,
 };
 use xrbk_macro::{derive_xrb, new, unwrap, ConstantX11Size, Readable, Wrap, Writable, X11Size};
 
 use crate::unit::Px;
 
 pub mod atom;
+pub mod keysym;
+pub mod known_extensions;
 pub mod set;
 pub mod visual;
 
+mod key_states;
 mod mask;
+mod modifier_map;
 mod res_id;
 mod wrapper;
 
 /// Whether something is enabled or disabled.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Toggle {
 	/// The thing is disabled.
 	Disabled,
@@ -49,7 +62,10 @@ pub enum Toggle {
 }
 
 /// Whether something is enabled, disabled, or the default is chosen.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ToggleOrDefault {
 	/// The thing is disabled.
 	Disabled,
@@ -66,7 +82,12 @@ pub enum ToggleOrDefault {
 /// Represents a particular time, expressed in milliseconds.
 ///
 /// Timestamps are typically the time since the last server reset. After
-/// approximately 49.7 days, the time will wrap around back to 0.
+/// approximately 49.7 days, the time will wrap around back to 0, so
+/// `Timestamp`s should be compared with [`is_later_than`] or
+/// [`wrapping_cmp`] rather than by their values.
+///
+/// [`is_later_than`]: Timestamp::is_later_than
+/// [`wrapping_cmp`]: Timestamp::wrapping_cmp
 #[derive(
 	Copy,
 	Clone,
@@ -86,36 +107,128 @@ pub enum ToggleOrDefault {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Timestamp(pub(crate) u32);
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+impl Timestamp {
+	/// Creates a new `Timestamp` representing the given number of
+	/// milliseconds.
+	#[must_use]
+	pub const fn from_millis(millis: u32) -> Self {
+		Self(millis)
+	}
+
+	/// Adds the given `duration` to this `Timestamp`, wrapping around back to
+	/// 0 if the maximum time is exceeded.
+	///
+	/// Any part of the `duration` smaller than a millisecond is ignored.
+	#[must_use]
+	pub const fn wrapping_add(self, duration: Duration) -> Self {
+		// Truncating the duration is equivalent to wrapping around for every
+		// full cycle of the time it contains.
+		#[allow(clippy::cast_possible_truncation)]
+		let millis = duration.as_millis() as u32;
+
+		Self(self.0.wrapping_add(millis))
+	}
+
+	/// Adds the given number of milliseconds to this `Timestamp`, wrapping
+	/// around back to 0 if the maximum time is exceeded.
+	#[must_use]
+	pub const fn wrapping_add_millis(self, millis: u32) -> Self {
+		Self(self.0.wrapping_add(millis))
+	}
+
+	/// Returns the number of milliseconds from the `earlier` `Timestamp` to
+	/// this `Timestamp`, wrapping around back to 0 if necessary.
+	///
+	/// If this `Timestamp` is actually earlier than `earlier`, this is the
+	/// number of milliseconds until time wraps around and reaches this
+	/// `Timestamp` again.
+	#[must_use]
+	pub const fn duration_since(self, earlier: Self) -> u32 {
+		self.0.wrapping_sub(earlier.0)
+	}
+
+	/// Returns whether this `Timestamp` is later than the `other` `Timestamp`.
+	///
+	/// Because time wraps around back to 0 after approximately 49.7 days, a
+	/// `Timestamp` cannot be compared to another simply by comparing their
+	/// values. Instead, the X11 protocol considers half of all possible times
+	/// to be later than a given time, and the other half to be earlier: a
+	/// `Timestamp` is later than the `other` if it is less than 2<sup>31</sup>
+	/// milliseconds after it, wrapping around if necessary.
+	///
+	/// This is the comparison used by the X server when deciding whether a
+	/// grab or focus change is out of date.
+	#[doc(alias = "is_after")]
+	#[must_use]
+	pub const fn is_later_than(self, other: Self) -> bool {
+		const HALF: u32 = 1 << 31;
+
+		self.0 != other.0 && self.duration_since(other) < HALF
+	}
+
+	/// Compares this `Timestamp` to the `other` `Timestamp`, taking into
+	/// account that time wraps around back to 0.
+	///
+	/// This returns [`Ordering::Greater`] if this `Timestamp`
+	/// [is later than] the `other`, [`Ordering::Equal`] if they are the same
+	/// time, and [`Ordering::Less`] otherwise.
+	///
+	/// `Timestamp`s do not implement [`Ord`], because this comparison is not a
+	/// total order: a `Timestamp` exactly 2<sup>31</sup> milliseconds from
+	/// another is considered earlier than it either way around.
+	///
+	/// [is later than]: Timestamp::is_later_than
+	#[must_use]
+	pub const fn wrapping_cmp(self, other: Self) -> Ordering {
+		if self.0 == other.0 {
+			Ordering::Equal
+		} else if self.is_later_than(other) {
+			Ordering::Greater
+		} else {
+			Ordering::Less
+		}
+	}
+}
+
+// The discriminants of `BitGravity` and `WindowGravity` are those of the X11
+// protocol, in which `Static` comes last.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BitGravity {
-	Forget,
-	Static,
-	NorthWest,
-	North,
-	NorthEast,
-	West,
-	Center,
-	East,
-	SouthWest,
-	South,
-	SouthEast,
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	Forget = 0,
+	Static = 10,
+	NorthWest = 1,
+	North = 2,
+	NorthEast = 3,
+	West = 4,
+	Center = 5,
+	East = 6,
+	SouthWest = 7,
+	South = 8,
+	SouthEast = 9,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindowGravity {
-	Unmap,
-	Static,
-	NorthWest,
-	North,
-	NorthEast,
-	West,
-	Center,
-	East,
-	SouthWest,
-	South,
-	SouthEast,
+	Unmap = 0,
+	Static = 10,
+	NorthWest = 1,
+	North = 2,
+	NorthEast = 3,
+	West = 4,
+	Center = 5,
+	East = 6,
+	SouthWest = 7,
+	South = 8,
+	SouthEast = 9,
 }
 
 // The `derive_xrb!` attribute here is used to write the discriminants as `u16`.
@@ -124,6 +237,8 @@ derive_xrb! {
 	///
 	/// [window]: Window
 	#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub enum WindowClass: u16 {
 		/// A [window] that both receives input and has a visual output (i.e. what
 		/// one would normally consider a window to be).
@@ -142,6 +257,11 @@ derive_xrb! {
 
 	impl Wrap for WindowClass {
 		type Integer = u16;
+		type Error = ReadError;
+
+		fn try_wrap(integer: u16) -> ReadResult<Self> {
+			Self::try_from(integer)
+		}
 	}
 
 	impl TryFrom<u16> for WindowClass {
@@ -152,7 +272,10 @@ derive_xrb! {
 				discrim if discrim == 1 => Ok(Self::InputOutput),
 				discrim if discrim == 2 => Ok(Self::InputOnly),
 
-				other_discrim => Err(ReadError::UnrecognizedDiscriminant(other_discrim as usize)),
+				other_discrim => Err(ReadError::UnrecognizedDiscriminant {
+					type_name: "WindowClass",
+					discriminant: other_discrim as usize,
+				}),
 			}
 		}
 	}
@@ -167,14 +290,21 @@ derive_xrb! {
 	}
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaintainContents {
 	Never,
 	WhenMapped,
 	Always,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[doc(alias = "NotifyMode")]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GrabMode {
 	Normal,
 	Grab,
@@ -184,34 +314,39 @@ pub enum GrabMode {
 /// Whether a grab causes a freeze in [event] processing.
 ///
 /// [event]: crate::message::Event
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FreezeMode {
-	/// [Event] processing is not frozen.
-	///
-	/// [Event]: crate::message::Event
-	#[doc(alias = "Asynchronous")]
-	Unfrozen,
-
+	// `Synchronous` is encoded as `0` and `Asynchronous` as `1`, so `Frozen`
+	// must come first.
 	/// [Event] processing is frozen.
 	///
 	/// [Event]: crate::message::Event
 	#[doc(alias = "Synchronous")]
 	Frozen,
+
+	/// [Event] processing is not frozen.
+	///
+	/// [Event]: crate::message::Event
+	#[doc(alias = "Asynchronous")]
+	Unfrozen,
 }
 
 /// The status of an attempted grab.
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+///
+/// Use [`GrabStatus::ok`] to convert this to a [`Result`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GrabStatus {
 	/// The grab was successful.
 	Success,
 
 	/// Another client already had a grab.
 	AlreadyGrabbed,
-	/// Another client already had an active grab and had frozen [event]
-	/// processing.
-	///
-	/// [event]: crate::message::Event
-	Frozen,
 	/// The given time was either earlier than the previous grab, or later than
 	/// the X server's [current time].
 	///
@@ -223,9 +358,55 @@ pub enum GrabStatus {
 	///
 	/// [window]: Window
 	NotViewable,
+	/// Another client already had an active grab and had frozen [event]
+	/// processing.
+	///
+	/// [event]: crate::message::Event
+	Frozen,
+}
+
+impl GrabStatus {
+	/// Returns `Ok(())` if the grab was successful, or else the reason that it
+	/// failed.
+	///
+	/// # Errors
+	/// Returns a [`GrabFailure`] if this is not [`GrabStatus::Success`].
+	pub const fn ok(self) -> Result<(), GrabFailure> {
+		match self {
+			Self::Success => Ok(()),
+
+			Self::AlreadyGrabbed => Err(GrabFailure::AlreadyGrabbed),
+			Self::InvalidTime => Err(GrabFailure::InvalidTime),
+			Self::NotViewable => Err(GrabFailure::NotViewable),
+			Self::Frozen => Err(GrabFailure::Frozen),
+		}
+	}
+}
+
+/// The reason that an attempted grab failed.
+///
+/// This is returned by [`GrabStatus::ok`]; each variant corresponds to the
+/// [`GrabStatus`] variant of the same name.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+pub enum GrabFailure {
+	/// See [`GrabStatus::AlreadyGrabbed`].
+	#[error("another client already has a grab")]
+	AlreadyGrabbed,
+	/// See [`GrabStatus::InvalidTime`].
+	#[error("the grab time is earlier than the last grab or later than the current time")]
+	InvalidTime,
+	/// See [`GrabStatus::NotViewable`].
+	#[error("the grabbed window or the window the cursor is confined to is not viewable")]
+	NotViewable,
+	/// See [`GrabStatus::Frozen`].
+	#[error("another client has frozen event processing with an active grab")]
+	Frozen,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StackMode {
 	Above,
 	Below,
@@ -252,37 +433,7 @@ pub enum StackMode {
 	Writable,
 	Wrap,
 )]
-pub struct Keysym(pub(crate) u32);
-
-impl Keysym {
-	pub const NO_SYMBOL: Self = Self::new(0x0000_0000);
-	pub const VOID_SYMBOL: Self = Self::new(0x00ff_ffff);
-
-	/// Returns the raw contained keysym value.
-	#[must_use]
-	pub const fn unwrap(&self) -> u32 {
-		self.0
-	}
-}
-
-#[derive(
-	Copy,
-	Clone,
-	Eq,
-	PartialEq,
-	Hash,
-	Debug,
-	From,
-	Into,
-	// `new` const fn
-	new,
-	// XRBK traits
-	X11Size,
-	ConstantX11Size,
-	Readable,
-	Writable,
-	Wrap,
-)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Keycode(pub(crate) u8);
 
 impl Keycode {
@@ -312,6 +463,7 @@ impl Keycode {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Button(pub(crate) u8);
 
 impl Button {
@@ -320,6 +472,10 @@ impl Button {
 	pub const SECONDARY: Self = Self::new(3);
 }
 
+// Buttons are numbered from one, so zero is used to encode `None` (e.g. a
+// disabled button in a button mapping).
+impl ZeroIsNone for Button {}
+
 #[derive(
 	Copy,
 	Clone,
@@ -339,9 +495,15 @@ impl Button {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Char8(pub(crate) u8);
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug, From, Into, X11Size, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct String8(Vec<Char8>);
 
 impl String8 {
@@ -354,6 +516,41 @@ impl String8 {
 	pub fn is_empty(&self) -> bool {
 		self.0.is_empty()
 	}
+
+	/// Encodes the given `string` in ISO Latin-1, the encoding used for
+	/// strings by the X11 protocol.
+	///
+	/// Each character is encoded as a single byte, so the [length] of the
+	/// `String8` is the number of characters in the `string`, not the number
+	/// of bytes it takes in UTF-8.
+	///
+	/// # Errors
+	/// A [`StringError::NotLatin1`] error is returned for the first character
+	/// in the `string` which cannot be encoded in ISO Latin-1.
+	///
+	/// [length]: String8::len
+	pub fn from_latin1(string: &str) -> Result<Self, StringError> {
+		string
+			.char_indices()
+			.map(|(index, char)| {
+				u8::try_from(char)
+					.map(Char8)
+					.map_err(|_| StringError::NotLatin1 { char, index })
+			})
+			.collect::<Result<_, _>>()
+			.map(Self)
+	}
+
+	/// Decodes this string from ISO Latin-1, the encoding used for strings by
+	/// the X11 protocol.
+	///
+	/// Every byte is a valid ISO Latin-1 character, so this cannot fail. The
+	/// bytes are not necessarily valid UTF-8, however: characters from `0x80`
+	/// to `0xff` take two bytes in UTF-8.
+	#[must_use]
+	pub fn to_latin1_string(&self) -> String {
+		self.0.iter().map(|Char8(byte)| char::from(*byte)).collect()
+	}
 }
 
 impl ReadableWithContext for String8 {
@@ -363,33 +560,173 @@ impl ReadableWithContext for String8 {
 	where
 		Self: Sized,
 	{
+		xrbk::ensure_remaining(reader, *length)?;
+
 		Ok(Self(<Vec<Char8>>::read_with(reader, length)?))
 	}
 }
 
+/// An error generated when creating a [`String8`] or [`LengthString8`].
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+pub enum StringError {
+	/// The string is longer than the maximum length of a [`LengthString8`].
+	#[error("the string is {0} bytes long, but a `LengthString8` is at most 255 bytes long")]
+	TooLong(usize),
+
+	/// The string contains a character which cannot be encoded in ISO
+	/// Latin-1.
+	#[error("{char:?} at byte {index} cannot be encoded in ISO Latin-1")]
+	NotLatin1 {
+		/// The character which cannot be encoded.
+		char: char,
+		/// The byte index of the `char` in the UTF-8 string.
+		index: usize,
+	},
+}
+
 derive_xrb! {
+	/// A [`String8`] which is preceded by its length.
+	///
+	/// As the length is a single byte, a `LengthString8` is at most 255 bytes
+	/// long. Creating a longer `LengthString8` with [`TryFrom`] fails with
+	/// [`StringError::TooLong`].
 	#[derive(
 		Clone,
 		Eq,
 		PartialEq,
 		Hash,
 		Debug,
-		From,
 		Into,
 		// XRBK traits
 		X11Size,
-		Readable,
 		Writable,
 	)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[cfg_attr(feature = "serde", serde(try_from = "String8", into = "String8"))]
 	pub struct LengthString8 {
 		#[allow(clippy::cast_possible_truncation)]
 		let len: u8 = string => string.len() as u8,
 
-		#[context(len => usize::from(*len))]
 		string: String8,
 	}
 }
 
+impl LengthString8 {
+	/// The maximum length of a `LengthString8`, in bytes.
+	pub const MAX_LEN: usize = u8::MAX as usize;
+
+	/// Returns the [`String8`] which this is a wrapper around.
+	#[must_use]
+	pub const fn as_string8(&self) -> &String8 {
+		&self.string
+	}
+
+	/// The length of the string, in bytes.
+	///
+	/// This does not include the byte used to write the length itself.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.string.len()
+	}
+
+	/// Whether the string is empty.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.string.is_empty()
+	}
+}
+
+impl TryFrom<String8> for LengthString8 {
+	type Error = StringError;
+
+	fn try_from(string: String8) -> Result<Self, Self::Error> {
+		if string.len() > Self::MAX_LEN {
+			return Err(StringError::TooLong(string.len()));
+		}
+
+		Ok(Self { string })
+	}
+}
+
+impl TryFrom<&str> for LengthString8 {
+	type Error = StringError;
+
+	fn try_from(string: &str) -> Result<Self, Self::Error> {
+		String8::from_latin1(string)?.try_into()
+	}
+}
+
+impl xrbk::Readable for LengthString8 {
+	fn read_from(reader: &mut impl Buf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		let len = u8::read_from(reader)?;
+
+		Ok(Self {
+			string: String8::read_with(reader, &usize::from(len))?,
+		})
+	}
+}
+
+derive_xrb! {
+	/// A borrowed form of [`LengthString8`] which does not copy the bytes of
+	/// the string.
+	///
+	/// This is read with [`ReadableRef`], borrowing the bytes of the string
+	/// directly from the buffer it was read from.
+	///
+	/// [`ReadableRef`]: xrbk::ReadableRef
+	#[derive(
+		Copy,
+		Clone,
+		Eq,
+		PartialEq,
+		Hash,
+		Debug,
+		// XRBK traits
+		ReadableRef,
+	)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+	pub struct LengthString8Ref<'a> {
+		#[allow(clippy::cast_possible_truncation)]
+		let len: u8 = string => string.len() as u8,
+
+		#[context(len => usize::from(*len))]
+		string: &'a [u8],
+	}
+}
+
+impl<'a> LengthString8Ref<'a> {
+	/// Returns the bytes of the string, without its length.
+	#[must_use]
+	pub const fn as_bytes(&self) -> &'a [u8] {
+		self.string
+	}
+
+	/// The length of the string, in bytes.
+	///
+	/// This does not include the byte used to write the length itself.
+	#[must_use]
+	pub const fn len(&self) -> usize {
+		self.string.len()
+	}
+
+	/// Whether the string is empty.
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.string.is_empty()
+	}
+}
+
+impl X11Size for LengthString8Ref<'_> {
+	fn x11_size(&self) -> usize {
+		// The length is written as a single byte before the string.
+		1 + self.string.len()
+	}
+}
+
 #[derive(
 	Copy,
 	Clone,
@@ -408,6 +745,9 @@ derive_xrb! {
 	Readable,
 	Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Char16(pub(crate) u8, pub(crate) u8);
 
 impl From<u16> for Char16 {
@@ -427,6 +767,9 @@ impl From<Char16> for u16 {
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug, From, Into, X11Size, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct String16(Vec<Char16>);
 
 impl String16 {
@@ -471,6 +814,9 @@ impl ReadableWithContext for String16 {
 	Readable,
 	Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coords {
 	/// The x coordinate, measured in pixels.
 	pub x: Px<i16>,
@@ -478,6 +824,49 @@ pub struct Coords {
 	pub y: Px<i16>,
 }
 
+impl Coords {
+	/// Returns these `Coords` moved by the given `x` and `y` offsets.
+	///
+	/// Coordinates which would fall outside the range of an [`i16`] are
+	/// clamped to that range. This matches the X server, which clips drawing
+	/// to the coordinates that it is able to represent.
+	///
+	/// See [`checked_offset_by`] for a version which detects overflow instead.
+	///
+	/// [`checked_offset_by`]: Coords::checked_offset_by
+	#[must_use]
+	pub fn offset_by(self, x: Px<i32>, y: Px<i32>) -> Self {
+		Self::new(
+			Px(saturate(i32::from(self.x.0).saturating_add(x.0))),
+			Px(saturate(i32::from(self.y.0).saturating_add(y.0))),
+		)
+	}
+
+	/// Returns these `Coords` moved by the given `x` and `y` offsets, or
+	/// [`None`] if either coordinate would fall outside the range of an
+	/// [`i16`].
+	#[must_use]
+	pub fn checked_offset_by(self, x: Px<i32>, y: Px<i32>) -> Option<Self> {
+		Some(Self::new(
+			Px(i16::try_from(i32::from(self.x.0).checked_add(x.0)?).ok()?),
+			Px(i16::try_from(i32::from(self.y.0).checked_add(y.0)?).ok()?),
+		))
+	}
+}
+
+/// Converts the given `value` to an [`i16`], clamping it to the range of an
+/// [`i16`].
+#[allow(clippy::cast_possible_truncation)]
+const fn saturate(value: i32) -> i16 {
+	if value < i16::MIN as i32 {
+		i16::MIN
+	} else if value > i16::MAX as i32 {
+		i16::MAX
+	} else {
+		value as i16
+	}
+}
+
 /// 2D dimensions (width and height), measured in pixels.
 #[derive(
 	Copy,
@@ -497,6 +886,9 @@ pub struct Coords {
 	Readable,
 	Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dimensions {
 	/// The width, measured in pixels.
 	pub width: Px<u16>,
@@ -508,6 +900,9 @@ pub struct Dimensions {
 #[derive(
 	Copy, Clone, Eq, PartialEq, Hash, Debug, new, X11Size, ConstantX11Size, Readable, Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
 	/// The x-coordinate of the upper left corner of the `Rectangle`.
 	pub x: Px<i16>,
@@ -531,10 +926,242 @@ impl Rectangle {
 	pub const fn as_dimensions(&self) -> Dimensions {
 		Dimensions::new(self.width, self.height)
 	}
+
+	/// Creates the `Rectangle` with the given opposite corners.
+	///
+	/// The corners may be given in either order. The rectangle includes the
+	/// column and row of the top-left corner, but not those of the
+	/// bottom-right corner, making this the inverse of
+	/// [`corners`](Rectangle::corners).
+	///
+	/// This cannot overflow: the distance between two [`i16`] coordinates
+	/// always fits in a [`u16`] dimension.
+	#[must_use]
+	pub fn from_corners(first: Coords, second: Coords) -> Self {
+		let (x, width) = span(first.x.0, second.x.0);
+		let (y, height) = span(first.y.0, second.y.0);
+
+		Self::new(Px(x), Px(y), Px(width), Px(height))
+	}
+
+	/// Returns the top-left and bottom-right corners of the rectangle, or
+	/// [`None`] if the bottom-right corner falls outside the range of an
+	/// [`i16`].
+	///
+	/// The bottom-right corner lies just outside the rectangle: see
+	/// [`from_corners`](Rectangle::from_corners).
+	#[must_use]
+	pub fn corners(&self) -> Option<(Coords, Coords)> {
+		let bottom_right = Coords::new(
+			Px(i16::try_from(self.right()).ok()?),
+			Px(i16::try_from(self.bottom()).ok()?),
+		);
+
+		Some((self.as_coords(), bottom_right))
+	}
+
+	/// Returns the top-left and bottom-right corners of the rectangle, clamping
+	/// the bottom-right corner to the range of an [`i16`].
+	///
+	/// The clamped corner is the furthest that the X server draws: any part of
+	/// the rectangle beyond it is clipped.
+	#[must_use]
+	pub fn saturating_corners(&self) -> (Coords, Coords) {
+		let bottom_right = Coords::new(Px(saturate(self.right())), Px(saturate(self.bottom())));
+
+		(self.as_coords(), bottom_right)
+	}
+
+	/// Returns whether the given `coords` lie within the rectangle.
+	///
+	/// The rectangle includes the column and row of its top-left corner, but
+	/// not the column and row just past its `width` and `height`.
+	#[must_use]
+	pub fn contains(&self, coords: Coords) -> bool {
+		let (x, y) = (i32::from(coords.x.0), i32::from(coords.y.0));
+
+		(i32::from(self.x.0)..self.right()).contains(&x)
+			&& (i32::from(self.y.0)..self.bottom()).contains(&y)
+	}
+
+	/// Returns the area shared by this rectangle and the `other` rectangle, or
+	/// [`None`] if they do not overlap.
+	#[must_use]
+	#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+	pub fn intersect(&self, other: &Self) -> Option<Self> {
+		let x = self.x.0.max(other.x.0);
+		let y = self.y.0.max(other.y.0);
+
+		let right = self.right().min(other.right());
+		let bottom = self.bottom().min(other.bottom());
+
+		if right <= i32::from(x) || bottom <= i32::from(y) {
+			return None;
+		}
+
+		// Neither dimension can be larger than those of either rectangle.
+		let width = (right - i32::from(x)) as u16;
+		let height = (bottom - i32::from(y)) as u16;
+
+		Some(Self::new(Px(x), Px(y), Px(width), Px(height)))
+	}
+
+	/// Returns this rectangle moved, and shrunk if necessary, so that it lies
+	/// within the given `parent` rectangle.
+	///
+	/// Both rectangles must be in the same coordinate space. A window's
+	/// geometry is relative to its parent, so to keep a window within its
+	/// parent, the `parent` rectangle should have `x` and `y` coordinates of
+	/// `0`.
+	#[must_use]
+	#[allow(clippy::cast_possible_truncation)]
+	pub fn clamp_within(&self, parent: &Self) -> Self {
+		let width = self.width.min(parent.width);
+		let height = self.height.min(parent.height);
+
+		// The result is always within the range of an `i16`: the lower bound is
+		// the `parent`'s coordinate, and the rectangle's own coordinate is
+		// only ever moved towards it.
+		let x = i32::from(self.x.0)
+			.min(parent.right() - i32::from(width.0))
+			.max(i32::from(parent.x.0)) as i16;
+		let y = i32::from(self.y.0)
+			.min(parent.bottom() - i32::from(height.0))
+			.max(i32::from(parent.y.0)) as i16;
+
+		Self::new(Px(x), Px(y), width, height)
+	}
+
+	/// The x-coordinate just past the right edge of the rectangle.
+	fn right(self) -> i32 {
+		i32::from(self.x.0) + i32::from(self.width.0)
+	}
+
+	/// The y-coordinate just past the bottom edge of the rectangle.
+	fn bottom(self) -> i32 {
+		i32::from(self.y.0) + i32::from(self.height.0)
+	}
+}
+
+/// The geometry of a [window]: its coordinates, dimensions, and border width.
+///
+/// The `coords` are those of the outside corner of the [window]'s border,
+/// relative to the top-left corner of the [window]'s parent. The `dimensions`
+/// are those of the inside of the [window], not including its border, which
+/// surrounds the [window] on every side.
+///
+/// [window]: Window
+#[derive(
+	Copy, Clone, Eq, PartialEq, Hash, Debug, new, X11Size, ConstantX11Size, Readable, Writable,
+)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+	/// The coordinates of the outside corner of the [window]'s border.
+	///
+	/// [window]: Window
+	#[doc(alias("x", "y"))]
+	pub coords: Coords,
+	/// The dimensions of the [window], not including its border.
+	///
+	/// [window]: Window
+	#[doc(alias("width", "height"))]
+	pub dimensions: Dimensions,
+	/// The width of the [window]'s border.
+	///
+	/// [window]: Window
+	pub border_width: Px<u16>,
+}
+
+impl WindowGeometry {
+	/// Returns the [window]'s `coords` and `dimensions` as a [`Rectangle`].
+	///
+	/// [window]: Window
+	#[must_use]
+	pub const fn as_rectangle(&self) -> Rectangle {
+		Rectangle::new(
+			self.coords.x,
+			self.coords.y,
+			self.dimensions.width,
+			self.dimensions.height,
+		)
+	}
+
+	/// Returns whether the given `coords` lie within the [window], including
+	/// its border.
+	///
+	/// The `coords` must be relative to the top-left corner of the [window]'s
+	/// parent, like the [window]'s own `coords`.
+	///
+	/// [window]: Window
+	#[must_use]
+	pub fn contains(&self, coords: Coords) -> bool {
+		let border = 2 * i32::from(self.border_width.0);
+
+		let (left, top) = (i32::from(self.coords.x.0), i32::from(self.coords.y.0));
+		let right = left + i32::from(self.dimensions.width.0) + border;
+		let bottom = top + i32::from(self.dimensions.height.0) + border;
+
+		(left..right).contains(&i32::from(coords.x.0))
+			&& (top..bottom).contains(&i32::from(coords.y.0))
+	}
+
+	/// Returns this geometry moved by the given `x` and `y` offsets.
+	///
+	/// Coordinates which would fall outside the range of an [`i16`] are
+	/// clamped to that range: see [`Coords::offset_by`].
+	#[must_use]
+	pub fn translate(&self, x: Px<i32>, y: Px<i32>) -> Self {
+		Self {
+			coords: self.coords.offset_by(x, y),
+			..*self
+		}
+	}
+}
+
+/// Returns the lower of the given coordinates and the distance between them.
+#[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+fn span(first: i16, second: i16) -> (i16, u16) {
+	let start = first.min(second);
+	let distance = i32::from(first.max(second)) - i32::from(start);
+
+	(start, distance as u16)
+}
+
+/// A [`Rectangle`] or [`Region`] could not be converted because its coordinates
+/// would fall outside the range of the other type.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+#[error("the coordinates are outside the range of the target type")]
+pub struct GeometryOverflow;
+
+impl TryFrom<Region> for Rectangle {
+	type Error = GeometryOverflow;
+
+	fn try_from(region: Region) -> Result<Self, Self::Error> {
+		let x = i16::try_from(region.x.0).map_err(|_| GeometryOverflow)?;
+		let y = i16::try_from(region.y.0).map_err(|_| GeometryOverflow)?;
+
+		Ok(Self::new(Px(x), Px(y), region.width, region.height))
+	}
+}
+
+impl TryFrom<Rectangle> for Region {
+	type Error = GeometryOverflow;
+
+	fn try_from(rectangle: Rectangle) -> Result<Self, Self::Error> {
+		let x = u16::try_from(rectangle.x.0).map_err(|_| GeometryOverflow)?;
+		let y = u16::try_from(rectangle.y.0).map_err(|_| GeometryOverflow)?;
+
+		Ok(Self::new(Px(x), Px(y), rectangle.width, rectangle.height))
+	}
 }
 
 /// Same as a [`Rectangle`], but with unsigned coordinates.
 #[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Region {
 	/// The x-coordinate of the upper left corner of the `Region`.
 	pub x: Px<u16>,
@@ -549,6 +1176,8 @@ pub struct Region {
 
 /// A circular or elliptical arc.
 #[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arc {
 	/// The [rectangle] which contains the arc.
 	///
@@ -577,7 +1206,8 @@ pub struct Arc {
 /// The address family of a host.
 ///
 /// This is used in [`Host`].
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HostFamily {
 	/// An IPv4 address.
 	///
@@ -594,15 +1224,73 @@ pub enum HostFamily {
 	/// A server-specific address interpreted by the X server.
 	///
 	/// See [`HostAddress::ServerInterpreted`] for more information.
-	ServerInterpreted = 5,
+	ServerInterpreted,
 	/// An IPv6 address.
 	///
 	/// See [`HostAddress::Ipv6`] for more information.
 	Ipv6,
+
+	/// A family not defined in the core X11 protocol, identified by its code.
+	///
+	/// See [`HostAddress::Unknown`] for more information.
+	Unknown(u8),
+}
+
+impl HostFamily {
+	/// The code identifying this family in the X11 protocol.
+	#[must_use]
+	pub const fn code(self) -> u8 {
+		match self {
+			Self::Ipv4 => 0,
+			Self::DecNet => 1,
+			Self::Chaos => 2,
+			Self::ServerInterpreted => 5,
+			Self::Ipv6 => 6,
+
+			Self::Unknown(code) => code,
+		}
+	}
+}
+
+impl From<u8> for HostFamily {
+	fn from(code: u8) -> Self {
+		match code {
+			0 => Self::Ipv4,
+			1 => Self::DecNet,
+			2 => Self::Chaos,
+			5 => Self::ServerInterpreted,
+			6 => Self::Ipv6,
+
+			code => Self::Unknown(code),
+		}
+	}
+}
+
+impl X11Size for HostFamily {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl ConstantX11Size for HostFamily {
+	const X11_SIZE: usize = 1;
+}
+
+impl Readable for HostFamily {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		Ok(u8::read_from(buf)?.into())
+	}
+}
+
+impl Writable for HostFamily {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.code().write_to(buf)
+	}
 }
 
 /// The string used to create an [`AsciiString`] was not encoded as ASCII.
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[error("the provided string was not encoded correctly in ASCII format")]
 pub struct NonAsciiEncoding;
 
@@ -610,6 +1298,7 @@ pub struct NonAsciiEncoding;
 ///
 /// This is used for [`HostAddress::ServerInterpreted`].
 #[derive(Clone, Debug, Hash, PartialEq, Eq, X11Size, Writable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AsciiString(Vec<u8>);
 
 impl AsciiString {
@@ -645,6 +1334,17 @@ impl AsciiString {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AsciiString {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The encoding is checked with `new`.
+		Self::new(<Vec<u8>>::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 impl ReadableWithContext for AsciiString {
 	type Context = usize;
 
@@ -657,6 +1357,7 @@ impl ReadableWithContext for AsciiString {
 ///
 /// [host]: Host
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HostAddress {
 	/// An IPv4 address.
 	Ipv4([u8; 4]),
@@ -687,6 +1388,17 @@ pub enum HostAddress {
 	},
 	/// An IPv6 address.
 	Ipv6([u8; 16]),
+
+	/// An address of a family not defined in the core X11 protocol.
+	Unknown {
+		/// The code identifying the address' family.
+		///
+		/// This is not the code of any family defined in the core X11
+		/// protocol.
+		family: u8,
+		/// The bytes of the address.
+		address: Vec<u8>,
+	},
 }
 
 impl HostAddress {
@@ -699,6 +1411,8 @@ impl HostAddress {
 			Self::Chaos(..) => HostFamily::Chaos,
 			Self::ServerInterpreted { .. } => HostFamily::ServerInterpreted,
 			Self::Ipv6(..) => HostFamily::Ipv6,
+
+			Self::Unknown { family, .. } => HostFamily::Unknown(*family),
 		}
 	}
 }
@@ -721,6 +1435,8 @@ impl X11Size for HostAddress {
 			},
 
 			Self::Ipv6(address) => address.x11_size(),
+
+			Self::Unknown { address, .. } => address.x11_size(),
 		}
 	}
 }
@@ -729,26 +1445,27 @@ impl ReadableWithContext for HostAddress {
 	type Context = (HostFamily, usize);
 
 	fn read_with(buf: &mut impl Buf, (family, length): &(HostFamily, usize)) -> ReadResult<Self> {
-		let buf = &mut buf.take(*length);
+		let buf = &mut xrbk::take(buf, *length);
 
 		match family {
 			HostFamily::Ipv4 => Ok(Self::Ipv4([
-				buf.get_u8(),
-				buf.get_u8(),
-				buf.get_u8(),
-				buf.get_u8(),
+				u8::read_from(buf)?,
+				u8::read_from(buf)?,
+				u8::read_from(buf)?,
+				u8::read_from(buf)?,
 			])),
-			HostFamily::DecNet => Ok(Self::DecNet([buf.get_u8(), buf.get_u8()])),
-			HostFamily::Chaos => Ok(Self::Chaos([buf.get_u8(), buf.get_u8()])),
+			HostFamily::DecNet => Ok(Self::DecNet([u8::read_from(buf)?, u8::read_from(buf)?])),
+			HostFamily::Chaos => Ok(Self::Chaos([u8::read_from(buf)?, u8::read_from(buf)?])),
 
 			HostFamily::ServerInterpreted => {
 				let mut address_type = vec![];
 				let mut address_value = vec![];
 
 				while buf.has_remaining() {
-					match buf.get_u8() {
+					match u8::read_from(buf)? {
+						// The `0` separating the type from the value has already
+						// been read.
 						0 => {
-							buf.advance(1);
 							address_value = <Vec<u8>>::read_with(buf, &buf.remaining())?;
 
 							break;
@@ -771,7 +1488,12 @@ impl ReadableWithContext for HostAddress {
 				}
 			},
 
-			HostFamily::Ipv6 => Ok(Self::Ipv6(array_init(|_| buf.get_u8()))),
+			HostFamily::Ipv6 => Ok(Self::Ipv6(<[u8; 16]>::read_from(buf)?)),
+
+			HostFamily::Unknown(family) => Ok(Self::Unknown {
+				family: *family,
+				address: <Vec<u8>>::read_with(buf, length)?,
+			}),
 		}
 	}
 }
@@ -795,6 +1517,8 @@ impl Writable for HostAddress {
 			},
 
 			Self::Ipv6(address) => address.write_to(buf)?,
+
+			Self::Unknown { address, .. } => address.write_to(buf)?,
 		}
 
 		Ok(())
@@ -806,6 +1530,8 @@ derive_xrb! {
 	///
 	/// [`ChangeHosts` request]: crate::x11::request::ChangeHosts
 	#[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct Host {
 		// The `address`' family.
 		let family: HostFamily = address => address.family(),
@@ -822,3 +1548,434 @@ derive_xrb! {
 		[_; address => pad(address)],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::rectangles;
+	use proptest::{arbitrary::any, prop_assert, prop_assert_eq, proptest};
+	use xrbk::Readable;
+
+	#[test]
+	fn test_grab_status_ok() {
+		assert_eq!(GrabStatus::Success.ok(), Ok(()));
+		assert_eq!(
+			GrabStatus::AlreadyGrabbed.ok(),
+			Err(GrabFailure::AlreadyGrabbed),
+		);
+		assert_eq!(GrabStatus::InvalidTime.ok(), Err(GrabFailure::InvalidTime));
+		assert_eq!(GrabStatus::NotViewable.ok(), Err(GrabFailure::NotViewable));
+		assert_eq!(GrabStatus::Frozen.ok(), Err(GrabFailure::Frozen));
+	}
+
+	#[test]
+	fn test_timestamp_wrapping_add() {
+		let time = Timestamp::from_millis(0xffff_fff0);
+
+		assert_eq!(
+			time.wrapping_add(Duration::from_millis(0x20)),
+			Timestamp::from_millis(0x10),
+		);
+		assert_eq!(time.wrapping_add(Duration::from_millis(1 << 32)), time,);
+	}
+
+	#[test]
+	fn test_timestamp_is_later_than_across_wrap() {
+		let before_wrap = Timestamp::from_millis(0xffff_ffff);
+		let after_wrap = Timestamp::from_millis(5);
+
+		assert!(after_wrap.is_later_than(before_wrap));
+		assert!(!before_wrap.is_later_than(after_wrap));
+		assert!(!after_wrap.is_later_than(after_wrap));
+
+		// Exactly half of the range ahead is considered earlier.
+		let time = Timestamp::from_millis(10);
+		assert!(time
+			.wrapping_add(Duration::from_millis((1 << 31) - 1))
+			.is_later_than(time));
+		assert!(!time
+			.wrapping_add(Duration::from_millis(1 << 31))
+			.is_later_than(time));
+	}
+
+	#[test]
+	fn test_timestamp_wrapping_cmp() {
+		let before_wrap = Timestamp::from_millis(0xffff_fff0);
+		let after_wrap = Timestamp::from_millis(0x10);
+
+		assert_eq!(after_wrap.wrapping_cmp(before_wrap), Ordering::Greater);
+		assert_eq!(before_wrap.wrapping_cmp(after_wrap), Ordering::Less);
+		assert_eq!(after_wrap.wrapping_cmp(after_wrap), Ordering::Equal);
+
+		// Sorting by `wrapping_cmp` puts times from before the wrap first.
+		let mut times = [
+			Timestamp::from_millis(0x20),
+			before_wrap,
+			after_wrap,
+			Timestamp::from_millis(0xffff_ffff),
+		];
+		times.sort_by(|a, b| a.wrapping_cmp(*b));
+
+		assert_eq!(
+			times,
+			[
+				before_wrap,
+				Timestamp::from_millis(0xffff_ffff),
+				after_wrap,
+				Timestamp::from_millis(0x20),
+			],
+		);
+	}
+
+	#[test]
+	fn test_currentable_time_from_timestamp() {
+		let time = Timestamp::from_millis(1234);
+
+		assert_eq!(CurrentableTime::from(time).time(), Some(time));
+		assert_eq!(CurrentableTime::CurrentTime.time(), None);
+	}
+
+	#[test]
+	fn test_currentable_time_resolve() {
+		let time = Timestamp::from_millis(1234);
+		let now = Timestamp::from_millis(5678);
+
+		assert_eq!(CurrentableTime::Other(time).resolve(now), time);
+		assert_eq!(CurrentableTime::CurrentTime.resolve(now), now);
+	}
+
+	#[test]
+	fn test_host_unknown_family() {
+		#[rustfmt::skip]
+		let bytes = [
+			// Family, unused byte, and address length.
+			254, 0, 0, 3,
+			// The address, padded to 4 bytes.
+			1, 2, 3, 0,
+		];
+
+		let host = Host::read_from(&mut &bytes[..]).unwrap();
+
+		assert_eq!(
+			host.address,
+			HostAddress::Unknown {
+				family: 254,
+				address: vec![1, 2, 3],
+			},
+		);
+		assert_eq!(host.address.family(), HostFamily::Unknown(254));
+
+		let mut written = vec![];
+		host.write_to(&mut written).unwrap();
+
+		assert_eq!(written, bytes);
+	}
+
+	#[test]
+	fn test_host_family_codes() {
+		for code in 0..=u8::MAX {
+			assert_eq!(HostFamily::from(code).code(), code);
+		}
+
+		assert_eq!(HostFamily::from(5), HostFamily::ServerInterpreted);
+		assert_eq!(HostFamily::from(3), HostFamily::Unknown(3));
+	}
+
+	#[test]
+	fn test_length_string8_round_trip() {
+		for len in [0, 1, 255] {
+			let string = LengthString8::try_from("x".repeat(len).as_str()).unwrap();
+
+			let mut bytes = vec![];
+			string.write_to(&mut bytes).unwrap();
+
+			assert_eq!(bytes.len(), 1 + len);
+			assert_eq!(usize::from(bytes[0]), len);
+			assert_eq!(LengthString8::read_exact(&bytes).unwrap(), string);
+		}
+	}
+
+	#[test]
+	fn test_read_exact() {
+		let string = LengthString8::try_from("abc").unwrap();
+		let geometry = WindowGeometry::new(
+			Coords::new(Px(1), Px(2)),
+			Dimensions::new(Px(3), Px(4)),
+			Px(5),
+		);
+
+		let mut string_bytes = vec![];
+		string.write_to(&mut string_bytes).unwrap();
+		let mut geometry_bytes = vec![];
+		geometry.write_to(&mut geometry_bytes).unwrap();
+
+		// Exactly the right number of bytes.
+		assert_eq!(LengthString8::read_exact(&string_bytes).unwrap(), string);
+		assert_eq!(
+			WindowGeometry::read_exact(&geometry_bytes).unwrap(),
+			geometry
+		);
+
+		// Too few bytes.
+		assert!(matches!(
+			LengthString8::read_exact(&string_bytes[..3]),
+			Err(ReadError::UnexpectedEndOfData { .. }),
+		));
+		assert!(matches!(
+			WindowGeometry::read_exact(&geometry_bytes[..9]),
+			Err(ReadError::UnexpectedEndOfData { .. }),
+		));
+
+		// Too many bytes.
+		string_bytes.push(0);
+		geometry_bytes.extend([0, 0]);
+
+		assert!(matches!(
+			LengthString8::read_exact(&string_bytes),
+			Err(ReadError::TrailingData { remaining: 1 }),
+		));
+		assert!(matches!(
+			WindowGeometry::read_exact(&geometry_bytes),
+			Err(ReadError::TrailingData { remaining: 2 }),
+		));
+		assert_eq!(
+			WindowGeometry::read_from_bytes(&geometry_bytes).unwrap(),
+			(geometry, 10),
+		);
+	}
+
+	#[test]
+	fn test_length_string8_too_long() {
+		assert_eq!(
+			LengthString8::try_from("x".repeat(256).as_str()),
+			Err(StringError::TooLong(256)),
+		);
+	}
+
+	#[test]
+	fn test_length_string8_latin1() {
+		// `é` is one byte in ISO Latin-1, but two bytes in UTF-8.
+		let string = LengthString8::try_from("café").unwrap();
+
+		assert_eq!(string.len(), 4);
+		assert_eq!(string.as_string8().to_latin1_string(), "café");
+
+		let mut bytes = vec![];
+		string.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes, [4, b'c', b'a', b'f', 0xe9]);
+
+		assert_eq!(
+			LengthString8::try_from("€"),
+			Err(StringError::NotLatin1 {
+				char: '€',
+				index: 0,
+			}),
+		);
+	}
+
+	#[test]
+	fn test_window_geometry_contains() {
+		let geometry = WindowGeometry::new(
+			Coords::new(Px(10), Px(20)),
+			Dimensions::new(Px(100), Px(50)),
+			Px(2),
+		);
+
+		// The border is included on every side.
+		assert!(geometry.contains(Coords::new(Px(10), Px(20))));
+		assert!(geometry.contains(Coords::new(Px(113), Px(73))));
+
+		assert!(!geometry.contains(Coords::new(Px(9), Px(20))));
+		assert!(!geometry.contains(Coords::new(Px(114), Px(50))));
+		assert!(!geometry.contains(Coords::new(Px(50), Px(74))));
+
+		let moved = geometry.translate(Px(-20), Px(5));
+
+		assert_eq!(moved.coords, Coords::new(Px(-10), Px(25)));
+		assert_eq!(moved.dimensions, geometry.dimensions);
+		assert_eq!(moved.border_width, geometry.border_width);
+
+		assert_eq!(
+			geometry.translate(Px(i32::MAX), Px(0)).coords,
+			Coords::new(Px(i16::MAX), Px(20)),
+		);
+	}
+
+	#[test]
+	fn test_string8_latin1_round_trip() {
+		for (string, encoded) in [
+			("WM_NAME", &b"WM_NAME"[..]),
+			// Accented characters are one byte each in ISO Latin-1.
+			("naïve façade", &b"na\xefve fa\xe7ade"[..]),
+		] {
+			let string8 = String8::from_latin1(string).unwrap();
+
+			let mut bytes = vec![];
+			string8.write_to(&mut bytes).unwrap();
+
+			assert_eq!(string8.len(), string.chars().count());
+			assert_eq!(bytes, encoded);
+
+			let read = String8::read_with(&mut &bytes[..], &bytes.len()).unwrap();
+
+			assert_eq!(read, string8);
+			assert_eq!(read.to_latin1_string(), string);
+		}
+	}
+
+	#[test]
+	fn test_string8_rejects_emoji() {
+		assert_eq!(
+			String8::from_latin1("café 🎉"),
+			Err(StringError::NotLatin1 {
+				char: '🎉',
+				index: 6,
+			}),
+		);
+	}
+
+	#[test]
+	fn test_length_string8_list_overrun() {
+		// The second string claims to be 5 bytes long, but only 2 bytes follow.
+		let bytes = [1, b'a', 5, b'b', b'c'];
+
+		assert!(matches!(
+			<Vec<LengthString8>>::read_with(&mut &bytes[..], &2),
+			Err(ReadError::UnexpectedEndOfData {
+				expected: 5,
+				found: 2,
+			}),
+		));
+		// Nor is there a length for a third string.
+		assert!(matches!(
+			<Vec<LengthString8>>::read_with(&mut &bytes[..2], &2),
+			Err(ReadError::UnexpectedEndOfData {
+				expected: 1,
+				found: 0,
+			}),
+		));
+	}
+
+	#[test]
+	fn test_rectangle_corners_at_limits() {
+		let full = Rectangle::new(Px(i16::MIN), Px(i16::MIN), Px(u16::MAX), Px(u16::MAX));
+		let max = Coords::new(Px(i16::MAX), Px(i16::MAX));
+
+		assert_eq!(full.corners(), Some((full.as_coords(), max)));
+		assert_eq!(Rectangle::from_corners(max, full.as_coords()), full);
+
+		let past = Rectangle::new(Px(i16::MAX), Px(0), Px(1), Px(1));
+
+		assert_eq!(past.corners(), None);
+		assert_eq!(
+			past.saturating_corners().1,
+			Coords::new(Px(i16::MAX), Px(1))
+		);
+	}
+
+	#[test]
+	fn test_coords_offset_by_saturates() {
+		let coords = Coords::new(Px(i16::MIN), Px(i16::MAX));
+
+		assert_eq!(coords.offset_by(Px(-1), Px(1)), coords);
+		assert_eq!(coords.checked_offset_by(Px(-1), Px(0)), None);
+		assert_eq!(
+			coords.checked_offset_by(Px(65535), Px(-65535)),
+			Some(Coords::new(Px(i16::MAX), Px(i16::MIN))),
+		);
+	}
+
+	proptest! {
+		#[test]
+		fn test_timestamp_arithmetic_across_wrap(
+			millis in (u32::MAX - 1000)..=u32::MAX,
+			delta in 0_u32..2000,
+		) {
+			let time = Timestamp::from_millis(millis);
+			let later = time.wrapping_add_millis(delta);
+
+			prop_assert_eq!(later, time.wrapping_add(Duration::from_millis(u64::from(delta))));
+			prop_assert_eq!(later.duration_since(time), delta);
+			prop_assert_eq!(time.duration_since(later), delta.wrapping_neg());
+
+			prop_assert_eq!(later.is_later_than(time), delta != 0);
+			prop_assert!(!time.is_later_than(later));
+			prop_assert_eq!(later.wrapping_cmp(time), delta.cmp(&0));
+		}
+
+		#[test]
+		fn test_timestamp_half_range_rule(millis in any::<u32>(), delta in any::<u32>()) {
+			let time = Timestamp::from_millis(millis);
+			let other = time.wrapping_add_millis(delta);
+
+			prop_assert_eq!(other.is_later_than(time), delta != 0 && delta < 1 << 31);
+			// At most one of two different times is later than the other.
+			prop_assert!(!(other.is_later_than(time) && time.is_later_than(other)));
+		}
+
+		#[test]
+		fn test_rectangle_corners_round_trip(rectangle in rectangles()) {
+			let right = i32::from(rectangle.x.0) + i32::from(rectangle.width.0);
+			let bottom = i32::from(rectangle.y.0) + i32::from(rectangle.height.0);
+
+			match rectangle.corners() {
+				Some((top_left, bottom_right)) => {
+					prop_assert_eq!(Rectangle::from_corners(top_left, bottom_right), rectangle);
+				},
+
+				None => prop_assert!(right > i32::from(i16::MAX) || bottom > i32::from(i16::MAX)),
+			}
+		}
+
+		#[test]
+		fn test_rectangle_contains(rectangle in rectangles(), coords in any::<Coords>()) {
+			let (x, y) = (i32::from(coords.x.0), i32::from(coords.y.0));
+			let left = i32::from(rectangle.x.0);
+			let top = i32::from(rectangle.y.0);
+
+			prop_assert_eq!(
+				rectangle.contains(coords),
+				left <= x
+					&& x < left + i32::from(rectangle.width.0)
+					&& top <= y
+					&& y < top + i32::from(rectangle.height.0),
+			);
+		}
+
+		#[test]
+		fn test_rectangle_intersect(first in rectangles(), second in rectangles()) {
+			let intersection = first.intersect(&second);
+			prop_assert_eq!(intersection, second.intersect(&first));
+
+			if let Some(intersection) = intersection {
+				prop_assert!(first.contains(intersection.as_coords()));
+				prop_assert!(second.contains(intersection.as_coords()));
+
+				prop_assert_eq!(first.intersect(&intersection), Some(intersection));
+				prop_assert_eq!(second.intersect(&intersection), Some(intersection));
+			}
+		}
+
+		#[test]
+		fn test_rectangle_clamp_within(rectangle in rectangles(), parent in rectangles()) {
+			let clamped = rectangle.clamp_within(&parent);
+
+			prop_assert!(clamped.width <= parent.width && clamped.height <= parent.height);
+
+			if clamped.width.0 > 0 && clamped.height.0 > 0 {
+				prop_assert_eq!(clamped.intersect(&parent), Some(clamped));
+			}
+			if rectangle.intersect(&parent) == Some(rectangle) {
+				prop_assert_eq!(clamped, rectangle);
+			}
+		}
+
+		#[test]
+		fn test_rectangle_region_conversion(rectangle in rectangles()) {
+			match Region::try_from(rectangle) {
+				Ok(region) => prop_assert_eq!(Rectangle::try_from(region), Ok(rectangle)),
+				Err(_) => prop_assert!(rectangle.x.0 < 0 || rectangle.y.0 < 0),
+			}
+		}
+	}
+}
@@ -2,11 +2,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-//! [`Atom`] and predefined atom `const`s defined in the core protocol.
+//! [`Atom`], predefined atom `const`s defined in the core protocol, and an
+//! [`AtomCache`] of the names of other atoms.
+
+use std::collections::HashMap;
 
 use derive_more::{From, Into};
+use xrbk::ZeroIsNone;
 use xrbk_macro::{ConstantX11Size, Readable, Wrap, Writable, X11Size};
 
+use crate::{
+	x11::{reply, request},
+	String8,
+	StringError,
+};
+
 /// A unique ID corresponding to a string name.
 ///
 /// `Atom`s are used to identify properties, types, and selections.
@@ -26,6 +36,7 @@ use xrbk_macro::{ConstantX11Size, Readable, Wrap, Writable, X11Size};
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Atom(u32);
 
 impl Atom {
@@ -42,6 +53,9 @@ impl Atom {
 	}
 }
 
+// No atom is ever assigned an ID of zero, so zero is used to encode `None`.
+impl ZeroIsNone for Atom {}
+
 macro_rules! atoms {
 	(
 		$(
@@ -53,6 +67,39 @@ macro_rules! atoms {
 			$(#[$attr])*
 			pub const $ATOM: Atom = Atom::new($id);
 		)*
+
+		impl Atom {
+			/// Returns the predefined `Atom` with the given `name`, if there is
+			/// one.
+			///
+			/// Only the atoms predefined in the core protocol are known; the
+			/// IDs of any other atoms must be found with a [`GetAtom`
+			/// request].
+			///
+			/// [`GetAtom` request]: crate::x11::request::GetAtom
+			#[must_use]
+			pub fn from_name(name: &str) -> Option<Self> {
+				match name {
+					$(::core::stringify!($ATOM) => Some($ATOM),)*
+					_ => None,
+				}
+			}
+
+			/// Returns the name of this `Atom` if it is predefined in the core
+			/// protocol.
+			///
+			/// The names of any other atoms must be found with a [`GetAtomName`
+			/// request].
+			///
+			/// [`GetAtomName` request]: crate::x11::request::GetAtomName
+			#[must_use]
+			pub const fn name(&self) -> Option<&'static str> {
+				match self.0 {
+					$($id => Some(::core::stringify!($ATOM)),)*
+					_ => None,
+				}
+			}
+		}
 	}
 }
 
@@ -126,3 +173,229 @@ atoms! {
 	WM_CLASS = 67,
 	WM_TRANSIENT_FOR = 68,
 }
+
+/// The result of looking up an [`Atom`] by name in an [`AtomCache`].
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub enum AtomLookup {
+	/// The [`Atom`] with the given name is already known.
+	Cached(Atom),
+	/// The [`Atom`] with the given name is not known.
+	///
+	/// This [`GetAtom` request] must be sent to find it, and its reply given
+	/// to [`AtomCache::resolve`].
+	///
+	/// [`GetAtom` request]: request::GetAtom
+	Request(request::GetAtom),
+}
+
+/// A cache of the names of [`Atom`]s, so that each need only be requested
+/// from the X server once.
+///
+/// The `AtomCache` does not send requests itself. Instead, where an [`Atom`]
+/// or name is not yet known, it returns the request which must be sent to find
+/// it; the reply to that request is then given back to the `AtomCache`.
+///
+/// The [atoms predefined in the core protocol] are always known.
+///
+/// [atoms predefined in the core protocol]: self
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AtomCache {
+	atoms: HashMap<String, Atom>,
+	names: HashMap<Atom, String>,
+}
+
+impl AtomCache {
+	/// Creates a new, empty `AtomCache`.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the [`Atom`] with the given `name`, if it is known.
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<Atom> {
+		Atom::from_name(name).or_else(|| self.atoms.get(name).copied())
+	}
+
+	/// Returns the name of the given [`Atom`], if it is known.
+	#[must_use]
+	pub fn name(&self, atom: Atom) -> Option<&str> {
+		atom.name()
+			.or_else(|| self.names.get(&atom).map(String::as_str))
+	}
+
+	/// Returns the [`Atom`] with the given `name` if it is known, or else the
+	/// [`GetAtom` request] which must be sent to find it.
+	///
+	/// `no_creation` is used for the [`GetAtom` request]: if it is `true`, the
+	/// X server will not create a new [`Atom`] if there is none with the given
+	/// `name`.
+	///
+	/// # Errors
+	/// Returns a [`StringError`] if the `name` cannot be encoded in ISO
+	/// Latin-1.
+	///
+	/// [`GetAtom` request]: request::GetAtom
+	pub fn intern_request(&self, name: &str, no_creation: bool) -> Result<AtomLookup, StringError> {
+		if let Some(atom) = self.get(name) {
+			return Ok(AtomLookup::Cached(atom));
+		}
+
+		Ok(AtomLookup::Request(request::GetAtom {
+			no_creation,
+			name: String8::from_latin1(name)?,
+		}))
+	}
+
+	/// Adds the [`Atom`] returned in the `reply` to a [`GetAtom` request] for
+	/// the given `name` to the cache, and returns it.
+	///
+	/// If the [`GetAtom` request] had `no_creation` set and there was no
+	/// [`Atom`] with the given `name`, the `reply` contains no [`Atom`]. In
+	/// that case, nothing is cached, since the [`Atom`] may yet be created,
+	/// and [`None`] is returned.
+	///
+	/// [`GetAtom` request]: request::GetAtom
+	pub fn resolve(&mut self, name: &str, reply: &reply::GetAtom) -> Option<Atom> {
+		let atom = reply.atom?;
+
+		self.atoms.insert(name.to_owned(), atom);
+		self.names.insert(atom, name.to_owned());
+
+		Some(atom)
+	}
+
+	/// Returns the name of the given [`Atom`] if it is known, or else the
+	/// [`GetAtomName` request] which must be sent to find it.
+	///
+	/// # Errors
+	/// Returns the [`GetAtomName` request] to send if the name of the
+	/// [`Atom`] is not known.
+	///
+	/// [`GetAtomName` request]: request::GetAtomName
+	pub fn name_request(&self, atom: Atom) -> Result<&str, request::GetAtomName> {
+		self.name(atom).ok_or(request::GetAtomName { target: atom })
+	}
+
+	/// Adds the name returned in the `reply` to a [`GetAtomName` request] for
+	/// the given [`Atom`] to the cache, and returns it.
+	///
+	/// [`GetAtomName` request]: request::GetAtomName
+	pub fn resolve_name(&mut self, atom: Atom, reply: &reply::GetAtomName) -> &str {
+		let name = reply.name.to_latin1_string();
+
+		self.atoms.insert(name.clone(), atom);
+		self.names.insert(atom, name);
+
+		&self.names[&atom]
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use xrbk::Readable;
+
+	#[test]
+	fn test_predefined_names() {
+		assert_eq!(Atom::from_name("PRIMARY"), Some(PRIMARY));
+		assert_eq!(Atom::from_name("WM_TRANSIENT_FOR"), Some(WM_TRANSIENT_FOR));
+		// `CLIPBOARD` is not predefined in the core protocol.
+		assert_eq!(Atom::from_name("CLIPBOARD"), None);
+
+		assert_eq!(STRING.unwrap(), 31);
+		assert_eq!(STRING.name(), Some("STRING"));
+		assert_eq!(Atom::new(69).name(), None);
+
+		for id in 1..=68 {
+			let atom = Atom::new(id);
+
+			assert_eq!(Atom::from_name(atom.name().unwrap()), Some(atom));
+		}
+	}
+
+	#[test]
+	fn test_atom_cache() {
+		let mut cache = AtomCache::new();
+
+		// Predefined atoms are always known.
+		assert_eq!(
+			cache.intern_request("WM_NAME", false),
+			Ok(AtomLookup::Cached(WM_NAME)),
+		);
+
+		// Miss.
+		let Ok(AtomLookup::Request(request)) = cache.intern_request("_NET_WM_NAME", false) else {
+			panic!("`_NET_WM_NAME` should not be cached yet");
+		};
+		assert_eq!(request.name, String8::from_latin1("_NET_WM_NAME").unwrap());
+		assert!(!request.no_creation);
+
+		let reply = reply::GetAtom {
+			sequence: 1,
+			atom: Some(Atom::new(300)),
+		};
+		assert_eq!(cache.resolve("_NET_WM_NAME", &reply), Some(Atom::new(300)));
+
+		// Hit.
+		assert_eq!(
+			cache.intern_request("_NET_WM_NAME", false),
+			Ok(AtomLookup::Cached(Atom::new(300))),
+		);
+		assert_eq!(cache.name_request(Atom::new(300)), Ok("_NET_WM_NAME"));
+
+		assert_eq!(
+			cache.intern_request("\u{263a}", false),
+			Err(StringError::NotLatin1 {
+				char: '\u{263a}',
+				index: 0,
+			}),
+		);
+	}
+
+	#[test]
+	fn test_atom_cache_no_creation_missing_atom() {
+		let mut cache = AtomCache::new();
+
+		let Ok(AtomLookup::Request(request)) = cache.intern_request("_MISSING", true) else {
+			panic!("`_MISSING` should not be cached");
+		};
+		assert!(request.no_creation);
+
+		// An atom of zero means that there is no atom with that name.
+		let bytes = [1, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+			.into_iter()
+			.chain([0; 16])
+			.collect::<Vec<u8>>();
+		let reply = reply::GetAtom::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(reply.atom, None);
+
+		assert_eq!(cache.resolve("_MISSING", &reply), None);
+		assert!(matches!(
+			cache.intern_request("_MISSING", true),
+			Ok(AtomLookup::Request(_)),
+		));
+	}
+
+	#[test]
+	fn test_atom_cache_names() {
+		let mut cache = AtomCache::new();
+
+		assert_eq!(cache.name_request(PRIMARY), Ok("PRIMARY"));
+		assert_eq!(
+			cache.name_request(Atom::new(400)),
+			Err(request::GetAtomName {
+				target: Atom::new(400),
+			}),
+		);
+
+		let reply = reply::GetAtomName {
+			sequence: 1,
+			name: String8::from_latin1("UTF8_STRING").unwrap(),
+		};
+		assert_eq!(cache.resolve_name(Atom::new(400), &reply), "UTF8_STRING");
+
+		assert_eq!(cache.get("UTF8_STRING"), Some(Atom::new(400)));
+		assert_eq!(cache.name(Atom::new(400)), Some("UTF8_STRING"));
+	}
+}
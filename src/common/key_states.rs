@@ -0,0 +1,202 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use derive_more::{From, Into};
+use xrbk_macro::{ConstantX11Size, Readable, Writable, X11Size};
+
+use crate::Keycode;
+
+/// A bit vector with a bit for each [keycode], such as whether each key is
+/// pressed.
+///
+/// Byte `N`, starting at `0`, contains the bits for [keycodes] `8N` to
+/// `8N + 7`. The least significant bit in each byte represents [keycode] `8N`.
+///
+/// # Keycodes 0 to 7
+/// [Keycodes] `0` to `7` are never used by the X server, but the bit vector
+/// still begins with their bits so that every other [keycode]'s bit is found
+/// at its own index. Those bits are ignored: [keycodes] `0` to `7` are never
+/// [pressed], and are skipped when collecting [keycodes] into `KeyStates`.
+///
+/// [keycode]: Keycode
+/// [keycodes]: Keycode
+/// [Keycodes]: Keycode
+/// [pressed]: KeyStates::is_pressed
+#[doc(alias("Keymap", "keys"))]
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Hash,
+	Debug,
+	Default,
+	From,
+	Into,
+	// XRBK traits
+	X11Size,
+	ConstantX11Size,
+	Readable,
+	Writable,
+)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyStates([u8; 32]);
+
+impl KeyStates {
+	/// The lowest [keycode] which may be used.
+	///
+	/// [keycode]: Keycode
+	const MIN_KEYCODE: u8 = 8;
+
+	/// `KeyStates` in which no key is pressed.
+	pub const EMPTY: Self = Self([0; 32]);
+
+	/// The index of the byte and the mask of the bit for the given `keycode`.
+	const fn position(keycode: Keycode) -> (usize, u8) {
+		let keycode = keycode.unwrap();
+
+		((keycode / 8) as usize, 1 << (keycode % 8))
+	}
+
+	/// Whether the bit for the given `keycode` is set.
+	///
+	/// This is always `false` for [keycodes] `0` to `7`.
+	///
+	/// [keycodes]: Keycode
+	#[must_use]
+	pub const fn is_set(&self, keycode: Keycode) -> bool {
+		let (byte, bit) = Self::position(keycode);
+
+		keycode.unwrap() >= Self::MIN_KEYCODE && self.0[byte] & bit != 0
+	}
+
+	/// Sets or clears the bit for the given `keycode`.
+	///
+	/// [Keycodes] `0` to `7` are ignored.
+	///
+	/// [Keycodes]: Keycode
+	pub const fn set(&mut self, keycode: Keycode, set: bool) {
+		if keycode.unwrap() < Self::MIN_KEYCODE {
+			return;
+		}
+
+		let (byte, bit) = Self::position(keycode);
+
+		if set {
+			self.0[byte] |= bit;
+		} else {
+			self.0[byte] &= !bit;
+		}
+	}
+
+	/// Returns an iterator over the [keycodes] whose bits are set, in
+	/// ascending order.
+	///
+	/// [keycodes]: Keycode
+	pub fn keycodes(&self) -> impl Iterator<Item = Keycode> + '_ {
+		(Self::MIN_KEYCODE..=u8::MAX)
+			.map(Keycode::new)
+			.filter(|keycode| self.is_set(*keycode))
+	}
+
+	/// Whether the key with the given `keycode` is pressed.
+	///
+	/// This is the same as [`is_set`], for `KeyStates` representing which
+	/// keys are pressed.
+	///
+	/// [`is_set`]: KeyStates::is_set
+	#[must_use]
+	pub const fn is_pressed(&self, keycode: Keycode) -> bool {
+		self.is_set(keycode)
+	}
+
+	/// Sets whether the key with the given `keycode` is pressed.
+	///
+	/// This is the same as [`set`], for `KeyStates` representing which keys
+	/// are pressed.
+	///
+	/// [`set`]: KeyStates::set
+	pub const fn set_pressed(&mut self, keycode: Keycode, pressed: bool) {
+		self.set(keycode, pressed);
+	}
+
+	/// Returns an iterator over the [keycodes] of the pressed keys, in
+	/// ascending order.
+	///
+	/// This is the same as [`keycodes`], for `KeyStates` representing which
+	/// keys are pressed.
+	///
+	/// [keycodes]: Keycode
+	/// [`keycodes`]: KeyStates::keycodes
+	pub fn pressed(&self) -> impl Iterator<Item = Keycode> + '_ {
+		self.keycodes()
+	}
+}
+
+impl FromIterator<Keycode> for KeyStates {
+	/// Creates `KeyStates` in which the bits for the given [keycodes] are
+	/// set.
+	///
+	/// [Keycodes][keycodes] `0` to `7` are ignored.
+	///
+	/// [keycodes]: Keycode
+	fn from_iter<I: IntoIterator<Item = Keycode>>(keycodes: I) -> Self {
+		let mut states = Self::EMPTY;
+
+		for keycode in keycodes {
+			states.set(keycode, true);
+		}
+
+		states
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_boundary_keycodes() {
+		let states: KeyStates = [Keycode::new(8), Keycode::new(255)].into_iter().collect();
+
+		assert_eq!(
+			<[u8; 32]>::from(states),
+			[
+				0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+				0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80,
+			]
+		);
+
+		assert!(states.is_pressed(Keycode::new(8)));
+		assert!(states.is_pressed(Keycode::new(255)));
+		assert!(!states.is_pressed(Keycode::new(9)));
+		assert!(!states.is_pressed(Keycode::new(254)));
+
+		assert_eq!(
+			states.pressed().collect::<Vec<_>>(),
+			[Keycode::new(8), Keycode::new(255)]
+		);
+	}
+
+	#[test]
+	fn test_keycodes_below_8_are_ignored() {
+		let states = KeyStates::from([0xff; 32]);
+
+		for keycode in 0..8 {
+			assert!(!states.is_pressed(Keycode::new(keycode)));
+		}
+		assert_eq!(states.pressed().count(), 248);
+
+		let states: KeyStates = (0..8).map(Keycode::new).collect();
+		assert_eq!(states, KeyStates::EMPTY);
+	}
+
+	#[test]
+	fn test_empty() {
+		assert_eq!(KeyStates::EMPTY.pressed().next(), None);
+		assert_eq!(KeyStates::default(), KeyStates::EMPTY);
+	}
+}
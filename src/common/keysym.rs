@@ -0,0 +1,614 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`Keysym`] and `const`s for commonly used keysyms.
+//!
+//! The `const`s are named as they are in the X Window System's `keysymdef.h`,
+//! without the `XK_` prefix. Keysyms whose names begin with a digit are
+//! prefixed with an underscore instead: `XK_0` is [`_0`].
+
+#![allow(non_upper_case_globals)]
+
+use derive_more::{From, Into};
+use xrbk_macro::{ConstantX11Size, Readable, Wrap, Writable, X11Size};
+
+/// A symbol engraved on a key.
+///
+/// Each [keycode] is mapped to a list of `Keysym`s, which may be retrieved with
+/// a [`GetKeyboardMapping` request]. Which of those `Keysym`s is chosen for a
+/// key press depends on which modifiers are held.
+///
+/// Keysyms in the Latin-1 range have the same value as the character they
+/// represent. Other Unicode characters are encoded as their code point plus
+/// `0x0100_0000`. See [`Keysym::from_char`] and [`Keysym::to_char`].
+///
+/// [keycode]: crate::Keycode
+/// [`GetKeyboardMapping` request]: crate::x11::request::GetKeyboardMapping
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Hash,
+	Debug,
+	From,
+	Into,
+	// XRBK traits
+	X11Size,
+	ConstantX11Size,
+	Readable,
+	Writable,
+	Wrap,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keysym(pub(crate) u32);
+
+/// The offset added to a Unicode code point to encode it as a [`Keysym`].
+const UNICODE_OFFSET: u32 = 0x0100_0000;
+
+impl Keysym {
+	/// The keysym of a key position with no symbol (`NoSymbol`).
+	pub const NO_SYMBOL: Self = Self::new(0x0000_0000);
+	/// A keysym which is never mapped to any symbol (`VoidSymbol`).
+	pub const VOID_SYMBOL: Self = Self::new(0x00ff_ffff);
+
+	/// Creates a new `Keysym` with the given raw keysym value.
+	#[must_use]
+	pub const fn new(keysym: u32) -> Self {
+		Self(keysym)
+	}
+
+	/// Returns the raw contained keysym value.
+	#[must_use]
+	pub const fn unwrap(&self) -> u32 {
+		self.0
+	}
+
+	/// Returns the `Keysym` which represents the given `char`, if there is one.
+	///
+	/// Printable Latin-1 characters are represented by the Latin-1 keysym of
+	/// the same value, and any other printable character by its Unicode keysym.
+	/// The control characters which correspond to a key - [`BackSpace`],
+	/// [`Tab`], [`Linefeed`], [`Return`], [`Escape`], and [`Delete`] - are
+	/// represented by that key's keysym. Returns [`None`] for any other control
+	/// character.
+	///
+	/// Some characters outside of Latin-1 also have a legacy keysym (`'€'` is
+	/// `EuroSign` as well as `U+20AC`); the Unicode keysym is always returned.
+	#[must_use]
+	pub const fn from_char(character: char) -> Option<Self> {
+		let keysym = match character {
+			'\u{8}' => BackSpace,
+			'\t' => Tab,
+			'\n' => Linefeed,
+			'\r' => Return,
+			'\u{1b}' => Escape,
+			'\u{7f}' => Delete,
+
+			'\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => Self::new(character as u32),
+			'\u{0}'..='\u{9f}' => return None,
+
+			_ => Self::new(character as u32 + UNICODE_OFFSET),
+		};
+
+		Some(keysym)
+	}
+
+	/// Returns the `char` which this `Keysym` represents, if there is one.
+	///
+	/// This is the inverse of [`Keysym::from_char`]: Latin-1 and Unicode
+	/// keysyms, as well as the keysyms of keys which correspond to a control
+	/// character, are converted. Returns [`None`] for any other keysym.
+	#[must_use]
+	pub const fn to_char(&self) -> Option<char> {
+		match *self {
+			BackSpace => Some('\u{8}'),
+			Tab => Some('\t'),
+			Linefeed => Some('\n'),
+			Return => Some('\r'),
+			Escape => Some('\u{1b}'),
+			Delete => Some('\u{7f}'),
+
+			Self(keysym @ (0x20..=0x7e | 0xa0..=0xff)) => char::from_u32(keysym),
+			// Unicode keysyms are only used for characters outside of Latin-1.
+			Self(keysym @ 0x0100_0100..=0x0110_ffff) => char::from_u32(keysym - UNICODE_OFFSET),
+
+			_ => None,
+		}
+	}
+}
+
+/// Defines a [`Keysym`] constant for each of the given names and values.
+///
+/// Each constant is documented with its name and value, followed by any doc
+/// comments given for it.
+macro_rules! keysyms {
+	(
+		$(
+			$(#[$attr:meta])*
+			$KEYSYM:ident = $value:expr
+		),*$(,)?
+	) => {
+		$(
+			#[doc = concat!("The `", stringify!($KEYSYM), "` keysym, `", stringify!($value), "`.")]
+			#[doc = ""]
+			$(#[$attr])*
+			pub const $KEYSYM: Keysym = Keysym::new($value);
+		)*
+	};
+}
+
+// TTY function keys
+keysyms! {
+	BackSpace = 0xff08,
+	Tab = 0xff09,
+	Linefeed = 0xff0a,
+	Clear = 0xff0b,
+	Return = 0xff0d,
+	Pause = 0xff13,
+	Scroll_Lock = 0xff14,
+	Sys_Req = 0xff15,
+	Escape = 0xff1b,
+	Delete = 0xffff,
+}
+
+// Cursor control and motion
+keysyms! {
+	Home = 0xff50,
+	Left = 0xff51,
+	Up = 0xff52,
+	Right = 0xff53,
+	Down = 0xff54,
+	Prior = 0xff55,
+	/// The same keysym as [`Prior`].
+	Page_Up = 0xff55,
+	Next = 0xff56,
+	/// The same keysym as [`Next`].
+	Page_Down = 0xff56,
+	End = 0xff57,
+	Begin = 0xff58,
+}
+
+// Miscellaneous functions
+keysyms! {
+	Select = 0xff60,
+	Print = 0xff61,
+	Execute = 0xff62,
+	Insert = 0xff63,
+	Undo = 0xff65,
+	Redo = 0xff66,
+	Menu = 0xff67,
+	Find = 0xff68,
+	Cancel = 0xff69,
+	Help = 0xff6a,
+	Break = 0xff6b,
+	Mode_switch = 0xff7e,
+	Num_Lock = 0xff7f,
+}
+
+// Keypad
+keysyms! {
+	KP_Space = 0xff80,
+	KP_Tab = 0xff89,
+	KP_Enter = 0xff8d,
+	KP_Home = 0xff95,
+	KP_Left = 0xff96,
+	KP_Up = 0xff97,
+	KP_Right = 0xff98,
+	KP_Down = 0xff99,
+	KP_Prior = 0xff9a,
+	KP_Next = 0xff9b,
+	KP_End = 0xff9c,
+	KP_Begin = 0xff9d,
+	KP_Insert = 0xff9e,
+	KP_Delete = 0xff9f,
+	KP_Multiply = 0xffaa,
+	KP_Add = 0xffab,
+	KP_Separator = 0xffac,
+	KP_Subtract = 0xffad,
+	KP_Decimal = 0xffae,
+	KP_Divide = 0xffaf,
+	KP_0 = 0xffb0,
+	KP_1 = 0xffb1,
+	KP_2 = 0xffb2,
+	KP_3 = 0xffb3,
+	KP_4 = 0xffb4,
+	KP_5 = 0xffb5,
+	KP_6 = 0xffb6,
+	KP_7 = 0xffb7,
+	KP_8 = 0xffb8,
+	KP_9 = 0xffb9,
+	KP_Equal = 0xffbd,
+}
+
+// Function keys
+keysyms! {
+	F1 = 0xffbe,
+	F2 = 0xffbf,
+	F3 = 0xffc0,
+	F4 = 0xffc1,
+	F5 = 0xffc2,
+	F6 = 0xffc3,
+	F7 = 0xffc4,
+	F8 = 0xffc5,
+	F9 = 0xffc6,
+	F10 = 0xffc7,
+	F11 = 0xffc8,
+	F12 = 0xffc9,
+}
+
+// Modifiers
+keysyms! {
+	Shift_L = 0xffe1,
+	Shift_R = 0xffe2,
+	Control_L = 0xffe3,
+	Control_R = 0xffe4,
+	Caps_Lock = 0xffe5,
+	Shift_Lock = 0xffe6,
+	Meta_L = 0xffe7,
+	Meta_R = 0xffe8,
+	Alt_L = 0xffe9,
+	Alt_R = 0xffea,
+	Super_L = 0xffeb,
+	Super_R = 0xffec,
+	Hyper_L = 0xffed,
+	Hyper_R = 0xffee,
+	ISO_Level3_Shift = 0xfe03,
+}
+
+// Latin-1
+keysyms! {
+	space = 0x0020,
+	exclam = 0x0021,
+	quotedbl = 0x0022,
+	numbersign = 0x0023,
+	dollar = 0x0024,
+	percent = 0x0025,
+	ampersand = 0x0026,
+	apostrophe = 0x0027,
+	parenleft = 0x0028,
+	parenright = 0x0029,
+	asterisk = 0x002a,
+	plus = 0x002b,
+	comma = 0x002c,
+	minus = 0x002d,
+	period = 0x002e,
+	slash = 0x002f,
+	_0 = 0x0030,
+	_1 = 0x0031,
+	_2 = 0x0032,
+	_3 = 0x0033,
+	_4 = 0x0034,
+	_5 = 0x0035,
+	_6 = 0x0036,
+	_7 = 0x0037,
+	_8 = 0x0038,
+	_9 = 0x0039,
+	colon = 0x003a,
+	semicolon = 0x003b,
+	less = 0x003c,
+	equal = 0x003d,
+	greater = 0x003e,
+	question = 0x003f,
+	at = 0x0040,
+	A = 0x0041,
+	B = 0x0042,
+	C = 0x0043,
+	D = 0x0044,
+	E = 0x0045,
+	F = 0x0046,
+	G = 0x0047,
+	H = 0x0048,
+	I = 0x0049,
+	J = 0x004a,
+	K = 0x004b,
+	L = 0x004c,
+	M = 0x004d,
+	N = 0x004e,
+	O = 0x004f,
+	P = 0x0050,
+	Q = 0x0051,
+	R = 0x0052,
+	S = 0x0053,
+	T = 0x0054,
+	U = 0x0055,
+	V = 0x0056,
+	W = 0x0057,
+	X = 0x0058,
+	Y = 0x0059,
+	Z = 0x005a,
+	bracketleft = 0x005b,
+	backslash = 0x005c,
+	bracketright = 0x005d,
+	asciicircum = 0x005e,
+	underscore = 0x005f,
+	grave = 0x0060,
+	a = 0x0061,
+	b = 0x0062,
+	c = 0x0063,
+	d = 0x0064,
+	e = 0x0065,
+	f = 0x0066,
+	g = 0x0067,
+	h = 0x0068,
+	i = 0x0069,
+	j = 0x006a,
+	k = 0x006b,
+	l = 0x006c,
+	m = 0x006d,
+	n = 0x006e,
+	o = 0x006f,
+	p = 0x0070,
+	q = 0x0071,
+	r = 0x0072,
+	s = 0x0073,
+	t = 0x0074,
+	u = 0x0075,
+	v = 0x0076,
+	w = 0x0077,
+	x = 0x0078,
+	y = 0x0079,
+	z = 0x007a,
+	braceleft = 0x007b,
+	bar = 0x007c,
+	braceright = 0x007d,
+	asciitilde = 0x007e,
+
+	nobreakspace = 0x00a0,
+	exclamdown = 0x00a1,
+	cent = 0x00a2,
+	sterling = 0x00a3,
+	currency = 0x00a4,
+	yen = 0x00a5,
+	brokenbar = 0x00a6,
+	section = 0x00a7,
+	diaeresis = 0x00a8,
+	copyright = 0x00a9,
+	ordfeminine = 0x00aa,
+	guillemetleft = 0x00ab,
+	notsign = 0x00ac,
+	hyphen = 0x00ad,
+	registered = 0x00ae,
+	macron = 0x00af,
+	degree = 0x00b0,
+	plusminus = 0x00b1,
+	twosuperior = 0x00b2,
+	threesuperior = 0x00b3,
+	acute = 0x00b4,
+	mu = 0x00b5,
+	paragraph = 0x00b6,
+	periodcentered = 0x00b7,
+	cedilla = 0x00b8,
+	onesuperior = 0x00b9,
+	ordmasculine = 0x00ba,
+	guillemetright = 0x00bb,
+	onequarter = 0x00bc,
+	onehalf = 0x00bd,
+	threequarters = 0x00be,
+	questiondown = 0x00bf,
+	Agrave = 0x00c0,
+	Aacute = 0x00c1,
+	Acircumflex = 0x00c2,
+	Atilde = 0x00c3,
+	Adiaeresis = 0x00c4,
+	Aring = 0x00c5,
+	AE = 0x00c6,
+	Ccedilla = 0x00c7,
+	Egrave = 0x00c8,
+	Eacute = 0x00c9,
+	Ecircumflex = 0x00ca,
+	Ediaeresis = 0x00cb,
+	Igrave = 0x00cc,
+	Iacute = 0x00cd,
+	Icircumflex = 0x00ce,
+	Idiaeresis = 0x00cf,
+	ETH = 0x00d0,
+	Ntilde = 0x00d1,
+	Ograve = 0x00d2,
+	Oacute = 0x00d3,
+	Ocircumflex = 0x00d4,
+	Otilde = 0x00d5,
+	Odiaeresis = 0x00d6,
+	multiply = 0x00d7,
+	Oslash = 0x00d8,
+	Ugrave = 0x00d9,
+	Uacute = 0x00da,
+	Ucircumflex = 0x00db,
+	Udiaeresis = 0x00dc,
+	Yacute = 0x00dd,
+	THORN = 0x00de,
+	ssharp = 0x00df,
+	agrave = 0x00e0,
+	aacute = 0x00e1,
+	acircumflex = 0x00e2,
+	atilde = 0x00e3,
+	adiaeresis = 0x00e4,
+	aring = 0x00e5,
+	ae = 0x00e6,
+	ccedilla = 0x00e7,
+	egrave = 0x00e8,
+	eacute = 0x00e9,
+	ecircumflex = 0x00ea,
+	ediaeresis = 0x00eb,
+	igrave = 0x00ec,
+	iacute = 0x00ed,
+	icircumflex = 0x00ee,
+	idiaeresis = 0x00ef,
+	eth = 0x00f0,
+	ntilde = 0x00f1,
+	ograve = 0x00f2,
+	oacute = 0x00f3,
+	ocircumflex = 0x00f4,
+	otilde = 0x00f5,
+	odiaeresis = 0x00f6,
+	division = 0x00f7,
+	oslash = 0x00f8,
+	ugrave = 0x00f9,
+	uacute = 0x00fa,
+	ucircumflex = 0x00fb,
+	udiaeresis = 0x00fc,
+	yacute = 0x00fd,
+	thorn = 0x00fe,
+	ydiaeresis = 0x00ff,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		x11::{reply, request::GetKeyboardMapping},
+		Keycode,
+	};
+	use xrbk::{Readable, Writable, X11Size};
+
+	#[test]
+	fn test_char_conversions() {
+		assert_eq!(Keysym::from_char('a'), Some(a));
+		assert_eq!(Keysym::from_char('Z'), Some(Z));
+		assert_eq!(Keysym::from_char('0'), Some(_0));
+		assert_eq!(Keysym::from_char('é'), Some(eacute));
+		assert_eq!(Keysym::from_char('\r'), Some(Return));
+		assert_eq!(Keysym::from_char('€'), Some(Keysym::new(0x0100_20ac)));
+		assert_eq!(Keysym::from_char('\u{0}'), None);
+		assert_eq!(Keysym::from_char('\u{85}'), None);
+
+		assert_eq!(F1.to_char(), None);
+		assert_eq!(Shift_L.to_char(), None);
+		assert_eq!(Keysym::NO_SYMBOL.to_char(), None);
+		// Surrogates are not `char`s.
+		assert_eq!(Keysym::new(0x0100_d800).to_char(), None);
+		// Latin-1 characters are never encoded as Unicode keysyms.
+		assert_eq!(Keysym::new(0x0100_00e9).to_char(), None);
+
+		for character in [
+			'\u{8}',
+			'\t',
+			' ',
+			'~',
+			'\u{a0}',
+			'ÿ',
+			'Ā',
+			'€',
+			'\u{10_ffff}',
+		] {
+			let keysym = Keysym::from_char(character).unwrap();
+
+			assert_eq!(keysym.to_char(), Some(character));
+		}
+	}
+
+	/// The keysyms of a US QWERTY keyboard, in the order of the keycodes which
+	/// an X server using evdev assigns to its keys, followed by keys with no
+	/// keysyms.
+	fn us_mapping() -> Vec<Vec<Keysym>> {
+		let pairs: [(Keysym, Keysym); 50] = [
+			(Escape, Escape),
+			(_1, exclam),
+			(_2, at),
+			(_3, numbersign),
+			(_4, dollar),
+			(_5, percent),
+			(_6, asciicircum),
+			(_7, ampersand),
+			(_8, asterisk),
+			(_9, parenleft),
+			(_0, parenright),
+			(minus, underscore),
+			(equal, plus),
+			(BackSpace, BackSpace),
+			(Tab, Tab),
+			(q, Q),
+			(w, W),
+			(e, E),
+			(r, R),
+			(t, T),
+			(y, Y),
+			(u, U),
+			(i, I),
+			(o, O),
+			(p, P),
+			(bracketleft, braceleft),
+			(bracketright, braceright),
+			(Return, Return),
+			(Control_L, Control_L),
+			(a, A),
+			(s, S),
+			(d, D),
+			(f, F),
+			(g, G),
+			(h, H),
+			(j, J),
+			(k, K),
+			(l, L),
+			(semicolon, colon),
+			(apostrophe, quotedbl),
+			(grave, asciitilde),
+			(Shift_L, Shift_L),
+			(backslash, bar),
+			(z, Z),
+			(x, X),
+			(c, C),
+			(v, V),
+			(b, B),
+			(n, N),
+			(m, M),
+		];
+
+		// Keycodes 8 to 255.
+		let mut mapping: Vec<_> = (0..248).map(|_| vec![Keysym::NO_SYMBOL; 4]).collect();
+
+		// Keycode 9 is the first key: `Escape`.
+		for (keysyms, (unshifted, shifted)) in mapping[1..].iter_mut().zip(pairs) {
+			keysyms[0] = unshifted;
+			keysyms[1] = shifted;
+			keysyms[2] = unshifted;
+			keysyms[3] = shifted;
+		}
+		// Keycodes 67 to 76.
+		for (keysyms, function_key) in mapping[59..]
+			.iter_mut()
+			.zip([F1, F2, F3, F4, F5, F6, F7, F8, F9, F10])
+		{
+			keysyms[0] = function_key;
+		}
+
+		mapping
+	}
+
+	#[test]
+	fn test_keyboard_mapping_round_trip() {
+		let request = GetKeyboardMapping {
+			range: Keycode::new(8)..=Keycode::new(255),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, [101, 0, 0, 2, 8, 248, 0, 0]);
+		assert_eq!(
+			GetKeyboardMapping::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+
+		let reply = reply::GetKeyboardMapping {
+			sequence: 42,
+			mappings: us_mapping(),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), reply.x11_size());
+		assert_eq!(bytes.len(), 32 + 248 * 4 * 4);
+		// The number of keysyms per keycode is the metabyte.
+		assert_eq!(bytes[1], 4);
+
+		let read = reply::GetKeyboardMapping::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(read, reply);
+		assert_eq!(read.mappings.len(), 248);
+		assert_eq!(read.mappings[2][1].to_char(), Some('!'));
+		assert_eq!(read.mappings[59][0], F1);
+	}
+}
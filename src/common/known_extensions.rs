@@ -0,0 +1,196 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`ExtensionName`] and `const`s for the names of commonly supported
+//! extensions.
+//!
+//! The X server only recognizes an extension if its name is spelled exactly
+//! right: uppercase and lowercase, spaces, and hyphens all matter. Using these
+//! `const`s in a [`QueryExtension` request] avoids misspelling them.
+//!
+//! ```
+//! use xrb::{known_extensions::BIG_REQUESTS, x11::request::QueryExtension};
+//!
+//! assert_eq!(&*BIG_REQUESTS, "BIG-REQUESTS");
+//!
+//! let request = QueryExtension::from(BIG_REQUESTS);
+//! assert_eq!(request.name.len(), 12);
+//! ```
+//!
+//! [`QueryExtension` request]: crate::x11::request::QueryExtension
+
+use std::{fmt, ops::Deref};
+
+use crate::{x11::request, Char8, String8};
+
+/// The name of an extension, as given in a [`QueryExtension` request].
+///
+/// See the [module documentation] for the names of commonly supported
+/// extensions.
+///
+/// [`QueryExtension` request]: request::QueryExtension
+/// [module documentation]: self
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ExtensionName(&'static str);
+
+impl ExtensionName {
+	/// Creates a new `ExtensionName` for the extension with exactly the given
+	/// `name`.
+	///
+	/// # Panics
+	/// Panics if the `name` contains a character which is not ASCII: extension
+	/// names are encoded in ISO Latin-1, and every known extension name is
+	/// ASCII.
+	#[must_use]
+	pub const fn new(name: &'static str) -> Self {
+		assert!(name.is_ascii(), "extension names must be ASCII");
+
+		Self(name)
+	}
+
+	/// Returns the name of the extension.
+	#[must_use]
+	pub const fn as_str(&self) -> &'static str {
+		self.0
+	}
+}
+
+impl Deref for ExtensionName {
+	type Target = str;
+
+	fn deref(&self) -> &Self::Target {
+		self.0
+	}
+}
+
+impl fmt::Display for ExtensionName {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.0)
+	}
+}
+
+impl From<ExtensionName> for String8 {
+	fn from(name: ExtensionName) -> Self {
+		// The name is ASCII, so each character is a single byte in both UTF-8
+		// and ISO Latin-1.
+		Self::from(name.0.bytes().map(Char8).collect::<Vec<_>>())
+	}
+}
+
+impl From<ExtensionName> for request::QueryExtension {
+	fn from(name: ExtensionName) -> Self {
+		Self { name: name.into() }
+	}
+}
+
+macro_rules! extension_names {
+	(
+		$(
+			$(#[$attr:meta])*
+			$NAME:ident = $name:literal
+		),*$(,)?
+	) => {
+		$(
+			$(#[$attr])*
+			///
+			#[doc = ::core::concat!("This is spelled `", $name, "`.")]
+			pub const $NAME: ExtensionName = ExtensionName::new($name);
+		)*
+
+		impl ExtensionName {
+			/// Returns the known `ExtensionName` which is spelled exactly like
+			/// the given `name`, if there is one.
+			#[must_use]
+			pub fn from_name(name: &str) -> Option<Self> {
+				match name {
+					$($name => Some($NAME),)*
+					_ => None,
+				}
+			}
+		}
+	};
+}
+
+extension_names! {
+	/// The BIG-REQUESTS extension, which allows requests longer than
+	/// 262,140 bytes.
+	BIG_REQUESTS = "BIG-REQUESTS",
+	/// The Composite extension, which renders windows offscreen.
+	COMPOSITE = "Composite",
+	/// The DAMAGE extension, which reports regions of drawables which have
+	/// changed.
+	DAMAGE = "DAMAGE",
+	/// The Double Buffer extension.
+	DOUBLE_BUFFER = "DOUBLE-BUFFER",
+	/// The Display Power Management Signaling extension.
+	DPMS = "DPMS",
+	/// The DRI3 extension, which shares direct rendering buffers with the X
+	/// server.
+	DRI3 = "DRI3",
+	/// The Generic Event extension, which allows events longer than 32 bytes.
+	GENERIC_EVENT = "Generic Event Extension",
+	/// The GLX extension, which provides OpenGL rendering.
+	GLX = "GLX",
+	/// The MIT Screen Saver extension.
+	MIT_SCREEN_SAVER = "MIT-SCREEN-SAVER",
+	/// The MIT Shared Memory extension, which shares images with the X server
+	/// through shared memory.
+	MIT_SHM = "MIT-SHM",
+	/// The Present extension, which synchronizes presenting pixmaps with the
+	/// display.
+	PRESENT = "Present",
+	/// The Resize and Rotate extension, which configures outputs and screens.
+	RANDR = "RANDR",
+	/// The RECORD extension, which records the protocol sent by other clients.
+	RECORD = "RECORD",
+	/// The RENDER extension, which provides image compositing.
+	RENDER = "RENDER",
+	/// The SECURITY extension, which distinguishes trusted and untrusted
+	/// clients.
+	SECURITY = "SECURITY",
+	/// The SHAPE extension, which allows non-rectangular windows.
+	SHAPE = "SHAPE",
+	/// The SYNC extension, which provides counters and fences.
+	SYNC = "SYNC",
+	/// The XC-MISC extension, which allows clients to reuse resource IDs.
+	XC_MISC = "XC-MISC",
+	/// The XFIXES extension.
+	XFIXES = "XFIXES",
+	/// The XINERAMA extension, which combines multiple screens into one.
+	XINERAMA = "XINERAMA",
+	/// The X Input extension, which supports input devices other than the core
+	/// keyboard and cursor.
+	XINPUT = "XInputExtension",
+	/// The X Keyboard extension.
+	XKEYBOARD = "XKEYBOARD",
+	/// The X-Resource extension, which reports the resources used by clients.
+	X_RESOURCE = "X-Resource",
+	/// The XTEST extension, which simulates input.
+	XTEST = "XTEST",
+	/// The X Video extension.
+	XVIDEO = "XVideo",
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_extension_name_from_name() {
+		assert_eq!(ExtensionName::from_name("BIG-REQUESTS"), Some(BIG_REQUESTS));
+		assert_eq!(ExtensionName::from_name("Generic Event Extension"), Some(GENERIC_EVENT));
+
+		// Extension names are case-sensitive and space-sensitive.
+		assert_eq!(ExtensionName::from_name("big-requests"), None);
+		assert_eq!(ExtensionName::from_name("RANDR "), None);
+	}
+
+	#[test]
+	fn test_extension_name_to_string8() {
+		let name = String8::from(XC_MISC);
+
+		assert_eq!(name, String8::from_latin1("XC-MISC").unwrap());
+		assert_eq!(name.to_latin1_string(), &*XC_MISC);
+	}
+}
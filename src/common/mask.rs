@@ -4,11 +4,80 @@
 
 #![allow(missing_docs)]
 
-use bitflags::bitflags;
-use xrbk_macro::{ConstantX11Size, Readable, Writable, X11Size};
+use thiserror::Error;
+
+/// An error returned by a mask's `validate_known` method if it contains bits
+/// which are not defined for it.
+///
+/// Masks are read with any undefined bits preserved, so that masks extended
+/// by extensions can still be read. `validate_known` may be used to reject
+/// them instead.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+#[error("{mask} contains the undefined bits {bits:#x}")]
+pub struct UndefinedMaskBits {
+	/// The name of the mask type.
+	pub mask: &'static str,
+	/// The bits of the mask which are not defined for it.
+	pub bits: u32,
+}
+
+/// Defines masks with [`bitflags!`], deriving the XRBK traits for them and
+/// implementing `validate_known`.
+///
+/// Masks are written and read as their underlying integer. Any bits which are
+/// not defined for the mask are preserved when it is read.
+///
+/// [`bitflags!`]: bitflags::bitflags
+macro_rules! bitmask {
+	($(
+		$(#[$meta:meta])*
+		$vis:vis struct $Mask:ident: $T:ty {
+			$($flags:tt)*
+		}
+	)+) => {
+		::bitflags::bitflags! {
+			$(
+				$(#[$meta])*
+				#[derive(
+					Default,
+					::xrbk_macro::X11Size,
+					::xrbk_macro::Readable,
+					::xrbk_macro::ConstantX11Size,
+					::xrbk_macro::Writable,
+				)]
+				$vis struct $Mask: $T {
+					$($flags)*
+				}
+			)+
+		}
+
+		$(
+			impl $Mask {
+				#[doc = concat!(
+					"Returns this `", stringify!($Mask), "` if it contains only the bits ",
+					"which are defined for it.",
+				)]
+				///
+				/// # Errors
+				/// Returns [`UndefinedMaskBits`] if any other bits are set.
+				pub fn validate_known(self) -> Result<Self, UndefinedMaskBits> {
+					let undefined = self.bits() & !Self::all().bits();
+
+					if undefined == 0 {
+						Ok(self)
+					} else {
+						Err(UndefinedMaskBits {
+							mask: stringify!($Mask),
+							bits: undefined.into(),
+						})
+					}
+				}
+			}
+		)+
+	};
+}
 
-bitflags! {
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
+bitmask! {
 	pub struct ColorChannelMask: u8 {
 		/// Whether the red color channel is enabled.
 		const RED = 0x01;
@@ -19,7 +88,6 @@ bitflags! {
 	}
 
 	/// A mask of events.
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
 	pub struct EventMask: u32 {
 		/// Key press events.
 		const KEY_PRESS = 0x0000_0001;
@@ -160,8 +228,15 @@ bitflags! {
 	/// - `PROPERTY_CHANGE`
 	/// - `COLORMAP_CHANGE`
 	/// - `OWNER_GRAB_BUTTON`
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
-	pub struct CursorEventMask: u32 {
+	///
+	/// A `CursorEventMask` is written as 16 bits, as `SETofPOINTEREVENT` is in
+	/// the [`GrabCursor`], [`GrabButton`], and [`ChangeActiveCursorGrab`]
+	/// requests. None of its events use the upper 16 bits of an [`EventMask`].
+	///
+	/// [`GrabCursor`]: crate::x11::request::GrabCursor
+	/// [`GrabButton`]: crate::x11::request::GrabButton
+	/// [`ChangeActiveCursorGrab`]: crate::x11::request::ChangeActiveCursorGrab
+	pub struct CursorEventMask: u16 {
 		// removes KEY_PRESS and KEY_RELEASE
 		/// Mouse button press events.
 		const BUTTON_PRESS = 0x0000_0004;
@@ -236,7 +311,6 @@ bitflags! {
 	/// - `PROPERTY_CHANGE`
 	/// - `COLORMAP_CHANGE`
 	/// - `OWNER_GRAB_BUTTON`
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
 	pub struct DeviceEventMask: u32 {
 		/// Key press events.
 		const KEY_PRESS = 0x0000_0001;
@@ -283,7 +357,7 @@ bitflags! {
 	///
 	/// This is the same as [`ModifierKeyMask`], but with masks for currently
 	/// held mouse buttons.
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
+	#[doc(alias = "KeyButtonMask")]
 	pub struct ModifierMask: u16 {
 		/// Whether `Shift` is held.
 		const SHIFT = 0x0001;
@@ -333,7 +407,6 @@ bitflags! {
 	/// mask for [`ANY_MODIFIER`].
 	///
 	/// [`ANY_MODIFIER`]: AnyModifierKeyMask::ANY_MODIFIER
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
 	pub struct ModifierKeyMask: u16 {
 		/// Whether `Shift` is held.
 		const SHIFT = 0x0001;
@@ -365,7 +438,6 @@ bitflags! {
 	/// [`ANY_MODIFIER`].
 	///
 	/// [`ANY_MODIFIER`]: AnyModifierKeyMask::ANY_MODIFIER
-	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
 	pub struct AnyModifierKeyMask: u16 {
 		/// Whether `Shift` is held.
 		const SHIFT = 0x0001;
@@ -375,7 +447,7 @@ bitflags! {
 		const CONTROL = 0x0004;
 
 		/// Whether 'modifier key 1' is held.
-		const MOD_1 = 0x0009;
+		const MOD_1 = 0x0008;
 		/// Whether 'modifier key 2' is held.
 		const MOD_2 = 0x0010;
 		/// Whether 'modifier key 3' is held.
@@ -394,3 +466,235 @@ bitflags! {
 		const ANY_MODIFIER = 0x8000;
 	}
 }
+
+/// Implements [`Serialize`] and [`Deserialize`] for the given masks.
+///
+/// Masks are represented as lists of the names of the flags they contain,
+/// rather than as integers. Every flag of each mask must be listed.
+///
+/// [`Serialize`]: serde::Serialize
+/// [`Deserialize`]: serde::Deserialize
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_for_mask {
+	($($Mask:ident {
+		$($FLAG:ident),+$(,)?
+	})+) => {
+		$(
+			impl ::serde::Serialize for $Mask {
+				fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+				where
+					S: ::serde::Serializer,
+				{
+					use ::serde::ser::{Error, SerializeSeq};
+
+					if !Self::all().contains(*self) {
+						return Err(S::Error::custom(format_args!(
+							"{self:?} contains bits which are not defined for {}",
+							stringify!($Mask),
+						)));
+					}
+
+					let mut seq = serializer.serialize_seq(None)?;
+
+					$(
+						if self.contains(Self::$FLAG) {
+							seq.serialize_element(stringify!($FLAG))?;
+						}
+					)+
+
+					seq.end()
+				}
+			}
+
+			impl<'de> ::serde::Deserialize<'de> for $Mask {
+				fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+				where
+					D: ::serde::Deserializer<'de>,
+				{
+					use ::serde::de::Error;
+
+					const FLAGS: &[&str] = &[$(stringify!($FLAG)),+];
+
+					<Vec<String>>::deserialize(deserializer)?
+						.into_iter()
+						.try_fold(Self::empty(), |mask, flag| match flag.as_str() {
+							$(stringify!($FLAG) => Ok(mask | Self::$FLAG),)+
+
+							other => Err(D::Error::unknown_variant(other, FLAGS)),
+						})
+				}
+			}
+		)+
+	};
+}
+
+#[cfg(feature = "serde")]
+pub(crate) use impl_serde_for_mask;
+
+#[cfg(feature = "serde")]
+impl_serde_for_mask! {
+	ColorChannelMask {
+		RED,
+		GREEN,
+		BLUE,
+	}
+
+	EventMask {
+		KEY_PRESS,
+		KEY_RELEASE,
+		BUTTON_PRESS,
+		BUTTON_RELEASE,
+		ENTER_WINDOW,
+		LEAVE_WINDOW,
+		ANY_MOTION,
+		MOTION_HINT,
+		BUTTON_1_MOTION,
+		BUTTON_2_MOTION,
+		BUTTON_3_MOTION,
+		BUTTON_4_MOTION,
+		BUTTON_5_MOTION,
+		ANY_BUTTON_MOTION,
+		KEYBOARD_STATE,
+		EXPOSURE,
+		VISIBILITY_CHANGE,
+		STRUCTURE_NOTIFY,
+		RESIZE_REDIRECT,
+		SUBSTRUCTURE_NOTIFY,
+		SUBSTRUCTURE_REDIRECT,
+		FOCUS_CHANGE,
+		PROPERTY_CHANGE,
+		COLORMAP_CHANGE,
+		OWNER_GRAB_BUTTON,
+	}
+
+	CursorEventMask {
+		BUTTON_PRESS,
+		BUTTON_RELEASE,
+		ENTER_WINDOW,
+		LEAVE_WINDOW,
+		ANY_MOTION,
+		MOTION_HINT,
+		BUTTON_1_MOTION,
+		BUTTON_2_MOTION,
+		BUTTON_3_MOTION,
+		BUTTON_4_MOTION,
+		BUTTON_5_MOTION,
+		ANY_BUTTON_MOTION,
+		KEY_STATE,
+	}
+
+	DeviceEventMask {
+		KEY_PRESS,
+		KEY_RELEASE,
+		BUTTON_PRESS,
+		BUTTON_RELEASE,
+		ANY_MOTION,
+		BUTTON_1_MOTION,
+		BUTTON_2_MOTION,
+		BUTTON_3_MOTION,
+		BUTTON_4_MOTION,
+		BUTTON_5_MOTION,
+		ANY_BUTTON_MOTION,
+	}
+
+	ModifierMask {
+		SHIFT,
+		LOCK,
+		CONTROL,
+		MOD_1,
+		MOD_2,
+		MOD_3,
+		MOD_4,
+		MOD_5,
+		BUTTON_1,
+		BUTTON_2,
+		BUTTON_3,
+		BUTTON_4,
+		BUTTON_5,
+	}
+
+	ModifierKeyMask {
+		SHIFT,
+		LOCK,
+		CONTROL,
+		MOD_1,
+		MOD_2,
+		MOD_3,
+		MOD_4,
+		MOD_5,
+	}
+
+	AnyModifierKeyMask {
+		SHIFT,
+		LOCK,
+		CONTROL,
+		MOD_1,
+		MOD_2,
+		MOD_3,
+		MOD_4,
+		MOD_5,
+		ANY_MODIFIER,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use xrbk::{ByteOrder, ConstantX11Size, Readable, Writable};
+
+	#[test]
+	fn test_mask_encoding() {
+		let mut bytes = vec![];
+
+		(CursorEventMask::BUTTON_PRESS | CursorEventMask::KEY_STATE)
+			.write_to(&mut bytes)
+			.unwrap();
+		(AnyModifierKeyMask::SHIFT | AnyModifierKeyMask::ANY_MODIFIER)
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		EventMask::OWNER_GRAB_BUTTON.write_to(&mut bytes).unwrap();
+
+		assert_eq!(CursorEventMask::X11_SIZE, 2);
+		assert_eq!(AnyModifierKeyMask::X11_SIZE, 2);
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			0x40, 0x04, // `CursorEventMask`
+			0x01, 0x80, // `AnyModifierKeyMask`, little-endian
+			0x01, 0, 0, 0, // `EventMask`
+		]);
+
+		assert_eq!(
+			format!(
+				"{:?}",
+				AnyModifierKeyMask::SHIFT | AnyModifierKeyMask::ANY_MODIFIER
+			),
+			"SHIFT | ANY_MODIFIER",
+		);
+	}
+
+	#[test]
+	fn test_undefined_bits_are_preserved() {
+		let mask = CursorEventMask::read_from(&mut &[0xff, 0xff][..]).unwrap();
+
+		assert!(mask.contains(CursorEventMask::all()));
+
+		let mut bytes = vec![];
+		mask.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes, [0xff, 0xff]);
+
+		assert_eq!(
+			mask.validate_known(),
+			Err(UndefinedMaskBits {
+				mask: "CursorEventMask",
+				bits: 0x8003,
+			}),
+		);
+		assert_eq!(
+			CursorEventMask::all().validate_known(),
+			Ok(CursorEventMask::all()),
+		);
+
+		let mask = AnyModifierKeyMask::read_from(&mut &[0x81, 0x01][..]).unwrap();
+		assert_eq!(mask.validate_known().unwrap_err().bits, 0x0100,);
+	}
+}
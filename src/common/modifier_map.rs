@@ -0,0 +1,317 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use xrbk::{
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadResult,
+	ReadableWithContext,
+	Writable,
+	WriteResult,
+	X11Size,
+};
+
+use crate::Keycode;
+
+/// One of the eight modifiers to which [keycodes] may be mapped.
+///
+/// [keycodes]: Keycode
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Modifier {
+	/// The shift modifier.
+	Shift,
+	/// The caps lock modifier.
+	CapsLock,
+	/// The control modifier.
+	Ctrl,
+
+	/// The Mod1 modifier.
+	Mod1,
+	/// The Mod2 modifier.
+	Mod2,
+	/// The Mod3 modifier.
+	Mod3,
+	/// The Mod4 modifier.
+	///
+	/// This is typically the key variously called 'super', 'meta', 'windows
+	/// key', 'cmd', etc.
+	Mod4,
+	/// The Mod5 modifier.
+	Mod5,
+}
+
+impl Modifier {
+	/// Every `Modifier`, in the order their [keycodes] are written in a
+	/// [`ModifierMap`].
+	///
+	/// [keycodes]: Keycode
+	pub const ALL: [Self; 8] = [
+		Self::Shift,
+		Self::CapsLock,
+		Self::Ctrl,
+		Self::Mod1,
+		Self::Mod2,
+		Self::Mod3,
+		Self::Mod4,
+		Self::Mod5,
+	];
+}
+
+/// The [keycodes] mapped to each [modifier].
+///
+/// A `ModifierMap` is written as a table with a row of [keycodes] for each
+/// [modifier]. Every row has the same length, given separately as the
+/// [`keycodes_per_modifier`], so shorter rows are padded with zeros: zero is
+/// never a valid [keycode], so it is ignored by the X server.
+///
+/// A [modifier] with no [keycodes] mapped to it is disabled.
+///
+/// [keycode]: Keycode
+/// [keycodes]: Keycode
+/// [modifier]: Modifier
+///
+/// [`keycodes_per_modifier`]: ModifierMap::keycodes_per_modifier
+#[doc(alias("ModifierMapping", "modmap"))]
+#[derive(Clone, Default, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "[Vec<Keycode>; 8]"))]
+pub struct ModifierMap([Vec<Keycode>; 8]);
+
+impl ModifierMap {
+	/// Creates a new [`ModifierMapBuilder`] with which a `ModifierMap` can be
+	/// constructed.
+	#[must_use]
+	pub fn builder() -> ModifierMapBuilder {
+		ModifierMapBuilder::new()
+	}
+
+	/// The [keycodes] mapped to the given `modifier`.
+	///
+	/// [keycodes]: Keycode
+	#[must_use]
+	pub fn keycodes_for(&self, modifier: Modifier) -> &[Keycode] {
+		&self.0[modifier as usize]
+	}
+
+	/// The length of each row of [keycodes] when the `ModifierMap` is written.
+	///
+	/// This is the greatest number of [keycodes] mapped to any one
+	/// [modifier].
+	///
+	/// [keycodes]: Keycode
+	/// [modifier]: Modifier
+	#[must_use]
+	#[allow(clippy::cast_possible_truncation)]
+	pub fn keycodes_per_modifier(&self) -> u8 {
+		// Each row has distinct, non-zero keycodes, so it can't be longer than
+		// `u8::MAX`.
+		self.0.iter().map(Vec::len).max().unwrap_or(0) as u8
+	}
+}
+
+impl From<[Vec<Keycode>; 8]> for ModifierMap {
+	/// Creates a `ModifierMap` from the [keycodes] mapped to each [modifier],
+	/// in the order of [`Modifier::ALL`].
+	///
+	/// As with [`ModifierMapBuilder::keycodes`], zero and repeated [keycodes]
+	/// are ignored.
+	///
+	/// [keycodes]: Keycode
+	/// [modifier]: Modifier
+	fn from(rows: [Vec<Keycode>; 8]) -> Self {
+		let mut builder = ModifierMapBuilder::new();
+
+		for (modifier, keycodes) in Modifier::ALL.into_iter().zip(rows) {
+			builder.keycodes(modifier, keycodes);
+		}
+
+		builder.build()
+	}
+}
+
+impl X11Size for ModifierMap {
+	fn x11_size(&self) -> usize {
+		8 * usize::from(self.keycodes_per_modifier()) * Keycode::X11_SIZE
+	}
+}
+
+impl ReadableWithContext for ModifierMap {
+	/// The number of [keycodes] in each row.
+	///
+	/// [keycodes]: Keycode
+	type Context = usize;
+
+	fn read_with(buf: &mut impl Buf, keycodes_per_modifier: &usize) -> ReadResult<Self> {
+		let mut builder = ModifierMapBuilder::new();
+
+		for modifier in Modifier::ALL {
+			let keycodes = Vec::<Keycode>::read_with(buf, keycodes_per_modifier)?;
+
+			builder.keycodes(modifier, keycodes);
+		}
+
+		Ok(builder.build())
+	}
+}
+
+impl Writable for ModifierMap {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		let keycodes_per_modifier = usize::from(self.keycodes_per_modifier());
+
+		for keycodes in &self.0 {
+			keycodes.write_to(buf)?;
+			// Pad the row with zeros to `keycodes_per_modifier`.
+			buf.put_bytes(0, keycodes_per_modifier - keycodes.len());
+		}
+
+		Ok(())
+	}
+}
+
+/// A builder used to construct a new [`ModifierMap`].
+///
+/// All [modifiers] start with no [keycodes] mapped to them, and can be
+/// configured with [`keycodes()`]. When the builder is configured, [`build()`]
+/// can be used to construct the resulting [`ModifierMap`].
+///
+/// The rows of [keycodes] don't need to be the same length: they are padded
+/// with zeros when the [`ModifierMap`] is written.
+///
+/// [keycodes]: Keycode
+/// [modifiers]: Modifier
+///
+/// [`keycodes()`]: ModifierMapBuilder::keycodes
+/// [`build()`]: ModifierMapBuilder::build
+#[derive(Clone, Default, Debug, Hash, PartialEq, Eq)]
+pub struct ModifierMapBuilder {
+	rows: [Vec<Keycode>; 8],
+}
+
+impl ModifierMapBuilder {
+	/// Creates a new `ModifierMapBuilder`.
+	///
+	/// All [modifiers] start with no [keycodes] mapped to them, and can be
+	/// configured with [`keycodes()`]. When the builder is configured,
+	/// [`build()`] can be used to build the resulting [`ModifierMap`].
+	///
+	/// [keycodes]: Keycode
+	/// [modifiers]: Modifier
+	///
+	/// [`keycodes()`]: ModifierMapBuilder::keycodes
+	/// [`build()`]: ModifierMapBuilder::build
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Constructs the resulting [`ModifierMap`] with the configured
+	/// [keycodes].
+	///
+	/// [keycodes]: Keycode
+	#[must_use]
+	pub fn build(self) -> ModifierMap {
+		ModifierMap(self.rows)
+	}
+
+	/// Configures the [keycodes] mapped to the given `modifier`, replacing any
+	/// previously configured for it.
+	///
+	/// Zero [keycodes], which are used to pad the rows of a written
+	/// [`ModifierMap`], are ignored, as are [keycodes] which are repeated.
+	///
+	/// [keycodes]: Keycode
+	pub fn keycodes(
+		&mut self, modifier: Modifier, keycodes: impl IntoIterator<Item = Keycode>,
+	) -> &mut Self {
+		let row = &mut self.rows[modifier as usize];
+		row.clear();
+
+		for keycode in keycodes {
+			if keycode.unwrap() != 0 && !row.contains(&keycode) {
+				row.push(keycode);
+			}
+		}
+
+		self
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_rows_are_padded() {
+		let mut builder = ModifierMap::builder();
+		builder
+			.keycodes(Modifier::Shift, [Keycode::new(50), Keycode::new(62)])
+			.keycodes(Modifier::Mod4, [Keycode::new(133)]);
+		let map = builder.build();
+
+		assert_eq!(map.keycodes_per_modifier(), 2);
+		assert_eq!(
+			map.keycodes_for(Modifier::Shift),
+			[Keycode::new(50), Keycode::new(62)]
+		);
+		assert_eq!(map.keycodes_for(Modifier::Mod4), [Keycode::new(133)]);
+		assert!(map.keycodes_for(Modifier::Ctrl).is_empty());
+
+		let mut bytes = vec![];
+		map.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				50, 62, // Shift
+				0, 0, // CapsLock
+				0, 0, // Ctrl
+				0, 0, // Mod1
+				0, 0, // Mod2
+				0, 0, // Mod3
+				133, 0, // Mod4
+				0, 0, // Mod5
+			]
+		);
+		assert_eq!(bytes.len(), map.x11_size());
+
+		assert_eq!(ModifierMap::read_with(&mut &bytes[..], &2).unwrap(), map);
+	}
+
+	#[test]
+	fn test_zero_and_repeated_keycodes_are_ignored() {
+		let mut builder = ModifierMap::builder();
+		builder.keycodes(
+			Modifier::Ctrl,
+			[37, 0, 105, 37].into_iter().map(Keycode::new),
+		);
+		let map = builder.build();
+
+		assert_eq!(
+			map.keycodes_for(Modifier::Ctrl),
+			[Keycode::new(37), Keycode::new(105)]
+		);
+		assert_eq!(map.keycodes_per_modifier(), 2);
+
+		// Every distinct non-zero keycode fits in a row.
+		let all = ModifierMap::from(std::array::from_fn(|_| {
+			(0..=u8::MAX).chain(0..=u8::MAX).map(Keycode::new).collect()
+		}));
+
+		assert_eq!(all.keycodes_per_modifier(), u8::MAX);
+	}
+
+	#[test]
+	fn test_empty() {
+		let map = ModifierMap::default();
+
+		assert_eq!(map.keycodes_per_modifier(), 0);
+		assert_eq!(map.x11_size(), 0);
+		assert_eq!(ModifierMap::read_with(&mut &[][..], &0).unwrap(), map);
+	}
+}
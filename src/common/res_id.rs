@@ -2,9 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use derive_more::{From, Into};
+use derive_more::Into;
+use xrbk::ZeroIsNone;
 use xrbk_macro::{new, unwrap, ConstantX11Size, Readable, Wrap, Writable, X11Size};
 
+use crate::message::__private;
+
 /// A resource ID referring to either a [`Window`] or a [`Pixmap`].
 ///
 /// Both [windows] and [pixmaps] can be used in graphics operations as `source`s
@@ -23,7 +26,6 @@ use xrbk_macro::{new, unwrap, ConstantX11Size, Readable, Wrap, Writable, X11Size
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -35,6 +37,7 @@ use xrbk_macro::{new, unwrap, ConstantX11Size, Readable, Wrap, Writable, X11Size
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Drawable(u32);
 
 impl From<Window> for Drawable {
@@ -72,7 +75,6 @@ impl From<Pixmap> for Drawable {
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -84,6 +86,7 @@ impl From<Pixmap> for Drawable {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Window(u32);
 
 impl From<Drawable> for Window {
@@ -110,7 +113,6 @@ impl From<Drawable> for Window {
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -122,6 +124,7 @@ impl From<Drawable> for Window {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pixmap(u32);
 
 impl From<Drawable> for Pixmap {
@@ -148,7 +151,6 @@ impl From<Drawable> for Pixmap {
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -160,6 +162,7 @@ impl From<Drawable> for Pixmap {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CursorAppearance(u32);
 
 /// A resource ID referring to either a [`Font`] or a [`GraphicsContext`].
@@ -170,7 +173,6 @@ pub struct CursorAppearance(u32);
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -182,6 +184,7 @@ pub struct CursorAppearance(u32);
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fontable(u32);
 
 impl From<Font> for Fontable {
@@ -215,7 +218,6 @@ impl From<GraphicsContext> for Fontable {
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -227,6 +229,7 @@ impl From<GraphicsContext> for Fontable {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Font(u32);
 
 impl From<Fontable> for Font {
@@ -259,7 +262,6 @@ impl From<Fontable> for Font {
 	PartialEq,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -271,6 +273,7 @@ impl From<Fontable> for Font {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphicsContext(u32);
 
 impl From<Fontable> for GraphicsContext {
@@ -295,9 +298,10 @@ impl From<Fontable> for GraphicsContext {
 	Clone,
 	Eq,
 	PartialEq,
+	PartialOrd,
+	Ord,
 	Hash,
 	Debug,
-	From,
 	Into,
 	// `new` and `unwrap` const fns
 	new,
@@ -309,4 +313,60 @@ impl From<Fontable> for GraphicsContext {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Colormap(u32);
+
+/// A type which wraps a resource ID.
+///
+/// This allows resource IDs to be allocated with a
+/// [`ResourceIdAllocator`] as the correct type.
+///
+/// Resource ID types can't be created from a plain `u32` with [`From`] or
+/// [`TryFrom`], so that one kind of resource ID can't accidentally be used as
+/// another; they are created explicitly with [`from_id`] or their `new`
+/// function instead.
+///
+/// This trait is sealed: it is only implemented for the resource ID types
+/// defined in XRB.
+///
+/// [`ResourceIdAllocator`]: crate::connection::ResourceIdAllocator
+/// [`from_id`]: ResourceId::from_id
+pub trait ResourceId: Copy + __private::Sealed {
+	/// Creates the resource ID from its raw `u32` value.
+	fn from_id(id: u32) -> Self;
+
+	/// Returns the raw `u32` value of the resource ID.
+	fn id(&self) -> u32;
+}
+
+macro_rules! impl_resource_id {
+	($($Id:ty),+$(,)?) => {
+		$(
+			impl __private::Sealed for $Id {}
+
+			impl ResourceId for $Id {
+				fn from_id(id: u32) -> Self {
+					Self::new(id)
+				}
+
+				fn id(&self) -> u32 {
+					self.unwrap()
+				}
+			}
+
+			// Resource IDs are never zero, so zero is used to encode `None`.
+			impl ZeroIsNone for $Id {}
+		)+
+	};
+}
+
+impl_resource_id! {
+	Drawable,
+	Window,
+	Pixmap,
+	CursorAppearance,
+	Fontable,
+	Font,
+	GraphicsContext,
+	Colormap,
+}
@@ -58,6 +58,71 @@ pub(self) fn read_set_value<T: Readable>(
 	})
 }
 
+/// Implements [`Serialize`] and [`Deserialize`] for the given set.
+///
+/// Sets are represented as maps of the values which are configured in them.
+/// They are deserialized with their builders, so that their masks and sizes
+/// are calculated as they are for any other set. Every value of the set must
+/// be listed, with the name of its builder method and getter.
+///
+/// [`Serialize`]: serde::Serialize
+/// [`Deserialize`]: serde::Deserialize
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_for_set {
+	($Set:ident, $Builder:ident {
+		$($value:ident: $Value:ty),+$(,)?
+	}) => {
+		impl ::serde::Serialize for $Set {
+			fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+			where
+				S: ::serde::Serializer,
+			{
+				use ::serde::ser::SerializeMap;
+
+				let mut map = serializer.serialize_map(None)?;
+
+				$(
+					if let Some($value) = self.$value() {
+						map.serialize_entry(stringify!($value), $value)?;
+					}
+				)+
+
+				map.end()
+			}
+		}
+
+		impl<'de> ::serde::Deserialize<'de> for $Set {
+			fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+			where
+				D: ::serde::Deserializer<'de>,
+			{
+				#[derive(::serde::Deserialize)]
+				#[serde(deny_unknown_fields)]
+				struct Values {
+					$(
+						#[serde(default)]
+						$value: Option<$Value>,
+					)+
+				}
+
+				let values = Values::deserialize(deserializer)?;
+				let mut builder = $Builder::new();
+
+				$(
+					if let Some($value) = values.$value {
+						builder.$value($value);
+					}
+				)+
+
+				Ok(builder.build())
+			}
+		}
+	};
+}
+
+#[cfg(feature = "serde")]
+pub(self) use impl_serde_for_set;
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub(self) struct __Px<Num>(Px<Num>);
 
@@ -84,6 +149,8 @@ macro_rules! impl_px {
 		$(
 			impl Readable for __Px<$type> {
 				fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+					xrbk::ensure_remaining(buf, Self::X11_SIZE)?;
+
 					Ok(Self(Px(match <$type>::try_from(buf.$get()) {
 						Ok($type) => $type,
 
@@ -140,7 +207,7 @@ impl Readable for __u8 {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match u8::try_from(buf.get_u32()) {
+		Ok(Self(match u8::try_from(u32::read_from(buf)?) {
 			Ok(u8) => u8,
 			Err(error) => return Err(ReadError::FailedConversion(Box::new(error))),
 		}))
@@ -182,7 +249,7 @@ impl Readable for __u16 {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match u16::try_from(buf.get_u32()) {
+		Ok(Self(match u16::try_from(u32::read_from(buf)?) {
 			Ok(u16) => u16,
 			Err(error) => return Err(ReadError::FailedConversion(Box::new(error))),
 		}))
@@ -224,7 +291,7 @@ impl Readable for __i16 {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match i16::try_from(buf.get_i32()) {
+		Ok(Self(match i16::try_from(i32::read_from(buf)?) {
 			Ok(i16) => i16,
 			Err(error) => return Err(ReadError::FailedConversion(Box::new(error))),
 		}))
@@ -267,7 +334,7 @@ impl Readable for __bool {
 	where
 		Self: Sized,
 	{
-		Ok(Self(buf.get_u32() != 0))
+		Ok(Self(u32::read_from(buf)? != 0))
 	}
 }
 
@@ -288,6 +355,20 @@ impl Writable for __bool {
 #[cfg(test)]
 mod test {
 	use super::*;
+	use crate::{
+		visual::ColorId,
+		BitGravity,
+		Colormap,
+		CopyableFromParent,
+		CursorAppearance,
+		DeviceEventMask,
+		EventMask,
+		MaintainContents,
+		ParentRelatable,
+		Pixmap,
+		StackMode,
+		WindowGravity,
+	};
 
 	#[test]
 	fn test_read_set_value_x11_size() {
@@ -300,4 +381,159 @@ mod test {
 
 		assert_eq!(x11_size, 7);
 	}
+
+	#[test]
+	fn test_window_config_value_list() {
+		let mut builder = WindowConfig::builder();
+		builder
+			.stack_mode(StackMode::Below)
+			.x(Px(-1))
+			.border_width(Px(2));
+		let config = builder.build();
+
+		let mut bytes = vec![];
+		config.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), config.x11_size());
+		// Only the configured values are written, in the order of their mask
+		// bits, each taking 4 bytes.
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			0, 0x51, 0, 0,
+			0xff, 0xff, 0xff, 0xff,
+			0, 0, 0, 2,
+			0, 0, 0, 1,
+		]);
+
+		let read = WindowConfig::read_from(&mut &bytes[..]).unwrap();
+
+		assert_eq!(read, config);
+		assert_eq!(read.x(), Some(&Px(-1)));
+		assert_eq!(read.width(), None);
+		assert_eq!(read.stack_mode(), Some(&StackMode::Below));
+	}
+
+	#[test]
+	fn test_empty_window_config() {
+		let config = WindowConfig::builder().build();
+
+		let mut bytes = vec![];
+		config.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, [0, 0, 0, 0]);
+		assert_eq!(config.x11_size(), 4);
+		assert_eq!(WindowConfig::read_from(&mut &bytes[..]).unwrap(), config);
+	}
+
+	/// Writes the `attributes`, checks their length against their
+	/// [`X11Size`], and returns the bytes after checking that they read back
+	/// to the same `attributes`.
+	fn attributes_round_trip(attributes: &Attributes) -> Vec<u8> {
+		let mut bytes = vec![];
+		attributes.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), attributes.x11_size());
+		assert_eq!(&Attributes::read_from(&mut &bytes[..]).unwrap(), attributes);
+
+		bytes
+	}
+
+	#[test]
+	fn test_empty_attributes() {
+		let bytes = attributes_round_trip(&Attributes::builder().build());
+
+		assert_eq!(bytes, [0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn test_all_attributes() {
+		let mut builder = Attributes::builder();
+		builder
+			.background_pixmap(ParentRelatable::ParentRelative)
+			.background_color(ColorId::new(0x00ff_ffff))
+			.border_pixmap(CopyableFromParent::Other(Pixmap::new(0x0040_0001)))
+			.border_color(ColorId::ZERO)
+			.bit_gravity(BitGravity::Static)
+			.window_gravity(WindowGravity::SouthEast)
+			.maintain_contents(MaintainContents::Always)
+			.maintained_planes(0x0000_00ff)
+			.maintenance_fallback_color(ColorId::ONE)
+			.override_redirect(true)
+			.maintain_windows_under(false)
+			.event_mask(EventMask::EXPOSURE | EventMask::STRUCTURE_NOTIFY)
+			.do_not_propagate_mask(DeviceEventMask::KEY_PRESS)
+			.colormap(CopyableFromParent::CopyFromParent)
+			.cursor_appearance(Some(CursorAppearance::new(0x0040_0002)));
+		let attributes = builder.build();
+
+		let bytes = attributes_round_trip(&attributes);
+
+		// Every value takes 4 bytes, including those which are smaller
+		// elsewhere in the protocol.
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			0, 0, 0x7f, 0xff,
+			0, 0, 0, 1, // background_pixmap
+			0, 0xff, 0xff, 0xff, // background_color
+			0, 0x40, 0, 1, // border_pixmap
+			0, 0, 0, 0, // border_color
+			0, 0, 0, 10, // bit_gravity
+			0, 0, 0, 9, // window_gravity
+			0, 0, 0, 2, // maintain_contents
+			0, 0, 0, 0xff, // maintained_planes
+			0, 0, 0, 1, // maintenance_fallback_color
+			0, 0, 0, 1, // override_redirect
+			0, 0, 0, 0, // maintain_windows_under
+			0, 0x02, 0x80, 0, // event_mask
+			0, 0, 0, 1, // do_not_propagate_mask
+			0, 0, 0, 0, // colormap
+			0, 0x40, 0, 2, // cursor_appearance
+		]);
+	}
+
+	#[test]
+	fn test_sparse_attributes() {
+		let mut builder = Attributes::builder();
+		// Configured out of order, but written in the order of the mask bits.
+		builder
+			.cursor_appearance(None)
+			.bit_gravity(BitGravity::NorthWest)
+			.background_pixmap(ParentRelatable::Other(None));
+		let attributes = builder.build();
+
+		let bytes = attributes_round_trip(&attributes);
+
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			0, 0, 0x40, 0x11,
+			0, 0, 0, 0, // background_pixmap
+			0, 0, 0, 1, // bit_gravity
+			0, 0, 0, 0, // cursor_appearance
+		]);
+
+		let read = Attributes::read_from(&mut &bytes[..]).unwrap();
+
+		assert_eq!(read.bit_gravity(), Some(&BitGravity::NorthWest));
+		assert_eq!(read.cursor_appearance(), Some(&None));
+		assert_eq!(read.window_gravity(), None);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_set_serde_recalculates_mask() {
+		let mut builder = WindowConfig::builder();
+		builder.x(Px(-10)).height(Px(200));
+
+		let config = builder.build();
+
+		let json = serde_json::to_value(&config).unwrap();
+
+		// Only the configured values are included.
+		assert_eq!(json, serde_json::json!({ "x": -10, "height": 200 }));
+
+		let deserialized: WindowConfig = serde_json::from_value(json).unwrap();
+
+		assert_eq!(deserialized, config);
+		assert_eq!(deserialized.x11_size(), config.x11_size());
+	}
 }
@@ -116,6 +116,7 @@ pub type ColormapAttribute = CopyableFromParent<Colormap>;
 ///
 /// [`InputOutput`]: crate::WindowClass::InputOutput
 /// [`InputOnly`]: crate::WindowClass::InputOnly
+#[doc(alias = "WindowAttributes")]
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Attributes {
 	/// Total [`X11Size`] of these `Attributes`.
@@ -137,7 +138,7 @@ pub struct Attributes {
 	bit_gravity: Option<__BitGravity>,
 	window_gravity: Option<__WindowGravity>,
 
-	maintain_contents: Option<MaintainContents>,
+	maintain_contents: Option<__MaintainContents>,
 	maintained_planes: Option<u32>,
 	maintenance_fallback_color: Option<ColorId>,
 
@@ -260,7 +261,7 @@ impl AttributesBuilder {
 			bit_gravity: self.bit_gravity.map(__BitGravity),
 			window_gravity: self.window_gravity.map(__WindowGravity),
 
-			maintain_contents: self.maintain_contents,
+			maintain_contents: self.maintain_contents.map(__MaintainContents),
 			maintained_planes: self.maintained_planes,
 			maintenance_fallback_color: self.maintenance_fallback_color,
 
@@ -707,7 +708,9 @@ impl Attributes {
 		reason = "const is omitted for API uniformity with the other methods and sets"
 	)]
 	pub fn maintain_contents(&self) -> Option<&MaintainContents> {
-		self.maintain_contents.as_ref()
+		self.maintain_contents
+			.as_ref()
+			.map(|__MaintainContents(maintain_contents)| maintain_contents)
 	}
 	/// Which bit planes of the [window] hold dynamic data which must be
 	/// maintained for [`maintain_contents`] and [`maintain_windows_under`].
@@ -844,6 +847,27 @@ impl Attributes {
 	}
 }
 
+#[cfg(feature = "serde")]
+super::impl_serde_for_set! {
+	Attributes, AttributesBuilder {
+		background_pixmap: BackgroundPixmap,
+		background_color: ColorId,
+		border_pixmap: BorderPixmap,
+		border_color: ColorId,
+		bit_gravity: BitGravity,
+		window_gravity: WindowGravity,
+		maintain_contents: MaintainContents,
+		maintained_planes: u32,
+		maintenance_fallback_color: ColorId,
+		override_redirect: bool,
+		maintain_windows_under: bool,
+		event_mask: EventMask,
+		do_not_propagate_mask: DeviceEventMask,
+		colormap: ColormapAttribute,
+		cursor_appearance: CursorAppearanceAttribute,
+	}
+}
+
 bitflags! {
 	/// A mask of [attributes] given for a [window].
 	///
@@ -953,6 +977,27 @@ bitflags! {
 	}
 }
 
+#[cfg(feature = "serde")]
+crate::impl_serde_for_mask! {
+	AttributesMask {
+		BACKGROUND_PIXMAP,
+		BACKGROUND_COLOR,
+		BORDER_PIXMAP,
+		BORDER_COLOR,
+		BIT_GRAVITY,
+		WINDOW_GRAVITY,
+		MAINTAIN_CONTENTS,
+		MAINTAINED_PLANES,
+		MAINTENANCE_FALLBACK_COLOR,
+		OVERRIDE_REDIRECT,
+		MAINTAIN_WINDOWS_UNDER,
+		EVENT_MASK,
+		DO_NOT_PROPAGATE_MASK,
+		COLORMAP,
+		CURSOR_APPEARANCE,
+	}
+}
+
 impl X11Size for Attributes {
 	fn x11_size(&self) -> usize {
 		self.x11_size
@@ -1159,21 +1204,24 @@ impl X11Size for __BitGravity {
 
 impl Readable for __BitGravity {
 	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => BitGravity::Forget,
-			discrim if discrim == 1 => BitGravity::Static,
-			discrim if discrim == 2 => BitGravity::NorthWest,
-			discrim if discrim == 3 => BitGravity::North,
-			discrim if discrim == 4 => BitGravity::NorthEast,
-			discrim if discrim == 5 => BitGravity::West,
-			discrim if discrim == 6 => BitGravity::Center,
-			discrim if discrim == 7 => BitGravity::East,
-			discrim if discrim == 8 => BitGravity::SouthWest,
-			discrim if discrim == 9 => BitGravity::South,
-			discrim if discrim == 10 => BitGravity::SouthEast,
+			discrim if discrim == 1 => BitGravity::NorthWest,
+			discrim if discrim == 2 => BitGravity::North,
+			discrim if discrim == 3 => BitGravity::NorthEast,
+			discrim if discrim == 4 => BitGravity::West,
+			discrim if discrim == 5 => BitGravity::Center,
+			discrim if discrim == 6 => BitGravity::East,
+			discrim if discrim == 7 => BitGravity::SouthWest,
+			discrim if discrim == 8 => BitGravity::South,
+			discrim if discrim == 9 => BitGravity::SouthEast,
+			discrim if discrim == 10 => BitGravity::Static,
 
 			other_discrim => {
-				return Err(ReadError::UnrecognizedDiscriminant(other_discrim as usize))
+				return Err(ReadError::UnrecognizedDiscriminant {
+					type_name: "BitGravity",
+					discriminant: other_discrim as usize,
+				})
 			},
 		}))
 	}
@@ -1185,16 +1233,16 @@ impl Writable for __BitGravity {
 
 		match bit_gravity {
 			BitGravity::Forget => buf.put_u32(0),
-			BitGravity::Static => buf.put_u32(1),
-			BitGravity::NorthWest => buf.put_u32(2),
-			BitGravity::North => buf.put_u32(3),
-			BitGravity::NorthEast => buf.put_u32(4),
-			BitGravity::West => buf.put_u32(5),
-			BitGravity::Center => buf.put_u32(6),
-			BitGravity::East => buf.put_u32(7),
-			BitGravity::SouthWest => buf.put_u32(8),
-			BitGravity::South => buf.put_u32(9),
-			BitGravity::SouthEast => buf.put_u32(10),
+			BitGravity::NorthWest => buf.put_u32(1),
+			BitGravity::North => buf.put_u32(2),
+			BitGravity::NorthEast => buf.put_u32(3),
+			BitGravity::West => buf.put_u32(4),
+			BitGravity::Center => buf.put_u32(5),
+			BitGravity::East => buf.put_u32(6),
+			BitGravity::SouthWest => buf.put_u32(7),
+			BitGravity::South => buf.put_u32(8),
+			BitGravity::SouthEast => buf.put_u32(9),
+			BitGravity::Static => buf.put_u32(10),
 		}
 
 		Ok(())
@@ -1220,21 +1268,24 @@ impl X11Size for __WindowGravity {
 
 impl Readable for __WindowGravity {
 	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => WindowGravity::Unmap,
-			discrim if discrim == 1 => WindowGravity::Static,
-			discrim if discrim == 2 => WindowGravity::NorthWest,
-			discrim if discrim == 3 => WindowGravity::North,
-			discrim if discrim == 4 => WindowGravity::NorthEast,
-			discrim if discrim == 5 => WindowGravity::West,
-			discrim if discrim == 6 => WindowGravity::Center,
-			discrim if discrim == 7 => WindowGravity::East,
-			discrim if discrim == 8 => WindowGravity::SouthWest,
-			discrim if discrim == 9 => WindowGravity::South,
-			discrim if discrim == 10 => WindowGravity::SouthEast,
+			discrim if discrim == 1 => WindowGravity::NorthWest,
+			discrim if discrim == 2 => WindowGravity::North,
+			discrim if discrim == 3 => WindowGravity::NorthEast,
+			discrim if discrim == 4 => WindowGravity::West,
+			discrim if discrim == 5 => WindowGravity::Center,
+			discrim if discrim == 6 => WindowGravity::East,
+			discrim if discrim == 7 => WindowGravity::SouthWest,
+			discrim if discrim == 8 => WindowGravity::South,
+			discrim if discrim == 9 => WindowGravity::SouthEast,
+			discrim if discrim == 10 => WindowGravity::Static,
 
 			other_discrim => {
-				return Err(ReadError::UnrecognizedDiscriminant(other_discrim as usize))
+				return Err(ReadError::UnrecognizedDiscriminant {
+					type_name: "WindowGravity",
+					discriminant: other_discrim as usize,
+				})
 			},
 		}))
 	}
@@ -1246,16 +1297,62 @@ impl Writable for __WindowGravity {
 
 		match window_gravity {
 			WindowGravity::Unmap => buf.put_u32(0),
-			WindowGravity::Static => buf.put_u32(1),
-			WindowGravity::NorthWest => buf.put_u32(2),
-			WindowGravity::North => buf.put_u32(3),
-			WindowGravity::NorthEast => buf.put_u32(4),
-			WindowGravity::West => buf.put_u32(5),
-			WindowGravity::Center => buf.put_u32(6),
-			WindowGravity::East => buf.put_u32(7),
-			WindowGravity::SouthWest => buf.put_u32(8),
-			WindowGravity::South => buf.put_u32(9),
-			WindowGravity::SouthEast => buf.put_u32(10),
+			WindowGravity::NorthWest => buf.put_u32(1),
+			WindowGravity::North => buf.put_u32(2),
+			WindowGravity::NorthEast => buf.put_u32(3),
+			WindowGravity::West => buf.put_u32(4),
+			WindowGravity::Center => buf.put_u32(5),
+			WindowGravity::East => buf.put_u32(6),
+			WindowGravity::SouthWest => buf.put_u32(7),
+			WindowGravity::South => buf.put_u32(8),
+			WindowGravity::SouthEast => buf.put_u32(9),
+			WindowGravity::Static => buf.put_u32(10),
+		}
+
+		Ok(())
+	}
+}
+
+/// A type wrapping a [`MaintainContents`] to represent it in [`Attributes`] as
+/// four bytes.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct __MaintainContents(MaintainContents);
+
+impl ConstantX11Size for __MaintainContents {
+	const X11_SIZE: usize = 4;
+}
+
+impl X11Size for __MaintainContents {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl Readable for __MaintainContents {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		Ok(Self(match u32::read_from(buf)? {
+			0 => MaintainContents::Never,
+			1 => MaintainContents::WhenMapped,
+			2 => MaintainContents::Always,
+
+			other_discrim => {
+				return Err(ReadError::UnrecognizedDiscriminant {
+					type_name: "MaintainContents",
+					discriminant: other_discrim as usize,
+				})
+			},
+		}))
+	}
+}
+
+impl Writable for __MaintainContents {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		let Self(maintain_contents) = self;
+
+		match maintain_contents {
+			MaintainContents::Never => buf.put_u32(0),
+			MaintainContents::WhenMapped => buf.put_u32(1),
+			MaintainContents::Always => buf.put_u32(2),
 		}
 
 		Ok(())
@@ -28,6 +28,8 @@ use xrbk::{
 /// Given a source and destination pixel, represents a bitwise operation applied
 /// to the source and destination to determine the resultant pixel.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Function {
 	/// The resultant pixel is bitwise zero; that is, it has a `0` for each bit.
 	Clear,
@@ -82,6 +84,7 @@ pub enum Function {
 /// The line can either be [`Thin`] (if it has the special value of zero), or
 /// [`Wide`] for any width greater than or equal to one.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineWidth {
 	/// A thin line, having the special width value of zero.
 	Thin,
@@ -124,6 +127,8 @@ impl LineWidth {
 
 /// Defines which sections of a line are drawn.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineStyle {
 	/// The full path of the line is drawn.
 	Solid,
@@ -146,6 +151,8 @@ pub enum LineStyle {
 
 /// Defines how the endpoints of a path are drawn.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CapStyle {
 	/// Equivalent to [`Butt`], except in the case of [`LineWidth::Thin`], where
 	/// the final endpoint is not drawn.
@@ -178,6 +185,8 @@ pub enum CapStyle {
 ///
 /// [`Thick`]: LineWidth::Thick
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum JoinStyle {
 	/// The outer edges of the two lines extend to meet at an angle, if that
 	/// angle is at least 11 degrees.
@@ -199,6 +208,8 @@ pub enum JoinStyle {
 ///
 /// [requests]: crate::x11::request
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillStyle {
 	/// This is the [foreground color], except for the odd dashes of line
 	/// requests with [`LineStyle::DoubleDash`], where it is the [background
@@ -223,12 +234,14 @@ pub enum FillStyle {
 	OpaqueStippled,
 }
 
-/// Defines what pixels are drawn for paths in [`FillPoly` requests].
+/// Defines what pixels are drawn for paths in [`FillPolygon` requests].
 ///
-/// [`FillPoly` requests]: crate::x11::request::FillPoly
+/// [`FillPolygon` requests]: crate::x11::request::FillPolygon
 // Hell if I know what the X11 protocol is talking about for these variants.
 // Really technical language. I imagine it's simply not worth documenting.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillRule {
 	EvenOdd,
 	Winding,
@@ -238,6 +251,8 @@ pub enum FillRule {
 ///
 /// [window]: crate::Window
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ChildMode {
 	/// Both source and destination [windows] are additionally clipped by all
 	/// viewable [`InputOutput`] children.
@@ -251,10 +266,12 @@ pub enum ChildMode {
 	IncludeDescendents,
 }
 
-/// Controls filling in the [`PolyFillArc` request].
+/// Controls filling in the [`FillArcs` request].
 ///
-/// [`PolyFillArc` request]: crate::x11::request::PolyFillArc
+/// [`FillArcs` request]: crate::x11::request::FillArcs
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArcMode {
 	/// Fills the shape created by tracing the arc and joining its endpoints in
 	/// a straight line.
@@ -891,12 +908,12 @@ impl GraphicsOptionsBuilder {
 		self
 	}
 
-	/// Configures the [mode used to draw arcs] in a [`PolyFillArc` request].
+	/// Configures the [mode used to draw arcs] in a [`FillArcs` request].
 	///
 	/// See [`GraphicsOptions::arc_mode`] for more information.
 	///
 	/// [mode used to draw arcs]: ArcMode
-	/// [`PolyFillArc` request]: crate::x11::request::PolyFillArc
+	/// [`FillArcs` request]: crate::x11::request::FillArcs
 	pub fn arc_mode(&mut self, arc_mode: ArcMode) -> &mut Self {
 		if self.arc_mode.is_none() {
 			self.x11_size += 4;
@@ -989,11 +1006,11 @@ impl GraphicsOptions {
 			.as_ref()
 			.map(|__FillStyle(fill_style)| fill_style)
 	}
-	/// Defines which pixels are drawn for paths in [`FillPoly` requests].
+	/// Defines which pixels are drawn for paths in [`FillPolygon` requests].
 	///
 	/// See [`FillRule`] for more information.
 	///
-	/// [`FillPoly` requests]: crate::x11::request::FillPoly
+	/// [`FillPolygon` requests]: crate::x11::request::FillPolygon
 	#[must_use]
 	pub fn fill_rule(&self) -> Option<&FillRule> {
 		self.fill_rule
@@ -1112,26 +1129,55 @@ impl GraphicsOptions {
 	}
 
 	/// Specifies the mode with which [arcs] are drawn in
-	/// [`PolyFillArc` requests].
+	/// [`FillArcs` requests].
 	///
 	/// See [`ArcMode`] for more information.
 	///
 	/// [arcs]: crate::Arc
-	/// [`PolyFillArc` requests]: crate::x11::request::PolyFillArc
+	/// [`FillArcs` requests]: crate::x11::request::FillArcs
 	#[must_use]
 	pub fn arc_mode(&self) -> Option<&ArcMode> {
 		self.arc_mode.as_ref().map(|__ArcMode(arc_mode)| arc_mode)
 	}
 }
 
+#[cfg(feature = "serde")]
+super::impl_serde_for_set! {
+	GraphicsOptions, GraphicsOptionsBuilder {
+		function: Function,
+		plane_mask: u32,
+		foreground_color: ColorId,
+		background_color: ColorId,
+		line_width: LineWidth,
+		line_style: LineStyle,
+		cap_style: CapStyle,
+		join_style: JoinStyle,
+		fill_style: FillStyle,
+		fill_rule: FillRule,
+		tile: Pixmap,
+		stipple: Pixmap,
+		tile_stipple_x: Px<i16>,
+		tile_stipple_y: Px<i16>,
+		font: Font,
+		child_mode: ChildMode,
+		graphics_exposure: bool,
+		clip_x: Px<i16>,
+		clip_y: Px<i16>,
+		clip_mask: ClipMask,
+		dash_offset: Px<u16>,
+		dashes: u8,
+		arc_mode: ArcMode,
+	}
+}
+
 bitflags! {
 	/// A mask of configured options for a [`GraphicsContext`].
 	///
 	/// This mask is used in the [`GraphicsOptions` set], as well as in the
-	/// [`CopyGraphicsContext` request] to specify which options are copied.
+	/// [`CopyGraphicsOptions` request] to specify which options are copied.
 	///
 	/// [`GraphicsContext`]: crate::GraphicsContext
-	/// [`CopyGraphicsContext` request]: crate::x11::request::CopyGraphicsContext
+	/// [`CopyGraphicsOptions` request]: crate::x11::request::CopyGraphicsOptions
 	/// [`GraphicsOptions` set]: GraphicsOptions
 	#[derive(Default, X11Size, Readable, ConstantX11Size, Writable)]
 	pub struct GraphicsOptionsMask: u32 {
@@ -1328,6 +1374,35 @@ bitflags! {
 	}
 }
 
+#[cfg(feature = "serde")]
+crate::impl_serde_for_mask! {
+	GraphicsOptionsMask {
+		FUNCTION,
+		PLANE_MASK,
+		FOREGROUND_COLOR,
+		BACKGROUND_COLOR,
+		LINE_WIDTH,
+		LINE_STYLE,
+		CAP_STYLE,
+		JOIN_STYLE,
+		FILL_STYLE,
+		FILL_RULE,
+		TILE,
+		STIPPLE,
+		TILE_STIPPLE_X,
+		TILE_STIPPLE_Y,
+		FONT,
+		CHILD_MODE,
+		GRAPHICS_EXPOSURE,
+		CLIP_X,
+		CLIP_Y,
+		CLIP_MASK,
+		DASH_OFFSET,
+		DASHES,
+		ARC_MODE,
+	}
+}
+
 // impl XRBK traits for GraphicsOptions {{{
 
 impl X11Size for GraphicsOptions {
@@ -1623,7 +1698,7 @@ impl Readable for __Function {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => Function::Clear,
 			discrim if discrim == 1 => Function::And,
 			discrim if discrim == 2 => Function::AndReverse,
@@ -1644,7 +1719,12 @@ impl Readable for __Function {
 			discrim if discrim == 14 => Function::Nand,
 			discrim if discrim == 15 => Function::Set,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "Function",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1701,7 +1781,7 @@ impl Readable for __LineWidth {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => LineWidth::Thin,
 			other_width => LineWidth::Thick(other_width as u16),
 		}))
@@ -1739,12 +1819,17 @@ impl Readable for __LineStyle {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => LineStyle::Solid,
 			discrim if discrim == 1 => LineStyle::OnOffDash,
 			discrim if discrim == 2 => LineStyle::DoubleDash,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "LineStyle",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1781,13 +1866,18 @@ impl Readable for __CapStyle {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => CapStyle::NotLast,
 			discrim if discrim == 1 => CapStyle::Butt,
 			discrim if discrim == 2 => CapStyle::Round,
 			discrim if discrim == 3 => CapStyle::Projecting,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "CapStyle",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1825,12 +1915,17 @@ impl Readable for __JoinStyle {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => JoinStyle::Miter,
 			discrim if discrim == 1 => JoinStyle::Round,
 			discrim if discrim == 2 => JoinStyle::Bevel,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "JoinStyle",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1867,13 +1962,18 @@ impl Readable for __FillStyle {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => FillStyle::Solid,
 			discrim if discrim == 1 => FillStyle::Tiled,
 			discrim if discrim == 2 => FillStyle::Stippled,
 			discrim if discrim == 3 => FillStyle::OpaqueStippled,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "FillStyle",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1911,11 +2011,16 @@ impl Readable for __FillRule {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => FillRule::EvenOdd,
 			discrim if discrim == 1 => FillRule::Winding,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "FillRule",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1951,11 +2056,16 @@ impl Readable for __ChildMode {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => ChildMode::ClipByChildren,
 			discrim if discrim == 1 => ChildMode::IncludeDescendents,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "ChildMode",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1991,11 +2101,16 @@ impl Readable for __ArcMode {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => ArcMode::Chord,
 			discrim if discrim == 1 => ArcMode::PieSlice,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "ArcMode",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
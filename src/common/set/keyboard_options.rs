@@ -24,6 +24,9 @@ use thiserror::Error;
 
 /// A value representing a non-negative percentage.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// `Percentage` checks its bounds when it is deserialized.
+#[cfg_attr(feature = "serde", allow(clippy::unsafe_derive_deserialize))]
 pub enum PercentOrDefault {
 	/// Resets the percentage to the default percentage.
 	Default,
@@ -108,6 +111,7 @@ impl Display for PercentOrDefault {
 
 /// A value representing a non-negative pitch measured in hertz.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PitchOrDefault {
 	/// Resets the pitch to the default pitch.
 	Reset,
@@ -181,6 +185,7 @@ impl Display for PitchOrDefault {
 
 /// A value representing a non-negative duration measured in milliseconds.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DurationOrDefault {
 	/// Resets the duration to the default duration.
 	Reset,
@@ -256,6 +261,7 @@ impl Display for DurationOrDefault {
 ///
 /// LEDs are numbered starting at one, to a maximum of 32.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Led(u8);
 
 /// Errors generated if the LED number was not in the range `1..=32`.
@@ -296,8 +302,20 @@ impl Led {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Led {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The LED number is checked with `new`.
+		Self::new(u8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 /// Whether LEDs are turned on or off.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LedMode {
 	/// The LED(s) is/are turned on.
 	Off,
@@ -684,6 +702,20 @@ impl KeyboardOptions {
 	}
 }
 
+#[cfg(feature = "serde")]
+super::impl_serde_for_set! {
+	KeyboardOptions, KeyboardOptionsBuilder {
+		key_click_volume: PercentOrDefault,
+		bell_volume: PercentOrDefault,
+		bell_pitch: PitchOrDefault,
+		bell_duration: DurationOrDefault,
+		led: Led,
+		led_mode: LedMode,
+		auto_repeated_key: Keycode,
+		auto_repeat_mode: ToggleOrDefault,
+	}
+}
+
 bitflags! {
 	/// A mask of configured options for the keyboard.
 	///
@@ -749,6 +781,20 @@ bitflags! {
 	}
 }
 
+#[cfg(feature = "serde")]
+crate::impl_serde_for_mask! {
+	KeyboardOptionsMask {
+		KEY_CLICK_VOLUME,
+		BELL_VOLUME,
+		BELL_PITCH,
+		BELL_DURATION,
+		LED,
+		LED_MODE,
+		AUTO_REPEATED_KEY,
+		AUTO_REPEAT_MODE,
+	}
+}
+
 impl X11Size for KeyboardOptions {
 	fn x11_size(&self) -> usize {
 		self.x11_size
@@ -884,7 +930,7 @@ impl Readable for __PercentOrDefault {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_i32() {
+		Ok(Self(match i32::read_from(buf)? {
 			reset if reset == -1 => PercentOrDefault::Default,
 
 			value => match u8::try_from(value) {
@@ -940,7 +986,7 @@ impl Readable for __PitchOrDefault {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_i32() {
+		Ok(Self(match i32::read_from(buf)? {
 			reset if reset == -1 => PitchOrDefault::Reset,
 
 			other => match u8::try_from(other) {
@@ -993,7 +1039,7 @@ impl Readable for __DurationOrDefault {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_i32() {
+		Ok(Self(match i32::read_from(buf)? {
 			reset if reset == -1 => DurationOrDefault::Reset,
 
 			other => match u8::try_from(other) {
@@ -1042,7 +1088,7 @@ impl Readable for __Led {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match u8::try_from(buf.get_u32()) {
+		Ok(Self(match u8::try_from(u32::read_from(buf)?) {
 			Ok(zero) if zero == 0 => return Err(ReadError::Other(Box::new(LedError::Zero))),
 			Ok(high) if high > 32 => {
 				return Err(ReadError::Other(Box::new(LedError::TooHigh(high))))
@@ -1083,11 +1129,16 @@ impl Readable for __LedMode {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			off if off == 0 => LedMode::Off,
 			on if on == 1 => LedMode::On,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "LedMode",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
@@ -1124,7 +1175,7 @@ impl Readable for __Keycode {
 		Self: Sized,
 	{
 		Ok(Self(Keycode::new(
-			buf.get_u32()
+			u32::read_from(buf)?
 				.try_into()
 				.expect("must fit into u8; represents u8 value"),
 		)))
@@ -1159,13 +1210,18 @@ impl Readable for __ToggleOrDefault {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => ToggleOrDefault::Disabled,
 			discrim if discrim == 1 => ToggleOrDefault::Enabled,
 
 			discrim if discrim == 2 => ToggleOrDefault::Default,
 
-			other_discrim => return Err(UnrecognizedDiscriminant(other_discrim as usize)),
+			other_discrim => {
+				return Err(UnrecognizedDiscriminant {
+					type_name: "ToggleOrDefault",
+					discriminant: other_discrim as usize,
+				})
+			},
 		}))
 	}
 }
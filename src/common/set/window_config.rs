@@ -115,7 +115,8 @@ impl WindowConfigBuilder {
 	#[must_use]
 	pub const fn new() -> Self {
 		Self {
-			x11_size: WindowConfigMask::X11_SIZE,
+			// The mask is followed by 2 unused bytes.
+			x11_size: WindowConfigMask::X11_SIZE + 2,
 
 			mask: WindowConfigMask::empty(),
 
@@ -359,7 +360,7 @@ impl Readable for WindowConfig {
 	{
 		let mask = WindowConfigMask::read_from(buf)?;
 		// 2 unused bytes after the mask.
-		buf.advance(2);
+		xrbk::skip(buf, 2)?;
 
 		let mut x11_size = mask.x11_size() + 2;
 
@@ -457,7 +458,7 @@ impl Readable for __StackMode {
 	where
 		Self: Sized,
 	{
-		Ok(Self(match buf.get_u32() {
+		Ok(Self(match u32::read_from(buf)? {
 			discrim if discrim == 0 => StackMode::Above,
 			discrim if discrim == 1 => StackMode::Below,
 			discrim if discrim == 2 => StackMode::TopIf,
@@ -465,7 +466,10 @@ impl Readable for __StackMode {
 			discrim if discrim == 4 => StackMode::Opposite,
 
 			other_discrim => {
-				return Err(ReadError::UnrecognizedDiscriminant(other_discrim as usize))
+				return Err(ReadError::UnrecognizedDiscriminant {
+					type_name: "StackMode",
+					discriminant: other_discrim as usize,
+				})
 			},
 		}))
 	}
@@ -487,6 +491,19 @@ impl Writable for __StackMode {
 	}
 }
 
+#[cfg(feature = "serde")]
+super::impl_serde_for_set! {
+	WindowConfig, WindowConfigBuilder {
+		x: Px<i16>,
+		y: Px<i16>,
+		width: Px<u16>,
+		height: Px<u16>,
+		border_width: Px<u16>,
+		sibling: Window,
+		stack_mode: StackMode,
+	}
+}
+
 bitflags! {
 	/// A mask of configured options for a [window].
 	///
@@ -552,3 +569,16 @@ bitflags! {
 		const STACK_MODE = 0x0040;
 	}
 }
+
+#[cfg(feature = "serde")]
+crate::impl_serde_for_mask! {
+	WindowConfigMask {
+		X,
+		Y,
+		WIDTH,
+		HEIGHT,
+		BORDER_WIDTH,
+		SIBLING,
+		STACK_MODE,
+	}
+}
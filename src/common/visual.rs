@@ -15,6 +15,7 @@ use crate::{
 	Window,
 };
 use derive_more::{From, Into};
+use xrbk::ZeroIsNone;
 use xrbk_macro::{derive_xrb, new, unwrap, ConstantX11Size, Readable, Wrap, Writable, X11Size};
 
 /// A color in the X Window System.
@@ -58,6 +59,9 @@ use xrbk_macro::{derive_xrb, new, unwrap, ConstantX11Size, Readable, Wrap, Writa
 	Readable,
 	Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorId(u32);
 
 impl ColorId {
@@ -92,6 +96,9 @@ impl ColorId {
 	Readable,
 	Writable,
 )]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RgbColor(
 	/// Red.
 	pub u16,
@@ -170,6 +177,7 @@ impl RgbColor {
 ///
 /// This is returned from [`RgbColor::from_hex`].
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RgbColorTooHigh;
 
 impl RgbColor {
@@ -264,6 +272,33 @@ impl RgbColor {
 	}
 }
 
+impl From<(u8, u8, u8)> for RgbColor {
+	/// Scales 8-bit color channels to the 16-bit channels of an `RgbColor`.
+	///
+	/// Each channel is scaled such that `0xff` is the maximum intensity,
+	/// `0xffff`.
+	///
+	/// # Examples
+	/// ```
+	/// use xrb::visual::RgbColor;
+	///
+	/// assert_eq!(RgbColor::from((u8::MAX, u8::MAX, u8::MAX)), RgbColor::WHITE);
+	///
+	/// let orange: (u8, u8, u8) = (0xff, 0x80, 0x00);
+	/// assert_eq!(RgbColor::from(orange), RgbColor(0xffff, 0x8080, 0x0000));
+	/// ```
+	fn from((red, green, blue): (u8, u8, u8)) -> Self {
+		/// Multiplying a byte by this repeats it in both bytes of a `u16`.
+		const SCALE: u16 = 0x0101;
+
+		Self(
+			u16::from(red) * SCALE,
+			u16::from(green) * SCALE,
+			u16::from(blue) * SCALE,
+		)
+	}
+}
+
 impl From<(u32, u32, u32)> for RgbColor {
 	#[allow(
 		clippy::cast_possible_truncation,
@@ -290,6 +325,8 @@ impl From<RgbColor> for (u32, u32, u32) {
 	Clone,
 	Eq,
 	PartialEq,
+	PartialOrd,
+	Ord,
 	Hash,
 	Debug,
 	From,
@@ -304,8 +341,12 @@ impl From<RgbColor> for (u32, u32, u32) {
 	Writable,
 	Wrap,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisualId(u32);
 
+// No visual is ever assigned an ID of zero, so zero is used to encode `None`.
+impl ZeroIsNone for VisualId {}
+
 derive_xrb! {
 	#[derive(
 		Copy,
@@ -320,6 +361,7 @@ derive_xrb! {
 		Readable,
 		Writable,
 	)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct Format {
 		pub depth: u8,
 		pub bits_per_pixel: u8,
@@ -330,6 +372,7 @@ derive_xrb! {
 
 derive_xrb! {
 	#[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct Screen {
 		pub root: Window,
 		pub default_colormap: Colormap,
@@ -360,6 +403,7 @@ derive_xrb! {
 	}
 
 	#[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct Depth {
 		pub depth: u8,
 		_,
@@ -374,6 +418,8 @@ derive_xrb! {
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VisualClass {
 	StaticGray,
 	GrayScale,
@@ -385,12 +431,19 @@ pub enum VisualClass {
 
 derive_xrb! {
 	#[derive(Clone, Eq, PartialEq, Hash, Debug, new, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct VisualType {
 		pub visual_id: VisualId,
 		pub class: VisualClass,
 		pub bits_per_rgb_value: u8,
 		pub colormap_entries: u16,
-		pub color_mask: RgbColor,
+
+		/// The bits of a pixel value which contain the red channel.
+		pub red_mask: u32,
+		/// The bits of a pixel value which contain the green channel.
+		pub green_mask: u32,
+		/// The bits of a pixel value which contain the blue channel.
+		pub blue_mask: u32,
 		[_; 4],
 	}
 }
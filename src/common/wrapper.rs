@@ -66,8 +66,25 @@ macro_rules! impl_writable {
 	}
 } // }}}
 
+/// Asserts, in debug builds, that the `value` of an `Other` variant is not one
+/// of the `reserved` values which are written for the other variants.
+///
+/// Such a `value` would be read back as one of those other variants.
+#[track_caller]
+fn debug_assert_unreserved(value: impl Into<u32>, reserved: &[u32]) {
+	let value = value.into();
+
+	debug_assert!(
+		!reserved.contains(&value),
+		"`Other({value})` cannot be written, as {value} is reserved for another variant"
+	);
+}
+
 /// Values which may be copied from the 'parent'.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CopyableFromParent<T> {
 	/// A value is initialized by copying the matching value of the parent.
 	///
@@ -83,6 +100,8 @@ pub enum CopyableFromParent<T> {
 	CopyFromParent,
 
 	/// The value is initialized as this value.
+	///
+	/// This value must not be zero, as zero means `CopyFromParent`.
 	Other(T),
 }
 
@@ -91,19 +110,22 @@ impl_constant_x11_size!(CopyableFromParent<WindowClass> { // {{{
 });
 
 impl_readable!(CopyableFromParent<WindowClass>: buf {
-	match buf.get_u32() {
+	match u16::read_from(buf)? {
 		discrim if discrim == 0 => Ok(Self::CopyFromParent),
 
 		discrim if discrim == 1 => Ok(Self::Other(WindowClass::InputOutput)),
 		discrim if discrim == 2 => Ok(Self::Other(WindowClass::InputOnly)),
 
-		other_discrim => Err(ReadError::UnrecognizedDiscriminant(other_discrim as usize)),
+		other_discrim => Err(ReadError::UnrecognizedDiscriminant {
+			type_name: "CopyableFromParent<WindowClass>",
+			discriminant: usize::from(other_discrim),
+		}),
 	}
 });
 
 impl_writable!(CopyableFromParent<WindowClass>: &self, buf {
 	match self {
-		Self::CopyFromParent => buf.put_u32(0),
+		Self::CopyFromParent => buf.put_u16(0),
 		Self::Other(class) => class.write_to(buf)?,
 	}
 
@@ -115,7 +137,7 @@ impl_constant_x11_size!(CopyableFromParent<Pixmap> {
 });
 
 impl_readable!(CopyableFromParent<Pixmap>: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::CopyFromParent,
 		val => Self::Other(Pixmap::new(val)),
 	})
@@ -124,7 +146,10 @@ impl_readable!(CopyableFromParent<Pixmap>: buf {
 impl_writable!(CopyableFromParent<Pixmap>: &self, buf {
 	match self {
 		Self::CopyFromParent => buf.put_u32(0),
-		Self::Other(val) => val.write_to(buf)?,
+		Self::Other(val) => {
+			debug_assert_unreserved(val.unwrap(), &[0]);
+			val.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -135,7 +160,7 @@ impl_constant_x11_size!(CopyableFromParent<VisualId> {
 });
 
 impl_readable!(CopyableFromParent<VisualId>: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::CopyFromParent,
 		val => Self::Other(VisualId::new(val)),
 	})
@@ -144,7 +169,10 @@ impl_readable!(CopyableFromParent<VisualId>: buf {
 impl_writable!(CopyableFromParent<VisualId>: &self, buf {
 	match self {
 		Self::CopyFromParent => buf.put_u32(0),
-		Self::Other(id) => id.write_to(buf)?,
+		Self::Other(id) => {
+			debug_assert_unreserved(id.unwrap(), &[0]);
+			id.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -155,7 +183,7 @@ impl_constant_x11_size!(CopyableFromParent<Colormap> {
 });
 
 impl_readable!(CopyableFromParent<Colormap>: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::CopyFromParent,
 		val => Self::Other(Colormap::new(val)),
 	})
@@ -164,7 +192,10 @@ impl_readable!(CopyableFromParent<Colormap>: buf {
 impl_writable!(CopyableFromParent<Colormap>: &self, buf {
 	match self {
 		Self::CopyFromParent => buf.put_u32(0),
-		Self::Other(val) => val.write_to(buf)?,
+		Self::Other(val) => {
+			debug_assert_unreserved(val.unwrap(), &[0]);
+			val.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -175,7 +206,7 @@ impl_constant_x11_size!(CopyableFromParent<u8> {
 });
 
 impl_readable!(CopyableFromParent<u8>: buf {
-	Ok(match buf.get_u8() {
+	Ok(match u8::read_from(buf)? {
 		discrim if discrim == 0 => Self::CopyFromParent,
 		val => Self::Other(val),
 	})
@@ -183,8 +214,11 @@ impl_readable!(CopyableFromParent<u8>: buf {
 
 impl_writable!(CopyableFromParent<u8>: &self, buf {
 	match self {
-		Self::CopyFromParent => buf.put_u32(0),
-		Self::Other(val) => val.write_to(buf)?,
+		Self::CopyFromParent => buf.put_u8(0),
+		Self::Other(val) => {
+			debug_assert_unreserved(*val, &[0]);
+			val.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -199,12 +233,20 @@ impl_writable!(CopyableFromParent<u8>: &self, buf {
 /// [pixmaps]: Pixmap
 /// [pixmap]: Pixmap
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ParentRelatable<T> {
 	/// The value of the 'parent' is used, as long as the parent has the same
 	/// `depth`.
 	ParentRelative,
 
 	/// This value is used.
+	///
+	/// For <code>ParentRelatable<[Option]<[Pixmap]>></code>, a [pixmap] must
+	/// not be `1`, as `1` means `ParentRelative`.
+	///
+	/// [pixmap]: Pixmap
 	Other(T),
 }
 
@@ -213,7 +255,7 @@ impl_constant_x11_size!(ParentRelatable<Option<Pixmap>> { // {{{
 });
 
 impl_readable!(ParentRelatable<Option<Pixmap>>: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::Other(None),
 
 		discrim if discrim == 1 => Self::ParentRelative,
@@ -227,7 +269,10 @@ impl_writable!(ParentRelatable<Option<Pixmap>>: &self, buf {
 		Self::ParentRelative => buf.put_u32(1),
 
 		Self::Other(None) => buf.put_u32(0),
-		Self::Other(Some(pixmap)) => pixmap.write_to(buf)?,
+		Self::Other(Some(pixmap)) => {
+			debug_assert_unreserved(pixmap.unwrap(), &[0, 1]);
+			pixmap.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -237,11 +282,16 @@ impl_writable!(ParentRelatable<Option<Pixmap>>: &self, buf {
 ///
 /// [`Any`]: Any::Any
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Any<T> {
 	/// Any value.
 	Any,
 
 	/// This specific value.
+	///
+	/// This value must not be zero, as zero means `Any`.
 	Other(T),
 }
 
@@ -250,7 +300,7 @@ impl_constant_x11_size!(Any<Atom> { // {{{
 });
 
 impl_readable!(Any<Atom>: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::Any,
 		val => Self::Other(Atom::new(val)),
 	})
@@ -259,7 +309,10 @@ impl_readable!(Any<Atom>: buf {
 impl_writable!(Any<Atom>: &self, buf {
 	match self {
 		Self::Any => buf.put_u32(0),
-		Self::Other(atom) => atom.write_to(buf)?,
+		Self::Other(atom) => {
+			debug_assert_unreserved(atom.unwrap(), &[0]);
+			atom.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -270,7 +323,7 @@ impl_constant_x11_size!(Any<Button> {
 });
 
 impl_readable!(Any<Button>: buf {
-	Ok(match buf.get_u8() {
+	Ok(match u8::read_from(buf)? {
 		discrim if discrim == 0 => Self::Any,
 		val => Self::Other(Button::new(val)),
 	})
@@ -279,7 +332,10 @@ impl_readable!(Any<Button>: buf {
 impl_writable!(Any<Button>: &self, buf {
 	match self {
 		Self::Any => buf.put_u8(0),
-		Self::Other(button) => button.write_to(buf)?,
+		Self::Other(button) => {
+			debug_assert_unreserved(button.unwrap(), &[0]);
+			button.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -290,7 +346,7 @@ impl_constant_x11_size!(Any<Keycode> {
 });
 
 impl_readable!(Any<Keycode>: buf {
-	Ok(match buf.get_u8() {
+	Ok(match u8::read_from(buf)? {
 		discrim if discrim == 0 => Self::Any,
 		val => Self::Other(Keycode::new(val)),
 	})
@@ -299,7 +355,10 @@ impl_readable!(Any<Keycode>: buf {
 impl_writable!(Any<Keycode>: &self, buf {
 	match self {
 		Self::Any => buf.put_u8(0),
-		Self::Other(keycode) => keycode.write_to(buf)?,
+		Self::Other(keycode) => {
+			debug_assert_unreserved(keycode.unwrap(), &[0]);
+			keycode.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -307,20 +366,59 @@ impl_writable!(Any<Keycode>: &self, buf {
 
 /// A time which may simply fill in for the current server time.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CurrentableTime {
 	/// The X server should treat this time as its current time.
 	CurrentTime,
 
 	/// The X server should treat this time as this `Timestamp`.
+	///
+	/// This `Timestamp` must not be zero, as zero means `CurrentTime`.
 	Other(Timestamp),
 }
 
+impl CurrentableTime {
+	/// Returns the [`Timestamp`] represented by this time, or [`None`] if this
+	/// is [`CurrentTime`].
+	///
+	/// [`CurrentTime`]: CurrentableTime::CurrentTime
+	#[must_use]
+	pub const fn time(&self) -> Option<Timestamp> {
+		match self {
+			Self::CurrentTime => None,
+			Self::Other(timestamp) => Some(*timestamp),
+		}
+	}
+
+	/// Returns the [`Timestamp`] represented by this time, using `server_now`
+	/// as the current server time if this is [`CurrentTime`].
+	///
+	/// This is the time which the X server uses in place of this time.
+	///
+	/// [`CurrentTime`]: CurrentableTime::CurrentTime
+	#[must_use]
+	pub const fn resolve(self, server_now: Timestamp) -> Timestamp {
+		match self {
+			Self::CurrentTime => server_now,
+			Self::Other(timestamp) => timestamp,
+		}
+	}
+}
+
+impl From<Timestamp> for CurrentableTime {
+	fn from(timestamp: Timestamp) -> Self {
+		Self::Other(timestamp)
+	}
+}
+
 impl_constant_x11_size!(CurrentableTime { // {{{
 	Timestamp::X11_SIZE
 });
 
 impl_readable!(CurrentableTime: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::CurrentTime,
 		val => Self::Other(Timestamp::new(val)),
 	})
@@ -329,7 +427,10 @@ impl_readable!(CurrentableTime: buf {
 impl_writable!(CurrentableTime: &self, buf {
 	match self {
 		Self::CurrentTime => buf.put_u32(0),
-		Self::Other(timestamp) => timestamp.write_to(buf)?,
+		Self::Other(timestamp) => {
+			debug_assert_unreserved(timestamp.unwrap(), &[0]);
+			timestamp.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -339,6 +440,8 @@ impl_writable!(CurrentableTime: &self, buf {
 ///
 /// [`SendEvent` request]: crate::x11::request::SendEvent
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DestinationWindow {
 	/// The [window] that the cursor is currently located within.
 	///
@@ -351,6 +454,9 @@ pub enum DestinationWindow {
 
 	/// This [window] in particular.
 	///
+	/// This [window] must not be `0` or `1`, as they mean `Cursor` and `Focus`
+	/// respectively.
+	///
 	/// [window]: Window
 	Other(Window),
 }
@@ -360,7 +466,7 @@ impl_constant_x11_size!(DestinationWindow { // {{{
 });
 
 impl_readable!(DestinationWindow: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::Cursor,
 		discrim if discrim == 1 => Self::Focus,
 
@@ -373,7 +479,10 @@ impl_writable!(DestinationWindow: &self, buf {
 		Self::Cursor => buf.put_u32(0),
 		Self::Focus => buf.put_u32(1),
 
-		Self::Other(window) => window.write_to(buf)?,
+		Self::Other(window) => {
+			debug_assert_unreserved(window.unwrap(), &[0, 1]);
+			window.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -383,6 +492,9 @@ impl_writable!(DestinationWindow: &self, buf {
 ///
 /// [window]: Window
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FocusWindow {
 	/// No [window] is focused.
 	///
@@ -398,6 +510,11 @@ pub enum FocusWindow {
 	CursorRoot,
 
 	/// This specific [window].
+	///
+	/// This [window] must not be `0` or `1`, as they mean `None` and
+	/// `CursorRoot` respectively.
+	///
+	/// [window]: Window
 	Other(Window),
 }
 
@@ -406,7 +523,7 @@ impl_constant_x11_size!(FocusWindow { // {{{
 });
 
 impl_readable!(FocusWindow: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		discrim if discrim == 0 => Self::None,
 		discrim if discrim == 1 => Self::CursorRoot,
 
@@ -419,7 +536,10 @@ impl_writable!(FocusWindow: &self, buf {
 		Self::None => buf.put_u32(0),
 		Self::CursorRoot => buf.put_u32(1),
 
-		Self::Other(window) => window.write_to(buf)?,
+		Self::Other(window) => {
+			debug_assert_unreserved(window.unwrap(), &[0, 1]);
+			window.write_to(buf)?;
+		},
 	}
 
 	Ok(())
@@ -429,6 +549,9 @@ impl_writable!(FocusWindow: &self, buf {
 ///
 /// [`KillClient` request]: crate::x11::request::KillClient
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KillClientTarget {
 	/// Destroy all remaining resources retained from connections that ended
 	/// with [`RetainResourcesMode::RetainTemporarily`].
@@ -445,6 +568,9 @@ pub enum KillClientTarget {
 	/// [`RetainResourcesMode::RetainPermanently`]: crate::x11::request::RetainResourcesMode::RetainPermanently
 	KillClient {
 		/// The resource whose client is to be killed.
+		///
+		/// This must not be zero, as zero means
+		/// `DestroyTemporarilyRetainedResources`.
 		resource: u32,
 	},
 }
@@ -454,7 +580,7 @@ impl_constant_x11_size!(KillClientTarget { // {{{
 });
 
 impl_readable!(KillClientTarget: buf {
-	Ok(match buf.get_u32() {
+	Ok(match u32::read_from(buf)? {
 		0 => Self::DestroyTemporarilyRetainedResources,
 		resource => Self::KillClient { resource },
 	})
@@ -463,8 +589,41 @@ impl_readable!(KillClientTarget: buf {
 impl_writable!(KillClientTarget: &self, buf {
 	match self {
 		Self::DestroyTemporarilyRetainedResources => buf.put_u32(0),
-		Self::KillClient { resource } => buf.put_u32(*resource),
+		Self::KillClient { resource } => {
+			debug_assert_unreserved(*resource, &[0]);
+			buf.put_u32(*resource);
+		},
 	}
 
 	Ok(())
 }); // }}}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::FocusWindow;
+
+	#[test]
+	fn test_copyable_from_parent_u8_writes_one_byte() {
+		let mut bytes = vec![];
+		CopyableFromParent::<u8>::CopyFromParent
+			.write_to(&mut bytes)
+			.unwrap();
+
+		assert_eq!(bytes, [0]);
+	}
+
+	#[test]
+	#[should_panic(expected = "reserved for another variant")]
+	fn test_reserved_other_value_is_not_written() {
+		FocusWindow::Other(Window::new(1))
+			.write_to(&mut vec![])
+			.unwrap();
+	}
+
+	#[test]
+	#[should_panic(expected = "zero means `None`")]
+	fn test_some_zero_is_not_written() {
+		Some(Window::new(0)).write_to(&mut vec![]).unwrap();
+	}
+}
@@ -0,0 +1,288 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Allocation of the resource IDs used by an X client.
+
+use crate::ResourceId;
+
+/// Allocates resource IDs from the [`resource_id_base`] and
+/// [`resource_id_mask`] given by the X server when a connection is set up.
+///
+/// Resource IDs are formed by setting some subset of the bits in the
+/// [`resource_id_mask`], which need not be contiguous, and ORing the result
+/// with the [`resource_id_base`].
+///
+/// IDs which have been [released] are reused once every other ID has been
+/// allocated. If every ID is in use, [`allocate`] returns [`None`]: more IDs
+/// may then be found with the [XC-MISC extension]'s [`GetXidRange` request]
+/// and given to the allocator with [`add_range`].
+///
+/// [`resource_id_base`]: super::ConnectionSuccess::resource_id_base
+/// [`resource_id_mask`]: super::ConnectionSuccess::resource_id_mask
+///
+/// [released]: ResourceIdAllocator::release
+/// [`allocate`]: ResourceIdAllocator::allocate
+/// [`add_range`]: ResourceIdAllocator::add_range
+///
+/// [XC-MISC extension]: crate::extension::xc_misc
+/// [`GetXidRange` request]: crate::extension::xc_misc::request::GetXidRange
+#[doc(alias("XidAllocator", "XIDAllocator"))]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ResourceIdAllocator {
+	base: u32,
+	mask: u32,
+
+	/// The bits of the `mask` which are set in the next resource ID which has
+	/// not yet been allocated, or [`None`] if every resource ID has been
+	/// allocated.
+	next: Option<u32>,
+	/// Resource IDs which have been released and may be reused.
+	released: Vec<u32>,
+	/// Ranges of unused resource IDs which have been added with
+	/// [`add_range`], as the next resource ID in each range and the number of
+	/// resource IDs remaining in it.
+	///
+	/// [`add_range`]: ResourceIdAllocator::add_range
+	ranges: Vec<(u32, u32)>,
+}
+
+impl ResourceIdAllocator {
+	/// Creates a new `ResourceIdAllocator` with the given [`resource_id_base`]
+	/// and [`resource_id_mask`].
+	///
+	/// [`resource_id_base`]: super::ConnectionSuccess::resource_id_base
+	/// [`resource_id_mask`]: super::ConnectionSuccess::resource_id_mask
+	#[must_use]
+	pub const fn new(base: u32, mask: u32) -> Self {
+		Self {
+			base,
+			mask,
+
+			next: Some(0),
+			released: Vec::new(),
+			ranges: Vec::new(),
+		}
+	}
+
+	/// Allocates a new resource ID.
+	///
+	/// Returns [`None`] if every resource ID is in use.
+	pub fn allocate<T: ResourceId>(&mut self) -> Option<T> {
+		while let Some(bits) = self.next {
+			// Add one to the bits of the `mask`, carrying through the bits
+			// which are not in the `mask`. The `mask` is exhausted once this
+			// wraps around to zero.
+			let following = (bits | !self.mask).wrapping_add(1) & self.mask;
+			self.next = (following != 0).then_some(following);
+
+			let id = self.base | bits;
+
+			// A resource ID of zero is reserved to mean 'no resource'.
+			if id != 0 {
+				return Some(T::from_id(id));
+			}
+		}
+
+		if let Some(id) = self.released.pop() {
+			return Some(T::from_id(id));
+		}
+
+		let step = self.step();
+		let (id, remaining) = self.ranges.last_mut()?;
+		let allocated = *id;
+
+		*remaining -= 1;
+		// The rest of the range is dropped if it runs past the largest
+		// possible resource ID.
+		match id.checked_add(step) {
+			Some(following) if *remaining != 0 => *id = following,
+			_ => {
+				self.ranges.pop();
+			},
+		}
+
+		Some(T::from_id(allocated))
+	}
+
+	/// Releases the given resource ID so that it can be allocated again.
+	///
+	/// The resource ID must not be released while it is still in use by the X
+	/// server, nor released more than once.
+	pub fn release<T: ResourceId>(&mut self, id: T) {
+		self.released.push(id.id());
+	}
+
+	/// Adds a range of `count` unused resource IDs, starting at `start_id`,
+	/// which may be allocated once every other resource ID is in use.
+	///
+	/// This is used to refill the allocator with the `start_id` and `count`
+	/// returned in the [XC-MISC extension]'s [`GetXidRange` reply]. As in
+	/// XCB, successive resource IDs in the range are separated by the lowest
+	/// bit set in the [`resource_id_mask`]. A `count` of zero, which means the
+	/// X server has no unused resource IDs left, is ignored.
+	///
+	/// [`resource_id_mask`]: super::ConnectionSuccess::resource_id_mask
+	///
+	/// [XC-MISC extension]: crate::extension::xc_misc
+	/// [`GetXidRange` reply]: crate::extension::xc_misc::reply::GetXidRange
+	pub fn add_range(&mut self, start_id: u32, count: u32) {
+		if count != 0 {
+			self.ranges.push((start_id, count));
+		}
+	}
+
+	/// The difference between successive resource IDs in a range added with
+	/// [`add_range`].
+	///
+	/// [`add_range`]: ResourceIdAllocator::add_range
+	const fn step(&self) -> u32 {
+		self.mask & self.mask.wrapping_neg()
+	}
+
+	/// Returns whether every resource ID is in use.
+	///
+	/// If this is `true`, [`allocate`] will return [`None`] until more
+	/// resource IDs are [released] or [added].
+	///
+	/// [`allocate`]: ResourceIdAllocator::allocate
+	/// [released]: ResourceIdAllocator::release
+	/// [added]: ResourceIdAllocator::add_range
+	#[must_use]
+	pub const fn is_exhausted(&self) -> bool {
+		self.next.is_none() && self.released.is_empty() && self.ranges.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{Colormap, Font, Pixmap, Window};
+
+	#[test]
+	fn test_allocate_in_order() {
+		let mut allocator = ResourceIdAllocator::new(0x0020_0000, 0x0000_0007);
+
+		let ids: Vec<u32> = std::iter::from_fn(|| allocator.allocate::<Window>())
+			.map(Window::unwrap)
+			.collect();
+
+		assert_eq!(ids, (0x0020_0000..=0x0020_0007).collect::<Vec<_>>());
+		assert!(allocator.is_exhausted());
+	}
+
+	#[test]
+	fn test_allocate_empty_mask() {
+		// Only the base itself can be allocated.
+		let mut allocator = ResourceIdAllocator::new(0x0040_0000, 0);
+
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0040_0000)));
+		assert_eq!(allocator.allocate::<Window>(), None);
+
+		// With a base of zero, nothing can be allocated at all.
+		let mut allocator = ResourceIdAllocator::new(0, 0);
+
+		assert_eq!(allocator.allocate::<Window>(), None);
+		assert!(allocator.is_exhausted());
+	}
+
+	#[test]
+	fn test_allocate_high_mask_bits() {
+		// The highest bit of the mask being set must not overflow.
+		let mut allocator = ResourceIdAllocator::new(0x0000_0001, 0x8000_0000);
+
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0000_0001)));
+		assert_eq!(allocator.allocate(), Some(Window::new(0x8000_0001)));
+		assert_eq!(allocator.allocate::<Window>(), None);
+	}
+
+	#[test]
+	fn test_allocate_non_contiguous_mask() {
+		let mut allocator = ResourceIdAllocator::new(0x0040_0000, 0b1010);
+
+		let ids: Vec<u32> = std::iter::from_fn(|| allocator.allocate::<Window>())
+			.map(Window::unwrap)
+			.collect();
+
+		assert_eq!(ids, [0x0040_0000, 0x0040_0002, 0x0040_0008, 0x0040_000a]);
+	}
+
+	#[test]
+	fn test_allocate_exhausted() {
+		let mut allocator = ResourceIdAllocator::new(0, 0b11);
+
+		// An ID of zero is never allocated.
+		assert_eq!(allocator.allocate(), Some(Window::new(1)));
+		assert_eq!(allocator.allocate(), Some(Window::new(2)));
+		assert_eq!(allocator.allocate(), Some(Window::new(3)));
+
+		assert!(allocator.is_exhausted());
+		assert_eq!(allocator.allocate::<Window>(), None);
+
+		allocator.release(Window::new(2));
+		assert!(!allocator.is_exhausted());
+
+		assert_eq!(allocator.allocate(), Some(Window::new(2)));
+		assert_eq!(allocator.allocate::<Window>(), None);
+	}
+
+	#[test]
+	fn test_reuse_released() {
+		let mut allocator = ResourceIdAllocator::new(0x0040_0000, 0b1);
+
+		let first: Window = allocator.allocate().unwrap();
+		let second: Pixmap = allocator.allocate().unwrap();
+		assert!(allocator.is_exhausted());
+
+		// Released IDs can be reused as any kind of resource.
+		allocator.release(first);
+		allocator.release(second);
+
+		assert_eq!(allocator.allocate(), Some(Font::new(0x0040_0001)));
+		assert_eq!(allocator.allocate(), Some(Colormap::new(0x0040_0000)));
+		assert!(allocator.is_exhausted());
+	}
+
+	#[test]
+	fn test_add_range() {
+		let mut allocator = ResourceIdAllocator::new(0x0040_0000, 0x0000_00f0);
+
+		while allocator.allocate::<Window>().is_some() {}
+		assert!(allocator.is_exhausted());
+
+		// A count of zero means no IDs are available.
+		allocator.add_range(0x0040_0000, 0);
+		assert!(allocator.is_exhausted());
+
+		// IDs in the range are separated by the lowest bit of the mask.
+		allocator.add_range(0x0040_0030, 3);
+		assert!(!allocator.is_exhausted());
+
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0040_0030)));
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0040_0040)));
+
+		// Released IDs are reused before the rest of the range.
+		allocator.release(Window::new(0x0040_0080));
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0040_0080)));
+
+		assert_eq!(allocator.allocate(), Some(Window::new(0x0040_0050)));
+		assert_eq!(allocator.allocate::<Window>(), None);
+		assert!(allocator.is_exhausted());
+	}
+
+	#[test]
+	fn test_add_range_overflow() {
+		let mut allocator = ResourceIdAllocator::new(0, 0x8000_0000);
+
+		while allocator.allocate::<Window>().is_some() {}
+		assert!(allocator.is_exhausted());
+
+		// A range which runs past the largest resource ID is cut short rather
+		// than overflowing.
+		allocator.add_range(0x8000_0001, 5);
+
+		assert_eq!(allocator.allocate(), Some(Window::new(0x8000_0001)));
+		assert_eq!(allocator.allocate::<Window>(), None);
+		assert!(allocator.is_exhausted());
+	}
+}
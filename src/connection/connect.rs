@@ -0,0 +1,322 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Establishing a connection with an X server over a byte stream.
+
+use std::io::{self, Read, Write};
+
+use thiserror::Error;
+use xrbk::{ByteOrder, ReadError, Readable, Writable, WriteError};
+
+use super::{
+	ConnError,
+	ConnectionAuthenticationError,
+	ConnectionFailure,
+	ConnectionResponse,
+	ConnectionSuccess,
+	Endianness,
+	InitConnection,
+};
+use crate::String8;
+
+/// The size of the header of a [`ConnectionResponse`], in bytes.
+///
+/// The header of every [`ConnectionResponse`] ends with the length of the rest
+/// of the response in 4-byte units.
+const RESPONSE_HEADER_SIZE: usize = 8;
+
+/// An error which prevented a [`Connection`] from being established.
+#[derive(Error, Debug)]
+pub enum ConnectError {
+	/// An I/O error occurred while sending the [`InitConnection`] message or
+	/// receiving the [`ConnectionResponse`].
+	#[error(transparent)]
+	Io(#[from] io::Error),
+
+	/// The [`InitConnection`] message could not be written.
+	#[error("failed to write the connection setup message: {0}")]
+	Write(#[from] WriteError),
+	/// The [`ConnectionResponse`] could not be read.
+	#[error("failed to read the connection setup response: {0}")]
+	Read(#[from] ReadError),
+
+	/// The X server refused the connection.
+	#[error("the X server refused the connection: {}", .0.reason.to_latin1_string())]
+	Failed(ConnectionFailure),
+	/// The X server refused the connection because authentication was
+	/// unsuccessful.
+	#[error("authentication with the X server failed: {}", .0.reason.to_latin1_string())]
+	AuthenticationError(ConnectionAuthenticationError),
+}
+
+impl From<ConnError> for ConnectError {
+	fn from(error: ConnError) -> Self {
+		match error {
+			ConnError::Failed(failure) => Self::Failed(failure),
+			ConnError::AuthenticationError(auth_error) => Self::AuthenticationError(auth_error),
+		}
+	}
+}
+
+/// A connection with an X server which has been successfully set up.
+///
+/// A `Connection` may be established over any byte stream which implements
+/// [`Read`] and [`Write`], such as a [`UnixStream`] or a [`TcpStream`].
+///
+/// Messages sent over the connection are in the [`ByteOrder`] chosen when it
+/// was set up: see [`order`](Connection::order).
+///
+/// [`UnixStream`]: std::os::unix::net::UnixStream
+/// [`TcpStream`]: std::net::TcpStream
+#[derive(Debug)]
+pub struct Connection<S> {
+	stream: S,
+	order: ByteOrder,
+	setup: ConnectionSuccess,
+}
+
+impl<S: Read + Write> Connection<S> {
+	/// Sets up a connection with the X server over the given `stream`.
+	///
+	/// An [`InitConnection`] message with the given byte `order` and
+	/// authorization protocol name and data is sent, and the X server's
+	/// [`ConnectionResponse`] is read in that byte `order`. The
+	/// `auth_protocol_name` and `auth_protocol_data` may be empty if no
+	/// authorization protocol is used.
+	///
+	/// # Errors
+	/// - [`ConnectError::Failed`] or [`ConnectError::AuthenticationError`] if
+	///   the X server refused the connection.
+	/// - [`ConnectError::Io`] if the `stream` could not be written to or read
+	///   from, including if it ended before the whole [`ConnectionResponse`]
+	///   was received.
+	/// - [`ConnectError::Write`] or [`ConnectError::Read`] if the messages
+	///   could not be (de)serialized.
+	pub fn connect(
+		mut stream: S, order: ByteOrder, auth_protocol_name: String8, auth_protocol_data: String8,
+	) -> Result<Self, ConnectError> {
+		let init = InitConnection {
+			byte_order: Endianness::from(order),
+			auth_protocol_name,
+			auth_protocol_data,
+		};
+
+		let mut bytes = vec![];
+		init.write_to_ordered(&mut bytes, order)?;
+
+		stream.write_all(&bytes)?;
+		stream.flush()?;
+
+		let mut bytes = vec![0; RESPONSE_HEADER_SIZE];
+		stream.read_exact(&mut bytes)?;
+
+		let additional_data_len = u16::read_from_ordered(&mut &bytes[6..], order)?;
+		bytes.resize(
+			RESPONSE_HEADER_SIZE + usize::from(additional_data_len) * 4,
+			0,
+		);
+		stream.read_exact(&mut bytes[RESPONSE_HEADER_SIZE..])?;
+
+		let buf = &mut &bytes[..];
+		let response = ConnectionResponse::read_from_ordered(buf, order)?;
+
+		if !buf.is_empty() {
+			return Err(ReadError::TrailingData {
+				remaining: buf.len(),
+			}
+			.into());
+		}
+
+		Ok(Self {
+			stream,
+			order,
+			setup: response.ok()?,
+		})
+	}
+}
+
+impl<S> Connection<S> {
+	/// The information about the X server sent when the connection was set
+	/// up.
+	#[must_use]
+	pub const fn setup(&self) -> &ConnectionSuccess {
+		&self.setup
+	}
+
+	/// The [`ByteOrder`] of messages sent over the connection.
+	#[must_use]
+	pub const fn order(&self) -> ByteOrder {
+		self.order
+	}
+
+	/// The stream over which the connection was established.
+	#[must_use]
+	pub const fn stream(&self) -> &S {
+		&self.stream
+	}
+
+	/// The stream over which the connection was established.
+	pub const fn stream_mut(&mut self) -> &mut S {
+		&mut self.stream
+	}
+
+	/// Returns the stream over which the connection was established and the
+	/// information about the X server sent when it was set up.
+	pub fn into_parts(self) -> (S, ConnectionSuccess) {
+		(self.stream, self.setup)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{connection::ImageEndianness, Char8, Keycode};
+
+	/// A stream which responds with the given bytes and records what is
+	/// written to it.
+	#[derive(Debug)]
+	struct MockStream<'a> {
+		response: &'a [u8],
+		sent: Vec<u8>,
+	}
+
+	impl<'a> MockStream<'a> {
+		const fn new(response: &'a [u8]) -> Self {
+			Self {
+				response,
+				sent: vec![],
+			}
+		}
+	}
+
+	impl Read for MockStream<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+			self.response.read(buf)
+		}
+	}
+
+	impl Write for MockStream<'_> {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.sent.write(buf)
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	fn string8(string: &str) -> String8 {
+		String8::from(string.bytes().map(Char8::new).collect::<Vec<_>>())
+	}
+
+	fn response_bytes(response: &ConnectionResponse, order: ByteOrder) -> Vec<u8> {
+		let mut bytes = vec![];
+		response.write_to_ordered(&mut bytes, order).unwrap();
+
+		bytes
+	}
+
+	#[test]
+	fn test_connect_success() {
+		let success = ConnectionSuccess {
+			protocol_major_version: 11,
+			protocol_minor_version: 0,
+			release_number: 12_101_004,
+			resource_id_base: 0x0020_0000,
+			resource_id_mask: 0x001f_ffff,
+			motion_buffer_size: 256,
+			maximum_request_length: u16::MAX,
+			image_byte_order: ImageEndianness::LittleEndian,
+			bitmap_format_bit_order: ImageEndianness::LittleEndian,
+			bitmap_format_scanline_unit: 32,
+			bitmap_format_scanline_padding: 32,
+			min_keycode: Keycode::new(8),
+			max_keycode: Keycode::new(255),
+			vendor: string8("The X.Org Foundation"),
+			pixmap_formats: vec![],
+			roots: vec![],
+		};
+		let auth_data = String8::from(vec![Char8::new(0xab); 16]);
+
+		for (order, byte_order) in [
+			(ByteOrder::BigEndian, 0x42),
+			(ByteOrder::LittleEndian, 0x6c),
+		] {
+			let response = response_bytes(&ConnectionResponse::Success(success.clone()), order);
+
+			let connection = Connection::connect(
+				MockStream::new(&response),
+				order,
+				string8("MIT-MAGIC-COOKIE-1"),
+				auth_data.clone(),
+			)
+			.unwrap();
+
+			assert_eq!(connection.order(), order);
+			assert_eq!(connection.setup(), &success);
+
+			let (stream, _) = connection.into_parts();
+			let init = InitConnection {
+				byte_order: Endianness::from(order),
+				auth_protocol_name: string8("MIT-MAGIC-COOKIE-1"),
+				auth_protocol_data: auth_data.clone(),
+			};
+			let mut sent = vec![];
+			init.write_to_ordered(&mut sent, order).unwrap();
+
+			assert_eq!(stream.sent, sent);
+			assert_eq!(stream.sent[0], byte_order);
+			assert!(stream.response.is_empty(), "not every byte was read");
+		}
+	}
+
+	#[test]
+	fn test_connect_failed() {
+		let failure = ConnectionFailure {
+			protocol_major_version: 11,
+			protocol_minor_version: 0,
+			reason: string8("No protocol specified"),
+		};
+		let response = response_bytes(
+			&ConnectionResponse::Failed(failure.clone()),
+			ByteOrder::BigEndian,
+		);
+
+		let error = Connection::connect(
+			MockStream::new(&response),
+			ByteOrder::BigEndian,
+			string8(""),
+			string8(""),
+		)
+		.unwrap_err();
+
+		assert_eq!(
+			error.to_string(),
+			"the X server refused the connection: No protocol specified",
+		);
+		assert!(matches!(error, ConnectError::Failed(received) if received == failure));
+	}
+
+	#[test]
+	fn test_connect_truncated_response() {
+		let failure = ConnectionFailure {
+			protocol_major_version: 11,
+			protocol_minor_version: 0,
+			reason: string8("No protocol specified"),
+		};
+		let response = response_bytes(&ConnectionResponse::Failed(failure), ByteOrder::BigEndian);
+
+		let error = Connection::connect(
+			MockStream::new(&response[..response.len() - 1]),
+			ByteOrder::BigEndian,
+			string8(""),
+			string8(""),
+		)
+		.unwrap_err();
+
+		assert!(
+			matches!(&error, ConnectError::Io(error) if error.kind() == io::ErrorKind::UnexpectedEof)
+		);
+	}
+}
@@ -0,0 +1,554 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The connection setup: the first messages sent by an X client and the X
+//! server when a connection is established.
+//!
+//! The X client begins by sending an [`InitConnection`] message, to which the
+//! X server responds with a [`ConnectionResponse`].
+
+use thiserror::Error;
+use xrbk::{pad, BufMut, ByteOrder, KnownByteOrder, Writable, WriteError, WriteResult, X11Size};
+use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+
+use crate::{
+	visual::{Format, Screen},
+	Keycode,
+	String8,
+};
+
+/// The byte order used by an X client, sent at the start of
+/// [`InitConnection`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+	BigEndian = 0x42,
+	LittleEndian = 0x6c,
+}
+
+impl From<Endianness> for ByteOrder {
+	fn from(endianness: Endianness) -> Self {
+		match endianness {
+			Endianness::BigEndian => Self::BigEndian,
+			Endianness::LittleEndian => Self::LittleEndian,
+		}
+	}
+}
+
+derive_xrb! {
+	/// The first message sent by an X client to initiate a connection with the
+	/// X server.
+	///
+	/// The X server responds with a [`ConnectionResponse`].
+	///
+	/// Every field after the `byte_order` must be written in that byte order:
+	/// see [`write_to_ordered`].
+	///
+	/// [`write_to_ordered`]: Writable::write_to_ordered
+	#[derive(Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct InitConnection {
+		/// The byte order in which every message over the connection is sent,
+		/// including the rest of this message.
+		pub byte_order: Endianness,
+		_,
+
+		// XRB is implemented for one specific version of the X11 protocol, so
+		// it doesn't make sense to allow any other version to be sent here.
+		let protocol_major_version: u16 = crate::PROTOCOL_MAJOR_VERSION,
+		let protocol_minor_version: u16 = crate::PROTOCOL_MINOR_VERSION,
+
+		#[allow(clippy::cast_possible_truncation)]
+		let auth_protocol_name_len: u16 = auth_protocol_name => auth_protocol_name.len() as u16,
+		#[allow(clippy::cast_possible_truncation)]
+		let auth_protocol_data_len: u16 = auth_protocol_data => auth_protocol_data.len() as u16,
+		[_; 2],
+
+		/// The name of the authorization protocol used, such as
+		/// `MIT-MAGIC-COOKIE-1`.
+		///
+		/// This may be empty if no authorization protocol is used.
+		#[context(auth_protocol_name_len => *auth_protocol_name_len as usize)]
+		pub auth_protocol_name: String8,
+		[_; ..],
+
+		/// The data used by the authorization protocol, such as the cookie for
+		/// `MIT-MAGIC-COOKIE-1`.
+		#[context(auth_protocol_data_len => *auth_protocol_data_len as usize)]
+		pub auth_protocol_data: String8,
+		[_; ..],
+	}
+}
+
+/// An error generated when an [`InitConnection`] message is written in a
+/// different byte order to its `byte_order`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+#[error(
+	"an InitConnection message with a {byte_order:?} byte order cannot be written in {order:?}"
+)]
+pub struct MismatchedByteOrder {
+	/// The `byte_order` of the [`InitConnection`] message.
+	pub byte_order: Endianness,
+	/// The byte order of the buffer to which the message was written.
+	pub order: ByteOrder,
+}
+
+impl Writable for InitConnection {
+	#[allow(clippy::cast_possible_truncation)]
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		// The byte order of the buffer can only be checked if it is known.
+		if let Some(order) = buf.known_byte_order() {
+			if order != ByteOrder::from(self.byte_order) {
+				return Err(WriteError::Other(Box::new(MismatchedByteOrder {
+					byte_order: self.byte_order,
+					order,
+				})));
+			}
+		}
+
+		self.byte_order.write_to(buf)?;
+		buf.put_u8(0);
+
+		buf.put_u16(crate::PROTOCOL_MAJOR_VERSION);
+		buf.put_u16(crate::PROTOCOL_MINOR_VERSION);
+
+		buf.put_u16(self.auth_protocol_name.len() as u16);
+		buf.put_u16(self.auth_protocol_data.len() as u16);
+		buf.put_bytes(0, 2);
+
+		self.auth_protocol_name.write_to(buf)?;
+		buf.put_bytes(0, pad(&self.auth_protocol_name));
+
+		self.auth_protocol_data.write_to(buf)?;
+		buf.put_bytes(0, pad(&self.auth_protocol_data));
+
+		Ok(())
+	}
+}
+
+impl From<ByteOrder> for Endianness {
+	fn from(order: ByteOrder) -> Self {
+		match order {
+			ByteOrder::BigEndian => Self::BigEndian,
+			ByteOrder::LittleEndian => Self::LittleEndian,
+		}
+	}
+}
+
+/// The byte order used by the X server for images.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageEndianness {
+	LittleEndian,
+	BigEndian,
+}
+
+derive_xrb! {
+	/// The X server's response to an [`InitConnection`] message.
+	///
+	/// The first byte of the response indicates which variant it is. The
+	/// [`Readable`] implementations of [`ConnectionFailure`],
+	/// [`ConnectionSuccess`], and [`ConnectionAuthenticationError`] expect that
+	/// byte to have already been read.
+	///
+	/// [`Readable`]: xrbk::Readable
+	#[derive(Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub enum ConnectionResponse {
+		/// There was a failure in attempting the connection.
+		Failed(ConnectionFailure),
+		/// The connection was successfully established.
+		Success(ConnectionSuccess),
+		/// The connection was refused because authentication was not successful.
+		Authenticate(ConnectionAuthenticationError),
+	}
+}
+
+/// The reason a connection was not established, returned by
+/// [`ConnectionResponse::ok`].
+pub enum ConnError {
+	/// There was a failure in attempting the connection.
+	Failed(ConnectionFailure),
+	/// The connection was refused because authentication was not successful.
+	AuthenticationError(ConnectionAuthenticationError),
+}
+
+impl ConnectionResponse {
+	/// Returns the [`ConnectionSuccess`] if the connection was successfully
+	/// established.
+	///
+	/// # Errors
+	/// Returns [`ConnError::Failed`] if the connection failed, and
+	/// [`ConnError::AuthenticationError`] if it was refused because
+	/// authentication was not successful.
+	// false negative for this lint here, so we allow it
+	#[allow(clippy::missing_const_for_fn)]
+	pub fn ok(self) -> Result<ConnectionSuccess, ConnError> {
+		match self {
+			Self::Failed(failure) => Err(ConnError::Failed(failure)),
+			Self::Success(success) => Ok(success),
+			Self::Authenticate(auth_error) => Err(ConnError::AuthenticationError(auth_error)),
+		}
+	}
+}
+
+derive_xrb! {
+	/// There was a failure in attempting the connection.
+	#[derive(Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct ConnectionFailure {
+		#[allow(clippy::cast_possible_truncation)]
+		let reason_len: u8 = reason => reason.len() as u8,
+
+		/// The major version of the X11 protocol used by the X server.
+		pub protocol_major_version: u16,
+		/// The minor version of the X11 protocol used by the X server.
+		pub protocol_minor_version: u16,
+
+		// Length in 4-byte units of "additional data".
+		#[allow(clippy::cast_possible_truncation)]
+		let additional_data_len: u16 = reason => ((reason.len() + pad(reason)) / 4) as u16,
+
+		/// The reason for the failure.
+		#[context(reason_len => *reason_len as usize)]
+		pub reason: String8,
+		// The header is 8 bytes, including the byte read by
+		// `ConnectionResponse`, so only the `reason` needs to be padded.
+		[_; reason => pad(reason)],
+	}
+
+	/// The connection was successfully established.
+	#[derive(Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct ConnectionSuccess {
+		_,
+		/// The major version of the X11 protocol used by the X server.
+		pub protocol_major_version: u16,
+		/// The minor version of the X11 protocol used by the X server.
+		pub protocol_minor_version: u16,
+
+		#[allow(clippy::cast_possible_truncation)]
+		let additional_data_len: u16 = pixmap_formats, vendor, roots => {
+			let vendor_len = vendor.len() + pad(vendor);
+			let len = 32 + pixmap_formats.x11_size() + vendor_len + roots.x11_size();
+
+			(len / 4) as u16
+		},
+
+		/// The vendor's release number of the X server.
+		pub release_number: u32,
+
+		/// The base from which the X client allocates resource IDs.
+		///
+		/// Resource IDs allocated by the X client are formed by setting some
+		/// subset of the bits in the [`resource_id_mask`] and ORing the result
+		/// with this base.
+		///
+		/// [`resource_id_mask`]: ConnectionSuccess::resource_id_mask
+		pub resource_id_base: u32,
+		/// The bits which the X client may set when allocating resource IDs.
+		///
+		/// See [`resource_id_base`] for more information.
+		///
+		/// [`resource_id_base`]: ConnectionSuccess::resource_id_base
+		pub resource_id_mask: u32,
+
+		/// The approximate maximum number of events which the X server keeps
+		/// in its motion history buffer.
+		pub motion_buffer_size: u32,
+
+		#[allow(clippy::cast_possible_truncation)]
+		let vendor_len: u16 = vendor => vendor.len() as u16,
+
+		/// The maximum length of a request accepted by the X server, in 4-byte
+		/// units.
+		pub maximum_request_length: u16,
+
+		#[allow(clippy::cast_possible_truncation)]
+		let roots_len: u8 = roots => roots.len() as u8,
+		#[allow(clippy::cast_possible_truncation)]
+		let pixmap_formats_len: u8 = pixmap_formats => pixmap_formats.len() as u8,
+
+		/// The byte order used by the X server for images.
+		pub image_byte_order: ImageEndianness,
+		/// The bit order used by the X server for bitmaps.
+		pub bitmap_format_bit_order: ImageEndianness,
+		/// The number of bits in each scanline unit of a bitmap.
+		pub bitmap_format_scanline_unit: u8,
+		/// The number of bits to which each scanline of a bitmap is padded.
+		pub bitmap_format_scanline_padding: u8,
+
+		/// The smallest [keycode] used by the X server.
+		///
+		/// This is never less than `8`.
+		///
+		/// [keycode]: Keycode
+		pub min_keycode: Keycode,
+		/// The largest [keycode] used by the X server.
+		///
+		/// [keycode]: Keycode
+		pub max_keycode: Keycode,
+		[_; 4],
+
+		/// The name of the X server's vendor.
+		#[context(vendor_len => *vendor_len as usize)]
+		pub vendor: String8,
+		// The header is 40 bytes, including the byte read by
+		// `ConnectionResponse`, so only the `vendor` needs to be padded.
+		[_; vendor => pad(vendor)],
+
+		/// The [formats] of images supported by the X server, one for each
+		/// depth.
+		///
+		/// [formats]: Format
+		#[context(pixmap_formats_len => *pixmap_formats_len as usize)]
+		pub pixmap_formats: Vec<Format>,
+		/// The [screens] of the X server.
+		///
+		/// [screens]: Screen
+		#[context(roots_len => *roots_len as usize)]
+		pub roots: Vec<Screen>,
+	}
+
+	/// The connection was refused because authentication was unsuccessful.
+	#[derive(Clone, Eq, PartialEq, Hash, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct ConnectionAuthenticationError {
+		[_; 5],
+
+		// Length in 4-byte units of "additional data".
+		#[allow(clippy::cast_possible_truncation)]
+		let additional_data_len: u16 = reason => ((reason.len() + pad(reason)) / 4) as u16,
+
+		/// The reason that authentication was unsuccessful.
+		///
+		/// The length of the `reason` is not sent separately from its padding,
+		/// so a `reason` which is read includes any padding bytes at the end.
+		#[context(additional_data_len => (*additional_data_len as usize) * 4)]
+		pub reason: String8,
+		[_; reason => pad(reason)],
+	}
+}
+
+#[cfg(feature = "try")]
+mod r#try {
+	use super::*;
+	use std::ops::{ControlFlow, FromResidual, Try};
+
+	impl FromResidual for ConnectionResponse {
+		fn from_residual(residual: <Self as Try>::Residual) -> Self {
+			residual
+		}
+	}
+
+	impl Try for ConnectionResponse {
+		type Output = ConnectionSuccess;
+		type Residual = Self;
+
+		fn from_output(output: Self::Output) -> Self {
+			Self::Success(output)
+		}
+
+		fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+			match self {
+				Self::Success(success) => ControlFlow::Continue(success),
+
+				Self::Failed(_) | Self::Authenticate(_) => ControlFlow::Break(self),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{visual::VisualClass, Char8};
+	use xrbk::{Readable, Writable};
+
+	/// The response to a successful connection, as sent by Xvfb with a single
+	/// 1280x1024 screen.
+	#[rustfmt::skip]
+	const SUCCESS: [u8; 284] = [
+		// header
+		0x01, 0x00, 0x00, 0x0b, 0x00, 0x00, 0x00, 0x45,
+		0x00, 0xb8, 0xa5, 0x8c, 0x00, 0x20, 0x00, 0x00,
+		0x00, 0x1f, 0xff, 0xff, 0x00, 0x00, 0x01, 0x00,
+		0x00, 0x14, 0xff, 0xff, 0x01, 0x07, 0x00, 0x00,
+		0x20, 0x20, 0x08, 0xff, 0x00, 0x00, 0x00, 0x00,
+		// vendor: "The X.Org Foundation"
+		0x54, 0x68, 0x65, 0x20, 0x58, 0x2e, 0x4f, 0x72,
+		0x67, 0x20, 0x46, 0x6f, 0x75, 0x6e, 0x64, 0x61,
+		0x74, 0x69, 0x6f, 0x6e,
+		// pixmap formats
+		0x01, 0x01, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x04, 0x08, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x08, 0x08, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x0f, 0x10, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x10, 0x10, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x18, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		0x20, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// screen
+		0x00, 0x00, 0x01, 0xd8, 0x00, 0x00, 0x00, 0x20,
+		0x00, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00,
+		0x00, 0xfa, 0x80, 0x33, 0x05, 0x00, 0x04, 0x00,
+		0x01, 0x52, 0x01, 0x0e, 0x00, 0x01, 0x00, 0x01,
+		0x00, 0x00, 0x00, 0x21, 0x00, 0x00, 0x18, 0x07,
+		// depth 24
+		0x18, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+		0x00, 0x00, 0x00, 0x21, 0x04, 0x08, 0x01, 0x00,
+		0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00,
+		0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00,
+		0x00, 0x00, 0x00, 0x22, 0x05, 0x08, 0x01, 0x00,
+		0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00,
+		0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00,
+		// depth 1
+		0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// depth 4
+		0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// depth 8
+		0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// depth 15
+		0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// depth 16
+		0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+		// depth 32
+		0x20, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+		0x00, 0x00, 0x00, 0x23, 0x04, 0x08, 0x01, 0x00,
+		0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00,
+		0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00,
+	];
+
+	fn string8(string: &str) -> String8 {
+		String8::from(string.bytes().map(Char8::new).collect::<Vec<_>>())
+	}
+
+	#[test]
+	fn test_read_connection_success() {
+		let buf = &mut &SUCCESS[..];
+		let response = ConnectionResponse::read_from(buf).unwrap();
+
+		assert!(buf.is_empty(), "not every byte was read");
+		assert_eq!(response.x11_size(), SUCCESS.len());
+
+		let ConnectionResponse::Success(success) = response else {
+			panic!("expected ConnectionResponse::Success, found {response:?}");
+		};
+
+		assert_eq!(success.protocol_major_version, 11);
+		assert_eq!(success.vendor, string8("The X.Org Foundation"));
+		assert_eq!(success.resource_id_mask, 0x001f_ffff);
+		assert_eq!(success.min_keycode, Keycode::new(8));
+		assert_eq!(success.pixmap_formats.len(), 7);
+		assert_eq!(success.pixmap_formats[5], Format::new(24, 32, 32));
+
+		let [screen] = &success.roots[..] else {
+			panic!("expected one screen, found {}", success.roots.len());
+		};
+		let depths: Vec<_> = screen
+			.allowed_depths
+			.iter()
+			.map(|depth| depth.depth)
+			.collect();
+
+		assert_eq!(depths, [24, 1, 4, 8, 15, 16, 32]);
+		assert_eq!(screen.allowed_depths[0].visuals.len(), 2);
+
+		let visual = &screen.allowed_depths[6].visuals[0];
+
+		assert_eq!(visual.class, VisualClass::TrueColor);
+		assert_eq!(visual.red_mask, 0x00ff_0000);
+		assert_eq!(visual.green_mask, 0x0000_ff00);
+		assert_eq!(visual.blue_mask, 0x0000_00ff);
+	}
+
+	#[test]
+	fn test_connection_success_write_is_lossless() {
+		let response = ConnectionResponse::read_from(&mut &SUCCESS[..]).unwrap();
+
+		let mut bytes = vec![];
+		response.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, SUCCESS);
+	}
+
+	#[test]
+	fn test_init_connection_round_trip() {
+		let init = InitConnection {
+			byte_order: Endianness::BigEndian,
+			auth_protocol_name: string8("MIT-MAGIC-COOKIE-1"),
+			auth_protocol_data: String8::from(vec![Char8::new(0xab); 16]),
+		};
+
+		let mut bytes = vec![];
+		init.write_to(&mut bytes).unwrap();
+
+		// 12-byte header + 18-byte name + 2 bytes of padding + 16-byte data.
+		assert_eq!(bytes.len(), 48);
+		assert_eq!(bytes.len(), init.x11_size());
+		assert_eq!(bytes[..8], [0x42, 0, 0, 11, 0, 0, 0, 18]);
+
+		assert_eq!(InitConnection::read_from(&mut &bytes[..]).unwrap(), init);
+	}
+
+	#[test]
+	fn test_init_connection_little_endian_round_trip() {
+		let init = InitConnection {
+			byte_order: Endianness::LittleEndian,
+			auth_protocol_name: string8("MIT-MAGIC-COOKIE-1"),
+			auth_protocol_data: String8::from(vec![Char8::new(0xab); 16]),
+		};
+
+		let mut bytes = vec![];
+		init.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+
+		assert_eq!(bytes.len(), init.x11_size());
+		assert_eq!(bytes[..12], [0x6c, 0, 11, 0, 0, 0, 18, 0, 16, 0, 0, 0]);
+
+		assert_eq!(
+			InitConnection::read_from_ordered(&mut &bytes[..], ByteOrder::LittleEndian).unwrap(),
+			init
+		);
+	}
+
+	#[test]
+	fn test_init_connection_mismatched_byte_order() {
+		let init = InitConnection {
+			byte_order: Endianness::LittleEndian,
+			auth_protocol_name: string8(""),
+			auth_protocol_data: string8(""),
+		};
+
+		let error = init.write_to(&mut vec![]).unwrap_err();
+
+		assert_eq!(
+			error.to_string(),
+			"an InitConnection message with a LittleEndian byte order cannot be written in \
+			 BigEndian",
+		);
+	}
+
+	#[test]
+	fn test_connection_failure_reason_is_padded() {
+		let response = ConnectionResponse::Failed(ConnectionFailure {
+			protocol_major_version: 11,
+			protocol_minor_version: 0,
+			reason: string8("Protocol version mismatch"),
+		});
+
+		let mut bytes = vec![];
+		response.write_to(&mut bytes).unwrap();
+
+		// 8-byte header + 25-byte reason + 3 bytes of padding.
+		assert_eq!(bytes.len(), 36);
+		assert_eq!(bytes.len(), response.x11_size());
+		assert_eq!(bytes[..8], [0, 25, 0, 11, 0, 0, 0, 7]);
+
+		assert_eq!(
+			ConnectionResponse::read_from(&mut &bytes[..]).unwrap(),
+			response
+		);
+	}
+}
@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Messages defined in extensions to the [core X11 protocol].
+//!
+//! The X server assigns each extension a major opcode, which is returned in
+//! the [`QueryExtension` reply]. Requests defined by extensions implement
+//! [`ExtensionRequest`], and are sent by wrapping them in [`WithMajorOpcode`]
+//! with the major opcode of their extension.
+//!
+//! [core X11 protocol]: crate::x11
+//!
+//! [`QueryExtension` reply]: crate::x11::reply::QueryExtension
+//! [`ExtensionRequest`]: crate::message::ExtensionRequest
+//! [`WithMajorOpcode`]: crate::message::WithMajorOpcode
+
+pub mod xc_misc;
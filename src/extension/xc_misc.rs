@@ -0,0 +1,30 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The [XC-MISC extension], which allows X clients to find [resource IDs]
+//! which are no longer in use.
+//!
+//! An X client is given a limited range of [resource IDs] when it connects.
+//! Long-running X clients may run out of unused IDs, even though many of the
+//! IDs they have used have since been freed; the XC-MISC extension allows
+//! those freed IDs to be found and reused.
+//!
+//! [XC-MISC extension]: https://www.x.org/releases/X11R7.7/doc/xcmiscproto/xc-misc.html
+//! [resource IDs]: crate::ResourceId
+
+pub mod reply;
+pub mod request;
+
+use crate::known_extensions::{self, ExtensionName};
+
+/// The name of the XC-MISC extension, as given in a [`QueryExtension`
+/// request].
+///
+/// [`QueryExtension` request]: crate::x11::request::QueryExtension
+pub const NAME: ExtensionName = known_extensions::XC_MISC;
+
+/// The major version of the XC-MISC extension implemented by XRB.
+pub const MAJOR_VERSION: u16 = 1;
+/// The minor version of the XC-MISC extension implemented by XRB.
+pub const MINOR_VERSION: u16 = 1;
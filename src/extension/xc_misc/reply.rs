@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [Replies] defined in the [XC-MISC extension].
+//!
+//! [Replies] are messages sent from the X server to an X client in response to
+//! a [request].
+//!
+//! [Replies]: Reply
+//! [request]: crate::message::ExtensionRequest
+//! [XC-MISC extension]: super
+
+extern crate self as xrb;
+
+use derivative::Derivative;
+use xrbk_macro::derive_xrb;
+
+use crate::{
+	extension::xc_misc::request,
+	message::{Reply, WithMajorOpcode},
+};
+
+derive_xrb! {
+	/// The [reply] to a [`GetVersion` request].
+	///
+	/// [reply]: Reply
+	///
+	/// [`GetVersion` request]: request::GetVersion
+	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct GetVersion: Reply for WithMajorOpcode<request::GetVersion> {
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[sequence]
+		pub sequence: u16,
+
+		/// The major version of the [XC-MISC extension] supported by the X
+		/// server.
+		///
+		/// [XC-MISC extension]: super
+		pub server_major_version: u16,
+		/// The minor version of the [XC-MISC extension] supported by the X
+		/// server.
+		///
+		/// [XC-MISC extension]: super
+		pub server_minor_version: u16,
+		[_; ..],
+	}
+
+	/// The [reply] to a [`GetXidRange` request].
+	///
+	/// [reply]: Reply
+	///
+	/// [`GetXidRange` request]: request::GetXidRange
+	#[doc(alias = "GetXIDRange")]
+	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct GetXidRange: Reply for WithMajorOpcode<request::GetXidRange> {
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[sequence]
+		pub sequence: u16,
+
+		/// The first unused [resource ID] in the range.
+		///
+		/// [resource ID]: crate::ResourceId
+		pub start_id: u32,
+		/// The number of unused [resource IDs] in the range.
+		///
+		/// If this is `0`, there are no unused [resource IDs] left.
+		///
+		/// [resource IDs]: crate::ResourceId
+		pub count: u32,
+		[_; ..],
+	}
+
+	/// The [reply] to a [`GetXidList` request].
+	///
+	/// [reply]: Reply
+	///
+	/// [`GetXidList` request]: request::GetXidList
+	#[doc(alias = "GetXIDList")]
+	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct GetXidList: Reply for WithMajorOpcode<request::GetXidList> {
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[sequence]
+		pub sequence: u16,
+
+		// The length of `ids`.
+		#[allow(clippy::cast_possible_truncation)]
+		let ids_len: u32 = ids => ids.len() as u32,
+		[_; 20],
+
+		/// The unused [resource IDs].
+		///
+		/// There are no more of these than the `count` given in the
+		/// [`GetXidList` request].
+		///
+		/// [resource IDs]: crate::ResourceId
+		///
+		/// [`GetXidList` request]: request::GetXidList
+		#[context(ids_len => *ids_len as usize)]
+		pub ids: Vec<u32>,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use xrbk::{Readable, Writable};
+
+	round_trip_tests! {
+		GetVersion => test_get_version_round_trip,
+		GetXidRange => test_get_xid_range_round_trip,
+		GetXidList => test_get_xid_list_round_trip,
+	}
+
+	#[test]
+	fn test_get_xid_range_bytes() {
+		let reply = GetXidRange {
+			sequence: 7,
+			start_id: 0x0040_0000,
+			count: 0x10,
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		let expected = [
+			1, 0, 0, 7,
+			0, 0, 0, 0,
+			0, 0x40, 0, 0,
+			0, 0, 0, 0x10,
+		];
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes[..16], expected);
+		assert!(bytes[16..].iter().all(|byte| *byte == 0));
+	}
+
+	#[test]
+	fn test_get_xid_list_length() {
+		let reply = GetXidList {
+			sequence: 2,
+			ids: vec![0x0040_0001, 0x0040_0005, 0x0040_0009],
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		// The 32-byte header is followed by the 3 IDs.
+		assert_eq!(bytes.len(), 44);
+		assert_eq!(bytes[4..8], [0, 0, 0, 3]);
+		assert_eq!(bytes[8..12], [0, 0, 0, 3]);
+		assert_eq!(bytes[32..36], [0, 0x40, 0, 1]);
+
+		assert_eq!(GetXidList::read_from(&mut &bytes[1..]).unwrap(), reply);
+	}
+}
@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [Requests] defined in the [XC-MISC extension].
+//!
+//! [Requests] are messages sent from an X client to the X server. These
+//! requests are sent by wrapping them in [`WithMajorOpcode`] with the major
+//! opcode returned for the [XC-MISC extension] in the [`QueryExtension`
+//! reply].
+//!
+//! [Requests]: ExtensionRequest
+//! [XC-MISC extension]: super
+//!
+//! [`WithMajorOpcode`]: crate::message::WithMajorOpcode
+//! [`QueryExtension` reply]: crate::x11::reply::QueryExtension
+
+extern crate self as xrb;
+
+use xrbk_macro::derive_xrb;
+
+use crate::{extension::xc_misc::reply, message::ExtensionRequest};
+
+derive_xrb! {
+	/// A [request] that negotiates the version of the [XC-MISC extension] used.
+	///
+	/// This [request] should be sent before any other [request] of the
+	/// [XC-MISC extension].
+	///
+	/// # Replies
+	/// This [request] generates a [`GetVersion` reply].
+	///
+	/// [request]: ExtensionRequest
+	/// [XC-MISC extension]: super
+	///
+	/// [`GetVersion` reply]: reply::GetVersion
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct GetVersion: ExtensionRequest(0) -> reply::GetVersion {
+		/// The major version of the [XC-MISC extension] supported by the X
+		/// client.
+		///
+		/// [XC-MISC extension]: super
+		pub client_major_version: u16,
+		/// The minor version of the [XC-MISC extension] supported by the X
+		/// client.
+		///
+		/// [XC-MISC extension]: super
+		pub client_minor_version: u16,
+	}
+
+	/// A [request] that returns a contiguous range of [resource IDs] which are
+	/// not in use.
+	///
+	/// The range is found within the IDs which may be used by this X client,
+	/// as given by the `resource_id_base` and `resource_id_mask` when the
+	/// connection was set up. If there are no unused IDs, the range is
+	/// empty.
+	///
+	/// # Replies
+	/// This [request] generates a [`GetXidRange` reply].
+	///
+	/// [request]: ExtensionRequest
+	/// [resource IDs]: crate::ResourceId
+	///
+	/// [`GetXidRange` reply]: reply::GetXidRange
+	#[doc(alias = "GetXIDRange")]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct GetXidRange: ExtensionRequest(1) -> reply::GetXidRange;
+
+	/// A [request] that returns up to `count` [resource IDs] which are not in
+	/// use.
+	///
+	/// Unlike the [`GetXidRange` request], the returned IDs need not be
+	/// contiguous.
+	///
+	/// # Replies
+	/// This [request] generates a [`GetXidList` reply].
+	///
+	/// [request]: ExtensionRequest
+	/// [resource IDs]: crate::ResourceId
+	///
+	/// [`GetXidRange` request]: GetXidRange
+	///
+	/// [`GetXidList` reply]: reply::GetXidList
+	#[doc(alias = "GetXIDList")]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct GetXidList: ExtensionRequest(2) -> reply::GetXidList {
+		/// The maximum number of unused [resource IDs] to return.
+		///
+		/// [resource IDs]: crate::ResourceId
+		pub count: u32,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::message::{LengthMode, Request, WithMajorOpcode, WritableExtensionRequest};
+	use xrbk::{Readable, Writable, X11Size};
+
+	/// The major opcode which the X server might assign to the XC-MISC
+	/// extension.
+	const MAJOR_OPCODE: u8 = 136;
+
+	#[test]
+	fn test_get_version_bytes() {
+		let request = GetVersion {
+			client_major_version: 1,
+			client_minor_version: 1,
+		};
+
+		let mut bytes = vec![];
+		request
+			.write_to_with_opcode(MAJOR_OPCODE, &mut bytes, LengthMode::Standard)
+			.unwrap();
+
+		assert_eq!(bytes, [136, 0, 0, 2, 0, 1, 0, 1]);
+		assert_eq!(request.length(), Ok(2));
+
+		// The major and minor opcodes are not read by `read_from`.
+		assert_eq!(GetVersion::read_from(&mut &bytes[2..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_get_xid_list_bytes() {
+		let request = GetXidList { count: 0x0102_0304 };
+
+		let mut bytes = vec![];
+		request
+			.write_to_with_opcode(MAJOR_OPCODE, &mut bytes, LengthMode::Standard)
+			.unwrap();
+
+		assert_eq!(bytes, [136, 2, 0, 2, 1, 2, 3, 4]);
+		assert_eq!(GetXidList::read_from(&mut &bytes[2..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_with_major_opcode() {
+		let request = WithMajorOpcode::new(MAJOR_OPCODE, GetXidRange);
+
+		assert_eq!(request.major_opcode(), MAJOR_OPCODE);
+		assert_eq!(
+			<WithMajorOpcode<GetXidRange> as Request>::MINOR_OPCODE,
+			Some(1)
+		);
+		assert!(<WithMajorOpcode<GetXidRange> as Request>::HAS_REPLY);
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, [136, 1, 0, 1]);
+		assert_eq!(bytes.len(), request.x11_size());
+
+		// The same request is written with whichever major opcode is given.
+		let mut bytes = vec![];
+		WithMajorOpcode::new(140, GetXidRange)
+			.write_to(&mut bytes)
+			.unwrap();
+
+		assert_eq!(bytes, [140, 1, 0, 1]);
+	}
+}
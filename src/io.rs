@@ -0,0 +1,19 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Utilities for reading messages from the stream of bytes in which they are
+//! received, for batching the requests which are sent, and for matching the
+//! two up.
+//!
+//! These are independent of any particular I/O API or async runtime: bytes are
+//! given to them as they are received, however that may be, and taken from
+//! them to be sent.
+
+pub use buffer::*;
+pub use reader::*;
+pub use state::*;
+
+pub mod buffer;
+pub mod reader;
+pub mod state;
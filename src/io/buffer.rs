@@ -0,0 +1,243 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Batching of the [requests] sent to the X server into a single write.
+//!
+//! [requests]: Request
+
+use bytes::{Bytes, BytesMut};
+use xrbk::{ByteOrder, WriteError};
+
+use crate::message::{Request, SendRequest};
+
+/// The sequence number assigned to a [request].
+///
+/// See [`Reply::sequence`] for more information.
+///
+/// [request]: Request
+/// [`Reply::sequence`]: crate::message::Reply::sequence
+pub type SequenceNumber = u16;
+
+/// Coalesces many [requests] into one buffer of bytes, assigning each its
+/// [sequence number].
+///
+/// [Requests] are serialized with [`push`] and the bytes of all the
+/// [requests] pushed since the last [`flush`] are returned by [`flush`], ready
+/// to be sent in a single write.
+///
+/// Every [request] sent on a connection is implicitly assigned the next
+/// sequence number, starting with `1`. Sequence numbers are 16 bits, so after
+/// `65535` they wrap around to `0` and continue from there. The
+/// `RequestBuffer` counts them in the same way, so it must be used for every
+/// [request] sent on its connection.
+///
+/// The sequence numbers of [requests] which generate [replies] are recorded,
+/// and may be taken with [`take_expected_replies`].
+///
+/// Like [`MessageReader`], the `RequestBuffer` is independent of any
+/// particular I/O API.
+///
+/// # Examples
+/// ```
+/// use xrb::{io::RequestBuffer, x11::request::{GetFocus, GrabServer}};
+/// use xrbk::ByteOrder;
+///
+/// let mut buffer = RequestBuffer::new(ByteOrder::BigEndian);
+///
+/// assert_eq!(buffer.push(&GrabServer).unwrap(), 1);
+/// assert_eq!(buffer.push(&GetFocus).unwrap(), 2);
+///
+/// assert_eq!(buffer.flush(), [36, 0, 0, 1, 43, 0, 0, 1][..]);
+/// assert_eq!(buffer.take_expected_replies(), [2]);
+/// ```
+///
+/// [request]: Request
+/// [requests]: Request
+/// [Requests]: Request
+/// [replies]: crate::message::Reply
+/// [sequence number]: SequenceNumber
+///
+/// [`push`]: RequestBuffer::push
+/// [`flush`]: RequestBuffer::flush
+/// [`take_expected_replies`]: RequestBuffer::take_expected_replies
+/// [`MessageReader`]: super::MessageReader
+#[derive(Debug)]
+pub struct RequestBuffer {
+	order: ByteOrder,
+	buffer: BytesMut,
+
+	/// The sequence number of the last [request] pushed.
+	///
+	/// [request]: Request
+	sequence: SequenceNumber,
+	/// The sequence numbers of the [requests] pushed which generate
+	/// [replies].
+	///
+	/// [requests]: Request
+	/// [replies]: crate::message::Reply
+	expected_replies: Vec<SequenceNumber>,
+}
+
+impl RequestBuffer {
+	/// Creates a new, empty `RequestBuffer` for a connection with the given
+	/// [`ByteOrder`].
+	///
+	/// The first [request] pushed is assigned the sequence number `1`.
+	///
+	/// [request]: Request
+	#[must_use]
+	pub fn new(order: ByteOrder) -> Self {
+		Self {
+			order,
+			buffer: BytesMut::new(),
+
+			sequence: 0,
+			expected_replies: Vec::new(),
+		}
+	}
+
+	/// The [`ByteOrder`] in which [requests] are written.
+	///
+	/// [requests]: Request
+	#[must_use]
+	pub const fn order(&self) -> ByteOrder {
+		self.order
+	}
+
+	/// The sequence number of the last [request] pushed, or `0` if none have
+	/// been pushed.
+	///
+	/// [request]: Request
+	#[must_use]
+	pub const fn last_sequence(&self) -> SequenceNumber {
+		self.sequence
+	}
+
+	/// Whether there are no [requests] waiting to be [flushed].
+	///
+	/// [requests]: Request
+	/// [flushed]: RequestBuffer::flush
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.buffer.is_empty()
+	}
+
+	/// The number of bytes waiting to be [flushed].
+	///
+	/// [flushed]: RequestBuffer::flush
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.buffer.len()
+	}
+
+	/// Serializes the given `request` to the end of the buffer, returning the
+	/// sequence number it is assigned.
+	///
+	/// # Errors
+	/// Returns a [`WriteError`] if the `request` could not be serialized. In
+	/// that case, the buffer is left unmodified and no sequence number is
+	/// used.
+	pub fn push<Req: Request>(&mut self, request: &Req) -> Result<SequenceNumber, WriteError> {
+		let len = self.buffer.len();
+		self.buffer.reserve(request.x11_size());
+
+		if let Err(error) = request.write_to_ordered(&mut self.buffer, self.order) {
+			// Remove any part of the request which was written.
+			self.buffer.truncate(len);
+
+			return Err(error);
+		}
+
+		self.sequence = self.sequence.wrapping_add(1);
+
+		if Req::HAS_REPLY {
+			self.expected_replies.push(self.sequence);
+		}
+
+		Ok(self.sequence)
+	}
+
+	/// Returns the bytes of every [request] pushed since the last flush,
+	/// leaving the buffer empty.
+	///
+	/// [request]: Request
+	pub fn flush(&mut self) -> Bytes {
+		self.buffer.split().freeze()
+	}
+
+	/// Returns the sequence numbers of the [requests] pushed which generate
+	/// [replies], in the order they were pushed, and forgets them.
+	///
+	/// [requests]: Request
+	/// [replies]: crate::message::Reply
+	pub fn take_expected_replies(&mut self) -> Vec<SequenceNumber> {
+		std::mem::take(&mut self.expected_replies)
+	}
+}
+
+impl SendRequest for RequestBuffer {
+	type Error = WriteError;
+
+	fn send_request<Req: Request>(&mut self, request: &Req) -> Result<(), Self::Error> {
+		self.push(request).map(|_| ())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		message::RawRequest,
+		x11::request::{GetFocus, GrabServer, NoOp},
+	};
+
+	#[test]
+	fn test_sequence_wraps_without_restarting() {
+		let mut buffer = RequestBuffer::new(ByteOrder::BigEndian);
+
+		for sequence in 1..=u16::MAX {
+			assert_eq!(buffer.push(&GrabServer).unwrap(), sequence);
+		}
+
+		// The sequence number after `65535` is `0`, not `1`.
+		assert_eq!(buffer.push(&GetFocus).unwrap(), 0);
+		assert_eq!(buffer.push(&GetFocus).unwrap(), 1);
+
+		assert_eq!(buffer.take_expected_replies(), [0, 1]);
+		assert!(buffer.take_expected_replies().is_empty());
+	}
+
+	#[test]
+	fn test_flush_coalesces_requests() {
+		let mut buffer = RequestBuffer::new(ByteOrder::LittleEndian);
+
+		buffer.push(&GrabServer).unwrap();
+		buffer.push(&NoOp { unused_units: 1 }).unwrap();
+
+		assert_eq!(buffer.len(), 12);
+		assert_eq!(buffer.flush(), [36, 0, 1, 0, 127, 0, 2, 0, 0, 0, 0, 0][..]);
+		assert!(buffer.is_empty());
+
+		// Sequence numbers continue after a flush.
+		assert_eq!(buffer.push(&GrabServer).unwrap(), 3);
+	}
+
+	#[test]
+	fn test_failed_push_leaves_buffer_unmodified() {
+		let mut buffer = RequestBuffer::new(ByteOrder::BigEndian);
+		buffer.push(&GrabServer).unwrap();
+
+		// Too long to be written with a `u16` length.
+		let request = RawRequest {
+			major_opcode: 200,
+			minor_opcode: Some(0),
+			body: vec![0; 4 * usize::from(u16::MAX)],
+		};
+
+		assert!(buffer.push(&request).is_err());
+		assert_eq!(buffer.last_sequence(), 1);
+		assert!(buffer.take_expected_replies().is_empty());
+		assert_eq!(buffer.flush(), [36, 0, 0, 1][..]);
+	}
+}
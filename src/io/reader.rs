@@ -0,0 +1,714 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Splitting of the bytes received from the X server into complete messages.
+
+use bytes::{Bytes, BytesMut};
+use thiserror::Error;
+use xrbk::ByteOrder;
+
+use crate::{
+	message::Event,
+	x11::event::{KeyboardState, SYNTHETIC_MASK},
+};
+
+/// The size of an [error] or [event], and the minimum size of a [reply], in
+/// bytes.
+///
+/// [error]: crate::message::Error
+/// [event]: crate::message::Event
+/// [reply]: crate::message::Reply
+const UNIT_SIZE: usize = 32;
+
+/// The [code] of a `GenericEvent`, defined by the Generic Event extension.
+///
+/// Like a [reply], a `GenericEvent` has a length field saying how many 4-byte
+/// units of extra data follow its first 32 bytes. Events sent with a
+/// [`SendEvent` request] are always 32 bytes, so this is only checked for
+/// events with a code of exactly `35`.
+///
+/// [code]: crate::message::Event::CODE
+/// [reply]: crate::message::Reply
+/// [`SendEvent` request]: crate::x11::request::SendEvent
+const GENERIC_EVENT_CODE: u8 = 35;
+
+/// An error generated when [pushing] bytes to a [`MessageReader`] would
+/// buffer more than its [limit].
+///
+/// [pushing]: MessageReader::push
+/// [limit]: MessageReader::limit
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+#[error("buffering {required} bytes would exceed the limit of {limit} bytes")]
+pub struct BufferLimitExceeded {
+	/// The [limit] of the [`MessageReader`].
+	///
+	/// [limit]: MessageReader::limit
+	pub limit: usize,
+	/// The number of bytes which would have been buffered.
+	pub required: usize,
+}
+
+/// The type of message sent by the X server, identified by its first byte.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum MessageKind {
+	/// An [error], identified by a first byte of `0`.
+	///
+	/// [error]: crate::message::Error
+	Error,
+	/// A [reply], identified by a first byte of `1`.
+	///
+	/// [reply]: crate::message::Reply
+	Reply,
+	/// An [event], identified by any other first byte.
+	///
+	/// The first byte is the [event]'s [code], with its most significant bit
+	/// set if it was sent by a [`SendEvent` request].
+	///
+	/// [event]: crate::message::Event
+	/// [code]: crate::message::Event::CODE
+	/// [`SendEvent` request]: crate::x11::request::SendEvent
+	Event,
+}
+
+impl MessageKind {
+	/// Returns the `MessageKind` identified by the given first byte of a
+	/// message.
+	#[must_use]
+	pub const fn from_first_byte(byte: u8) -> Self {
+		match byte {
+			0 => Self::Error,
+			1 => Self::Reply,
+			_ => Self::Event,
+		}
+	}
+}
+
+/// A complete message received from the X server which has not yet been read.
+///
+/// Its bytes include the first byte of the message, which identifies its
+/// [kind]. As the [`Readable`] implementations of messages expect that byte to
+/// have already been read, it must be skipped when the message is read.
+///
+/// The fields of the message's header which are common to every message of
+/// its [kind] are read when it is received, so that it can be dispatched
+/// without being read in full.
+///
+/// [kind]: MessageKind
+/// [`Readable`]: xrbk::Readable
+#[doc(alias = "FramedMessage")]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RawMessage {
+	kind: MessageKind,
+	bytes: Bytes,
+
+	sequence: Option<u16>,
+}
+
+impl RawMessage {
+	/// The [kind] of message which this is.
+	///
+	/// [kind]: MessageKind
+	#[must_use]
+	pub const fn kind(&self) -> MessageKind {
+		self.kind
+	}
+
+	/// The bytes of the message, including its first byte.
+	#[must_use]
+	pub fn bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// The sequence number of the last [request] which the X server had
+	/// received when it sent this message.
+	///
+	/// This is [`None`] for [`KeyboardState` events], which are the only
+	/// messages without a sequence number.
+	///
+	/// [request]: crate::message::Request
+	/// [`KeyboardState` events]: KeyboardState
+	#[must_use]
+	pub const fn sequence(&self) -> Option<u16> {
+		self.sequence
+	}
+
+	/// The [error code] of an [error], or the [code] of an [event].
+	///
+	/// The [code] of an [event] does not include the most significant bit
+	/// set for [events] sent with a [`SendEvent` request]; see
+	/// [`is_synthetic`].
+	///
+	/// This is [`None`] for [replies].
+	///
+	/// [error]: crate::message::Error
+	/// [error code]: crate::message::Error::CODE
+	/// [event]: Event
+	/// [events]: Event
+	/// [code]: Event::CODE
+	/// [replies]: crate::message::Reply
+	///
+	/// [`SendEvent` request]: crate::x11::request::SendEvent
+	/// [`is_synthetic`]: RawMessage::is_synthetic
+	#[must_use]
+	pub fn code(&self) -> Option<u8> {
+		match self.kind {
+			MessageKind::Error => Some(self.bytes[1]),
+			MessageKind::Event => Some(self.bytes[0] & !SYNTHETIC_MASK),
+
+			MessageKind::Reply => None,
+		}
+	}
+
+	/// Whether this is an [event] which was sent with a
+	/// [`SendEvent` request].
+	///
+	/// [event]: Event
+	/// [`SendEvent` request]: crate::x11::request::SendEvent
+	#[must_use]
+	pub fn is_synthetic(&self) -> bool {
+		self.kind == MessageKind::Event && self.bytes[0] & SYNTHETIC_MASK != 0
+	}
+
+	/// Returns the bytes of the message, including its first byte.
+	#[must_use]
+	pub fn into_bytes(self) -> Bytes {
+		self.bytes
+	}
+}
+
+/// Splits the bytes received from the X server into complete [messages].
+///
+/// Bytes are given to the `MessageReader` with [`push`] as they are received,
+/// in chunks of any size. Once the whole of a message has been received, it is
+/// returned by [`next_message`].
+///
+/// [Errors] and [events] are 32 bytes long. [Replies] and `GenericEvent`s,
+/// which are defined by the Generic Event extension, are at least 32 bytes
+/// long, and their length field says how many 4-byte units of extra data
+/// follow. The length field is read in the given [`ByteOrder`], which must be
+/// that of the connection.
+///
+/// The bytes of each [`RawMessage`] refer to the `MessageReader`'s buffer,
+/// rather than being copied out of it. The buffer only grows as bytes are
+/// [pushed], never because of a length field which has yet to be satisfied.
+///
+/// The number of bytes which may be buffered is capped by the reader's
+/// [limit], so that a misbehaving X server cannot make it grow without bound.
+/// A message larger than the [limit] can never be received.
+///
+/// The `MessageReader` is independent of any particular I/O API, so it may be
+/// used with blocking sockets and async runtimes alike.
+///
+/// [limit]: MessageReader::limit
+/// [messages]: RawMessage
+/// [Errors]: crate::message::Error
+/// [events]: crate::message::Event
+/// [Replies]: crate::message::Reply
+///
+/// [`push`]: MessageReader::push
+/// [pushed]: MessageReader::push
+/// [`next_message`]: MessageReader::next_message
+#[derive(Debug)]
+pub struct MessageReader {
+	order: ByteOrder,
+	buffer: BytesMut,
+	/// The maximum number of bytes which may be buffered.
+	limit: usize,
+
+	/// The size of the next message, once enough of it has been received to
+	/// determine that.
+	next_size: Option<usize>,
+}
+
+impl MessageReader {
+	/// The [limit] of a `MessageReader` created with [`MessageReader::new`]:
+	/// 256 MiB.
+	///
+	/// This is almost twice the size of a [`CaptureImage` reply] for a whole
+	/// 8K screen with 32 bits per pixel.
+	///
+	/// [limit]: MessageReader::limit
+	/// [`CaptureImage` reply]: crate::x11::reply::CaptureImage
+	pub const DEFAULT_LIMIT: usize = 256 * 1024 * 1024;
+
+	/// Creates a new `MessageReader` for a connection with the given
+	/// [`ByteOrder`].
+	///
+	/// No more than [`DEFAULT_LIMIT`] bytes may be buffered. See
+	/// [`MessageReader::with_limit`] for a different limit.
+	///
+	/// [`DEFAULT_LIMIT`]: MessageReader::DEFAULT_LIMIT
+	#[must_use]
+	pub fn new(order: ByteOrder) -> Self {
+		Self::with_limit(order, Self::DEFAULT_LIMIT)
+	}
+
+	/// Creates a new `MessageReader` for a connection with the given
+	/// [`ByteOrder`] which buffers no more than `limit` bytes.
+	#[must_use]
+	pub fn with_limit(order: ByteOrder, limit: usize) -> Self {
+		Self {
+			order,
+			buffer: BytesMut::new(),
+			limit,
+
+			next_size: None,
+		}
+	}
+
+	/// The maximum number of bytes which may be buffered.
+	///
+	/// [Pushing][push] bytes beyond this limit fails with
+	/// [`BufferLimitExceeded`].
+	///
+	/// [push]: MessageReader::push
+	#[must_use]
+	pub const fn limit(&self) -> usize {
+		self.limit
+	}
+
+	/// The [`ByteOrder`] in which the length fields of [replies] are read.
+	///
+	/// [replies]: crate::message::Reply
+	#[must_use]
+	pub const fn order(&self) -> ByteOrder {
+		self.order
+	}
+
+	/// The number of bytes which have been [pushed] but not yet returned as
+	/// part of a [`RawMessage`].
+	///
+	/// [pushed]: MessageReader::push
+	#[must_use]
+	pub fn buffered(&self) -> usize {
+		self.buffer.len()
+	}
+
+	/// Adds bytes received from the X server to the end of the buffer.
+	///
+	/// # Errors
+	/// Returns [`BufferLimitExceeded`] if this would buffer more than the
+	/// reader's [limit]. In that case, none of the `bytes` are buffered.
+	///
+	/// [limit]: MessageReader::limit
+	#[doc(alias = "feed")]
+	pub fn push(&mut self, bytes: &[u8]) -> Result<(), BufferLimitExceeded> {
+		let required = self.buffer.len().saturating_add(bytes.len());
+
+		if required > self.limit {
+			return Err(BufferLimitExceeded {
+				limit: self.limit,
+				required,
+			});
+		}
+
+		self.buffer.extend_from_slice(bytes);
+
+		Ok(())
+	}
+
+	/// Returns the next complete message, or [`None`] if the whole of the next
+	/// message has not yet been received.
+	pub fn next_message(&mut self) -> Option<RawMessage> {
+		let size = if let Some(size) = self.next_size {
+			size
+		} else {
+			// Nothing is reserved for the rest of the message: its length
+			// field comes from the X server, and the buffer only grows as the
+			// bytes it describes are pushed.
+			let size = self.peek_size()?;
+			self.next_size = Some(size);
+
+			size
+		};
+
+		if self.buffer.len() < size {
+			return None;
+		}
+
+		self.next_size = None;
+		let bytes = self.buffer.split_to(size).freeze();
+
+		let kind = MessageKind::from_first_byte(bytes[0]);
+		let sequence =
+			if kind == MessageKind::Event && bytes[0] & !SYNTHETIC_MASK == KeyboardState::CODE {
+				None
+			} else {
+				let sequence = [bytes[2], bytes[3]];
+
+				Some(match self.order {
+					ByteOrder::BigEndian => u16::from_be_bytes(sequence),
+					ByteOrder::LittleEndian => u16::from_le_bytes(sequence),
+				})
+			};
+
+		Some(RawMessage {
+			kind,
+			bytes,
+
+			sequence,
+		})
+	}
+
+	/// Returns the size of the next message, or [`None`] if not enough of it
+	/// has been received to determine that.
+	fn peek_size(&self) -> Option<usize> {
+		if self.buffer.len() < UNIT_SIZE {
+			return None;
+		}
+
+		match MessageKind::from_first_byte(self.buffer[0]) {
+			MessageKind::Reply => Some(self.extended_size()),
+			MessageKind::Event if self.buffer[0] == GENERIC_EVENT_CODE => {
+				Some(self.extended_size())
+			},
+
+			MessageKind::Error | MessageKind::Event => Some(UNIT_SIZE),
+		}
+	}
+
+	/// Returns the size of the next message if it is a [reply] or a
+	/// `GenericEvent`, both of which give the number of 4-byte units which
+	/// follow their first 32 bytes at the same position.
+	///
+	/// At least 8 bytes must have been received.
+	///
+	/// [reply]: crate::message::Reply
+	fn extended_size(&self) -> usize {
+		let length = [
+			self.buffer[4],
+			self.buffer[5],
+			self.buffer[6],
+			self.buffer[7],
+		];
+		let length = match self.order {
+			ByteOrder::BigEndian => u32::from_be_bytes(length),
+			ByteOrder::LittleEndian => u32::from_le_bytes(length),
+		};
+
+		UNIT_SIZE.saturating_add((length as usize).saturating_mul(4))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		x11::{error, event, reply},
+		Atom,
+		CurrentableTime,
+		LengthString8,
+		Window,
+	};
+	use xrbk::Writable;
+
+	/// Returns the bytes of a short session: an [event], a [reply] with extra
+	/// data, an [error], and another [event].
+	///
+	/// [event]: crate::message::Event
+	/// [reply]: crate::message::Reply
+	/// [error]: crate::message::Error
+	fn session(order: ByteOrder) -> Vec<u8> {
+		let event = event::Selection {
+			sequence: 1,
+			time: CurrentableTime::CurrentTime,
+			requester: Window::new(0x0040_0001),
+			selection: Atom::new(1),
+			target_type: Atom::new(31),
+			property: Some(Atom::new(31)),
+		};
+		let names = ["BIG-REQUESTS", "XKEYBOARD", "XInputExtension"]
+			.into_iter()
+			.map(|name| LengthString8::try_from(name).unwrap())
+			.collect();
+		let reply = reply::ListExtensions { sequence: 2, names };
+		let error = error::Window {
+			sequence: 3,
+			invalid_window_id: 0x0040_0002,
+			minor_opcode: 0,
+			major_opcode: 4,
+		};
+
+		let mut bytes = vec![];
+		event.write_to_ordered(&mut bytes, order).unwrap();
+		reply.write_to_ordered(&mut bytes, order).unwrap();
+		error.write_to_ordered(&mut bytes, order).unwrap();
+		event.write_to_ordered(&mut bytes, order).unwrap();
+
+		bytes
+	}
+
+	fn read_all(reader: &mut MessageReader, messages: &mut Vec<RawMessage>) {
+		while let Some(message) = reader.next_message() {
+			messages.push(message);
+		}
+	}
+
+	#[test]
+	fn test_one_byte_chunks_match_whole_stream() {
+		for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+			let session = session(order);
+
+			let mut whole = vec![];
+			let mut reader = MessageReader::new(order);
+			reader.push(&session).unwrap();
+			read_all(&mut reader, &mut whole);
+
+			let mut chunked = vec![];
+			let mut reader = MessageReader::new(order);
+			for byte in &session {
+				reader.push(&[*byte]).unwrap();
+				read_all(&mut reader, &mut chunked);
+			}
+
+			let kinds: Vec<_> = whole.iter().map(RawMessage::kind).collect();
+			assert_eq!(
+				kinds,
+				[
+					MessageKind::Event,
+					MessageKind::Reply,
+					MessageKind::Error,
+					MessageKind::Event,
+				],
+			);
+			assert!(whole[1].bytes().len() > UNIT_SIZE);
+
+			assert_eq!(chunked, whole);
+			assert_eq!(reader.buffered(), 0);
+		}
+	}
+
+	#[test]
+	fn test_incomplete_reply_is_held_back() {
+		let session = session(ByteOrder::BigEndian);
+		// The reply's names take up 40 bytes once padded.
+		let reply_end = UNIT_SIZE + UNIT_SIZE + 40;
+
+		let mut reader = MessageReader::new(ByteOrder::BigEndian);
+		reader.push(&session[..reply_end - 1]).unwrap();
+
+		assert_eq!(reader.next_message().unwrap().kind(), MessageKind::Event);
+		assert!(reader.next_message().is_none());
+
+		reader.push(&session[reply_end - 1..reply_end]).unwrap();
+
+		let reply = reader.next_message().unwrap();
+		assert_eq!(reply.kind(), MessageKind::Reply);
+		assert_eq!(reply.bytes(), &session[UNIT_SIZE..reply_end]);
+		assert!(reader.next_message().is_none());
+	}
+
+	/// Returns the bytes of a session with a [reply] of several kilobytes,
+	/// followed by a [`KeyboardState` event] and an [event] sent with a
+	/// [`SendEvent` request].
+	///
+	/// [reply]: crate::message::Reply
+	/// [event]: crate::message::Event
+	/// [`KeyboardState` event]: KeyboardState
+	/// [`SendEvent` request]: crate::x11::request::SendEvent
+	fn large_session(order: ByteOrder) -> Vec<u8> {
+		let character_info = |i: i16| reply::CharacterInfo {
+			left_side_bearing: 0,
+			right_side_bearing: i,
+			width: i,
+			ascent: 10,
+			descent: 2,
+			attributes: 0,
+		};
+		let reply = reply::QueryFont {
+			sequence: 4,
+
+			min_bounds: character_info(0),
+			max_bounds: character_info(399),
+
+			first_character_or_min_minor_index: 0,
+			last_character_or_max_minor_index: 399,
+			fallback_character: 0,
+
+			draw_direction: reply::DrawDirection::LeftToRight,
+
+			min_major_index: 0,
+			max_major_index: 0,
+
+			all_characters_exist: true,
+
+			font_ascent: 10,
+			font_descent: 2,
+
+			properties: vec![],
+			character_infos: (0..400).map(character_info).collect(),
+		};
+		let keyboard_state = event::KeyboardState { keys: [0xff; 31] };
+
+		let mut bytes = session(order);
+		reply.write_to_ordered(&mut bytes, order).unwrap();
+		keyboard_state.write_to_ordered(&mut bytes, order).unwrap();
+
+		// Send the first event again, as if with a `SendEvent` request.
+		let synthetic = bytes[..UNIT_SIZE].to_vec();
+		bytes.extend_from_slice(&synthetic);
+		let last = bytes.len() - UNIT_SIZE;
+		bytes[last] |= SYNTHETIC_MASK;
+
+		bytes
+	}
+
+	#[test]
+	fn test_adversarial_split_points() {
+		let order = ByteOrder::LittleEndian;
+		let session = large_session(order);
+
+		let mut whole = vec![];
+		let mut reader = MessageReader::new(order);
+		reader.push(&session).unwrap();
+		read_all(&mut reader, &mut whole);
+
+		assert_eq!(whole.len(), 7);
+		assert!(whole[4].bytes().len() > 4096);
+
+		// Every split of the stream into two chunks, including those which
+		// split the header of the large reply.
+		for split in 0..=session.len() {
+			let mut messages = vec![];
+			let mut reader = MessageReader::new(order);
+
+			reader.push(&session[..split]).unwrap();
+			read_all(&mut reader, &mut messages);
+			reader.push(&session[split..]).unwrap();
+			read_all(&mut reader, &mut messages);
+
+			assert_eq!(messages, whole, "split at {split}");
+		}
+
+		// Chunks of irregular sizes, from a fixed pseudorandom sequence.
+		let mut state: u32 = 1;
+		let mut messages = vec![];
+		let mut reader = MessageReader::new(order);
+		let mut remaining = &session[..];
+
+		while !remaining.is_empty() {
+			state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+			let len = (state >> 16) as usize % 100;
+			let (chunk, rest) = remaining.split_at(len.min(remaining.len()));
+
+			reader.push(chunk).unwrap();
+			read_all(&mut reader, &mut messages);
+			remaining = rest;
+		}
+
+		assert_eq!(messages, whole);
+		assert_eq!(reader.buffered(), 0);
+	}
+
+	#[test]
+	fn test_header_fields() {
+		let order = ByteOrder::BigEndian;
+
+		let mut messages = vec![];
+		let mut reader = MessageReader::new(order);
+		reader.push(&large_session(order)).unwrap();
+		read_all(&mut reader, &mut messages);
+
+		let headers: Vec<_> = messages
+			.iter()
+			.map(|message| {
+				(
+					message.kind(),
+					message.sequence(),
+					message.code(),
+					message.is_synthetic(),
+				)
+			})
+			.collect();
+
+		assert_eq!(
+			headers,
+			[
+				(MessageKind::Event, Some(1), Some(31), false),
+				(MessageKind::Reply, Some(2), None, false),
+				(MessageKind::Error, Some(3), Some(3), false),
+				(MessageKind::Event, Some(1), Some(31), false),
+				(MessageKind::Reply, Some(4), None, false),
+				(MessageKind::Event, None, Some(11), false),
+				(MessageKind::Event, Some(1), Some(31), true),
+			],
+		);
+	}
+
+	#[test]
+	fn test_generic_event_length() {
+		for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+			// A `GenericEvent` with 2 units of extra data.
+			let mut generic = vec![0; UNIT_SIZE + 8];
+			generic[0] = GENERIC_EVENT_CODE;
+			generic[4..8].copy_from_slice(&match order {
+				ByteOrder::BigEndian => 2_u32.to_be_bytes(),
+				ByteOrder::LittleEndian => 2_u32.to_le_bytes(),
+			});
+			// The same event sent with a `SendEvent` request, which is only
+			// 32 bytes however long its length field says it is.
+			let mut synthetic = generic[..UNIT_SIZE].to_vec();
+			synthetic[0] |= SYNTHETIC_MASK;
+
+			let mut bytes = generic.clone();
+			bytes.extend_from_slice(&synthetic);
+			bytes.extend_from_slice(&session(order));
+
+			let mut messages = vec![];
+			let mut reader = MessageReader::new(order);
+			reader.push(&bytes).unwrap();
+			read_all(&mut reader, &mut messages);
+
+			assert_eq!(messages.len(), 6);
+			assert_eq!(messages[0].bytes(), &generic[..]);
+			assert_eq!(messages[1].bytes(), &synthetic[..]);
+			assert_eq!(messages[2].code(), Some(31));
+			assert_eq!(reader.buffered(), 0);
+		}
+	}
+
+	#[test]
+	fn test_limit() {
+		let session = session(ByteOrder::BigEndian);
+
+		let mut reader = MessageReader::with_limit(ByteOrder::BigEndian, 48);
+		reader.push(&session[..40]).unwrap();
+
+		assert_eq!(
+			reader.push(&session[40..50]),
+			Err(BufferLimitExceeded {
+				limit: 48,
+				required: 50,
+			}),
+		);
+		// Nothing is buffered when the limit would be exceeded.
+		assert_eq!(reader.buffered(), 40);
+
+		// Reading the first event makes room for more.
+		assert_eq!(reader.next_message().unwrap().kind(), MessageKind::Event);
+		reader.push(&session[40..50]).unwrap();
+		assert_eq!(reader.buffered(), 18);
+	}
+
+	#[test]
+	fn test_huge_reply_length() {
+		let mut header = [0; UNIT_SIZE];
+		header[0] = 1;
+		header[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+
+		let mut reader = MessageReader::new(ByteOrder::BigEndian);
+		reader.push(&header).unwrap();
+
+		// Nothing is reserved for the rest of the reply.
+		assert!(reader.next_message().is_none());
+		assert!(reader.buffer.capacity() < 1024);
+
+		let mut reader = MessageReader::with_limit(ByteOrder::BigEndian, 1024);
+		reader.push(&header).unwrap();
+
+		// The reply can never be received.
+		assert!(reader.next_message().is_none());
+		assert!(reader.push(&[0; 1024]).is_err());
+	}
+}
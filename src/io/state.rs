@@ -0,0 +1,727 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Correlation of the messages received from the X server with the [requests]
+//! which were sent.
+//!
+//! [requests]: Request
+
+use std::collections::VecDeque;
+
+use thiserror::Error;
+use xrbk::ByteOrder;
+
+use super::{MessageKind, SequenceNumber};
+use crate::{
+	message::{Event, Request},
+	x11::event::{KeyboardState, SYNTHETIC_MASK},
+};
+
+/// The number of bytes of a message's header needed to [categorize] it.
+///
+/// [categorize]: ProtocolState::categorize
+const HEADER_SIZE: usize = 4;
+
+/// A [request] which has been recorded with a [`ProtocolState`] and not yet
+/// been completed.
+///
+/// [request]: Request
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+struct Outstanding {
+	sequence: SequenceNumber,
+
+	major_opcode: u8,
+	minor_opcode: Option<u16>,
+
+	has_reply: bool,
+
+	replied: bool,
+	errored: bool,
+}
+
+/// What a message received from the X server was sent in response to, as
+/// [categorized] by a [`ProtocolState`].
+///
+/// The opcodes of the [request] which a [reply] or [error] was generated by
+/// are given so that the right type can be chosen to read it as.
+///
+/// [categorized]: ProtocolState::categorize
+/// [request]: Request
+/// [reply]: crate::message::Reply
+/// [error]: crate::message::Error
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Incoming {
+	/// A [reply] to the [request] with the given `sequence` number.
+	///
+	/// [Requests] such as [`ListFontsWithInfo`] may generate more than one
+	/// [reply], each of which is categorized as a `Reply`.
+	///
+	/// [reply]: crate::message::Reply
+	/// [request]: Request
+	/// [Requests]: Request
+	/// [`ListFontsWithInfo`]: crate::x11::request::ListFontsWithInfo
+	Reply {
+		/// The sequence number of the [request].
+		///
+		/// [request]: Request
+		sequence: SequenceNumber,
+
+		/// The [major opcode] of the [request].
+		///
+		/// [major opcode]: Request::MAJOR_OPCODE
+		/// [request]: Request
+		major_opcode: u8,
+		/// The [minor opcode] of the [request], if it has one.
+		///
+		/// [minor opcode]: Request::MINOR_OPCODE
+		/// [request]: Request
+		minor_opcode: Option<u16>,
+	},
+
+	/// An [error] generated by the [request] with the given `sequence` number.
+	///
+	/// [error]: crate::message::Error
+	/// [request]: Request
+	Error {
+		/// The sequence number of the [request].
+		///
+		/// [request]: Request
+		sequence: SequenceNumber,
+
+		/// The [major opcode] of the [request].
+		///
+		/// [major opcode]: Request::MAJOR_OPCODE
+		/// [request]: Request
+		major_opcode: u8,
+		/// The [minor opcode] of the [request], if it has one.
+		///
+		/// [minor opcode]: Request::MINOR_OPCODE
+		/// [request]: Request
+		minor_opcode: Option<u16>,
+	},
+
+	/// An [event], which is not sent in response to any particular [request].
+	///
+	/// [event]: Event
+	/// [request]: Request
+	Event {
+		/// The sequence number of the last [request] which the X server had
+		/// processed when it sent the [event].
+		///
+		/// This is [`None`] for [`KeyboardState` events], which have no
+		/// sequence number.
+		///
+		/// [request]: Request
+		/// [event]: Event
+		/// [`KeyboardState` events]: KeyboardState
+		sequence: Option<SequenceNumber>,
+	},
+}
+
+/// An error generated when a message received from the X server does not
+/// match the [requests] which were recorded as sent.
+///
+/// This means that the connection is out of sync: either a [request] was sent
+/// without being recorded, or the X server has not followed the protocol.
+///
+/// [request]: Request
+/// [requests]: Request
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Error)]
+pub enum DesyncError {
+	/// Fewer than the 4 bytes needed to categorize a message were given.
+	#[error("a message header of {len} bytes is too short to be categorized")]
+	IncompleteHeader {
+		/// The number of bytes which were given.
+		len: usize,
+	},
+
+	/// A message was received for a sequence number with no outstanding
+	/// recorded [request].
+	///
+	/// [request]: Request
+	#[error("received {kind:?} for sequence number {sequence}, which has no outstanding request")]
+	UnrecordedSequence {
+		/// The [kind] of message which was received.
+		///
+		/// [kind]: MessageKind
+		kind: MessageKind,
+		/// The sequence number of the message.
+		sequence: SequenceNumber,
+	},
+
+	/// A [reply] was received for a [request] which does not generate
+	/// [replies].
+	///
+	/// [reply]: crate::message::Reply
+	/// [replies]: crate::message::Reply
+	/// [request]: Request
+	#[error("received a reply for sequence number {sequence}, whose request has no reply")]
+	UnexpectedReply {
+		/// The sequence number of the [request].
+		///
+		/// [request]: Request
+		sequence: SequenceNumber,
+	},
+
+	/// Both a [reply] and an [error] were received for the same [request].
+	///
+	/// [reply]: crate::message::Reply
+	/// [error]: crate::message::Error
+	/// [request]: Request
+	#[error("received both a reply and an error for sequence number {sequence}")]
+	ReplyAndError {
+		/// The sequence number of the [request].
+		///
+		/// [request]: Request
+		sequence: SequenceNumber,
+	},
+
+	/// More than one [error] was received for the same [request].
+	///
+	/// [error]: crate::message::Error
+	/// [request]: Request
+	#[error("received more than one error for sequence number {sequence}")]
+	DuplicateError {
+		/// The sequence number of the [request].
+		///
+		/// [request]: Request
+		sequence: SequenceNumber,
+	},
+}
+
+/// Tracks the [requests] which have been sent so that the messages received
+/// from the X server can be matched with them.
+///
+/// Every [request] sent on the connection must be [recorded] with its sequence
+/// number, in the order that they are sent - such as those returned by
+/// [`RequestBuffer::push`]. Each message received is then [categorized] as a
+/// [reply] to a [request], an [error] generated by a [request], or an [event].
+///
+/// The X server processes [requests] in the order they are sent, and every
+/// message it sends includes the sequence number of the last [request] it
+/// processed. A [request] is outstanding until a message with a later
+/// sequence number is received, as until then more [replies] to it may
+/// follow. Only those outstanding [requests] are kept.
+///
+/// Sequence numbers wrap around after `65535`, so no more than `65535`
+/// [requests] may be outstanding at once.
+///
+/// Like [`MessageReader`] and [`RequestBuffer`], the `ProtocolState` does no
+/// I/O of its own, so it can be shared by blocking and async clients alike.
+///
+/// # Examples
+/// ```
+/// use xrb::{
+///     io::{Incoming, ProtocolState},
+///     x11::request::{GetFocus, GrabServer},
+/// };
+/// use xrbk::ByteOrder;
+///
+/// let mut state = ProtocolState::new(ByteOrder::BigEndian);
+///
+/// state.send_recorded(&GrabServer, 1);
+/// state.send_recorded(&GetFocus, 2);
+///
+/// // The header of a reply with a sequence number of 2.
+/// assert_eq!(
+///     state.categorize(&[1, 0, 0, 2]),
+///     Ok(Incoming::Reply {
+///         sequence: 2,
+///         major_opcode: 43,
+///         minor_opcode: None,
+///     }),
+/// );
+/// ```
+///
+/// [request]: Request
+/// [requests]: Request
+/// [recorded]: ProtocolState::send_recorded
+/// [categorized]: ProtocolState::categorize
+/// [reply]: crate::message::Reply
+/// [replies]: crate::message::Reply
+/// [error]: crate::message::Error
+/// [event]: Event
+///
+/// [`RequestBuffer::push`]: super::RequestBuffer::push
+/// [`RequestBuffer`]: super::RequestBuffer
+/// [`MessageReader`]: super::MessageReader
+#[derive(Debug)]
+pub struct ProtocolState {
+	order: ByteOrder,
+
+	/// The outstanding [requests], in the order they were sent.
+	///
+	/// [requests]: Request
+	outstanding: VecDeque<Outstanding>,
+
+	/// The sequence number of the last message received.
+	last_received: SequenceNumber,
+	/// The sequence number of the last [request] recorded.
+	///
+	/// [request]: Request
+	last_recorded: SequenceNumber,
+}
+
+impl ProtocolState {
+	/// Creates a new `ProtocolState` for a connection with the given
+	/// [`ByteOrder`] on which no [requests] have been sent.
+	///
+	/// [requests]: Request
+	#[must_use]
+	pub const fn new(order: ByteOrder) -> Self {
+		Self {
+			order,
+
+			outstanding: VecDeque::new(),
+
+			last_received: 0,
+			last_recorded: 0,
+		}
+	}
+
+	/// The [`ByteOrder`] in which sequence numbers are read.
+	#[must_use]
+	pub const fn order(&self) -> ByteOrder {
+		self.order
+	}
+
+	/// The number of recorded [requests] which are still outstanding.
+	///
+	/// [requests]: Request
+	#[must_use]
+	pub fn outstanding(&self) -> usize {
+		self.outstanding.len()
+	}
+
+	/// Records that the given [request] was sent with the given `sequence`
+	/// number.
+	///
+	/// The [request]'s opcodes are taken from its [`major_opcode()`] and
+	/// [`minor_opcode()`], so that those of a [`RawRequest`] or an extension
+	/// request are recorded correctly.
+	///
+	/// [request]: Request
+	/// [`major_opcode()`]: Request::major_opcode
+	/// [`minor_opcode()`]: Request::minor_opcode
+	/// [`RawRequest`]: crate::message::RawRequest
+	#[doc(alias = "record")]
+	pub fn send_recorded<Req: Request>(&mut self, request: &Req, sequence: SequenceNumber) {
+		self.outstanding.push_back(Outstanding {
+			sequence,
+
+			major_opcode: request.major_opcode(),
+			minor_opcode: request.minor_opcode(),
+
+			has_reply: Req::HAS_REPLY,
+
+			replied: false,
+			errored: false,
+		});
+		self.last_recorded = sequence;
+	}
+
+	/// Categorizes the message received from the X server with the given
+	/// header.
+	///
+	/// Only the first 4 bytes of the message are needed: its first byte and
+	/// its sequence number. The [bytes of a `RawMessage`] may be given as they
+	/// are.
+	///
+	/// Any outstanding [requests] sent before the message's sequence number
+	/// are completed.
+	///
+	/// # Errors
+	/// Returns a [`DesyncError`] if the message does not match the recorded
+	/// [requests]. In that case, the message is not categorized and the
+	/// outstanding [requests] are left unchanged.
+	///
+	/// [requests]: Request
+	/// [bytes of a `RawMessage`]: super::RawMessage::bytes
+	pub fn categorize(&mut self, header: &[u8]) -> Result<Incoming, DesyncError> {
+		if header.len() < HEADER_SIZE {
+			return Err(DesyncError::IncompleteHeader { len: header.len() });
+		}
+
+		let kind = MessageKind::from_first_byte(header[0]);
+
+		if kind == MessageKind::Event && header[0] & !SYNTHETIC_MASK == KeyboardState::CODE {
+			return Ok(Incoming::Event { sequence: None });
+		}
+
+		let sequence = [header[2], header[3]];
+		let sequence = match self.order {
+			ByteOrder::BigEndian => u16::from_be_bytes(sequence),
+			ByteOrder::LittleEndian => u16::from_le_bytes(sequence),
+		};
+
+		// Sequence numbers are compared by their distance from the last one
+		// received, so that they may wrap around.
+		let distance = |other: SequenceNumber| other.wrapping_sub(self.last_received);
+
+		if distance(sequence) > distance(self.last_recorded) {
+			return Err(DesyncError::UnrecordedSequence { kind, sequence });
+		}
+
+		// The request with this sequence number, if it is outstanding.
+		let request = self
+			.outstanding
+			.iter()
+			.position(|request| request.sequence == sequence);
+
+		let incoming = match kind {
+			MessageKind::Event => Incoming::Event {
+				sequence: Some(sequence),
+			},
+
+			MessageKind::Reply => {
+				let Some(index) = request else {
+					return Err(DesyncError::UnrecordedSequence { kind, sequence });
+				};
+				let request = &mut self.outstanding[index];
+
+				if !request.has_reply {
+					return Err(DesyncError::UnexpectedReply { sequence });
+				}
+				if request.errored {
+					return Err(DesyncError::ReplyAndError { sequence });
+				}
+
+				request.replied = true;
+
+				Incoming::Reply {
+					sequence,
+
+					major_opcode: request.major_opcode,
+					minor_opcode: request.minor_opcode,
+				}
+			},
+
+			MessageKind::Error => {
+				let Some(index) = request else {
+					return Err(DesyncError::UnrecordedSequence { kind, sequence });
+				};
+				let request = &mut self.outstanding[index];
+
+				if request.replied {
+					return Err(DesyncError::ReplyAndError { sequence });
+				}
+				if request.errored {
+					return Err(DesyncError::DuplicateError { sequence });
+				}
+
+				request.errored = true;
+
+				Incoming::Error {
+					sequence,
+
+					major_opcode: request.major_opcode,
+					minor_opcode: request.minor_opcode,
+				}
+			},
+		};
+
+		// Every request sent before this one has been completed.
+		while let Some(request) = self.outstanding.front() {
+			if request.sequence == sequence || distance(request.sequence) > distance(sequence) {
+				break;
+			}
+
+			self.outstanding.pop_front();
+		}
+
+		self.last_received = sequence;
+
+		Ok(incoming)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		known_extensions::BIG_REQUESTS,
+		message::RawRequest,
+		x11::request::{GetFocus, GrabServer, QueryExtension},
+	};
+	use proptest::{collection::vec, prelude::*};
+
+	/// Returns the header of a message of the given `kind` with the given
+	/// `sequence` number, in big-endian byte order.
+	fn header(kind: MessageKind, sequence: SequenceNumber) -> [u8; 4] {
+		let [high, low] = sequence.to_be_bytes();
+
+		match kind {
+			MessageKind::Error => [0, 3, high, low],
+			MessageKind::Reply => [1, 0, high, low],
+			MessageKind::Event => [12, 0, high, low],
+		}
+	}
+
+	fn reply(sequence: SequenceNumber, major_opcode: u8) -> Incoming {
+		Incoming::Reply {
+			sequence,
+			major_opcode,
+			minor_opcode: None,
+		}
+	}
+
+	#[test]
+	fn test_categorize() {
+		let mut state = ProtocolState::new(ByteOrder::BigEndian);
+
+		state.send_recorded(&GrabServer, 1);
+		state.send_recorded(&GetFocus, 2);
+		state.send_recorded(&GrabServer, 3);
+		assert_eq!(state.outstanding(), 3);
+
+		assert_eq!(
+			state.categorize(&header(MessageKind::Event, 0)),
+			Ok(Incoming::Event { sequence: Some(0) }),
+		);
+		assert_eq!(
+			state.categorize(&header(MessageKind::Reply, 2)),
+			Ok(reply(2, GetFocus::MAJOR_OPCODE)),
+		);
+		// `GrabServer` had no reply or error, so it is complete.
+		assert_eq!(state.outstanding(), 2);
+
+		assert_eq!(
+			state.categorize(&header(MessageKind::Error, 3)),
+			Ok(Incoming::Error {
+				sequence: 3,
+				major_opcode: GrabServer::MAJOR_OPCODE,
+				minor_opcode: None,
+			}),
+		);
+		assert_eq!(state.outstanding(), 1);
+
+		// `KeyboardState` events have no sequence number.
+		assert_eq!(
+			state.categorize(&[KeyboardState::CODE, 0, 0, 0]),
+			Ok(Incoming::Event { sequence: None }),
+		);
+	}
+
+	#[test]
+	fn test_categorize_raw_request() {
+		let mut state = ProtocolState::new(ByteOrder::BigEndian);
+
+		// A `RawRequest`'s opcodes are only known from the request itself.
+		let request = RawRequest {
+			major_opcode: 140,
+			minor_opcode: Some(3),
+			body: vec![],
+		};
+		state.send_recorded(&request, 1);
+
+		assert_eq!(
+			state.categorize(&header(MessageKind::Error, 1)),
+			Ok(Incoming::Error {
+				sequence: 1,
+				major_opcode: 140,
+				minor_opcode: Some(3),
+			}),
+		);
+	}
+
+	#[test]
+	fn test_desync() {
+		let mut state = ProtocolState::new(ByteOrder::BigEndian);
+
+		state.send_recorded(&GrabServer, 1);
+		state.send_recorded(&GetFocus, 2);
+		state.send_recorded(&GetFocus, 3);
+
+		assert_eq!(
+			state.categorize(&header(MessageKind::Reply, 4)),
+			Err(DesyncError::UnrecordedSequence {
+				kind: MessageKind::Reply,
+				sequence: 4,
+			}),
+		);
+		assert_eq!(
+			state.categorize(&header(MessageKind::Reply, 1)),
+			Err(DesyncError::UnexpectedReply { sequence: 1 }),
+		);
+		assert_eq!(
+			state.categorize(&[1, 0]),
+			Err(DesyncError::IncompleteHeader { len: 2 }),
+		);
+		// Nothing was completed by the messages which were not categorized.
+		assert_eq!(state.outstanding(), 3);
+
+		state.categorize(&header(MessageKind::Reply, 2)).unwrap();
+		assert_eq!(
+			state.categorize(&header(MessageKind::Error, 2)),
+			Err(DesyncError::ReplyAndError { sequence: 2 }),
+		);
+
+		state.categorize(&header(MessageKind::Error, 3)).unwrap();
+		assert_eq!(
+			state.categorize(&header(MessageKind::Reply, 3)),
+			Err(DesyncError::ReplyAndError { sequence: 3 }),
+		);
+		assert_eq!(
+			state.categorize(&header(MessageKind::Error, 3)),
+			Err(DesyncError::DuplicateError { sequence: 3 }),
+		);
+
+		// Request 2 has been completed, so no more replies are expected.
+		assert_eq!(
+			state.categorize(&header(MessageKind::Reply, 2)),
+			Err(DesyncError::UnrecordedSequence {
+				kind: MessageKind::Reply,
+				sequence: 2,
+			}),
+		);
+	}
+
+	#[test]
+	fn test_sequence_wraps() {
+		let mut state = ProtocolState::new(ByteOrder::LittleEndian);
+
+		for sequence in 1..=u16::MAX {
+			state.send_recorded(&GrabServer, sequence);
+		}
+		state.categorize(&[12, 0, 0xff, 0xff]).unwrap();
+		assert_eq!(state.outstanding(), 1);
+
+		// The sequence number after `65535` is `0`.
+		state.send_recorded(&GetFocus, 0);
+		state.send_recorded(&GetFocus, 1);
+
+		assert_eq!(
+			state.categorize(&[1, 0, 0, 0]),
+			Ok(reply(0, GetFocus::MAJOR_OPCODE)),
+		);
+		assert_eq!(state.outstanding(), 2);
+		assert_eq!(
+			state.categorize(&[1, 0, 1, 0]),
+			Ok(reply(1, GetFocus::MAJOR_OPCODE)),
+		);
+		assert_eq!(state.outstanding(), 1);
+	}
+
+	/// A [request] sent in a simulated session, and how the X server responds
+	/// to it.
+	///
+	/// [request]: Request
+	#[derive(Clone, Debug)]
+	enum Response {
+		/// A request without a reply which succeeds.
+		Success,
+		/// A request without a reply which generates an error.
+		Error,
+		/// A request with a reply which generates an error.
+		ReplyError,
+		/// A request with a reply which generates the given number of
+		/// replies.
+		Replies(usize),
+	}
+
+	fn responses() -> impl Strategy<Value = Response> {
+		prop_oneof![
+			Just(Response::Success),
+			Just(Response::Error),
+			Just(Response::ReplyError),
+			(1..3usize).prop_map(Response::Replies),
+		]
+	}
+
+	proptest! {
+		#[test]
+		fn test_interleaved_session(
+			skipped in prop_oneof![0..16u16, 65_500..=u16::MAX],
+			requests in vec((responses(), 0..3usize), 1..64),
+			sends_first in vec(any::<bool>(), 0..256),
+		) {
+			let mut state = ProtocolState::new(ByteOrder::BigEndian);
+
+			// Skip ahead, so that sequence numbers may wrap around.
+			for sequence in 1..=skipped {
+				state.send_recorded(&GrabServer, sequence);
+			}
+			state.categorize(&header(MessageKind::Event, skipped)).unwrap();
+			prop_assert!(state.outstanding() <= 1);
+
+			// The messages sent by the X server, each with the number of
+			// requests which must have been sent for it to be received.
+			let mut messages = vec![];
+			let mut sequence = skipped;
+
+			for (index, (response, events)) in requests.iter().enumerate() {
+				sequence = sequence.wrapping_add(1);
+				let sent = index + 1;
+
+				let (major_opcode, kind, count) = match response {
+					Response::Success => (GrabServer::MAJOR_OPCODE, MessageKind::Error, 0),
+					Response::Error => (GrabServer::MAJOR_OPCODE, MessageKind::Error, 1),
+					Response::ReplyError => (GetFocus::MAJOR_OPCODE, MessageKind::Error, 1),
+					Response::Replies(count) => {
+						(GetFocus::MAJOR_OPCODE, MessageKind::Reply, *count)
+					},
+				};
+
+				for _ in 0..count {
+					let expected = if kind == MessageKind::Reply {
+						reply(sequence, major_opcode)
+					} else {
+						Incoming::Error { sequence, major_opcode, minor_opcode: None }
+					};
+
+					messages.push((sent, header(kind, sequence), expected));
+				}
+				for _ in 0..*events {
+					let expected = Incoming::Event { sequence: Some(sequence) };
+
+					messages.push((sent, header(MessageKind::Event, sequence), expected));
+				}
+			}
+
+			let mut sent = 0;
+			// The number of the request which the last message received was
+			// for.
+			let mut last_received = 0;
+			let mut received = messages.iter().peekable();
+			let mut sends_first = sends_first.into_iter();
+			let mut sequence = skipped;
+
+			while sent < requests.len() || received.peek().is_some() {
+				let can_receive = received.peek().is_some_and(|(required, ..)| *required <= sent);
+				let send = sent < requests.len()
+					&& (!can_receive || sends_first.next().unwrap_or(false));
+
+				if send {
+					sequence = sequence.wrapping_add(1);
+
+					match requests[sent].0 {
+						Response::Success | Response::Error => {
+							state.send_recorded(&GrabServer, sequence);
+						},
+						Response::ReplyError | Response::Replies(_) => {
+							state.send_recorded(&GetFocus, sequence);
+						},
+					}
+
+					sent += 1;
+				} else if let Some((required, header, expected)) = received.next() {
+					prop_assert_eq!(state.categorize(header), Ok(*expected));
+					last_received = *required;
+				}
+
+				// Only requests which haven't been completed are kept.
+				prop_assert!(state.outstanding() <= sent - last_received + 1);
+			}
+
+			// After a final round trip, only that request is left.
+			sequence = sequence.wrapping_add(1);
+			state.send_recorded(&QueryExtension::from(BIG_REQUESTS), sequence);
+			prop_assert_eq!(
+				state.categorize(&header(MessageKind::Reply, sequence)),
+				Ok(reply(sequence, QueryExtension::MAJOR_OPCODE)),
+			);
+			prop_assert_eq!(state.outstanding(), 1);
+		}
+	}
+}
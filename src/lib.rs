@@ -13,6 +13,8 @@
 // This is so we can provide a reason when we ignore a particular lint with
 // `allow`.
 #![feature(lint_reasons)]
+// Used to read arrays of lists without leaking already-read lists on failure.
+#![feature(array_try_from_fn)]
 // // Used for convenience. Will remove if XRB is reaching stability and this is
 // // still unstable.
 // #![feature(if_let_guard)]
@@ -82,8 +84,16 @@ pub const PROTOCOL_MAJOR_VERSION: u16 = 11;
 /// probably safe to assume it won't.
 pub const PROTOCOL_MINOR_VERSION: u16 = 0;
 
+#[cfg(test)]
+mod arbitrary;
 pub(crate) mod common;
 pub mod connection;
+pub mod extension;
+pub mod io;
 pub mod message;
+pub mod prelude;
+pub mod properties;
 pub mod unit;
+#[cfg(feature = "arbitrary")]
+mod unstructured;
 pub mod x11;
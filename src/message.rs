@@ -4,12 +4,107 @@
 
 //! Traits defining the format of messages sent via the X11 protocol.
 
-use crate::x11::error;
-use xrbk::{Readable, Writable, X11Size};
+use std::convert::Infallible;
+
+use crate::x11::{error, request};
+use xrbk::{
+	Buf,
+	BufMut,
+	DynBufMut,
+	ReadError,
+	ReadResult,
+	Readable,
+	Writable,
+	WriteError,
+	WriteResult,
+	X11Size,
+};
+
+/// Items used by the code generated by `derive_xrb!`.
+#[doc(hidden)]
+pub mod __private {
+	/// A supertrait of the [`Request`], [`ExtensionRequest`], [`Reply`],
+	/// [`Event`], and [`Error`] traits which is implemented by `derive_xrb!`.
+	///
+	/// Since messages can only implement those traits by being defined with
+	/// `derive_xrb!`, methods can be added to them without breaking other
+	/// crates.
+	///
+	/// It is also a supertrait of [`ResourceId`], which is only implemented
+	/// for the resource ID types defined in XRB.
+	///
+	/// [`Request`]: super::Request
+	/// [`ExtensionRequest`]: super::ExtensionRequest
+	/// [`Reply`]: super::Reply
+	/// [`Event`]: super::Event
+	/// [`Error`]: super::Error
+	/// [`ResourceId`]: crate::ResourceId
+	pub trait Sealed {}
+
+	#[cfg(test)]
+	pub use inventory;
+
+	/// A message registered when testing.
+	///
+	/// `derive_xrb!` registers every message it defines, and messages which
+	/// are not defined with `derive_xrb!` are registered with
+	/// `register_message!`. This is used to check that
+	/// [`all_message_types!`] lists every message.
+	///
+	/// [`all_message_types!`]: crate::all_message_types
+	#[cfg(test)]
+	pub struct RegisteredMessage {
+		/// The kind of message.
+		pub kind: MessageKind,
+		/// The name of the message's type.
+		pub name: &'static str,
+		/// The [`module_path!`] of the module in which the message is defined.
+		pub module_path: &'static str,
+	}
+
+	#[cfg(test)]
+	inventory::collect!(RegisteredMessage);
+
+	/// The kind of a [`RegisteredMessage`].
+	#[cfg(test)]
+	#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+	pub enum MessageKind {
+		/// A [request](super::Request).
+		Request,
+		/// A [reply](super::Reply).
+		Reply,
+		/// An [event](super::Event).
+		Event,
+		/// An [error](super::Error).
+		Error,
+	}
+}
+
+/// Registers a message which is not defined with `derive_xrb!` when testing,
+/// as `derive_xrb!` does for the messages it defines.
+///
+/// See [`RegisteredMessage`](__private::RegisteredMessage).
+macro_rules! register_message {
+	($kind:ident $Message:ident) => {
+		#[cfg(test)]
+		crate::message::__private::inventory::submit! {
+			crate::message::__private::RegisteredMessage {
+				kind: crate::message::__private::MessageKind::$kind,
+				name: stringify!($Message),
+				module_path: module_path!(),
+			}
+		}
+	};
+}
+
+pub(crate) use register_message;
 
 /// A message sent from an X client to the X server.
+///
+/// This trait is sealed: requests implement it by being defined with
+/// `derive_xrb!`.
 #[doc(notable_trait)]
-pub trait Request: X11Size + Writable {
+pub trait Request: __private::Sealed + X11Size + Writable {
 	/// The type representing the [other possible errors][other-errors]
 	/// generated by this `Request`.
 	///
@@ -60,6 +155,32 @@ pub trait Request: X11Size + Writable {
 	/// has one request.
 	const MINOR_OPCODE: Option<u16>;
 
+	/// Whether this `Request` generates a [reply].
+	///
+	/// This is `false` if [`Request::Reply`] is `()`, and `true` otherwise.
+	///
+	/// [reply]: Reply
+	const HAS_REPLY: bool;
+
+	/// The major opcode of this `Request`.
+	///
+	/// This is [`MAJOR_OPCODE`] for every `Request` but [`RawRequest`], whose
+	/// major opcode is only known at runtime.
+	///
+	/// [`MAJOR_OPCODE`]: Request::MAJOR_OPCODE
+	fn major_opcode(&self) -> u8 {
+		Self::MAJOR_OPCODE
+	}
+	/// The minor opcode of this `Request`, if any.
+	///
+	/// This is [`MINOR_OPCODE`] for every `Request` but [`RawRequest`], whose
+	/// minor opcode is only known at runtime.
+	///
+	/// [`MINOR_OPCODE`]: Request::MINOR_OPCODE
+	fn minor_opcode(&self) -> Option<u16> {
+		Self::MINOR_OPCODE
+	}
+
 	/// The size of this `Request`, including the header, in 4-byte units.
 	///
 	/// ***Implementors: please see the [implementation notes section][impl] at
@@ -85,6 +206,11 @@ pub trait Request: X11Size + Writable {
 	/// |...                |...                |...       |
 	/// |`4n - 4`           |`4n`               |`n`       |
 	///
+	/// # Errors
+	/// Returns a [`RequestTooLong`] error if the `Request` is too long for its
+	/// length to fit in a `u16`. Such a `Request` can only be written with
+	/// [`LengthMode::Extended`].
+	///
 	/// # Implementation notes
 	/// This method is implemented by default based on the [`X11Size`]
 	/// implementation.
@@ -123,8 +249,7 @@ pub trait Request: X11Size + Writable {
 	///     }
 	/// }
 	/// ```
-	#[allow(clippy::cast_possible_truncation)]
-	fn length(&self) -> u16 {
+	fn length(&self) -> Result<u16, RequestTooLong> {
 		let size = self.x11_size();
 
 		assert_eq!(
@@ -133,7 +258,317 @@ pub trait Request: X11Size + Writable {
 			"expected Request size to be a multiple of 4, found {size}"
 		);
 
-		(size / 4) as u16
+		request_length(size)
+	}
+}
+
+/// How the [`length()`] of a [request] is encoded.
+///
+/// [`length()`]: Request::length
+/// [request]: Request
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Default)]
+pub enum LengthMode {
+	/// The [`length()`] is always written as a `u16`.
+	///
+	/// [Requests] longer than `u16::MAX` 4-byte units cannot be written.
+	///
+	/// [`length()`]: Request::length
+	/// [Requests]: Request
+	#[default]
+	Standard,
+
+	/// The [`length()`] is written as a `u16` if it fits, otherwise it is
+	/// written in the extended form defined by the BIG-REQUESTS extension.
+	///
+	/// In the extended form, the `u16` length is `0` and is followed by the
+	/// length as a `u32`. That `u32` length includes its own 4 bytes, so a
+	/// [request] written in the extended form is 4 bytes longer than its
+	/// [`X11Size`].
+	///
+	/// The extended form may only be used once the BIG-REQUESTS extension has
+	/// been enabled.
+	///
+	/// [`length()`]: Request::length
+	/// [request]: Request
+	Extended,
+}
+
+/// An error returned when a [request] is too long to be written with the
+/// given [`LengthMode`].
+///
+/// [request]: Request
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, thiserror::Error)]
+#[error("a request of {size} bytes is too long to be written with {mode:?} lengths")]
+pub struct RequestTooLong {
+	/// The size of the [request], in bytes.
+	///
+	/// [request]: Request
+	pub size: usize,
+	/// The [`LengthMode`] with which the [request] was written.
+	///
+	/// [request]: Request
+	pub mode: LengthMode,
+}
+
+/// A [request] which can be written with a particular [`LengthMode`].
+///
+/// This is implemented by [`derive_xrb!`] for every [request] it defines. The
+/// [`Writable`] implementation of those [requests] uses
+/// [`LengthMode::Standard`].
+///
+/// [request]: Request
+/// [requests]: Request
+/// [`derive_xrb!`]: xrbk_macro::derive_xrb!
+pub trait WritableRequest: Request {
+	/// Writes this [request], encoding its [`length()`] with the given
+	/// `mode`.
+	///
+	/// # Errors
+	/// Returns a [`WriteError`] wrapping a [`RequestTooLong`] error if this
+	/// [request] is too long to be written with the given `mode`, or any
+	/// other [`WriteError`] if a part of it could not be written.
+	///
+	/// [request]: Request
+	/// [`length()`]: Request::length
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult;
+
+	/// Writes this [request] to a [`DynBufMut`] trait object, encoding its
+	/// [`length()`] with the given `mode`.
+	///
+	/// As with [`Writable::write_to_dyn`], if the `dyn-buffers` feature is
+	/// enabled, [requests] defined with [`derive_xrb!`] are written by this
+	/// function so that their code is only compiled once, and
+	/// [`write_to_with`] only forwards to it. By default, this forwards to
+	/// [`write_to_with`].
+	///
+	/// # Errors
+	/// See [`write_to_with`].
+	///
+	/// [request]: Request
+	/// [requests]: Request
+	/// [`length()`]: Request::length
+	/// [`derive_xrb!`]: xrbk_macro::derive_xrb!
+	/// [`write_to_with`]: WritableRequest::write_to_with
+	fn write_to_with_dyn(&self, mut buf: &mut dyn DynBufMut, mode: LengthMode) -> WriteResult {
+		self.write_to_with(&mut buf, mode)
+	}
+}
+
+/// Returns the [`length()`] of a [request] of the given `size` in bytes.
+///
+/// This is used in implementations of [`Request`] and [`ExtensionRequest`].
+///
+/// # Errors
+/// Returns a [`RequestTooLong`] error if the length does not fit in a `u16`,
+/// and so can only be written with [`LengthMode::Extended`].
+///
+/// [`length()`]: Request::length
+/// [request]: Request
+pub fn request_length(size: usize) -> Result<u16, RequestTooLong> {
+	u16::try_from(size / 4).map_err(|_| RequestTooLong {
+		size,
+		mode: LengthMode::Standard,
+	})
+}
+
+/// Writes the length of a [request] of the given `size` in bytes, encoded
+/// with the given `mode`.
+///
+/// This is used in implementations of [`WritableRequest`].
+///
+/// # Errors
+/// Returns a [`WriteError`] wrapping a [`RequestTooLong`] error if the
+/// [request] is too long to be written with the given `mode`.
+///
+/// [request]: Request
+pub fn write_request_length(buf: &mut impl BufMut, size: usize, mode: LengthMode) -> WriteResult {
+	let too_long = || WriteError::Other(Box::new(RequestTooLong { size, mode }));
+
+	if let Ok(length) = request_length(size) {
+		buf.put_u16(length);
+
+		return Ok(());
+	}
+
+	match mode {
+		LengthMode::Standard => Err(too_long()),
+
+		LengthMode::Extended => {
+			// The extended length includes its own 4 bytes.
+			let length = u32::try_from(size / 4 + 1).map_err(|_| too_long())?;
+
+			buf.put_u16(0);
+			buf.put_u32(length);
+
+			Ok(())
+		},
+	}
+}
+
+/// A [request] defined by an extension.
+///
+/// The major opcode of an extension is assigned by the X server, and returned
+/// in the [`QueryExtension` reply], so it is only known at runtime. An
+/// `ExtensionRequest` is therefore not a [`Request`] itself: it is sent by
+/// wrapping it in [`WithMajorOpcode`] along with the major opcode of its
+/// extension.
+///
+/// The `ExtensionRequest` is identified within its extension by its
+/// [`MINOR_OPCODE`], which is written in the metabyte position.
+///
+/// [request]: Request
+/// [`MINOR_OPCODE`]: ExtensionRequest::MINOR_OPCODE
+///
+/// [`QueryExtension` reply]: crate::x11::reply::QueryExtension
+///
+/// This trait is sealed: extension requests implement it by being defined with
+/// `derive_xrb!`.
+#[doc(notable_trait)]
+pub trait ExtensionRequest: __private::Sealed + X11Size {
+	/// The type representing the other possible errors generated by this
+	/// `ExtensionRequest`.
+	///
+	/// See [`Request::OtherErrors`] for more information.
+	type OtherErrors;
+
+	/// The type of [`Reply`] generated by this `ExtensionRequest`.
+	///
+	/// For `ExtensionRequest`s which do not generate a [reply], this is `()`.
+	///
+	/// [reply]: Reply
+	type Reply;
+
+	/// The minor opcode that uniquely identifies this `ExtensionRequest`
+	/// within its extension.
+	const MINOR_OPCODE: u8;
+
+	/// Whether this `ExtensionRequest` generates a [reply].
+	///
+	/// This is `false` if [`ExtensionRequest::Reply`] is `()`, and `true`
+	/// otherwise.
+	///
+	/// [reply]: Reply
+	const HAS_REPLY: bool;
+
+	/// The size of this `ExtensionRequest`, including the header, in 4-byte
+	/// units.
+	///
+	/// See [`Request::length`] for more information.
+	///
+	/// # Errors
+	/// See [`Request::length`].
+	fn length(&self) -> Result<u16, RequestTooLong> {
+		let size = self.x11_size();
+
+		assert_eq!(
+			size % 4,
+			0,
+			"expected ExtensionRequest size to be a multiple of 4, found {size}"
+		);
+
+		request_length(size)
+	}
+}
+
+/// An [`ExtensionRequest`] which can be written with the major opcode of its
+/// extension.
+///
+/// This is implemented by [`derive_xrb!`] for every [`ExtensionRequest`] it
+/// defines.
+///
+/// [`derive_xrb!`]: xrbk_macro::derive_xrb!
+pub trait WritableExtensionRequest: ExtensionRequest {
+	/// Writes this [`ExtensionRequest`] with the given `major_opcode`,
+	/// encoding its [`length()`] with the given `mode`.
+	///
+	/// The `major_opcode` is the major opcode of the extension, as returned
+	/// in the [`QueryExtension` reply].
+	///
+	/// # Errors
+	/// See [`WritableRequest::write_to_with`].
+	///
+	/// [`length()`]: ExtensionRequest::length
+	///
+	/// [`QueryExtension` reply]: crate::x11::reply::QueryExtension
+	fn write_to_with_opcode(
+		&self, major_opcode: u8, buf: &mut impl BufMut, mode: LengthMode,
+	) -> WriteResult;
+}
+
+/// An [`ExtensionRequest`] together with the major opcode of its extension,
+/// which makes it a [`Request`].
+///
+/// ```
+/// use xrb::{
+///     extension::xc_misc::request::GetXidRange,
+///     message::{Request, WithMajorOpcode},
+/// };
+/// use xrbk::Writable;
+///
+/// // The major opcode returned in the `QueryExtension` reply for XC-MISC.
+/// let request = WithMajorOpcode::new(136, GetXidRange);
+///
+/// let mut bytes = vec![];
+/// request.write_to(&mut bytes).unwrap();
+///
+/// // The major opcode, the minor opcode, and the length.
+/// assert_eq!(bytes, [136, 1, 0, 1]);
+/// assert_eq!(request.major_opcode(), 136);
+/// ```
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WithMajorOpcode<Req> {
+	/// The major opcode of the extension which defines the `request`.
+	pub major_opcode: u8,
+	/// The [`ExtensionRequest`].
+	pub request: Req,
+}
+
+impl<Req> WithMajorOpcode<Req> {
+	/// Wraps the given `request` with the `major_opcode` of its extension.
+	#[must_use]
+	pub const fn new(major_opcode: u8, request: Req) -> Self {
+		Self {
+			major_opcode,
+			request,
+		}
+	}
+}
+
+impl<Req: WritableExtensionRequest> __private::Sealed for WithMajorOpcode<Req> {}
+
+impl<Req: WritableExtensionRequest> Request for WithMajorOpcode<Req> {
+	type OtherErrors = Req::OtherErrors;
+	type Reply = Req::Reply;
+
+	// As with `RawRequest`, the major opcode is only known at runtime.
+	const MAJOR_OPCODE: u8 = 0;
+	const MINOR_OPCODE: Option<u16> = Some(Req::MINOR_OPCODE as u16);
+	const HAS_REPLY: bool = Req::HAS_REPLY;
+
+	fn major_opcode(&self) -> u8 {
+		self.major_opcode
+	}
+
+	fn length(&self) -> Result<u16, RequestTooLong> {
+		self.request.length()
+	}
+}
+
+impl<Req: X11Size> X11Size for WithMajorOpcode<Req> {
+	fn x11_size(&self) -> usize {
+		self.request.x11_size()
+	}
+}
+
+impl<Req: WritableExtensionRequest> Writable for WithMajorOpcode<Req> {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl<Req: WritableExtensionRequest> WritableRequest for WithMajorOpcode<Req> {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		self.request.write_to_with_opcode(self.major_opcode, buf, mode)
 	}
 }
 
@@ -209,10 +644,91 @@ pub enum RequestError<OtherErrors> {
 	Other(OtherErrors),
 }
 
+/// Something to which [requests] can be sent.
+///
+/// This is a minimal abstraction over a connection to an X server, allowing
+/// helpers which send [requests] to be written without depending on any
+/// particular connection type.
+///
+/// `SendRequest` is implemented for any closure which accepts the serialized
+/// bytes of a [request]:
+/// ```
+/// use std::convert::Infallible;
+/// use xrb::{message::SendRequest, x11::request::GrabServer};
+///
+/// let mut bytes = vec![];
+/// let mut sink = |request: &[u8]| -> Result<(), Infallible> {
+///     bytes.extend_from_slice(request);
+///     Ok(())
+/// };
+///
+/// sink.send_request(&GrabServer).unwrap();
+/// assert_eq!(bytes, [36, 0, 0, 1]);
+/// ```
+///
+/// [request]: Request
+/// [requests]: Request
+pub trait SendRequest {
+	/// The type of error which may be returned when sending a [request].
+	///
+	/// [request]: Request
+	type Error;
+
+	/// Sends the given `request`.
+	///
+	/// # Errors
+	/// Returns [`Self::Error`] if the `request` could not be serialized or
+	/// sent.
+	fn send_request<Req: Request>(&mut self, request: &Req) -> Result<(), Self::Error>;
+}
+
+/// An error returned when a closure implementing [`SendRequest`] fails to
+/// send a [request].
+///
+/// [request]: Request
+#[derive(thiserror::Error, Debug)]
+pub enum SendError<E> {
+	/// The [request] could not be serialized.
+	///
+	/// [request]: Request
+	#[error("failed to serialize the request: {0}")]
+	Write(WriteError),
+
+	/// The closure failed to send the serialized [request].
+	///
+	/// [request]: Request
+	#[error("failed to send the request")]
+	Send(E),
+}
+
+impl<F, E> SendRequest for F
+where
+	F: FnMut(&[u8]) -> Result<(), E>,
+{
+	type Error = SendError<E>;
+
+	fn send_request<Req: Request>(&mut self, request: &Req) -> Result<(), Self::Error> {
+		let mut bytes = Vec::with_capacity(request.x11_size());
+		request.write_to(&mut bytes).map_err(SendError::Write)?;
+
+		self(&bytes).map_err(SendError::Send)
+	}
+}
+
 /// A message sent from the X server to an X client in response to a
 /// [`Request`].
+///
+/// Most `Reply`s are [`Readable`] on their own. Some, however, can only be read
+/// with information from the [request] that generated them; those are read
+/// with [`read_with_request`] instead.
+///
+/// [request]: Request
+/// [`read_with_request`]: Reply::read_with_request
+///
+/// This trait is sealed: replies implement it by being defined with
+/// `derive_xrb!`.
 #[doc(notable_trait)]
-pub trait Reply: X11Size + Readable {
+pub trait Reply: __private::Sealed + X11Size {
 	/// The [request] that generates this `Reply`.
 	///
 	/// The type indicated here must implement [`Request`] with a
@@ -271,7 +787,7 @@ pub trait Reply: X11Size + Readable {
 	///     #     pub drawable: xrb::Drawable,
 	///     # }
 	///     #
-	///     #[derive(Debug, Hash, Readable, Writable, X11Size)]
+	///     #[derive(Debug, Readable, Writable, X11Size)]
 	///     pub struct GetGeometryReply: Reply for GetGeometry {
 	///         // Header is 8 bytes.
 	///
@@ -317,6 +833,65 @@ pub trait Reply: X11Size + Readable {
 	///
 	/// [request]: Request
 	fn sequence(&self) -> u16;
+
+	/// Reads this `Reply` from the given `buf`, using the [request] that
+	/// generated it.
+	///
+	/// For `Reply`s which are [`Readable`] on their own, this ignores the
+	/// [request] and calls [`Readable::read_from`].
+	///
+	/// When using [`derive_xrb!`], a `Reply` may refer to its [request] in
+	/// `#[context]` attributes with `self::request`, in which case it is not
+	/// [`Readable`] on its own:
+	///
+	/// [`derive_xrb!`]: xrbk_macro::derive_xrb!
+	///
+	/// ```
+	/// use xrbk_macro::derive_xrb;
+	/// use xrb::message::Reply;
+	/// # use xrb::message::Request;
+	///
+	/// derive_xrb! {
+	///     # #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	///     # pub struct GetCounts: Request(200) -> GetCountsReply {
+	///     #     pub count: u16,
+	///     #     [_; 2],
+	///     # }
+	///     #
+	///     #[derive(Debug, X11Size, Readable, Writable)]
+	///     pub struct GetCountsReply: Reply for GetCounts {
+	///         #[sequence]
+	///         pub sequence: u16,
+	///         [_; 24],
+	///
+	///         // The number of counts is the `count` from the request.
+	///         #[context(self::request => usize::from(request.count))]
+	///         pub counts: Vec<u32>,
+	///     }
+	/// }
+	///
+	/// # use xrbk::Writable;
+	/// #
+	/// # let mut bytes = vec![];
+	/// # GetCountsReply { sequence: 0, counts: vec![1, 2] }.write_to(&mut bytes).unwrap();
+	/// #
+	/// // The first byte of a reply is not read.
+	/// let reply = GetCountsReply::read_with_request(
+	///     &mut &bytes[1..],
+	///     &GetCounts { count: 2 },
+	/// )?;
+	/// assert_eq!(reply.counts, [1, 2]);
+	/// #
+	/// # Ok::<(), xrbk::ReadError>(())
+	/// ```
+	///
+	/// [request]: Request
+	///
+	/// # Errors
+	/// Returns a [`ReadError`] if the `Reply` could not be read.
+	fn read_with_request(buf: &mut impl Buf, request: &Self::Request) -> ReadResult<Self>
+	where
+		Self: Sized;
 }
 
 /// A message sent from the X server to an X client.
@@ -326,8 +901,11 @@ pub trait Reply: X11Size + Readable {
 ///
 /// [replies]: Reply
 /// [request]: Request
+///
+/// This trait is sealed: events implement it by being defined with
+/// `derive_xrb!`.
 #[doc(notable_trait)]
-pub trait Event: X11Size + Readable + Writable {
+pub trait Event: __private::Sealed + X11Size + Readable + Writable {
 	/// The code uniquely identifying this `Event` (among other `Event`s).
 	///
 	/// Event codes 64 to 127 are reserved for extensions. The core X11 protocol
@@ -346,8 +924,11 @@ pub trait Event: X11Size + Readable + Writable {
 /// [request].
 ///
 /// [request]: Request
+///
+/// This trait is sealed: errors implement it by being defined with
+/// `derive_xrb!`.
 #[doc(notable_trait)]
-pub trait Error: X11Size + Readable {
+pub trait Error: __private::Sealed + X11Size + Readable {
 	/// The code uniquely identifying this `Error` (among other `Error`s).
 	///
 	/// Error codes 128 to 255 are reserved for extensions.
@@ -370,3 +951,987 @@ pub trait Error: X11Size + Readable {
 	/// [major opcode]: Request::MAJOR_OPCODE
 	fn major_opcode(&self) -> u8;
 }
+
+/// The [codes] of the [errors] represented by a type, such as the
+/// [`OtherErrors`] of a [request].
+///
+/// This is implemented for every [error], for [`Infallible`], which represents
+/// no errors, and for the enums of the errors which may be generated by each
+/// core [request].
+///
+/// [codes]: Error::CODE
+/// [error]: Error
+/// [errors]: Error
+/// [request]: Request
+///
+/// [`OtherErrors`]: Request::OtherErrors
+pub trait ErrorCodes {
+	/// The [codes] of the [errors] represented by this type.
+	///
+	/// [codes]: Error::CODE
+	/// [errors]: Error
+	const CODES: &'static [u8];
+}
+
+impl<E: Error> ErrorCodes for E {
+	const CODES: &'static [u8] = &[E::CODE];
+}
+
+impl ErrorCodes for Infallible {
+	const CODES: &'static [u8] = &[];
+}
+
+/// A [request] written from its opcodes and the raw bytes of its body.
+///
+/// `RawRequest` allows [requests] which are not modeled by XRB, such as those
+/// of unimplemented extensions, to be sent. The header, length, and padding
+/// of the [request] are written automatically.
+///
+/// If there is no `minor_opcode`, the first byte of the `body` is written in
+/// the metabyte position of the header, just as it is for core [requests].
+/// Otherwise, the `minor_opcode` is written in the metabyte position and the
+/// whole `body` follows the header.
+///
+/// Since the opcodes of a `RawRequest` are only known at runtime, its
+/// [`MAJOR_OPCODE`] and [`MINOR_OPCODE`] are `0` and [`None`]; use
+/// [`Request::major_opcode`] and [`Request::minor_opcode`] instead.
+///
+/// # Examples
+/// ```
+/// use xrb::message::RawRequest;
+/// use xrbk::Writable;
+///
+/// // A `QueryVersion` request of the XFIXES extension.
+/// let request = RawRequest {
+///     major_opcode: 138,
+///     minor_opcode: Some(0),
+///     body: vec![0, 0, 0, 5, 0, 0, 0, 0],
+/// };
+///
+/// let mut bytes = vec![];
+/// request.write_to(&mut bytes).unwrap();
+///
+/// assert_eq!(bytes, [138, 0, 0, 3, 0, 0, 0, 5, 0, 0, 0, 0]);
+/// ```
+///
+/// [request]: Request
+/// [requests]: Request
+///
+/// [`MAJOR_OPCODE`]: Request::MAJOR_OPCODE
+/// [`MINOR_OPCODE`]: Request::MINOR_OPCODE
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RawRequest {
+	/// The major opcode of the [request].
+	///
+	/// For extension [requests], this is the major opcode returned for the
+	/// extension in the [`QueryExtension` reply].
+	///
+	/// [request]: Request
+	/// [requests]: Request
+	///
+	/// [`QueryExtension` reply]: crate::x11::reply::QueryExtension
+	pub major_opcode: u8,
+	/// The minor opcode of the [request], if any.
+	///
+	/// [request]: Request
+	pub minor_opcode: Option<u8>,
+
+	/// The bytes of the [request] which follow its opcodes, not including
+	/// its length or padding.
+	///
+	/// [request]: Request
+	pub body: Vec<u8>,
+}
+
+impl RawRequest {
+	/// The bytes of the `body` which follow the header.
+	///
+	/// This excludes the first byte of the `body` if it is written in the
+	/// metabyte position.
+	fn data(&self) -> &[u8] {
+		match self.minor_opcode {
+			Some(_) => &self.body,
+			None => self.body.get(1..).unwrap_or_default(),
+		}
+	}
+}
+
+impl __private::Sealed for RawRequest {}
+
+impl Request for RawRequest {
+	type OtherErrors = Infallible;
+	type Reply = RawReply;
+
+	const MAJOR_OPCODE: u8 = 0;
+	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = true;
+
+	fn major_opcode(&self) -> u8 {
+		self.major_opcode
+	}
+
+	fn minor_opcode(&self) -> Option<u16> {
+		self.minor_opcode.map(u16::from)
+	}
+}
+
+impl X11Size for RawRequest {
+	fn x11_size(&self) -> usize {
+		const HEADER: usize = 4;
+		const ALIGNMENT: usize = 4;
+
+		HEADER + self.data().len().next_multiple_of(ALIGNMENT)
+	}
+}
+
+impl Writable for RawRequest {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl WritableRequest for RawRequest {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		const ALIGNMENT: usize = 4;
+
+		buf.put_u8(self.major_opcode);
+		// The minor opcode or the first byte of the body.
+		buf.put_u8(
+			self.minor_opcode
+				.or_else(|| self.body.first().copied())
+				.unwrap_or(0),
+		);
+		write_request_length(buf, self.x11_size(), mode)?;
+
+		let data = self.data();
+		buf.put_slice(data);
+		// Padding.
+		buf.put_bytes(0, data.len().next_multiple_of(ALIGNMENT) - data.len());
+
+		Ok(())
+	}
+}
+
+/// A [reply] to a [`RawRequest`], with the raw bytes of its body.
+///
+/// The [`length()`] of the reply is given by [`Reply::length`].
+///
+/// [reply]: Reply
+/// [`length()`]: Reply::length
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RawReply {
+	/// The byte in the metabyte position of the [reply]'s header.
+	///
+	/// [reply]: Reply
+	pub metabyte: u8,
+	/// The [sequence number] of the [`RawRequest`] which generated this
+	/// [reply].
+	///
+	/// [reply]: Reply
+	/// [sequence number]: Reply::sequence
+	pub sequence: u16,
+
+	/// The bytes of the [reply] following its 8-byte header.
+	///
+	/// This is always at least 24 bytes long, and a multiple of 4 bytes long,
+	/// for a [reply] that has been read.
+	///
+	/// [reply]: Reply
+	pub body: Vec<u8>,
+}
+
+impl __private::Sealed for RawReply {}
+
+impl Reply for RawReply {
+	type Request = RawRequest;
+
+	fn sequence(&self) -> u16 {
+		self.sequence
+	}
+
+	fn read_with_request(buf: &mut impl Buf, _request: &RawRequest) -> ReadResult<Self> {
+		Self::read_from(buf)
+	}
+}
+
+impl X11Size for RawReply {
+	fn x11_size(&self) -> usize {
+		const HEADER: usize = 8;
+
+		HEADER + self.body.len()
+	}
+}
+
+impl Readable for RawReply {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		const MINIMUM_BODY: usize = 24;
+		const ALIGNMENT: usize = 4;
+
+		let metabyte = u8::read_from(buf)?;
+		let sequence = u16::read_from(buf)?;
+		let length = u32::read_from(buf)? as usize;
+
+		let body_size = MINIMUM_BODY + length * ALIGNMENT;
+		xrbk::ensure_remaining(buf, body_size)?;
+
+		let mut body = vec![0; body_size];
+		buf.copy_to_slice(&mut body);
+
+		Ok(Self {
+			metabyte,
+			sequence,
+			body,
+		})
+	}
+}
+
+impl Writable for RawReply {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		// Indicates that this is a reply.
+		buf.put_u8(1);
+		buf.put_u8(self.metabyte);
+		buf.put_u16(self.sequence);
+		buf.put_u32(self.length());
+
+		buf.put_slice(&self.body);
+
+		Ok(())
+	}
+}
+
+/// A [`Buf`] which reads the bytes of a `prefix` before those of the buffer it
+/// wraps.
+///
+/// This is used by [`AnyRequest::read_from`] to read a [request]'s header
+/// again once its length is known. The `prefix` is big endian, while the
+/// reading of multi-byte integers from the wrapped buffer is forwarded to it,
+/// so that an [`Ordered`] buffer's byte order is respected.
+///
+/// [request]: Request
+/// [`Ordered`]: xrbk::Ordered
+struct Prefixed<'a, B> {
+	prefix: &'a [u8],
+	inner: B,
+}
+
+/// Generates overrides of [`Buf`] methods which read from the `prefix` if any
+/// of it remains, and otherwise forward to the inner buffer.
+macro_rules! prefixed_gets {
+	($($get:ident -> $ty:ty;)*) => {
+		$(
+			fn $get(&mut self) -> $ty {
+				if self.prefix.is_empty() {
+					self.inner.$get()
+				} else {
+					self.prefix.$get()
+				}
+			}
+		)*
+	};
+}
+
+impl<B: Buf> Buf for Prefixed<'_, B> {
+	fn remaining(&self) -> usize {
+		self.prefix.len() + self.inner.remaining()
+	}
+
+	fn chunk(&self) -> &[u8] {
+		if self.prefix.is_empty() {
+			self.inner.chunk()
+		} else {
+			self.prefix
+		}
+	}
+
+	fn advance(&mut self, cnt: usize) {
+		let from_prefix = cnt.min(self.prefix.len());
+
+		self.prefix.advance(from_prefix);
+		self.inner.advance(cnt - from_prefix);
+	}
+
+	prefixed_gets! {
+		get_u16 -> u16;
+		get_i16 -> i16;
+		get_u32 -> u32;
+		get_i32 -> i32;
+		get_u64 -> u64;
+		get_i64 -> i64;
+		get_u128 -> u128;
+		get_i128 -> i128;
+		get_f32 -> f32;
+		get_f64 -> f64;
+	}
+}
+
+/// Generates [`AnyRequest`] with a variant for each of the given [requests].
+///
+/// [requests]: Request
+macro_rules! any_request {
+	($($Request:ident),+$(,)?) => {
+		/// Any [request] defined in the [core X11 protocol], or an unknown
+		/// [request].
+		///
+		/// This allows a [request] to be read without knowing its type in
+		/// advance, as an X server must: see [`AnyRequest::read_from`].
+		///
+		/// [request]: Request
+		/// [core X11 protocol]: crate::x11
+		#[allow(deprecated)]
+		#[derive(Debug)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		pub enum AnyRequest {
+			$(
+				#[doc = concat!("A [`", stringify!($Request), "`](request::", stringify!($Request), ") request.")]
+				$Request(request::$Request),
+			)+
+			/// A [request] of an unrecognized type, such as one defined in an
+			/// extension.
+			///
+			/// [`ChangeKeyboardMapping` requests] are also read as `Unknown`,
+			/// because their type depends on more than their opcode.
+			///
+			/// [request]: Request
+			/// [`ChangeKeyboardMapping` requests]: request::ChangeKeyboardMapping
+			Unknown {
+				/// The [request]'s major opcode.
+				///
+				/// [request]: Request
+				opcode: u8,
+				/// The byte in the metabyte position of the [request]'s
+				/// header.
+				///
+				/// For extension [requests], this is the minor opcode.
+				///
+				/// [request]: Request
+				/// [requests]: Request
+				metabyte: u8,
+
+				/// The bytes of the [request] following its header, including
+				/// any padding.
+				///
+				/// [request]: Request
+				data: Vec<u8>,
+			},
+		}
+
+		#[allow(deprecated)]
+		impl AnyRequest {
+			/// Reads a [request] of any type, including its major opcode.
+			///
+			/// The type of [request] is identified by its major opcode. Major
+			/// opcodes which are not those of [requests] defined in the core
+			/// X11 protocol are read as [`AnyRequest::Unknown`], rather than
+			/// an error.
+			///
+			/// Exactly the number of bytes given by the [request]'s length are
+			/// consumed from the `buf`, even if the [request] itself is read
+			/// from fewer of them, so that the next [request] can be read
+			/// from the same `buf`. Lengths in the extended form defined by
+			/// the BIG-REQUESTS extension are accepted.
+			///
+			/// # Errors
+			/// Returns an error if fewer bytes remain in the `buf` than the
+			/// [request]'s length, or if the [request] could not be read.
+			///
+			/// [request]: Request
+			/// [requests]: Request
+			pub fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+				const HEADER: usize = 4;
+				const EXTENDED_HEADER: usize = 8;
+
+				xrbk::ensure_remaining(buf, HEADER)?;
+
+				let opcode = buf.get_u8();
+				let metabyte = buf.get_u8();
+				let length = buf.get_u16();
+
+				// The header of the request following its major opcode, which is
+				// read again by the request itself.
+				let mut bytes = vec![metabyte];
+				bytes.put_u16(length);
+
+				// A length of zero means that the length is given in the
+				// extended form that follows it.
+				let (size, header) = if length == 0 {
+					xrbk::ensure_remaining(buf, EXTENDED_HEADER - HEADER)?;
+
+					let length = buf.get_u32();
+					bytes.put_u32(length);
+
+					(length as usize * 4, EXTENDED_HEADER)
+				} else {
+					(usize::from(length) * 4, HEADER)
+				};
+
+				if size < header {
+					return Err(ReadError::UnexpectedEndOfData {
+						expected: header,
+						found: size,
+					});
+				}
+
+				xrbk::ensure_remaining(buf, size - header)?;
+
+				// The request's body is read from `buf` itself, rather than from
+				// a copy of its bytes, so that the byte order of an `Ordered`
+				// buffer is respected.
+				let buf = &mut Prefixed {
+					prefix: &bytes[..],
+					inner: xrbk::take(buf, size - header),
+				};
+
+				let request = match opcode {
+					$(
+						<request::$Request as Request>::MAJOR_OPCODE => {
+							Self::$Request(request::$Request::read_from(buf)?)
+						},
+					)+
+
+					_ => {
+						xrbk::skip(buf, header - 1)?;
+
+						let mut data = vec![0; size - header];
+						buf.copy_to_slice(&mut data);
+
+						Self::Unknown {
+							opcode,
+							metabyte,
+							data,
+						}
+					},
+				};
+
+				// Skip any bytes of the request which were not read.
+				xrbk::skip(buf, buf.remaining())?;
+
+				Ok(request)
+			}
+
+			/// The [request]'s major opcode.
+			///
+			/// [request]: Request
+			#[must_use]
+			pub const fn major_opcode(&self) -> u8 {
+				match self {
+					$(Self::$Request(_) => <request::$Request as Request>::MAJOR_OPCODE,)+
+
+					Self::Unknown { opcode, .. } => *opcode,
+				}
+			}
+		}
+
+		#[allow(deprecated)]
+		impl X11Size for AnyRequest {
+			fn x11_size(&self) -> usize {
+				const HEADER: usize = 4;
+
+				match self {
+					$(Self::$Request(request) => request.x11_size(),)+
+
+					Self::Unknown { data, .. } => HEADER + data.len().next_multiple_of(4),
+				}
+			}
+		}
+
+		#[allow(deprecated)]
+		impl Writable for AnyRequest {
+			fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+				match self {
+					$(Self::$Request(request) => request.write_to(buf),)+
+
+					Self::Unknown {
+						opcode,
+						metabyte,
+						data,
+					} => {
+						buf.put_u8(*opcode);
+						buf.put_u8(*metabyte);
+						write_request_length(buf, self.x11_size(), LengthMode::Standard)?;
+
+						buf.put_slice(data);
+						// Padding.
+						buf.put_bytes(0, data.len().next_multiple_of(4) - data.len());
+
+						Ok(())
+					},
+				}
+			}
+		}
+	};
+}
+
+any_request! {
+	CreateWindow,
+	ChangeWindowAttributes,
+	GetWindowAttributes,
+	DestroyWindow,
+	DestroyChildren,
+	ChangeSavedWindows,
+	ReparentWindow,
+	MapWindow,
+	MapChildren,
+	UnmapWindow,
+	UnmapChildren,
+	ConfigureWindow,
+	CirculateWindow,
+	GetGeometry,
+	QueryWindowTree,
+	GetAtom,
+	GetAtomName,
+	ModifyProperty,
+	DeleteProperty,
+	GetProperty,
+	ListProperties,
+	SetSelectionOwner,
+	GetSelectionOwner,
+	ConvertSelection,
+	SendEvent,
+	GrabCursor,
+	UngrabCursor,
+	GrabButton,
+	UngrabButton,
+	ChangeActiveCursorGrab,
+	GrabKeyboard,
+	UngrabKeyboard,
+	GrabKey,
+	UngrabKey,
+	AllowEvents,
+	GrabServer,
+	UngrabServer,
+	QueryCursorLocation,
+	GetMotionHistory,
+	ConvertCoordinates,
+	WarpCursor,
+	SetFocus,
+	GetFocus,
+	QueryKeyboard,
+	AssignFont,
+	UnassignFont,
+	QueryFont,
+	QueryTextExtents,
+	ListFonts,
+	ListFontsWithInfo,
+	SetFontSearchDirectories,
+	GetFontSearchDirectories,
+	CreatePixmap,
+	FreePixmap,
+	CreateGraphicsContext,
+	ChangeGraphicsOptions,
+	CopyGraphicsOptions,
+	SetDashes,
+	SetClipRectangles,
+	DestroyGraphicsContext,
+	ClearArea,
+	CopyArea,
+	CopyBitPlane,
+	DrawPoints,
+	DrawPath,
+	DrawLines,
+	DrawRectangles,
+	DrawArcs,
+	FillPolygon,
+	FillRectangles,
+	FillArcs,
+	PlaceImage,
+	CaptureImage,
+	DrawText8,
+	DrawText16,
+	ImageText8,
+	ImageText16,
+	CreateColormap,
+	DestroyColormap,
+	MoveColormap,
+	InstallColormap,
+	UninstallColormap,
+	ListInstalledColormaps,
+	AllocateColor,
+	AllocateNamedColor,
+	AllocateColorCells,
+	AllocateColorPlanes,
+	DestroyColormapEntries,
+	StoreColors,
+	StoreNamedColor,
+	QueryColors,
+	GetNamedColor,
+	CreateCursorAppearance,
+	CreateGlyphCursorAppearance,
+	DestroyCursorAppearance,
+	RecolorCursorAppearance,
+	QueryIdealDimensions,
+	QueryExtension,
+	ListExtensions,
+	GetKeyboardMapping,
+	ChangeKeyboardOptions,
+	GetKeyboardOptions,
+	RingBell,
+	ChangeCursorOptions,
+	GetCursorOptions,
+	SetScreenSaver,
+	GetScreenSaver,
+	ChangeHosts,
+	QueryAccessControl,
+	SetAccessControl,
+	SetRetainResourcesMode,
+	KillClient,
+	RotateProperties,
+	ForceScreenSaver,
+	SetButtonMapping,
+	GetButtonMapping,
+	SetModifierMapping,
+	GetModifierMapping,
+	NoOp,
+}
+
+#[cfg(test)]
+mod test {
+	extern crate self as xrb;
+
+	use std::{any::TypeId, convert::Infallible};
+
+	use derivative::Derivative;
+
+	use super::*;
+	use crate::{
+		x11::{reply, request},
+		GrabStatus,
+		String8,
+		Window,
+	};
+	use xrbk::{ByteOrder, Ordered};
+	use xrbk_macro::derive_xrb;
+
+	/// Returns the [`TypeId`]s of the `Req`'s [`Reply`] and
+	/// [`OtherErrors`](Request::OtherErrors) types, as generic code would see
+	/// them.
+	fn associated_types<Req>() -> (TypeId, TypeId)
+	where
+		Req: Request,
+		Req::Reply: 'static,
+		Req::OtherErrors: 'static,
+	{
+		(TypeId::of::<Req::Reply>(), TypeId::of::<Req::OtherErrors>())
+	}
+
+	#[test]
+	fn test_derived_request_associated_types() {
+		assert_eq!(
+			associated_types::<request::GrabCursor>(),
+			(
+				TypeId::of::<reply::GrabCursor>(),
+				TypeId::of::<request::GrabCursorError>(),
+			),
+		);
+		assert_eq!(request::GrabCursor::MAJOR_OPCODE, 26);
+		assert_eq!(request::GrabCursor::MINOR_OPCODE, None);
+
+		// Without a reply or other errors.
+		assert_eq!(
+			associated_types::<request::UngrabCursor>(),
+			(TypeId::of::<()>(), TypeId::of::<Infallible>()),
+		);
+	}
+
+	#[test]
+	fn test_derived_reply_trait() {
+		fn sequence<Rep: Reply>(reply: &Rep) -> u16 {
+			reply.sequence()
+		}
+
+		let reply = reply::GrabCursor {
+			sequence: 42,
+			grab_status: GrabStatus::Success,
+		};
+
+		assert_eq!(sequence(&reply), 42);
+		assert_eq!(reply.length(), 0);
+		assert_eq!(
+			TypeId::of::<<reply::GrabCursor as Reply>::Request>(),
+			TypeId::of::<request::GrabCursor>(),
+		);
+	}
+
+	derive_xrb! {
+		#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+		pub struct GetCounts: Request(200) -> GetCountsReply {
+			pub count: u16,
+			[_; 2],
+		}
+
+		#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+		#[derivative(PartialEq, Eq)]
+		pub struct GetCountsReply: Reply for GetCounts {
+			#[sequence]
+			pub sequence: u16,
+			[_; 24],
+
+			#[context(self::request => usize::from(request.count))]
+			pub counts: Vec<u32>,
+		}
+	}
+
+	#[test]
+	fn test_read_reply_with_request() {
+		let reply = GetCountsReply {
+			sequence: 7,
+			counts: vec![1, 2, 3],
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+		assert_eq!(reply.length(), 3);
+
+		let read = GetCountsReply::read_with_request(&mut &bytes[1..], &GetCounts { count: 3 });
+		assert_eq!(read.unwrap(), reply);
+
+		// The request says there are more counts than were written.
+		let read = GetCountsReply::read_with_request(&mut &bytes[1..], &GetCounts { count: 4 });
+		assert!(read.is_err());
+
+		// Replies which don't use their request ignore it.
+		let raw = RawRequest {
+			major_opcode: 200,
+			minor_opcode: None,
+			body: vec![],
+		};
+		let read = RawReply::read_with_request(&mut &bytes[1..], &raw).unwrap();
+		assert_eq!(read, RawReply::read_from(&mut &bytes[1..]).unwrap());
+	}
+
+	#[test]
+	fn test_raw_request_matches_typed() {
+		let typed = request::QueryExtension {
+			name: crate::String8::from_latin1("XFIXES").unwrap(),
+		};
+		let raw = RawRequest {
+			major_opcode: 98,
+			minor_opcode: None,
+			// The unused metabyte, the length of the name, 2 unused bytes, and
+			// the name.
+			body: [&[0, 0, 6, 0, 0][..], b"XFIXES"].concat(),
+		};
+
+		let mut typed_bytes = vec![];
+		typed.write_to(&mut typed_bytes).unwrap();
+		let mut raw_bytes = vec![];
+		raw.write_to(&mut raw_bytes).unwrap();
+
+		assert_eq!(raw_bytes, typed_bytes);
+		assert_eq!(raw.x11_size(), typed.x11_size());
+		assert_eq!(raw.length(), typed.length());
+		assert_eq!(raw.major_opcode(), typed.major_opcode());
+	}
+
+	#[test]
+	fn test_raw_request_metabyte() {
+		let mut bytes = vec![];
+		RawRequest {
+			major_opcode: 129,
+			minor_opcode: None,
+			body: vec![7, 1, 2],
+		}
+		.write_to(&mut bytes)
+		.unwrap();
+		assert_eq!(bytes, [129, 7, 0, 2, 1, 2, 0, 0]);
+
+		let mut bytes = vec![];
+		RawRequest {
+			major_opcode: 129,
+			minor_opcode: Some(4),
+			body: vec![7, 1, 2],
+		}
+		.write_to(&mut bytes)
+		.unwrap();
+		assert_eq!(bytes, [129, 4, 0, 2, 7, 1, 2, 0]);
+
+		let mut bytes = vec![];
+		RawRequest {
+			major_opcode: 129,
+			minor_opcode: None,
+			body: vec![],
+		}
+		.write_to(&mut bytes)
+		.unwrap();
+		assert_eq!(bytes, [129, 0, 0, 1]);
+	}
+
+	#[test]
+	fn test_raw_reply_matches_typed() {
+		let typed = reply::QueryExtension {
+			sequence: 3,
+			present: true,
+			major_opcode: Some(138),
+			first_event_code: Some(87),
+			first_error_code: Some(140),
+		};
+
+		let mut bytes = vec![];
+		typed.write_to(&mut bytes).unwrap();
+
+		let raw = RawReply::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(raw.metabyte, 0);
+		assert_eq!(raw.sequence(), 3);
+		assert_eq!(raw.length(), 0);
+		assert_eq!(raw.body[..4], [1, 138, 87, 140]);
+
+		let mut raw_bytes = vec![];
+		raw.write_to(&mut raw_bytes).unwrap();
+
+		assert_eq!(raw_bytes, bytes);
+	}
+
+	#[test]
+	fn test_any_request_reads_stream() {
+		let get_atom = request::GetAtom {
+			no_creation: true,
+			name: String8::from_latin1("WM_NAME").unwrap(),
+		};
+		let no_op = request::NoOp { unused_units: 2 };
+		// A `QueryVersion` request of the XFIXES extension.
+		let extension = RawRequest {
+			major_opcode: 138,
+			minor_opcode: Some(0),
+			body: vec![0, 0, 0, 5, 0, 0, 0, 0],
+		};
+
+		let mut bytes = vec![];
+		get_atom.write_to(&mut bytes).unwrap();
+		request::GetFocus.write_to(&mut bytes).unwrap();
+		extension.write_to(&mut bytes).unwrap();
+		no_op.write_to(&mut bytes).unwrap();
+		// A `GetFocus` request with a length in the extended form.
+		bytes.extend([43, 0, 0, 0, 0, 0, 0, 2]);
+		// A `GetFocus` request with 4 more bytes than it reads.
+		bytes.extend([43, 0, 0, 2, 0, 0, 0, 0]);
+		request::GrabServer.write_to(&mut bytes).unwrap();
+
+		let buf = &mut &bytes[..];
+		let mut requests = vec![];
+
+		while !buf.is_empty() {
+			requests.push(AnyRequest::read_from(buf).unwrap());
+		}
+
+		assert_eq!(requests.len(), 7);
+
+		assert!(matches!(&requests[0], AnyRequest::GetAtom(request) if *request == get_atom));
+		assert!(matches!(requests[1], AnyRequest::GetFocus(_)));
+		assert!(matches!(
+			&requests[2],
+			AnyRequest::Unknown {
+				opcode: 138,
+				metabyte: 0,
+				data,
+			} if *data == extension.body
+		));
+		assert!(matches!(&requests[3], AnyRequest::NoOp(request) if *request == no_op));
+		assert!(matches!(requests[4], AnyRequest::GetFocus(_)));
+		assert!(matches!(requests[5], AnyRequest::GetFocus(_)));
+		assert!(matches!(requests[6], AnyRequest::GrabServer(_)));
+
+		assert_eq!(requests[2].major_opcode(), 138);
+		assert_eq!(requests[6].major_opcode(), 36);
+	}
+
+	#[test]
+	fn test_any_request_reads_little_endian() {
+		let map_window = request::MapWindow {
+			target: Window::new(0x0040_0001),
+		};
+
+		let mut bytes = vec![];
+		map_window
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		assert_eq!(bytes, [8, 0, 2, 0, 0x01, 0, 0x40, 0]);
+		// A `GetFocus` request with a length in the extended form.
+		bytes.extend([43, 0, 0, 0, 2, 0, 0, 0]);
+
+		let buf = &mut Ordered::new(&bytes[..], ByteOrder::LittleEndian);
+
+		let read = AnyRequest::read_from(buf).unwrap();
+		assert!(matches!(&read, AnyRequest::MapWindow(request) if *request == map_window));
+		assert!(matches!(
+			AnyRequest::read_from(buf).unwrap(),
+			AnyRequest::GetFocus(_)
+		));
+		assert_eq!(buf.remaining(), 0);
+
+		let mut written = vec![];
+		read.write_to_ordered(&mut written, ByteOrder::LittleEndian)
+			.unwrap();
+		assert_eq!(written, bytes[..8]);
+	}
+
+	#[test]
+	fn test_any_request_round_trip() {
+		let mut bytes = vec![];
+		request::GetFocus.write_to(&mut bytes).unwrap();
+		// `ChangeKeyboardMapping` requests can't be read by their type.
+		request::ChangeKeyboardMapping::<2> {
+			first_keycode: crate::Keycode(8),
+			mappings: vec![[crate::Keysym::new(0x61), crate::Keysym::new(0x41)]],
+		}
+		.write_to(&mut bytes)
+		.unwrap();
+
+		let buf = &mut &bytes[..];
+		let requests = [
+			AnyRequest::read_from(buf).unwrap(),
+			AnyRequest::read_from(buf).unwrap(),
+		];
+
+		assert!(buf.is_empty());
+		assert!(matches!(
+			requests[1],
+			AnyRequest::Unknown { opcode: 100, .. }
+		));
+
+		let mut written = vec![];
+
+		for request in &requests {
+			request.write_to(&mut written).unwrap();
+		}
+
+		assert_eq!(written, bytes);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_any_request_serde_round_trip() {
+		let mut bytes = vec![];
+		request::GetFocus.write_to(&mut bytes).unwrap();
+		bytes.extend([200, 1, 0, 2, 1, 2, 3, 4]);
+
+		let buf = &mut &bytes[..];
+		let requests = [
+			AnyRequest::read_from(buf).unwrap(),
+			AnyRequest::read_from(buf).unwrap(),
+		];
+
+		let json = serde_json::to_value(&requests).unwrap();
+
+		// Requests are tagged with the name of their type.
+		assert!(json[0].get("GetFocus").is_some());
+		assert_eq!(json[1]["Unknown"]["data"], serde_json::json!([1, 2, 3, 4]));
+
+		let mut written = vec![];
+
+		for request in serde_json::from_value::<Vec<AnyRequest>>(json).unwrap() {
+			request.write_to(&mut written).unwrap();
+		}
+
+		assert_eq!(written, bytes);
+	}
+
+	#[test]
+	fn test_any_request_too_short() {
+		// A length of zero in the extended form.
+		let bytes = [43, 0, 0, 0, 0, 0, 0, 0];
+
+		assert!(AnyRequest::read_from(&mut &bytes[..]).is_err());
+		// Fewer bytes than the length.
+		assert!(AnyRequest::read_from(&mut &[43, 0, 0, 2, 0][..]).is_err());
+	}
+}
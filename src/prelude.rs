@@ -0,0 +1,39 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The traits and types which are needed to send and receive messages.
+//!
+//! This includes the [XRBK prelude], so that crates using XRB don't need to
+//! depend on XRBK or `bytes` themselves.
+//!
+//! ```
+//! use xrb::{prelude::*, unit::SignedPercentage, x11::request::RingBell};
+//!
+//! let request = RingBell {
+//!     volume: SignedPercentage::new(50).unwrap(),
+//! };
+//!
+//! let mut bytes = vec![];
+//! request.write_to(&mut bytes)?;
+//!
+//! assert_eq!(bytes.len(), usize::from(request.length().unwrap()) * 4);
+//! assert_eq!(bytes[0], RingBell::MAJOR_OPCODE);
+//! #
+//! # Ok::<(), xrbk::WriteError>(())
+//! ```
+//!
+//! [XRBK prelude]: xrbk::prelude
+
+pub use crate::message::{
+	Error,
+	Event,
+	ExtensionRequest,
+	Reply,
+	Request,
+	SendRequest,
+	WithMajorOpcode,
+	WritableExtensionRequest,
+	WritableRequest,
+};
+pub use xrbk::prelude::*;
@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Encodings of the standard window properties defined by the [ICCCM] and
+//! [EWMH].
+//!
+//! These properties are set by clients on their top-level [windows] to
+//! describe them to the window manager. Their values are sent as a
+//! [`DataList`] with a [`ModifyProperty` request] and received in a
+//! [`GetProperty` reply]: the types in this module convert between those
+//! values and their meaning, independently of how they are sent.
+//!
+//! [ICCCM]: https://x.org/releases/X11R7.7/doc/xorg-docs/icccm/icccm.html
+//! [EWMH]: https://specifications.freedesktop.org/wm-spec/latest/
+//!
+//! [windows]: crate::Window
+//!
+//! [`ModifyProperty` request]: crate::x11::request::ModifyProperty
+//! [`GetProperty` reply]: crate::x11::reply::GetProperty
+
+use thiserror::Error;
+use xrbk::{ReadError, Readable, Writable};
+
+use crate::x11::request::{DataFormat, DataList};
+
+pub use ewmh_state::EwmhState;
+pub use wm_hints::{WindowState, WmHints, WmHintsFlags};
+pub use wm_size_hints::{AspectRatio, HintSource, WmSizeHints, WmSizeHintsFlags};
+
+mod ewmh_state;
+mod wm_hints;
+mod wm_size_hints;
+
+/// An error generated when a property's value cannot be decoded.
+#[derive(Error, Debug)]
+pub enum PropertyValueError {
+	/// The value was not of the [`DataFormat`] used by the property.
+	#[error("expected a property value of format {expected:?}, found {found:?}")]
+	WrongFormat {
+		/// The [`DataFormat`] used by the property.
+		expected: DataFormat,
+		/// The [`DataFormat`] of the value.
+		found: DataFormat,
+	},
+
+	/// The value could not be read.
+	#[error(transparent)]
+	Read(#[from] ReadError),
+}
+
+/// A position given in a property, such as an icon position in [`WmHints`].
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HintPosition {
+	/// The x coordinate, measured in pixels.
+	pub x: i32,
+	/// The y coordinate, measured in pixels.
+	pub y: i32,
+}
+
+/// A size given in a property, such as the minimum size in [`WmSizeHints`].
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HintSize {
+	/// The width, measured in pixels.
+	pub width: i32,
+	/// The height, measured in pixels.
+	pub height: i32,
+}
+
+/// Reads a property of format [`DataFormat::I32`] which is encoded in `units`
+/// 32-bit values.
+///
+/// Values written by older clients may be shorter than the current encoding.
+/// They are padded with zeros, so that the fields which they do not contain
+/// are read as absent.
+fn read_property_value<T: Readable>(
+	value: &DataList, units: usize,
+) -> Result<T, PropertyValueError> {
+	let DataList::I32(values) = value else {
+		return Err(PropertyValueError::WrongFormat {
+			expected: DataFormat::I32,
+			found: value.format(),
+		});
+	};
+
+	let mut bytes: Vec<u8> = values
+		.iter()
+		.flat_map(|value| value.to_be_bytes())
+		.collect();
+	if bytes.len() < units * 4 {
+		bytes.resize(units * 4, 0);
+	}
+
+	Ok(T::read_from(&mut &bytes[..])?)
+}
+
+/// Writes a property value as a list of 32-bit values.
+fn write_property_value(value: &impl Writable) -> DataList {
+	let mut bytes = vec![];
+	value
+		.write_to(&mut bytes)
+		.expect("property values are always writable");
+
+	DataList::I32(
+		bytes
+			.chunks_exact(4)
+			.map(|unit| i32::from_be_bytes([unit[0], unit[1], unit[2], unit[3]]))
+			.collect(),
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_wrong_format() {
+		let value = DataList::I8(vec![0; 36]);
+
+		assert!(matches!(
+			WmHints::from_property_value(&value),
+			Err(PropertyValueError::WrongFormat {
+				expected: DataFormat::I32,
+				found: DataFormat::I8,
+			}),
+		));
+	}
+}
@@ -0,0 +1,222 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use bitflags::bitflags;
+
+use super::PropertyValueError;
+use crate::{
+	atom,
+	x11::request::{DataFormat, DataList},
+	Atom,
+	AtomCache,
+};
+
+bitflags! {
+	/// The value of the `_NET_WM_STATE` property, which lists the states of a
+	/// window defined by the [EWMH].
+	///
+	/// The property is a list of [atoms], one for each state, but those
+	/// [atoms] are not predefined: their IDs differ between X servers, so an
+	/// [`AtomCache`] which knows them is needed to encode or decode the
+	/// property.
+	///
+	/// [EWMH]: https://specifications.freedesktop.org/wm-spec/latest/
+	/// [atoms]: Atom
+	#[doc(alias = "_NET_WM_STATE")]
+	#[derive(Default)]
+	pub struct EwmhState: u32 {
+		/// `_NET_WM_STATE_MODAL`: the window is a modal dialog box.
+		const MODAL = 0x0000_0001;
+		/// `_NET_WM_STATE_STICKY`: the window's position is fixed on the
+		/// screen, even when the virtual desktop scrolls.
+		const STICKY = 0x0000_0002;
+		/// `_NET_WM_STATE_MAXIMIZED_VERT`: the window is maximized
+		/// vertically.
+		const MAXIMIZED_VERTICAL = 0x0000_0004;
+		/// `_NET_WM_STATE_MAXIMIZED_HORZ`: the window is maximized
+		/// horizontally.
+		const MAXIMIZED_HORIZONTAL = 0x0000_0008;
+		/// `_NET_WM_STATE_SHADED`: the window is shaded.
+		const SHADED = 0x0000_0010;
+		/// `_NET_WM_STATE_SKIP_TASKBAR`: the window is not shown on a
+		/// taskbar.
+		const SKIP_TASKBAR = 0x0000_0020;
+		/// `_NET_WM_STATE_SKIP_PAGER`: the window is not shown on a pager.
+		const SKIP_PAGER = 0x0000_0040;
+		/// `_NET_WM_STATE_HIDDEN`: the window is minimized.
+		const HIDDEN = 0x0000_0080;
+		/// `_NET_WM_STATE_FULLSCREEN`: the window fills the whole screen,
+		/// without decorations.
+		const FULLSCREEN = 0x0000_0100;
+		/// `_NET_WM_STATE_ABOVE`: the window is shown above most other
+		/// windows.
+		const ABOVE = 0x0000_0200;
+		/// `_NET_WM_STATE_BELOW`: the window is shown below most other
+		/// windows.
+		const BELOW = 0x0000_0400;
+		/// `_NET_WM_STATE_DEMANDS_ATTENTION`: some action in or with the
+		/// window requires the user's attention.
+		const DEMANDS_ATTENTION = 0x0000_0800;
+		/// `_NET_WM_STATE_FOCUSED`: the window has input focus.
+		const FOCUSED = 0x0000_1000;
+	}
+}
+
+impl EwmhState {
+	/// The type of the `_NET_WM_STATE` property.
+	pub const TYPE: Atom = atom::ATOM;
+
+	/// The name of the [atom] representing each state.
+	///
+	/// [atom]: Atom
+	pub const ATOM_NAMES: [(Self, &'static str); 13] = [
+		(Self::MODAL, "_NET_WM_STATE_MODAL"),
+		(Self::STICKY, "_NET_WM_STATE_STICKY"),
+		(Self::MAXIMIZED_VERTICAL, "_NET_WM_STATE_MAXIMIZED_VERT"),
+		(Self::MAXIMIZED_HORIZONTAL, "_NET_WM_STATE_MAXIMIZED_HORZ"),
+		(Self::SHADED, "_NET_WM_STATE_SHADED"),
+		(Self::SKIP_TASKBAR, "_NET_WM_STATE_SKIP_TASKBAR"),
+		(Self::SKIP_PAGER, "_NET_WM_STATE_SKIP_PAGER"),
+		(Self::HIDDEN, "_NET_WM_STATE_HIDDEN"),
+		(Self::FULLSCREEN, "_NET_WM_STATE_FULLSCREEN"),
+		(Self::ABOVE, "_NET_WM_STATE_ABOVE"),
+		(Self::BELOW, "_NET_WM_STATE_BELOW"),
+		(Self::DEMANDS_ATTENTION, "_NET_WM_STATE_DEMANDS_ATTENTION"),
+		(Self::FOCUSED, "_NET_WM_STATE_FOCUSED"),
+	];
+
+	/// Collects the states represented by the given [atoms].
+	///
+	/// [Atoms][atoms] whose names are not known by the `cache`, or which do
+	/// not represent a state, are ignored.
+	///
+	/// [atoms]: Atom
+	#[must_use]
+	pub fn from_atoms(atoms: impl IntoIterator<Item = Atom>, cache: &AtomCache) -> Self {
+		let mut states = Self::empty();
+
+		for atom in atoms {
+			if let Some(name) = cache.name(atom) {
+				for (state, state_name) in Self::ATOM_NAMES {
+					if name == state_name {
+						states |= state;
+					}
+				}
+			}
+		}
+
+		states
+	}
+
+	/// Returns the [atoms] representing these states.
+	///
+	/// # Errors
+	/// Returns the name of the first [atom] which is not known by the
+	/// `cache`. It must be found with [`AtomCache::intern_request`] first.
+	///
+	/// [atom]: Atom
+	/// [atoms]: Atom
+	pub fn to_atoms(self, cache: &AtomCache) -> Result<Vec<Atom>, &'static str> {
+		Self::ATOM_NAMES
+			.into_iter()
+			.filter(|(state, _)| self.contains(*state))
+			.map(|(_, name)| cache.get(name).ok_or(name))
+			.collect()
+	}
+
+	/// Decodes the value of a `_NET_WM_STATE` property.
+	///
+	/// See [`EwmhState::from_atoms`] for more information.
+	///
+	/// # Errors
+	/// Returns a [`PropertyValueError`] if the `value` is not a list of `i32`
+	/// values.
+	pub fn from_property_value(
+		value: &DataList, cache: &AtomCache,
+	) -> Result<Self, PropertyValueError> {
+		let DataList::I32(values) = value else {
+			return Err(PropertyValueError::WrongFormat {
+				expected: DataFormat::I32,
+				found: value.format(),
+			});
+		};
+
+		Ok(Self::from_atoms(
+			values
+				.iter()
+				.map(|value| Atom::new(u32::from_ne_bytes(value.to_ne_bytes()))),
+			cache,
+		))
+	}
+
+	/// Encodes these states as the value of a `_NET_WM_STATE` property.
+	///
+	/// # Errors
+	/// Returns the name of the first [atom] which is not known by the
+	/// `cache`. It must be found with [`AtomCache::intern_request`] first.
+	///
+	/// [atom]: Atom
+	pub fn to_property_value(self, cache: &AtomCache) -> Result<DataList, &'static str> {
+		Ok(DataList::I32(
+			self.to_atoms(cache)?
+				.into_iter()
+				.map(|atom| i32::from_ne_bytes(atom.unwrap().to_ne_bytes()))
+				.collect(),
+		))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::x11::reply;
+
+	/// Returns an [`AtomCache`] in which each state's atom is known, with IDs
+	/// starting from 300.
+	fn cache() -> AtomCache {
+		let mut cache = AtomCache::new();
+
+		for (id, (_, name)) in (300..).zip(EwmhState::ATOM_NAMES) {
+			cache.resolve(
+				name,
+				&reply::GetAtom {
+					sequence: 0,
+					atom: Some(Atom::new(id)),
+				},
+			);
+		}
+
+		cache
+	}
+
+	#[test]
+	fn test_fullscreen_fixture() {
+		// xprop:
+		//     _NET_WM_STATE(ATOM) = _NET_WM_STATE_FULLSCREEN, _NET_WM_STATE_FOCUSED
+		let cache = cache();
+		let value = DataList::I32(vec![308, 312]);
+
+		let state = EwmhState::FULLSCREEN | EwmhState::FOCUSED;
+
+		assert_eq!(
+			EwmhState::from_property_value(&value, &cache).unwrap(),
+			state
+		);
+		assert_eq!(state.to_property_value(&cache).unwrap(), value);
+	}
+
+	#[test]
+	fn test_unknown_atoms() {
+		let cache = cache();
+
+		// `WM_HINTS` is known, but is not a state. `1000` is not known.
+		let atoms = [atom::WM_HINTS, Atom::new(1000), Atom::new(307)];
+		assert_eq!(EwmhState::from_atoms(atoms, &cache), EwmhState::HIDDEN);
+
+		assert_eq!(
+			EwmhState::ABOVE.to_atoms(&AtomCache::new()),
+			Err("_NET_WM_STATE_ABOVE")
+		);
+	}
+}
@@ -0,0 +1,333 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use bitflags::bitflags;
+use xrbk::{
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadError,
+	ReadResult,
+	Readable,
+	Writable,
+	WriteResult,
+	X11Size,
+};
+
+use super::{read_property_value, write_property_value, HintPosition, PropertyValueError};
+use crate::{atom, x11::request::DataList, Atom, Pixmap, Window};
+
+bitflags! {
+	/// Which of the fields of [`WmHints`] are present.
+	///
+	/// These flags are computed from the fields of [`WmHints`] when it is
+	/// written; see [`WmHints::flags`].
+	#[derive(Default)]
+	pub struct WmHintsFlags: u32 {
+		/// Whether [`input`](WmHints::input) is present.
+		const INPUT = 0x0000_0001;
+		/// Whether [`initial_state`](WmHints::initial_state) is present.
+		const INITIAL_STATE = 0x0000_0002;
+		/// Whether [`icon_pixmap`](WmHints::icon_pixmap) is present.
+		const ICON_PIXMAP = 0x0000_0004;
+		/// Whether [`icon_window`](WmHints::icon_window) is present.
+		const ICON_WINDOW = 0x0000_0008;
+		/// Whether [`icon_position`](WmHints::icon_position) is present.
+		const ICON_POSITION = 0x0000_0010;
+		/// Whether [`icon_mask`](WmHints::icon_mask) is present.
+		const ICON_MASK = 0x0000_0020;
+		/// Whether [`window_group`](WmHints::window_group) is present.
+		const WINDOW_GROUP = 0x0000_0040;
+
+		/// Whether the window is [`urgent`](WmHints::urgent).
+		const URGENT = 0x0000_0100;
+	}
+}
+
+/// The state in which a window is requested to be first shown, or its current
+/// state.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowState {
+	/// The window is not mapped, nor iconified.
+	#[doc(alias = "WithdrawnState")]
+	Withdrawn,
+	/// The window is shown normally.
+	#[doc(alias = "NormalState")]
+	Normal,
+	/// The window is iconified.
+	#[doc(alias = "IconicState")]
+	Iconic,
+}
+
+impl TryFrom<u32> for WindowState {
+	type Error = ReadError;
+
+	fn try_from(value: u32) -> Result<Self, Self::Error> {
+		match value {
+			0 => Ok(Self::Withdrawn),
+			1 => Ok(Self::Normal),
+			3 => Ok(Self::Iconic),
+
+			other => Err(ReadError::UnrecognizedDiscriminant {
+				type_name: "WindowState",
+				discriminant: other as usize,
+			}),
+		}
+	}
+}
+
+impl From<WindowState> for u32 {
+	fn from(state: WindowState) -> Self {
+		match state {
+			WindowState::Withdrawn => 0,
+			WindowState::Normal => 1,
+			WindowState::Iconic => 3,
+		}
+	}
+}
+
+/// The value of the `WM_HINTS` property, which describes how a window should
+/// be treated by the window manager other than its size and position.
+///
+/// Every field is optional: only the fields which are present are written,
+/// and the [flags] saying which fields are present are computed when it is
+/// written.
+///
+/// [flags]: WmHints::flags
+#[doc(alias("WM_HINTS", "XWMHints"))]
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WmHints {
+	/// Whether the client relies on the window manager to give the window
+	/// input focus.
+	pub input: Option<bool>,
+	/// The state in which the window is first shown when it is mapped.
+	pub initial_state: Option<WindowState>,
+
+	/// A [pixmap] to display as the window's icon.
+	///
+	/// [pixmap]: Pixmap
+	pub icon_pixmap: Option<Pixmap>,
+	/// A [window] to display as the window's icon.
+	///
+	/// [window]: Window
+	pub icon_window: Option<Window>,
+	/// The position at which the window's icon is requested to be shown.
+	pub icon_position: Option<HintPosition>,
+	/// A [pixmap] which masks the shape of the `icon_pixmap`.
+	///
+	/// [pixmap]: Pixmap
+	pub icon_mask: Option<Pixmap>,
+
+	/// The leader of the group of [windows] to which the window belongs.
+	///
+	/// [windows]: Window
+	#[doc(alias = "group_leader")]
+	pub window_group: Option<Window>,
+
+	/// Whether the contents of the window require the urgent attention of
+	/// the user.
+	pub urgent: bool,
+}
+
+impl WmHints {
+	/// The type of the `WM_HINTS` property.
+	pub const TYPE: Atom = atom::WM_HINTS;
+
+	/// The number of 32-bit values in the `WM_HINTS` property.
+	const UNITS: usize = 9;
+
+	/// Returns the [`WmHintsFlags`] saying which fields are present.
+	#[must_use]
+	pub fn flags(&self) -> WmHintsFlags {
+		let mut flags = WmHintsFlags::empty();
+
+		flags.set(WmHintsFlags::INPUT, self.input.is_some());
+		flags.set(WmHintsFlags::INITIAL_STATE, self.initial_state.is_some());
+		flags.set(WmHintsFlags::ICON_PIXMAP, self.icon_pixmap.is_some());
+		flags.set(WmHintsFlags::ICON_WINDOW, self.icon_window.is_some());
+		flags.set(WmHintsFlags::ICON_POSITION, self.icon_position.is_some());
+		flags.set(WmHintsFlags::ICON_MASK, self.icon_mask.is_some());
+		flags.set(WmHintsFlags::WINDOW_GROUP, self.window_group.is_some());
+		flags.set(WmHintsFlags::URGENT, self.urgent);
+
+		flags
+	}
+
+	/// Decodes the value of a `WM_HINTS` property.
+	///
+	/// # Errors
+	/// Returns a [`PropertyValueError`] if the `value` is not a list of `i32`
+	/// values or contains an unrecognized [`WindowState`].
+	pub fn from_property_value(value: &DataList) -> Result<Self, PropertyValueError> {
+		read_property_value(value, Self::UNITS)
+	}
+
+	/// Encodes these hints as the value of a `WM_HINTS` property.
+	#[must_use]
+	pub fn to_property_value(&self) -> DataList {
+		write_property_value(self)
+	}
+}
+
+impl ConstantX11Size for WmHints {
+	const X11_SIZE: usize = Self::UNITS * 4;
+}
+
+impl X11Size for WmHints {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl Readable for WmHints {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		xrbk::ensure_remaining(buf, Self::X11_SIZE)?;
+
+		let flags = WmHintsFlags::from_bits_truncate(u32::read_from(buf)?);
+
+		let input = u32::read_from(buf)?;
+		let initial_state = u32::read_from(buf)?;
+		let icon_pixmap = Pixmap::read_from(buf)?;
+		let icon_window = Window::read_from(buf)?;
+		let icon_position = HintPosition {
+			x: i32::read_from(buf)?,
+			y: i32::read_from(buf)?,
+		};
+		let icon_mask = Pixmap::read_from(buf)?;
+		let window_group = Window::read_from(buf)?;
+
+		Ok(Self {
+			input: flags.contains(WmHintsFlags::INPUT).then_some(input != 0),
+			initial_state: if flags.contains(WmHintsFlags::INITIAL_STATE) {
+				Some(WindowState::try_from(initial_state)?)
+			} else {
+				None
+			},
+
+			icon_pixmap: flags
+				.contains(WmHintsFlags::ICON_PIXMAP)
+				.then_some(icon_pixmap),
+			icon_window: flags
+				.contains(WmHintsFlags::ICON_WINDOW)
+				.then_some(icon_window),
+			icon_position: flags
+				.contains(WmHintsFlags::ICON_POSITION)
+				.then_some(icon_position),
+			icon_mask: flags.contains(WmHintsFlags::ICON_MASK).then_some(icon_mask),
+
+			window_group: flags
+				.contains(WmHintsFlags::WINDOW_GROUP)
+				.then_some(window_group),
+
+			urgent: flags.contains(WmHintsFlags::URGENT),
+		})
+	}
+}
+
+impl Writable for WmHints {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		// Fields which are not present are written as zero.
+		let icon_position = self.icon_position.unwrap_or_default();
+
+		self.flags().bits().write_to(buf)?;
+
+		u32::from(self.input.unwrap_or_default()).write_to(buf)?;
+		self.initial_state.map_or(0, u32::from).write_to(buf)?;
+		self.icon_pixmap.map_or(0, Pixmap::unwrap).write_to(buf)?;
+		self.icon_window.map_or(0, Window::unwrap).write_to(buf)?;
+		icon_position.x.write_to(buf)?;
+		icon_position.y.write_to(buf)?;
+		self.icon_mask.map_or(0, Pixmap::unwrap).write_to(buf)?;
+		self.window_group.map_or(0, Window::unwrap).write_to(buf)?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Converts the given 32-bit values to a property value.
+	fn property_value(units: &[u32]) -> DataList {
+		DataList::I32(
+			units
+				.iter()
+				.map(|unit| i32::from_ne_bytes(unit.to_ne_bytes()))
+				.collect(),
+		)
+	}
+
+	#[test]
+	fn test_group_leader_fixture() {
+		// xprop:
+		//     Client accepts input or input focus: True
+		//     Initial state is Normal State.
+		//     window id # of group leader: 0x1400006
+		let value = property_value(&[0x43, 1, 1, 0, 0, 0, 0, 0, 0x0140_0006]);
+
+		let hints = WmHints {
+			input: Some(true),
+			initial_state: Some(WindowState::Normal),
+			window_group: Some(Window::new(0x0140_0006)),
+			..WmHints::default()
+		};
+
+		assert_eq!(WmHints::from_property_value(&value).unwrap(), hints);
+		assert_eq!(hints.to_property_value(), value);
+	}
+
+	#[test]
+	fn test_urgent_icon_fixture() {
+		// xprop:
+		//     Client accepts input or input focus: False
+		//     bitmap id # to use for icon: 0x2600012
+		//     bitmap id # of mask for icon: 0x2600014
+		//     window id # of group leader: 0x2600001
+		//     The urgency hint bit is set
+		let value = property_value(&[0x165, 0, 0, 0x0260_0012, 0, 0, 0, 0x0260_0014, 0x0260_0001]);
+
+		let hints = WmHints {
+			input: Some(false),
+			icon_pixmap: Some(Pixmap::new(0x0260_0012)),
+			icon_mask: Some(Pixmap::new(0x0260_0014)),
+			window_group: Some(Window::new(0x0260_0001)),
+			urgent: true,
+			..WmHints::default()
+		};
+
+		assert_eq!(WmHints::from_property_value(&value).unwrap(), hints);
+		assert_eq!(hints.to_property_value(), value);
+	}
+
+	#[test]
+	fn test_fields_without_flags_are_ignored() {
+		// Only `INITIAL_STATE` is set, so every other field is ignored.
+		let value = property_value(&[0x02, 1, 3, 5, 6, 7, 8, 9, 10]);
+
+		let hints = WmHints::from_property_value(&value).unwrap();
+
+		assert_eq!(
+			hints,
+			WmHints {
+				initial_state: Some(WindowState::Iconic),
+				..WmHints::default()
+			}
+		);
+		assert_eq!(
+			hints.to_property_value(),
+			property_value(&[0x02, 0, 3, 0, 0, 0, 0, 0, 0])
+		);
+	}
+
+	#[test]
+	fn test_unrecognized_initial_state() {
+		let value = property_value(&[0x02, 0, 2, 0, 0, 0, 0, 0, 0]);
+
+		assert!(WmHints::from_property_value(&value).is_err());
+	}
+}
@@ -0,0 +1,482 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use bitflags::bitflags;
+use xrbk::{
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadError,
+	ReadResult,
+	Readable,
+	Writable,
+	WriteResult,
+	X11Size,
+};
+
+use super::{
+	read_property_value,
+	write_property_value,
+	HintPosition,
+	HintSize,
+	PropertyValueError,
+};
+use crate::{atom, x11::request::DataList, Atom, WindowGravity};
+
+bitflags! {
+	/// Which of the fields of [`WmSizeHints`] are present.
+	///
+	/// These flags are computed from the fields of [`WmSizeHints`] when it is
+	/// written; see [`WmSizeHints::flags`].
+	#[derive(Default)]
+	pub struct WmSizeHintsFlags: u32 {
+		/// Whether the [`position`](WmSizeHints::position) was specified by
+		/// the user.
+		#[doc(alias = "USPosition")]
+		const USER_POSITION = 0x0000_0001;
+		/// Whether the [`size`](WmSizeHints::size) was specified by the user.
+		#[doc(alias = "USSize")]
+		const USER_SIZE = 0x0000_0002;
+		/// Whether the [`position`](WmSizeHints::position) was specified by
+		/// the program.
+		#[doc(alias = "PPosition")]
+		const PROGRAM_POSITION = 0x0000_0004;
+		/// Whether the [`size`](WmSizeHints::size) was specified by the
+		/// program.
+		#[doc(alias = "PSize")]
+		const PROGRAM_SIZE = 0x0000_0008;
+
+		/// Whether [`min_size`](WmSizeHints::min_size) is present.
+		const MIN_SIZE = 0x0000_0010;
+		/// Whether [`max_size`](WmSizeHints::max_size) is present.
+		const MAX_SIZE = 0x0000_0020;
+		/// Whether [`size_increment`](WmSizeHints::size_increment) is present.
+		const SIZE_INCREMENT = 0x0000_0040;
+		/// Whether [`aspect_ratios`](WmSizeHints::aspect_ratios) is present.
+		const ASPECT_RATIOS = 0x0000_0080;
+		/// Whether [`base_size`](WmSizeHints::base_size) is present.
+		const BASE_SIZE = 0x0000_0100;
+		/// Whether [`gravity`](WmSizeHints::gravity) is present.
+		const GRAVITY = 0x0000_0200;
+	}
+}
+
+/// Whether a position or size in [`WmSizeHints`] was specified by the user or
+/// by the program.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HintSource {
+	/// The value was specified by the user, and should be respected.
+	User,
+	/// The value was chosen by the program.
+	Program,
+}
+
+/// An aspect ratio of a window's width to its height.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AspectRatio {
+	/// The width part of the ratio.
+	pub numerator: i32,
+	/// The height part of the ratio.
+	pub denominator: i32,
+}
+
+/// The value of the `WM_NORMAL_HINTS` property, which describes the sizes a
+/// window may be given.
+///
+/// Every field is optional: only the fields which are present are written,
+/// and the [flags] saying which fields are present are computed when it is
+/// written.
+///
+/// [flags]: WmSizeHints::flags
+#[doc(alias("WM_NORMAL_HINTS", "WM_SIZE_HINTS", "XSizeHints"))]
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WmSizeHints {
+	/// The position of the window, and whether it was specified by the user
+	/// or the program.
+	///
+	/// The position and size themselves are obsolete: window managers use
+	/// the window's actual geometry instead. Only whether they were specified
+	/// by the user or the program is still meaningful. If both flags are set
+	/// when read, the source is [`HintSource::User`].
+	pub position: Option<(HintSource, HintPosition)>,
+	/// The size of the window, and whether it was specified by the user or
+	/// the program.
+	///
+	/// See [`position`](WmSizeHints::position) for more information.
+	pub size: Option<(HintSource, HintSize)>,
+
+	/// The minimum size of the window.
+	pub min_size: Option<HintSize>,
+	/// The maximum size of the window.
+	pub max_size: Option<HintSize>,
+	/// The increments by which the size of the window may change, starting
+	/// from the `base_size`.
+	#[doc(alias = "resize_increment")]
+	pub size_increment: Option<HintSize>,
+	/// The minimum and maximum aspect ratios of the window, in that order.
+	pub aspect_ratios: Option<(AspectRatio, AspectRatio)>,
+	/// The size from which the `size_increment`s are counted.
+	pub base_size: Option<HintSize>,
+
+	/// How the window should be moved when its size is changed by the window
+	/// manager.
+	#[doc(alias = "win_gravity")]
+	pub gravity: Option<WindowGravity>,
+}
+
+impl WmSizeHints {
+	/// The type of the `WM_NORMAL_HINTS` property.
+	pub const TYPE: Atom = atom::WM_SIZE_HINTS;
+
+	/// The number of 32-bit values in the `WM_NORMAL_HINTS` property.
+	///
+	/// Clients written before `base_size` and `gravity` were added write only
+	/// the first 15.
+	const UNITS: usize = 18;
+
+	/// Returns the [`WmSizeHintsFlags`] saying which fields are present.
+	#[must_use]
+	pub fn flags(&self) -> WmSizeHintsFlags {
+		let mut flags = WmSizeHintsFlags::empty();
+
+		match self.position {
+			Some((HintSource::User, _)) => flags |= WmSizeHintsFlags::USER_POSITION,
+			Some((HintSource::Program, _)) => flags |= WmSizeHintsFlags::PROGRAM_POSITION,
+			None => {},
+		}
+		match self.size {
+			Some((HintSource::User, _)) => flags |= WmSizeHintsFlags::USER_SIZE,
+			Some((HintSource::Program, _)) => flags |= WmSizeHintsFlags::PROGRAM_SIZE,
+			None => {},
+		}
+
+		flags.set(WmSizeHintsFlags::MIN_SIZE, self.min_size.is_some());
+		flags.set(WmSizeHintsFlags::MAX_SIZE, self.max_size.is_some());
+		flags.set(
+			WmSizeHintsFlags::SIZE_INCREMENT,
+			self.size_increment.is_some(),
+		);
+		flags.set(
+			WmSizeHintsFlags::ASPECT_RATIOS,
+			self.aspect_ratios.is_some(),
+		);
+		flags.set(WmSizeHintsFlags::BASE_SIZE, self.base_size.is_some());
+		flags.set(WmSizeHintsFlags::GRAVITY, self.gravity.is_some());
+
+		flags
+	}
+
+	/// Decodes the value of a `WM_NORMAL_HINTS` property.
+	///
+	/// # Errors
+	/// Returns a [`PropertyValueError`] if the `value` is not a list of `i32`
+	/// values or contains an unrecognized [`WindowGravity`].
+	pub fn from_property_value(value: &DataList) -> Result<Self, PropertyValueError> {
+		read_property_value(value, Self::UNITS)
+	}
+
+	/// Encodes these hints as the value of a `WM_NORMAL_HINTS` property.
+	#[must_use]
+	pub fn to_property_value(&self) -> DataList {
+		write_property_value(self)
+	}
+}
+
+/// Returns the [`HintSource`] given by the `user` and `program` flags.
+const fn hint_source(
+	flags: WmSizeHintsFlags, user: WmSizeHintsFlags, program: WmSizeHintsFlags,
+) -> Option<HintSource> {
+	if flags.contains(user) {
+		Some(HintSource::User)
+	} else if flags.contains(program) {
+		Some(HintSource::Program)
+	} else {
+		None
+	}
+}
+
+/// Reads a [`HintSize`].
+fn read_size(buf: &mut impl Buf) -> ReadResult<HintSize> {
+	Ok(HintSize {
+		width: i32::read_from(buf)?,
+		height: i32::read_from(buf)?,
+	})
+}
+
+/// Writes the given [`HintSize`], or zeros if it is [`None`].
+fn write_size(size: Option<HintSize>, buf: &mut impl BufMut) -> WriteResult {
+	let size = size.unwrap_or_default();
+
+	size.width.write_to(buf)?;
+	size.height.write_to(buf)?;
+
+	Ok(())
+}
+
+/// Reads an [`AspectRatio`].
+fn read_aspect_ratio(buf: &mut impl Buf) -> ReadResult<AspectRatio> {
+	Ok(AspectRatio {
+		numerator: i32::read_from(buf)?,
+		denominator: i32::read_from(buf)?,
+	})
+}
+
+impl ConstantX11Size for WmSizeHints {
+	const X11_SIZE: usize = Self::UNITS * 4;
+}
+
+impl X11Size for WmSizeHints {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl Readable for WmSizeHints {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		xrbk::ensure_remaining(buf, Self::X11_SIZE)?;
+
+		let flags = WmSizeHintsFlags::from_bits_truncate(u32::read_from(buf)?);
+
+		let position = HintPosition {
+			x: i32::read_from(buf)?,
+			y: i32::read_from(buf)?,
+		};
+		let size = read_size(buf)?;
+
+		let min_size = read_size(buf)?;
+		let max_size = read_size(buf)?;
+		let size_increment = read_size(buf)?;
+		let min_aspect_ratio = read_aspect_ratio(buf)?;
+		let max_aspect_ratio = read_aspect_ratio(buf)?;
+		let base_size = read_size(buf)?;
+
+		let gravity = u32::read_from(buf)?;
+
+		Ok(Self {
+			position: hint_source(
+				flags,
+				WmSizeHintsFlags::USER_POSITION,
+				WmSizeHintsFlags::PROGRAM_POSITION,
+			)
+			.map(|source| (source, position)),
+			size: hint_source(
+				flags,
+				WmSizeHintsFlags::USER_SIZE,
+				WmSizeHintsFlags::PROGRAM_SIZE,
+			)
+			.map(|source| (source, size)),
+
+			min_size: flags
+				.contains(WmSizeHintsFlags::MIN_SIZE)
+				.then_some(min_size),
+			max_size: flags
+				.contains(WmSizeHintsFlags::MAX_SIZE)
+				.then_some(max_size),
+			size_increment: flags
+				.contains(WmSizeHintsFlags::SIZE_INCREMENT)
+				.then_some(size_increment),
+			aspect_ratios: flags
+				.contains(WmSizeHintsFlags::ASPECT_RATIOS)
+				.then_some((min_aspect_ratio, max_aspect_ratio)),
+			base_size: flags
+				.contains(WmSizeHintsFlags::BASE_SIZE)
+				.then_some(base_size),
+
+			gravity: if flags.contains(WmSizeHintsFlags::GRAVITY) {
+				// The gravity is a 32-bit value, but `WindowGravity` is read
+				// from a single byte.
+				match u8::try_from(gravity) {
+					Ok(gravity) => Some(WindowGravity::read_from(&mut &[gravity][..])?),
+
+					Err(_) => {
+						return Err(ReadError::UnrecognizedDiscriminant {
+							type_name: "WindowGravity",
+							discriminant: gravity as usize,
+						})
+					},
+				}
+			} else {
+				None
+			},
+		})
+	}
+}
+
+impl Writable for WmSizeHints {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		// Fields which are not present are written as zero.
+		let position = self
+			.position
+			.map(|(_, position)| position)
+			.unwrap_or_default();
+		let no_aspect_ratio = AspectRatio {
+			numerator: 0,
+			denominator: 0,
+		};
+		let (min_aspect_ratio, max_aspect_ratio) = self
+			.aspect_ratios
+			.unwrap_or((no_aspect_ratio, no_aspect_ratio));
+
+		self.flags().bits().write_to(buf)?;
+
+		position.x.write_to(buf)?;
+		position.y.write_to(buf)?;
+		write_size(self.size.map(|(_, size)| size), buf)?;
+
+		write_size(self.min_size, buf)?;
+		write_size(self.max_size, buf)?;
+		write_size(self.size_increment, buf)?;
+		min_aspect_ratio.numerator.write_to(buf)?;
+		min_aspect_ratio.denominator.write_to(buf)?;
+		max_aspect_ratio.numerator.write_to(buf)?;
+		max_aspect_ratio.denominator.write_to(buf)?;
+		write_size(self.base_size, buf)?;
+
+		match self.gravity {
+			Some(gravity) => {
+				let mut byte = vec![];
+				gravity.write_to(&mut byte)?;
+
+				u32::from(byte[0]).write_to(buf)?;
+			},
+
+			None => 0_u32.write_to(buf)?,
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// Converts the given 32-bit values to a property value.
+	fn property_value(units: &[i32]) -> DataList {
+		DataList::I32(units.to_vec())
+	}
+
+	#[test]
+	fn test_xterm_fixture() {
+		// xprop:
+		//     user specified size: 484 by 316
+		//     program specified minimum size: 10 by 17
+		//     program specified resize increment: 6 by 13
+		//     program specified base size: 4 by 4
+		//     window gravity: NorthWest
+		#[rustfmt::skip]
+		let value = property_value(&[
+			0x352, // flags
+			0, 0, 484, 316, // position, size
+			10, 17, // min_size
+			0, 0, // max_size
+			6, 13, // size_increment
+			0, 0, 0, 0, // aspect_ratios
+			4, 4, // base_size
+			1, // gravity
+		]);
+
+		let hints = WmSizeHints {
+			size: Some((
+				HintSource::User,
+				HintSize {
+					width: 484,
+					height: 316,
+				},
+			)),
+			min_size: Some(HintSize {
+				width: 10,
+				height: 17,
+			}),
+			size_increment: Some(HintSize {
+				width: 6,
+				height: 13,
+			}),
+			base_size: Some(HintSize {
+				width: 4,
+				height: 4,
+			}),
+			gravity: Some(WindowGravity::NorthWest),
+			..WmSizeHints::default()
+		};
+
+		assert_eq!(WmSizeHints::from_property_value(&value).unwrap(), hints);
+		assert_eq!(hints.to_property_value(), value);
+	}
+
+	#[test]
+	fn test_fixed_aspect_fixture() {
+		// xprop:
+		//     program specified location: 0, 0
+		//     program specified minimum size: 320 by 180
+		//     program specified maximum size: 3840 by 2160
+		//     program specified minimum aspect ratio: 16/9
+		//     program specified maximum aspect ratio: 16/9
+		//     window gravity: Static
+		#[rustfmt::skip]
+		let value = property_value(&[
+			0x2b4, // flags
+			0, 0, 0, 0, // position, size
+			320, 180, // min_size
+			3840, 2160, // max_size
+			0, 0, // size_increment
+			16, 9, 16, 9, // aspect_ratios
+			0, 0, // base_size
+			10, // gravity
+		]);
+
+		let ratio = AspectRatio {
+			numerator: 16,
+			denominator: 9,
+		};
+		let hints = WmSizeHints {
+			position: Some((HintSource::Program, HintPosition::default())),
+			min_size: Some(HintSize {
+				width: 320,
+				height: 180,
+			}),
+			max_size: Some(HintSize {
+				width: 3840,
+				height: 2160,
+			}),
+			aspect_ratios: Some((ratio, ratio)),
+			gravity: Some(WindowGravity::Static),
+			..WmSizeHints::default()
+		};
+
+		assert_eq!(WmSizeHints::from_property_value(&value).unwrap(), hints);
+		assert_eq!(hints.to_property_value(), value);
+	}
+
+	#[test]
+	fn test_pre_icccm_1_value() {
+		// Only 15 values, without `base_size` and `gravity`.
+		let value = property_value(&[0x10, 0, 0, 0, 0, 100, 50, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+		assert_eq!(
+			WmSizeHints::from_property_value(&value).unwrap(),
+			WmSizeHints {
+				min_size: Some(HintSize {
+					width: 100,
+					height: 50,
+				}),
+				..WmSizeHints::default()
+			}
+		);
+	}
+
+	#[test]
+	fn test_unrecognized_gravity() {
+		let mut units = [0; 18];
+		units[0] = 0x200;
+		units[17] = 11;
+
+		assert!(WmSizeHints::from_property_value(&property_value(&units)).is_err());
+	}
+}
@@ -6,6 +6,7 @@
 
 use std::{
 	cmp::Ordering,
+	convert::Infallible,
 	fmt::{Display, Formatter},
 };
 
@@ -111,6 +112,8 @@ macro_rules! impl_xrbk_traits {
 	RemAssign,
 	Sum,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Px<Num>(pub Num);
 
 impl<Num> Px<Num> {
@@ -159,6 +162,8 @@ impl_xrbk_traits!(Px<Num>(Num));
 	RemAssign,
 	Sum,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mm<Num>(pub Num);
 
 impl<Num> Mm<Num> {
@@ -207,6 +212,8 @@ impl_xrbk_traits!(Mm<Num>(Num));
 	RemAssign,
 	Sum,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ms<Num>(pub Num);
 
 impl<Num> Ms<Num> {
@@ -255,6 +262,8 @@ impl_xrbk_traits!(Ms<Num>(Num));
 	RemAssign,
 	Sum,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sec<Num>(pub Num);
 
 impl<Num> Sec<Num> {
@@ -282,10 +291,14 @@ where
 impl<Num> Wrap for Sec<Num>
 where
 	Num: Copy + Clone + Readable + Writable + ConstantX11Size + From<Self> + TryFrom<u64>,
-	Self: TryFrom<Num>,
 	u64: From<Num>,
 {
 	type Integer = Num;
+	type Error = Infallible;
+
+	fn try_wrap(num: Num) -> Result<Self, Infallible> {
+		Ok(Self(num))
+	}
 }
 
 impl<Num> From<Num> for Sec<Num> {
@@ -324,6 +337,8 @@ impl_xrbk_traits!(Sec<Num>(Num));
 	RemAssign,
 	Sum,
 )]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hz<Num>(pub Num);
 
 impl<Num> Hz<Num> {
@@ -352,6 +367,7 @@ impl_xrbk_traits!(Hz<Num>(Num));
 
 /// A value measured as a percentage from 0% to 100%.
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Percentage(u8);
 
 impl Percentage {
@@ -402,6 +418,17 @@ impl Percentage {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Percentage {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The bounds are checked with `new`.
+		Self::new(u8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 impl PartialEq<u8> for Percentage {
 	fn eq(&self, other: &u8) -> bool {
 		self.0 == *other
@@ -430,6 +457,7 @@ impl_xrbk_traits!(Percentage(u8));
 
 /// A value measured as a percentage from -100% to 100%.
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SignedPercentage(i8);
 
 impl SignedPercentage {
@@ -482,6 +510,17 @@ impl SignedPercentage {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SignedPercentage {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The bounds are checked with `new`.
+		Self::new(i8::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 impl PartialEq<i8> for SignedPercentage {
 	fn eq(&self, other: &i8) -> bool {
 		self.0 == *other
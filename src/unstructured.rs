@@ -0,0 +1,400 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Generation of arbitrary messages from unstructured bytes, enabled by the
+//! `arbitrary` feature.
+//!
+//! [`Arbitrary`] is implemented here for the leaf types whose values are
+//! restricted by the protocol, respecting the same restrictions as the
+//! property-based round-trip tests. Types without such restrictions derive it
+//! where they are defined, with
+//! `#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]`, and
+//! messages defined with `derive_xrb!` implement it when they derive both
+//! `Readable` and `Writable`.
+
+use std::ops::RangeInclusive;
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+	set::{
+		Attributes,
+		AttributesBuilder,
+		DurationOrDefault,
+		GraphicsOptions,
+		GraphicsOptionsBuilder,
+		GraphicsOptionsMask,
+		KeyboardOptions,
+		KeyboardOptionsBuilder,
+		Led,
+		LedMode,
+		LineWidth,
+		PercentOrDefault,
+		PitchOrDefault,
+		WindowConfig,
+		WindowConfigBuilder,
+		WindowConfigMask,
+	},
+	unit::{Percentage, SignedPercentage},
+	visual::VisualId,
+	x11::{
+		event::EnterLeaveMask,
+		reply::{GetProperty, KeyMapping},
+		request::{BitPlane, DataList, Delay},
+	},
+	AnyModifierKeyMask,
+	AsciiString,
+	Atom,
+	Button,
+	Char8,
+	ColorChannelMask,
+	Colormap,
+	CursorAppearance,
+	CursorEventMask,
+	DeviceEventMask,
+	Drawable,
+	EventMask,
+	Font,
+	Fontable,
+	GraphicsContext,
+	HostAddress,
+	HostFamily,
+	Keycode,
+	Keysym,
+	LengthString8,
+	ModifierKeyMask,
+	ModifierMap,
+	ModifierMask,
+	Pixmap,
+	String8,
+	Timestamp,
+	Window,
+};
+
+/// Implements [`Arbitrary`] for types constructed with their `new` function
+/// from a value in the given range.
+macro_rules! arbitrary_new {
+	($($Type:ty: $range:expr;)+) => {
+		$(
+			impl<'a> Arbitrary<'a> for $Type {
+				fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+					u.int_in_range($range).map(Self::new)
+				}
+			}
+		)+
+	};
+}
+
+/// Implements [`Arbitrary`] for masks, never setting bits which are not
+/// defined for them.
+macro_rules! arbitrary_masks {
+	($($Mask:ty: $Bits:ty),+$(,)?) => {
+		$(
+			impl<'a> Arbitrary<'a> for $Mask {
+				fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+					u.arbitrary::<$Bits>().map(Self::from_bits_truncate)
+				}
+			}
+		)+
+	};
+}
+
+/// Implements [`Arbitrary`] for types whose values are checked by their `new`
+/// function, constructing them from a value in the given range.
+macro_rules! arbitrary_checked {
+	($($Type:ty: $range:expr;)+) => {
+		$(
+			impl<'a> Arbitrary<'a> for $Type {
+				fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+					Ok(Self::new(u.int_in_range($range)?).unwrap())
+				}
+			}
+		)+
+	};
+}
+
+/// Implements [`Arbitrary`] for sets, setting each of the given options with
+/// their builder if an arbitrary `Option` is `Some`.
+macro_rules! arbitrary_sets {
+	($($Set:ty: $Builder:ty { $($option:ident),+$(,)? })+) => {
+		$(
+			impl<'a> Arbitrary<'a> for $Set {
+				fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+					let mut builder = <$Builder>::new();
+
+					$(
+						if let Some(value) = u.arbitrary()? {
+							builder.$option(value);
+						}
+					)+
+
+					Ok(builder.build())
+				}
+			}
+		)+
+	};
+}
+
+/// The range of valid resource IDs.
+const RES_IDS: RangeInclusive<u32> = 2..=0x1fff_ffff;
+
+arbitrary_new! {
+	Drawable: RES_IDS;
+	Window: RES_IDS;
+	Pixmap: RES_IDS;
+	CursorAppearance: RES_IDS;
+	Fontable: RES_IDS;
+	Font: RES_IDS;
+	GraphicsContext: RES_IDS;
+	Colormap: RES_IDS;
+	VisualId: RES_IDS;
+
+	Atom: 1..=0x1fff_ffff_u32;
+
+	Keycode: 8..=u8::MAX;
+	Button: 1..=u8::MAX;
+	Keysym: 0..=u32::MAX;
+	LineWidth: 0..=u16::MAX;
+	Timestamp: 1..=u32::MAX;
+}
+
+arbitrary_masks!(
+	EventMask: u32,
+	CursorEventMask: u16,
+	DeviceEventMask: u32,
+	ModifierMask: u16,
+	ModifierKeyMask: u16,
+	AnyModifierKeyMask: u16,
+	ColorChannelMask: u8,
+	EnterLeaveMask: u8,
+	GraphicsOptionsMask: u32,
+	WindowConfigMask: u16,
+);
+
+arbitrary_checked! {
+	Percentage: 0..=100_u8;
+	SignedPercentage: -100..=100_i8;
+	PercentOrDefault: -1..=100_i8;
+	PitchOrDefault: -1..=i16::from(u8::MAX);
+	DurationOrDefault: -1..=i16::from(u8::MAX);
+	Led: 1..=32_u8;
+	Delay: -1..=i16::MAX;
+}
+
+arbitrary_sets! {
+	Attributes: AttributesBuilder {
+		background_pixmap,
+		background_color,
+		border_pixmap,
+		border_color,
+		bit_gravity,
+		window_gravity,
+		maintain_contents,
+		maintained_planes,
+		maintenance_fallback_color,
+		override_redirect,
+		maintain_windows_under,
+		event_mask,
+		do_not_propagate_mask,
+		colormap,
+		cursor_appearance,
+	}
+
+	GraphicsOptions: GraphicsOptionsBuilder {
+		function,
+		plane_mask,
+		foreground_color,
+		background_color,
+		line_width,
+		line_style,
+		cap_style,
+		join_style,
+		fill_style,
+		fill_rule,
+		tile,
+		stipple,
+		tile_stipple_x,
+		tile_stipple_y,
+		font,
+		child_mode,
+		graphics_exposure,
+		clip_x,
+		clip_y,
+		clip_mask,
+		dash_offset,
+		dashes,
+		arc_mode,
+	}
+
+	KeyboardOptions: KeyboardOptionsBuilder {
+		key_click_volume,
+		bell_volume,
+		bell_pitch,
+		bell_duration,
+		led,
+		led_mode,
+		auto_repeated_key,
+		auto_repeat_mode,
+	}
+
+	WindowConfig: WindowConfigBuilder {
+		x,
+		y,
+		width,
+		height,
+		border_width,
+		sibling,
+		stack_mode,
+	}
+}
+
+impl<'a> Arbitrary<'a> for BitPlane {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(Self::nth(u.int_in_range(0..=31)?).unwrap())
+	}
+}
+
+impl<'a> Arbitrary<'a> for ModifierMap {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		// Zero and repeated keycodes are removed by `ModifierMap::from`.
+		u.arbitrary::<[Vec<Keycode>; 8]>().map(Self::from)
+	}
+}
+
+impl<'a> Arbitrary<'a> for LedMode {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(if u.arbitrary()? { Self::On } else { Self::Off })
+	}
+}
+
+/// Generates a [`String8`] with a length in the given range.
+fn string8s(u: &mut Unstructured, lens: RangeInclusive<usize>) -> Result<String8> {
+	let len = u.arbitrary_len::<Char8>()?.clamp(*lens.start(), *lens.end());
+	let string = std::iter::repeat_with(|| u.arbitrary())
+		.take(len)
+		.collect::<Result<Vec<Char8>>>()?;
+
+	Ok(String8::from(string))
+}
+
+impl<'a> Arbitrary<'a> for LengthString8 {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		// The length of a `LengthString8` is written as a single byte.
+		Ok(Self::try_from(string8s(u, 0..=Self::MAX_LEN)?).unwrap())
+	}
+}
+
+impl<'a> Arbitrary<'a> for AsciiString {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		// `0` is excluded, as it is used to separate the two `AsciiString`s in
+		// a `HostAddress::ServerInterpreted`.
+		let len = u.arbitrary_len::<u8>()?;
+		let string = std::iter::repeat_with(|| u.int_in_range(1..=0x7f_u8))
+			.take(len)
+			.collect::<Result<_>>()?;
+
+		Ok(Self::new(string).unwrap())
+	}
+}
+
+impl<'a> Arbitrary<'a> for HostAddress {
+	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+		Ok(match u.choose_index(6)? {
+			0 => Self::Ipv4(u.arbitrary()?),
+			1 => Self::DecNet(u.arbitrary()?),
+			2 => Self::Chaos(u.arbitrary()?),
+			3 => Self::ServerInterpreted {
+				address_type: u.arbitrary()?,
+				address_value: u.arbitrary()?,
+			},
+			4 => Self::Ipv6(u.arbitrary()?),
+
+			_ => {
+				// An `Unknown` address must not use the code of a known
+				// family, or it would be read as an address of that family.
+				let family = u.arbitrary()?;
+
+				if !matches!(HostFamily::from(family), HostFamily::Unknown(_)) {
+					return Err(arbitrary::Error::IncorrectFormat);
+				}
+
+				Self::Unknown {
+					family,
+					address: u.arbitrary()?,
+				}
+			},
+		})
+	}
+}
+
+/// Generates a non-empty range of [`Keycode`]s.
+pub fn keycode_ranges(u: &mut Unstructured) -> Result<RangeInclusive<Keycode>> {
+	let (first, second): (Keycode, Keycode) = u.arbitrary()?;
+
+	Ok(if first.unwrap() <= second.unwrap() {
+		first..=second
+	} else {
+		second..=first
+	})
+}
+
+/// Generates a list of [`KeyMapping`]s which each have the same number of
+/// [`Keysym`]s, as is required by the protocol.
+pub fn key_mappings(u: &mut Unstructured) -> Result<Vec<KeyMapping>> {
+	let keysyms_per_keycode = u.int_in_range(1..=8)?;
+	let len = u.arbitrary_len::<[Keysym; 8]>()?;
+
+	std::iter::repeat_with(|| {
+		std::iter::repeat_with(|| u.arbitrary())
+			.take(keysyms_per_keycode)
+			.collect()
+	})
+	.take(len)
+	.collect()
+}
+
+/// Generates a non-empty [`String8`] whose length can be written as a single
+/// byte.
+pub fn non_empty_string8s(u: &mut Unstructured) -> Result<String8> {
+	string8s(u, 1..=LengthString8::MAX_LEN)
+}
+
+/// Generates a list of bytes with a length that is a multiple of `4`.
+///
+/// Padding cannot be told apart from the data it follows if the length of that
+/// data is not otherwise given.
+pub fn aligned_bytes(u: &mut Unstructured) -> Result<Vec<u8>> {
+	let words: Vec<[u8; 4]> = u.arbitrary()?;
+
+	Ok(words.concat())
+}
+
+/// Generates a [`GetProperty` reply] whose `format` matches its `value`, and
+/// which has no `value` if the property does not exist.
+///
+/// [`GetProperty` reply]: GetProperty
+pub fn get_property_replies(u: &mut Unstructured) -> Result<GetProperty> {
+	let sequence = u.arbitrary()?;
+	let bytes_remaining = u.arbitrary()?;
+
+	Ok(if u.arbitrary()? {
+		let value: DataList = u.arbitrary()?;
+
+		GetProperty {
+			sequence,
+			format: Some(value.format()),
+			r#type: Some(u.arbitrary()?),
+			bytes_remaining,
+			value,
+		}
+	} else {
+		GetProperty {
+			sequence,
+			format: None,
+			r#type: None,
+			bytes_remaining,
+			value: DataList::I8(vec![]),
+		}
+	})
+}
@@ -12,5 +12,7 @@
 
 pub mod error;
 pub mod event;
+pub mod registry;
 pub mod reply;
 pub mod request;
+pub mod util;
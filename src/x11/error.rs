@@ -14,6 +14,7 @@
 use crate::message::Error;
 
 use derivative::Derivative;
+use xrbk::{Buf, BufMut, ReadError, ReadResult, Readable, Writable, WriteResult, X11Size};
 use xrbk_macro::derive_xrb;
 extern crate self as xrb;
 
@@ -26,6 +27,7 @@ derive_xrb! {
 	/// [major opcode]: crate::message::Request::MAJOR_OPCODE
 	/// [minor opcode]: crate::message::Request::MINOR_OPCODE
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Request: Error(1) {
 		/// The sequence number identifying the [request] that was
@@ -33,7 +35,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] meant to refer to the type of [request] that was
@@ -73,6 +74,7 @@ derive_xrb! {
 	/// [request]: crate::message::Request
 	/// [error]: Error
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Value: Error(2) {
 		/// The sequence number identifying the [request] that was
@@ -80,7 +82,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The numerical value which fell outside of the accepted ranges.
@@ -121,6 +122,7 @@ derive_xrb! {
 	/// [window]: crate::Window
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Window: Error(3) {
 		/// The sequence number identifying the [request] that was
@@ -128,7 +130,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Window`] ID.
@@ -169,6 +170,7 @@ derive_xrb! {
 	/// [pixmap]: crate::Pixmap
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Pixmap: Error(4) {
 		/// The sequence number identifying the [request] that was
@@ -176,7 +178,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Pixmap`] ID.
@@ -217,6 +218,7 @@ derive_xrb! {
 	/// [atom]: crate::Atom
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Atom: Error(5) {
 		/// The sequence number identifying the [request] that was
@@ -224,7 +226,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Atom`] ID.
@@ -265,6 +266,7 @@ derive_xrb! {
 	/// [cursor appearance]: crate::CursorAppearance
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct CursorAppearance: Error(6) {
 		/// The sequence number identifying the [request] that was
@@ -272,7 +274,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`CursorAppearance`] ID.
@@ -314,6 +315,7 @@ derive_xrb! {
 	/// [font]: crate::Font
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Font: Error(7) {
 		/// The sequence number identifying the [request] that was
@@ -321,7 +323,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Font`] ID.
@@ -371,6 +372,7 @@ derive_xrb! {
 	/// [request]: crate::message::Request
 	/// [graphics context]: crate::GraphicsContext
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Match: Error(8) {
 		/// The sequence number identifying the [request] that was
@@ -378,7 +380,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -410,6 +411,7 @@ derive_xrb! {
 	/// [pixmap]: crate::Pixmap
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Drawable: Error(9) {
 		/// The sequence number identifying the [request] that was
@@ -417,7 +419,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Drawable`] ID.
@@ -476,6 +477,7 @@ derive_xrb! {
 	/// [button]: crate::Button
 	/// [colormap]: crate::Colormap
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Access: Error(10) {
 		/// The sequence number identifying the [request] that was
@@ -483,7 +485,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -511,6 +512,7 @@ derive_xrb! {
 	///
 	/// [error]: Error
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Alloc: Error(11) {
 		/// The sequence number identifying the [request] that was
@@ -518,7 +520,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -549,6 +550,7 @@ derive_xrb! {
 	/// [colormap]: crate::Colormap
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Colormap: Error(12) {
 		/// The sequence number identifying the [request] that was
@@ -556,7 +558,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`Colormap`] ID.
@@ -597,6 +598,7 @@ derive_xrb! {
 	/// [graphics context]: crate::GraphicsContext
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GraphicsContext: Error(13) {
 		/// The sequence number identifying the [request] that was
@@ -604,7 +606,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The invalid [`GraphicsContext`] ID.
@@ -642,6 +643,7 @@ derive_xrb! {
 	///
 	/// [error]: Error
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ResourceIdChoice: Error(14) {
 		/// The sequence number identifying the [request] that was
@@ -649,7 +651,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The resource ID that was either not assigned to the client or was
@@ -684,6 +685,7 @@ derive_xrb! {
 	/// [request]: crate::message::Request
 	/// [font]: crate::Font
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Name: Error(15) {
 		/// The sequence number identifying the [request] that was
@@ -691,7 +693,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -723,6 +724,7 @@ derive_xrb! {
 	/// [error]: Error
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Length: Error(16) {
 		/// The sequence number identifying the [request] that was
@@ -730,7 +732,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -759,6 +760,7 @@ derive_xrb! {
 	/// [error]: Error
 	/// [request]: crate::message::Request
 	#[derive(Debug, Derivative, Writable, Readable, X11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Implementation: Error(17) {
 		/// The sequence number identifying the [request] that was
@@ -766,7 +768,6 @@ derive_xrb! {
 		///
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [minor opcode] referring to the type of [request] that was sent.
@@ -789,3 +790,233 @@ derive_xrb! {
 		[_; ..],
 	}
 }
+
+/// An [error] of an unrecognized type, such as one defined in an extension.
+///
+/// [error]: Error
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownError {
+	/// The [error]'s 32 bytes, including the `0` which identifies it as an
+	/// [error] and its code.
+	///
+	/// [error]: Error
+	pub bytes: [u8; 32],
+}
+
+impl UnknownError {
+	/// The [error]'s code.
+	///
+	/// [error]: Error
+	#[must_use]
+	pub const fn code(&self) -> u8 {
+		self.bytes[1]
+	}
+}
+
+/// Generates [`AnyError`] with a variant for each of the given [errors].
+///
+/// [errors]: Error
+macro_rules! any_error {
+	($($Error:ident),+$(,)?) => {
+		/// Any [error] defined in the [core X11 protocol], or an
+		/// [`UnknownError`].
+		///
+		/// This allows an [error] to be read without knowing its type in
+		/// advance: see [`AnyError::read_from`].
+		///
+		/// [error]: Error
+		/// [core X11 protocol]: super
+		#[derive(Debug, Hash, PartialEq, Eq)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		pub enum AnyError {
+			$(
+				#[doc = concat!("A [`", stringify!($Error), "`] error.")]
+				$Error($Error),
+			)+
+			/// An [error] of an unrecognized type, such as one defined in an
+			/// extension.
+			///
+			/// [error]: Error
+			Unknown(UnknownError),
+		}
+
+		impl AnyError {
+			/// Reads an [error] of any type from its 32 bytes, including the
+			/// `0` which identifies it as an [error] and its code.
+			///
+			/// The type of [error] is identified by its code. Codes which are
+			/// not those of [errors] defined in the core X11 protocol are read
+			/// as an [`UnknownError`], rather than an error.
+			///
+			/// # Errors
+			/// - [`ReadError::UnrecognizedDiscriminant`] if the first byte is
+			///   not `0`, meaning that the message is not an [error].
+			/// - Any other error if fewer than 32 bytes remain in the `buf`, or
+			///   if the [error] could not be read.
+			///
+			/// [error]: Error
+			/// [errors]: Error
+			pub fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+				xrbk::ensure_remaining(buf, 32)?;
+
+				let discriminant = buf.get_u8();
+				if discriminant != 0 {
+					return Err(ReadError::UnrecognizedDiscriminant {
+						type_name: "AnyError",
+						discriminant: discriminant.into(),
+					});
+				}
+
+				let code = buf.get_u8();
+
+				// The error is read from `buf` itself, rather than from a copy of
+				// its bytes, so that the byte order of an `Ordered` buffer is
+				// respected.
+				let buf = &mut xrbk::take(buf, 30);
+				let error = match code {
+					$(<$Error as Error>::CODE => Self::$Error($Error::read_from(buf)?),)+
+
+					_ => {
+						let mut bytes = [0; 32];
+						bytes[1] = code;
+						buf.copy_to_slice(&mut bytes[2..]);
+
+						Self::Unknown(UnknownError { bytes })
+					},
+				};
+
+				match buf.limit() {
+					0 => Ok(error),
+					remaining => Err(ReadError::TrailingData { remaining }),
+				}
+			}
+
+			/// The [error]'s code.
+			///
+			/// [error]: Error
+			#[must_use]
+			pub const fn code(&self) -> u8 {
+				match self {
+					$(Self::$Error(_) => <$Error as Error>::CODE,)+
+
+					Self::Unknown(error) => error.code(),
+				}
+			}
+		}
+
+		impl X11Size for AnyError {
+			fn x11_size(&self) -> usize {
+				32
+			}
+		}
+
+		impl Writable for AnyError {
+			fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+				match self {
+					$(Self::$Error(error) => error.write_to(buf),)+
+
+					Self::Unknown(error) => {
+						buf.put_slice(&error.bytes);
+
+						Ok(())
+					},
+				}
+			}
+		}
+	};
+}
+
+any_error! {
+	Request,
+	Value,
+	Window,
+	Pixmap,
+	Atom,
+	CursorAppearance,
+	Font,
+	Match,
+	Drawable,
+	Access,
+	Alloc,
+	Colormap,
+	GraphicsContext,
+	ResourceIdChoice,
+	Name,
+	Length,
+	Implementation,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use xrbk::{ByteOrder, Ordered};
+
+	#[test]
+	fn test_any_error_read() {
+		let error = Window {
+			sequence: 3,
+			invalid_window_id: 0x0040_0002,
+			minor_opcode: 0,
+			major_opcode: 4,
+		};
+
+		let mut bytes = vec![];
+		error.write_to(&mut bytes).unwrap();
+
+		let buf = &mut &bytes[..];
+		let read = AnyError::read_from(buf).unwrap();
+
+		assert!(buf.is_empty(), "not every byte was read");
+		assert_eq!(read.code(), Window::CODE);
+		assert_eq!(read, AnyError::Window(error));
+	}
+
+	#[test]
+	fn test_any_error_read_little_endian() {
+		let error = Window {
+			sequence: 0x0102,
+			invalid_window_id: 0x0040_0002,
+			minor_opcode: 0,
+			major_opcode: 4,
+		};
+
+		let mut bytes = vec![];
+		error
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		assert_eq!(bytes[2..8], [0x02, 0x01, 0x02, 0, 0x40, 0]);
+
+		let buf = &mut Ordered::new(&bytes[..], ByteOrder::LittleEndian);
+		let read = AnyError::read_from(buf).unwrap();
+
+		assert_eq!(buf.remaining(), 0, "not every byte was read");
+		assert!(matches!(read, AnyError::Window(read) if read.eq_including_sequence(&error)));
+	}
+
+	#[test]
+	fn test_any_error_unknown_code() {
+		let mut bytes = [0xab; 32];
+		bytes[0] = 0;
+		bytes[1] = 140;
+
+		let read = AnyError::read_from(&mut &bytes[..]).unwrap();
+
+		assert_eq!(read.code(), 140);
+		assert_eq!(read, AnyError::Unknown(UnknownError { bytes }));
+	}
+
+	#[test]
+	fn test_any_error_rejects_other_messages() {
+		let mut bytes = [0; 32];
+		bytes[0] = 1;
+
+		assert!(matches!(
+			AnyError::read_from(&mut &bytes[..]),
+			Err(ReadError::UnrecognizedDiscriminant {
+				type_name: "AnyError",
+				discriminant: 1,
+			}),
+		));
+	}
+}
@@ -12,9 +12,22 @@
 extern crate self as xrb;
 
 use bitflags::bitflags;
+use bytes::buf::UninitSlice;
 use derivative::Derivative;
 
-use xrbk::{Buf, ConstantX11Size, ReadResult, Readable, ReadableWithContext, X11Size};
+use xrbk::{
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	Discriminated,
+	ReadError,
+	ReadResult,
+	Readable,
+	ReadableWithContext,
+	Writable,
+	WriteResult,
+	X11Size,
+};
 use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
@@ -49,6 +62,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`KEY_PRESS`]: crate::EventMask::KEY_PRESS
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct KeyPress: Event(2) {
 		/// The [sequence number] associated with the last [request] related
@@ -58,7 +72,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The keycode of the key that was pressed.
@@ -130,6 +143,7 @@ derive_xrb! {
 	/// [event]: Event
 	/// [`KEY_RELEASE`]: crate::EventMask::KEY_RELEASE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct KeyRelease: Event(3) {
 		/// The [sequence number] associated with the last [request] related
@@ -139,7 +153,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The keycode of the key which was released.
@@ -214,6 +227,7 @@ derive_xrb! {
 	/// [mouse button]: Button
 	/// [`BUTTON_PRESS`]: crate::EventMask::BUTTON_PRESS
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ButtonPress: Event(4) {
 		/// The [sequence number] associated with the last [request] related
@@ -223,7 +237,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The mouse button which was pressed.
@@ -296,6 +309,7 @@ derive_xrb! {
 	/// [mouse button]: Button
 	/// [`BUTTON_RELEASE`]: crate::EventMask::BUTTON_RELEASE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ButtonRelease: Event(5) {
 		/// The [sequence number] associated with the last [request] related
@@ -305,7 +319,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The mouse button which was released.
@@ -373,7 +386,9 @@ derive_xrb! {
 /// This is used in the [`Motion` event].
 ///
 /// [`Motion` event]: Motion
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MotionNotificationType {
 	/// The [`Motion` event] was not one generated for a client selecting
 	/// [`MOTION_HINT`].
@@ -441,6 +456,7 @@ derive_xrb! {
 	/// [event]: Event
 	/// [window]: Window
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Motion: Event(6) {
 		/// The [sequence number] associated with the last [request] related
@@ -450,7 +466,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The type of `Motion` event sent.
@@ -560,7 +575,10 @@ derive_xrb! {
 ///
 /// [event]: Event
 /// [window]: Window
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[doc(alias = "NotifyDetail")]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EnterLeaveDetail {
 	/// Used for [`LeaveWindow` events] when the cursor leaves a [window] and
 	/// enters an ancestor of that [window], and for [`EnterWindow` events]
@@ -629,6 +647,14 @@ bitflags! {
 	}
 }
 
+#[cfg(feature = "serde")]
+crate::impl_serde_for_mask! {
+	EnterLeaveMask {
+		FOCUS,
+		SAME_SCREEN,
+	}
+}
+
 derive_xrb! {
 	/// An [event] generated when the cursor enters a [window].
 	///
@@ -646,6 +672,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`ENTER_WINDOW`]: crate::EventMask::ENTER_WINDOW
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct EnterWindow: Event(7) {
 		/// The [sequence number] associated with the last [request] related
@@ -655,7 +682,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// Detail about how the [event] was generated.
@@ -737,6 +763,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`LEAVE_WINDOW`]: crate::EventMask::LEAVE_WINDOW
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct LeaveWindow: Event(8) {
 		/// The [sequence number] associated with the last [request] related
@@ -746,7 +773,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// Detail about how the [event] was generated.
@@ -941,7 +967,10 @@ derive_xrb! {
 ///
 /// [event]: Event
 /// [window]: Window
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[doc(alias = "NotifyDetail")]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FocusDetail {
 	/// Used for [`Unfocus` events] for the [window] which has been unfocused if
 	/// the newly focused [window] is an ancestor of that [window], and for
@@ -990,7 +1019,10 @@ pub enum FocusDetail {
 
 /// Detail about how an [`Unfocus`] or [`Focus`] event was generated in relation
 /// to grabs.
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[doc(alias = "NotifyMode")]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FocusGrabMode {
 	/// Used for [`Unfocus`] and [`Focus`] events generated when the keyboard is
 	/// not grabbed.
@@ -1026,6 +1058,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`FOCUS_CHANGE`]: crate::EventMask::FOCUS_CHANGE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Focus: Event(9) {
 		/// The [sequence number] associated with the last [request] related
@@ -1035,7 +1068,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// Detail about how the [event] was generated.
@@ -1085,6 +1117,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`FOCUS_CHANGE`]: crate::EventMask::FOCUS_CHANGE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Unfocus: Event(10) {
 		/// The [sequence number] associated with the last [request] related
@@ -1094,7 +1127,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// Detail about how the [event] was generated.
@@ -1136,7 +1168,9 @@ derive_xrb! {
 	/// [event]: Event
 	/// [window]: Window
 	/// [`KEYBOARD_STATE`]: crate::EventMask::KEYBOARD_STATE
-	#[derive(Debug, Hash, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct KeyboardState: Event(11) {
 		/// A bit vector representing the current keyboard state.
 		///
@@ -1179,6 +1213,8 @@ derive_xrb! {
 	///
 	/// [`EXPOSURE`]: crate::EventMask::EXPOSURE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Expose: Event(12) {
 		/// The [sequence number] associated with the last [request] related
@@ -1188,7 +1224,6 @@ derive_xrb! {
 		/// [event]: Event
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window which this `Expose` event applies to.
@@ -1218,6 +1253,8 @@ derive_xrb! {
 	/// [`GraphicsContext`]: crate::GraphicsContext
 	/// [`graphics_exposure`]: crate::set::GraphicsOptions::graphics_exposure
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GraphicsExposure: Event(13) {
 		/// The [sequence number] associated with the last [request] related
@@ -1227,7 +1264,6 @@ derive_xrb! {
 		/// [event]: Event
 		/// [request]: crate::message::Request
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [`Drawable`] this `GraphicsExposure` event applies to.
@@ -1273,6 +1309,8 @@ derive_xrb! {
 	/// [`GraphicsContext`]: crate::GraphicsContext
 	/// [`graphics_exposure`]: crate::set::GraphicsOptions::graphics_exposure
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct NoExposure: Event(14) {
 		/// The [sequence number] associated with the last [request] related
@@ -1282,7 +1320,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [`Drawable`] this `NoExposure` event applies to.
@@ -1309,13 +1346,31 @@ derive_xrb! {
 	}
 }
 
+impl Expose {
+	/// Whether this is the last `Expose` event in a series generated for its
+	/// `window`.
+	///
+	/// Clients which only redraw once all the exposed regions of a `window`
+	/// are known can ignore every `Expose` event for which this is `false`.
+	///
+	/// This is `true` when the [`count`] is `0`.
+	///
+	/// [`count`]: Expose::count
+	#[must_use]
+	pub const fn is_last(&self) -> bool {
+		self.count == 0
+	}
+}
+
 /// The state of a [window]'s visibility.
 ///
 /// This is used in the [`Visibility` event].
 ///
 /// [window]: Window
 /// [`Visibility` event]: Visibility
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VisibilityState {
 	/// There is nothing obscuring the `window`.
 	///
@@ -1377,6 +1432,7 @@ derive_xrb! {
 	///
 	/// [`VISIBILITY_CHANGE`]: crate::EventMask::VISIBILITY_CHANGE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Visibility: Event(15) {
 		/// The [sequence number] associated with the last [request] related
@@ -1386,7 +1442,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window this `Visibility` event applies to.
@@ -1406,6 +1461,8 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Create: Event(16) {
 		/// The [sequence number] associated with the last [request] related
@@ -1415,7 +1472,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The parent of the `window` that was created.
@@ -1463,6 +1519,8 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Destroy: Event(17) {
 		/// The [sequence number] associated with the last [request] related
@@ -1472,7 +1530,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which this `Destroy` event was generated.
@@ -1503,6 +1560,8 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Unmap: Event(18) {
 		/// The [sequence number] associated with the last [request] related
@@ -1512,7 +1571,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which this `Unmap` event was generated.
@@ -1551,6 +1609,8 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Map: Event(19) {
 		/// The [sequence number] associated with the last [request] related
@@ -1560,7 +1620,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which this `Map` event was generated.
@@ -1606,6 +1665,7 @@ derive_xrb! {
 	///
 	/// [`SUBSTRUCTURE_REDIRECT`]: crate::EventMask::SUBSTRUCTURE_REDIRECT
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct MapWindowRequest: Event(20) {
 		/// The [sequence number] associated with the last [request] related
@@ -1615,7 +1675,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The `window`'s parent.
@@ -1642,6 +1701,8 @@ derive_xrb! {
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Reparent: Event(21) {
 		/// The [sequence number] associated with the last [request] related
@@ -1651,7 +1712,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which this `Reparent` event was generated.
@@ -1700,6 +1760,8 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Configure: Event(22) {
 		/// The [sequence number] associated with the last [request] related
@@ -1709,7 +1771,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which this `Configure` event was generated.
@@ -1730,6 +1791,7 @@ derive_xrb! {
 		///
 		/// If the `window` has no siblings or the `window` is lower than all
 		/// its siblings in the window stack, this is [`None`].
+		#[doc(alias = "above_sibling")]
 		pub sibling_below: Option<Window>,
 
 		/// The geometry (coordinates and dimensions) of the `window`.
@@ -1782,6 +1844,7 @@ derive_xrb! {
 	///
 	/// [`SUBSTRUCTURE_REDIRECT`]: crate::EventMask::SUBSTRUCTURE_REDIRECT
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ConfigureWindowRequest: Event(23) {
 		/// The [sequence number] associated with the last [request] related
@@ -1791,7 +1854,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		#[metabyte]
@@ -1847,6 +1909,7 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Gravity: Event(24) {
 		/// The [sequence number] associated with the last [request] related
@@ -1856,7 +1919,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window which this `Gravity` event was generated on.
@@ -1890,6 +1952,7 @@ derive_xrb! {
 	/// [`RESIZE_REDIRECT`]: crate::EventMask::RESIZE_REDIRECT
 	/// [`ConfigureWindow` request]: super::request::ConfigureWindow
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ResizeRequest: Event(25) {
 		/// The [sequence number] associated with the last [request] related
@@ -1899,7 +1962,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window which the [`ConfigureWindow` request] attempted to
@@ -1929,7 +1991,9 @@ derive_xrb! {
 /// [window]: Window
 /// [`CirculateWindow` request]: super::request::CirculateWindow
 /// [`Circulate` events]: Circulate
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Placement {
 	/// The `window` is now above all its siblings in the stack.
 	Top,
@@ -1952,6 +2016,7 @@ derive_xrb! {
 	/// [`STRUCTURE_NOTIFY`]: crate::EventMask::STRUCTURE_NOTIFY
 	/// [`SUBSTRUCTURE_NOTIFY`]: crate::EventMask::SUBSTRUCTURE_NOTIFY
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Circulate: Event(26) {
 		/// The [sequence number] associated with the last [request] related
@@ -1961,7 +2026,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window which this `Circulate` event was generated on.
@@ -1995,6 +2059,7 @@ derive_xrb! {
 	/// [`SUBSTRUCTURE_REDIRECT`]: crate::EventMask::SUBSTRUCTURE_REDIRECT
 	/// [`CirculateWindow` request]: super::request::CirculateWindow
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct CirculateWindowRequest: Event(27) {
 		/// The [sequence number] associated with the last [request] related
@@ -2004,7 +2069,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The parent of the `window` the [`CirculateWindow` request] applies
@@ -2035,7 +2099,9 @@ derive_xrb! {
 /// [`Property` event]: Property
 /// [`Modified`]: PropertyChange::Modified
 /// [`Deleted`]: PropertyChange::Deleted
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyChange {
 	/// The `property` was added or its value was changed.
 	Modified,
@@ -2055,6 +2121,7 @@ derive_xrb! {
 	/// [window]: Window
 	/// [`PROPERTY_CHANGE`]: crate::EventMask::PROPERTY_CHANGE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Property: Event(28) {
 		/// The [sequence number] associated with the last [request] related
@@ -2064,7 +2131,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window on which the `property` was changed.
@@ -2094,6 +2160,8 @@ derive_xrb! {
 	/// [event]: Event
 	/// [`SetSelectionOwner` request]: super::request::SetSelectionOwner
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct SelectionClear: Event(29) {
 		/// The [sequence number] associated with the last [request] related
@@ -2103,7 +2171,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The time at which the new `selection` owner was defined.
@@ -2132,6 +2199,8 @@ derive_xrb! {
 	/// [`Selection` event]: Selection
 	/// [`SendEvent` request]: super::request::SendEvent
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ConvertSelectionRequest: Event(30) {
 		/// The [sequence number] associated with the last [request] related
@@ -2141,7 +2210,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The time at which the [`ConvertSelection` request] was sent.
@@ -2160,7 +2228,11 @@ derive_xrb! {
 		pub selection: Atom,
 		/// The type that the `selection` should be converted into.
 		pub target_type: Atom,
-		// TODO: undocumented in core protocol, check ICCCM
+		/// The property on the `requester` in which the `selection` should be
+		/// placed once it has been converted.
+		///
+		/// If this is [`None`], the `requester` is an obsolete client, and the
+		/// `target_type` should be used as the name of the property instead.
 		pub property: Option<Atom>,
 		[_; ..],
 	}
@@ -2179,6 +2251,8 @@ derive_xrb! {
 	/// [`ConvertSelection` request]: super::request::ConvertSelection
 	/// [`SendEvent` request]: super::request::SendEvent
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Selection: Event(31) {
 		/// The [sequence number] associated with the last [request] related
@@ -2188,7 +2262,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The time at which this `Selection` event was generated.
@@ -2207,7 +2280,12 @@ derive_xrb! {
 		///
 		/// The `selection` may or may not have been converted.
 		pub target_type: Atom,
-		// TODO: undocumented in core protocol, check ICCCM.
+		/// The property on the `requester` in which the converted `selection`
+		/// was placed.
+		///
+		/// If this is [`None`], the conversion was refused: either the
+		/// `selection` has no owner, or its owner could not convert it to the
+		/// `target_type`.
 		pub property: Option<Atom>,
 		[_; ..],
 	}
@@ -2215,7 +2293,8 @@ derive_xrb! {
 	/// The reason why a [`Colormap` event] was generated.
 	///
 	/// [`Colormap` event]: Colormap
-	#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+	#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub enum ColormapDetail {
 		/// The `window`'s [`colormap` attribute] was changed.
 		///
@@ -2231,7 +2310,8 @@ derive_xrb! {
 	///
 	/// [window]: Window
 	/// [colormap]: crate::Colormap
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub enum ColormapState {
 		/// The [window]'s [colormap] is not currently installed.
 		///
@@ -2259,6 +2339,7 @@ derive_xrb! {
 	///
 	/// [`COLORMAP_CHANGE`]: crate::EventMask::COLORMAP_CHANGE
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct Colormap: Event(32) {
 		/// The [sequence number] associated with the last [request] related
@@ -2268,7 +2349,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The window that this [event] relates to.
@@ -2297,7 +2377,9 @@ derive_xrb! {
 /// `i8` values, 10 `i16` values, or 5 `i32` values.
 ///
 /// [`ClientMessage` event]: ClientMessage
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClientMessageFormat {
 	/// 20 `i8` values: [`ClientMessageData::I8`].
 	I8 = 8,
@@ -2311,6 +2393,8 @@ pub enum ClientMessageFormat {
 ///
 /// [`ClientMessage` event]: ClientMessage
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[no_discrim]
 pub enum ClientMessageData {
 	/// Data comprised of 20 `i8` values.
@@ -2346,6 +2430,16 @@ impl ReadableWithContext for ClientMessageData {
 	}
 }
 
+impl Discriminated for ClientMessageData {
+	fn discriminant(&self) -> ClientMessageFormat {
+		match self {
+			Self::I8(_) => ClientMessageFormat::I8,
+			Self::I16(_) => ClientMessageFormat::I16,
+			Self::I32(_) => ClientMessageFormat::I32,
+		}
+	}
+}
+
 derive_xrb! {
 	/// An [event] generated by a [`SendEvent` request].
 	///
@@ -2357,6 +2451,7 @@ derive_xrb! {
 	/// [`SendEvent` request]: super::request::SendEvent
 	/// [window]: Window
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ClientMessage: Event(33) {
 		/// The [sequence number] associated with the last [request] related
@@ -2366,16 +2461,14 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// Whether `data` is `[i8; 20]`, `[i16; 10]`, or `[i32; 5]`.
+		///
+		/// This must match the variant of `data`: a `ClientMessage` event
+		/// for which it does not cannot be written.
 		#[metabyte]
-		let format: ClientMessageFormat = data => match data {
-			ClientMessageData::I8(_) => ClientMessageFormat::I8,
-			ClientMessageData::I16(_) => ClientMessageFormat::I16,
-			ClientMessageData::I32(_) => ClientMessageFormat::I32,
-		},
+		pub format: ClientMessageFormat,
 
 		/// The recipient of this `ClientMessage` event.
 		pub window: Window,
@@ -2385,7 +2478,7 @@ derive_xrb! {
 		/// The data contained in this [event].
 		///
 		/// [event]: Event
-		#[context(format => *format)]
+		#[discriminated_by(format)]
 		pub data: ClientMessageData,
 	}
 }
@@ -2394,7 +2487,10 @@ derive_xrb! {
 ///
 /// [request]: crate::message::Request
 /// [`MappingChange` event]: MappingChange
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MappingRequest {
 	/// The [`MappingChange` event] was generated by a
 	/// [`SetModifierMapping` request].
@@ -2430,6 +2526,8 @@ derive_xrb! {
 	/// [`ChangeKeyboardMapping`]: super::request::ChangeKeyboardMapping
 	/// [`SetCursorMapping`]: super::request::SetButtonMapping
 	#[derive(Debug, Derivative, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct MappingChange: Event(34) {
 		/// The [sequence number] associated with the last [request] related
@@ -2439,7 +2537,6 @@ derive_xrb! {
 		/// [request]: crate::message::Request
 		/// [event]: Event
 		#[sequence]
-		#[derivative(PartialEq = "ignore", Hash = "ignore")]
 		pub sequence: u16,
 
 		/// The [request] that generated this `MappingChange` event.
@@ -2464,3 +2561,1091 @@ derive_xrb! {
 		[_; ..],
 	}
 }
+
+/// The bit of an [event]'s code which is set if it was sent by a
+/// [`SendEvent` request].
+///
+/// [event]: Event
+/// [`SendEvent` request]: super::request::SendEvent
+pub(crate) const SYNTHETIC_MASK: u8 = 0x80;
+
+/// An [event] of an unrecognized type, such as one defined in an extension.
+///
+/// [event]: Event
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownEvent {
+	/// The [event]'s 32 bytes, including its code.
+	///
+	/// [event]: Event
+	pub bytes: [u8; 32],
+}
+
+impl UnknownEvent {
+	/// The [event]'s code, without the bit which is set if it was sent by a
+	/// [`SendEvent` request].
+	///
+	/// [event]: Event
+	/// [`SendEvent` request]: super::request::SendEvent
+	#[must_use]
+	pub const fn code(&self) -> u8 {
+		self.bytes[0] & !SYNTHETIC_MASK
+	}
+}
+
+/// An [event] which can be sent in a [`SendEvent` request].
+///
+/// This is implemented for every [event], and for [`AnyEvent`] so that a
+/// [`SendEvent` request] can be read without knowing the type of [event] it
+/// contains.
+///
+/// [event]: Event
+/// [`SendEvent` request]: super::request::SendEvent
+pub trait SendableEvent: ConstantX11Size + X11Size + Writable {
+	/// Reads the [event], including its code, along with whether it was sent
+	/// by a [`SendEvent` request].
+	///
+	/// # Errors
+	/// Returns [`ReadError::UnrecognizedDiscriminant`] if the code is not that
+	/// of this type of [event], or any other error if the [event] could not
+	/// be read.
+	///
+	/// [event]: Event
+	/// [`SendEvent` request]: super::request::SendEvent
+	fn read_maybe_synthetic(buf: &mut impl Buf) -> ReadResult<MaybeSynthetic<Self>>
+	where
+		Self: Sized;
+
+	/// Reads the [event], including its code.
+	///
+	/// The bit of the code which is set if the [event] was sent by a
+	/// [`SendEvent` request] is ignored.
+	///
+	/// # Errors
+	/// See [`read_maybe_synthetic`].
+	///
+	/// [event]: Event
+	/// [`SendEvent` request]: super::request::SendEvent
+	/// [`read_maybe_synthetic`]: SendableEvent::read_maybe_synthetic
+	fn read_sent(buf: &mut impl Buf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		Ok(Self::read_maybe_synthetic(buf)?.event)
+	}
+}
+
+impl<E: Event + ConstantX11Size> SendableEvent for E {
+	fn read_maybe_synthetic(buf: &mut impl Buf) -> ReadResult<MaybeSynthetic<Self>> {
+		let code = u8::read_from(buf)?;
+		let synthetic = code & SYNTHETIC_MASK != 0;
+
+		match code & !SYNTHETIC_MASK {
+			code if code == E::CODE => Ok(MaybeSynthetic {
+				event: E::read_from(buf)?,
+				synthetic,
+			}),
+
+			other => Err(ReadError::UnrecognizedDiscriminant {
+				type_name: std::any::type_name::<E>(),
+				discriminant: usize::from(other),
+			}),
+		}
+	}
+}
+
+impl SendableEvent for AnyEvent {
+	fn read_maybe_synthetic(buf: &mut impl Buf) -> ReadResult<MaybeSynthetic<Self>> {
+		let (event, synthetic) = Self::read_from(buf)?;
+
+		Ok(MaybeSynthetic { event, synthetic })
+	}
+}
+
+/// An [event] along with whether it was sent by a [`SendEvent` request].
+///
+/// [Events] sent by a [`SendEvent` request] are written with the most
+/// significant bit of their code set. Reading an [event] as a
+/// `MaybeSynthetic` records that bit in `synthetic`, and writing it sets the
+/// bit again, so that the [event] is written exactly as it was read.
+///
+/// `E` may be [`AnyEvent`] to read an [event] of any type.
+///
+/// # Examples
+/// ```
+/// use xrb::x11::event::{AnyEvent, MaybeSynthetic};
+/// use xrbk::{Readable, Writable};
+///
+/// // A `KeymapNotify` event sent by a `SendEvent` request.
+/// let mut bytes = [0; 32];
+/// bytes[0] = 11 | 0x80;
+///
+/// let event = MaybeSynthetic::<AnyEvent>::read_from(&mut &bytes[..]).unwrap();
+/// assert!(event.synthetic);
+///
+/// let mut written = vec![];
+/// event.write_to(&mut written).unwrap();
+/// assert_eq!(written, bytes);
+/// ```
+///
+/// [event]: Event
+/// [Events]: Event
+/// [`SendEvent` request]: super::request::SendEvent
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaybeSynthetic<E> {
+	/// The [event].
+	///
+	/// [event]: Event
+	pub event: E,
+	/// Whether the `event` was sent by a [`SendEvent` request].
+	///
+	/// [`SendEvent` request]: super::request::SendEvent
+	pub synthetic: bool,
+}
+
+/// The largest size of an [event], in bytes.
+///
+/// [event]: Event
+const MAX_EVENT_SIZE: usize = 32;
+
+impl<E: SendableEvent> MaybeSynthetic<E> {
+	/// Referencing this associated `const` causes a compilation error if the
+	/// `event` is larger than any [event] can be.
+	///
+	/// [event]: Event
+	const EVENT_FITS: () = assert!(
+		E::X11_SIZE <= MAX_EVENT_SIZE,
+		"events must be no larger than 32 bytes",
+	);
+}
+
+impl<E: SendableEvent> X11Size for MaybeSynthetic<E> {
+	fn x11_size(&self) -> usize {
+		E::X11_SIZE
+	}
+}
+
+impl<E: SendableEvent> ConstantX11Size for MaybeSynthetic<E> {
+	const X11_SIZE: usize = E::X11_SIZE;
+}
+
+impl<E: SendableEvent> Readable for MaybeSynthetic<E> {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		E::read_maybe_synthetic(buf)
+	}
+}
+
+impl<E: SendableEvent> Writable for MaybeSynthetic<E> {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		#[allow(clippy::let_unit_value)]
+		let () = Self::EVENT_FITS;
+
+		self.event.write_to(&mut SyntheticCode {
+			inner: buf,
+			synthetic: self.synthetic,
+
+			code: [0],
+			code_written: false,
+		})
+	}
+}
+
+/// A [`BufMut`] which sets or clears the [`SYNTHETIC_MASK`] bit of the first
+/// byte written to it, which is an [event]'s code, as it is written to the
+/// buffer it wraps.
+///
+/// Every other byte is written directly to the wrapped buffer, and the writing
+/// of multi-byte integers is forwarded to it, so that an [`Ordered`] buffer's
+/// byte order is respected.
+///
+/// [event]: Event
+/// [`Ordered`]: xrbk::Ordered
+struct SyntheticCode<'a, B> {
+	inner: &'a mut B,
+	synthetic: bool,
+
+	/// Holds the [event]'s code until it is written to `inner`.
+	///
+	/// [event]: Event
+	code: [u8; 1],
+	/// Whether the [event]'s code has been written to `inner`.
+	///
+	/// [event]: Event
+	code_written: bool,
+}
+
+/// Generates overrides of [`BufMut`] methods which forward to the inner
+/// buffer once the [event]'s code has been written.
+///
+/// [event]: Event
+macro_rules! synthetic_code_puts {
+	($($put:ident($ty:ty);)*) => {
+		$(
+			fn $put(&mut self, n: $ty) {
+				assert!(self.code_written, "an event's code must be written first");
+
+				self.inner.$put(n);
+			}
+		)*
+	};
+}
+
+// SAFETY: until the code is written, `chunk_mut` returns `code`, which is
+//         initialized, and `advance_mut` writes it to `inner`. After that,
+//         every method is forwarded directly to `inner`.
+unsafe impl<B: BufMut> BufMut for SyntheticCode<'_, B> {
+	fn remaining_mut(&self) -> usize {
+		self.inner.remaining_mut()
+	}
+
+	unsafe fn advance_mut(&mut self, cnt: usize) {
+		if self.code_written {
+			// SAFETY: the caller upholds the same contract for `inner`.
+			unsafe { self.inner.advance_mut(cnt) };
+		} else if cnt > 0 {
+			let [code] = self.code;
+			let code = if self.synthetic {
+				code | SYNTHETIC_MASK
+			} else {
+				code & !SYNTHETIC_MASK
+			};
+
+			self.inner.put_u8(code);
+			self.code_written = true;
+		}
+	}
+
+	fn chunk_mut(&mut self) -> &mut UninitSlice {
+		if self.code_written {
+			self.inner.chunk_mut()
+		} else {
+			UninitSlice::new(&mut self.code)
+		}
+	}
+
+	synthetic_code_puts! {
+		put_u16(u16);
+		put_i16(i16);
+		put_u32(u32);
+		put_i32(i32);
+		put_u64(u64);
+		put_i64(i64);
+		put_u128(u128);
+		put_i128(i128);
+		put_f32(f32);
+		put_f64(f64);
+	}
+}
+
+/// Generates [`AnyEvent`] with a variant for each of the given [events].
+///
+/// [events]: Event
+macro_rules! any_event {
+	($($Event:ident),+$(,)?) => {
+		/// Any [event] defined in the [core X11 protocol], or an
+		/// [`UnknownEvent`].
+		///
+		/// This allows an [event] to be read without knowing its type in
+		/// advance: see [`AnyEvent::read_from`].
+		///
+		/// [event]: Event
+		/// [core X11 protocol]: super
+		#[derive(Debug, Hash, PartialEq, Eq)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		pub enum AnyEvent {
+			$(
+				#[doc = concat!("A [`", stringify!($Event), "`] event.")]
+				$Event($Event),
+			)+
+			/// An [event] of an unrecognized type, such as one defined in an
+			/// extension.
+			///
+			/// [event]: Event
+			Unknown(UnknownEvent),
+		}
+
+		impl AnyEvent {
+			/// Reads an [event] of any type from its 32 bytes, including its
+			/// code.
+			///
+			/// The type of [event] is identified by its code. Codes which are
+			/// not those of [events] defined in the core X11 protocol are read
+			/// as an [`UnknownEvent`], rather than an error.
+			///
+			/// Returns the [event] along with whether it was sent by a
+			/// [`SendEvent` request]. The [event] can be written with that
+			/// flag again as a [`MaybeSynthetic<AnyEvent>`].
+			///
+			/// # Errors
+			/// Returns an error if fewer than 32 bytes remain in the `buf`, or
+			/// if the [event] could not be read.
+			///
+			/// [event]: Event
+			/// [events]: Event
+			/// [`SendEvent` request]: super::request::SendEvent
+			pub fn read_from(buf: &mut impl Buf) -> ReadResult<(Self, bool)> {
+				xrbk::ensure_remaining(buf, 32)?;
+
+				let code = buf.get_u8();
+				let synthetic = code & SYNTHETIC_MASK != 0;
+
+				// The event is read from `buf` itself, rather than from a copy of
+				// its bytes, so that the byte order of an `Ordered` buffer is
+				// respected.
+				let buf = &mut xrbk::take(buf, 31);
+				let event = match code & !SYNTHETIC_MASK {
+					$(<$Event as Event>::CODE => Self::$Event($Event::read_from(buf)?),)+
+
+					_ => {
+						let mut bytes = [0; 32];
+						bytes[0] = code;
+						buf.copy_to_slice(&mut bytes[1..]);
+
+						Self::Unknown(UnknownEvent { bytes })
+					},
+				};
+
+				match buf.limit() {
+					0 => Ok((event, synthetic)),
+					remaining => Err(ReadError::TrailingData { remaining }),
+				}
+			}
+
+			/// The [event]'s code, without the bit which is set if it was sent
+			/// by a [`SendEvent` request].
+			///
+			/// [event]: Event
+			/// [`SendEvent` request]: super::request::SendEvent
+			#[must_use]
+			pub const fn code(&self) -> u8 {
+				match self {
+					$(Self::$Event(_) => <$Event as Event>::CODE,)+
+
+					Self::Unknown(event) => event.code(),
+				}
+			}
+		}
+
+		impl ConstantX11Size for AnyEvent {
+			const X11_SIZE: usize = 32;
+		}
+
+		// Every event is 32 bytes.
+		$(
+			const _: () = assert!(
+				<$Event as ConstantX11Size>::X11_SIZE == AnyEvent::X11_SIZE,
+				concat!("`", stringify!($Event), "` events must be 32 bytes"),
+			);
+		)+
+
+		impl X11Size for AnyEvent {
+			fn x11_size(&self) -> usize {
+				Self::X11_SIZE
+			}
+		}
+
+		impl Writable for AnyEvent {
+			fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+				match self {
+					$(Self::$Event(event) => event.write_to(buf),)+
+
+					Self::Unknown(event) => {
+						buf.put_slice(&event.bytes);
+
+						Ok(())
+					},
+				}
+			}
+		}
+	};
+}
+
+any_event! {
+	KeyPress,
+	KeyRelease,
+	ButtonPress,
+	ButtonRelease,
+	Motion,
+	EnterWindow,
+	LeaveWindow,
+	Focus,
+	Unfocus,
+	KeyboardState,
+	Expose,
+	GraphicsExposure,
+	NoExposure,
+	Visibility,
+	Create,
+	Destroy,
+	Unmap,
+	Map,
+	MapWindowRequest,
+	Reparent,
+	Configure,
+	ConfigureWindowRequest,
+	Gravity,
+	ResizeRequest,
+	Circulate,
+	CirculateWindowRequest,
+	Property,
+	SelectionClear,
+	ConvertSelectionRequest,
+	Selection,
+	Colormap,
+	ClientMessage,
+	MappingChange,
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		arbitrary::{assert_round_trip, round_trip_tests},
+		atom,
+	};
+	use std::array;
+	use xrbk::{ByteOrder, Ordered, Writable, WriteError};
+
+	round_trip_tests! {
+		KeyboardState => test_keyboard_state_round_trip,
+		GraphicsExposure => test_graphics_exposure_round_trip,
+		NoExposure => test_no_exposure_round_trip,
+		SelectionClear => test_selection_clear_round_trip,
+		ConvertSelectionRequest => test_convert_selection_request_round_trip,
+		Selection => test_selection_round_trip,
+		MappingChange => test_mapping_change_round_trip,
+		Expose => test_expose_round_trip,
+		Create => test_create_round_trip,
+		Destroy => test_destroy_round_trip,
+		Unmap => test_unmap_round_trip,
+		Map => test_map_round_trip,
+		Reparent => test_reparent_round_trip,
+		Configure => test_configure_round_trip,
+	}
+
+	#[test]
+	fn test_keyboard_state_layout() {
+		#[allow(clippy::cast_possible_truncation)]
+		let event = KeyboardState {
+			keys: array::from_fn(|i| i as u8 + 1),
+		};
+
+		let mut bytes = vec![];
+		event.write_to(&mut bytes).unwrap();
+
+		// `KeyboardState` has no sequence number: its `keys` immediately
+		// follow its code.
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes[0], 11);
+		assert_eq!(bytes[1..], event.keys);
+
+		let read = KeyboardState::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(read.sequence(), None);
+		assert_eq!(read, event);
+	}
+
+	#[test]
+	fn test_selection_refused() {
+		let event = Selection {
+			sequence: 1,
+			time: CurrentableTime::CurrentTime,
+			requester: Window::new(0x0040_0001),
+			selection: Atom::new(1),
+			target_type: Atom::new(31),
+			property: None,
+		};
+
+		let mut bytes = vec![];
+		event.write_to(&mut bytes).unwrap();
+
+		// A `property` of `None` is written as `0`.
+		assert_eq!(bytes[20..24], [0; 4]);
+		assert_round_trip(&event).unwrap();
+	}
+
+	#[test]
+	fn test_selection_layout() {
+		let event = Selection {
+			sequence: 0x0102,
+			time: CurrentableTime::Other(Timestamp::new(0x0a0b_0c0d)),
+			requester: Window::new(0x0040_0001),
+			selection: atom::PRIMARY,
+			target_type: atom::STRING,
+			property: Some(Atom::new(0x01b3)),
+		};
+
+		let mut bytes = vec![];
+		event.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				// Code, unused byte, and sequence number.
+				31, 0, 0x01, 0x02,
+				// `time`
+				0x0a, 0x0b, 0x0c, 0x0d,
+				// `requester`
+				0x00, 0x40, 0x00, 0x01,
+				// `selection`
+				0, 0, 0, 1,
+				// `target_type`
+				0, 0, 0, 31,
+				// `property`
+				0, 0, 0x01, 0xb3,
+				// Unused bytes.
+				0, 0, 0, 0, 0, 0, 0, 0,
+			],
+		);
+
+		let read = Selection::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(read.sequence, 0x0102);
+		assert_eq!(read.property, Some(Atom::new(0x01b3)));
+		assert_eq!(read, event);
+	}
+
+	fn client_message(format: ClientMessageFormat, data: ClientMessageData) -> ClientMessage {
+		ClientMessage {
+			sequence: 0x0102,
+			format,
+			window: Window::new(0x0040_0001),
+			r#type: Atom::new(0x01b3),
+			data,
+		}
+	}
+
+	#[test]
+	fn test_client_message_layout() {
+		let event = client_message(
+			ClientMessageFormat::I16,
+			ClientMessageData::I16([1, -1, 2, -2, 3, -3, 4, -4, 5, -5]),
+		);
+
+		let mut bytes = vec![];
+		event.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				// Code, `format`, and sequence number.
+				33, 16, 0x01, 0x02,
+				// `window`
+				0x00, 0x40, 0x00, 0x01,
+				// `type`
+				0, 0, 0x01, 0xb3,
+				// `data`
+				0, 1, 0xff, 0xff, 0, 2, 0xff, 0xfe, 0, 3,
+				0xff, 0xfd, 0, 4, 0xff, 0xfc, 0, 5, 0xff, 0xfb,
+			],
+		);
+	}
+
+	#[test]
+	fn test_client_message_round_trip() {
+		for (format, data) in [
+			(
+				ClientMessageFormat::I8,
+				ClientMessageData::I8(array::from_fn(|i| i as i8 - 10)),
+			),
+			(
+				ClientMessageFormat::I16,
+				ClientMessageData::I16(array::from_fn(|i| i as i16 * -300)),
+			),
+			(
+				ClientMessageFormat::I32,
+				ClientMessageData::I32([0, -1, i32::MAX, i32::MIN, 0x0102_0304]),
+			),
+		] {
+			let event = client_message(format, data);
+
+			let mut bytes = vec![];
+			event.write_to(&mut bytes).unwrap();
+			assert_eq!(bytes.len(), 32);
+
+			let read = ClientMessage::read_from(&mut &bytes[1..]).unwrap();
+			assert_eq!(read.format, format);
+			assert_eq!(read, event);
+		}
+	}
+
+	#[test]
+	fn test_client_message_mismatched_format() {
+		let event = client_message(ClientMessageFormat::I8, ClientMessageData::I32([1; 5]));
+
+		let error = event.write_to(&mut vec![]).unwrap_err();
+
+		assert!(matches!(error, WriteError::InField { field: "data", .. }));
+		assert!(matches!(
+			error.root_cause(),
+			WriteError::MismatchedDiscriminant { .. },
+		));
+	}
+
+	/// The events generated when the cursor moves from one top-level window to
+	/// a sibling and focus follows it: `LeaveWindow` and `Unfocus` on the first
+	/// window, then `EnterWindow` and `Focus` on the second.
+	#[rustfmt::skip]
+	const CROSSING: [[u8; 32]; 4] = [
+		[
+			// Code, `detail` (`Nonlinear`), and sequence number.
+			8, 3, 0, 0x2a,
+			// `time`
+			0, 0x12, 0xd6, 0x87,
+			// `root`, `event_window`, and `child_window` (`None`)
+			0, 0, 0x02, 0x79, 0x01, 0x20, 0, 0x09, 0, 0, 0, 0,
+			// `root_coords` and `event_coords`
+			0x01, 0xf4, 0x01, 0x2c, 0x01, 0xe4, 0, 0x0a,
+			// `modifiers` (`MOD_2`), `grab_mode` (`Normal`), and `mask`
+			// (`FOCUS | SAME_SCREEN`)
+			0, 0x10, 0, 0x03,
+		],
+		[
+			// Code, `detail` (`Nonlinear`), and sequence number.
+			10, 3, 0, 0x2a,
+			// `window`
+			0x01, 0x20, 0, 0x09,
+			// `grab_mode` (`Normal`) and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0, 0, 0, 0, 0, 0, 0, 0,
+		],
+		[
+			// Code, `detail` (`Nonlinear`), and sequence number.
+			7, 3, 0, 0x2a,
+			// `time`
+			0, 0x12, 0xd6, 0x87,
+			// `root`, `event_window`, and `child_window` (`None`)
+			0, 0, 0x02, 0x79, 0x01, 0x40, 0, 0x09, 0, 0, 0, 0,
+			// `root_coords` and `event_coords`
+			0x01, 0xf4, 0x01, 0x2c, 0, 0, 0, 0x0a,
+			// `modifiers` (`MOD_2`), `grab_mode` (`Normal`), and `mask`
+			// (`SAME_SCREEN`)
+			0, 0x10, 0, 0x02,
+		],
+		[
+			// Code, `detail` (`Nonlinear`), and sequence number.
+			9, 3, 0, 0x2a,
+			// `window`
+			0x01, 0x40, 0, 0x09,
+			// `grab_mode` (`Normal`) and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0, 0, 0, 0, 0, 0, 0, 0,
+		],
+	];
+
+	#[test]
+	fn test_crossing_and_focus_events() {
+		for (bytes, code) in CROSSING.iter().zip([8, 10, 7, 9]) {
+			let (event, synthetic) = AnyEvent::read_from(&mut &bytes[..]).unwrap();
+
+			assert!(!synthetic);
+			assert_eq!(event.code(), code);
+
+			let mut written = vec![];
+			event.write_to(&mut written).unwrap();
+			assert_eq!(written, bytes);
+		}
+
+		let leave = LeaveWindow::read_from(&mut &CROSSING[0][1..]).unwrap();
+		let unfocus = Unfocus::read_from(&mut &CROSSING[1][1..]).unwrap();
+		let enter = EnterWindow::read_from(&mut &CROSSING[2][1..]).unwrap();
+		let focus = Focus::read_from(&mut &CROSSING[3][1..]).unwrap();
+
+		assert_eq!(leave.sequence, 0x2a);
+		assert_eq!(leave.detail, EnterLeaveDetail::Nonlinear);
+		assert_eq!(leave.time, Timestamp::new(0x0012_d687));
+		assert_eq!(leave.root, Window::new(0x0000_0279));
+		assert_eq!(leave.event_window, Window::new(0x0120_0009));
+		assert_eq!(leave.child_window, None);
+		assert_eq!(leave.root_coords, Coords::new(Px(500), Px(300)));
+		assert_eq!(leave.event_coords, Coords::new(Px(484), Px(10)));
+		assert_eq!(leave.modifiers, ModifierMask::MOD_2);
+		assert_eq!(leave.grab_mode, GrabMode::Normal);
+		assert_eq!(
+			leave.mask,
+			EnterLeaveMask::FOCUS | EnterLeaveMask::SAME_SCREEN
+		);
+
+		assert_eq!(unfocus.detail, FocusDetail::Nonlinear);
+		assert_eq!(unfocus.window, leave.event_window);
+		assert_eq!(unfocus.grab_mode, FocusGrabMode::Normal);
+
+		assert_eq!(enter.detail, EnterLeaveDetail::Nonlinear);
+		assert_eq!(enter.event_window, Window::new(0x0140_0009));
+		assert_eq!(enter.event_coords, Coords::new(Px(0), Px(10)));
+		// The entered window is not yet focused.
+		assert_eq!(enter.mask, EnterLeaveMask::SAME_SCREEN);
+
+		assert_eq!(focus.detail, FocusDetail::Nonlinear);
+		assert_eq!(focus.window, enter.event_window);
+	}
+
+	#[test]
+	fn test_focus_details() {
+		for (detail, byte) in [
+			(FocusDetail::Ancestor, 0),
+			(FocusDetail::Intermediate, 1),
+			(FocusDetail::Descendent, 2),
+			(FocusDetail::Nonlinear, 3),
+			(FocusDetail::NonlinearIntermediate, 4),
+			(FocusDetail::Cursor, 5),
+			(FocusDetail::CursorRoot, 6),
+			(FocusDetail::None, 7),
+		] {
+			let mut bytes = CROSSING[3];
+			bytes[1] = byte;
+			bytes[8] = 3;
+
+			let read = Focus::read_from(&mut &bytes[1..]).unwrap();
+
+			assert_eq!(read.detail, detail);
+			assert_eq!(read.grab_mode, FocusGrabMode::WhileGrabbed);
+		}
+	}
+
+	/// The events generated when an xterm is created, managed by a
+	/// reparenting window manager, and later closed, as received by the
+	/// window manager, together with the `Expose` event received by the xterm
+	/// once it is mapped.
+	///
+	/// In order: `Create`, `Reparent`, `Configure`, `Map`, `Expose`, `Unmap`,
+	/// and `Destroy`.
+	#[rustfmt::skip]
+	const STRUCTURE: [[u8; 32]; 7] = [
+		[
+			// Code, unused byte, and sequence number.
+			16, 0, 0, 0x10,
+			// `parent` and `window`
+			0, 0, 0x02, 0x79, 0x01, 0x40, 0, 0x0e,
+			// `geometry` and `border_width`
+			0, 0, 0, 0, 0x01, 0xe4, 0x01, 0x3c, 0, 0x02,
+			// `override_redirect` and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			21, 0, 0, 0x12,
+			// `event_window`, `window`, and `new_parent`
+			0, 0, 0x02, 0x79, 0x01, 0x40, 0, 0x0e, 0, 0x60, 0, 0x03,
+			// `coords`
+			0, 0x02, 0, 0x18,
+			// `override_redirect` and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			22, 0, 0, 0x14,
+			// `event_window`, `window`, and `sibling_below` (`None`)
+			0, 0x60, 0, 0x03, 0x01, 0x40, 0, 0x0e, 0, 0, 0, 0,
+			// `geometry` and `border_width`
+			0, 0, 0, 0x16, 0x01, 0xe4, 0x01, 0x3c, 0, 0,
+			// `override_redirect` and unused bytes.
+			0, 0, 0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			19, 0, 0, 0x16,
+			// `event_window` and `window`
+			0, 0x60, 0, 0x03, 0x01, 0x40, 0, 0x0e,
+			// `override_redirect` and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			12, 0, 0, 0x05,
+			// `window`
+			0x01, 0x40, 0, 0x0e,
+			// `region` and `count`
+			0, 0, 0, 0, 0x01, 0xe4, 0x01, 0x3c, 0, 0,
+			// Unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			18, 0, 0, 0x30,
+			// `event_window` and `window`
+			0, 0x60, 0, 0x03, 0x01, 0x40, 0, 0x0e,
+			// `from_configure` and unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0, 0, 0, 0,
+		],
+		[
+			// Code, unused byte, and sequence number.
+			17, 0, 0, 0x32,
+			// `event_window` and `window`
+			0, 0x60, 0, 0x03, 0x01, 0x40, 0, 0x0e,
+			// Unused bytes.
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0, 0, 0, 0,
+		],
+	];
+
+	#[test]
+	fn test_structure_events() {
+		for (bytes, code) in STRUCTURE.iter().zip([16, 21, 22, 19, 12, 18, 17]) {
+			let (event, synthetic) = AnyEvent::read_from(&mut &bytes[..]).unwrap();
+
+			assert!(!synthetic);
+			assert_eq!(event.code(), code);
+			assert_eq!(event.x11_size(), 32);
+
+			let mut written = vec![];
+			event.write_to(&mut written).unwrap();
+			assert_eq!(written, bytes);
+		}
+
+		let root = Window::new(0x0000_0279);
+		let xterm = Window::new(0x0140_000e);
+		let frame = Window::new(0x0060_0003);
+
+		let create = Create::read_from(&mut &STRUCTURE[0][1..]).unwrap();
+		let reparent = Reparent::read_from(&mut &STRUCTURE[1][1..]).unwrap();
+		let configure = Configure::read_from(&mut &STRUCTURE[2][1..]).unwrap();
+		let map = Map::read_from(&mut &STRUCTURE[3][1..]).unwrap();
+		let expose = Expose::read_from(&mut &STRUCTURE[4][1..]).unwrap();
+		let unmap = Unmap::read_from(&mut &STRUCTURE[5][1..]).unwrap();
+		let destroy = Destroy::read_from(&mut &STRUCTURE[6][1..]).unwrap();
+
+		assert_eq!(create.sequence, 0x10);
+		assert_eq!(create.parent, root);
+		assert_eq!(create.window, xterm);
+		assert_eq!(
+			create.geometry,
+			Rectangle::new(Px(0), Px(0), Px(484), Px(316))
+		);
+		assert_eq!(create.border_width, Px(2));
+		assert!(!create.override_redirect);
+
+		assert_eq!(reparent.event_window, root);
+		assert_eq!(reparent.window, xterm);
+		assert_eq!(reparent.new_parent, frame);
+		assert_eq!(reparent.coords, Coords::new(Px(2), Px(24)));
+
+		assert_eq!(configure.event_window, frame);
+		assert_eq!(configure.window, xterm);
+		assert_eq!(configure.sibling_below, None);
+		assert_eq!(
+			configure.geometry,
+			Rectangle::new(Px(0), Px(22), Px(484), Px(316))
+		);
+		assert_eq!(configure.border_width, Px(0));
+
+		assert_eq!(map.event_window, frame);
+		assert_eq!(map.window, xterm);
+		assert!(!map.override_redirect);
+
+		assert_eq!(expose.window, xterm);
+		assert_eq!(expose.region, Region::new(Px(0), Px(0), Px(484), Px(316)));
+		assert!(expose.is_last());
+
+		assert_eq!(unmap.event_window, frame);
+		assert!(!unmap.from_configure);
+
+		assert_eq!(destroy.event_window, frame);
+		assert_eq!(destroy.window, xterm);
+	}
+
+	#[test]
+	fn test_configure_sibling_below() {
+		let mut configure = Configure::read_from(&mut &STRUCTURE[2][1..]).unwrap();
+		configure.sibling_below = Some(Window::new(0x0060_0001));
+
+		let mut bytes = vec![];
+		configure.write_to(&mut bytes).unwrap();
+
+		// A `sibling_below` of `None` is written as `0`.
+		assert_eq!(STRUCTURE[2][12..16], [0; 4]);
+		assert_eq!(bytes[12..16], [0, 0x60, 0, 0x01]);
+		assert_round_trip(&configure).unwrap();
+	}
+
+	#[test]
+	fn test_expose_is_last() {
+		let mut expose = Expose::read_from(&mut &STRUCTURE[4][1..]).unwrap();
+		assert!(expose.is_last());
+
+		// Further `Expose` events follow.
+		expose.count = 3;
+		assert!(!expose.is_last());
+	}
+
+	#[test]
+	fn test_any_event_read_little_endian() {
+		let map = Map::read_from(&mut &STRUCTURE[3][1..]).unwrap();
+
+		let mut bytes = vec![];
+		map.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		bytes[0] |= SYNTHETIC_MASK;
+
+		// The sequence number and `event_window` are written least significant
+		// byte first.
+		assert_eq!(
+			bytes[..8],
+			[19 | SYNTHETIC_MASK, 0, 0x16, 0, 0x03, 0, 0x60, 0]
+		);
+
+		let buf = &mut Ordered::new(&bytes[..], ByteOrder::LittleEndian);
+		let (event, synthetic) = AnyEvent::read_from(buf).unwrap();
+
+		assert_eq!(buf.remaining(), 0, "not every byte was read");
+		assert!(synthetic);
+		assert!(matches!(event, AnyEvent::Map(event) if event.eq_including_sequence(&map)));
+
+		let read =
+			MaybeSynthetic::<AnyEvent>::read_from_ordered(&mut &bytes[..], ByteOrder::LittleEndian)
+				.unwrap();
+
+		assert!(read.synthetic);
+		assert!(matches!(read.event, AnyEvent::Map(event) if event.window == map.window));
+	}
+
+	#[test]
+	fn test_maybe_synthetic_write_little_endian() {
+		let map = Map::read_from(&mut &STRUCTURE[3][1..]).unwrap();
+
+		let mut expected = vec![];
+		map.write_to_ordered(&mut expected, ByteOrder::LittleEndian)
+			.unwrap();
+
+		for synthetic in [false, true] {
+			let mut bytes = vec![];
+			MaybeSynthetic {
+				event: Map::read_from(&mut &STRUCTURE[3][1..]).unwrap(),
+				synthetic,
+			}
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+
+			expected[0] = if synthetic { 19 | SYNTHETIC_MASK } else { 19 };
+			assert_eq!(bytes, expected);
+		}
+	}
+
+	#[test]
+	fn test_any_event_read_synthetic() {
+		let event = Selection {
+			sequence: 1,
+			time: CurrentableTime::CurrentTime,
+			requester: Window::new(0x0040_0001),
+			selection: Atom::new(1),
+			target_type: Atom::new(31),
+			property: Some(Atom::new(31)),
+		};
+
+		let mut bytes = vec![];
+		event.write_to(&mut bytes).unwrap();
+		bytes[0] |= SYNTHETIC_MASK;
+
+		let buf = &mut &bytes[..];
+		let (read, synthetic) = AnyEvent::read_from(buf).unwrap();
+
+		assert!(synthetic);
+		assert!(buf.is_empty(), "not every byte was read");
+		assert_eq!(read.code(), Selection::CODE);
+		assert!(matches!(read, AnyEvent::Selection(read) if read == event));
+	}
+
+	#[test]
+	fn test_maybe_synthetic_round_trip() {
+		let event = || Property {
+			sequence: 0x0102,
+			window: Window::new(0x0040_0001),
+			property: atom::WM_NAME,
+			time: Timestamp::new(0x0a0b_0c0d),
+			change: PropertyChange::Modified,
+		};
+
+		let mut expected = vec![];
+		event().write_to(&mut expected).unwrap();
+
+		for synthetic in [false, true] {
+			if synthetic {
+				expected[0] |= SYNTHETIC_MASK;
+			}
+
+			let wrapped = MaybeSynthetic {
+				event: event(),
+				synthetic,
+			};
+
+			let mut bytes = vec![];
+			wrapped.write_to(&mut bytes).unwrap();
+			assert_eq!(bytes, expected);
+
+			let buf = &mut &bytes[..];
+			assert_eq!(MaybeSynthetic::<Property>::read_from(buf).unwrap(), wrapped);
+			assert!(buf.is_empty(), "not every byte was read");
+
+			let read = MaybeSynthetic::<AnyEvent>::read_from(&mut &bytes[..]).unwrap();
+			assert_eq!(read.synthetic, synthetic);
+			assert!(matches!(&read.event, AnyEvent::Property(read) if *read == event()));
+
+			let mut written = vec![];
+			read.write_to(&mut written).unwrap();
+			assert_eq!(written, expected);
+		}
+	}
+
+	#[test]
+	fn test_maybe_synthetic_clears_flag() {
+		let mut bytes = [0xab; 32];
+		bytes[0] = 89 | SYNTHETIC_MASK;
+
+		let mut read = MaybeSynthetic::<AnyEvent>::read_from(&mut &bytes[..]).unwrap();
+		assert!(read.synthetic);
+
+		read.synthetic = false;
+
+		let mut written = vec![];
+		read.write_to(&mut written).unwrap();
+
+		assert_eq!(written[0], 89);
+		assert_eq!(written[1..], bytes[1..]);
+	}
+
+	#[test]
+	fn test_maybe_synthetic_wrong_code() {
+		let mut bytes = vec![];
+		KeyboardState { keys: [0; 31] }.write_to(&mut bytes).unwrap();
+		bytes[0] |= SYNTHETIC_MASK;
+
+		assert!(matches!(
+			MaybeSynthetic::<Property>::read_from(&mut &bytes[..]),
+			Err(ReadError::UnrecognizedDiscriminant { discriminant: 11, .. }),
+		));
+	}
+
+	#[test]
+	fn test_any_event_unknown_code() {
+		let mut bytes = [0xab; 32];
+		bytes[0] = 89;
+
+		let (read, synthetic) = AnyEvent::read_from(&mut &bytes[..]).unwrap();
+
+		assert!(!synthetic);
+		assert_eq!(read.code(), 89);
+		assert!(matches!(read, AnyEvent::Unknown(UnknownEvent { bytes: read }) if read == bytes));
+
+		let mut written = vec![];
+		read.write_to(&mut written).unwrap();
+
+		assert_eq!(written, bytes);
+	}
+
+	#[test]
+	fn test_event_eq_ignores_sequence() {
+		let destroy = |sequence| Destroy {
+			sequence,
+			event_window: Window::new(0x0040_0001),
+			window: Window::new(0x0040_0002),
+		};
+
+		assert_eq!(destroy(1), destroy(2));
+		assert!(!destroy(1).eq_including_sequence(&destroy(2)));
+		assert!(destroy(1).eq_including_sequence(&destroy(1)));
+	}
+}
@@ -0,0 +1,1033 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A registry of the [requests], events, and errors defined in the core X11
+//! protocol, so that they can be identified by their opcodes and codes at
+//! runtime.
+//!
+//! This is useful for tools such as proxies and loggers, which must determine
+//! the type of a [request] from its major opcode before it can be read, or
+//! which want to name an opcode or code in their output.
+//!
+//! The opcodes, codes, [reply] associations, and errors of every entry are
+//! taken from the [`Request`], [`Event`], and [`Error`] implementations of the
+//! messages, so that the registry cannot differ from them.
+//!
+//! Every message type which can be read on its own, including [replies],
+//! events, and errors, is also listed by the [`all_message_types!`] macro, so
+//! that code can be generated for each of them.
+//!
+//! [request]: Request
+//! [requests]: Request
+//! [reply]: crate::message::Reply
+//! [replies]: crate::message::Reply
+//!
+//! [`all_message_types!`]: crate::all_message_types
+//! [`Event`]: crate::message::Event
+//! [`Error`]: crate::message::Error
+
+use std::{
+	any::{Any, TypeId},
+	sync::OnceLock,
+};
+
+use xrbk::{Buf, ConstantX11Size, ReadResult, Readable};
+
+use crate::{
+	message::{Error, ErrorCodes, Event, Request},
+	x11::{event::SYNTHETIC_MASK, request::*},
+};
+
+/// Information about a type of [request] which is available at runtime.
+///
+/// This is returned by [`lookup_request`].
+///
+/// [request]: Request
+#[derive(Copy, Clone, Debug)]
+pub struct RequestDescriptor {
+	/// The name of the [request]'s type.
+	///
+	/// [request]: Request
+	pub name: &'static str,
+
+	/// The [request]'s [major opcode].
+	///
+	/// [request]: Request
+	/// [major opcode]: Request::MAJOR_OPCODE
+	pub major_opcode: u8,
+	/// The [request]'s [minor opcode], if any.
+	///
+	/// [request]: Request
+	/// [minor opcode]: Request::MINOR_OPCODE
+	pub minor_opcode: Option<u16>,
+
+	/// Whether the [request] generates a [reply].
+	///
+	/// [request]: Request
+	/// [reply]: crate::message::Reply
+	pub has_reply: bool,
+	/// The [codes] of the [errors] which the [request] may generate, other
+	/// than the [`Alloc`], [`Implementation`], and [`Length`] errors which
+	/// any [request] may generate.
+	///
+	/// These are the [codes] of the [request]'s [`OtherErrors`].
+	///
+	/// [request]: Request
+	/// [codes]: Error::CODE
+	/// [errors]: Error
+	///
+	/// [`OtherErrors`]: Request::OtherErrors
+	/// [`Alloc`]: crate::x11::error::Alloc
+	/// [`Implementation`]: crate::x11::error::Implementation
+	/// [`Length`]: crate::x11::error::Length
+	pub errors: &'static [u8],
+
+	/// The minimum [`length()`] of the [request], in 4-byte units.
+	///
+	/// [request]: Request
+	/// [`length()`]: Request::length
+	pub min_length: u16,
+
+	/// Reads the [request], returning it as a [`Box<dyn Any>`] which can be
+	/// downcast to the [request]'s type.
+	///
+	/// Like the [`Readable`] implementations of [requests], this expects the
+	/// major opcode to have already been read.
+	///
+	/// This is [`None`] if the [request]'s type cannot be determined from its
+	/// opcodes alone: for example, a [`ChangeKeyboardMapping` request] is
+	/// generic over the number of [keysyms] per [keycode].
+	///
+	/// A [`SendEvent` request] is read as a
+	/// <code>[SendEvent]<[AnyEvent]></code>.
+	///
+	/// [request]: Request
+	/// [requests]: Request
+	/// [keysyms]: crate::Keysym
+	/// [keycode]: crate::Keycode
+	///
+	/// [`ChangeKeyboardMapping` request]: ChangeKeyboardMapping
+	/// [`SendEvent` request]: SendEvent
+	/// [AnyEvent]: crate::x11::event::AnyEvent
+	pub read: Option<ReadRequest>,
+}
+
+/// A function which reads a [request] of a particular type, returning it as a
+/// [`Box<dyn Any>`].
+///
+/// See [`RequestDescriptor::read`] for more information.
+///
+/// [request]: Request
+pub type ReadRequest = fn(&mut dyn Buf) -> ReadResult<Box<dyn Any>>;
+
+impl RequestDescriptor {
+	fn new<Req>(name: &'static str, min_length: u16) -> Self
+	where
+		Req: Request + Readable + 'static,
+		Req::OtherErrors: ErrorCodes,
+		Req::Reply: 'static,
+	{
+		Self {
+			name,
+
+			major_opcode: Req::MAJOR_OPCODE,
+			minor_opcode: Req::MINOR_OPCODE,
+
+			has_reply: TypeId::of::<Req::Reply>() != TypeId::of::<()>(),
+			errors: Req::OtherErrors::CODES,
+
+			min_length,
+
+			read: Some(|mut buf| Ok(Box::new(Req::read_from(&mut buf)?))),
+		}
+	}
+}
+
+/// Returns the [`RequestDescriptor`] for the [request] with the given opcodes,
+/// if it is defined in the core X11 protocol.
+///
+/// [Requests] defined in the core X11 protocol have no minor opcode, so
+/// `minor_opcode` must be [`None`] to find them.
+///
+/// [request]: Request
+/// [Requests]: Request
+#[must_use]
+pub fn lookup_request(major_opcode: u8, minor_opcode: Option<u8>) -> Option<RequestDescriptor> {
+	core_requests()
+		.iter()
+		.find(|descriptor| {
+			descriptor.major_opcode == major_opcode
+				&& descriptor.minor_opcode == minor_opcode.map(u16::from)
+		})
+		.copied()
+}
+
+/// Returns the name of the [request] with the given opcodes, if it is defined
+/// in the core X11 protocol.
+///
+/// See [`lookup_request`] for more information.
+///
+/// # Examples
+/// ```
+/// use xrb::x11::registry::request_name;
+///
+/// assert_eq!(request_name(28, None), Some("GrabButton"));
+/// assert_eq!(request_name(200, None), None);
+/// ```
+///
+/// [request]: Request
+#[must_use]
+pub fn request_name(major_opcode: u8, minor_opcode: Option<u8>) -> Option<&'static str> {
+	lookup_request(major_opcode, minor_opcode).map(|descriptor| descriptor.name)
+}
+
+/// Information about a type of [event] which is available at runtime.
+///
+/// See [`CORE_EVENTS`].
+///
+/// [event]: Event
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EventDescriptor {
+	/// The name of the [event]'s type.
+	///
+	/// [event]: Event
+	pub name: &'static str,
+	/// The [event]'s [code].
+	///
+	/// [event]: Event
+	/// [code]: Event::CODE
+	pub code: u8,
+}
+
+/// Information about a type of [error] which is available at runtime.
+///
+/// See [`CORE_ERRORS`].
+///
+/// [error]: Error
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorDescriptor {
+	/// The name of the [error]'s type.
+	///
+	/// [error]: Error
+	pub name: &'static str,
+	/// The [error]'s [code].
+	///
+	/// [error]: Error
+	/// [code]: Error::CODE
+	pub code: u8,
+}
+
+/// Generates [`CORE_EVENTS`] and [`CORE_ERRORS`] from the lists given by
+/// [`all_message_types!`].
+///
+/// [`all_message_types!`]: crate::all_message_types
+macro_rules! code_descriptors {
+	(
+		requests: [$($Request:ident),+$(,)?],
+		replies: [$($Reply:ident),+$(,)?],
+		events: [$($Event:ident),+$(,)?],
+		errors: [$($Error:ident),+$(,)?]$(,)?
+	) => {
+		/// Every [event] defined in the core X11 protocol, in order of their
+		/// [codes].
+		///
+		/// [event]: Event
+		/// [codes]: Event::CODE
+		pub const CORE_EVENTS: &[EventDescriptor] = &[$(
+			EventDescriptor {
+				name: stringify!($Event),
+				code: <crate::x11::event::$Event as Event>::CODE,
+			},
+		)+];
+
+		/// Every [error] defined in the core X11 protocol, in order of their
+		/// [codes].
+		///
+		/// [error]: Error
+		/// [codes]: Error::CODE
+		pub const CORE_ERRORS: &[ErrorDescriptor] = &[$(
+			ErrorDescriptor {
+				name: stringify!($Error),
+				code: <crate::x11::error::$Error as Error>::CODE,
+			},
+		)+];
+	};
+}
+
+crate::all_message_types!(code_descriptors);
+
+/// Returns the name of the [event] with the given [code], if it is defined in
+/// the core X11 protocol.
+///
+/// The most significant bit of the [code], which is set for [events] sent with
+/// a [`SendEvent` request], is ignored.
+///
+/// # Examples
+/// ```
+/// use xrb::x11::registry::event_name;
+///
+/// assert_eq!(event_name(2), Some("KeyPress"));
+/// assert_eq!(event_name(2 | 0x80), Some("KeyPress"));
+/// assert_eq!(event_name(64), None);
+/// ```
+///
+/// [event]: Event
+/// [events]: Event
+/// [code]: Event::CODE
+/// [`SendEvent` request]: SendEvent
+#[must_use]
+pub fn event_name(code: u8) -> Option<&'static str> {
+	CORE_EVENTS
+		.iter()
+		.find(|descriptor| descriptor.code == code & !SYNTHETIC_MASK)
+		.map(|descriptor| descriptor.name)
+}
+
+/// Returns the name of the [error] with the given [code], if it is defined in
+/// the core X11 protocol.
+///
+/// # Examples
+/// ```
+/// use xrb::x11::registry::error_name;
+///
+/// assert_eq!(error_name(3), Some("Window"));
+/// assert_eq!(error_name(128), None);
+/// ```
+///
+/// [error]: Error
+/// [code]: Error::CODE
+#[must_use]
+pub fn error_name(code: u8) -> Option<&'static str> {
+	CORE_ERRORS
+		.iter()
+		.find(|descriptor| descriptor.code == code)
+		.map(|descriptor| descriptor.name)
+}
+
+/// Invokes the given macro with the name of every message type defined in the
+/// core X11 protocol which can be read on its own.
+///
+/// This is the list of types which generic tooling, such as fuzzers, loggers,
+/// and dispatchers like [`AnyRequest`] and [`AnyEvent`], needs to cover. The
+/// given macro is invoked like so:
+/// ```ignore
+/// callback! {
+///     requests: [CreateWindow, ChangeWindowAttributes, ..., NoOp],
+///     replies: [GetWindowAttributes, GetGeometry, ..., GetModifierMapping],
+///     events: [KeyPress, KeyRelease, ..., MappingChange],
+///     errors: [Request, Value, ..., Implementation],
+/// }
+/// ```
+/// Each name refers to a type in [`request`], [`reply`], [`event`], or
+/// [`error`] respectively. [Requests] and [replies] share names, so the names
+/// must be qualified with their modules.
+///
+/// [`ChangeKeyboardMapping`] is not included, because its type depends on
+/// more than its opcode, and so it cannot be read on its own.
+///
+/// `derive_xrb!` registers every message it defines when XRB is tested, and
+/// XRB's tests fail if a registered message is missing from this list, so a
+/// new message cannot be left out of it.
+///
+/// # Examples
+/// ```
+/// use xrb::all_message_types;
+///
+/// macro_rules! count_events {
+///     (
+///         requests: [$($Request:ident),+$(,)?],
+///         replies: [$($Reply:ident),+$(,)?],
+///         events: [$($Event:ident),+$(,)?],
+///         errors: [$($Error:ident),+$(,)?]$(,)?
+///     ) => {
+///         [$(stringify!($Event)),+].len()
+///     };
+/// }
+///
+/// assert_eq!(all_message_types!(count_events), 33);
+/// ```
+///
+/// [Requests]: crate::message::Request
+/// [replies]: crate::message::Reply
+///
+/// [`request`]: crate::x11::request
+/// [`reply`]: crate::x11::reply
+/// [`event`]: crate::x11::event
+/// [`error`]: crate::x11::error
+///
+/// [`AnyRequest`]: crate::message::AnyRequest
+/// [`AnyEvent`]: crate::x11::event::AnyEvent
+/// [`ChangeKeyboardMapping`]: crate::x11::request::ChangeKeyboardMapping
+#[macro_export]
+macro_rules! all_message_types {
+	($($callback:ident)::+) => {
+		$($callback)::+! {
+			requests: [
+				CreateWindow,
+				ChangeWindowAttributes,
+				GetWindowAttributes,
+				DestroyWindow,
+				DestroyChildren,
+				ChangeSavedWindows,
+				ReparentWindow,
+				MapWindow,
+				MapChildren,
+				UnmapWindow,
+				UnmapChildren,
+				ConfigureWindow,
+				CirculateWindow,
+				GetGeometry,
+				QueryWindowTree,
+				GetAtom,
+				GetAtomName,
+				ModifyProperty,
+				DeleteProperty,
+				GetProperty,
+				ListProperties,
+				SetSelectionOwner,
+				GetSelectionOwner,
+				ConvertSelection,
+				SendEvent,
+				GrabCursor,
+				UngrabCursor,
+				GrabButton,
+				UngrabButton,
+				ChangeActiveCursorGrab,
+				GrabKeyboard,
+				UngrabKeyboard,
+				GrabKey,
+				UngrabKey,
+				AllowEvents,
+				GrabServer,
+				UngrabServer,
+				QueryCursorLocation,
+				GetMotionHistory,
+				ConvertCoordinates,
+				WarpCursor,
+				SetFocus,
+				GetFocus,
+				QueryKeyboard,
+				AssignFont,
+				UnassignFont,
+				QueryFont,
+				QueryTextExtents,
+				ListFonts,
+				ListFontsWithInfo,
+				SetFontSearchDirectories,
+				GetFontSearchDirectories,
+				CreatePixmap,
+				FreePixmap,
+				CreateGraphicsContext,
+				ChangeGraphicsOptions,
+				CopyGraphicsOptions,
+				SetDashes,
+				SetClipRectangles,
+				DestroyGraphicsContext,
+				ClearArea,
+				CopyArea,
+				CopyBitPlane,
+				DrawPoints,
+				DrawPath,
+				DrawLines,
+				DrawRectangles,
+				DrawArcs,
+				FillPolygon,
+				FillRectangles,
+				FillArcs,
+				PlaceImage,
+				CaptureImage,
+				DrawText8,
+				DrawText16,
+				ImageText8,
+				ImageText16,
+				CreateColormap,
+				DestroyColormap,
+				MoveColormap,
+				InstallColormap,
+				UninstallColormap,
+				ListInstalledColormaps,
+				AllocateColor,
+				AllocateNamedColor,
+				AllocateColorCells,
+				AllocateColorPlanes,
+				DestroyColormapEntries,
+				StoreColors,
+				StoreNamedColor,
+				QueryColors,
+				GetNamedColor,
+				CreateCursorAppearance,
+				CreateGlyphCursorAppearance,
+				DestroyCursorAppearance,
+				RecolorCursorAppearance,
+				QueryIdealDimensions,
+				QueryExtension,
+				ListExtensions,
+				GetKeyboardMapping,
+				ChangeKeyboardOptions,
+				GetKeyboardOptions,
+				RingBell,
+				ChangeCursorOptions,
+				GetCursorOptions,
+				SetScreenSaver,
+				GetScreenSaver,
+				ChangeHosts,
+				QueryAccessControl,
+				SetAccessControl,
+				SetRetainResourcesMode,
+				KillClient,
+				RotateProperties,
+				ForceScreenSaver,
+				SetButtonMapping,
+				GetButtonMapping,
+				SetModifierMapping,
+				GetModifierMapping,
+				NoOp,
+			],
+			replies: [
+				GetWindowAttributes,
+				GetGeometry,
+				QueryWindowTree,
+				GetAtom,
+				GetAtomName,
+				GetProperty,
+				ListProperties,
+				GetSelectionOwner,
+				GrabCursor,
+				GrabKeyboard,
+				QueryCursorLocation,
+				GetMotionHistory,
+				ConvertCoordinates,
+				GetFocus,
+				QueryKeyboard,
+				QueryFont,
+				QueryTextExtents,
+				ListFonts,
+				ListFontsWithInfo,
+				GetFontSearchDirectories,
+				CaptureImage,
+				ListInstalledColormaps,
+				AllocateColor,
+				AllocateNamedColor,
+				AllocateColorCells,
+				AllocateColorPlanes,
+				QueryColors,
+				GetNamedColor,
+				QueryIdealDimensions,
+				QueryExtension,
+				ListExtensions,
+				GetKeyboardMapping,
+				GetKeyboardOptions,
+				GetCursorOptions,
+				GetScreenSaver,
+				QueryAccessControl,
+				SetButtonMapping,
+				GetButtonMapping,
+				SetModifierMapping,
+				GetModifierMapping,
+			],
+			events: [
+				KeyPress,
+				KeyRelease,
+				ButtonPress,
+				ButtonRelease,
+				Motion,
+				EnterWindow,
+				LeaveWindow,
+				Focus,
+				Unfocus,
+				KeyboardState,
+				Expose,
+				GraphicsExposure,
+				NoExposure,
+				Visibility,
+				Create,
+				Destroy,
+				Unmap,
+				Map,
+				MapWindowRequest,
+				Reparent,
+				Configure,
+				ConfigureWindowRequest,
+				Gravity,
+				ResizeRequest,
+				Circulate,
+				CirculateWindowRequest,
+				Property,
+				SelectionClear,
+				ConvertSelectionRequest,
+				Selection,
+				Colormap,
+				ClientMessage,
+				MappingChange,
+			],
+			errors: [
+				Request,
+				Value,
+				Window,
+				Pixmap,
+				Atom,
+				CursorAppearance,
+				Font,
+				Match,
+				Drawable,
+				Access,
+				Alloc,
+				Colormap,
+				GraphicsContext,
+				ResourceIdChoice,
+				Name,
+				Length,
+				Implementation,
+			],
+		}
+	};
+}
+
+/// Generates a [`RequestDescriptor`] for each of the given [requests].
+///
+/// The minimum [`length()`] of each [request] may be given in 4-byte units,
+/// following the [request]'s type. If it is not given, the [request] must
+/// implement [`ConstantX11Size`], and its [`X11_SIZE`] is used instead.
+///
+/// [request]: Request
+/// [requests]: Request
+/// [`length()`]: Request::length
+/// [`X11_SIZE`]: ConstantX11Size::X11_SIZE
+macro_rules! descriptors {
+	($($Request:ident $(: $min_length:literal)?),+$(,)?) => {
+		vec![$(
+			RequestDescriptor::new::<$Request>(
+				stringify!($Request),
+				descriptors!(@min_length $Request $(: $min_length)?),
+			),
+		)+]
+	};
+
+	(@min_length $Request:ident) => {
+		// Request sizes are limited to `u16::MAX` 4-byte units.
+		#[allow(clippy::cast_possible_truncation)]
+		{
+			(<$Request as ConstantX11Size>::X11_SIZE / 4) as u16
+		}
+	};
+	(@min_length $Request:ident: $min_length:literal) => {
+		$min_length
+	};
+}
+
+/// Returns the [`RequestDescriptor`] of every [request] defined in the core X11
+/// protocol, in order of their major opcodes.
+///
+/// [request]: Request
+#[allow(deprecated, clippy::too_many_lines)]
+#[must_use]
+pub fn core_requests() -> &'static [RequestDescriptor] {
+	static CORE_REQUESTS: OnceLock<Vec<RequestDescriptor>> = OnceLock::new();
+
+	CORE_REQUESTS.get_or_init(|| {
+		// The minimum lengths which are given are those listed in the X11
+		// protocol specification for each request with all lists empty.
+		let mut descriptors = descriptors! {
+			CreateWindow: 8,
+			ChangeWindowAttributes: 3,
+			GetWindowAttributes,
+			DestroyWindow,
+			DestroyChildren,
+			ChangeSavedWindows,
+			ReparentWindow,
+			MapWindow,
+			MapChildren,
+			UnmapWindow,
+			UnmapChildren,
+			ConfigureWindow: 3,
+			CirculateWindow,
+			GetGeometry,
+			QueryWindowTree,
+			GetAtom: 2,
+			GetAtomName,
+			ModifyProperty: 6,
+			DeleteProperty,
+			GetProperty,
+			ListProperties,
+			SetSelectionOwner,
+			GetSelectionOwner,
+			ConvertSelection,
+			SendEvent,
+			GrabCursor,
+			UngrabCursor,
+			GrabButton,
+			UngrabButton,
+			ChangeActiveCursorGrab,
+			GrabKeyboard,
+			UngrabKeyboard,
+			GrabKey,
+			UngrabKey,
+			AllowEvents,
+			GrabServer,
+			UngrabServer,
+			QueryCursorLocation,
+			GetMotionHistory,
+			ConvertCoordinates,
+			WarpCursor,
+			SetFocus,
+			GetFocus,
+			QueryKeyboard,
+			AssignFont: 3,
+			UnassignFont,
+			QueryFont,
+			QueryTextExtents: 2,
+			ListFonts: 2,
+			ListFontsWithInfo: 2,
+			SetFontSearchDirectories: 2,
+			GetFontSearchDirectories,
+			CreatePixmap,
+			FreePixmap,
+			CreateGraphicsContext: 4,
+			ChangeGraphicsOptions: 3,
+			CopyGraphicsOptions,
+			SetDashes: 3,
+			SetClipRectangles: 3,
+			DestroyGraphicsContext,
+			ClearArea,
+			CopyArea,
+			CopyBitPlane,
+			DrawPoints: 3,
+			DrawPath: 3,
+			DrawLines: 3,
+			DrawRectangles: 3,
+			DrawArcs: 3,
+			FillPolygon: 4,
+			FillRectangles: 3,
+			FillArcs: 3,
+			PlaceImage: 6,
+			CaptureImage: 5,
+			DrawText8: 4,
+			DrawText16: 4,
+			ImageText8: 4,
+			ImageText16: 4,
+			CreateColormap: 4,
+			DestroyColormap: 2,
+			MoveColormap,
+			InstallColormap: 2,
+			UninstallColormap: 2,
+			ListInstalledColormaps: 2,
+			AllocateColor: 4,
+			AllocateNamedColor: 3,
+			AllocateColorCells: 3,
+			AllocateColorPlanes: 4,
+			DestroyColormapEntries: 3,
+			StoreColors: 2,
+			StoreNamedColor: 4,
+			QueryColors: 2,
+			GetNamedColor: 3,
+			CreateCursorAppearance: 8,
+			CreateGlyphCursorAppearance: 8,
+			DestroyCursorAppearance: 2,
+			RecolorCursorAppearance: 5,
+			QueryIdealDimensions: 3,
+			QueryExtension: 2,
+			ListExtensions,
+			GetKeyboardMapping: 2,
+			ChangeKeyboardOptions: 2,
+			GetKeyboardOptions: 1,
+			RingBell: 1,
+			ChangeCursorOptions: 3,
+			GetCursorOptions: 1,
+			SetScreenSaver,
+			GetScreenSaver,
+			ChangeHosts: 2,
+			QueryAccessControl: 1,
+			SetAccessControl: 1,
+			SetRetainResourcesMode,
+			KillClient,
+			RotateProperties: 3,
+			ForceScreenSaver: 1,
+			SetButtonMapping: 1,
+			GetButtonMapping: 1,
+			SetModifierMapping: 1,
+			GetModifierMapping: 1,
+			NoOp: 1,
+		};
+
+		// The type of this request depends on more than its opcode, so it
+		// cannot be read.
+		descriptors.push(RequestDescriptor {
+			name: "ChangeKeyboardMapping",
+
+			major_opcode: 100,
+			minor_opcode: None,
+
+			has_reply: false,
+			errors: <ChangeKeyboardMapping<1> as Request>::OtherErrors::CODES,
+
+			min_length: 2,
+
+			read: None,
+		});
+		descriptors.sort_by_key(|descriptor| descriptor.major_opcode);
+
+		descriptors
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::message::__private::{inventory, MessageKind, RegisteredMessage};
+	use proptest::{prop_assert, prop_assert_eq, test_runner::TestCaseError};
+	use std::{collections::HashSet, fmt::Debug};
+	use xrbk::Writable;
+
+	/// Asserts that the given `request` is read back unchanged when it is
+	/// identified by its major opcode with [`lookup_request`], as a proxy
+	/// intercepting requests would, and that its header is consistent with
+	/// its [`RequestDescriptor`].
+	fn assert_read_through_registry<Req>(request: &Req) -> Result<(), TestCaseError>
+	where
+		Req: Request + Writable + PartialEq + Debug + 'static,
+	{
+		let mut bytes = vec![];
+		request
+			.write_to(&mut bytes)
+			.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+		let descriptor = lookup_request(bytes[0], None)
+			.ok_or_else(|| TestCaseError::fail("request is not registered"))?;
+		let length = u16::from_be_bytes([bytes[2], bytes[3]]);
+
+		prop_assert_eq!(usize::from(length) * 4, bytes.len());
+		prop_assert!(length >= descriptor.min_length);
+
+		let read = (descriptor.read.unwrap())(&mut &bytes[1..])
+			.map_err(|error| TestCaseError::fail(error.to_string()))?;
+
+		prop_assert_eq!(read.downcast_ref::<Req>(), Some(request));
+
+		Ok(())
+	}
+
+	proptest::proptest! {
+		#[test]
+		fn test_registry_reads_grab_cursor(request: GrabCursor) {
+			assert_read_through_registry(&request)?;
+		}
+
+		#[test]
+		fn test_registry_reads_warp_cursor(request: WarpCursor) {
+			assert_read_through_registry(&request)?;
+		}
+
+		#[test]
+		fn test_registry_reads_set_button_mapping(request: SetButtonMapping) {
+			assert_read_through_registry(&request)?;
+		}
+
+		#[test]
+		fn test_registry_reads_set_modifier_mapping(request: SetModifierMapping) {
+			assert_read_through_registry(&request)?;
+		}
+	}
+
+	#[test]
+	fn test_every_core_request_is_registered() {
+		for major_opcode in (1..=119).chain([127]) {
+			let descriptor = lookup_request(major_opcode, None);
+
+			assert!(
+				descriptor.is_some(),
+				"no request registered for major opcode {major_opcode}"
+			);
+			assert_eq!(descriptor.unwrap().major_opcode, major_opcode);
+		}
+
+		assert_eq!(core_requests().len(), 120);
+	}
+
+	#[test]
+	fn test_lookup_request_reads_request() {
+		let request = GetFocus;
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		let descriptor = lookup_request(bytes[0], None).unwrap();
+
+		assert_eq!(descriptor.name, "GetFocus");
+		assert!(descriptor.has_reply);
+		assert_eq!(descriptor.min_length, 1);
+
+		let read = (descriptor.read.unwrap())(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(read.downcast_ref::<GetFocus>(), Some(&request));
+	}
+
+	#[test]
+	fn test_lookup_request_without_reply() {
+		let descriptor = lookup_request(GrabServer::MAJOR_OPCODE, None).unwrap();
+
+		assert_eq!(descriptor.name, "GrabServer");
+		assert!(!descriptor.has_reply);
+	}
+
+	/// Asserts that writing what is read from `bytes[skip..]` and reading it
+	/// back again reads an equal message.
+	///
+	/// `skip` is the number of bytes at the start of the message which are
+	/// read before the type of the message is known.
+	fn assert_fixpoint<T: Readable + Writable + PartialEq + Debug>(
+		name: &str, bytes: &[u8], skip: usize,
+	) {
+		let Ok(message) = T::read_from(&mut &bytes[skip..]) else {
+			return;
+		};
+
+		let mut written = vec![];
+		if message.write_to(&mut written).is_err() {
+			return;
+		}
+
+		let reread = T::read_from(&mut &written[skip..])
+			.unwrap_or_else(|error| panic!("{name} could not be read back: {error}"));
+
+		assert_eq!(message, reread, "{name} changed when read back");
+	}
+
+	macro_rules! assert_all_fixpoints {
+		(
+			requests: [$($Request:ident),+$(,)?],
+			replies: [$($Reply:ident),+$(,)?],
+			events: [$($Event:ident),+$(,)?],
+			errors: [$($Error:ident),+$(,)?]$(,)?
+		) => {
+			#[allow(deprecated)]
+			fn assert_all_fixpoints(bytes: &[u8]) {
+				use crate::x11::{error, event, reply, request};
+
+				$(assert_fixpoint::<request::$Request>(stringify!($Request), bytes, 1);)+
+				$(assert_fixpoint::<reply::$Reply>(stringify!($Reply), bytes, 1);)+
+				$(assert_fixpoint::<event::$Event>(stringify!($Event), bytes, 1);)+
+				$(assert_fixpoint::<error::$Error>(stringify!($Error), bytes, 2);)+
+			}
+
+			#[test]
+			fn test_all_message_types_are_registered() {
+				$(
+					assert!(
+						core_requests().iter().any(|descriptor| {
+							descriptor.name == stringify!($Request)
+						}),
+						"{} is not registered",
+						stringify!($Request),
+					);
+				)+
+
+				assert_eq!([$(stringify!($Request)),+].len(), core_requests().len() - 1);
+			}
+
+			#[test]
+			fn test_all_message_types_lists_every_message() {
+				let listed: HashSet<_> = [
+					$((MessageKind::Request, stringify!($Request)),)+
+					$((MessageKind::Reply, stringify!($Reply)),)+
+					$((MessageKind::Event, stringify!($Event)),)+
+					$((MessageKind::Error, stringify!($Error)),)+
+				]
+				.into_iter()
+				.collect();
+
+				let registered: HashSet<_> = inventory::iter::<RegisteredMessage>
+					.into_iter()
+					.filter(|message| message.module_path.starts_with("xrb::x11::"))
+					.map(|message| (message.kind, message.name))
+					// `ChangeKeyboardMapping` cannot be read on its own.
+					.filter(|&message| message != (MessageKind::Request, "ChangeKeyboardMapping"))
+					.collect();
+
+				assert_eq!(
+					registered.difference(&listed).collect::<Vec<_>>(),
+					Vec::<&(MessageKind, &str)>::new(),
+					"messages are missing from `all_message_types!`",
+				);
+				assert_eq!(
+					listed.difference(&registered).collect::<Vec<_>>(),
+					Vec::<&(MessageKind, &str)>::new(),
+					"`all_message_types!` lists messages which are not registered",
+				);
+			}
+		};
+	}
+
+	crate::all_message_types!(assert_all_fixpoints);
+
+	#[test]
+	fn test_all_message_types_read_write_fixpoint() {
+		let mut counting = [0; 256];
+		for (i, byte) in counting.iter_mut().enumerate() {
+			*byte = i as u8;
+		}
+
+		for bytes in [[0; 256], [1; 256], [0xff; 256], counting] {
+			assert_all_fixpoints(&bytes);
+		}
+	}
+
+	#[test]
+	fn test_request_names() {
+		assert_eq!(request_name(1, None), Some("CreateWindow"));
+		assert_eq!(request_name(28, None), Some("GrabButton"));
+		assert_eq!(request_name(100, None), Some("ChangeKeyboardMapping"));
+		assert_eq!(request_name(127, None), Some("NoOp"));
+
+		assert_eq!(request_name(0, None), None);
+		assert_eq!(request_name(120, None), None);
+		assert_eq!(request_name(28, Some(0)), None);
+	}
+
+	#[test]
+	fn test_event_and_error_names() {
+		assert_eq!(event_name(2), Some("KeyPress"));
+		assert_eq!(event_name(28), Some("Property"));
+		assert_eq!(event_name(34), Some("MappingChange"));
+		// Sent with a `SendEvent` request.
+		assert_eq!(event_name(33 | 0x80), Some("ClientMessage"));
+		assert_eq!(event_name(0), None);
+		assert_eq!(event_name(35), None);
+
+		assert_eq!(error_name(1), Some("Request"));
+		assert_eq!(error_name(8), Some("Match"));
+		assert_eq!(error_name(17), Some("Implementation"));
+		assert_eq!(error_name(0), None);
+		assert_eq!(error_name(18), None);
+	}
+
+	#[test]
+	fn test_core_codes_are_in_order() {
+		assert!(core_requests()
+			.windows(2)
+			.all(|pair| pair[0].major_opcode < pair[1].major_opcode));
+
+		assert!(CORE_EVENTS.iter().map(|event| event.code).eq(2..=34));
+		assert!(CORE_ERRORS.iter().map(|error| error.code).eq(1..=17));
+	}
+
+	#[test]
+	fn test_request_errors() {
+		let errors = |major_opcode| lookup_request(major_opcode, None).unwrap().errors;
+
+		// `GetFocus` generates no other errors.
+		assert!(errors(GetFocus::MAJOR_OPCODE).is_empty());
+		// `DestroyWindow` generates only `Window` errors.
+		assert_eq!(errors(DestroyWindow::MAJOR_OPCODE), [3]);
+		// `RotateProperties` generates `Atom`, `Match`, and `Window` errors.
+		assert_eq!(errors(RotateProperties::MAJOR_OPCODE), [5, 8, 3]);
+		// `ChangeKeyboardMapping` generates `Value` errors.
+		assert_eq!(errors(100), [2]);
+	}
+
+	#[test]
+	fn test_lookup_request_with_minor_opcode() {
+		assert!(lookup_request(GrabServer::MAJOR_OPCODE, Some(0)).is_none());
+		assert!(lookup_request(128, None).is_none());
+	}
+}
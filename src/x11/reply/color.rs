@@ -35,6 +35,8 @@ derive_xrb! {
 	///
 	/// [`ListInstalledColormaps` request]: request::ListInstalledColormaps
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ListInstalledColormaps: Reply for request::ListInstalledColormaps {
 		/// The sequence number identifying the [request] that generated this
@@ -47,7 +49,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `colormaps`.
@@ -63,7 +64,7 @@ derive_xrb! {
 		/// This list has no indication as to which [colormaps] are contained in
 		/// the [screen]'s list of required [colormaps].
 		///
-		/// [colormaps]: Colormaps
+		/// [colormaps]: Colormap
 		/// [screen]: crate::visual::Screen
 		#[context(colormaps_len => usize::from(*colormaps_len))]
 		pub colormaps: Vec<Colormap>,
@@ -76,6 +77,8 @@ derive_xrb! {
 	/// [`AllocateColor` request]: request::AllocateColor
 	#[doc(alias("AllocColor"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct AllocateColor: Reply for request::AllocateColor {
 		/// The sequence number identifying the [request] that generated this
@@ -88,7 +91,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The actual RGB values that were allocated.
@@ -99,6 +101,7 @@ derive_xrb! {
 		[_; 2],
 
 		/// The [`ColorId`] referring to the `actual_color`.
+		#[doc(alias("pixel"))]
 		pub color_id: ColorId,
 		[_; ..],
 	}
@@ -110,6 +113,8 @@ derive_xrb! {
 	/// [`AllocateNamedColor` request]: request::AllocateNamedColor
 	#[doc(alias("AllocNamedColor"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct AllocateNamedColor: Reply for request::AllocateNamedColor {
 		/// The sequence number identifying the [request] that generated this
@@ -122,12 +127,17 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
+		/// The [`ColorId`] referring to the allocated `actual_color`.
+		#[doc(alias("pixel"))]
+		pub color_id: ColorId,
+
 		/// The ideal or 'true' color which the name represents.
+		#[doc(alias("exact"))]
 		pub ideal_color: RgbColor,
 		/// The closest color that the display was able to provide.
+		#[doc(alias("visual"))]
 		pub actual_color: RgbColor,
 		[_; ..],
 	}
@@ -139,6 +149,8 @@ derive_xrb! {
 	/// [`AllocateColorCells` request]: request::AllocateColorCells
 	#[doc(alias("AllocColorCells"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct AllocateColorCells: Reply for request::AllocateColorCells {
 		/// The sequence number identifying the [request] that generated this
@@ -151,7 +163,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `colors`.
@@ -197,6 +208,8 @@ derive_xrb! {
 	/// [`AllocateColorPlanes` request]: request::AllocateColorPlanes
 	#[doc(alias("AllocColorPlanes"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct AllocateColorPlanes: Reply for request::AllocateColorPlanes {
 		/// The sequence number identifying the [request] that generated this
@@ -209,7 +222,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `colors`.
@@ -249,6 +261,9 @@ derive_xrb! {
 ///
 /// [`QueryColors` request]: request::QueryColors
 #[derive(Derivative, Debug)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Hash, PartialEq, Eq)]
 pub struct QueryColors {
 	/// The sequence number identifying the [request] that generated this
@@ -273,39 +288,49 @@ pub struct QueryColors {
 	pub colors: Vec<RgbColor>,
 }
 
+impl crate::message::__private::Sealed for QueryColors {}
+crate::message::register_message!(Reply QueryColors);
+
 impl Reply for QueryColors {
 	type Request = request::QueryColors;
 
 	fn sequence(&self) -> u16 {
 		self.sequence
 	}
+
+	fn read_with_request(buf: &mut impl Buf, _request: &Self::Request) -> ReadResult<Self> {
+		Self::read_from(buf)
+	}
 }
 
 impl X11Size for QueryColors {
 	fn x11_size(&self) -> usize {
 		const HEADER: usize = 8;
 
-		HEADER + u16::X11_SIZE + 22 + self.colors.x11_size()
+		// Each color is followed by 2 unused bytes.
+		HEADER + u16::X11_SIZE + 22 + self.colors.len() * (RgbColor::X11_SIZE + 2)
 	}
 }
 
 impl Readable for QueryColors {
 	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
-		buf.advance(1);
-		let sequence = buf.get_u16();
+		xrbk::skip(buf, 1)?;
+		let sequence = u16::read_from(buf)?;
 
-		let length = (buf.get_u32() as usize) * 4;
-		let buf = &mut buf.take(length - 8);
+		// The length does not include the first 32 bytes of the reply, 8 of
+		// which have already been read.
+		let length = (u32::read_from(buf)? as usize) * 4;
+		let buf = &mut xrbk::take(buf, 24 + length);
 
-		let colors_len = buf.get_u16();
-		buf.advance(22);
+		let colors_len = u16::read_from(buf)?;
+		xrbk::skip(buf, 22)?;
 
 		let colors = {
 			let mut colors = vec![];
 
 			for _ in 0..colors_len {
 				colors.push(RgbColor::read_from(buf)?);
-				buf.advance(2);
+				xrbk::skip(buf, 2)?;
 			}
 
 			colors
@@ -318,7 +343,7 @@ impl Readable for QueryColors {
 impl Writable for QueryColors {
 	#[allow(clippy::cast_possible_truncation)]
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		let buf = &mut buf.limit((self.length() as usize) * 4);
+		let buf = &mut xrbk::limit(buf, self.x11_size());
 
 		buf.put_u8(1);
 		buf.put_u8(0);
@@ -345,6 +370,8 @@ derive_xrb! {
 	/// [`GetNamedColor` request]: request::GetNamedColor
 	#[doc(alias("LookupColor"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetNamedColor: Reply for request::GetNamedColor {
 		/// The sequence number identifying the [request] that generated this
@@ -357,18 +384,130 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The ideal [RGB values] of the color.
 		///
 		/// [RGB values]: RgbColor
+		#[doc(alias("exact"))]
 		pub ideal_color: RgbColor,
 		/// The closest [RGB values] to the `ideal_color` that the display could
 		/// provide.
 		///
 		/// [RGB values]: RgbColor
+		#[doc(alias("visual"))]
 		pub actual_color: RgbColor,
 		[_; ..],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+
+	round_trip_tests! {
+		ListInstalledColormaps => test_list_installed_colormaps_round_trip,
+		AllocateColor => test_allocate_color_round_trip,
+		AllocateNamedColor => test_allocate_named_color_round_trip,
+		AllocateColorCells => test_allocate_color_cells_round_trip,
+		AllocateColorPlanes => test_allocate_color_planes_round_trip,
+		QueryColors => test_query_colors_round_trip,
+		GetNamedColor => test_get_named_color_round_trip,
+	}
+
+	#[test]
+	fn test_list_installed_colormaps() {
+		let empty = ListInstalledColormaps {
+			sequence: 2,
+			colormaps: vec![],
+		};
+
+		let mut bytes = vec![];
+		empty.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(empty.length(), 0);
+		assert_eq!(bytes[8..10], [0, 0]);
+		assert_eq!(
+			ListInstalledColormaps::read_from(&mut &bytes[1..]).unwrap(),
+			empty
+		);
+
+		let three = ListInstalledColormaps {
+			sequence: 3,
+			colormaps: vec![
+				Colormap::new(0x0000_0020),
+				Colormap::new(0x0020_0001),
+				Colormap::new(0x0020_0002),
+			],
+		};
+
+		let mut bytes = vec![];
+		three.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 44);
+		assert_eq!(three.length(), 3);
+		assert_eq!(bytes[8..10], [0, 3]);
+		assert_eq!(bytes[32..36], [0x00, 0x00, 0x00, 0x20]);
+		assert_eq!(bytes[40..], [0x00, 0x20, 0x00, 0x02]);
+		assert_eq!(
+			ListInstalledColormaps::read_from(&mut &bytes[1..]).unwrap(),
+			three
+		);
+	}
+
+	#[test]
+	fn test_query_colors_fixture() {
+		let reply = QueryColors {
+			sequence: 7,
+			colors: vec![
+				RgbColor::new(0xffff, 0, 0),
+				RgbColor::new(0, 0x8000, 0),
+				RgbColor::new(0x0102, 0x0304, 0x0506),
+			],
+		};
+
+		#[rustfmt::skip]
+		let bytes = [
+			1, 0, 0, 7, // header
+			0, 0, 0, 6, // length
+			0, 3, // colors_len
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0, 0, // colors[0]
+			0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0, 0, // colors[1]
+			0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0, 0, // colors[2]
+		];
+
+		let mut written = vec![];
+		reply.write_to(&mut written).unwrap();
+		assert_eq!(written, bytes);
+
+		let read = QueryColors::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(read.sequence, 7);
+		assert_eq!(read, reply);
+	}
+
+	#[test]
+	fn test_allocate_named_color_fixture() {
+		let reply = AllocateNamedColor {
+			sequence: 1,
+			color_id: ColorId::new(0x0000_00ff),
+			ideal_color: RgbColor::new(0xffff, 0x8080, 0),
+			actual_color: RgbColor::new(0xff00, 0x8000, 0),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes[8..12], [0x00, 0x00, 0x00, 0xff]);
+		assert_eq!(bytes[12..18], [0xff, 0xff, 0x80, 0x80, 0x00, 0x00]);
+		assert_eq!(bytes[18..24], [0xff, 0x00, 0x80, 0x00, 0x00, 0x00]);
+
+		assert_eq!(
+			AllocateNamedColor::read_from(&mut &bytes[1..]).unwrap(),
+			reply
+		);
+	}
+}
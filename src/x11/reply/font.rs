@@ -38,6 +38,9 @@ use crate::{message::Reply, x11::request, Atom, LengthString8, String8};
 ///
 /// The value of this property is uninterpreted by XRB.
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontProperty {
 	/// The name of the font property.
 	pub name: Atom,
@@ -53,6 +56,9 @@ pub struct FontProperty {
 ///
 /// For a nonexistent character, all of these fields are zero.
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacterInfo {
 	/// The extent of this character's appearance beyond its left edge.
 	///
@@ -98,6 +104,9 @@ impl ConstantX11Size for CharacterInfo {
 /// [`LeftToRight`]: DrawDirection::LeftToRight
 /// [`RightToLeft`]: DrawDirection::RightToLeft
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DrawDirection {
 	/// Most [`CharacterInfo`]s in the font have a positive width.
 	LeftToRight,
@@ -116,6 +125,8 @@ derive_xrb! {
 	///
 	/// [`QueryFont` request]: request::QueryFont
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryFont: Reply for request::QueryFont {
 		/// The sequence number identifying the [request] that generated this
@@ -128,7 +139,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// A [`CharacterInfo`] representing the minimum bounds of all fields in
@@ -268,6 +278,8 @@ derive_xrb! {
 	///
 	/// [`QueryTextExtents` request]: request::QueryTextExtents
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryTextExtents: Reply for request::QueryTextExtents {
 		/// The sequence number identifying the [request] that generated this
@@ -280,7 +292,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// A hint as to whether most characters in a font have a positive
@@ -326,6 +337,8 @@ derive_xrb! {
 	///
 	/// [`ListFonts` request]: request::ListFonts
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ListFonts: Reply for request::ListFonts {
 		/// The sequence number identifying the [request] that generated this
@@ -338,7 +351,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `names`.
@@ -362,6 +374,10 @@ derive_xrb! {
 /// [reply]: Reply
 ///
 /// [`ListFontsWithInfo` request]: request::ListFontsWithInfo
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ListFontsWithInfo {
 	/// Information about one of the available fonts.
 	Font(FontWithInfo),
@@ -372,6 +388,9 @@ pub enum ListFontsWithInfo {
 	Terminate(TerminateListFontsWithInfo),
 }
 
+impl crate::message::__private::Sealed for ListFontsWithInfo {}
+crate::message::register_message!(Reply ListFontsWithInfo);
+
 impl Reply for ListFontsWithInfo {
 	type Request = request::ListFontsWithInfo;
 
@@ -381,6 +400,10 @@ impl Reply for ListFontsWithInfo {
 			| Self::Terminate(TerminateListFontsWithInfo { sequence, .. }) => *sequence,
 		}
 	}
+
+	fn read_with_request(buf: &mut impl Buf, _request: &Self::Request) -> ReadResult<Self> {
+		Self::read_from(buf)
+	}
 }
 
 impl X11Size for ListFontsWithInfo {
@@ -397,8 +420,8 @@ impl Readable for ListFontsWithInfo {
 	where
 		Self: Sized,
 	{
-		let name_len = buf.get_u8();
-		let sequence = buf.get_u16();
+		let name_len = u8::read_from(buf)?;
+		let sequence = u16::read_from(buf)?;
 
 		Ok(match name_len {
 			zero if zero == 0 => Self::Terminate(<_>::read_with(buf, &sequence)?),
@@ -431,6 +454,9 @@ impl Writable for ListFontsWithInfo {
 /// [`ListFontsWithInfo` request]: request::ListFontsWithInfo
 /// [`TerminateListFontsWithInfo` reply]: TerminateListFontsWithInfo
 #[derive(Derivative, Debug)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Hash, PartialEq, Eq)]
 pub struct FontWithInfo {
 	/// The sequence number identifying the [request] that generated this
@@ -565,6 +591,9 @@ pub struct FontWithInfo {
 	pub properties: Vec<FontProperty>,
 
 	/// The name of this font.
+	// An empty name would be read as a `TerminateListFontsWithInfo` reply.
+	#[cfg_attr(test, proptest(filter = "|name| !name.is_empty()"))]
+	#[cfg_attr(feature = "arbitrary", arbitrary(with = crate::unstructured::non_empty_string8s))]
 	pub name: String8,
 }
 
@@ -607,15 +636,15 @@ impl ReadableWithContext for FontWithInfo {
 		//   `ListFontsWithInfo` reply
 
 		// Read the length - take away the 8 bytes we've already read.
-		let length = ((buf.get_u32() as usize) * 4) + (32 - 8);
+		let length = ((u32::read_from(buf)? as usize) * 4) + (32 - 8);
 		// Limit `buf` by the read `length`.
-		let buf = &mut buf.take(length);
+		let buf = &mut xrbk::take(buf, length);
 
 		let min_bounds = CharacterInfo::read_from(buf)?;
-		buf.advance(4); // 4 unused bytes
+		xrbk::skip(buf, 4)?; // 4 unused bytes
 
 		let max_bounds = CharacterInfo::read_from(buf)?;
-		buf.advance(4); // 4 unused bytes
+		xrbk::skip(buf, 4)?; // 4 unused bytes
 
 		let first_character_or_min_minor_index = u16::read_from(buf)?;
 		let last_character_or_max_minor_index = u16::read_from(buf)?;
@@ -639,7 +668,7 @@ impl ReadableWithContext for FontWithInfo {
 		let properties = <Vec<FontProperty>>::read_with(buf, &properties_len)?;
 
 		let name = String8::read_with(buf, &name_len)?;
-		buf.advance(pad(&name));
+		xrbk::skip(buf, pad(&name))?;
 
 		Ok(Self {
 			sequence: *sequence,
@@ -725,6 +754,9 @@ impl Writable for FontWithInfo {
 ///
 /// [`ListFontsWithInfo` request]: request::ListFontsWithInfo
 #[derive(Derivative, Debug)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Hash, PartialEq, Eq)]
 pub struct TerminateListFontsWithInfo {
 	/// The sequence number identifying the [request] that generated this
@@ -784,10 +816,10 @@ impl ReadableWithContext for TerminateListFontsWithInfo {
 
 		// Then we skip the length because we know what it is meant to be... should
 		// probably verify that...
-		buf.advance(4);
+		xrbk::skip(buf, 4)?;
 
 		// And then skip the 52 remaining unused bytes.
-		buf.advance(52);
+		xrbk::skip(buf, 52)?;
 
 		Ok(Self {
 			sequence: *sequence,
@@ -803,6 +835,8 @@ derive_xrb! {
 	/// [`GetFontSearchDirectories` request]: request::GetFontSearchDirectories
 	#[doc(alias = "GetFontPath")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetFontSearchDirectories: Reply for request::GetFontSearchDirectories {
 		/// The sequence number identifying the [request] that generated this
@@ -815,7 +849,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `directories`.
@@ -830,3 +863,181 @@ derive_xrb! {
 		[_; directories => pad(directories)],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{arbitrary::round_trip_tests, atom};
+
+	round_trip_tests! {
+		QueryFont => test_query_font_round_trip,
+		QueryTextExtents => test_query_text_extents_round_trip,
+		ListFonts => test_list_fonts_round_trip,
+		ListFontsWithInfo => test_list_fonts_with_info_round_trip,
+		GetFontSearchDirectories => test_get_font_search_directories_round_trip,
+	}
+
+	#[test]
+	fn test_query_font_with_256_character_infos() {
+		let character_info = |i: i16| CharacterInfo {
+			left_side_bearing: -i,
+			right_side_bearing: i,
+			width: i,
+			ascent: 10,
+			descent: 2,
+			attributes: 0,
+		};
+
+		let reply = QueryFont {
+			sequence: 1,
+
+			min_bounds: character_info(0),
+			max_bounds: character_info(255),
+
+			first_character_or_min_minor_index: 0,
+			last_character_or_max_minor_index: 255,
+			fallback_character: 0,
+
+			draw_direction: DrawDirection::LeftToRight,
+
+			min_major_index: 0,
+			max_major_index: 0,
+
+			all_characters_exist: true,
+
+			font_ascent: 10,
+			font_descent: 2,
+
+			properties: vec![],
+			character_infos: (0..256).map(character_info).collect(),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		// 60 bytes for the fixed fields, then 12 bytes for each character info.
+		assert_eq!(bytes.len(), 60 + 256 * 12);
+		assert_eq!(bytes.len(), reply.x11_size());
+		// `properties_len`
+		assert_eq!(bytes[46..48], [0, 0]);
+		// `character_infos_len`
+		assert_eq!(bytes[56..60], 256_u32.to_be_bytes());
+
+		let read = QueryFont::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(read, reply);
+		assert_eq!(read.character_infos.len(), 256);
+	}
+
+	#[test]
+	fn test_query_font_fixed_fixture() {
+		// The 6x13 `fixed` font, with only its characters `g` to `i`.
+		let character_info = |right_side_bearing, ascent, descent| CharacterInfo {
+			left_side_bearing: 1,
+			right_side_bearing,
+			width: 6,
+			ascent,
+			descent,
+			attributes: 0,
+		};
+
+		let reply = QueryFont {
+			sequence: 5,
+
+			min_bounds: character_info(4, 6, 0),
+			max_bounds: character_info(5, 10, 3),
+
+			first_character_or_min_minor_index: 0x67,
+			last_character_or_max_minor_index: 0x69,
+			fallback_character: 0,
+
+			draw_direction: DrawDirection::LeftToRight,
+
+			min_major_index: 0,
+			max_major_index: 0,
+
+			all_characters_exist: true,
+
+			font_ascent: 11,
+			font_descent: 2,
+
+			properties: vec![
+				FontProperty {
+					name: atom::POINT_SIZE,
+					value: [0, 0, 0, 120],
+				},
+				FontProperty {
+					name: atom::RESOLUTION,
+					value: [0, 0, 0, 75],
+				},
+			],
+			character_infos: vec![
+				character_info(5, 6, 3),
+				character_info(5, 9, 0),
+				character_info(4, 10, 0),
+			],
+		};
+
+		#[rustfmt::skip]
+		let bytes = [
+			1, 0, 0, 5, // header
+			0, 0, 0, 20, // length
+			0, 1, 0, 4, 0, 6, 0, 6, 0, 0, 0, 0, // min_bounds
+			0, 0, 0, 0,
+			0, 1, 0, 5, 0, 6, 0, 10, 0, 3, 0, 0, // max_bounds
+			0, 0, 0, 0,
+			0, 0x67, // first_character_or_min_minor_index
+			0, 0x69, // last_character_or_max_minor_index
+			0, 0, // fallback_character
+			0, 2, // properties_len
+			0, // draw_direction
+			0, // min_major_index
+			0, // max_major_index
+			1, // all_characters_exist
+			0, 11, // font_ascent
+			0, 2, // font_descent
+			0, 0, 0, 3, // character_infos_len
+			0, 0, 0, 59, 0, 0, 0, 120, // properties[0]
+			0, 0, 0, 60, 0, 0, 0, 75, // properties[1]
+			0, 1, 0, 5, 0, 6, 0, 6, 0, 3, 0, 0, // character_infos[0]
+			0, 1, 0, 5, 0, 6, 0, 9, 0, 0, 0, 0, // character_infos[1]
+			0, 1, 0, 4, 0, 6, 0, 10, 0, 0, 0, 0, // character_infos[2]
+		];
+
+		let mut written = vec![];
+		reply.write_to(&mut written).unwrap();
+		assert_eq!(written, bytes);
+
+		let read = QueryFont::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(read.sequence, 5);
+		assert_eq!(read, reply);
+	}
+
+	#[test]
+	fn test_list_fonts_fixture() {
+		let reply = ListFonts {
+			sequence: 3,
+			names: ["6x13", "fixed"]
+				.into_iter()
+				.map(|name| LengthString8::try_from(name).unwrap())
+				.collect(),
+		};
+
+		#[rustfmt::skip]
+		let bytes = [
+			1, 0, 0, 3, // header
+			0, 0, 0, 3, // length
+			0, 2, // names_len
+			0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+			4, b'6', b'x', b'1', b'3', // names[0]
+			5, b'f', b'i', b'x', b'e', b'd', // names[1]
+			0, // padding
+		];
+
+		let mut written = vec![];
+		reply.write_to(&mut written).unwrap();
+		assert_eq!(written, bytes);
+
+		assert_eq!(ListFonts::read_from(&mut &bytes[1..]).unwrap(), reply);
+	}
+}
@@ -33,6 +33,8 @@ derive_xrb! {
 	///
 	/// [`CaptureImage` request]: request::CaptureImage
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct CaptureImage: Reply for request::CaptureImage {
 		/// The sequence number identifying the [request] that generated this
@@ -45,7 +47,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The depth of the `target` [drawable] when it was created.
@@ -65,8 +66,37 @@ derive_xrb! {
 
 		// FIXME: how do we know what is padding and what is data?????
 		/// The image's data.
-		#[context(self::remaining => remaining)]
+		#[context(self::length => length * 4)]
+		#[cfg_attr(test, proptest(strategy = "crate::arbitrary::aligned_bytes()"))]
+		#[arbitrary(with = crate::unstructured::aligned_bytes)]
 		pub data: Vec<u8>,
 		[_; data => pad(data)],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use xrbk::Readable;
+
+	round_trip_tests! {
+		CaptureImage => test_capture_image_round_trip,
+	}
+
+	#[test]
+	fn test_capture_image_data_from_length() {
+		let mut bytes = vec![
+			1, 24, 0, 7, // reply, depth, sequence
+			0, 0, 0, 2, // length
+			0, 0, 0, 0, // visual
+		];
+		bytes.extend([0; 20]);
+		bytes.extend([1, 2, 3, 4, 5, 6, 7, 8]);
+
+		let reply = CaptureImage::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(reply.depth, 24);
+		assert_eq!(reply.data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+	}
+}
@@ -17,6 +17,7 @@ derive_xrb! {
 	/// [`QueryIdealDimensions` request]: request::QueryIdealDimensions
 	#[doc(alias("QueryBestSize"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryIdealDimensions: Reply for request::QueryIdealDimensions {
 		/// The sequence number identifying the [request] that generated this
@@ -29,7 +30,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The ideal [dimensions], as described in the
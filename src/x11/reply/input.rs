@@ -16,11 +16,20 @@
 
 extern crate self as xrb;
 
-use array_init::array_init;
 use derivative::Derivative;
-use xrbk::{Buf, BufMut, ConstantX11Size, ReadResult, Readable, Writable, WriteResult, X11Size};
+use xrbk::{
+	pad,
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadResult,
+	Readable,
+	Writable,
+	WriteResult,
+	X11Size,
+};
 
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
 	message::Reply,
@@ -33,9 +42,10 @@ use crate::{
 	Coords,
 	FocusWindow,
 	GrabStatus,
-	Keycode,
+	KeyStates,
 	Keysym,
 	ModifierMask,
+	ModifierMap,
 	Timestamp,
 	Toggle,
 	Window,
@@ -49,6 +59,8 @@ derive_xrb! {
 	/// [`GrabCursor` request]: request::GrabCursor
 	#[doc(alias = "GrabPointer")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GrabCursor: Reply for request::GrabCursor {
 		/// The sequence number identifying the [request] that generated this
@@ -61,7 +73,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The status of the attempted grab.
@@ -80,6 +91,8 @@ derive_xrb! {
 	///
 	/// [`GrabKeyboard` request]: request::GrabKeyboard
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GrabKeyboard: Reply for request::GrabKeyboard {
 		/// The sequence number identifying the [request] that generated this
@@ -92,7 +105,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The status of the attempted grab.
@@ -113,6 +125,8 @@ derive_xrb! {
 	/// [`QueryCursorLocation` request]: request::QueryCursorLocation
 	#[doc(alias("QueryPointer, QueryCursor, GetCursorPos, GetCursorLocation"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryCursorLocation: Reply for request::QueryCursorLocation {
 		/// The sequence number identifying the [request] that generated this
@@ -125,7 +139,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the cursor is on the `same_screen` as the given `target`
@@ -173,7 +186,10 @@ derive_xrb! {
 /// [time]: Timestamp
 ///
 /// [`GetMotionHistory` reply]: GetMotionHistory
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeCoords {
 	/// The [time] at which the cursor was at the `coords`.
 	///
@@ -191,6 +207,8 @@ derive_xrb! {
 	/// [`GetMotionHistory` request]: request::GetMotionHistory
 	#[doc(alias = "GetMotionEvents")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetMotionHistory: Reply for request::GetMotionHistory {
 		/// The sequence number identifying the [request] that generated this
@@ -203,7 +221,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `motion_history`.
@@ -226,6 +243,8 @@ derive_xrb! {
 	/// [`ConvertCoordinates` request]: request::ConvertCoordinates
 	#[doc(alias = "TranslateCoordinates")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ConvertCoordinates: Reply for request::ConvertCoordinates {
 		/// The sequence number identifying the [request] that generated this
@@ -238,7 +257,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the `original` [window] and the `output` [window] are on the
@@ -278,6 +296,8 @@ derive_xrb! {
 	/// [`GetFocus` request]: request::GetFocus
 	#[doc(alias = "GetInputFocus")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetFocus: Reply for request::GetFocus {
 		/// The sequence number identifying the [request] that generated this
@@ -290,7 +310,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// What the focus will retain to if the focused [window] becomes
@@ -312,6 +331,8 @@ derive_xrb! {
 	/// [`QueryKeyboard` request]: request::QueryKeyboard
 	#[doc(alias = "QueryKeymap")]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryKeyboard: Reply for request::QueryKeyboard {
 		/// The sequence number identifying the [request] that generated this
@@ -324,22 +345,52 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
-		/// A bit vector representing the currently held keys of the keyboard.
+		/// Which keys of the keyboard are currently held.
 		///
-		/// A bit is `0` if the key is not held, and `1` if it is held. Byte
-		/// `N`, starting at `0`, contains the bits for keys `8N` to `8N + 7`.
-		/// The least significant bit in the byte represents key `8N`.
-		pub keys: [u8; 32],
+		/// See [`KeyStates::is_pressed`] and [`KeyStates::pressed`].
+		pub keys: KeyStates,
+	}
+}
+
+impl GetMotionHistory {
+	/// Returns an iterator over the recorded cursor motion between the `start`
+	/// and `end` times (inclusive).
+	///
+	/// Times are compared with [`Timestamp::wrapping_cmp`], so the range may
+	/// span the point at which time wraps around back to 0.
+	pub fn between(
+		&self, start: Timestamp, end: Timestamp,
+	) -> impl Iterator<Item = &TimeCoords> + '_ {
+		self.motion_history
+			.iter()
+			.filter(move |TimeCoords { time, .. }| {
+				time.wrapping_cmp(start).is_ge() && time.wrapping_cmp(end).is_le()
+			})
+	}
+
+	/// Returns the total movement of the cursor over the recorded motion, as
+	/// the `x` and `y` distances from its first to its last coordinates.
+	///
+	/// The motion history is listed in the order it was recorded. Returns
+	/// [`None`] if there is no recorded motion.
+	#[must_use]
+	pub fn displacement(&self) -> Option<(i32, i32)> {
+		let first = self.motion_history.first()?.coords;
+		let last = self.motion_history.last()?.coords;
+
+		Some((
+			i32::from(last.x.0) - i32::from(first.x.0),
+			i32::from(last.y.0) - i32::from(first.y.0),
+		))
 	}
 }
 
 /// The [keysyms] mapped to a particular [keycode].
 ///
 /// [keysyms]: Keysym
-/// [keycode]: Keycode
+/// [keycode]: crate::Keycode
 pub type KeyMapping = Vec<Keysym>;
 
 /// The [reply] to a [`GetKeyboardMapping` request].
@@ -348,6 +399,9 @@ pub type KeyMapping = Vec<Keysym>;
 ///
 /// [`GetKeyboardMapping` request]: request::GetKeyboardMapping
 #[derive(Derivative, Debug)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Hash, PartialEq, Eq)]
 pub struct GetKeyboardMapping {
 	/// The sequence number identifying the [request] that generated this
@@ -364,17 +418,26 @@ pub struct GetKeyboardMapping {
 
 	/// The mapping of [keysyms] for each [keycode] in the specified `range`.
 	///
-	/// [keycode]: Keycode
+	/// [keycode]: crate::Keycode
 	/// [keysyms]: Keysym
+	#[cfg_attr(test, proptest(strategy = "crate::arbitrary::key_mappings()"))]
+	#[cfg_attr(feature = "arbitrary", arbitrary(with = crate::unstructured::key_mappings))]
 	pub mappings: Vec<KeyMapping>,
 }
 
+impl crate::message::__private::Sealed for GetKeyboardMapping {}
+crate::message::register_message!(Reply GetKeyboardMapping);
+
 impl Reply for GetKeyboardMapping {
 	type Request = request::GetKeyboardMapping;
 
 	fn sequence(&self) -> u16 {
 		self.sequence
 	}
+
+	fn read_with_request(buf: &mut impl Buf, _request: &Self::Request) -> ReadResult<Self> {
+		Self::read_from(buf)
+	}
 }
 
 impl X11Size for GetKeyboardMapping {
@@ -400,21 +463,22 @@ impl Readable for GetKeyboardMapping {
 		//        and `sequence` should be context for `ReadableWithContext`.
 		//
 		// FIXME: This is a change that needs to be done for all replies...
-		buf.advance(1);
-		let keysyms_per_keycode = buf.get_u8();
-		let sequence = buf.get_u16();
+		let keysyms_per_keycode = u8::read_from(buf)?;
+		let sequence = u16::read_from(buf)?;
 
-		let length = (buf.get_u32() as usize) * 4;
-		let buf = &mut buf.take(length - HEADER);
+		// The length does not include the first 32 bytes of the reply, `HEADER`
+		// of which have already been read.
+		let length = (u32::read_from(buf)? as usize) * 4;
+		let buf = &mut xrbk::take(buf, (32 - HEADER) + length);
 
 		// }}}
 
 		// 24 unused bytes.
-		buf.advance(24);
+		xrbk::skip(buf, 24)?;
 
 		let mappings = {
 			let mapping_size = usize::from(keysyms_per_keycode) * Keysym::X11_SIZE;
-			let mappings_len = buf.remaining() / mapping_size;
+			let mappings_len = buf.remaining().checked_div(mapping_size).unwrap_or(0);
 
 			let mut mappings = vec![];
 
@@ -438,15 +502,14 @@ impl Readable for GetKeyboardMapping {
 impl Writable for GetKeyboardMapping {
 	#[allow(clippy::cast_possible_truncation)]
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		let buf = &mut buf.limit(self.x11_size());
+		let buf = &mut xrbk::limit(buf, self.x11_size());
 
 		// Header {{{
 
 		// Indicates that this is a reply.
 		buf.put_u8(1);
 		// The number of keysyms in each mapping.
-		let mapping_size = self.mappings.x11_size() / self.mappings.len();
-		let keysyms_per_keycode = (mapping_size / Keysym::X11_SIZE) as u8;
+		let keysyms_per_keycode = self.mappings.first().map_or(0, Vec::len) as u8;
 		keysyms_per_keycode.write_to(buf)?;
 		// The sequence number.
 		self.sequence.write_to(buf)?;
@@ -473,6 +536,8 @@ derive_xrb! {
 	/// [`GetKeyboardOptions` request]: request::GetKeyboardOptions
 	#[doc(alias("GetKeyboardControl"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetKeyboardOptions: Reply for request::GetKeyboardOptions {
 		/// The sequence number identifying the [request] that generated this
@@ -485,7 +550,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the global [auto repeat mode] is enabled.
@@ -536,21 +600,19 @@ derive_xrb! {
 		pub bell_duration: Ms<u16>,
 		[_; 2],
 
-		/// A bit vector representing whether each key has [auto repeat mode]
-		/// enabled.
+		/// A bit vector in which the bit for each key is set if that key has
+		/// [auto repeat mode] enabled.
 		///
-		/// Byte `N`, starting at `0`, contains the bits for [keycodes] `8N` to
-		/// `8N + 7`. The least significant bit in each byte represents key
-		/// `8N`.
+		/// Whether a particular key has [auto repeat mode] enabled can be
+		/// found with [`KeyStates::is_set`].
 		///
 		/// See [`KeyboardOptions::auto_repeat_mode`] for more information.
 		///
-		/// [keycodes]: Keycode
 		/// [auto repeat mode]: crate::set::KeyboardOptions::auto_repeat_mode
 		///
 		/// [`KeyboardOptions::auto_repeat_mode`]: crate::set::KeyboardOptions::auto_repeat_mode
 		#[doc(alias("auto_repeats"))]
-		pub auto_repeat_modes: [u8; 32],
+		pub auto_repeat_modes: KeyStates,
 	}
 
 	/// The [reply] to a [`GetCursorOptions` request].
@@ -560,6 +622,8 @@ derive_xrb! {
 	/// [`GetCursorOptions` request]: request::GetCursorOptions
 	#[doc(alias("GetPointerControl", "GetPointerOptions", "GetCursorControl"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetCursorOptions: Reply for request::GetCursorOptions {
 		/// The sequence number identifying the [request] that generated this
@@ -572,7 +636,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The multiplier applied to the acceleration of the cursor when the
@@ -595,7 +658,10 @@ derive_xrb! {
 ///
 /// [`SetButtonMapping` request]: request::SetButtonMapping
 /// [`SetButtonMapping` reply]: SetButtonMapping
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SetButtonMappingStatus {
 	/// The [`SetButtonMapping` request] was successful.
 	///
@@ -619,6 +685,8 @@ derive_xrb! {
 	/// [`SetButtonMapping` request]: request::SetButtonMapping
 	#[doc(alias("SetPointerMapping", "SetCursorMapping"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct SetButtonMapping: Reply for request::SetButtonMapping {
 		/// The sequence number identifying the [request] that generated this
@@ -631,7 +699,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the [`SetButtonMapping` request] was successful.
@@ -639,8 +706,10 @@ derive_xrb! {
 		/// See [`SetButtonMappingStatus`] for more information.
 		///
 		/// [`SetButtonMapping` request]: request::SetButtonMapping
+		#[metabyte]
 		pub status: SetButtonMappingStatus,
-		[_; ..],
+
+		[_; 24],
 	}
 
 	/// The [reply] to a [`GetButtonMapping` request].
@@ -650,6 +719,8 @@ derive_xrb! {
 	/// [`GetButtonMapping` request]: request::GetButtonMapping
 	#[doc(alias("GetPointerMapping", "GetCursorMapping"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetButtonMapping: Reply for request::GetButtonMapping {
 		/// The sequence number identifying the [request] that generated this
@@ -662,7 +733,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `mappings`.
@@ -682,6 +752,7 @@ derive_xrb! {
 		/// [button]: Button
 		#[context(mappings_len => usize::from(*mappings_len))]
 		pub mappings: Vec<Option<Button>>,
+		[_; mappings => pad(mappings)],
 	}
 }
 
@@ -693,7 +764,10 @@ derive_xrb! {
 ///
 /// [`SetModifierMapping` request]: request::SetModifierMapping
 /// [`SetModifierMapping` reply]: SetModifierMapping
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SetModifierMappingStatus {
 	/// The [`SetModifierMapping` request] was successful.
 	///
@@ -728,6 +802,8 @@ derive_xrb! {
 	///
 	/// [`SetModifierMapping` request]: request::SetModifierMapping
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct SetModifierMapping: Reply for request::SetModifierMapping {
 		/// The sequence number identifying the [request] that generated this
@@ -740,7 +816,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the [`SetModifierMapping` request] was [successful].
@@ -757,147 +832,301 @@ derive_xrb! {
 	}
 }
 
-/// The [reply] to a [`GetModifierMapping` request].
-///
-/// [reply]: Reply
-///
-/// [`GetModifierMapping` request]: request::GetModifierMapping
-#[derive(Derivative, Debug)]
-#[derivative(Hash, PartialEq, Eq)]
-pub struct GetModifierMapping {
-	/// The sequence number identifying the [request] that generated this
-	/// [reply].
-	///
-	/// See [`Reply::sequence`] for more information.
+derive_xrb! {
+	/// The [reply] to a [`GetModifierMapping` request].
 	///
-	/// [request]: crate::message::Request
 	/// [reply]: Reply
 	///
-	/// [`Reply::sequence`]: Reply::sequence
-	#[derivative(Hash = "ignore", PartialEq = "ignore")]
-	pub sequence: u16,
+	/// [`GetModifierMapping` request]: request::GetModifierMapping
+	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct GetModifierMapping: Reply for request::GetModifierMapping {
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[sequence]
+		pub sequence: u16,
 
-	/// The [keycodes] mapped to the shift modifier.
-	///
-	/// [keycodes]: Keycode
-	pub shift_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the caps lock modifier.
-	///
-	/// [keycodes]: Keycode
-	pub capslock_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the control modifier.
-	///
-	/// [keycodes]: Keycode
-	pub ctrl_keycodes: Vec<Keycode>,
+		// The number of keycodes in each row of the `modifier_map`.
+		#[metabyte]
+		let keycodes_per_modifier: u8 = modifier_map => modifier_map.keycodes_per_modifier(),
+		[_; 24],
 
-	/// The [keycodes] mapped to the Mod1 modifier.
-	///
-	/// [keycodes]: Keycode
-	pub mod1_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod2 modifier.
-	///
-	/// [keycodes]: Keycode
-	pub mod2_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod3 modifier.
-	///
-	/// [keycodes]: Keycode
-	pub mod3_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod4 modifier.
-	///
-	/// This is typically the key variously called 'super', 'meta', 'windows
-	/// key', 'cmd', etc.
-	///
-	/// [keycodes]: Keycode
-	pub mod4_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod5 modifier.
-	///
-	/// [keycodes]: Keycode
-	pub mod5_keycodes: Vec<Keycode>,
+		/// The [keycodes] mapped to each modifier.
+		///
+		/// [keycodes]: crate::Keycode
+		#[context(keycodes_per_modifier => usize::from(*keycodes_per_modifier))]
+		pub modifier_map: ModifierMap,
+	}
 }
 
-impl GetModifierMapping {
-	fn max_keycodes_len(&self) -> usize {
-		[
-			&self.shift_keycodes,
-			&self.capslock_keycodes,
-			&self.ctrl_keycodes,
-			&self.mod1_keycodes,
-			&self.mod2_keycodes,
-			&self.mod3_keycodes,
-			&self.mod4_keycodes,
-			&self.mod5_keycodes,
-		]
-		.into_iter()
-		.map(Vec::len)
-		.max()
-		.expect("there's definitely more than one element")
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{arbitrary::round_trip_tests, Keycode, Modifier};
+
+	round_trip_tests! {
+		GrabCursor => test_grab_cursor_round_trip,
+		GrabKeyboard => test_grab_keyboard_round_trip,
+		QueryCursorLocation => test_query_cursor_location_arbitrary_round_trip,
+		GetMotionHistory => test_get_motion_history_round_trip,
+		ConvertCoordinates => test_convert_coordinates_arbitrary_round_trip,
+		GetFocus => test_get_focus_round_trip,
+		QueryKeyboard => test_query_keyboard_round_trip,
+		GetKeyboardMapping => test_get_keyboard_mapping_round_trip,
+		GetKeyboardOptions => test_get_keyboard_options_round_trip,
+		GetCursorOptions => test_get_cursor_options_round_trip,
+		SetButtonMapping => test_set_button_mapping_round_trip,
+		GetButtonMapping => test_get_button_mapping_round_trip,
+		SetModifierMapping => test_set_modifier_mapping_round_trip,
+		GetModifierMapping => test_get_modifier_mapping_round_trip,
 	}
-}
 
-impl Reply for GetModifierMapping {
-	type Request = request::GetModifierMapping;
+	/// Writes the given `reply`, then reads it back, skipping the first byte
+	/// which indicates that the message is a reply.
+	fn round_trip<R: Reply + Readable + Writable>(reply: &R) -> R {
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
 
-	fn sequence(&self) -> u16 {
-		self.sequence
+		assert_eq!(bytes.len(), reply.x11_size());
+		assert_eq!(bytes.len(), 32 + (reply.length() as usize) * 4);
+
+		let mut buf = &bytes[1..];
+		R::read_from(&mut buf).unwrap()
 	}
-}
 
-impl X11Size for GetModifierMapping {
-	fn x11_size(&self) -> usize {
-		const HEADER: usize = 8;
-		const CONSTANT_SIZES: usize = HEADER + 24;
+	#[test]
+	fn test_query_cursor_location_round_trip() {
+		let reply = QueryCursorLocation {
+			sequence: 1,
+			same_screen: true,
+			root: Window::new(0x0100_0000),
+			child: Some(Window::new(0x0200_0001)),
+			root_coords: Coords::new(Px(-5), Px(10)),
+			target_coords: Coords::new(Px(3), Px(7)),
+			modifiers: ModifierMask::SHIFT | ModifierMask::CONTROL,
+		};
+
+		assert_eq!(reply.x11_size(), 32);
 
-		let keycodes_size = self.max_keycodes_len() * Keycode::X11_SIZE;
+		let read = round_trip(&reply);
 
-		CONSTANT_SIZES + (8 * keycodes_size)
+		assert_eq!(read.sequence, reply.sequence);
+		assert_eq!(read, reply);
 	}
-}
 
-impl Readable for GetModifierMapping {
-	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
-	where
-		Self: Sized,
-	{
-		const HEADER: usize = 8;
-		const ALIGNMENT: usize = 4;
+	#[test]
+	fn test_convert_coordinates_round_trip() {
+		let reply = ConvertCoordinates {
+			sequence: 2,
+			same_screen: false,
+			child: None,
+			output_coords: Coords::new(Px(0), Px(0)),
+		};
 
-		// FIXME: the first 4 bytes of the header should be read separately, with the
-		// metabyte        position and sequence being given as context. That applies to
-		// all replies.
-		buf.advance(1);
+		assert_eq!(reply.x11_size(), 32);
+		assert_eq!(round_trip(&reply), reply);
+	}
 
-		let keycodes_per_modifier = buf.get_u8();
-		let sequence = buf.get_u16();
+	#[test]
+	fn test_get_motion_history_round_trip_empty() {
+		let reply = GetMotionHistory {
+			sequence: 3,
+			motion_history: vec![],
+		};
 
-		let total_size = ((buf.get_u32() as usize) * ALIGNMENT) - HEADER;
-		let buf = &mut buf.take(total_size);
+		assert_eq!(reply.x11_size(), 32);
+		assert_eq!(reply.length(), 0);
+		assert_eq!(round_trip(&reply), reply);
+	}
 
-		let [shift_keycodes, capslock_keycodes, ctrl_keycodes, mod1_keycodes, mod2_keycodes, mod3_keycodes, mod4_keycodes, mod5_keycodes] =
-			array_init(|_| {
-				let mut keycodes = vec![];
+	#[test]
+	fn test_get_motion_history_round_trip_many() {
+		let motion_history: Vec<TimeCoords> = (0..100_i16)
+			.map(|i| TimeCoords {
+				time: Timestamp::new(u32::MAX.wrapping_add(u32::from(i.unsigned_abs()))),
+				coords: Coords::new(Px(i), Px(-i)),
+			})
+			.collect();
+
+		let reply = GetMotionHistory {
+			sequence: 4,
+			motion_history,
+		};
 
-				for _ in 0..keycodes_per_modifier {
-					match buf.get_u8() {
-						0 => {},
-						code => keycodes.push(Keycode(code)),
-					}
-				}
+		assert_eq!(reply.x11_size(), 32 + 100 * TimeCoords::X11_SIZE);
+		assert_eq!(reply.length(), 200);
+
+		let read = round_trip(&reply);
 
-				keycodes
-			});
+		assert_eq!(read.motion_history.len(), 100);
+		assert_eq!(read, reply);
+	}
+
+	fn motion_history(times: [u32; 4]) -> GetMotionHistory {
+		GetMotionHistory {
+			sequence: 5,
+			motion_history: times
+				.into_iter()
+				.zip([(10, 10), (12, 8), (20, -5), (-3, 40)])
+				.map(|(time, (x, y))| TimeCoords {
+					time: Timestamp::new(time),
+					coords: Coords::new(Px(x), Px(y)),
+				})
+				.collect(),
+		}
+	}
 
-		Ok(Self {
-			sequence,
+	#[test]
+	fn test_motion_history_between() {
+		let reply = motion_history([100, 200, 300, 400]);
 
-			shift_keycodes,
-			capslock_keycodes,
-			ctrl_keycodes,
+		let times = |start, end| -> Vec<u32> {
+			reply
+				.between(Timestamp::new(start), Timestamp::new(end))
+				.map(|time_coords| time_coords.time.unwrap())
+				.collect()
+		};
+
+		assert_eq!(times(200, 300), [200, 300]);
+		assert_eq!(times(150, 1000), [200, 300, 400]);
+		assert_eq!(times(300, 200), [] as [u32; 0]);
+
+		// Motion recorded across the point at which time wraps around.
+		let reply = motion_history([0xffff_fff0, 0xffff_ffff, 0x10, 0x20]);
+
+		let times = |start, end| -> Vec<u32> {
+			reply
+				.between(Timestamp::new(start), Timestamp::new(end))
+				.map(|time_coords| time_coords.time.unwrap())
+				.collect()
+		};
+
+		assert_eq!(times(0xffff_fff8, 0x10), [0xffff_ffff, 0x10]);
+		assert_eq!(times(0, 0x20), [0x10, 0x20]);
+	}
+
+	#[test]
+	fn test_motion_history_displacement() {
+		assert_eq!(
+			motion_history([100, 200, 300, 400]).displacement(),
+			Some((-13, 30))
+		);
+
+		let mut reply = motion_history([100, 200, 300, 400]);
+		reply.motion_history.truncate(1);
+		assert_eq!(reply.displacement(), Some((0, 0)));
+
+		reply.motion_history.clear();
+		assert_eq!(reply.displacement(), None);
+	}
+
+	/// Each [`GrabStatus`] and the byte which represents it.
+	const GRAB_STATUSES: [(GrabStatus, u8); 5] = [
+		(GrabStatus::Success, 0),
+		(GrabStatus::AlreadyGrabbed, 1),
+		(GrabStatus::InvalidTime, 2),
+		(GrabStatus::NotViewable, 3),
+		(GrabStatus::Frozen, 4),
+	];
+
+	/// A grab reply with the given status byte and a sequence of `0x1234`.
+	fn grab_reply_fixture(status: u8) -> Vec<u8> {
+		// Reply, status, sequence, length, then 24 unused bytes.
+		[1, status, 0x12, 0x34, 0, 0, 0, 0]
+			.into_iter()
+			.chain([0; 24])
+			.collect()
+	}
+
+	#[test]
+	fn test_grab_cursor_fixtures() {
+		for (grab_status, byte) in GRAB_STATUSES {
+			let bytes = grab_reply_fixture(byte);
+			let reply = GrabCursor::read_from(&mut &bytes[1..]).unwrap();
+
+			assert_eq!(reply.sequence, 0x1234);
+			assert_eq!(reply.grab_status, grab_status);
+
+			let mut written = vec![];
+			reply.write_to(&mut written).unwrap();
+			assert_eq!(written, bytes);
+		}
+	}
+
+	#[test]
+	fn test_grab_keyboard_fixtures() {
+		for (grab_status, byte) in GRAB_STATUSES {
+			let bytes = grab_reply_fixture(byte);
+			let reply = GrabKeyboard::read_from(&mut &bytes[1..]).unwrap();
+
+			assert_eq!(reply.sequence, 0x1234);
+			assert_eq!(reply.grab_status, grab_status);
+
+			let mut written = vec![];
+			reply.write_to(&mut written).unwrap();
+			assert_eq!(written, bytes);
+		}
+	}
+
+	#[test]
+	fn test_set_button_mapping_status_is_metabyte() {
+		let reply = SetButtonMapping {
+			sequence: 5,
+			status: SetButtonMappingStatus::Busy,
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes[1], 1);
+		assert_eq!(round_trip(&reply), reply);
+	}
 
-			mod1_keycodes,
-			mod2_keycodes,
-			mod3_keycodes,
-			mod4_keycodes,
-			mod5_keycodes,
-		})
+	#[test]
+	fn test_get_modifier_mapping_keycodes_per_modifier() {
+		for keycodes_per_modifier in [2_u8, 4] {
+			let keycodes: Vec<_> = (0..keycodes_per_modifier)
+				.map(|i| Keycode::new(50 + i))
+				.collect();
+
+			let mut modifier_map = ModifierMap::builder();
+			modifier_map
+				.keycodes(Modifier::Shift, keycodes)
+				.keycodes(Modifier::CapsLock, [Keycode::new(66)])
+				.keycodes(Modifier::Ctrl, [Keycode::new(37), Keycode::new(105)])
+				.keycodes(Modifier::Mod2, [Keycode::new(77)])
+				.keycodes(Modifier::Mod4, [Keycode::new(133), Keycode::new(134)]);
+
+			let reply = GetModifierMapping {
+				sequence: 6,
+				modifier_map: modifier_map.build(),
+			};
+
+			let mut bytes = vec![];
+			reply.write_to(&mut bytes).unwrap();
+
+			let keycodes_len = 8 * usize::from(keycodes_per_modifier);
+
+			assert_eq!(bytes[1], keycodes_per_modifier);
+			assert_eq!(reply.length() as usize, keycodes_len / 4);
+			assert_eq!(bytes.len(), 32 + keycodes_len);
+
+			// Each modifier's keycodes are padded with zeros to
+			// `keycodes_per_modifier`.
+			let capslock = 32 + usize::from(keycodes_per_modifier);
+			assert_eq!(bytes[capslock..capslock + 2], [66, 0]);
+
+			assert_eq!(round_trip(&reply), reply);
+		}
 	}
 }
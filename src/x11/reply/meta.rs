@@ -16,11 +16,112 @@
 
 extern crate self as xrb;
 
+use std::num::NonZeroU16;
+
 use derivative::Derivative;
-use xrbk::pad;
+use xrbk::{
+	pad,
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadResult,
+	Readable,
+	Writable,
+	WriteResult,
+	X11Size,
+};
 use xrbk_macro::derive_xrb;
 
-use crate::{message::Reply, unit::Sec, x11::request, Host, LengthString8, Toggle};
+use crate::{
+	message::Reply,
+	unit::Sec,
+	x11::request,
+	Host,
+	LengthString8,
+	LengthString8Ref,
+	Toggle,
+};
+
+/// The `timeout` or `interval` of the screensaver in a
+/// [`GetScreenSaver` reply].
+///
+/// This is encoded as a `u16`: `0` means [`Disabled`], and any other number of
+/// seconds means [`Enabled`].
+///
+/// [`Disabled`]: ScreenSaverDelay::Disabled
+/// [`Enabled`]: ScreenSaverDelay::Enabled
+///
+/// [`GetScreenSaver` reply]: GetScreenSaver
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScreenSaverDelay {
+	/// The option is disabled.
+	Disabled,
+
+	/// The option is enabled with the given delay.
+	///
+	/// A delay of zero seconds cannot be represented, as it would be written
+	/// as [`Disabled`].
+	///
+	/// [`Disabled`]: ScreenSaverDelay::Disabled
+	Enabled(Sec<NonZeroU16>),
+}
+
+impl ScreenSaverDelay {
+	/// Creates a new `ScreenSaverDelay` from its encoded number of seconds.
+	///
+	/// `0` is [`Disabled`], and any other number of seconds is [`Enabled`].
+	///
+	/// [`Disabled`]: ScreenSaverDelay::Disabled
+	/// [`Enabled`]: ScreenSaverDelay::Enabled
+	#[must_use]
+	pub const fn new(seconds: u16) -> Self {
+		match NonZeroU16::new(seconds) {
+			Some(seconds) => Self::Enabled(Sec(seconds)),
+			None => Self::Disabled,
+		}
+	}
+
+	/// Returns the encoded number of seconds of this `ScreenSaverDelay`.
+	///
+	/// This is `0` if it is [`Disabled`].
+	///
+	/// [`Disabled`]: ScreenSaverDelay::Disabled
+	#[must_use]
+	pub const fn seconds(&self) -> u16 {
+		match self {
+			Self::Disabled => 0,
+			Self::Enabled(Sec(seconds)) => seconds.get(),
+		}
+	}
+}
+
+impl ConstantX11Size for ScreenSaverDelay {
+	const X11_SIZE: usize = u16::X11_SIZE;
+}
+
+impl X11Size for ScreenSaverDelay {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl Readable for ScreenSaverDelay {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		Ok(Self::new(u16::read_from(buf)?))
+	}
+}
+
+impl Writable for ScreenSaverDelay {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.seconds().write_to(buf)
+	}
+}
 
 derive_xrb! {
 	/// The [reply] to a [`QueryExtension` request].
@@ -29,6 +130,7 @@ derive_xrb! {
 	///
 	/// [`QueryExtension` request]: request::QueryExtension
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryExtension: Reply for request::QueryExtension {
 		/// The sequence number identifying the [request] that generated this
@@ -41,29 +143,36 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the specified extension is present.
 		pub present: bool,
 
 		/// The [major opcode] of the specified extension if the extension is
-		/// present and it has has a [major opcode].
+		/// present.
 		///
 		/// [major opcode]: crate::message::Request::MAJOR_OPCODE
+		#[present_if(present)]
 		pub major_opcode: Option<u8>,
 		/// The first [event code] defined by the specified extension if the
-		/// extension is present and it defines any [events].
+		/// extension is present.
+		///
+		/// If the extension does not define any [events], this is zero.
 		///
 		/// [events]: crate::message::Event
 		/// [event code]: crate::message::Event::CODE
+		#[present_if(present)]
 		pub first_event_code: Option<u8>,
 		/// The first [error code] defined by the specified extension if the
-		/// extension is present and it defines any [errors].
+		/// extension is present.
+		///
+		/// If the extension does not define any [errors], this is zero.
 		///
 		/// [errors]: crate::message::Error
-		/// [event code]: crate::message::Event::CODE
+		/// [error code]: crate::message::Error::CODE
+		#[present_if(present)]
 		pub first_error_code: Option<u8>,
+		[_; ..],
 	}
 
 	/// The [reply] to a [`ListExtensions` request].
@@ -72,6 +181,8 @@ derive_xrb! {
 	///
 	/// [`ListExtensions` request]: request::ListExtensions
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ListExtensions: Reply for request::ListExtensions {
 		/// The sequence number identifying the [request] that generated this
@@ -84,7 +195,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `names`.
@@ -106,6 +216,8 @@ derive_xrb! {
 	///
 	/// [`GetScreenSaver` request]: request::GetScreenSaver
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetScreenSaver: Reply for request::GetScreenSaver {
 		/// The sequence number identifying the [request] that generated this
@@ -118,29 +230,26 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the screensaver is enabled and, if so, how long without
 		/// input before it is activated.
 		///
-		/// This is [`Some`] if the screensaver is enabled, and [`None`] if it
-		/// is not.
-		///
 		/// See [`SetScreenSaver::timeout`] for more information.
 		///
 		/// [`SetScreenSaver::timeout`]: request::SetScreenSaver::timeout
-		pub timeout: Option<Sec<u16>>,
+		pub timeout: ScreenSaverDelay,
 		/// A hint for screensavers with periodic changes as to the interval
 		/// between those changes.
 		///
-		/// If this is [`None`], this hints that no periodic change should be
-		/// made.
+		/// If this is [`Disabled`], this hints that no periodic change should
+		/// be made.
 		///
 		/// See [`SetScreenSaver::interval`] for more information.
 		///
+		/// [`Disabled`]: ScreenSaverDelay::Disabled
 		/// [`SetScreenSaver::interval`]: request::SetScreenSaver::interval
-		pub interval: Option<Sec<u16>>,
+		pub interval: ScreenSaverDelay,
 
 		/// Whether it is preferred that displays that support blanking go blank
 		/// when the screensaver is activated.
@@ -167,6 +276,8 @@ derive_xrb! {
 	/// [`QueryAccessControl` request]: request::QueryAccessControl
 	#[doc(alias("ListHosts"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryAccessControl: Reply for request::QueryAccessControl {
 		/// The sequence number identifying the [request] that generated this
@@ -179,7 +290,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether access control is [enabled].
@@ -202,3 +312,318 @@ derive_xrb! {
 		// added at the end here.
 	}
 }
+
+derive_xrb! {
+	/// A borrowed form of the [`ListExtensions` reply] which does not copy the
+	/// extension names.
+	///
+	/// Reading a [`ListExtensions` reply] allocates a new string for every
+	/// extension name; this type, read with [`ReadableRef`], instead borrows
+	/// the bytes of each name directly from the buffer it was read from.
+	///
+	/// [`ReadableRef`] reads this from the bytes following the first byte of
+	/// the reply, which identifies it as a reply.
+	///
+	/// [`ListExtensions` reply]: ListExtensions
+	/// [`ReadableRef`]: xrbk::ReadableRef
+	#[derive(Derivative, Debug, ReadableRef)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct ListExtensionsRef<'a> {
+		// The length of `names`.
+		#[allow(clippy::cast_possible_truncation)]
+		let names_len: u8 = names => names.len() as u8,
+
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[derivative(Hash = "ignore", PartialEq = "ignore")]
+		pub sequence: u16,
+
+		// The length of the reply after its first 32 bytes, in 4-byte units.
+		#[allow(clippy::cast_possible_truncation)]
+		let length: u32 = names => ((names.x11_size() + pad(names)) / 4) as u32,
+		[_; 24],
+
+		/// The names of all extensions supported by the X server.
+		#[context(names_len => usize::from(*names_len))]
+		pub names: Vec<LengthString8Ref<'a>>,
+		// The rest of the reply's `length`.
+		[_; length, names => (*length as usize * 4).saturating_sub(names.x11_size())],
+	}
+}
+
+impl X11Size for ListExtensionsRef<'_> {
+	fn x11_size(&self) -> usize {
+		const CONSTANT_SIZES: usize = 32;
+
+		CONSTANT_SIZES + self.names.x11_size() + pad(&self.names)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use crate::{AsciiString, HostAddress};
+	use std::{
+		collections::hash_map::DefaultHasher,
+		hash::{Hash, Hasher},
+	};
+	use xrbk::{ByteOrder, Readable, ReadableRef, Writable, X11Size};
+
+	round_trip_tests! {
+		QueryExtension => test_query_extension_round_trip,
+		ListExtensions => test_list_extensions_round_trip,
+		GetScreenSaver => test_get_screen_saver_round_trip,
+		QueryAccessControl => test_query_access_control_round_trip,
+	}
+
+	/// The hosts listed by `xhost` as:
+	/// ```text
+	/// access control enabled, only authorized clients can connect
+	/// INET:192.168.1.10
+	/// INET6:::1
+	/// SI:localuser:alice
+	/// ```
+	#[rustfmt::skip]
+	const XHOST_REPLY: [u8; 80] = [
+		// Reply, `access_control`, sequence, and length.
+		1, 1, 0, 7, 0, 0, 0, 12,
+		// The number of hosts and 22 unused bytes.
+		0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+
+		// IPv4 family, unused byte, and address length.
+		0, 0, 0, 4,
+		192, 168, 1, 10,
+
+		// IPv6 family, unused byte, and address length.
+		6, 0, 0, 16,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+
+		// Server interpreted family, unused byte, and address length.
+		5, 0, 0, 15,
+		b'l', b'o', b'c', b'a', b'l', b'u', b's', b'e', b'r', 0, b'a', b'l', b'i', b'c', b'e',
+		// Padding.
+		0,
+	];
+
+	#[test]
+	fn test_query_access_control_xhost_fixture() {
+		let ascii = |string: &str| AsciiString::new(string.as_bytes().to_vec()).unwrap();
+
+		let reply = QueryAccessControl::read_from(&mut &XHOST_REPLY[1..]).unwrap();
+
+		assert_eq!(reply.sequence, 7);
+		assert_eq!(reply.access_control, Toggle::Enabled);
+		assert_eq!(
+			reply.hosts,
+			[
+				Host::new(HostAddress::Ipv4([192, 168, 1, 10])),
+				Host::new(HostAddress::Ipv6(std::net::Ipv6Addr::LOCALHOST.octets())),
+				Host::new(HostAddress::ServerInterpreted {
+					address_type: ascii("localuser"),
+					address_value: ascii("alice"),
+				}),
+			],
+		);
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, XHOST_REPLY);
+		assert_eq!(bytes.len(), reply.x11_size());
+	}
+
+	#[test]
+	fn test_query_extension_zero_opcode_is_present() {
+		let reply = QueryExtension {
+			sequence: 1,
+			present: true,
+			major_opcode: Some(0),
+			first_event_code: Some(0),
+			first_error_code: Some(0),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), 32);
+
+		let read = QueryExtension::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(read, reply);
+	}
+
+	#[test]
+	fn test_query_extension_not_present_round_trip() {
+		let reply = QueryExtension {
+			sequence: 3,
+			present: false,
+			major_opcode: None,
+			first_event_code: None,
+			first_error_code: None,
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes[4..8], [0, 0, 0, 0]);
+
+		let read = QueryExtension::read_from(&mut &bytes[1..]).unwrap();
+		assert!(read.eq_including_sequence(&reply));
+	}
+
+	#[test]
+	fn test_query_extension_not_present_ignores_garbage() {
+		let mut bytes = vec![];
+		QueryExtension {
+			sequence: 1,
+			present: false,
+			major_opcode: None,
+			first_event_code: None,
+			first_error_code: None,
+		}
+		.write_to(&mut bytes)
+		.unwrap();
+		assert_eq!(bytes[9..12], [0, 0, 0]);
+
+		// Fill the opcode bytes with garbage.
+		bytes[9..12].copy_from_slice(&[0x80, 0x40, 0xff]);
+
+		let read = QueryExtension::read_from(&mut &bytes[1..]).unwrap();
+		assert_eq!(read.major_opcode, None);
+		assert_eq!(read.first_event_code, None);
+		assert_eq!(read.first_error_code, None);
+
+		// The garbage is normalized to zeros when written again.
+		let mut rewritten = vec![];
+		read.write_to(&mut rewritten).unwrap();
+		assert_eq!(rewritten[9..12], [0, 0, 0]);
+	}
+
+	#[test]
+	fn test_list_extensions_round_trip_in_both_orders() {
+		let names: Vec<LengthString8> = ["BIG-REQUESTS", "XKEYBOARD"]
+			.into_iter()
+			.map(|name| LengthString8::try_from(name).unwrap())
+			.collect();
+
+		for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+			let reply = ListExtensions {
+				sequence: 0x0102,
+				names: names.clone(),
+			};
+
+			let mut bytes = vec![];
+			reply.write_to_ordered(&mut bytes, order).unwrap();
+			assert_eq!(bytes.len(), reply.x11_size());
+
+			// The first byte of a reply is not read by `read_from`.
+			let read = ListExtensions::read_from_ordered(&mut &bytes[1..], order).unwrap();
+			assert_eq!(read, reply);
+
+			match order {
+				ByteOrder::BigEndian => assert_eq!(bytes[2..4], [0x01, 0x02]),
+				ByteOrder::LittleEndian => assert_eq!(bytes[2..4], [0x02, 0x01]),
+			}
+		}
+	}
+
+	#[test]
+	fn test_list_extensions_ref_matches_owned() {
+		let reply = ListExtensions {
+			sequence: 9,
+			names: ["BIG-REQUESTS", "Generic Event Extension", "XKEYBOARD"]
+				.into_iter()
+				.map(|name| LengthString8::try_from(name).unwrap())
+				.collect(),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+		// Trailing bytes belonging to whatever message comes next.
+		bytes.extend_from_slice(&[0xff; 4]);
+
+		let (borrowed, remaining) = ListExtensionsRef::read_ref(&bytes[1..]).unwrap();
+
+		assert_eq!(remaining, &[0xff; 4]);
+		assert_eq!(borrowed.x11_size(), reply.x11_size());
+
+		assert_eq!(borrowed.sequence, reply.sequence);
+
+		let names: Vec<_> = borrowed
+			.names
+			.iter()
+			.map(LengthString8Ref::as_bytes)
+			.collect();
+		assert_eq!(
+			names,
+			[
+				&b"BIG-REQUESTS"[..],
+				b"Generic Event Extension",
+				b"XKEYBOARD"
+			],
+		);
+	}
+
+	/// Returns the hash of the given `value`.
+	fn hash_of(value: &impl Hash) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		value.hash(&mut hasher);
+
+		hasher.finish()
+	}
+
+	#[test]
+	fn test_eq_ignores_sequence() {
+		let query_extension = |sequence| QueryExtension {
+			sequence,
+			present: true,
+			major_opcode: Some(130),
+			first_event_code: Some(64),
+			first_error_code: Some(128),
+		};
+		let (first, second) = (query_extension(1), query_extension(2));
+
+		assert_eq!(first, second);
+		assert_eq!(hash_of(&first), hash_of(&second));
+		assert!(!first.eq_including_sequence(&second));
+		assert!(first.eq_including_sequence(&query_extension(1)));
+
+		let list_extensions = |sequence| ListExtensions {
+			sequence,
+			names: vec![LengthString8::try_from("BIG-REQUESTS").unwrap()],
+		};
+		let (first, second) = (list_extensions(3), list_extensions(4));
+
+		assert_eq!(first, second);
+		assert_eq!(hash_of(&first), hash_of(&second));
+		assert!(!first.eq_including_sequence(&second));
+		assert!(first.eq_including_sequence(&list_extensions(3)));
+
+		let get_screen_saver = |sequence| GetScreenSaver {
+			sequence,
+			timeout: ScreenSaverDelay::new(600),
+			interval: ScreenSaverDelay::Disabled,
+			prefer_blanking: Toggle::Enabled,
+			allow_expose_events: Toggle::Disabled,
+		};
+		let (first, second) = (get_screen_saver(0xfffe), get_screen_saver(0xffff));
+
+		assert_eq!(first, second);
+		assert_eq!(hash_of(&first), hash_of(&second));
+		assert!(!first.eq_including_sequence(&second));
+		assert!(first.eq_including_sequence(&get_screen_saver(0xfffe)));
+
+		// Other fields are still compared.
+		assert_ne!(get_screen_saver(0), GetScreenSaver {
+			interval: ScreenSaverDelay::new(5),
+			..get_screen_saver(0)
+		});
+	}
+}
@@ -18,7 +18,7 @@ extern crate self as xrb;
 
 use derivative::Derivative;
 
-use xrbk::pad;
+use xrbk::{pad, X11Size};
 use xrbk_macro::derive_xrb;
 
 use crate::{
@@ -37,6 +37,8 @@ derive_xrb! {
 	/// [`GetAtom` request]: request::GetAtom
 	#[doc(alias("InternAtom", "CreateAtom"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetAtom: Reply for request::GetAtom {
 		/// The sequence number identifying the [request] that generated this
@@ -49,7 +51,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The returned [atom].
@@ -68,6 +69,8 @@ derive_xrb! {
 	///
 	/// [`GetAtomName` request]: request::GetAtomName
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetAtomName: Reply for request::GetAtomName {
 		/// The sequence number identifying the [request] that generated this
@@ -80,7 +83,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `name`.
@@ -102,7 +104,9 @@ derive_xrb! {
 	///
 	/// [`GetProperty` request]: request::GetProperty
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
+	#[arbitrary(with = crate::unstructured::get_property_replies)]
 	pub struct GetProperty: Reply for request::GetProperty {
 		/// The sequence number identifying the [request] that generated this
 		/// [reply].
@@ -114,7 +118,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// Whether the `value` is empty ([`None`]), or made up of `i8` values,
@@ -154,6 +157,7 @@ derive_xrb! {
 		/// empty list.
 		#[context(format, value_len => (format.unwrap_or(DataFormat::I8), *value_len))]
 		pub value: DataList,
+		[_; value => pad(value)],
 	}
 
 	/// The [reply] for a [`ListProperties` request].
@@ -162,6 +166,8 @@ derive_xrb! {
 	///
 	/// [`ListProperties` request]: request::ListProperties
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct ListProperties: Reply for request::ListProperties {
 		/// The sequence number identifying the [request] that generated this
@@ -174,7 +180,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		// The length of `properties`.
@@ -196,6 +201,8 @@ derive_xrb! {
 	///
 	/// [`GetSelectionOwner` request]: request::GetSelectionOwner
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetSelectionOwner: Reply for request::GetSelectionOwner {
 		/// The sequence number identifying the [request] that generated this
@@ -208,7 +215,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The owner of the given `selection`.
@@ -218,3 +224,158 @@ derive_xrb! {
 		[_; ..],
 	}
 }
+
+derive_xrb! {
+	/// A borrowed form of the [`GetProperty` reply] which does not copy the
+	/// property's `value`.
+	///
+	/// Property values may be very large - megabytes, even. Reading a
+	/// [`GetProperty` reply] copies the whole value into a freshly allocated
+	/// list; this type, read with [`ReadableRef`], instead borrows the raw
+	/// bytes of the value directly from the buffer it was read from.
+	///
+	/// [`ReadableRef`] reads this from the bytes following the first byte of
+	/// the reply, which identifies it as a reply.
+	///
+	/// [`GetProperty` reply]: GetProperty
+	/// [`ReadableRef`]: xrbk::ReadableRef
+	#[derive(Derivative, Debug, ReadableRef)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+	#[derivative(Hash, PartialEq, Eq)]
+	pub struct GetPropertyRef<'a> {
+		/// Whether the `value` is empty ([`None`]), or made up of `i8` values,
+		/// `i16` values, or `i32` values.
+		pub format: Option<DataFormat>,
+
+		/// The sequence number identifying the [request] that generated this
+		/// [reply].
+		///
+		/// See [`Reply::sequence`] for more information.
+		///
+		/// [request]: crate::message::Request
+		/// [reply]: Reply
+		///
+		/// [`Reply::sequence`]: Reply::sequence
+		#[derivative(Hash = "ignore", PartialEq = "ignore")]
+		pub sequence: u16,
+
+		// The length of the reply after its first 32 bytes, in 4-byte units.
+		#[allow(clippy::cast_possible_truncation)]
+		let length: u32 = value => ((value.len() + pad(value)) / 4) as u32,
+
+		/// The actual type of the property.
+		pub r#type: Option<Atom>,
+		/// The number of bytes remaining in the `property`'s data.
+		///
+		/// See [`GetProperty::bytes_remaining`] for more information.
+		#[doc(alias = "bytes_after")]
+		pub bytes_remaining: u32,
+
+		// The length of `value` in `format`-sized units.
+		#[allow(clippy::cast_possible_truncation)]
+		let value_len: u32 = format, value => format.map_or(0, |format| {
+			value.len() / usize::from(u8::from(format) / 8)
+		}) as u32,
+		[_; 12],
+
+		/// The raw bytes of the property's value.
+		///
+		/// These bytes are not interpreted according to the `format`: for
+		/// [`DataFormat::I16`] and [`DataFormat::I32`], each value is made up
+		/// of two or four bytes respectively.
+		#[context(format, value_len => format.map_or(0, |format| {
+			(*value_len as usize) * usize::from(u8::from(format) / 8)
+		}))]
+		pub value: &'a [u8],
+		// The rest of the reply's `length`.
+		[_; length, value => (*length as usize * 4).saturating_sub(value.len())],
+	}
+}
+
+impl X11Size for GetPropertyRef<'_> {
+	fn x11_size(&self) -> usize {
+		const CONSTANT_SIZES: usize = 32;
+
+		CONSTANT_SIZES + self.value.x11_size() + pad(&self.value)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use xrbk::{Readable, ReadableRef, Writable};
+
+	round_trip_tests! {
+		GetAtom => test_get_atom_round_trip,
+		GetAtomName => test_get_atom_name_round_trip,
+		GetProperty => test_get_property_round_trip,
+		ListProperties => test_list_properties_round_trip,
+		GetSelectionOwner => test_get_selection_owner_round_trip,
+	}
+
+	#[test]
+	fn test_get_property_empty_values() {
+		// The property does not exist.
+		let missing = GetProperty {
+			sequence: 3,
+			format: None,
+			r#type: None,
+			bytes_remaining: 0,
+			value: DataList::I8(vec![]),
+		};
+		// The property exists, but its type did not match the requested type.
+		let mismatched = GetProperty {
+			sequence: 3,
+			format: Some(DataFormat::I32),
+			r#type: Some(Atom::new(33)),
+			bytes_remaining: 8,
+			value: DataList::I32(vec![]),
+		};
+
+		for (reply, header) in [
+			(&missing, [1, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+			(
+				&mismatched,
+				[1, 32, 0, 3, 0, 0, 0, 0, 0, 0, 0, 33, 0, 0, 0, 8],
+			),
+		] {
+			let mut bytes = vec![];
+			reply.write_to(&mut bytes).unwrap();
+
+			assert_eq!(bytes.len(), 32);
+			assert_eq!(bytes[..16], header);
+			// The number of values and 12 unused bytes.
+			assert_eq!(bytes[16..], [0; 16]);
+
+			assert_eq!(&GetProperty::read_from(&mut &bytes[1..]).unwrap(), reply);
+		}
+	}
+
+	#[test]
+	fn test_get_property_ref_matches_owned() {
+		let reply = GetProperty {
+			sequence: 7,
+			format: Some(DataFormat::I16),
+			r#type: Some(Atom::new(31)),
+			bytes_remaining: 0,
+			value: DataList::I16((0..513).collect()),
+		};
+
+		let mut bytes = vec![];
+		reply.write_to(&mut bytes).unwrap();
+		// Trailing bytes belonging to whatever message comes next.
+		bytes.extend_from_slice(&[0xff; 4]);
+
+		let (borrowed, remaining) = GetPropertyRef::read_ref(&bytes[1..]).unwrap();
+
+		assert_eq!(remaining, &[0xff; 4]);
+		assert_eq!(borrowed.x11_size(), reply.x11_size());
+
+		assert_eq!(borrowed.sequence, reply.sequence);
+		assert_eq!(borrowed.format, reply.format);
+		assert_eq!(borrowed.r#type, reply.r#type);
+		assert_eq!(borrowed.value.len(), 513 * 2);
+		assert_eq!(&borrowed.value[..4], &[0, 0, 0, 1]);
+	}
+}
@@ -22,7 +22,6 @@ use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
 
 use crate::{
 	message::Reply,
-	unit::Px,
 	visual::{ColorId, VisualId},
 	x11::request,
 	BitGravity,
@@ -30,31 +29,34 @@ use crate::{
 	DeviceEventMask,
 	EventMask,
 	MaintainContents,
-	Rectangle,
 	Window,
 	WindowClass,
+	WindowGeometry,
 	WindowGravity,
 };
 
 /// The state of the [window] regarding how it is mapped.
 ///
 /// [window]: Window
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MapState {
 	/// The [window] is not mapped.
 	///
 	/// [window]: Window
-	Unmapped,
+	Unmapped = 0,
 
 	/// The [window] is mapped but one of its ancestors is unmapped.
 	///
 	/// [window]: Window
-	Unviewable,
+	Unviewable = 1,
 
 	/// The [window] is mapped and all of its ancestors are mapped.
 	///
 	/// [window]: Window
-	Viewable,
+	Viewable = 2,
 }
 
 derive_xrb! {
@@ -64,6 +66,8 @@ derive_xrb! {
 	///
 	/// [`GetWindowAttributes` request]: request::GetWindowAttributes
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetWindowAttributes: Reply for request::GetWindowAttributes {
 		/// The sequence number identifying the [request] that generated this
@@ -76,7 +80,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The conditions under which the X server should maintain the obscured
@@ -242,6 +245,8 @@ derive_xrb! {
 	/// [`GetGeometry` request]: request::GetGeometry
 	#[doc(alias("GetX", "GetY", "GetWidth", "GetHeight", "GetBorderWidth"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct GetGeometry: Reply for request::GetGeometry {
 		/// The sequence number identifying the [request] that generated this
@@ -254,7 +259,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The number of bits per pixel for the [drawable].
@@ -271,23 +275,16 @@ derive_xrb! {
 
 		/// The [drawable]'s geometry.
 		///
-		/// For a [pixmap], the `x` and `y` coordinates will always be zero.
+		/// For a [pixmap], the `coords` and `border_width` will always be zero.
 		///
-		/// For a [window], the coordinates are relative to the top-left corner
-		/// of the [window]'s parent.
+		/// For a [window], the `coords` are relative to the top-left corner of
+		/// the [window]'s parent.
 		///
 		/// [window]: Window
 		/// [pixmap]: crate::Pixmap
 		/// [drawable]: crate::Drawable
-		#[doc(alias("x", "y", "width", "height"))]
-		pub geometry: Rectangle,
-		/// The width of the [drawable]'s border.
-		///
-		/// For a [pixmap], this will always be zero.
-		///
-		/// [pixmap]: crate::Pixmap
-		/// [drawable]: crate::Drawable
-		pub border_width: Px<u16>,
+		#[doc(alias("x", "y", "width", "height", "border_width"))]
+		pub geometry: WindowGeometry,
 		[_; ..],
 	}
 
@@ -300,6 +297,8 @@ derive_xrb! {
 	#[doc(alias("QueryParent", "QueryChildren", "QueryRoot"))]
 	#[doc(alias("GetParent", "GetChildren", "GetRoot"))]
 	#[derive(Derivative, Debug, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	#[derivative(Hash, PartialEq, Eq)]
 	pub struct QueryWindowTree: Reply for request::QueryWindowTree {
 		/// The sequence number identifying the [request] that generated this
@@ -312,7 +311,6 @@ derive_xrb! {
 		///
 		/// [`Reply::sequence`]: Reply::sequence
 		#[sequence]
-		#[derivative(Hash = "ignore", PartialEq = "ignore")]
 		pub sequence: u16,
 
 		/// The `target` [window]'s root [window].
@@ -336,3 +334,111 @@ derive_xrb! {
 		pub children: Vec<Window>,
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+
+	use crate::{unit::Px, Coords, Dimensions};
+	use xrbk::{ConstantX11Size, Readable, Writable};
+
+	round_trip_tests! {
+		GetWindowAttributes => test_get_window_attributes_round_trip,
+		GetGeometry => test_get_geometry_round_trip,
+		QueryWindowTree => test_query_window_tree_round_trip,
+	}
+
+	#[test]
+	fn test_get_window_attributes_fixture() {
+		#[rustfmt::skip]
+		let bytes = [
+			// Reply, backing-store `WhenMapped`, sequence, length.
+			1, 1, 0x00, 0x2a, 0, 0, 0, 3,
+			// Visual.
+			0x00, 0x00, 0x00, 0x21,
+			// Class `InputOutput`, bit-gravity `Static`, win-gravity
+			// `NorthWest`.
+			0x00, 0x01, 10, 1,
+			// Backing-planes.
+			0xff, 0xff, 0xff, 0xff,
+			// Backing-pixel.
+			0x00, 0x00, 0x00, 0x00,
+			// Save-under, map-is-installed, map-state `Viewable`,
+			// override-redirect.
+			0, 1, 2, 1,
+			// Colormap.
+			0x00, 0x00, 0x00, 0x20,
+			// All-event-masks: `StructureNotify`, `PropertyChange`.
+			0x00, 0x42, 0x00, 0x00,
+			// Your-event-mask: `PropertyChange`.
+			0x00, 0x40, 0x00, 0x00,
+			// Do-not-propagate-mask, 2 unused bytes.
+			0x00, 0x00, 0, 0,
+		];
+
+		let reply = GetWindowAttributes::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(reply.sequence, 42);
+		assert_eq!(reply.maintain_contents, MaintainContents::WhenMapped);
+		assert_eq!(reply.visual, VisualId::new(0x21));
+		assert_eq!(reply.class, WindowClass::InputOutput);
+		assert_eq!(reply.bit_gravity, BitGravity::Static);
+		assert_eq!(reply.window_graivty, WindowGravity::NorthWest);
+		assert_eq!(reply.maintained_planes, 0xffff_ffff);
+		assert!(!reply.maintain_windows_under);
+		assert!(reply.map_installed);
+		assert_eq!(reply.map_state, MapState::Viewable);
+		assert!(reply.override_redirect);
+		assert_eq!(reply.colormap, Some(Colormap::new(0x20)));
+		assert_eq!(
+			reply.all_event_masks,
+			EventMask::STRUCTURE_NOTIFY | EventMask::PROPERTY_CHANGE,
+		);
+		assert_eq!(reply.your_event_mask, EventMask::PROPERTY_CHANGE);
+		assert_eq!(reply.do_not_propagate_mask, DeviceEventMask::empty());
+
+		let mut written = vec![];
+		reply.write_to(&mut written).unwrap();
+
+		assert_eq!(written, bytes);
+		assert_eq!(reply.length(), 3);
+	}
+
+	#[test]
+	fn test_get_geometry_fixture() {
+		#[rustfmt::skip]
+		let bytes = [
+			// Reply, depth, sequence, length.
+			1, 24, 0x00, 0x07, 0, 0, 0, 0,
+			// Root.
+			0x00, 0x00, 0x05, 0x3a,
+			// X, y.
+			0x00, 0x0a, 0xff, 0xec,
+			// Width, height.
+			0x02, 0x80, 0x01, 0xe0,
+			// Border-width, 10 unused bytes.
+			0x00, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		];
+
+		let reply = GetGeometry::read_from(&mut &bytes[1..]).unwrap();
+
+		assert_eq!(reply.sequence, 7);
+		assert_eq!(reply.depth, 24);
+		assert_eq!(reply.root, Window::new(0x53a));
+		assert_eq!(
+			reply.geometry,
+			WindowGeometry::new(
+				Coords::new(Px(10), Px(-20)),
+				Dimensions::new(Px(640), Px(480)),
+				Px(2),
+			),
+		);
+
+		let mut written = vec![];
+		reply.write_to(&mut written).unwrap();
+
+		assert_eq!(written, bytes);
+		assert_eq!(GetGeometry::X11_SIZE, 32);
+	}
+}
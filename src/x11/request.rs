@@ -9,6 +9,67 @@
 //! [Requests]: crate::message::Request
 //! [core X11 protocol]: super
 
+/// Defines an enum of the [errors] which may be generated by a [request], with
+/// an [`ErrorCodes`] implementation listing their [codes].
+///
+/// Each [error] is given by the name of its type in [`x11::error`], and each
+/// variant wraps the [error] of the same name:
+/// ```ignore
+/// request_error! {
+///     pub enum CreateColormapError for CreateColormap {
+///         ResourceIdChoice,
+///         Match,
+///         Value,
+///         Window,
+///     }
+/// }
+/// ```
+///
+/// `error` must refer to [`x11::error`] where this is used.
+///
+/// [error]: crate::message::Error
+/// [errors]: crate::message::Error
+/// [request]: crate::message::Request
+/// [codes]: crate::message::Error::CODE
+/// [`ErrorCodes`]: crate::message::ErrorCodes
+/// [`x11::error`]: crate::x11::error
+macro_rules! request_error {
+	(
+		$(#[$meta:meta])*
+		$vis:vis enum $Name:ident for $Request:ty {
+			$($($Error:ident),+$(,)?)?
+		}
+	) => {
+		#[doc = concat!(
+			"An [error](crate::message::Error) generated because of a failed [`",
+			stringify!($Request),
+			"` request](",
+			stringify!($Request),
+			")."
+		)]
+		#[doc = ""]
+		$(#[$meta])*
+		$vis enum $Name {
+			$($(
+				#[doc = concat!(
+					"A [`",
+					stringify!($Error),
+					"` error](error::",
+					stringify!($Error),
+					")."
+				)]
+				$Error(error::$Error)
+			),+)?
+		}
+
+		impl $crate::message::ErrorCodes for $Name {
+			const CODES: &'static [u8] = &[$($(
+				<error::$Error as $crate::message::Error>::CODE
+			),+)?];
+		}
+	};
+}
+
 // TODO: should these modules be private and re-exported, or public?
 //       or public and also re-exported?
 
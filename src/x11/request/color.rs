@@ -12,7 +12,7 @@
 extern crate self as xrb;
 
 use xrbk::{pad, ConstantX11Size};
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
 	message::Request,
@@ -24,37 +24,6 @@ use crate::{
 	Window,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum CreateColormapError for CreateColormap {
 		ResourceIdChoice,
@@ -74,7 +43,9 @@ request_error! {
 /// [all entries allocated]: InitialColormapAllocation::All
 ///
 /// [colormap]: Colormap
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InitialColormapAllocation {
 	/// The [colormap] initially has no entries, or those initial entries are
 	/// defined elsewhere.
@@ -139,7 +110,8 @@ derive_xrb! {
 	/// [`ResourceIdChoice` error]: error::ResourceIdChoice
 	/// [`Window` error]: error::Window
 	/// [`Match` error]: error::Match
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreateColormap: Request(78, CreateColormapError) {
 		/// Whether this [colormap] begins with [no entries allocated] or
 		/// [all entries allocated].
@@ -229,7 +201,8 @@ derive_xrb! {
 	///
 	/// [`Colormap` error]: error::Colormap
 	#[doc(alias("FreeColormap"))]
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyColormap: Request(79, error::Colormap) {
 		/// The [colormap] which is to be deleted.
 		///
@@ -271,6 +244,7 @@ derive_xrb! {
 	/// [`Colormap` error]: error::Colormap
 	#[doc(alias("CopyColormapAndFree"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct MoveColormap: Request(80, MoveColormapError) {
 		/// The [`Colormap` ID] that will be associated with the new [colormap].
 		///
@@ -345,7 +319,8 @@ derive_xrb! {
 	/// [`Colormap` event]: crate::x11::event::Colormap
 	///
 	/// [`Colormap` error]: error::Colormap
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct InstallColormap: Request(81, error::Colormap) {
 		/// The [colormap] that is to be installed.
 		///
@@ -402,7 +377,8 @@ derive_xrb! {
 	/// [`Colormap` event]: crate::x11::event::Colormap
 	///
 	/// [`Colormap` error]: error::Colormap
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UninstallColormap: Request(82, error::Colormap) {
 		/// The [colormap] that is to be uninstalled.
 		///
@@ -434,7 +410,8 @@ derive_xrb! {
 	/// [`ListInstalledColormaps` reply]: reply::ListInstalledColormaps
 	///
 	/// [`Window` error]: error::Window
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ListInstalledColormaps: Request(83, error::Window) -> reply::ListInstalledColormaps {
 		/// The [window] for which this [request] returns its installed
 		/// [colormaps].
@@ -479,6 +456,8 @@ derive_xrb! {
 	/// [`Colormap` error]: error::Colormap
 	#[doc(alias("AllocColor"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AllocateColor: Request(84, error::Colormap) -> reply::AllocateColor {
 		/// The [colormap] for which the [colormap] entry is allocated.
 		///
@@ -526,6 +505,8 @@ derive_xrb! {
 	/// [`Name` error]: error::Name
 	#[doc(alias("AllocNamedColor"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AllocateNamedColor: Request(
 		85,
 		AllocateNamedColorError,
@@ -602,6 +583,7 @@ derive_xrb! {
 	/// [`Value` error]: error::Value
 	#[doc(alias("AllocColorCells"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AllocateColorCells: Request(
 		86,
 		AllocateColorCellsError,
@@ -700,6 +682,7 @@ derive_xrb! {
 	/// [`RequestError::Alloc`]: crate::message::RequestError::Alloc
 	#[doc(alias("AllocColorPlanes"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AllocateColorPlanes: Request(
 		87,
 		AllocateColorPlanesError,
@@ -795,6 +778,7 @@ derive_xrb! {
 	// TODO: rename all Destroy* requests to Delete*
 	#[doc(alias("FreeColors"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyColormapEntries: Request(88, DestroyColormapEntriesError) {
 		/// The [colormap] for which the [colormap] entries are deleted.
 		///
@@ -839,6 +823,7 @@ derive_xrb! {
 	///
 	/// [`StoreColors` request]: StoreColors
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ColormapEntryChange {
 		/// The [`ColorId`] of the changed [colormap] entry.
 		///
@@ -855,15 +840,6 @@ derive_xrb! {
 		_,
 	}
 
-	impl ConstantX11Size for ColormapEntryChange {
-		const X11_SIZE: usize = {
-			ColorId::X11_SIZE
-			+ RgbColor::X11_SIZE
-			+ ColorChannelMask::X11_SIZE
-			+ 1
-		};
-	}
-
 	/// A [request] that changes the [RGB values] of the given [colormap]
 	/// entries.
 	///
@@ -887,6 +863,7 @@ derive_xrb! {
 	/// [`Colormap` error]: error::Colormap
 	/// [`Value` error]: error::Value
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct StoreColors: Request(89, StoreColorsError) {
 		/// The [colormap] for which the [colormap] entries are changed.
 		///
@@ -957,7 +934,9 @@ derive_xrb! {
 	/// [`Value` error]: error::Value
 	/// [`Name` error]: error::Name
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct StoreNamedColor: Request(90, StoreNamedColorError) {
+		#[metabyte]
 		/// The mask for which of the [colormap] entry's color channels are
 		/// changed.
 		///
@@ -1032,6 +1011,8 @@ derive_xrb! {
 	/// [`Colormap` error]: error::Colormap
 	/// [`Value` error]: error::Value
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryColors: Request(91, QueryColorsError) -> reply::QueryColors {
 		/// The [colormap] on which the [RGB values] of the given [colormap]
 		/// entries are queried.
@@ -1096,6 +1077,8 @@ derive_xrb! {
 	/// [`Name` error]: error::Name
 	#[doc(alias("LookupColor"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetNamedColor: Request(92, GetNamedColorError) -> reply::GetNamedColor {
 		/// The [colormap] whose [screen] defines the requested color.
 		///
@@ -1113,7 +1096,7 @@ derive_xrb! {
 		// The length of `name`.
 		#[allow(clippy::cast_possible_truncation)]
 		let name_len: u16 = name => name.len() as u16,
-		[_; 22],
+		[_; 2],
 
 		/// The name of the color which this [request] gets the [RGB values] of.
 		///
@@ -1133,3 +1116,171 @@ derive_xrb! {
 		[_; name => pad(name)],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use xrbk::{Readable, Writable, X11Size};
+
+	round_trip_tests! {
+		AllocateColor => test_allocate_color_round_trip,
+		AllocateNamedColor => test_allocate_named_color_round_trip,
+		QueryColors => test_query_colors_round_trip,
+		GetNamedColor => test_get_named_color_round_trip,
+	}
+
+	/// Writes the `request`, checks that it matches the given `bytes`, and
+	/// checks that the `bytes` read back to the same `request`.
+	fn assert_fixture<Req>(request: &Req, bytes: &[u8])
+	where
+		Req: Request + ConstantX11Size + Readable + PartialEq + std::fmt::Debug,
+	{
+		let mut written = vec![];
+		request.write_to(&mut written).unwrap();
+
+		assert_eq!(written, bytes);
+		assert_eq!(written.len(), Req::X11_SIZE);
+
+		// The major opcode is not read by `read_from`.
+		assert_eq!(&Req::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_create_colormap_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&CreateColormap {
+				initial_allocation: InitialColormapAllocation::All,
+				colormap_id: Colormap::new(0x0020_0001),
+				window: Window::new(0x0000_0100),
+				visual: VisualId::new(0x0000_0021),
+			},
+			&[
+				78, 1, 0, 4, // header
+				0x00, 0x20, 0x00, 0x01, // colormap_id
+				0x00, 0x00, 0x01, 0x00, // window
+				0x00, 0x00, 0x00, 0x21, // visual
+			],
+		);
+	}
+
+	#[test]
+	fn test_destroy_colormap_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&DestroyColormap {
+				target: Colormap::new(0x0020_0001),
+			},
+			&[
+				79, 0, 0, 2, // header
+				0x00, 0x20, 0x00, 0x01, // target
+			],
+		);
+	}
+
+	#[test]
+	fn test_move_colormap_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&MoveColormap {
+				colormap_id: Colormap::new(0x0020_0002),
+				source: Colormap::new(0x0020_0001),
+			},
+			&[
+				80, 0, 0, 3, // header
+				0x00, 0x20, 0x00, 0x02, // colormap_id
+				0x00, 0x20, 0x00, 0x01, // source
+			],
+		);
+	}
+
+	#[test]
+	fn test_install_colormap_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&InstallColormap {
+				target: Colormap::new(0x0020_0001),
+			},
+			&[
+				81, 0, 0, 2, // header
+				0x00, 0x20, 0x00, 0x01, // target
+			],
+		);
+	}
+
+	#[test]
+	fn test_uninstall_colormap_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&UninstallColormap {
+				target: Colormap::new(0x0020_0001),
+			},
+			&[
+				82, 0, 0, 2, // header
+				0x00, 0x20, 0x00, 0x01, // target
+			],
+		);
+	}
+
+	#[test]
+	fn test_list_installed_colormaps_fixture() {
+		#[rustfmt::skip]
+		assert_fixture(
+			&ListInstalledColormaps {
+				target: Window::new(0x0000_0100),
+			},
+			&[
+				83, 0, 0, 2, // header
+				0x00, 0x00, 0x01, 0x00, // target
+			],
+		);
+	}
+
+	#[test]
+	fn test_query_colors_fixture() {
+		let request = QueryColors {
+			target: Colormap::new(0x0020_0001),
+			colors: vec![ColorId::ZERO, ColorId::ONE, ColorId::new(0x00ff_8000)],
+		};
+
+		#[rustfmt::skip]
+		let bytes = [
+			91, 0, 0, 5, // header
+			0x00, 0x20, 0x00, 0x01, // target
+			0x00, 0x00, 0x00, 0x00, // colors[0]
+			0x00, 0x00, 0x00, 0x01, // colors[1]
+			0x00, 0xff, 0x80, 0x00, // colors[2]
+		];
+
+		let mut written = vec![];
+		request.write_to(&mut written).unwrap();
+		assert_eq!(written, bytes);
+
+		assert_eq!(QueryColors::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_store_named_color_mask_is_metabyte() {
+		let request = StoreNamedColor {
+			mask: ColorChannelMask::RED | ColorChannelMask::BLUE,
+			target: Colormap::new(0x0020_0001),
+			id: ColorId::ONE,
+			name: String8::from_latin1("red").unwrap(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 20);
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(bytes[..4], [90, 0x05, 0, 5]);
+		assert_eq!(bytes[12..16], [0, 3, 0, 0]);
+		assert_eq!(bytes[16..], [b'r', b'e', b'd', 0]);
+
+		assert_eq!(
+			StoreNamedColor::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+	}
+}
@@ -25,37 +25,6 @@ use crate::{
 	String8,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum AssignFontError for AssignFont {
 		ResourceIdChoice,
@@ -70,6 +39,8 @@ derive_xrb! {
 	/// [request]: Request
 	#[doc(alias("OpenFont", "CreateFont", "LoadFont", "AddFont"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AssignFont: Request(45, AssignFontError) {
 		/// The [`Font` ID] to associate with the font specified by `name`.
 		///
@@ -100,6 +71,8 @@ derive_xrb! {
 	/// [`Font` ID]: Font
 	#[doc(alias("CloseFont", "DeleteFont", "UnloadFont", "RemoveFont"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UnassignFont: Request(46) {
 		/// The [`Font` ID] which is having its association with a font removed.
 		///
@@ -125,6 +98,8 @@ derive_xrb! {
 	///
 	/// [`Font` error]: error::Font
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryFont: Request(47, error::Font) -> reply::QueryFont {
 		/// The font which this [request] returns information about.
 		///
@@ -174,6 +149,7 @@ derive_xrb! {
 	///
 	/// [`Font` error]: error::Font
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryTextExtents: Request(48, error::Font) -> reply::QueryTextExtents {
 		// Whether `text` is of odd length. Is it is, it has 2 bytes of padding
 		// following it.
@@ -197,7 +173,7 @@ derive_xrb! {
 		/// [request]: Request
 		#[context(self::remaining, odd_length => {
 			// We remove the padding at the end, which can be determined from `odd_length`.
-			let remaining = remaining - query_text_extents_padding(*odd_length);
+			let remaining = remaining.saturating_sub(query_text_extents_padding(*odd_length));
 
 			// We then divide the length, which is the number of bytes, by the number of bytes
 			// per character.
@@ -219,6 +195,7 @@ derive_xrb! {
 	///
 	/// [`ListFonts` reply]: reply::ListFonts
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ListFonts: Request(49) -> reply::ListFonts {
 		/// The maximum number of names that will appear in the returned font
 		/// `names`.
@@ -258,6 +235,7 @@ derive_xrb! {
 	/// [`ListFontsWithInfo` replies]: reply::ListFontsWithInfo
 	/// [`QueryFont` reply]: reply::QueryFont
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ListFontsWithInfo: Request(50) -> reply::ListFontsWithInfo {
 		/// The maximum number of [`FontWithInfo` replies] that will be returned.
 		///
@@ -294,6 +272,7 @@ derive_xrb! {
 	/// [`Value` error]: error::Value
 	#[doc(alias = "SetFontPath")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetFontSearchDirectories: Request(51, error::Value) {
 		// The length of `directories`.
 		#[allow(clippy::cast_possible_truncation)]
@@ -317,5 +296,61 @@ derive_xrb! {
 	///
 	/// [request]: Request
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetFontSearchDirectories: Request(52) -> reply::GetFontSearchDirectories;
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{arbitrary::round_trip_tests, GraphicsContext};
+	use xrbk::{Readable, Writable};
+
+	round_trip_tests! {
+		AssignFont => test_assign_font_round_trip,
+		UnassignFont => test_unassign_font_round_trip,
+		QueryFont => test_query_font_round_trip,
+	}
+
+	#[test]
+	fn test_list_fonts_fixture() {
+		let request = ListFonts {
+			max_names_count: 10,
+			pattern: String8::from_latin1("*fixed*").unwrap(),
+		};
+
+		#[rustfmt::skip]
+		let bytes = [
+			49, 0, 0, 4, // header
+			0, 10, // max_names_count
+			0, 7, // pattern_len
+			b'*', b'f', b'i', b'x', b'e', b'd', b'*', // pattern
+			0, // padding
+		];
+
+		let mut written = vec![];
+		request.write_to(&mut written).unwrap();
+		assert_eq!(written, bytes);
+
+		// The major opcode is not read by `read_from`.
+		assert_eq!(ListFonts::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_query_font_accepts_fonts_and_graphics_contexts() {
+		let font = QueryFont {
+			target: Font::new(0x0060_0001).into(),
+		};
+		let context = QueryFont {
+			target: GraphicsContext::new(0x0060_0001).into(),
+		};
+
+		// Both are written as the same resource ID.
+		let (mut font_bytes, mut context_bytes) = (vec![], vec![]);
+		font.write_to(&mut font_bytes).unwrap();
+		context.write_to(&mut context_bytes).unwrap();
+
+		assert_eq!(font_bytes, [47, 0, 0, 2, 0x00, 0x60, 0x00, 0x01]);
+		assert_eq!(font_bytes, context_bytes);
+	}
+}
@@ -22,6 +22,7 @@ use xrbk::{
 	Buf,
 	BufMut,
 	ConstantX11Size,
+	ReadError,
 	ReadResult,
 	Readable,
 	ReadableWithContext,
@@ -32,7 +33,7 @@ use xrbk::{
 use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
-	message::Request,
+	message::{write_request_length, LengthMode, Request, WritableRequest},
 	unit::Px,
 	x11::{error, reply},
 	Arc,
@@ -47,37 +48,6 @@ use crate::{
 	Window,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum ClearAreaError for ClearArea {
 		Match,
@@ -111,6 +81,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Match` error]: error::Match
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ClearArea: Request(61, ClearAreaError) {
 		/// Whether [`GraphicsExposure` events] should be generated for regions
 		/// of the `area` which are visible or maintained.
@@ -158,6 +130,10 @@ derive_xrb! {
 	/// A [request] that copies an area of the given `source` [drawable] into
 	/// the given `destination` [drawable].
 	///
+	/// The `source` and `destination` may be the same [drawable], and the area
+	/// which is copied may overlap the area it is copied to: the result is the
+	/// same as if the whole area was copied before any of it was replaced.
+	///
 	/// [Regions][regions] of the `source` that are obscured and have not been
 	/// [maintained], as well as [regions] specified by `source_coords` and
 	/// `dimensions` fall outside of the `source` itself, are not copied. If the
@@ -210,6 +186,8 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	/// [`Match` error]: error::Match
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CopyArea: Request(62, CopyAreaError) {
 		/// The [drawable] from which the area is copied.
 		///
@@ -277,6 +255,87 @@ derive_xrb! {
 	}
 }
 
+/// A single bit plane of a [drawable].
+///
+/// A `BitPlane` has exactly one bit set: bit plane `n` is represented by the
+/// value <code>1 << n</code>.
+///
+/// This is used in the [`CopyBitPlane` request].
+///
+/// [drawable]: Drawable
+///
+/// [`CopyBitPlane` request]: CopyBitPlane
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Writable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BitPlane(u32);
+
+/// An error generated when a [`BitPlane`] is created from a value which does
+/// not have exactly one bit set.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Error)]
+#[error("a bit plane must have exactly one bit set, found {0:#x}")]
+pub struct InvalidBitPlane(pub u32);
+
+impl BitPlane {
+	/// Creates a new `BitPlane` from its value.
+	///
+	/// # Errors
+	/// Returns an [`InvalidBitPlane`] error if the `value` does not have
+	/// exactly one bit set.
+	pub const fn new(value: u32) -> Result<Self, InvalidBitPlane> {
+		if value.is_power_of_two() {
+			Ok(Self(value))
+		} else {
+			Err(InvalidBitPlane(value))
+		}
+	}
+
+	/// Returns the bit plane with the given `index`, which is the position of
+	/// its bit.
+	///
+	/// Returns [`None`] if the `index` is not less than 32.
+	#[must_use]
+	pub const fn nth(index: u32) -> Option<Self> {
+		match 1_u32.checked_shl(index) {
+			Some(value) => Some(Self(value)),
+			None => None,
+		}
+	}
+
+	/// Unwraps the value of the `BitPlane`, which has exactly one bit set.
+	#[must_use]
+	pub const fn unwrap(self) -> u32 {
+		let Self(value) = self;
+
+		value
+	}
+
+	/// Returns the index of the bit plane, which is the position of its bit.
+	#[must_use]
+	pub const fn index(self) -> u32 {
+		self.unwrap().trailing_zeros()
+	}
+}
+
+impl Readable for BitPlane {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		Self::new(u32::read_from(buf)?).map_err(|error| ReadError::FailedConversion(Box::new(error)))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BitPlane {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The bit plane is checked with `new`.
+		Self::new(u32::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 request_error! {
 	#[doc(alias("CopyPlaneError"))]
 	pub enum CopyBitPlaneError for CopyBitPlane {
@@ -298,6 +357,9 @@ derive_xrb! {
 	/// contains a bit set to 1, and [`background_color`] where the `bit_plane`
 	/// in the `source` [drawable] contains a bit set to 0.
 	///
+	/// As in the [`CopyArea` request], the `source` and `destination` may be
+	/// the same [drawable], and the areas may overlap.
+	///
 	/// # Graphics options used
 	/// This [request] uses the following [options] of the `graphics_context`:
 	/// - [`function`]
@@ -321,9 +383,9 @@ derive_xrb! {
 	/// A [`Match` error] is generated if the `source` [drawable] does not have
 	/// the same root [window] as the `destination` [drawable].
 	///
-	/// A [`Value` error] is generated if the `bit_plane` does not have exactly
-	/// one bit set to 1, or if the value of the `bit_plane` is not less than
-	/// 2<sup>`depth`</sup>, where `depth` is the `source` [drawable]'s depth.
+	/// A [`Value` error] is generated if the value of the `bit_plane` is not
+	/// less than 2<sup>`depth`</sup>, where `depth` is the `source`
+	/// [drawable]'s depth.
 	///
 	/// [drawable]: Drawable
 	/// [pixmap]: Pixmap
@@ -342,12 +404,16 @@ derive_xrb! {
 	/// [`clip_y`]: GraphicsOptions::clip_y
 	/// [`clip_mask`]: GraphicsOptions::clip_mask
 	///
+	/// [`CopyArea` request]: CopyArea
+	///
 	/// [`Drawable` error]: error::Drawable
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	/// [`Match` error]: error::Match
 	/// [`Value` error]: error::Value
 	#[doc(alias("CopyPlane"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CopyBitPlane: Request(63, CopyBitPlaneError) {
 		/// The [drawable] used as the source in this graphics operation.
 		///
@@ -426,19 +492,18 @@ derive_xrb! {
 
 		/// The bit plane that is copied.
 		///
-		/// Exactly one bit must be set and the value must be less than
-		/// 2<sup>`depth`</sup>, where `depth` is the depth of the `source`
-		/// [drawable].
+		/// The value must be less than 2<sup>`depth`</sup>, where `depth` is
+		/// the depth of the `source` [drawable].
 		///
 		/// # Errors
-		/// A [`Value` error] is generated if this does not have exactly one bit
-		/// set to 1, or if this value is not less than 2<sup>`depth`</sup>,
-		/// where `depth` is the depth of the `source` [drawable].
+		/// A [`Value` error] is generated if this value is not less than
+		/// 2<sup>`depth`</sup>, where `depth` is the depth of the `source`
+		/// [drawable].
 		///
 		/// [drawable]: Drawable
 		///
 		/// [`Value` error]: error::Value
-		pub bit_plane: u32,
+		pub bit_plane: BitPlane,
 	}
 }
 
@@ -459,7 +524,9 @@ request_error! {
 ///
 /// [coordinates]: Coords
 /// [drawable]: Drawable
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CoordinateMode {
 	/// [Coordinates] are relative to the top-left corner of the [drawable].
 	///
@@ -518,6 +585,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias("PolyPoint", "DrawPoint"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DrawPoints: Request(64, DrawPointsError) {
 		/// Whether the `points` are drawn relative to the `target` or the
 		/// previously drawn point.
@@ -658,6 +726,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolyLine", "DrawLines", "DrawLine"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DrawPath: Request(65, DrawPathError) {
 		/// Whether the [coordinates] of each point in `points` are relative to
 		/// the `target` or to the previous point.
@@ -718,6 +787,8 @@ request_error! {
 /// A line from the given `start` point to the given `end` point.
 #[doc(alias("Segment"))]
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
 	/// The start of the line.
 	pub start: Coords,
@@ -793,6 +864,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolySegment", "DrawSegment"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DrawLines: Request(66, DrawLinesError) {
 		/// The [drawable] on which the given `lines` are drawn.
 		///
@@ -909,6 +981,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolyRectangle"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DrawRectangles: Request(67, DrawRectanglesError) {
 		/// The [drawable] on which the `rectangles`' outlines are drawn.
 		///
@@ -1024,6 +1097,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolyArc"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DrawArcs: Request(68, DrawArcsError) {
 		/// The [drawable] on which the [arcs] are drawn.
 		///
@@ -1081,6 +1155,8 @@ request_error! {
 ///
 /// [`FillPolygon` request]: FillPolygon
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShapeMode {
 	/// The shape may intersect itself.
 	Complex,
@@ -1158,6 +1234,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("FillPoly"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct FillPolygon: Request(69, FillPolygonError) {
 		/// The [drawable] on which the filled polygon is drawn.
 		///
@@ -1287,6 +1364,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolyFillRectangle"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct FillRectangles: Request(70, FillRectanglesError) {
 		/// The [drawable] on which the [rectangles] are filled.
 		///
@@ -1400,6 +1478,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("PolyFillArc"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct FillArcs: Request(71, FillArcsError) {
 		/// The [drawable] on which the [arcs] are filled.
 		///
@@ -1452,7 +1531,9 @@ request_error! {
 ///
 /// [`PlaceImage` request]: PlaceImage
 #[doc(alias("PutImageFormat"))]
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlaceImageFormat {
 	/// The image must be in XY format.
 	///
@@ -1533,6 +1614,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias("PutImage"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct PlaceImage: Request(72, PlaceImageError) {
 		/// The [image format] used.
 		///
@@ -1625,6 +1707,389 @@ derive_xrb! {
 	}
 }
 
+/// An error returned when [`ImageData`] could not be created.
+#[derive(Debug, Hash, PartialEq, Eq, Error)]
+pub enum ImageDataError {
+	/// Scanlines can only be padded to 8, 16, or 32 bits.
+	#[error("scanlines can only be padded to 8, 16, or 32 bits, found {0}")]
+	InvalidScanlinePadding(u8),
+
+	/// Pixels in Z format can only be 1, 4, 8, 16, 24, or 32 bits.
+	#[error("pixels can only be 1, 4, 8, 16, 24, or 32 bits, found {0}")]
+	InvalidBitsPerPixel(u8),
+
+	/// The left padding must be less than the scanline padding.
+	#[error(
+		"the left padding must be less than the scanline padding of {scanline_padding}, found \
+		 {left_padding}"
+	)]
+	LeftPaddingTooLarge {
+		/// The left padding that was given.
+		left_padding: u8,
+		/// The scanline padding that was given.
+		scanline_padding: u8,
+	},
+
+	/// The given image data did not match the image's dimensions.
+	#[error("expected {expected} bytes of image data, found {found}")]
+	WrongLength {
+		/// The number of bytes expected for the image's dimensions.
+		expected: usize,
+		/// The number of bytes that were given.
+		found: usize,
+	},
+}
+
+/// The data of an image sent in a [`PlaceImage` request] or returned in a
+/// [`CaptureImage` reply], with each scanline padded as required by the X
+/// server.
+///
+/// The X server requires every scanline of an image to be padded to a
+/// multiple of a number of bits given when the connection is set up.
+/// `ImageData` takes scanlines which are only rounded up to a whole byte and
+/// adds that padding, or takes data which is already padded, such as that of
+/// a [`CaptureImage` reply], and checks that its length matches the image's
+/// dimensions. [`to_packed()`] removes the padding again.
+///
+/// The padded data can then be used in a [`PlaceImage` request] with
+/// [`into_data()`], along with its [`left_padding()`].
+///
+/// [`PlaceImage` request]: PlaceImage
+/// [`CaptureImage` reply]: reply::CaptureImage
+///
+/// [`to_packed()`]: ImageData::to_packed
+/// [`into_data()`]: ImageData::into_data
+/// [`left_padding()`]: ImageData::left_padding
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ImageData {
+	layout: ImageLayout,
+
+	data: Vec<u8>,
+}
+
+/// The size of each scanline of an image, with and without padding.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+struct ImageLayout {
+	left_padding: u8,
+	/// The number of bytes in each scanline, rounded up to a whole byte.
+	unpadded_stride: usize,
+	/// The number of bytes in each scanline, padded to the scanline padding.
+	stride: usize,
+	scanlines: usize,
+}
+
+impl ImageLayout {
+	/// The layout of an image in [`PlaceImageFormat::Bitmap`] or
+	/// [`PlaceImageFormat::XyPixmap`] format.
+	fn xy(
+		dimensions: Dimensions, depth: u8, left_padding: u8, scanline_padding: u8,
+	) -> Result<Self, ImageDataError> {
+		check_scanline_padding(scanline_padding)?;
+
+		if left_padding >= scanline_padding {
+			return Err(ImageDataError::LeftPaddingTooLarge {
+				left_padding,
+				scanline_padding,
+			});
+		}
+
+		let scanline_bits = usize::from(left_padding) + usize::from(dimensions.width.0);
+		let scanlines = usize::from(depth) * usize::from(dimensions.height.0);
+
+		Ok(Self::new(
+			left_padding,
+			scanline_bits,
+			scanlines,
+			scanline_padding,
+		))
+	}
+
+	/// The layout of an image in [`PlaceImageFormat::Zpixmap`] format.
+	fn z(
+		dimensions: Dimensions, bits_per_pixel: u8, scanline_padding: u8,
+	) -> Result<Self, ImageDataError> {
+		if !matches!(bits_per_pixel, 1 | 4 | 8 | 16 | 24 | 32) {
+			return Err(ImageDataError::InvalidBitsPerPixel(bits_per_pixel));
+		}
+
+		check_scanline_padding(scanline_padding)?;
+
+		let scanline_bits = usize::from(dimensions.width.0) * usize::from(bits_per_pixel);
+		let scanlines = usize::from(dimensions.height.0);
+
+		Ok(Self::new(0, scanline_bits, scanlines, scanline_padding))
+	}
+
+	const fn new(
+		left_padding: u8, scanline_bits: usize, scanlines: usize, scanline_padding: u8,
+	) -> Self {
+		Self {
+			left_padding,
+			unpadded_stride: scanline_bits.div_ceil(8),
+			stride: scanline_bits.next_multiple_of(scanline_padding as usize) / 8,
+			scanlines,
+		}
+	}
+
+	/// Pads each scanline in `bytes` to the `stride`.
+	fn pad(self, bytes: &[u8]) -> Result<ImageData, ImageDataError> {
+		let expected = self.unpadded_stride * self.scanlines;
+		if bytes.len() != expected {
+			return Err(ImageDataError::WrongLength {
+				expected,
+				found: bytes.len(),
+			});
+		}
+
+		let mut data = Vec::with_capacity(self.stride * self.scanlines);
+
+		// A zero-width image has no data.
+		if self.unpadded_stride != 0 {
+			for scanline in bytes.chunks_exact(self.unpadded_stride) {
+				data.extend_from_slice(scanline);
+				data.resize(data.len() + (self.stride - self.unpadded_stride), 0);
+			}
+		}
+
+		Ok(ImageData { layout: self, data })
+	}
+
+	/// Checks that `data`, which is already padded, is the expected length.
+	fn padded(self, data: Vec<u8>) -> Result<ImageData, ImageDataError> {
+		let expected = self.stride * self.scanlines;
+		if data.len() != expected {
+			return Err(ImageDataError::WrongLength {
+				expected,
+				found: data.len(),
+			});
+		}
+
+		Ok(ImageData { layout: self, data })
+	}
+}
+
+impl ImageData {
+	/// Creates new `ImageData` for an image in [`PlaceImageFormat::Bitmap`] or
+	/// [`PlaceImageFormat::XyPixmap`] format.
+	///
+	/// `bytes` contains `depth` bit planes, from most significant to least
+	/// significant. Each bit plane contains `dimensions.height` scanlines of
+	/// `dimensions.width` bits, each rounded up to a whole byte.
+	///
+	/// `scanline_padding` is the `bitmap_format_scanline_padding` given in
+	/// [`connection::ConnectionSuccess`].
+	///
+	/// # Errors
+	/// An [`InvalidScanlinePadding`] error is returned if `scanline_padding` is
+	/// not `8`, `16`, or `32`.
+	///
+	/// A [`WrongLength`] error is returned if `bytes` is not the length of
+	/// `depth` bit planes of the given `dimensions`.
+	///
+	/// [`connection::ConnectionSuccess`]: crate::connection::ConnectionSuccess
+	///
+	/// [`InvalidScanlinePadding`]: ImageDataError::InvalidScanlinePadding
+	/// [`WrongLength`]: ImageDataError::WrongLength
+	pub fn new(
+		dimensions: Dimensions, depth: u8, scanline_padding: u8, bytes: &[u8],
+	) -> Result<Self, ImageDataError> {
+		Self::with_left_padding(dimensions, depth, 0, scanline_padding, bytes)
+	}
+
+	/// Creates new `ImageData` for an image in [`PlaceImageFormat::Bitmap`] or
+	/// [`PlaceImageFormat::XyPixmap`] format which begins `left_padding` bits
+	/// into each scanline.
+	///
+	/// This allows an image to be taken from the middle of a byte without
+	/// shifting its bits: each scanline in `bytes` contains `left_padding`
+	/// bits which are ignored by the X server, followed by `dimensions.width`
+	/// bits of the image, rounded up to a whole byte.
+	///
+	/// Otherwise, this is the same as [`ImageData::new`].
+	///
+	/// # Errors
+	/// An [`InvalidScanlinePadding`] error is returned if `scanline_padding` is
+	/// not `8`, `16`, or `32`.
+	///
+	/// A [`LeftPaddingTooLarge`] error is returned if `left_padding` is not
+	/// less than `scanline_padding`.
+	///
+	/// A [`WrongLength`] error is returned if `bytes` is not the length of
+	/// `depth` bit planes of the given `dimensions` and `left_padding`.
+	///
+	/// [`InvalidScanlinePadding`]: ImageDataError::InvalidScanlinePadding
+	/// [`LeftPaddingTooLarge`]: ImageDataError::LeftPaddingTooLarge
+	/// [`WrongLength`]: ImageDataError::WrongLength
+	pub fn with_left_padding(
+		dimensions: Dimensions, depth: u8, left_padding: u8, scanline_padding: u8, bytes: &[u8],
+	) -> Result<Self, ImageDataError> {
+		ImageLayout::xy(dimensions, depth, left_padding, scanline_padding)?.pad(bytes)
+	}
+
+	/// Creates new `ImageData` for an image in [`PlaceImageFormat::Zpixmap`]
+	/// format.
+	///
+	/// `bytes` contains `dimensions.height` scanlines of `dimensions.width`
+	/// pixels, each of which is `bits_per_pixel` bits. Each scanline is rounded
+	/// up to a whole byte.
+	///
+	/// `bits_per_pixel` and `scanline_padding` are given by the [`Format`]
+	/// matching the image's depth in [`connection::ConnectionSuccess`].
+	///
+	/// # Errors
+	/// An [`InvalidBitsPerPixel`] error is returned if `bits_per_pixel` is not
+	/// `1`, `4`, `8`, `16`, `24`, or `32`.
+	///
+	/// An [`InvalidScanlinePadding`] error is returned if `scanline_padding` is
+	/// not `8`, `16`, or `32`.
+	///
+	/// A [`WrongLength`] error is returned if `bytes` is not the length of an
+	/// image of the given `dimensions` and `bits_per_pixel`.
+	///
+	/// [`Format`]: crate::visual::Format
+	/// [`connection::ConnectionSuccess`]: crate::connection::ConnectionSuccess
+	///
+	/// [`InvalidBitsPerPixel`]: ImageDataError::InvalidBitsPerPixel
+	/// [`InvalidScanlinePadding`]: ImageDataError::InvalidScanlinePadding
+	/// [`WrongLength`]: ImageDataError::WrongLength
+	pub fn new_zpixmap(
+		dimensions: Dimensions, bits_per_pixel: u8, scanline_padding: u8, bytes: &[u8],
+	) -> Result<Self, ImageDataError> {
+		ImageLayout::z(dimensions, bits_per_pixel, scanline_padding)?.pad(bytes)
+	}
+
+	/// Wraps `data` for an image in [`CaptureImageFormat::XyPixmap`] format
+	/// whose scanlines are already padded, such as the `data` of a
+	/// [`CaptureImage` reply].
+	///
+	/// `depth` is the number of bit planes in `data`. For a
+	/// [`CaptureImage` reply], this is the number of bits set in the
+	/// `plane_mask` of the [`CaptureImage` request].
+	///
+	/// Otherwise, this is the same as [`ImageData::new`].
+	///
+	/// # Errors
+	/// An [`InvalidScanlinePadding`] error is returned if `scanline_padding` is
+	/// not `8`, `16`, or `32`.
+	///
+	/// A [`WrongLength`] error is returned if `data` is not the length of
+	/// `depth` padded bit planes of the given `dimensions`.
+	///
+	/// [`CaptureImage` reply]: reply::CaptureImage
+	/// [`CaptureImage` request]: CaptureImage
+	///
+	/// [`InvalidScanlinePadding`]: ImageDataError::InvalidScanlinePadding
+	/// [`WrongLength`]: ImageDataError::WrongLength
+	pub fn from_padded(
+		dimensions: Dimensions, depth: u8, scanline_padding: u8, data: Vec<u8>,
+	) -> Result<Self, ImageDataError> {
+		ImageLayout::xy(dimensions, depth, 0, scanline_padding)?.padded(data)
+	}
+
+	/// Wraps `data` for an image in [`CaptureImageFormat::Zpixmap`] format
+	/// whose scanlines are already padded, such as the `data` of a
+	/// [`CaptureImage` reply].
+	///
+	/// Otherwise, this is the same as [`ImageData::new_zpixmap`].
+	///
+	/// # Errors
+	/// An [`InvalidBitsPerPixel`] error is returned if `bits_per_pixel` is not
+	/// `1`, `4`, `8`, `16`, `24`, or `32`.
+	///
+	/// An [`InvalidScanlinePadding`] error is returned if `scanline_padding` is
+	/// not `8`, `16`, or `32`.
+	///
+	/// A [`WrongLength`] error is returned if `data` is not the length of a
+	/// padded image of the given `dimensions` and `bits_per_pixel`.
+	///
+	/// [`CaptureImage` reply]: reply::CaptureImage
+	///
+	/// [`InvalidBitsPerPixel`]: ImageDataError::InvalidBitsPerPixel
+	/// [`InvalidScanlinePadding`]: ImageDataError::InvalidScanlinePadding
+	/// [`WrongLength`]: ImageDataError::WrongLength
+	pub fn from_padded_zpixmap(
+		dimensions: Dimensions, bits_per_pixel: u8, scanline_padding: u8, data: Vec<u8>,
+	) -> Result<Self, ImageDataError> {
+		ImageLayout::z(dimensions, bits_per_pixel, scanline_padding)?.padded(data)
+	}
+
+	/// The number of bits at the start of each scanline which are to be ignored
+	/// by the X server.
+	///
+	/// This is the `left_padding` of the [`PlaceImage` request].
+	///
+	/// [`PlaceImage` request]: PlaceImage
+	#[must_use]
+	pub const fn left_padding(&self) -> u8 {
+		self.layout.left_padding
+	}
+
+	/// The number of bytes in each padded scanline.
+	#[must_use]
+	pub const fn stride(&self) -> usize {
+		self.layout.stride
+	}
+
+	/// The number of bytes of padded data expected for this image's
+	/// dimensions, which is always the length of its [`data()`].
+	///
+	/// [`data()`]: ImageData::data
+	#[must_use]
+	pub const fn expected_len(&self) -> usize {
+		self.layout.stride * self.layout.scanlines
+	}
+
+	/// The padded image data.
+	#[must_use]
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Returns the image data with the padding at the end of each scanline
+	/// removed, leaving each scanline rounded up to a whole byte.
+	///
+	/// This is the form of the `bytes` given to [`ImageData::new`],
+	/// [`ImageData::with_left_padding`], and [`ImageData::new_zpixmap`].
+	#[must_use]
+	pub fn to_packed(&self) -> Vec<u8> {
+		let ImageLayout {
+			unpadded_stride,
+			stride,
+			..
+		} = self.layout;
+
+		// A zero-width image has no data.
+		if unpadded_stride == 0 {
+			return vec![];
+		}
+
+		self.data
+			.chunks_exact(stride)
+			.flat_map(|scanline| &scanline[..unpadded_stride])
+			.copied()
+			.collect()
+	}
+
+	/// Unwraps this `ImageData`, returning the padded image data.
+	///
+	/// This is the `data` of the [`PlaceImage` request].
+	///
+	/// [`PlaceImage` request]: PlaceImage
+	#[must_use]
+	#[allow(clippy::missing_const_for_fn, reason = "false positive")]
+	pub fn into_data(self) -> Vec<u8> {
+		self.data
+	}
+}
+
+/// Returns an error if `scanline_padding` is not `8`, `16`, or `32`.
+const fn check_scanline_padding(scanline_padding: u8) -> Result<(), ImageDataError> {
+	match scanline_padding {
+		8 | 16 | 32 => Ok(()),
+		other => Err(ImageDataError::InvalidScanlinePadding(other)),
+	}
+}
+
 request_error! {
 	#[doc(alias("GetImageError"))]
 	pub enum CaptureImageError for CaptureImage {
@@ -1641,10 +2106,14 @@ request_error! {
 /// [`CaptureImage` request]: CaptureImage
 /// [`CaptureImage` reply]: reply::CaptureImage
 #[doc(alias("GetImageFormat"))]
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CaptureImageFormat {
 	/// The image is returned in XY format.
-	XyPixmap,
+	// `0` is the bitmap format of `PlaceImageFormat`, which cannot be
+	// captured.
+	XyPixmap = 1,
 
 	/// The image is returned in Z format.
 	Zpixmap,
@@ -1698,6 +2167,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias("GetImage"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CaptureImage: Request(73, CaptureImageError) -> reply::CaptureImage {
 		/// The [image format] of the image that is returned in the
 		/// [`CaptureImage` reply].
@@ -1757,6 +2227,7 @@ request_error! {
 ///
 /// [`DrawText8` request]: DrawText8
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextItem8 {
 	/// Specifies text that is to be drawn with the `graphics_context`'s current
 	/// [font].
@@ -1787,8 +2258,12 @@ impl Readable for TextItem8 {
 	where
 		Self: Sized,
 	{
-		Ok(match buf.get_u8() {
-			font_shift if font_shift == 255 => Self::Font(Font::new(buf.get_u32())),
+		Ok(match u8::read_from(buf)? {
+			font_shift if font_shift == 255 => {
+				// The font is big endian, whatever the connection's byte order
+				// is, so it must not be read with `u32::read_from`.
+				Self::Font(Font::new(u32::from_be_bytes(<[u8; 4]>::read_from(buf)?)))
+			},
 			string_len => Self::Text(Box::new(Text8::read_with(buf, &string_len)?)),
 		})
 	}
@@ -1803,7 +2278,9 @@ impl Writable for TextItem8 {
 				// Font-shift indicator
 				buf.put_u8(255);
 
-				font.write_to(buf)?;
+				// The font is big endian, whatever the connection's byte order
+				// is, so it must not be written with `Font::write_to`.
+				buf.put_slice(&font.unwrap().to_be_bytes());
 			},
 		}
 
@@ -1821,6 +2298,7 @@ impl Writable for TextItem8 {
 ///
 /// [`DrawText8` request]: DrawText8
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Text8 {
 	horizontal_offset: Px<i8>,
 	string: String8,
@@ -1880,6 +2358,28 @@ impl Text8 {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Text8 {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		#[derive(serde::Deserialize)]
+		struct Fields {
+			horizontal_offset: Px<i8>,
+			string: String8,
+		}
+
+		// The length of the `string` is checked with `new`.
+		let Fields {
+			horizontal_offset,
+			string,
+		} = Fields::deserialize(deserializer)?;
+
+		Self::new(horizontal_offset, string).map_err(serde::de::Error::custom)
+	}
+}
+
 impl X11Size for Text8 {
 	fn x11_size(&self) -> usize {
 		u8::X11_SIZE + i8::X11_SIZE + self.string.x11_size()
@@ -1971,6 +2471,7 @@ impl Writable for Text8 {
 /// [`Font` error]: error::Font
 #[doc(alias("PolyText8"))]
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DrawText8 {
 	/// The [drawable] on which the text is drawn.
 	///
@@ -2026,12 +2527,16 @@ pub struct DrawText8 {
 	pub text_items: Vec<TextItem8>,
 }
 
+impl crate::message::__private::Sealed for DrawText8 {}
+crate::message::register_message!(Request DrawText8);
+
 impl Request for DrawText8 {
 	type OtherErrors = DrawText8Error;
 	type Reply = ();
 
 	const MAJOR_OPCODE: u8 = 74;
 	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = false;
 }
 
 impl X11Size for DrawText8 {
@@ -2063,11 +2568,11 @@ impl Readable for DrawText8 {
 		// major opcode is already read
 
 		// Metabyte position is unused.
-		buf.advance(1);
+		xrbk::skip(buf, 1)?;
 
 		// Read the length and bound buf to not read more than it.
-		let length = (usize::from(buf.get_u16()) * 4) - 2;
-		let buf = &mut buf.take(length);
+		let length = (usize::from(u16::read_from(buf)?) * 4).saturating_sub(4);
+		let buf = &mut xrbk::take(buf, length);
 
 		let target = Drawable::read_from(buf)?;
 		let graphics_context = GraphicsContext::read_from(buf)?;
@@ -2084,7 +2589,7 @@ impl Readable for DrawText8 {
 		};
 
 		// Advance the padding bytes at the end.
-		buf.advance(pad(&text_items));
+		xrbk::skip(buf, pad(&text_items))?;
 
 		Ok(Self {
 			target,
@@ -2097,12 +2602,20 @@ impl Readable for DrawText8 {
 
 impl Writable for DrawText8 {
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		let buf = &mut buf.limit(self.x11_size());
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl WritableRequest for DrawText8 {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		const HEADER: usize = 4;
 
 		buf.put_u8(Self::MAJOR_OPCODE);
 		// Unused metabyte position.
 		buf.put_u8(0);
-		buf.put_u16(self.length());
+		write_request_length(buf, self.x11_size(), mode)?;
+
+		let buf = &mut xrbk::limit(buf, self.x11_size() - HEADER);
 
 		self.target.write_to(buf)?;
 		self.graphics_context.write_to(buf)?;
@@ -2130,6 +2643,7 @@ request_error! {
 ///
 /// [`DrawText16` request]: DrawText16
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextItem16 {
 	/// Specifies text that is to be drawn with the `graphics_context`'s current
 	/// [font].
@@ -2160,8 +2674,12 @@ impl Readable for TextItem16 {
 	where
 		Self: Sized,
 	{
-		Ok(match buf.get_u8() {
-			font_shift if font_shift == 255 => Self::Font(Font::new(buf.get_u32())),
+		Ok(match u8::read_from(buf)? {
+			font_shift if font_shift == 255 => {
+				// The font is big endian, whatever the connection's byte order
+				// is, so it must not be read with `u32::read_from`.
+				Self::Font(Font::new(u32::from_be_bytes(<[u8; 4]>::read_from(buf)?)))
+			},
 			string_len => Self::Text(Box::new(Text16::read_with(buf, &string_len)?)),
 		})
 	}
@@ -2176,7 +2694,9 @@ impl Writable for TextItem16 {
 				// Font-shift indicator
 				buf.put_u8(255);
 
-				font.write_to(buf)?;
+				// The font is big endian, whatever the connection's byte order
+				// is, so it must not be written with `Font::write_to`.
+				buf.put_slice(&font.unwrap().to_be_bytes());
 			},
 		}
 
@@ -2194,6 +2714,7 @@ impl Writable for TextItem16 {
 ///
 /// [`DrawText16` request]: DrawText16
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Text16 {
 	horizontal_offset: Px<i8>,
 	string: String16,
@@ -2243,6 +2764,28 @@ impl Text16 {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Text16 {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		#[derive(serde::Deserialize)]
+		struct Fields {
+			horizontal_offset: Px<i8>,
+			string: String16,
+		}
+
+		// The length of the `string` is checked with `new`.
+		let Fields {
+			horizontal_offset,
+			string,
+		} = Fields::deserialize(deserializer)?;
+
+		Self::new(horizontal_offset, string).map_err(serde::de::Error::custom)
+	}
+}
+
 impl X11Size for Text16 {
 	fn x11_size(&self) -> usize {
 		u8::X11_SIZE + i8::X11_SIZE + self.string.x11_size()
@@ -2339,6 +2882,7 @@ impl Writable for Text16 {
 /// [`Font` error]: error::Font
 #[doc(alias("PolyText16"))]
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DrawText16 {
 	/// The [drawable] on which the text is drawn.
 	///
@@ -2394,12 +2938,16 @@ pub struct DrawText16 {
 	pub text_items: Vec<TextItem16>,
 }
 
+impl crate::message::__private::Sealed for DrawText16 {}
+crate::message::register_message!(Request DrawText16);
+
 impl Request for DrawText16 {
 	type OtherErrors = DrawText8Error;
 	type Reply = ();
 
 	const MAJOR_OPCODE: u8 = 75;
 	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = false;
 }
 
 impl X11Size for DrawText16 {
@@ -2431,11 +2979,11 @@ impl Readable for DrawText16 {
 		// major opcode is already read
 
 		// Metabyte position is unused.
-		buf.advance(1);
+		xrbk::skip(buf, 1)?;
 
 		// Read the length and bound buf to not read more than it.
-		let length = (usize::from(buf.get_u16()) * 4) - 2;
-		let buf = &mut buf.take(length);
+		let length = (usize::from(u16::read_from(buf)?) * 4).saturating_sub(4);
+		let buf = &mut xrbk::take(buf, length);
 
 		let target = Drawable::read_from(buf)?;
 		let graphics_context = GraphicsContext::read_from(buf)?;
@@ -2452,7 +3000,7 @@ impl Readable for DrawText16 {
 		};
 
 		// Advance the padding bytes at the end.
-		buf.advance(pad(&text_items));
+		xrbk::skip(buf, pad(&text_items))?;
 
 		Ok(Self {
 			target,
@@ -2465,12 +3013,20 @@ impl Readable for DrawText16 {
 
 impl Writable for DrawText16 {
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		let buf = &mut buf.limit(self.x11_size());
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl WritableRequest for DrawText16 {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		const HEADER: usize = 4;
 
 		buf.put_u8(Self::MAJOR_OPCODE);
 		// Unused metabyte position.
 		buf.put_u8(0);
-		buf.put_u16(self.length());
+		write_request_length(buf, self.x11_size(), mode)?;
+
+		let buf = &mut xrbk::limit(buf, self.x11_size() - HEADER);
 
 		self.target.write_to(buf)?;
 		self.graphics_context.write_to(buf)?;
@@ -2579,6 +3135,8 @@ derive_xrb! {
 	/// [`Drawable` error]: error::Drawable
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ImageText8: Request(76, ImageText8Error) {
 		// The length of `string`.
 		#[metabyte]
@@ -2716,6 +3274,8 @@ derive_xrb! {
 	/// [`Drawable` error]: error::Drawable
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ImageText16: Request(77, ImageText16Error) {
 		// The length of `string`.
 		#[metabyte]
@@ -2761,3 +3321,416 @@ derive_xrb! {
 		[_; string => pad(string)],
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		arbitrary::{assert_round_trip, round_trip_tests},
+		message::RequestTooLong,
+	};
+	use xrbk::ByteOrder;
+
+	round_trip_tests! {
+		ClearArea => test_clear_area_round_trip,
+		CopyArea => test_copy_area_round_trip,
+		CopyBitPlane => test_copy_bit_plane_round_trip,
+		ImageText8 => test_image_text8_round_trip,
+		ImageText16 => test_image_text16_round_trip,
+	}
+
+	#[test]
+	fn test_bit_plane_single_bit() {
+		assert_eq!(BitPlane::new(1).map(BitPlane::unwrap), Ok(1));
+		assert_eq!(BitPlane::new(0x8000_0000).map(BitPlane::index), Ok(31));
+		assert_eq!(BitPlane::nth(4), BitPlane::new(0x10).ok());
+		assert_eq!(BitPlane::nth(32), None);
+	}
+
+	#[test]
+	fn test_bit_plane_rejects_zero_and_multiple_bits() {
+		assert_eq!(BitPlane::new(0), Err(InvalidBitPlane(0)));
+		assert_eq!(BitPlane::new(0b11), Err(InvalidBitPlane(0b11)));
+		assert_eq!(BitPlane::new(u32::MAX), Err(InvalidBitPlane(u32::MAX)));
+	}
+
+	#[test]
+	fn test_copy_bit_plane_rejects_multiple_bits() {
+		let request = CopyBitPlane {
+			source: Drawable::new(0x0040_0001),
+			destination: Drawable::new(0x0040_0002),
+			graphics_context: GraphicsContext::new(0x0040_0003),
+			source_coords: Coords::new(Px(0), Px(0)),
+			destination_coords: Coords::new(Px(8), Px(8)),
+			dimensions: Dimensions::new(Px(16), Px(16)),
+			bit_plane: BitPlane::nth(0).unwrap(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		// The major opcode is not read by `read_from`.
+		assert_eq!(CopyBitPlane::read_from(&mut &bytes[1..]).unwrap(), request);
+
+		bytes[28..32].copy_from_slice(&0b11_u32.to_be_bytes());
+		let error = CopyBitPlane::read_from(&mut &bytes[1..]).unwrap_err();
+		assert!(matches!(error.root_cause(), ReadError::FailedConversion(_)));
+	}
+
+	#[test]
+	fn test_image_data_one_pixel_wide() {
+		let dimensions = Dimensions {
+			width: Px(1),
+			height: Px(3),
+		};
+		let image = ImageData::new(dimensions, 1, 32, &[0x80, 0x00, 0x80]).unwrap();
+
+		assert_eq!(image.stride(), 4);
+		assert_eq!(image.data(), [0x80, 0, 0, 0, 0x00, 0, 0, 0, 0x80, 0, 0, 0],);
+	}
+
+	#[test]
+	fn test_image_data_already_aligned() {
+		let dimensions = Dimensions {
+			width: Px(8),
+			height: Px(2),
+		};
+		let bytes = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+		let image = ImageData::new_zpixmap(dimensions, 8, 32, &bytes).unwrap();
+
+		assert_eq!(image.stride(), 8);
+		assert_eq!(image.into_data(), bytes);
+	}
+
+	#[test]
+	fn test_image_data_left_padding() {
+		let dimensions = Dimensions {
+			width: Px(4),
+			height: Px(1),
+		};
+
+		let image = ImageData::with_left_padding(dimensions, 2, 6, 8, &[0xff, 0xc0, 0x0f, 0xc0]);
+		assert_eq!(image.unwrap().data(), [0xff, 0xc0, 0x0f, 0xc0]);
+
+		assert_eq!(
+			ImageData::with_left_padding(dimensions, 1, 8, 8, &[0xff]),
+			Err(ImageDataError::LeftPaddingTooLarge {
+				left_padding: 8,
+				scanline_padding: 8,
+			}),
+		);
+	}
+
+	#[test]
+	fn test_image_data_round_trip_depths() {
+		// An odd width, so that every scanline needs padding.
+		let dimensions = Dimensions {
+			width: Px(5),
+			height: Px(3),
+		};
+
+		for (format, depth, bits_per_pixel) in [
+			(PlaceImageFormat::Bitmap, 1, 1),
+			(PlaceImageFormat::Zpixmap, 8, 8),
+			(PlaceImageFormat::Zpixmap, 16, 16),
+			(PlaceImageFormat::Zpixmap, 24, 32),
+			(PlaceImageFormat::Zpixmap, 32, 32),
+		] {
+			let zpixmap = format == PlaceImageFormat::Zpixmap;
+			let packed_len = (5 * usize::from(bits_per_pixel)).div_ceil(8) * 3;
+			let bytes: Vec<u8> = (1..=packed_len).map(|i| i as u8).collect();
+
+			let image = if zpixmap {
+				ImageData::new_zpixmap(dimensions, bits_per_pixel, 32, &bytes)
+			} else {
+				ImageData::new(dimensions, depth, 32, &bytes)
+			}
+			.unwrap();
+
+			assert_eq!(image.stride() % 4, 0);
+			assert_eq!(image.data().len(), image.expected_len());
+			assert_eq!(image.to_packed(), bytes);
+
+			let request = PlaceImage {
+				format,
+				target: Drawable::new(0x0040_0001),
+				graphics_context: GraphicsContext::new(0x0040_0002),
+				dimensions,
+				coordinates: Coords::new(Px(0), Px(0)),
+				left_padding: image.left_padding(),
+				depth,
+				data: image.into_data(),
+			};
+
+			let mut bytes = vec![];
+			request.write_to(&mut bytes).unwrap();
+			let request = PlaceImage::read_from(&mut &bytes[1..]).unwrap();
+
+			// The data is captured again just as it was placed.
+			let reply = reply::CaptureImage {
+				sequence: 1,
+				depth,
+				visual: None,
+				data: request.data,
+			};
+
+			let mut bytes = vec![];
+			reply.write_to(&mut bytes).unwrap();
+			let reply = reply::CaptureImage::read_from(&mut &bytes[1..]).unwrap();
+
+			let captured = if zpixmap {
+				ImageData::from_padded_zpixmap(dimensions, bits_per_pixel, 32, reply.data)
+			} else {
+				ImageData::from_padded(dimensions, depth, 32, reply.data)
+			}
+			.unwrap();
+
+			assert_eq!(
+				captured.to_packed(),
+				(1..=packed_len).map(|i| i as u8).collect::<Vec<_>>(),
+				"depth {depth}",
+			);
+		}
+	}
+
+	#[test]
+	fn test_image_data_from_padded_wrong_length() {
+		let dimensions = Dimensions {
+			width: Px(3),
+			height: Px(2),
+		};
+
+		// Each 3-pixel scanline of 8 bits per pixel is padded to 4 bytes.
+		assert_eq!(
+			ImageData::from_padded_zpixmap(dimensions, 8, 32, vec![0; 6]),
+			Err(ImageDataError::WrongLength {
+				expected: 8,
+				found: 6,
+			}),
+		);
+		assert_eq!(
+			ImageData::from_padded(dimensions, 1, 32, vec![0; 8])
+				.unwrap()
+				.expected_len(),
+			8,
+		);
+	}
+
+	#[test]
+	fn test_place_image_extended_length() {
+		let request = PlaceImage {
+			format: PlaceImageFormat::Zpixmap,
+			target: Drawable::new(0x0040_0001),
+			graphics_context: GraphicsContext::new(0x0040_0002),
+			dimensions: Dimensions {
+				width: Px(256),
+				height: Px(256),
+			},
+			coordinates: Coords::new(Px(0), Px(0)),
+			left_padding: 0,
+			depth: 32,
+			data: vec![0xab; 256 * 256 * 4],
+		};
+		// 24-byte header and fields + 262144 bytes of data.
+		let units: u32 = (24 + 262_144) / 4;
+		assert_eq!(request.x11_size(), units as usize * 4);
+
+		// The length doesn't fit in a `u16`, so it is not truncated.
+		assert_eq!(
+			request.length(),
+			Err(RequestTooLong {
+				size: 262_168,
+				mode: LengthMode::Standard,
+			}),
+		);
+
+		let mut bytes = vec![];
+		let error = request.write_to(&mut bytes).unwrap_err();
+
+		assert_eq!(
+			error.to_string(),
+			"a request of 262168 bytes is too long to be written with Standard lengths",
+		);
+
+		let mut bytes = vec![];
+		request
+			.write_to_with(&mut bytes, LengthMode::Extended)
+			.unwrap();
+
+		assert_eq!(bytes.len(), request.x11_size() + 4);
+		assert_eq!(bytes[2..4], [0, 0]);
+		assert_eq!(bytes[4..8], (units + 1).to_be_bytes());
+
+		let buf = &mut &bytes[1..];
+
+		assert_eq!(PlaceImage::read_from(buf).unwrap(), request);
+		assert!(buf.is_empty(), "not every byte was read");
+	}
+
+	#[test]
+	fn test_extended_length_mode_only_when_needed() {
+		let request = DrawText8 {
+			target: Drawable::new(0x0040_0001),
+			graphics_context: GraphicsContext::new(0x0040_0002),
+			coordinates: Coords::new(Px(0), Px(0)),
+			text_items: vec![],
+		};
+
+		let mut standard = vec![];
+		request.write_to(&mut standard).unwrap();
+		let mut extended = vec![];
+		request
+			.write_to_with(&mut extended, LengthMode::Extended)
+			.unwrap();
+
+		assert_eq!(standard, extended);
+		assert_eq!(standard[2..4], [0, 4]);
+		assert_eq!(request.length(), Ok(4));
+	}
+
+	fn points(count: i16) -> Vec<Coords> {
+		(0..count).map(|i| Coords::new(Px(i), Px(-i))).collect()
+	}
+
+	#[test]
+	fn test_poly_requests_round_trip() {
+		let target = Drawable::new(0x0040_0001);
+		let graphics_context = GraphicsContext::new(0x0040_0002);
+
+		for count in [0, 1, 100] {
+			assert_round_trip(&DrawPath {
+				coordinate_mode: CoordinateMode::Previous,
+				target,
+				graphics_context,
+				points: points(count),
+			})
+			.unwrap();
+
+			assert_round_trip(&DrawLines {
+				target,
+				graphics_context,
+				lines: points(count)
+					.into_iter()
+					.map(|start| Line {
+						start,
+						end: Coords::new(Px(7), Px(8)),
+					})
+					.collect(),
+			})
+			.unwrap();
+
+			assert_round_trip(&DrawRectangles {
+				target,
+				graphics_context,
+				rectangles: (0..count)
+					.map(|i| Rectangle {
+						x: Px(i),
+						y: Px(i),
+						width: Px(10),
+						height: Px(20),
+					})
+					.collect(),
+			})
+			.unwrap();
+
+			assert_round_trip(&FillPolygon {
+				target,
+				graphics_context,
+				shape: ShapeMode::Convex,
+				coordinate_mode: CoordinateMode::Drawable,
+				points: points(count),
+			})
+			.unwrap();
+		}
+	}
+
+	#[test]
+	fn test_fill_polygon_layout() {
+		let request = FillPolygon {
+			target: Drawable::new(0x0040_0001),
+			graphics_context: GraphicsContext::new(0x0040_0002),
+			shape: ShapeMode::Nonconvex,
+			coordinate_mode: CoordinateMode::Previous,
+			points: points(3),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		// A 16-byte header followed by 3 points of 4 bytes each.
+		assert_eq!(bytes.len(), 28);
+		assert_eq!(bytes[..4], [69, 0, 0, 7]);
+		assert_eq!(bytes[12..16], [1, 1, 0, 0]);
+		assert_eq!(bytes[16..20], [0, 0, 0, 0]);
+		assert_eq!(bytes[24..28], [0, 2, 0xff, 0xfe]);
+	}
+
+	#[test]
+	fn test_draw_path_too_many_points() {
+		let mut request = DrawPath {
+			coordinate_mode: CoordinateMode::Drawable,
+			target: Drawable::new(0x0040_0001),
+			graphics_context: GraphicsContext::new(0x0040_0002),
+			// The largest number of points that fit in a length of `u16::MAX`
+			// units, after the 12-byte header.
+			points: vec![Coords::new(Px(1), Px(2)); (usize::from(u16::MAX) * 4 - 12) / 4],
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes[2..4], u16::MAX.to_be_bytes());
+
+		request.points.push(Coords::new(Px(3), Px(4)));
+
+		let error = request.write_to(&mut vec![]).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"a request of 262144 bytes is too long to be written with Standard lengths",
+		);
+	}
+
+	#[test]
+	fn test_capture_image_format_values() {
+		for (format, value) in [
+			(CaptureImageFormat::XyPixmap, 1),
+			(CaptureImageFormat::Zpixmap, 2),
+		] {
+			let mut bytes = vec![];
+			format.write_to(&mut bytes).unwrap();
+			assert_eq!(bytes, [value]);
+
+			assert_eq!(
+				CaptureImageFormat::read_from(&mut &bytes[..]).unwrap(),
+				format
+			);
+		}
+
+		assert!(CaptureImageFormat::read_from(&mut &[0][..]).is_err());
+	}
+
+	#[test]
+	fn test_text_item_font_is_big_endian() {
+		let font = Font::new(0x0102_0304);
+
+		for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+			let mut bytes = vec![];
+			TextItem8::Font(font)
+				.write_to_ordered(&mut bytes, order)
+				.unwrap();
+			TextItem16::Font(font)
+				.write_to_ordered(&mut bytes, order)
+				.unwrap();
+			assert_eq!(bytes, [255, 1, 2, 3, 4, 255, 1, 2, 3, 4]);
+
+			let buf = &mut &bytes[..];
+			assert_eq!(
+				TextItem8::read_from_ordered(buf, order).unwrap(),
+				TextItem8::Font(font)
+			);
+			assert_eq!(
+				TextItem16::read_from_ordered(buf, order).unwrap(),
+				TextItem16::Font(font)
+			);
+		}
+	}
+}
@@ -13,7 +13,7 @@
 extern crate self as xrb;
 
 use xrbk::{pad, ConstantX11Size};
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
 	message::Request,
@@ -30,37 +30,6 @@ use crate::{
 	Rectangle,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum CreatePixmapError for CreatePixmap {
 		Drawable,
@@ -94,6 +63,7 @@ derive_xrb! {
 	/// [`ResourceIdChoice` error]: error::ResourceIdChoice
 	/// [`Value` error]: error::Value
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreatePixmap: Request(53, CreatePixmapError) {
 		/// The depth of the [pixmap].
 		///
@@ -162,6 +132,7 @@ derive_xrb! {
 	///
 	/// [`Pixmap` error]: error::Pixmap
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct FreePixmap: Request(54, error::Pixmap) {
 		/// The [pixmap] which is to have its association with its ID removed.
 		///
@@ -210,6 +181,7 @@ derive_xrb! {
 	/// [`Drawable` error]: error::Drawable
 	#[doc(alias("CreateGc", "CreateGC", "CreateGcontext", "CreateGContext"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreateGraphicsContext: Request(55, CreateGraphicsContextError) {
 		/// The [`GraphicsContext` ID] which is to be assigned to the
 		/// [`GraphicsContext`].
@@ -302,6 +274,7 @@ derive_xrb! {
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[doc(alias("ChangeGc", "ChangeGC", "ChangeGraphicsContext"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeGraphicsOptions: Request(56, ChangeGraphicsOptionsError) {
 		/// The [`GraphicsContext`] for which this [request] changes its
 		/// [graphics options].
@@ -356,6 +329,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias("CopyGc", "CopyGC", "CopyGraphicsContext", "CopyGcontext"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CopyGraphicsOptions: Request(57, CopyGraphicsOptionsError) {
 		/// The [`GraphicsContext`] from which the [options] specified in
 		/// `options_mask` are copied.
@@ -431,6 +405,7 @@ derive_xrb! {
 	///
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetDashes: Request(58, SetDashesError) {
 		/// The [`GraphicsContext`] on which this [request] configures its
 		/// dashes.
@@ -474,7 +449,9 @@ request_error! {
 /// [rectangles]: Rectangle
 ///
 /// [`SetClipRectangles` request]: SetClipRectangles
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClipRectanglesOrdering {
 	/// No particular order is specified.
 	///
@@ -550,6 +527,7 @@ derive_xrb! {
 	///
 	/// [`GraphicsContext` error]: error::GraphicsContext
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetClipRectangles: Request(59, SetClipRectanglesError) {
 		/// Specifies the ordering of [rectangles] within `clip_rectangles`.
 		///
@@ -625,6 +603,7 @@ derive_xrb! {
 	#[doc(alias("FreeGc", "FreeGcontext", "FreeGraphicsContext"))]
 	#[doc(alias("DestroyGc", "DestroyGcontext"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyGraphicsContext: Request(60, error::GraphicsContext) {
 		/// The [`GraphicsContext`] which is to be deleted.
 		///
@@ -698,6 +677,7 @@ derive_xrb! {
 	/// [`Pixmap` error]: error::Pixmap
 	#[doc(alias("CreateCursor"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreateCursorAppearance: Request(93, CreateCursorAppearanceError) {
 		/// The [`CursorAppearance` ID] which is to be assigned to the
 		/// [`CursorAppearance`].
@@ -857,11 +837,8 @@ derive_xrb! {
 	/// glyph defined in the [font] specified by `source_font`.
 	///
 	/// A [`Value` error] is generated if `mask_font` is [`Some`] but
-	/// `mask_char` is [`None`].
-	///
-	/// A [`Value` error] is generated if both `mask_font` and `mask_char` are
-	/// [`Some`] but `mask_char` does not refer to a glyph defined in the font
-	/// specified by `mask_font`.
+	/// `mask_char` does not refer to a glyph defined in the font specified by
+	/// `mask_font`.
 	///
 	/// [font]: Font
 	/// [request]: Request
@@ -873,6 +850,7 @@ derive_xrb! {
 	/// [`Value` error]: error::Value
 	#[doc(alias("CreateGlyphCursor"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreateGlyphCursorAppearance: Request(94, CreateGlyphCursorAppearanceError) {
 		/// The [`CursorAppearance` ID] which is to be assigned to the
 		/// [`CursorAppearance`].
@@ -942,10 +920,13 @@ derive_xrb! {
 		///
 		/// [`Value` error]: error::Value
 		pub source_char: u16,
-		/// An optional character which masks the appearance of the cursor.
+		/// The character which masks the appearance of the cursor.
+		///
+		/// If [`mask_font`] is [`Some`], this character masks the character
+		/// specified by [`source_char`]. Otherwise, this is ignored.
 		///
-		/// If this is [`Some`], it masks the character specified by
-		/// [`source_char`].
+		/// Zero is a valid character, so there is no value which means that
+		/// there is no mask character: that is specified by [`mask_font`].
 		///
 		/// For [fonts][font] that use two-byte matrix indexing, this value
 		/// should be specified like so:
@@ -956,22 +937,20 @@ derive_xrb! {
 		/// #
 		/// let char = Char16::new(byte1, byte2);
 		///
-		/// let mask_char = Some(u16::from(char));
+		/// let mask_char = u16::from(char);
 		/// ```
 		///
 		/// # Errors
-		/// A [`Value` error] is generated if [`mask_font`] is [`Some`] but this
-		/// is [`None`].
-		///
-		/// A [`Value` error] is generated if this does not refer to a glyph
-		/// defined in the [font] specified by [`mask_font`].
+		/// A [`Value` error] is generated if [`mask_font`] is [`Some`] and this
+		/// does not refer to a glyph defined in the [font] specified by
+		/// [`mask_font`].
 		///
 		/// [`mask_font`]: CreateGlyphCursorAppearance::mask_font
 		///
 		/// [font]: Font
 		///
 		/// [`Value` error]: error::Value
-		pub mask_char: Option<u16>,
+		pub mask_char: u16,
 
 		/// The foreground color used for the cursor's visual appearance.
 		///
@@ -1004,6 +983,7 @@ derive_xrb! {
 	///
 	/// [`CursorAppearance` error]: error::CursorAppearance
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyCursorAppearance: Request(95, error::CursorAppearance) {
 		/// The [`CursorAppearance`] that is to be deleted.
 		///
@@ -1030,6 +1010,7 @@ derive_xrb! {
 	///
 	/// [`CursorAppearance` error]: error::CursorAppearance
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct RecolorCursorAppearance: Request(96, error::CursorAppearance) {
 		/// The [`CursorAppearance`] which is to be recolored.
 		///
@@ -1081,7 +1062,9 @@ request_error! {
 ///
 /// [`QueryIdealDimension` request]: QueryIdealDimensions
 #[doc(alias("QueryBestSizeClass", "QueryIdealDimensionsClass"))]
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DimensionClass {
 	/// The largest [`CursorAppearance`] [dimensions] that can be fully
 	/// displayed are returned.
@@ -1138,6 +1121,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias("QueryBestSize"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryIdealDimensions: Request(
 		97,
 		QueryIdealDimensionsError,
@@ -1183,3 +1167,183 @@ derive_xrb! {
 		pub dimensions: Dimensions,
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		set::{ArcMode, CapStyle, FillStyle, Function, JoinStyle, LineStyle, LineWidth},
+		visual::ColorId,
+		Window,
+	};
+	use xrbk::{ByteOrder, Readable, Writable, X11Size};
+
+	/// Writes the `request`, checks its length against its [`X11Size`], and
+	/// returns the bytes after checking that they read back to the same
+	/// `request`.
+	fn create_graphics_context_round_trip(request: &CreateGraphicsContext) -> Vec<u8> {
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(
+			&CreateGraphicsContext::read_from(&mut &bytes[1..]).unwrap(),
+			request,
+		);
+
+		bytes
+	}
+
+	#[test]
+	fn test_create_graphics_context_foreground_only() {
+		let mut options = GraphicsOptions::builder();
+		options.foreground_color(ColorId::new(0x00ff_0000));
+
+		let request = CreateGraphicsContext {
+			graphics_context_id: GraphicsContext::new(0x0060_0002),
+			drawable: Window::new(0x0000_0100).into(),
+			graphics_options: options.build(),
+		};
+
+		#[rustfmt::skip]
+		assert_eq!(create_graphics_context_round_trip(&request), [
+			55, 0, 0, 5, // opcode, unused, length
+			0, 0x60, 0, 2, // graphics_context_id
+			0, 0, 1, 0, // drawable
+			0, 0, 0, 0x04, // options mask
+			0, 0xff, 0, 0, // foreground_color
+		]);
+	}
+
+	#[test]
+	fn test_create_graphics_context_many_options() {
+		let mut options = GraphicsOptions::builder();
+		// Configured out of order, but written in the order of the mask bits.
+		options
+			.arc_mode(ArcMode::Chord)
+			.font(Font::new(0x0060_0000))
+			.function(Function::Xor)
+			.plane_mask(0x0000_00ff)
+			.foreground_color(ColorId::new(0x00ff_0000))
+			.background_color(ColorId::ONE)
+			.line_width(LineWidth::new(3))
+			.line_style(LineStyle::OnOffDash)
+			.cap_style(CapStyle::Round)
+			.join_style(JoinStyle::Bevel)
+			.fill_style(FillStyle::Solid)
+			.graphics_exposure(false);
+
+		let request = CreateGraphicsContext {
+			graphics_context_id: GraphicsContext::new(0x0060_0003),
+			drawable: Window::new(0x0000_0100).into(),
+			graphics_options: options.build(),
+		};
+
+		// Every value takes 4 bytes, including those which are smaller
+		// elsewhere in the protocol.
+		#[rustfmt::skip]
+		assert_eq!(create_graphics_context_round_trip(&request), [
+			55, 0, 0, 16, // opcode, unused, length
+			0, 0x60, 0, 3, // graphics_context_id
+			0, 0, 1, 0, // drawable
+			0, 0x41, 0x41, 0xff, // options mask
+			0, 0, 0, 6, // function
+			0, 0, 0, 0xff, // plane_mask
+			0, 0xff, 0, 0, // foreground_color
+			0, 0, 0, 1, // background_color
+			0, 0, 0, 3, // line_width
+			0, 0, 0, 1, // line_style
+			0, 0, 0, 2, // cap_style
+			0, 0, 0, 2, // join_style
+			0, 0, 0, 0, // fill_style
+			0, 0x60, 0, 0, // font
+			0, 0, 0, 0, // graphics_exposure
+			0, 0, 0, 0, // arc_mode
+		]);
+	}
+
+	#[test]
+	fn test_copy_graphics_options() {
+		let request = CopyGraphicsOptions {
+			source: GraphicsContext::new(0x0060_0002),
+			destination: GraphicsContext::new(0x0060_0003),
+			options_mask: GraphicsOptionsMask::FOREGROUND_COLOR | GraphicsOptionsMask::FONT,
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			57, 0, 0, 4, // opcode, unused, length
+			0, 0x60, 0, 2, // source
+			0, 0x60, 0, 3, // destination
+			0, 0, 0x40, 0x04, // options_mask
+		]);
+		assert_eq!(
+			CopyGraphicsOptions::read_from(&mut &bytes[1..]).unwrap(),
+			request,
+		);
+	}
+
+	/// The `left_ptr` cursor from the standard cursor font, as created by
+	/// Xlib's `XCreateFontCursor(display, XC_left_ptr)`.
+	fn left_pointer() -> CreateGlyphCursorAppearance {
+		CreateGlyphCursorAppearance {
+			cursor_appearance_id: CursorAppearance::new(0x0060_0001),
+			source_font: Font::new(0x0060_0000),
+			mask_font: Some(Font::new(0x0060_0000)),
+			source_char: 68,
+			mask_char: 69,
+			foreground_color: RgbColor::BLACK,
+			background_color: RgbColor::from((u8::MAX, u8::MAX, u8::MAX)),
+		}
+	}
+
+	#[test]
+	fn test_create_glyph_cursor_left_pointer() {
+		#[rustfmt::skip]
+		let expected = [
+			94, 0, 8, 0, // opcode, unused, length
+			0x01, 0x00, 0x60, 0x00, // cursor_appearance_id
+			0x00, 0x00, 0x60, 0x00, // source_font
+			0x00, 0x00, 0x60, 0x00, // mask_font
+			68, 0, 69, 0, // source_char, mask_char
+			0, 0, 0, 0, 0, 0, // foreground_color
+			0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // background_color
+		];
+
+		let mut bytes = vec![];
+		left_pointer()
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+
+		assert_eq!(bytes, expected);
+		assert_eq!(
+			CreateGlyphCursorAppearance::read_from_ordered(
+				&mut &bytes[1..],
+				ByteOrder::LittleEndian
+			)
+			.unwrap(),
+			left_pointer(),
+		);
+	}
+
+	#[test]
+	fn test_create_glyph_cursor_without_mask() {
+		let request = CreateGlyphCursorAppearance {
+			mask_font: None,
+			..left_pointer()
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		// No mask font is encoded as `0`.
+		assert_eq!(bytes[12..16], [0, 0, 0, 0]);
+		assert_eq!(
+			CreateGlyphCursorAppearance::read_from(&mut &bytes[1..]).unwrap(),
+			request,
+		);
+	}
+}
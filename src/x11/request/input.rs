@@ -24,16 +24,15 @@ use xrbk::{
 	WriteResult,
 	X11Size,
 };
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
-use array_init::array_init;
-use std::ops::RangeInclusive;
+use std::{num::NonZeroU16, ops::RangeInclusive};
 use thiserror::Error;
 
 use crate::{
-	message::Request,
+	message::{write_request_length, LengthMode, Request, WritableRequest},
 	set::KeyboardOptions,
-	unit::{Px, SignedPercentage},
+	unit::{Px, SignedPercentage, ValueOutOfBounds},
 	x11::{error, reply},
 	Any,
 	AnyModifierKeyMask,
@@ -46,40 +45,10 @@ use crate::{
 	FreezeMode,
 	Keycode,
 	Keysym,
+	ModifierMap,
 	Window,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum GrabCursorError for GrabCursor {
 		CursorAppearance,
@@ -115,12 +84,16 @@ derive_xrb! {
 	/// [`CursorAppearance` error]: error::CursorAppearance
 	#[doc(alias = "GrabPointer")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[builder]
 	pub struct GrabCursor: Request(26, GrabCursorError) -> reply::GrabCursor {
 		/// Whether cursor [events] which would normally be reported to this
 		/// client are reported normally.
 		///
 		/// [events]: crate::message::Event
 		#[metabyte]
+		#[default]
 		pub owner_events: bool,
 
 		/// The [window] on which the cursor is grabbed.
@@ -138,6 +111,7 @@ derive_xrb! {
 		/// your client.
 		///
 		/// [events]: crate::message::Event
+		#[default]
 		pub event_mask: CursorEventMask,
 
 		/// The [freeze mode] applied to the cursor.
@@ -156,6 +130,7 @@ derive_xrb! {
 		///
 		/// [`AllowEvents` request]: AllowEvents
 		#[doc(alias("pointer_mode", "cursor_mode"))]
+		#[default(FreezeMode::Unfrozen)]
 		pub cursor_freeze: FreezeMode,
 		/// The [freeze mode] applied to the keyboard.
 		///
@@ -173,6 +148,7 @@ derive_xrb! {
 		///
 		/// [`AllowEvents` request]: AllowEvents
 		#[doc(alias = "keyboard_mode")]
+		#[default(FreezeMode::Unfrozen)]
 		pub keyboard_freeze: FreezeMode,
 
 		/// Optionally confines the cursor to the given [window].
@@ -212,6 +188,7 @@ derive_xrb! {
 		/// The [time] at which this grab is recorded as having been initiated.
 		///
 		/// [time]: crate::Timestamp
+		#[default(CurrentableTime::CurrentTime)]
 		pub time: CurrentableTime,
 	}
 
@@ -228,6 +205,8 @@ derive_xrb! {
 	/// [`LeaveWindow`]: crate::x11::event::LeaveWindow
 	#[doc(alias = "UngrabPointer")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UngrabCursor: Request(27) {
 		/// The [time] at which the grab is recorded as having been released.
 		///
@@ -279,12 +258,17 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`CursorAppearance` error]: error::CursorAppearance
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	#[x11_compat]
+	#[builder]
 	pub struct GrabButton: Request(28, GrabButtonError) {
 		/// Whether cursor [events] which would normally be reported to this
 		/// client are reported normally.
 		///
 		/// [events]: crate::message::Event
 		#[metabyte]
+		#[default]
 		pub owner_events: bool,
 
 		/// The [window] on which the `button` is grabbed.
@@ -302,6 +286,7 @@ derive_xrb! {
 		/// grabbing client.
 		///
 		/// [events]: crate::message::Event
+		#[default]
 		pub event_mask: CursorEventMask,
 
 		/// The [freeze mode] applied to the cursor.
@@ -320,6 +305,7 @@ derive_xrb! {
 		///
 		/// [`AllowEvents` request]: AllowEvents
 		#[doc(alias("pointer_mode", "cursor_mode"))]
+		#[default(FreezeMode::Unfrozen)]
 		pub cursor_freeze: FreezeMode,
 		/// The [freeze mode] applied to the keyboard.
 		///
@@ -337,6 +323,7 @@ derive_xrb! {
 		///
 		/// [`AllowEvents` request]: AllowEvents
 		#[doc(alias = "keyboard_mode")]
+		#[default(FreezeMode::Unfrozen)]
 		pub keyboard_freeze: FreezeMode,
 
 		/// Optionally confines the cursor to the given [window].
@@ -370,6 +357,7 @@ derive_xrb! {
 		/// [window]: Window
 		///
 		/// [`CursorAppearance` error]: error::CursorAppearance
+		#[doc(alias = "cursor")]
 		pub cursor_appearance: Option<CursorAppearance>,
 
 		/// The [button] for which this grab is established.
@@ -392,6 +380,7 @@ derive_xrb! {
 		/// at all.
 		///
 		/// [`ANY_MODIFIER`]: AnyModifierKeyMask::ANY_MODIFIER
+		#[default]
 		pub modifiers: AnyModifierKeyMask,
 	}
 }
@@ -418,6 +407,8 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UngrabButton: Request(29, UngrabButtonError) {
 		/// The [button] which the [passive button grab] was established for.
 		///
@@ -487,6 +478,8 @@ derive_xrb! {
 	/// [`CursorAppearance` error]: error::CursorAppearance
 	#[doc(alias = "ChangeActivePointerGrab")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeActiveCursorGrab: Request(30, ChangeActiveCursorGrabError) {
 		/// Optionally overrides the [appearance of the cursor], no matter which
 		/// [window] it is within, for the duration of the grab.
@@ -554,6 +547,8 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GrabKeyboard: Request(31, GrabKeyboardError) -> reply::GrabKeyboard {
 		/// Whether key [events] which would normally be reported to this client
 		/// are reported normally.
@@ -633,6 +628,8 @@ derive_xrb! {
 	/// [`Focus`]: crate::x11::event::Focus
 	/// [`Unfocus`]: crate::x11::event::Unfocus
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UngrabKeyboard: Request(32) {
 		/// The [time] at which the grab is recorded as having been released.
 		///
@@ -680,6 +677,8 @@ derive_xrb! {
 	/// [`Access` error]: error::Access
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GrabKey: Request(33, GrabKeyError) {
 		/// Whether key [events] which would normally be reported to this client
 		/// are reported normally.
@@ -785,6 +784,8 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UngrabKey: Request(34, UngrabKeyError) {
 		/// The key which the [passive key grab] was established for.
 		///
@@ -834,7 +835,10 @@ derive_xrb! {
 /// [`AllowEvents` request].
 ///
 /// [`AllowEvents` request]: AllowEvents
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AllowEventsMode {
 	/// Unfreezes the cursor if it is frozen and you have active grab on the
 	/// cursor.
@@ -910,6 +914,8 @@ derive_xrb! {
 	/// [frozen]: FreezeMode::Frozen
 	/// [request]: Request
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct AllowEvents: Request(35, error::Value) {
 		/// The conditions under which the queued [events] are released.
 		///
@@ -935,6 +941,8 @@ derive_xrb! {
 	///
 	/// [request]: Request
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GrabServer: Request(36);
 
 	/// A [request] that unfreezes processing of [requests][request] and
@@ -942,6 +950,8 @@ derive_xrb! {
 	///
 	/// [request]: Request
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UngrabServer: Request(37);
 
 	/// A [request] that gets the current location of the cursor.
@@ -956,6 +966,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias("QueryPointer, QueryCursor, GetCursorPos, GetCursorLocation"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryCursorLocation: Request(38, error::Window) -> reply::QueryCursorLocation {
 		/// Specifies a [window] to receive relative coordinates of the cursor
 		/// in relation to, if the cursor is on the same screen.
@@ -986,6 +998,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "GetMotionEvents")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetMotionHistory: Request(39, error::Window) -> reply::GetMotionHistory {
 		/// The [window] for which the motion history is returned.
 		///
@@ -1023,6 +1037,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "TranslateCoordinates")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ConvertCoordinates: Request(40, error::Window) -> reply::ConvertCoordinates {
 		/// The [window] which the `original_coords` are relative to.
 		///
@@ -1068,6 +1084,9 @@ derive_xrb! {
 ///
 /// [`WarpCursor` request]: WarpCursor
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WarpSourceDimension {
 	/// Set the `source_width` to the width of the `source` [window] minus the x
 	/// coordinate or the `source_height` to the height of the `source` [window]
@@ -1076,7 +1095,10 @@ pub enum WarpSourceDimension {
 	/// [window]: Window
 	FillRemaining,
 	/// This specific width or height.
-	Other(u16),
+	///
+	/// This is never zero: a width or height of zero means
+	/// [`FillRemaining`](WarpSourceDimension::FillRemaining).
+	Other(NonZeroU16),
 }
 
 impl ConstantX11Size for WarpSourceDimension {
@@ -1094,10 +1116,7 @@ impl Readable for WarpSourceDimension {
 	where
 		Self: Sized,
 	{
-		Ok(match buf.get_u16() {
-			zero if zero == 0 => Self::FillRemaining,
-			other => Self::Other(other),
-		})
+		Ok(NonZeroU16::new(u16::read_from(buf)?).map_or_else(|| Self::FillRemaining, Self::Other))
 	}
 }
 
@@ -1105,7 +1124,7 @@ impl Writable for WarpSourceDimension {
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
 		match self {
 			Self::FillRemaining => buf.put_u16(0),
-			Self::Other(other) => other.write_to(buf)?,
+			Self::Other(other) => other.get().write_to(buf)?,
 		}
 
 		Ok(())
@@ -1125,6 +1144,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "WarpPointer")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct WarpCursor: Request(41, error::Window) {
 		/// The [window] which the cursor is being warped from.
 		///
@@ -1200,7 +1221,10 @@ request_error! {
 /// [window]: Window
 ///
 /// [`SetFocus` request]: SetFocus
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RevertFocus {
 	/// Revert the focus to no [window].
 	///
@@ -1246,6 +1270,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias("SetInputFocus", "Focus", "FocusWindow"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetFocus: Request(42, SetFocusError) {
 		/// What the focus should revert to if the focused [window] becomes
 		/// unviewable.
@@ -1282,6 +1308,8 @@ derive_xrb! {
 	/// [`GetFocus` reply]: reply::GetFocus
 	#[doc(alias = "GetInputFocus")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetFocus: Request(43) -> reply::GetFocus;
 
 	/// A [request] that returns a bit vector of the currently held keys on the
@@ -1295,6 +1323,8 @@ derive_xrb! {
 	/// [`QueryKeyboard` reply]: reply::QueryKeyboard
 	#[doc(alias = "QueryKeymap")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryKeyboard: Request(44) -> reply::QueryKeyboard;
 }
 
@@ -1408,12 +1438,18 @@ pub struct ChangeKeyboardMapping<const KEYSYMS_PER_KEYCODE: usize> {
 	pub mappings: Vec<[Keysym; KEYSYMS_PER_KEYCODE]>,
 }
 
+impl<const KEYSYMS_PER_KEYCODE: usize> crate::message::__private::Sealed
+	for ChangeKeyboardMapping<KEYSYMS_PER_KEYCODE>
+{
+}
+
 impl<const KEYSYMS_PER_KEYCODE: usize> Request for ChangeKeyboardMapping<KEYSYMS_PER_KEYCODE> {
 	type OtherErrors = error::Value;
 	type Reply = ();
 
 	const MAJOR_OPCODE: u8 = 100;
 	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = false;
 }
 
 impl<const KEYSYMS_PER_KEYCODE: usize> X11Size for ChangeKeyboardMapping<KEYSYMS_PER_KEYCODE> {
@@ -1436,17 +1472,27 @@ impl<const KEYSYMS_PER_KEYCODE: usize> X11Size for ChangeKeyboardMapping<KEYSYMS
 // compile time.
 
 impl<const KEYSYMS_PER_KEYCODE: usize> Writable for ChangeKeyboardMapping<KEYSYMS_PER_KEYCODE> {
-	#[allow(clippy::cast_possible_truncation)]
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		// Limit `buf` by the length (converted to bytes).
-		let buf = &mut buf.limit(usize::from(self.length()) * 4);
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl<const KEYSYMS_PER_KEYCODE: usize> WritableRequest
+	for ChangeKeyboardMapping<KEYSYMS_PER_KEYCODE>
+{
+	#[allow(clippy::cast_possible_truncation)]
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		const HEADER: usize = 4;
 
 		// The major opcode.
 		Self::MAJOR_OPCODE.write_to(buf)?;
 		// Length of `mappings`.
 		(self.mappings.len() as u8).write_to(buf)?;
 		// The length of the message.
-		self.length().write_to(buf)?;
+		write_request_length(buf, self.x11_size(), mode)?;
+
+		// Limit `buf` by the rest of the message.
+		let buf = &mut xrbk::limit(buf, self.x11_size() - HEADER);
 
 		self.first_keycode.write_to(buf)?;
 		(KEYSYMS_PER_KEYCODE as u8).write_to(buf)?;
@@ -1503,6 +1549,9 @@ impl<const KEYSYMS_PER_KEYCODE: usize> Writable for ChangeKeyboardMapping<KEYSYM
 ///
 /// [`Value` error]: error::Value
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetKeyboardMapping {
 	/// The range of [keycodes] for which this [request] returns their mapped
 	/// [keysyms].
@@ -1532,15 +1581,21 @@ pub struct GetKeyboardMapping {
 	// ```
 	// let count = keycodes.end().unwrap() - first_keycode.unwrap();
 	// ```
+	#[cfg_attr(test, proptest(strategy = "crate::arbitrary::keycode_ranges()"))]
+	#[cfg_attr(feature = "arbitrary", arbitrary(with = crate::unstructured::keycode_ranges))]
 	pub range: RangeInclusive<Keycode>,
 }
 
+impl crate::message::__private::Sealed for GetKeyboardMapping {}
+crate::message::register_message!(Request GetKeyboardMapping);
+
 impl Request for GetKeyboardMapping {
 	type OtherErrors = error::Value;
 	type Reply = reply::GetKeyboardMapping;
 
 	const MAJOR_OPCODE: u8 = 101;
 	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = true;
 }
 
 impl ConstantX11Size for GetKeyboardMapping {
@@ -1565,32 +1620,48 @@ impl Readable for GetKeyboardMapping {
 		const HEADER: usize = 4;
 
 		// Unused metabyte.
-		buf.advance(1);
+		xrbk::skip(buf, 1)?;
 
 		// The message length.
-		let length = usize::from(buf.get_u16()) * 4;
-		let buf = &mut buf.take(length - HEADER);
+		let length = usize::from(u16::read_from(buf)?) * 4;
+		let buf = &mut xrbk::take(buf, length.saturating_sub(HEADER));
 
 		let first_keycode = Keycode::read_from(buf)?;
-		let keycode_count = buf.get_u8();
-		buf.advance(2);
+		let keycode_count = u8::read_from(buf)?;
+		xrbk::skip(buf, 2)?;
+
+		// The range must contain at least one keycode, and must not end after
+		// the last keycode.
+		let Some(last_keycode) = keycode_count
+			.checked_sub(1)
+			.and_then(|extra| first_keycode.unwrap().checked_add(extra))
+		else {
+			return Err(ReadError::Other(Box::new(ValueOutOfBounds {
+				min: 1,
+				max: (u8::MAX - first_keycode.unwrap()).saturating_add(1),
+				found: keycode_count,
+			})));
+		};
 
 		Ok(Self {
-			range: RangeInclusive::new(
-				first_keycode,
-				Keycode::new(first_keycode.unwrap() + keycode_count - 1),
-			),
+			range: RangeInclusive::new(first_keycode, Keycode::new(last_keycode)),
 		})
 	}
 }
 
 impl Writable for GetKeyboardMapping {
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl WritableRequest for GetKeyboardMapping {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
 		Self::MAJOR_OPCODE.write_to(buf)?;
 		// Unused metabyte.
 		buf.put_u8(0);
 		// Message length.
-		self.length().write_to(buf)?;
+		write_request_length(buf, self.x11_size(), mode)?;
 
 		// First keycode.
 		self.range.start().write_to(buf)?;
@@ -1621,6 +1692,8 @@ derive_xrb! {
 	/// [options]: KeyboardOptions
 	#[doc(alias("ChangeKeyboardControl"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeKeyboardOptions: Request(102, ChangeKeyboardOptionsError) {
 		/// The changes that are made to the [keyboard options].
 		///
@@ -1643,6 +1716,8 @@ derive_xrb! {
 	/// [`GetKeyboardOptions` reply]: reply::GetKeyboardOptions
 	#[doc(alias("GetKeyboardControl"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetKeyboardOptions: Request(103) -> reply::GetKeyboardOptions;
 
 	/// A [request] that rings the bell on the keyboard at the given volume.
@@ -1680,18 +1755,41 @@ derive_xrb! {
 	/// [`bell_volume`]: KeyboardOptions::bell_volume
 	#[doc(alias("Bell"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct RingBell: Request(104, error::Value) {
 		/// The volume at which the bell is rung relative to the base
 		/// [`bell_volume`].
 		///
 		/// [`bell_volume`]: KeyboardOptions::bell_volume
+		#[metabyte]
 		#[doc(alias("percent"))]
 		pub volume: SignedPercentage,
 	}
 }
 
+impl RingBell {
+	/// Creates a new `RingBell` request with the given `volume`, relative to
+	/// the base [`bell_volume`].
+	///
+	/// # Errors
+	/// Returns a [`ValueOutOfBounds`] error if `volume < -100` or
+	/// `volume > 100`.
+	///
+	/// [`bell_volume`]: KeyboardOptions::bell_volume
+	pub const fn new(volume: i8) -> Result<Self, ValueOutOfBounds<i8>> {
+		match SignedPercentage::new(volume) {
+			Ok(volume) => Ok(Self { volume }),
+			Err(error) => Err(error),
+		}
+	}
+}
+
 /// Represents a type that may be chosen as its default value.
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OrDefault<T> {
 	/// The default value is chosen.
 	Default,
@@ -1714,7 +1812,7 @@ impl Readable for OrDefault<Px<u8>> {
 	where
 		Self: Sized,
 	{
-		match buf.get_i16() {
+		match i16::read_from(buf)? {
 			default if default == -1 => Ok(Self::Default),
 
 			other => match u8::try_from(other) {
@@ -1740,6 +1838,9 @@ impl Writable for OrDefault<Px<u8>> {
 ///
 /// The denominator may not be zero.
 #[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Fraction<T: X11Size + Readable + Writable>(T, T);
 
 impl<T: X11Size + Readable + Writable> Fraction<T> {
@@ -1765,6 +1866,7 @@ impl<T: X11Size + Readable + Writable> Fraction<T> {
 
 /// A denominator of zero was provided to a [`Fraction`].
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[error("denominator was specified as 0; cannot divide by zero")]
 pub struct DivideByZero;
 
@@ -1783,6 +1885,54 @@ impl Fraction<OrDefault<Px<u8>>> {
 	}
 }
 
+impl From<f32> for Fraction<OrDefault<Px<u8>>> {
+	/// Approximates the given `value` with the closest fraction whose
+	/// numerator and denominator are no greater than `255`.
+	///
+	/// Negative values and NaN are approximated as `0 / 1`, and values greater
+	/// than `255` as `255 / 1`.
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	fn from(value: f32) -> Self {
+		if value.is_nan() || value <= 0.0 {
+			return Self(OrDefault::Other(Px(0)), OrDefault::Other(Px(1)));
+		}
+
+		let mut closest = (0, 1, f32::INFINITY);
+
+		// The closest fraction with the smallest denominator is chosen.
+		for denominator in 1..=u8::MAX {
+			// Truncation is not possible, as the value is clamped to `255`.
+			let numerator = (value * f32::from(denominator)).round().min(255.0) as u8;
+			let error = (f32::from(numerator) / f32::from(denominator) - value).abs();
+
+			if error < closest.2 {
+				closest = (numerator, denominator, error);
+			}
+		}
+
+		let (numerator, denominator, _) = closest;
+
+		Self(
+			OrDefault::Other(Px(numerator)),
+			OrDefault::Other(Px(denominator)),
+		)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fraction<OrDefault<Px<u8>>> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The denominator is checked with `new`.
+		let (numerator, denominator) =
+			<(OrDefault<Px<u8>>, OrDefault<Px<u8>>)>::deserialize(deserializer)?;
+
+		Self::new(numerator, denominator).map_err(serde::de::Error::custom)
+	}
+}
+
 impl Fraction<Px<u16>> {
 	/// Creates a new [`Fraction`] with the given `numerator` and `denominator`.
 	///
@@ -1796,6 +1946,19 @@ impl Fraction<Px<u16>> {
 	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Fraction<Px<u16>> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		// The denominator is checked with `new`.
+		let (numerator, denominator) = <(Px<u16>, Px<u16>)>::deserialize(deserializer)?;
+
+		Self::new(numerator, denominator).map_err(serde::de::Error::custom)
+	}
+}
+
 derive_xrb! {
 	/// A [request] that changes the options configured for the cursor.
 	///
@@ -1804,6 +1967,8 @@ derive_xrb! {
 	/// [request]: Request
 	#[doc(alias("ChangePointerControl", "ChangePointerOptions", "ChangeCursorControl"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeCursorOptions: Request(105, error::Value) {
 		/// A multiplier applied to the acceleration of the cursor when the
 		/// [`threshold`] is exceeded.
@@ -1831,6 +1996,8 @@ derive_xrb! {
 	/// [request]: Request
 	#[doc(alias("GetPointerControl", "GetPointerOptions", "GetCursorControl"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetCursorOptions: Request(106) -> reply::GetCursorOptions;
 
 	/// A [request] that changes the mapping of the [mouse buttons].
@@ -1868,6 +2035,8 @@ derive_xrb! {
 	/// [`Value` error]: error::Value
 	#[doc(alias("SetPointerMapping", "SetCursorMapping"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetButtonMapping: Request(116, error::Value) -> reply::SetButtonMapping {
 		// The length of `mappings`.
 		#[metabyte]
@@ -1913,221 +2082,731 @@ derive_xrb! {
 	/// [`GetButtonMapping` reply]: reply::GetButtonMapping
 	#[doc(alias("GetPointerMapping", "GetCursorMapping"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetButtonMapping: Request(117) -> reply::GetButtonMapping;
 }
 
-/// A [request] that sets the mapping of [keycodes] for each modifier.
-///
-/// Each modifier has zero or more [keycodes] mapped to it. For example, the
-/// shift modifier typically has both <kbd>⇧ Left Shift</kbd> and
-/// <kbd>⇧ Right Shift</kbd> keycodes mapped to it, while the caps lock modifier
-/// only has one <kbd>Caps Lock</kbd> key mapped to it.
-///
-/// If no [keycodes] are mapped to a modifier, that modifier is disabled.
-///
-/// See also: [`GetModifierMapping`].
-///
-/// # Events generated
-/// A [`MappingChange` event] is generated if this [request] is [successful] in
-/// changing the modifier mapping.
-///
-/// # Replies
-/// This [request] generates a [`SetModifierMapping` reply].
-///
-/// # Errors
-/// A [`Value` error] is generated if any of the specified [keycodes] are either
-/// less than the [`min_keycode`] or greater than the [`max_keycode`] returned
-/// during [connection setup].
-///
-/// [keycodes]: Keycode
-/// [request]: Request
-/// [connection setup]: crate::connection::InitConnection
-///
-/// [successful]: reply::SetModifierMappingStatus::Success
-///
-/// [`min_keycode`]: crate::connection::ConnectionSuccess::min_keycode
-/// [`max_keycode`]: crate::connection::ConnectionSuccess::max_keycode
-///
-/// [`SetModifierMapping` request]: SetModifierMapping
-/// [`SetModifierMapping` reply]: reply::SetModifierMapping
-///
-/// [`MappingChange` event]: crate::x11::event::MappingChange
-///
-/// [`Value` error]: error::Value
-#[derive(Debug, Hash, PartialEq, Eq)]
-pub struct SetModifierMapping {
-	/// The [keycodes] mapped to the shift modifier.
+derive_xrb! {
+	/// A [request] that sets the mapping of [keycodes] for each modifier.
 	///
-	/// [keycodes]: Keycode
-	pub shift_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the caps lock modifier.
+	/// Each modifier has zero or more [keycodes] mapped to it. For example, the
+	/// shift modifier typically has both <kbd>⇧ Left Shift</kbd> and
+	/// <kbd>⇧ Right Shift</kbd> keycodes mapped to it, while the caps lock
+	/// modifier only has one <kbd>Caps Lock</kbd> key mapped to it.
 	///
-	/// [keycodes]: Keycode
-	pub capslock_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the control modifier.
+	/// If no [keycodes] are mapped to a modifier, that modifier is disabled.
+	///
+	/// See also: [`GetModifierMapping`].
+	///
+	/// # Events generated
+	/// A [`MappingChange` event] is generated if this [request] is [successful]
+	/// in changing the modifier mapping.
+	///
+	/// # Replies
+	/// This [request] generates a [`SetModifierMapping` reply].
+	///
+	/// # Errors
+	/// A [`Value` error] is generated if any of the specified [keycodes] are
+	/// either less than the [`min_keycode`] or greater than the [`max_keycode`]
+	/// returned during [connection setup].
 	///
 	/// [keycodes]: Keycode
-	pub ctrl_keycodes: Vec<Keycode>,
+	/// [request]: Request
+	/// [connection setup]: crate::connection::InitConnection
+	///
+	/// [successful]: reply::SetModifierMappingStatus::Success
+	///
+	/// [`min_keycode`]: crate::connection::ConnectionSuccess::min_keycode
+	/// [`max_keycode`]: crate::connection::ConnectionSuccess::max_keycode
+	///
+	/// [`SetModifierMapping` request]: SetModifierMapping
+	/// [`SetModifierMapping` reply]: reply::SetModifierMapping
+	///
+	/// [`MappingChange` event]: crate::x11::event::MappingChange
+	///
+	/// [`Value` error]: error::Value
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct SetModifierMapping: Request(118, error::Value) -> reply::SetModifierMapping {
+		// The number of keycodes in each row of the `modifier_map`.
+		#[metabyte]
+		let keycodes_per_modifier: u8 = modifier_map => modifier_map.keycodes_per_modifier(),
+
+		/// The [keycodes] to map to each modifier.
+		///
+		/// [keycodes]: Keycode
+		#[context(keycodes_per_modifier => usize::from(*keycodes_per_modifier))]
+		pub modifier_map: ModifierMap,
+	}
+}
 
-	/// The [keycodes] mapped to the Mod1 modifier.
+derive_xrb! {
+	/// A [request] that returns the [keycodes] currently mapped to each
+	/// modifier.
 	///
-	/// [keycodes]: Keycode
-	pub mod1_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod2 modifier.
+	/// Each modifier has zero or more [keycodes] mapped to it. For example, the
+	/// shift modifier typically has both <kbd>⇧ Left Shift</kbd> and
+	/// <kbd>⇧ Right Shift</kbd> keycodes mapped to it, while the caps lock
+	/// modifier only has one <kbd>Caps Lock</kbd> key mapped to it.
 	///
-	/// [keycodes]: Keycode
-	pub mod2_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod3 modifier.
+	/// If no [keycodes] are mapped to a modifier, that modifier is disabled.
 	///
-	/// [keycodes]: Keycode
-	pub mod3_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod4 modifier.
+	/// See also: [`SetModifierMapping`].
 	///
-	/// This is typically the key variously called 'super', 'meta', 'windows
-	/// key', 'cmd', etc.
+	/// # Replies
+	/// This [request] generates a [`GetModifierMapping` reply].
 	///
 	/// [keycodes]: Keycode
-	pub mod4_keycodes: Vec<Keycode>,
-	/// The [keycodes] mapped to the Mod5 modifier.
+	/// [request]: Request
 	///
-	/// [keycodes]: Keycode
-	pub mod5_keycodes: Vec<Keycode>,
+	/// [`GetModifierMapping` reply]: reply::GetModifierMapping
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+	pub struct GetModifierMapping: Request(119) -> reply::GetModifierMapping;
 }
 
-impl SetModifierMapping {
-	fn max_keycodes_len(&self) -> usize {
-		[
-			&self.shift_keycodes,
-			&self.capslock_keycodes,
-			&self.ctrl_keycodes,
-			&self.mod1_keycodes,
-			&self.mod2_keycodes,
-			&self.mod3_keycodes,
-			&self.mod4_keycodes,
-			&self.mod5_keycodes,
-		]
-		.into_iter()
-		.map(Vec::len)
-		.max()
-		.expect("there's definitely more than one element")
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::arbitrary::round_trip_tests;
+	use std::iter;
+	use xrbk::ByteOrder;
+
+	// `ChangeKeyboardMapping` is not included because it does not implement
+	// `Readable`.
+	round_trip_tests! {
+		GrabCursor => test_grab_cursor_round_trip,
+		UngrabCursor => test_ungrab_cursor_round_trip,
+		GrabButton => test_grab_button_round_trip,
+		UngrabButton => test_ungrab_button_round_trip,
+		ChangeActiveCursorGrab => test_change_active_cursor_grab_round_trip,
+		GrabKeyboard => test_grab_keyboard_round_trip,
+		UngrabKeyboard => test_ungrab_keyboard_round_trip,
+		GrabKey => test_grab_key_round_trip,
+		UngrabKey => test_ungrab_key_round_trip,
+		AllowEvents => test_allow_events_round_trip,
+		GrabServer => test_grab_server_round_trip,
+		UngrabServer => test_ungrab_server_round_trip,
+		QueryCursorLocation => test_query_cursor_location_round_trip,
+		GetMotionHistory => test_get_motion_history_round_trip,
+		ConvertCoordinates => test_convert_coordinates_round_trip,
+		WarpCursor => test_warp_cursor_round_trip,
+		SetFocus => test_set_focus_round_trip,
+		GetFocus => test_get_focus_round_trip,
+		QueryKeyboard => test_query_keyboard_round_trip,
+		GetKeyboardMapping => test_get_keyboard_mapping_round_trip,
+		ChangeKeyboardOptions => test_change_keyboard_options_round_trip,
+		GetKeyboardOptions => test_get_keyboard_options_round_trip,
+		RingBell => test_ring_bell_round_trip,
+		ChangeCursorOptions => test_change_cursor_options_round_trip,
+		GetCursorOptions => test_get_cursor_options_round_trip,
+		SetButtonMapping => test_set_button_mapping_round_trip,
+		GetButtonMapping => test_get_button_mapping_round_trip,
+		SetModifierMapping => test_set_modifier_mapping_round_trip,
+		GetModifierMapping => test_get_modifier_mapping_round_trip,
 	}
-}
 
-impl Request for SetModifierMapping {
-	type OtherErrors = error::Value;
-	type Reply = reply::SetModifierMapping;
+	fn grab_cursor() -> GrabCursor {
+		GrabCursor {
+			owner_events: true,
+			grab_window: Window::new(0x0040_0001),
+			event_mask: CursorEventMask::BUTTON_PRESS | CursorEventMask::ANY_MOTION,
+			cursor_freeze: FreezeMode::Unfrozen,
+			keyboard_freeze: FreezeMode::Frozen,
+			confine_to: None,
+			cursor_appearance: Some(CursorAppearance::new(0x0040_0002)),
+			time: CurrentableTime::CurrentTime,
+		}
+	}
 
-	const MAJOR_OPCODE: u8 = 118;
-	const MINOR_OPCODE: Option<u16> = None;
-}
+	#[test]
+	fn test_grab_cursor_builder() {
+		let window = Window::new(0x0040_0001);
+
+		assert_eq!(
+			GrabCursor::new(window),
+			GrabCursor {
+				owner_events: false,
+				grab_window: window,
+				event_mask: CursorEventMask::empty(),
+				cursor_freeze: FreezeMode::Unfrozen,
+				keyboard_freeze: FreezeMode::Unfrozen,
+				confine_to: None,
+				cursor_appearance: None,
+				time: CurrentableTime::CurrentTime,
+			},
+		);
 
-impl X11Size for SetModifierMapping {
-	fn x11_size(&self) -> usize {
-		const HEADER: usize = 4;
+		let request = GrabCursor::builder(window)
+			.owner_events(true)
+			.event_mask(CursorEventMask::BUTTON_PRESS | CursorEventMask::ANY_MOTION)
+			.keyboard_freeze(FreezeMode::Frozen)
+			.cursor_appearance(CursorAppearance::new(0x0040_0002))
+			.build();
 
-		let keycodes_size = self.max_keycodes_len() * Keycode::X11_SIZE;
+		assert_eq!(request, grab_cursor());
+	}
 
-		HEADER + (8 * keycodes_size)
+	#[test]
+	fn test_grab_button_builder() {
+		let window = Window::new(0x0040_0001);
+		let button = Any::Other(Button::PRIMARY);
+
+		assert_eq!(
+			GrabButton::new(window, button),
+			GrabButton {
+				owner_events: false,
+				grab_window: window,
+				event_mask: CursorEventMask::empty(),
+				cursor_freeze: FreezeMode::Unfrozen,
+				keyboard_freeze: FreezeMode::Unfrozen,
+				confine_to: None,
+				cursor_appearance: None,
+				button,
+				modifiers: AnyModifierKeyMask::empty(),
+			},
+		);
+
+		let request = GrabButton::builder(window, button)
+			.event_mask(CursorEventMask::BUTTON_RELEASE)
+			.cursor_freeze(FreezeMode::Frozen)
+			.confine_to(Window::new(0x0040_0002))
+			.modifiers(AnyModifierKeyMask::ANY_MODIFIER)
+			.build();
+
+		assert_eq!(
+			request,
+			GrabButton {
+				owner_events: false,
+				grab_window: window,
+				event_mask: CursorEventMask::BUTTON_RELEASE,
+				cursor_freeze: FreezeMode::Frozen,
+				keyboard_freeze: FreezeMode::Unfrozen,
+				confine_to: Some(Window::new(0x0040_0002)),
+				cursor_appearance: None,
+				button,
+				modifiers: AnyModifierKeyMask::ANY_MODIFIER,
+			},
+		);
 	}
-}
 
-impl Readable for SetModifierMapping {
-	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
-	where
-		Self: Sized,
-	{
-		const ALIGNMENT: usize = 4;
+	#[test]
+	fn test_grab_cursor_round_trip_in_both_orders() {
+		for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+			let request = grab_cursor();
+
+			let mut bytes = vec![];
+			request.write_to_ordered(&mut bytes, order).unwrap();
+			assert_eq!(bytes.len(), request.x11_size());
+
+			// The major opcode is not read by `read_from`.
+			let read = GrabCursor::read_from_ordered(&mut &bytes[1..], order).unwrap();
+			assert_eq!(read, request);
+		}
+	}
+
+	#[test]
+	fn test_grab_cursor_big_endian_fixture() {
+		#[rustfmt::skip]
+		let bytes = [
+			26, 1, 0, 6, // header
+			0x00, 0x40, 0x00, 0x01, // grab_window
+			0x00, 0x44, // event_mask
+			1, // cursor_freeze
+			0, // keyboard_freeze
+			0x00, 0x00, 0x00, 0x00, // confine_to
+			0x00, 0x40, 0x00, 0x02, // cursor_appearance
+			0x00, 0x00, 0x00, 0x00, // time
+		];
+
+		let mut written = vec![];
+		grab_cursor()
+			.write_to_ordered(&mut written, ByteOrder::BigEndian)
+			.unwrap();
+		assert_eq!(written, bytes);
+
+		assert_eq!(
+			GrabCursor::read_from_ordered(&mut &bytes[1..], ByteOrder::BigEndian).unwrap(),
+			grab_cursor()
+		);
+	}
+
+	#[test]
+	fn test_unrecognized_discriminants() {
+		assert!(matches!(
+			AllowEventsMode::read_from(&mut &[8][..]),
+			Err(ReadError::UnrecognizedDiscriminant {
+				type_name: "AllowEventsMode",
+				discriminant: 8,
+			}),
+		));
+		assert!(matches!(
+			RevertFocus::read_from(&mut &[3][..]),
+			Err(ReadError::UnrecognizedDiscriminant {
+				type_name: "RevertFocus",
+				discriminant: 3,
+			}),
+		));
+
+		let mut bytes = vec![];
+		grab_cursor().write_to(&mut bytes).unwrap();
+		// Replace the `cursor_freeze` mode with an unrecognized discriminant.
+		bytes[10] = 2;
+
+		let error = GrabCursor::read_from(&mut &bytes[1..]).unwrap_err();
+
+		assert_eq!(
+			error.to_string(),
+			"GrabCursor.cursor_freeze: unrecognized FreezeMode discriminant: 2"
+		);
+		assert!(matches!(
+			error.root_cause(),
+			ReadError::UnrecognizedDiscriminant {
+				type_name: "FreezeMode",
+				discriminant: 2,
+			},
+		));
+
+		let source = std::error::Error::source(&error).unwrap();
+		assert_eq!(
+			source.to_string(),
+			"unrecognized FreezeMode discriminant: 2"
+		);
+	}
+
+	#[test]
+	fn test_grab_cursor_optional_ids() {
+		let some = GrabCursor {
+			confine_to: Some(Window::new(0x0040_0003)),
+			..grab_cursor()
+		};
+		let none = GrabCursor {
+			confine_to: None,
+			cursor_appearance: None,
+			..grab_cursor()
+		};
+
+		let mut bytes = vec![];
+		some.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes[12..16], [0x00, 0x40, 0x00, 0x03]);
+		assert_eq!(bytes[16..20], [0x00, 0x40, 0x00, 0x02]);
+		assert_eq!(GrabCursor::read_from(&mut &bytes[1..]).unwrap(), some);
+
+		let mut bytes = vec![];
+		none.write_to(&mut bytes).unwrap();
+		// `None` is written as zero.
+		assert_eq!(bytes[12..20], [0; 8]);
+		assert_eq!(GrabCursor::read_from(&mut &bytes[1..]).unwrap(), none);
+	}
+
+	#[test]
+	fn test_grab_cursor_truncated() {
+		let mut bytes = vec![];
+		grab_cursor().write_to(&mut bytes).unwrap();
+
+		// The major opcode is not read by `read_from`.
+		let bytes = &bytes[1..];
+
+		for len in 0..bytes.len() {
+			assert!(
+				matches!(
+					GrabCursor::read_from(&mut &bytes[..len])
+						.as_ref()
+						.map_err(ReadError::root_cause),
+					Err(ReadError::UnexpectedEndOfData { .. }),
+				),
+				"reading {len} of {} bytes did not fail",
+				bytes.len(),
+			);
+		}
+	}
+
+	#[test]
+	fn test_grab_cursor_length_is_byte_swapped() {
+		let request = grab_cursor();
+
+		let mut big = vec![];
+		request
+			.write_to_ordered(&mut big, ByteOrder::BigEndian)
+			.unwrap();
+		let mut little = vec![];
+		request
+			.write_to_ordered(&mut little, ByteOrder::LittleEndian)
+			.unwrap();
+
+		assert_eq!(big[2..4], [0, 6]);
+		assert_eq!(little[2..4], [6, 0]);
+	}
+
+	#[cfg(feature = "trace-wire")]
+	#[test]
+	fn test_grab_cursor_wire_layout() {
+		use xrbk::{WireField, WireLayout};
+
+		let request = grab_cursor();
+		let layout: Vec<_> = request
+			.wire_layout()
+			.unwrap()
+			.into_iter()
+			.map(|WireField { name, offset, size }| (name, offset, size))
+			.collect();
+
+		assert_eq!(
+			layout,
+			[
+				("major_opcode", 0, 1),
+				("owner_events", 1, 1),
+				("length", 2, 2),
+				("grab_window", 4, 4),
+				("event_mask", 8, 2),
+				("cursor_freeze", 10, 1),
+				("keyboard_freeze", 11, 1),
+				("confine_to", 12, 4),
+				("cursor_appearance", 16, 4),
+				("time", 20, 4),
+			],
+		);
+
+		let dump = request.fmt_wire().unwrap();
+		assert_eq!(dump.lines().count(), layout.len());
+		assert!(dump.contains("     8  event_mask         00 44\n"));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn test_grab_cursor_serde_round_trip() {
+		let request = grab_cursor();
+
+		let json = serde_json::to_value(&request).unwrap();
+
+		// Masks are represented by the names of their flags.
+		assert_eq!(
+			json["event_mask"],
+			serde_json::json!(["BUTTON_PRESS", "ANY_MOTION"])
+		);
+
+		assert_eq!(serde_json::from_value::<GrabCursor>(json).unwrap(), request);
+	}
+
+	#[cfg(feature = "tracing")]
+	mod trace {
+		use super::*;
+		use std::{
+			fmt::Debug,
+			sync::{Arc, Mutex},
+		};
+		use tracing::{
+			field::{Field, Visit},
+			span::{Attributes, Id, Record},
+			subscriber::with_default,
+			Event,
+			Metadata,
+			Subscriber,
+		};
+		use tracing_core::span::Current;
+
+		/// The name and fields of a span, or the level and fields of an event.
+		type Recorded = (&'static str, Vec<(String, String)>);
+
+		/// A [`Subscriber`] which records every span and event.
+		#[derive(Default)]
+		struct Recorder {
+			spans: Arc<Mutex<Vec<Recorded>>>,
+			events: Arc<Mutex<Vec<Recorded>>>,
+
+			metadata: Mutex<Vec<&'static Metadata<'static>>>,
+			/// The spans which have been entered, so that the current span can
+			/// be found.
+			entered: Mutex<Vec<Id>>,
+		}
+
+		#[derive(Default)]
+		struct Fields(Vec<(String, String)>);
+
+		impl Visit for Fields {
+			fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+				self.0.push((field.name().to_owned(), format!("{value:?}")));
+			}
+		}
+
+		#[allow(clippy::cast_possible_truncation)]
+		impl Subscriber for Recorder {
+			fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+				true
+			}
+
+			fn new_span(&self, span: &Attributes<'_>) -> Id {
+				let mut fields = Fields::default();
+				span.record(&mut fields);
+
+				let mut spans = self.spans.lock().unwrap();
+				spans.push((span.metadata().name(), fields.0));
+				self.metadata.lock().unwrap().push(span.metadata());
+
+				Id::from_u64(spans.len() as u64)
+			}
+
+			fn record(&self, span: &Id, values: &Record<'_>) {
+				let mut fields = Fields::default();
+				values.record(&mut fields);
+
+				self.spans.lock().unwrap()[span.into_u64() as usize - 1]
+					.1
+					.extend(fields.0);
+			}
+
+			fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+			fn event(&self, event: &Event<'_>) {
+				let mut fields = Fields::default();
+				event.record(&mut fields);
+
+				self.events
+					.lock()
+					.unwrap()
+					.push((event.metadata().level().as_str(), fields.0));
+			}
+
+			fn enter(&self, span: &Id) {
+				self.entered.lock().unwrap().push(span.clone());
+			}
 
-		let keycodes_per_modifier = buf.get_u8();
+			fn exit(&self, _span: &Id) {
+				self.entered.lock().unwrap().pop();
+			}
 
-		let total_size = usize::from(buf.get_u16()) * ALIGNMENT;
-		let buf = &mut buf.take(total_size);
+			fn current_span(&self) -> Current {
+				match self.entered.lock().unwrap().last() {
+					Some(span) => {
+						let metadata = self.metadata.lock().unwrap()[span.into_u64() as usize - 1];
 
-		let [shift_keycodes, capslock_keycodes, ctrl_keycodes, mod1_keycodes, mod2_keycodes, mod3_keycodes, mod4_keycodes, mod5_keycodes] =
-			array_init(|_| {
-				let mut keycodes = vec![];
+						Current::new(span.clone(), metadata)
+					},
 
-				for _ in 0..keycodes_per_modifier {
-					match buf.get_u8() {
-						0 => {},
-						code => keycodes.push(Keycode(code)),
-					}
+					None => Current::none(),
 				}
+			}
+		}
 
-				keycodes
+		fn field(name: &str, value: &str) -> (String, String) {
+			(name.to_owned(), value.to_owned())
+		}
+
+		#[test]
+		fn test_grab_cursor_write_span() {
+			let recorder = Recorder::default();
+			let spans = Arc::clone(&recorder.spans);
+
+			with_default(recorder, || {
+				grab_cursor().write_to(&mut vec![]).unwrap();
 			});
 
-		Ok(Self {
-			shift_keycodes,
-			capslock_keycodes,
-			ctrl_keycodes,
-
-			mod1_keycodes,
-			mod2_keycodes,
-			mod3_keycodes,
-			mod4_keycodes,
-			mod5_keycodes,
-		})
+			assert_eq!(
+				*spans.lock().unwrap(),
+				[(
+					"write",
+					vec![
+						field("message", "\"GrabCursor\""),
+						field("major_opcode", "26"),
+						field("minor_opcode", "None"),
+						field("length", "24"),
+					],
+				)],
+			);
+		}
+
+		#[test]
+		fn test_grab_cursor_write_event() {
+			let recorder = Recorder::default();
+			let events = Arc::clone(&recorder.events);
+
+			with_default(recorder, || {
+				grab_cursor().write_to(&mut vec![]).unwrap();
+			});
+
+			assert_eq!(
+				*events.lock().unwrap(),
+				[(
+					"TRACE",
+					vec![
+						field("message", "\"GrabCursor\""),
+						field("direction", "\"write\""),
+						field("length", "24"),
+					],
+				)],
+			);
+		}
+
+		#[test]
+		fn test_grab_cursor_read_error_event() {
+			let mut bytes = vec![];
+			grab_cursor().write_to(&mut bytes).unwrap();
+			// Replace the `cursor_freeze` mode with an unrecognized discriminant.
+			bytes[10] = 5;
+
+			let recorder = Recorder::default();
+			let (spans, events) = (Arc::clone(&recorder.spans), Arc::clone(&recorder.events));
+
+			with_default(recorder, || {
+				assert!(GrabCursor::read_from(&mut &bytes[1..]).is_err());
+			});
+
+			let spans = spans.lock().unwrap();
+			assert_eq!(spans.len(), 1);
+			assert_eq!(spans[0].0, "read");
+			assert!(spans[0].1.contains(&field("length", "24")));
+
+			assert_eq!(
+				*events.lock().unwrap(),
+				[(
+					"DEBUG",
+					vec![field(
+						"error",
+						"GrabCursor.cursor_freeze: unrecognized FreezeMode discriminant: 5",
+					)],
+				)],
+			);
+		}
 	}
-}
 
-impl Writable for SetModifierMapping {
-	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		const HEADER: usize = 4;
+	#[test]
+	fn test_fraction_zero_denominator() {
+		let one = || OrDefault::Other(Px(1));
+
+		assert!(matches!(
+			Fraction::<OrDefault<Px<u8>>>::new(one(), OrDefault::Other(Px(0))),
+			Err(DivideByZero),
+		));
+		assert!(matches!(
+			Fraction::<Px<u16>>::new(Px(1), Px(0)),
+			Err(DivideByZero),
+		));
+
+		// The default denominator is not zero.
+		assert!(Fraction::<OrDefault<Px<u8>>>::new(one(), OrDefault::Default).is_ok());
+		assert!(Fraction::<Px<u16>>::new(Px(0), Px(1)).is_ok());
+	}
 
-		let max_keycodes_len = self.max_keycodes_len();
-		let keycodes_size = max_keycodes_len * Keycode::X11_SIZE;
-
-		let buf = &mut buf.limit(HEADER + (8 * keycodes_size));
-
-		// For each keycodes field, we want to make sure that they are written
-		// as the same length as the longest list. Fortunately, that is easy to
-		// do, because (a) the order of each list does not matter, and (b) a `0`
-		// means that position is simply ignored, so we can just fill the
-		// remaining positions with `0`s.
-
-		for field in [
-			&self.shift_keycodes,
-			&self.capslock_keycodes,
-			&self.ctrl_keycodes,
-			&self.mod1_keycodes,
-			&self.mod2_keycodes,
-			&self.mod3_keycodes,
-			&self.mod4_keycodes,
-			&self.mod5_keycodes,
-		] {
-			for index in 0..max_keycodes_len {
-				match field.get(index) {
-					Some(Keycode(code)) => buf.put_u8(*code),
-					None => buf.put_u8(0),
-				}
+	#[test]
+	fn test_fraction_from_f32() {
+		let pair = |value: f32| {
+			let fraction = Fraction::from(value);
+
+			match fraction.pair() {
+				(OrDefault::Other(Px(numerator)), OrDefault::Other(Px(denominator))) => {
+					(*numerator, *denominator)
+				},
+
+				_ => panic!("approximated fractions are never the default"),
 			}
+		};
+
+		assert_eq!(pair(2.0), (2, 1));
+		assert_eq!(pair(1.5), (3, 2));
+		assert_eq!(pair(0.25), (1, 4));
+		assert_eq!(pair(300.0), (255, 1));
+		assert_eq!(pair(-1.0), (0, 1));
+		assert_eq!(pair(f32::NAN), (0, 1));
+
+		// No fraction of numbers up to `255` is closer to pi than `245 / 78`,
+		// which is within `1e-3`.
+		let (numerator, denominator) = pair(std::f32::consts::PI);
+		assert!((f32::from(numerator) / f32::from(denominator) - std::f32::consts::PI).abs() < 1e-3);
+	}
+
+	#[test]
+	fn test_warp_source_dimension_round_trip_is_lossless() {
+		let dimensions = iter::once(WarpSourceDimension::FillRemaining)
+			.chain((1..=u16::MAX).map(|n| WarpSourceDimension::Other(NonZeroU16::new(n).unwrap())));
+
+		for dimension in dimensions {
+			let mut bytes = vec![];
+			dimension.write_to(&mut bytes).unwrap();
+
+			assert_eq!(
+				WarpSourceDimension::read_from(&mut &bytes[..]).unwrap(),
+				dimension
+			);
 		}
+	}
 
-		Ok(())
+	#[test]
+	#[allow(deprecated)]
+	fn test_grab_button_x11_compat() {
+		let root = Window::new(0x0000_0100);
+
+		// xcb_grab_button(conn, 0, root, XCB_EVENT_MASK_BUTTON_PRESS,
+		//                 XCB_GRAB_MODE_ASYNC, XCB_GRAB_MODE_ASYNC, XCB_NONE,
+		//                 XCB_NONE, 1, XCB_MOD_MASK_1);
+		let request = GrabButton::x11_compat(
+			false,
+			root,
+			CursorEventMask::BUTTON_PRESS,
+			FreezeMode::Unfrozen,
+			FreezeMode::Unfrozen,
+			0,
+			0,
+			Any::Other(Button::PRIMARY),
+			AnyModifierKeyMask::MOD_1,
+		);
+
+		assert_eq!(
+			request,
+			GrabButton {
+				owner_events: false,
+				grab_window: root,
+				event_mask: CursorEventMask::BUTTON_PRESS,
+				cursor_freeze: FreezeMode::Unfrozen,
+				keyboard_freeze: FreezeMode::Unfrozen,
+				confine_to: None,
+				cursor_appearance: None,
+				button: Any::Other(Button::PRIMARY),
+				modifiers: AnyModifierKeyMask::MOD_1,
+			},
+		);
+
+		let request = GrabButton::x11_compat(
+			true,
+			root,
+			CursorEventMask::empty(),
+			FreezeMode::Frozen,
+			FreezeMode::Unfrozen,
+			0x0040_0001,
+			0x0040_0002,
+			Any::Any,
+			AnyModifierKeyMask::ANY_MODIFIER,
+		);
+
+		assert_eq!(request.confine_to, Some(Window::new(0x0040_0001)));
+		assert_eq!(
+			request.cursor_appearance,
+			Some(CursorAppearance::new(0x0040_0002)),
+		);
 	}
-}
 
-derive_xrb! {
-	/// A [request] that returns the [keycodes] currently mapped to each
-	/// modifier.
-	///
-	/// Each modifier has zero or more [keycodes] mapped to it. For example, the
-	/// shift modifier typically has both <kbd>⇧ Left Shift</kbd> and
-	/// <kbd>⇧ Right Shift</kbd> keycodes mapped to it, while the caps lock
-	/// modifier only has one <kbd>Caps Lock</kbd> key mapped to it.
-	///
-	/// If no [keycodes] are mapped to a modifier, that modifier is disabled.
-	///
-	/// See also: [`SetModifierMapping`].
-	///
-	/// # Replies
-	/// This [request] generates a [`GetModifierMapping` reply].
-	///
-	/// [keycodes]: Keycode
-	/// [request]: Request
-	///
-	/// [`GetModifierMapping` reply]: reply::GetModifierMapping
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
-	pub struct GetModifierMapping: Request(119) -> reply::GetModifierMapping;
+	#[test]
+	fn test_ring_bell_bounds() {
+		for volume in [-100, 0, 100] {
+			assert_eq!(RingBell::new(volume).unwrap().volume, volume);
+		}
+
+		for volume in [i8::MIN, -101, 101, i8::MAX] {
+			assert_eq!(
+				RingBell::new(volume),
+				Err(ValueOutOfBounds {
+					min: -100,
+					max: 100,
+					found: volume,
+				}),
+			);
+		}
+
+		let mut bytes = vec![];
+		RingBell::new(-100).unwrap().write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, [104, -100_i8 as u8, 0, 1]);
+	}
 }
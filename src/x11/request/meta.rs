@@ -25,11 +25,11 @@ use xrbk::{
 	WriteResult,
 	X11Size,
 };
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
-	message::Request,
-	unit::Sec,
+	message::{write_request_length, LengthMode, Request, WritableRequest},
+	unit::{Sec, ValueOutOfBounds},
 	x11::{error, reply},
 	Host,
 	KillClientTarget,
@@ -39,37 +39,6 @@ use crate::{
 	Window,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum ChangeSavedWindowsError for ChangeSavedWindows {
 		Match,
@@ -79,7 +48,9 @@ request_error! {
 }
 
 /// Whether something is added or removed.
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddOrRemove {
 	/// The thing is added.
 	Add,
@@ -115,6 +86,7 @@ derive_xrb! {
 	/// [reparented]: super::ReparentWindow
 	#[doc(alias = "ChangeSaveSet")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeSavedWindows: Request(6, ChangeSavedWindowsError) {
 		#[metabyte]
 		/// Whether the `window` is added to or removed from your saved
@@ -150,6 +122,7 @@ derive_xrb! {
 	///
 	/// [`QueryExtension` reply]: reply::QueryExtension
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryExtension: Request(98) -> reply::QueryExtension {
 		// Length of `name`.
 		#[allow(clippy::cast_possible_truncation)]
@@ -160,6 +133,12 @@ derive_xrb! {
 		///
 		/// This name should use ISO Latin-1 encoding. Uppercase and lowercase
 		/// matter.
+		///
+		/// The names of commonly supported extensions are available in
+		/// [`known_extensions`], and a `QueryExtension` request can be created
+		/// from one with [`From`].
+		///
+		/// [`known_extensions`]: crate::known_extensions
 		#[context(name_len => usize::from(*name_len))]
 		pub name: String8,
 		[_; name => pad(name)],
@@ -174,14 +153,24 @@ derive_xrb! {
 	///
 	/// [`ListExtensions` reply]: reply::ListExtensions
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ListExtensions: Request(99) -> reply::ListExtensions;
 }
 
 /// The delay used for `timeout` and `interval` in the
 /// [`SetScreenSaver` request].
 ///
+/// This is encoded as an `i16`: `-1` means [`Default`], `0` means
+/// [`Disabled`], and any positive number of seconds means [`Enabled`]. Other
+/// negative values are invalid.
+///
+/// [`Default`]: Delay::Default
+/// [`Disabled`]: Delay::Disabled
+/// [`Enabled`]: Delay::Enabled
+///
 /// [`SetScreenSaver` request]: SetScreenSaver
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Delay {
 	/// The default option is used.
 	Default,
@@ -189,7 +178,41 @@ pub enum Delay {
 	Disabled,
 
 	/// The option is enabled after the given delay.
-	Enabled(Sec<u8>),
+	///
+	/// The delay must be no greater than [`i16::MAX`] seconds. A greater delay
+	/// is written as [`i16::MIN`], and so generates a [`Value` error].
+	///
+	/// [`Value` error]: error::Value
+	Enabled(Sec<u16>),
+}
+
+impl Delay {
+	/// Creates a new `Delay` from its encoded number of seconds.
+	///
+	/// `-1` is [`Default`], `0` is [`Disabled`], and any positive number of
+	/// seconds is [`Enabled`].
+	///
+	/// # Errors
+	/// Returns a [`ValueOutOfBounds`] error if `seconds < -1`.
+	///
+	/// [`Default`]: Delay::Default
+	/// [`Disabled`]: Delay::Disabled
+	/// [`Enabled`]: Delay::Enabled
+	pub const fn new(seconds: i16) -> Result<Self, ValueOutOfBounds<i16>> {
+		match seconds {
+			-1 => Ok(Self::Default),
+			0 => Ok(Self::Disabled),
+
+			#[allow(clippy::cast_sign_loss, reason = "`seconds` is positive")]
+			seconds if seconds > 0 => Ok(Self::Enabled(Sec(seconds as u16))),
+
+			other => Err(ValueOutOfBounds {
+				min: -1,
+				max: i16::MAX,
+				found: other,
+			}),
+		}
+	}
 }
 
 impl ConstantX11Size for Delay {
@@ -207,15 +230,7 @@ impl Readable for Delay {
 	where
 		Self: Sized,
 	{
-		match buf.get_i16() {
-			-1 => Ok(Self::Default),
-			0 => Ok(Self::Disabled),
-
-			other => match u8::try_from(other) {
-				Ok(sec) => Ok(Self::Enabled(Sec(sec))),
-				Err(error) => Err(FailedConversion(Box::new(error))),
-			},
-		}
+		Self::new(i16::read_from(buf)?).map_err(|error| FailedConversion(Box::new(error)))
 	}
 }
 
@@ -225,7 +240,9 @@ impl Writable for Delay {
 			Self::Default => buf.put_i16(-1),
 			Self::Disabled => buf.put_i16(0),
 
-			Self::Enabled(Sec(sec)) => i16::from(*sec).write_to(buf)?,
+			// A delay which is too long is written as a value which the X
+			// server rejects, rather than wrapping around to a valid one.
+			Self::Enabled(Sec(sec)) => i16::try_from(*sec).unwrap_or(i16::MIN).write_to(buf)?,
 		}
 
 		Ok(())
@@ -265,6 +282,7 @@ derive_xrb! {
 	///
 	/// [`Expose` events]: crate::x11::event::Expose
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetScreenSaver: Request(107, error::Value) {
 		/// Whether the screensaver is [`Enabled`] and, if so, how long without
 		/// input before it is activated.
@@ -300,9 +318,42 @@ derive_xrb! {
 	///
 	/// [`GetScreenSaver` reply]: reply::GetScreenSaver
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetScreenSaver: Request(108) -> reply::GetScreenSaver;
 }
 
+impl SetScreenSaver {
+	/// Creates a new `SetScreenSaver` request, with the `timeout` and
+	/// `interval` given as their encoded numbers of seconds.
+	///
+	/// See [`Delay::new`] for how the `timeout` and `interval` are
+	/// interpreted.
+	///
+	/// # Errors
+	/// Returns a [`ValueOutOfBounds`] error if `timeout < -1` or
+	/// `interval < -1`.
+	pub const fn new(
+		timeout: i16, interval: i16, prefer_blanking: ToggleOrDefault,
+		allow_expose_events: ToggleOrDefault,
+	) -> Result<Self, ValueOutOfBounds<i16>> {
+		let timeout = match Delay::new(timeout) {
+			Ok(timeout) => timeout,
+			Err(error) => return Err(error),
+		};
+		let interval = match Delay::new(interval) {
+			Ok(interval) => interval,
+			Err(error) => return Err(error),
+		};
+
+		Ok(Self {
+			timeout,
+			interval,
+			prefer_blanking,
+			allow_expose_events,
+		})
+	}
+}
+
 request_error! {
 	pub enum ChangeHostsError for ChangeHosts {
 		Access,
@@ -341,6 +392,7 @@ derive_xrb! {
 	/// [`Access` error]: error::Access
 	#[deprecated(note = "more secure forms of authentication are preferred.")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeHosts: Request(109, ChangeHostsError) {
 		/// Whether the `host` is to be [added] to or [removed] from the access
 		/// control list.
@@ -378,6 +430,7 @@ derive_xrb! {
 	#[doc(alias("ListHosts"))]
 	#[deprecated(note = "more secure forms of authentication are preferred.")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryAccessControl: Request(110) -> reply::QueryAccessControl;
 }
 
@@ -401,6 +454,7 @@ derive_xrb! {
 	/// [disabled]: Toggle::Disabled
 	#[deprecated(note = "more secure forms of authentication are preferred.")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetAccessControl: Request(111, SetAccessControlError) {
 		/// Whether access control is [enabled] or [disabled].
 		///
@@ -418,7 +472,9 @@ derive_xrb! {
 ///
 /// [`Destroy`]: RetainResourcesMode::Destroy
 #[doc(alias("CloseDownMode"))]
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RetainResourcesMode {
 	/// All of the client's resources are destroyed immediately.
 	///
@@ -464,6 +520,7 @@ derive_xrb! {
 	/// [request]: Request
 	#[doc(alias("SetCloseDownMode"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetRetainResourcesMode: Request(112, error::Value) {
 		/// The [`RetainResourcesMode`] set for your client.
 		///
@@ -487,6 +544,7 @@ derive_xrb! {
 	///
 	/// [request]: Request
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct KillClient: Request(113, error::Value) {
 		/// The target of this `KillClient` [request].
 		///
@@ -502,7 +560,9 @@ derive_xrb! {
 ///
 /// [resets the activation timer]: ForceScreenSaverMode::Reset
 /// [activates the screensaver]: ForceScreenSaverMode::Activate
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ForceScreenSaverMode {
 	/// If the screensaver is currently [enabled], the activation timer (i.e.
 	/// the time left before its activation) is reset and, if the screensaver is
@@ -531,7 +591,8 @@ derive_xrb! {
 	///
 	/// [reset]: ForceScreenSaverMode::Reset
 	/// [activate]: ForceScreenSaverMode::Activate
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ForceScreenSaver: Request(115, error::Value) {
 		/// Whether the screensaver's [activation timer is reset] or the
 		/// screensaver is [forcibly activated].
@@ -555,18 +616,23 @@ derive_xrb! {
 ///
 /// [request]: Request
 #[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NoOp {
 	/// The number of unused 4-byte units to add to the [request] after the
 	/// initial 4-byte header.
 	pub unused_units: u16,
 }
 
+impl crate::message::__private::Sealed for NoOp {}
+crate::message::register_message!(Request NoOp);
+
 impl Request for NoOp {
 	type OtherErrors = Infallible;
 	type Reply = ();
 
 	const MAJOR_OPCODE: u8 = 127;
 	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = false;
 }
 
 impl X11Size for NoOp {
@@ -583,12 +649,12 @@ impl Readable for NoOp {
 		const ALIGNMENT: usize = 4;
 
 		// Unused metabyte.
-		buf.advance(1);
+		xrbk::skip(buf, 1)?;
 
 		// One unit is subtracted for the header.
-		let unused_units = buf.get_u16() - 1;
+		let unused_units = u16::read_from(buf)?.saturating_sub(1);
 
-		let buf = &mut buf.take(usize::from(unused_units) * ALIGNMENT);
+		let buf = &mut xrbk::take(buf, usize::from(unused_units) * ALIGNMENT);
 		// Unused bytes.
 		buf.advance(buf.remaining());
 
@@ -598,15 +664,19 @@ impl Readable for NoOp {
 
 impl Writable for NoOp {
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
-		const ALIGNMENT: usize = 4;
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
 
-		let buf = &mut buf.limit(self.x11_size());
+impl WritableRequest for NoOp {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		const ALIGNMENT: usize = 4;
 
 		Self::MAJOR_OPCODE.write_to(buf)?;
 		// Unused metabyte.
 		buf.put_u8(0);
 		// Message length.
-		self.length().write_to(buf)?;
+		write_request_length(buf, self.x11_size(), mode)?;
 
 		// Unused bytes.
 		buf.put_bytes(0, usize::from(self.unused_units) * ALIGNMENT);
@@ -614,3 +684,125 @@ impl Writable for NoOp {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use xrbk::ReadError;
+
+	#[test]
+	fn test_query_extension_truncated() {
+		let request = QueryExtension {
+			name: String8::from_latin1("XKEYBOARD").unwrap(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		// The major opcode is not read by `read_from`.
+		let bytes = &bytes[1..];
+		assert_eq!(QueryExtension::read_from(&mut &bytes[..]).unwrap(), request);
+
+		for len in 0..bytes.len() {
+			assert!(
+				matches!(
+					QueryExtension::read_from(&mut &bytes[..len]),
+					Err(ReadError::UnexpectedEndOfData { .. }),
+				),
+				"reading {len} of {} bytes did not fail",
+				bytes.len(),
+			);
+		}
+	}
+
+	#[test]
+	fn test_query_extension_length() {
+		use crate::known_extensions::{BIG_REQUESTS, DRI3, GLX, RANDR, XC_MISC};
+
+		// The 8-byte header, followed by the name padded to a multiple of 4
+		// bytes.
+		for (name, length) in [(GLX, 3), (DRI3, 3), (RANDR, 4), (XC_MISC, 4), (BIG_REQUESTS, 5)] {
+			let request = QueryExtension::from(name);
+
+			let mut bytes = vec![];
+			request.write_to(&mut bytes).unwrap();
+
+			assert_eq!(request.length(), Ok(length), "length of {name:?}");
+			assert_eq!(bytes.len(), usize::from(length) * 4, "size of {name:?}");
+			assert_eq!(bytes[2..4], length.to_be_bytes());
+			assert_eq!(bytes[4..6], u16::try_from(name.len()).unwrap().to_be_bytes());
+			assert_eq!(&bytes[8..8 + name.len()], name.as_bytes());
+
+			// The padding is zeroed.
+			assert!(bytes[8 + name.len()..].iter().all(|&byte| byte == 0));
+		}
+	}
+
+	#[test]
+	fn test_delay_bounds() {
+		assert_eq!(Delay::new(-1), Ok(Delay::Default));
+		assert_eq!(Delay::new(0), Ok(Delay::Disabled));
+		assert_eq!(Delay::new(1), Ok(Delay::Enabled(Sec(1))));
+		assert_eq!(Delay::new(i16::MAX), Ok(Delay::Enabled(Sec(32767))));
+
+		for seconds in [-2, i16::MIN] {
+			assert_eq!(
+				Delay::new(seconds),
+				Err(ValueOutOfBounds {
+					min: -1,
+					max: i16::MAX,
+					found: seconds,
+				}),
+			);
+		}
+	}
+
+	#[test]
+	fn test_set_screen_saver() {
+		// The X server's default timeout of 600 seconds.
+		let request =
+			SetScreenSaver::new(600, -1, ToggleOrDefault::Default, ToggleOrDefault::Enabled)
+				.unwrap();
+
+		assert_eq!(request.timeout, Delay::Enabled(Sec(600)));
+		assert_eq!(request.interval, Delay::Default);
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				107, 0, 0, 3,
+				0x02, 0x58, // timeout
+				0xff, 0xff, // interval
+				2, 1, 0, 0,
+			]
+		);
+
+		// The major opcode is not read by `read_from`.
+		assert_eq!(
+			SetScreenSaver::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+
+		bytes[6..8].copy_from_slice(&(-2_i16).to_be_bytes());
+		let error = SetScreenSaver::read_from(&mut &bytes[1..]).unwrap_err();
+		assert!(matches!(error.root_cause(), ReadError::FailedConversion(_),));
+
+		assert!(
+			SetScreenSaver::new(-2, 0, ToggleOrDefault::Default, ToggleOrDefault::Default).is_err()
+		);
+	}
+
+	#[test]
+	fn test_delay_too_long() {
+		// A delay which cannot be represented is written as an invalid value,
+		// rather than `-1`.
+		let mut bytes = vec![];
+		Delay::Enabled(Sec(u16::MAX)).write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes, i16::MIN.to_be_bytes());
+	}
+}
@@ -8,7 +8,7 @@
 //! [Requests] are messages sent from an X client to the X server.
 //!
 //! [atoms]: Atom
-//! [events]: Event
+//! [events]: crate::message::Event
 //! [Requests]: Request
 //! [core X11 protocol]: crate::x11
 
@@ -22,17 +22,23 @@ use xrbk::{
 	ReadError,
 	ReadError::UnrecognizedDiscriminant,
 	ReadResult,
+	Readable,
 	ReadableWithContext,
 	Wrap,
 	Writable,
 	WriteResult,
 	X11Size,
+	ZeroIsNone,
 };
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
-	message::{Event, Request},
-	x11::{error, reply},
+	message::{write_request_length, LengthMode, Request, WritableRequest},
+	x11::{
+		error,
+		event::{AnyEvent, SendableEvent},
+		reply,
+	},
 	Any,
 	Atom,
 	CurrentableTime,
@@ -42,37 +48,6 @@ use crate::{
 	Window,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 derive_xrb! {
 	/// A [request] that returns the [atom] with the given `name`.
 	///
@@ -90,6 +65,7 @@ derive_xrb! {
 	/// [`GetAtom` reply]: reply::GetAtom
 	#[doc(alias("InternAtom", "CreateAtom"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetAtom: Request(16, error::Value) -> reply::GetAtom {
 		#[metabyte]
 		/// Whether the X server should avoid creating a new [atom] for an
@@ -139,6 +115,7 @@ derive_xrb! {
 	///
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetAtomName: Request(17, error::Atom) -> reply::GetAtomName {
 		/// The [atom] for which this [request] gets its name.
 		///
@@ -173,7 +150,10 @@ request_error! {
 ///
 /// [window]: Window
 #[doc(alias = "ChangePropertyMode")]
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ModifyPropertyMode {
 	/// The property replaces an existing property; the previous value is
 	/// discarded.
@@ -189,6 +169,9 @@ pub enum ModifyPropertyMode {
 /// Whether a [`DataList`] is formatted as a list of `i8` values, `i16` values,
 /// or `i32` values.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataFormat {
 	/// The list is formatted as `i8` values.
 	I8 = 8,
@@ -204,8 +187,17 @@ impl ConstantX11Size for DataFormat {
 
 impl Wrap for DataFormat {
 	type Integer = u8;
+	type Error = ReadError;
+
+	fn try_wrap(integer: u8) -> ReadResult<Self> {
+		Self::try_from(integer)
+	}
 }
 
+// There is no `DataFormat` with a discriminant of zero, so zero is used to
+// encode `None`.
+impl ZeroIsNone for DataFormat {}
+
 impl TryFrom<u8> for DataFormat {
 	type Error = ReadError;
 
@@ -215,7 +207,10 @@ impl TryFrom<u8> for DataFormat {
 			i16 if i16 == 16 => Ok(Self::I16),
 			i32 if i32 == 32 => Ok(Self::I32),
 
-			other => Err(UnrecognizedDiscriminant(usize::from(other))),
+			other => Err(UnrecognizedDiscriminant {
+				type_name: "DataFormat",
+				discriminant: usize::from(other),
+			}),
 		}
 	}
 }
@@ -234,6 +229,9 @@ impl From<DataFormat> for u8 {
 ///
 /// This represents uninterpreted 'raw' data.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataList {
 	/// A list of `i8` values.
 	I8(Vec<i8>),
@@ -265,6 +263,67 @@ impl DataList {
 			Self::I32(list) => list.is_empty(),
 		}
 	}
+
+	/// The [`DataFormat`] of the values in this `DataList`.
+	#[must_use]
+	pub const fn format(&self) -> DataFormat {
+		match self {
+			Self::I8(_) => DataFormat::I8,
+			Self::I16(_) => DataFormat::I16,
+			Self::I32(_) => DataFormat::I32,
+		}
+	}
+
+	/// Encodes the given `string` in UTF-8 as a list of `i8` values.
+	///
+	/// This is the format of properties of type `UTF8_STRING`, which can hold
+	/// any text. That [atom] is not predefined, so it must be interned before
+	/// it can be used as the property's type.
+	///
+	/// Properties of type [`STRING`] are encoded in ISO Latin-1 instead: a
+	/// [`String8`] can be converted to a `DataList` for those.
+	///
+	/// [atom]: Atom
+	/// [`STRING`]: crate::atom::STRING
+	#[must_use]
+	pub fn from_utf8(string: &str) -> Self {
+		Self::I8(
+			string
+				.bytes()
+				.map(|byte| i8::from_be_bytes([byte]))
+				.collect(),
+		)
+	}
+
+	/// Decodes this `DataList` as a UTF-8 string.
+	///
+	/// Returns [`None`] if this is not a list of `i8` values, or if they are
+	/// not valid UTF-8.
+	#[must_use]
+	pub fn to_utf8_string(&self) -> Option<String> {
+		match self {
+			Self::I8(list) => {
+				String::from_utf8(list.iter().map(|byte| byte.to_be_bytes()[0]).collect()).ok()
+			},
+
+			_ => None,
+		}
+	}
+}
+
+impl From<String8> for DataList {
+	/// Converts the `string` to a list of `i8` values, the format of properties
+	/// of type [`STRING`].
+	///
+	/// [`STRING`]: crate::atom::STRING
+	fn from(string: String8) -> Self {
+		Self::I8(
+			Vec::from(string)
+				.into_iter()
+				.map(|char| i8::from_be_bytes([char.unwrap()]))
+				.collect(),
+		)
+	}
 }
 
 impl X11Size for DataList {
@@ -335,6 +394,8 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	#[doc(alias = "ChangeProperty")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ModifyProperty: Request(18, ModifyPropertyError) {
 		#[metabyte]
 		/// The way in which the property is modified.
@@ -400,11 +461,7 @@ derive_xrb! {
 
 		// Whether the `data` is formatted as `i8` values, `i16` values, or
 		// `i32` values.
-		let format: DataFormat = data => match data {
-			DataList::I8(_) => DataFormat::I8,
-			DataList::I16(_) => DataFormat::I16,
-			DataList::I32(_) => DataFormat::I32,
-		},
+		let format: DataFormat = data => data.format(),
 		[_; 3],
 
 		// The length of `data` in number of values (i.e., an `i32` value is
@@ -414,9 +471,14 @@ derive_xrb! {
 
 		/// The property's value.
 		///
-		/// See [`DataList`] for information on the format of this data.
+		/// See [`DataList`] for information on the format of this data. The
+		/// format and the number of values are sent as part of the [request],
+		/// so they always match the `data`.
+		///
+		/// [request]: Request
 		#[context(format, data_len => (*format, *data_len))]
 		pub data: DataList,
+		[_; data => pad(data)],
 	}
 }
 
@@ -449,6 +511,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DeleteProperty: Request(19, DeletePropertyError) {
 		/// The [window] for which this [request] removes the `property`.
 		///
@@ -508,6 +572,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetProperty: Request(20, GetPropertyError) -> reply::GetProperty {
 		/// Whether the `property` should be deleted from the `target` [window].
 		///
@@ -586,6 +652,8 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ListProperties: Request(21, error::Window) -> reply::ListProperties {
 		/// The [window] for which this [request] returns its properties.
 		///
@@ -639,6 +707,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct SetSelectionOwner: Request(22, SetSelectionOwnerError) {
 		/// Sets the new owner of the `selection`.
 		///
@@ -693,6 +763,8 @@ derive_xrb! {
 	///
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetSelectionOwner: Request(23) -> reply::GetSelectionOwner {
 		/// The selection for which this [request] returns its owner.
 		///
@@ -733,6 +805,8 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Atom` error]: error::Atom
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ConvertSelection: Request(24, ConvertSelectionError) {
 		/// Your [window] which is requesting this conversion.
 		///
@@ -767,6 +841,19 @@ derive_xrb! {
 		///
 		/// [`Atom` error]: error::Atom
 		pub target_type: Atom,
+		/// The property on the `requester` in which the owner should place the
+		/// converted selection.
+		///
+		/// [`None`] is used by obsolete clients: the owner should then use the
+		/// `target_type` as the name of the property.
+		///
+		/// # Errors
+		/// An [`Atom` error] is generated if this is [`Some`] but does not
+		/// refer to a defined [atom].
+		///
+		/// [atom]: Atom
+		///
+		/// [`Atom` error]: error::Atom
 		pub property: Option<Atom>,
 
 		/// The [time] at which this conversion is recorded as having taken
@@ -784,75 +871,173 @@ request_error! {
 	}
 }
 
-derive_xrb! {
-	/// A [request] that sends the given [event] to the given [window].
-	///
-	/// If the `event_mask` is empty, the [event] is sent to the client that
-	/// created the [window] - if that client no longer exists, the [event] is
-	/// not sent.
-	///
-	/// If `propagate` is `false`, the [event] is sent to every client selecting
-	/// any of the [events][event] indicated in the `event_mask`.
-	///
-	/// If `propagate` is `true` and no clients have selected any of the
-	/// [events][event] indicated in the `event_mask` on the [window], the
-	/// [event] is sent to the closest ancestor [window] of the [window] which
-	/// some client has selected at least one of the indicated [events][event]
-	/// for (provided no [windows][window] between the original destination and
-	/// the closest ancestor have that [event] in their
-	/// [`do_not_propagate_mask`]). The [event] is sent to every client
-	/// selecting any of the [events][event] indicated in the `event_mask` on
-	/// the final destination.
-	///
-	/// Active grabs are ignored for this [request].
+/// The size of the block of bytes in which the `event` of a [`SendEvent`
+/// request] is sent.
+///
+/// [`SendEvent` request]: SendEvent
+const SENT_EVENT_SIZE: usize = 32;
+
+/// A [request] that sends the given [event] to the given [window].
+///
+/// If the `event_mask` is empty, the [event] is sent to the client that
+/// created the [window] - if that client no longer exists, the [event] is
+/// not sent.
+///
+/// If `propagate` is `false`, the [event] is sent to every client selecting
+/// any of the [events][event] indicated in the `event_mask`.
+///
+/// If `propagate` is `true` and no clients have selected any of the
+/// [events][event] indicated in the `event_mask` on the [window], the
+/// [event] is sent to the closest ancestor [window] of the [window] which
+/// some client has selected at least one of the indicated [events][event]
+/// for (provided no [windows][window] between the original destination and
+/// the closest ancestor have that [event] in their
+/// [`do_not_propagate_mask`]). The [event] is sent to every client
+/// selecting any of the [events][event] indicated in the `event_mask` on
+/// the final destination.
+///
+/// Active grabs are ignored for this [request].
+///
+/// The [event] is sent in a block of 32 bytes, including its code. Any type of
+/// [event] may be sent, but an [event] larger than 32 bytes causes a
+/// compilation error when this [request] is written. A `SendEvent` request
+/// containing [`AnyEvent`] - the default - can be read without knowing the
+/// type of [event] it contains.
+///
+/// # Errors
+/// A [`Window` error] is generated if the `destination` is
+/// [`DestinationWindow::Other`] and the specified [window] is not defined.
+///
+/// [window]: Window
+/// [event]: crate::message::Event
+/// [request]: Request
+///
+/// [`do_not_propagate_mask`]: crate::set::Attributes::do_not_propagate_mask
+///
+/// [`Window` error]: error::Window
+#[derive(Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SendEvent<E = AnyEvent> {
+	/// Whether the `event` should be propagated to the closest appropriate
+	/// ancestor, if necessary.
+	///
+	/// That is, whether the `event` should be propagated to the closest
+	/// ancestor of the `destination` [window] which some client has
+	/// selected any of the [events] indicated in the `event_mask` on if no
+	/// clients have selected any of the [events] in the `event_mask` on the
+	/// `destination` [window].
 	///
-	/// # Errors
-	/// A [`Window` error] is generated if the `destination` is [`DestinationWindow::Other`] and the
-	/// specified [window] is not defined.
+	/// [window]: Window
+	/// [events]: crate::message::Event
+	pub propagate: bool,
+
+	/// The destination [window] for the `event`.
 	///
 	/// [window]: Window
-	/// [event]: Event
-	/// [request]: Request
+	pub destination: DestinationWindow,
+
+	/// The mask of [events][event] which should be selected for the [event]
+	/// to be sent to the selecting clients.
 	///
-	/// [`do_not_propagate_mask`]: crate::set::Attributes::do_not_propagate_mask
+	/// [event]: crate::message::Event
+	pub event_mask: EventMask,
+
+	/// The [event] that is sent.
 	///
-	/// [`Window` error]: error::Window
-	// FIXME: this requires that the event is absolutely 32 bytes, which is
-	//        currently not bounded.
-	//
-	// This feature would be nice for this:
-	// <https://github.com/rust-lang/rust/issues/92827>
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
-	pub struct SendEvent<E: Event + ConstantX11Size>: Request(25, SendEventError) {
-		/// Whether the `event` should be propagated to the closest appropriate
-		/// ancestor, if necessary.
-		///
-		/// That is, whether the `event` should be propagated to the closest
-		/// ancestor of the `destination` [window] which some client has
-		/// selected any of the [events] indicated in the `event_mask` on if no
-		/// clients have selected any of the [events] in the `event_mask` on the
-		/// `destination` [window].
-		///
-		/// [window]: Window
-		/// [events]: Event
-		#[metabyte]
-		pub propagate: bool,
+	/// [event]: crate::message::Event
+	pub event: E,
+}
 
-		/// The destination [window] for the `event`.
-		///
-		/// [window]: Window
-		pub destination: DestinationWindow,
+impl<E: SendableEvent> SendEvent<E> {
+	/// Referencing this associated `const` causes a compilation error if the
+	/// `event` does not fit in the block of bytes in which it is sent.
+	const EVENT_FITS: () = assert!(
+		E::X11_SIZE <= SENT_EVENT_SIZE,
+		"events sent in a `SendEvent` request must be no larger than 32 bytes",
+	);
+}
 
-		/// The mask of [events][event] which should be selected for the [event]
-		/// to be sent to the selecting clients.
-		///
-		/// [event]: Event
-		pub event_mask: EventMask,
+impl<E: SendableEvent> crate::message::__private::Sealed for SendEvent<E> {}
+crate::message::register_message!(Request SendEvent);
 
-		/// The [event] that is sent.
-		///
-		/// [event]: Event
-		pub event: E,
+impl<E: SendableEvent> Request for SendEvent<E> {
+	type OtherErrors = SendEventError;
+	type Reply = ();
+
+	const MAJOR_OPCODE: u8 = 25;
+	const MINOR_OPCODE: Option<u16> = None;
+	const HAS_REPLY: bool = false;
+}
+
+impl<E: SendableEvent> ConstantX11Size for SendEvent<E> {
+	const X11_SIZE: usize = {
+		const HEADER: usize = 4;
+
+		HEADER + DestinationWindow::X11_SIZE + EventMask::X11_SIZE + SENT_EVENT_SIZE
+	};
+}
+
+impl<E: SendableEvent> X11Size for SendEvent<E> {
+	fn x11_size(&self) -> usize {
+		Self::X11_SIZE
+	}
+}
+
+impl<E: SendableEvent> Readable for SendEvent<E> {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		const HEADER: usize = 4;
+
+		let propagate = bool::read_from(buf)?;
+
+		// The message length.
+		let length = usize::from(u16::read_from(buf)?) * 4;
+		xrbk::ensure_remaining(buf, length.saturating_sub(HEADER))?;
+		let buf = &mut xrbk::take(buf, length.saturating_sub(HEADER));
+
+		let destination = DestinationWindow::read_from(buf)?;
+		let event_mask = EventMask::read_from(buf)?;
+
+		let event_buf = &mut xrbk::take(buf, SENT_EVENT_SIZE);
+		let event = E::read_sent(event_buf)?;
+		// Skip any padding after the event.
+		xrbk::skip(event_buf, event_buf.remaining())?;
+
+		Ok(Self {
+			propagate,
+			destination,
+			event_mask,
+			event,
+		})
+	}
+}
+
+impl<E: SendableEvent> Writable for SendEvent<E> {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		self.write_to_with(buf, LengthMode::Standard)
+	}
+}
+
+impl<E: SendableEvent> WritableRequest for SendEvent<E> {
+	fn write_to_with(&self, buf: &mut impl BufMut, mode: LengthMode) -> WriteResult {
+		#[allow(clippy::let_unit_value)]
+		let () = Self::EVENT_FITS;
+
+		Self::MAJOR_OPCODE.write_to(buf)?;
+		self.propagate.write_to(buf)?;
+		// The message length.
+		write_request_length(buf, self.x11_size(), mode)?;
+
+		self.destination.write_to(buf)?;
+		self.event_mask.write_to(buf)?;
+
+		self.event.write_to(buf)?;
+		// Pad the event to 32 bytes.
+		buf.put_bytes(0, SENT_EVENT_SIZE - E::X11_SIZE);
+
+		Ok(())
 	}
 }
 
@@ -914,7 +1099,9 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Atom` error]: error::Atom
 	/// [`Match` error]: error::Match
-	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct RotateProperties: Request(114, RotatePropertiesError) {
 		/// The [window] for which the given `properties` are rotated.
 		///
@@ -960,3 +1147,340 @@ derive_xrb! {
 		pub properties: Vec<Atom>,
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		arbitrary::round_trip_tests,
+		atom,
+		unit::Px,
+		x11::{
+			event::{KeyPress, Property, PropertyChange},
+			registry::lookup_request,
+		},
+		Coords,
+		Keycode,
+		ModifierMask,
+		Timestamp,
+	};
+	use xrbk::ByteOrder;
+
+	round_trip_tests! {
+		ModifyProperty => test_modify_property_round_trip,
+		DeleteProperty => test_delete_property_round_trip,
+		GetProperty => test_get_property_round_trip,
+		ListProperties => test_list_properties_round_trip,
+		SetSelectionOwner => test_set_selection_owner_round_trip,
+		GetSelectionOwner => test_get_selection_owner_round_trip,
+		ConvertSelection => test_convert_selection_round_trip,
+		RotateProperties => test_rotate_properties_round_trip,
+	}
+
+	fn modify_property(data: DataList) -> ModifyProperty {
+		ModifyProperty {
+			modify_mode: ModifyPropertyMode::Append,
+			target: Window::new(0x0040_0001),
+			property: atom::WM_NAME,
+			r#type: atom::STRING,
+			data,
+		}
+	}
+
+	#[test]
+	fn test_text_property_data() {
+		let latin1 = DataList::from(String8::from_latin1("café").unwrap());
+		let utf8 = DataList::from_utf8("café 🎉");
+
+		let mut bytes = vec![];
+		modify_property(latin1).write_to(&mut bytes).unwrap();
+		assert_eq!(bytes[24..28], [b'c', b'a', b'f', 0xe9]);
+
+		let mut bytes = vec![];
+		modify_property(utf8.clone()).write_to(&mut bytes).unwrap();
+		assert_eq!(bytes[20..24], 10u32.to_be_bytes());
+		assert_eq!(bytes[24..34], *"café 🎉".as_bytes());
+
+		assert_eq!(utf8.to_utf8_string().as_deref(), Some("café 🎉"));
+		assert_eq!(DataList::I32(vec![]).to_utf8_string(), None);
+	}
+
+	#[test]
+	fn test_modify_property_layout() {
+		#[rustfmt::skip]
+		let cases = [
+			// Three 1-byte values, padded by one byte.
+			(DataList::I8(vec![b'x' as i8, b'r' as i8, b'b' as i8]), 8, 3, vec![b'x', b'r', b'b', 0]),
+			// One 2-byte value, padded by two bytes.
+			(DataList::I16(vec![-2]), 16, 1, vec![0xff, 0xfe, 0, 0]),
+			// Two 4-byte values, which need no padding.
+			(DataList::I32(vec![1, -1]), 32, 2, vec![0, 0, 0, 1, 0xff, 0xff, 0xff, 0xff]),
+			// No values at all.
+			(DataList::I32(vec![]), 32, 0, vec![]),
+		];
+
+		for (data, format, data_len, data_bytes) in cases {
+			let request = modify_property(data);
+
+			let mut bytes = vec![];
+			request.write_to(&mut bytes).unwrap();
+
+			assert_eq!(bytes.len(), 24 + data_bytes.len());
+			assert_eq!(bytes.len(), request.x11_size());
+
+			#[rustfmt::skip]
+			assert_eq!(
+				bytes[..24],
+				[
+					// Major opcode, `modify_mode`, and length.
+					18, 2, 0, (bytes.len() / 4) as u8,
+					// `target`
+					0x00, 0x40, 0x00, 0x01,
+					// `property`
+					0, 0, 0, 39,
+					// `type`
+					0, 0, 0, 31,
+					// `format` and 3 unused bytes.
+					format, 0, 0, 0,
+					// The number of values in `data`.
+					0, 0, 0, data_len,
+				],
+			);
+			assert_eq!(bytes[24..], data_bytes);
+
+			assert_eq!(
+				ModifyProperty::read_from(&mut &bytes[1..]).unwrap(),
+				request
+			);
+		}
+	}
+
+	#[test]
+	fn test_get_property_any_type() {
+		let request = GetProperty {
+			delete: true,
+			target: Window::new(0x0040_0001),
+			property: atom::WM_NAME,
+			r#type: Any::Any,
+			offset: 0,
+			length: u32::MAX,
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				// Major opcode, `delete`, and length.
+				20, 1, 0, 6,
+				// `target`
+				0x00, 0x40, 0x00, 0x01,
+				// `property`
+				0, 0, 0, 39,
+				// `type`: `AnyPropertyType` is zero.
+				0, 0, 0, 0,
+				// `offset`
+				0, 0, 0, 0,
+				// `length`
+				0xff, 0xff, 0xff, 0xff,
+			],
+		);
+		assert_eq!(GetProperty::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_convert_selection_layout() {
+		let request = ConvertSelection {
+			requester: Window::new(0x0040_0001),
+			selection: atom::PRIMARY,
+			target_type: atom::STRING,
+			property: Some(Atom::new(0x01b3)),
+			time: CurrentableTime::CurrentTime,
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				// Major opcode, unused byte, and length.
+				24, 0, 0, 6,
+				// `requester`
+				0x00, 0x40, 0x00, 0x01,
+				// `selection`
+				0, 0, 0, 1,
+				// `target_type`
+				0, 0, 0, 31,
+				// `property`
+				0, 0, 0x01, 0xb3,
+				// `time`
+				0, 0, 0, 0,
+			],
+		);
+		assert_eq!(
+			ConvertSelection::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+	}
+
+	#[test]
+	fn test_rotate_properties_layout() {
+		#[rustfmt::skip]
+		let cases = [
+			// No properties at all.
+			(vec![], vec![]),
+			// Three properties, each written as 4 bytes.
+			(
+				vec![atom::WM_NAME, atom::WM_ICON_NAME, Atom::new(0x01b3)],
+				vec![0, 0, 0, 39, 0, 0, 0, 37, 0, 0, 0x01, 0xb3],
+			),
+		];
+
+		for (properties, properties_bytes) in cases {
+			let request = RotateProperties {
+				target: Window::new(0x0040_0001),
+				shift: -2,
+				properties,
+			};
+
+			let mut bytes = vec![];
+			request.write_to(&mut bytes).unwrap();
+
+			assert_eq!(bytes.len(), 12 + properties_bytes.len());
+			assert_eq!(bytes.len(), request.x11_size());
+
+			#[rustfmt::skip]
+			assert_eq!(
+				bytes[..12],
+				[
+					// Major opcode, unused byte, and length.
+					114, 0, 0, (bytes.len() / 4) as u8,
+					// `target`
+					0x00, 0x40, 0x00, 0x01,
+					// The number of `properties`, then `shift`.
+					0, (properties_bytes.len() / 4) as u8, 0xff, 0xfe,
+				],
+			);
+			assert_eq!(bytes[12..], properties_bytes);
+
+			assert_eq!(
+				RotateProperties::read_from(&mut &bytes[1..]).unwrap(),
+				request
+			);
+		}
+	}
+
+	fn key_press() -> KeyPress {
+		KeyPress {
+			sequence: 0,
+			keycode: Keycode::new(38),
+			time: Timestamp::new(0x0a0b_0c0d),
+			root: Window::new(0x0000_0539),
+			event_window: Window::new(0x0040_0001),
+			child_window: None,
+			root_coords: Coords::new(Px(100), Px(200)),
+			event_coords: Coords::new(Px(10), Px(20)),
+			modifiers: ModifierMask::SHIFT,
+			same_screen: true,
+		}
+	}
+
+	fn property() -> Property {
+		Property {
+			sequence: 0,
+			window: Window::new(0x0040_0001),
+			property: atom::WM_NAME,
+			time: Timestamp::new(0x0a0b_0c0d),
+			change: PropertyChange::Modified,
+		}
+	}
+
+	#[test]
+	fn test_send_event_key_press() {
+		let request = SendEvent {
+			propagate: true,
+			destination: DestinationWindow::Focus,
+			event_mask: EventMask::KEY_PRESS,
+			event: key_press(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 44);
+		// Major opcode, `propagate`, and length.
+		assert_eq!(bytes[..4], [25, 1, 0, 11]);
+		// `destination`
+		assert_eq!(bytes[4..8], [0, 0, 0, 1]);
+		// `event_mask`
+		assert_eq!(bytes[8..12], [0, 0, 0, 1]);
+
+		let mut event = vec![];
+		key_press().write_to(&mut event).unwrap();
+		assert_eq!(bytes[12..], event);
+
+		assert_eq!(
+			<SendEvent<KeyPress>>::read_from(&mut &bytes[1..]).unwrap(),
+			request,
+		);
+	}
+
+	#[test]
+	fn test_send_event_any_event() {
+		let request = SendEvent {
+			propagate: false,
+			destination: DestinationWindow::Other(Window::new(0x0040_0001)),
+			event_mask: EventMask::PROPERTY_CHANGE,
+			event: property(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), 44);
+
+		// The event is recovered by its code.
+		let read = SendEvent::read_from(&mut &bytes[1..]).unwrap();
+		assert!(!read.propagate);
+		assert_eq!(read.destination, request.destination);
+		assert_eq!(read.event_mask, request.event_mask);
+		assert!(matches!(read.event, AnyEvent::Property(event) if event == property()));
+
+		// Reading the event as the wrong type fails.
+		assert!(matches!(
+			<SendEvent<KeyPress>>::read_from(&mut &bytes[1..]),
+			Err(ReadError::UnrecognizedDiscriminant {
+				discriminant: 28,
+				..
+			}),
+		));
+
+		let descriptor = lookup_request(25, None).unwrap();
+		let read = (descriptor.read.unwrap())(&mut &bytes[1..]).unwrap();
+		assert!(read.downcast_ref::<SendEvent>().is_some());
+	}
+
+	#[test]
+	fn test_send_event_any_event_little_endian() {
+		let request = SendEvent {
+			propagate: false,
+			destination: DestinationWindow::Other(Window::new(0x0040_0001)),
+			event_mask: EventMask::PROPERTY_CHANGE,
+			event: property(),
+		};
+
+		let mut bytes = vec![];
+		request
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		// The event's `window`.
+		assert_eq!(bytes[16..20], [0x01, 0, 0x40, 0]);
+
+		let read = SendEvent::read_from_ordered(&mut &bytes[1..], ByteOrder::LittleEndian).unwrap();
+		assert_eq!(read.destination, request.destination);
+		assert!(matches!(read.event, AnyEvent::Property(event) if event == property()));
+	}
+}
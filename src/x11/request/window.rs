@@ -13,7 +13,7 @@
 
 extern crate self as xrb;
 
-use xrbk_macro::{derive_xrb, Readable, Writable, X11Size};
+use xrbk_macro::{derive_xrb, ConstantX11Size, Readable, Writable, X11Size};
 
 use crate::{
 	message::Request,
@@ -29,37 +29,6 @@ use crate::{
 	WindowClass,
 };
 
-macro_rules! request_error {
-	(
-		$(#[$meta:meta])*
-		$vis:vis enum $Name:ident for $Request:ty {
-			$($($Error:ident),+$(,)?)?
-		}
-	) => {
-		#[doc = concat!(
-			"An [error](crate::message::Error) generated because of a failed [`",
-			stringify!($Request),
-			"` request](",
-			stringify!($Request),
-			")."
-		)]
-		#[doc = ""]
-		$(#[$meta])*
-		$vis enum $Name {
-			$($(
-				#[doc = concat!(
-					"A [`",
-					stringify!($Error),
-					"` error](error::",
-					stringify!($Error),
-					")."
-				)]
-				$Error(error::$Error)
-			),+)?
-		}
-	};
-}
-
 request_error! {
 	pub enum CreateWindowError for CreateWindow {
 		Colormap,
@@ -78,6 +47,7 @@ derive_xrb! {
 	/// [request]: Request
 	/// [window]: Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CreateWindow: Request(1, CreateWindowError) {
 		#[metabyte]
 		/// The [window]'s depth.
@@ -211,6 +181,7 @@ derive_xrb! {
 	/// [`RESIZE_REDIRECT`]: crate::EventMask::RESIZE_REDIRECT
 	/// [`BUTTON_PRESS`]: crate::EventMask::BUTTON_PRESS
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ChangeWindowAttributes: Request(2, ChangeWindowAttributesError) {
 		/// The [window] which the `attributes` are changed on.
 		///
@@ -231,6 +202,7 @@ derive_xrb! {
 	/// [attributes]: Attributes
 	/// [window]: Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetWindowAttributes: Request(3, error::Window) -> reply::GetWindowAttributes {
 		/// The [window] for which this [request] gets the [attributes].
 		///
@@ -264,6 +236,7 @@ derive_xrb! {
 	/// [`UnmapWindow` request]: UnmapWindow
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyWindow: Request(4, error::Window) {
 		/// The [window] which is the target of the `DestroyWindow` [request].
 		///
@@ -296,6 +269,7 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "DestroySubwindows")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct DestroyChildren: Request(5, error::Window) {
 		/// The [window] which will have its children [destroyed].
 		///
@@ -361,6 +335,7 @@ derive_xrb! {
 	/// [`Match` error]: error::Match
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ReparentWindow: Request(7, ReparentWindowError) {
 		/// The [window] which will be transferred to be a child of the
 		/// `new_parent`.
@@ -431,6 +406,7 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct MapWindow: Request(8, error::Window) {
 		/// The [window] which is the target of the `MapWindow` [request].
 		///
@@ -464,6 +440,7 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "MapSubwindows")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct MapChildren: Request(9, error::Window) {
 		/// The [window] which will have its unmapped children [mapped].
 		///
@@ -498,6 +475,7 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UnmapWindow: Request(10, error::Window) {
 		/// The [window] which is the target of the `UnmapWindow` [request].
 		///
@@ -530,6 +508,7 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	#[doc(alias = "UnmapSubwindows")]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct UnmapChildren: Request(11, error::Window) {
 		/// The [window] which will have its mapped children [unmapped].
 		///
@@ -592,6 +571,7 @@ derive_xrb! {
 	/// [`Window` error]: error::Window
 	/// [`Match` error]: error::Match
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct ConfigureWindow: Request(12, ConfigureWindowError) {
 		/// The [window] which is the target of the `ConfigureWindow` [request].
 		///
@@ -660,7 +640,9 @@ request_error! {
 /// [window]: Window
 ///
 /// [`CirculateWindow` request]: CirculateWindow
-#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CirculateDirection {
 	/// Raises the lowest mapped child that is occluded by another child, if
 	/// any, to the top of the stack.
@@ -695,6 +677,7 @@ derive_xrb! {
 	///
 	/// [`Window` error]: error::Window
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct CirculateWindow: Request(13, CirculateWindowError) {
 		#[metabyte]
 		/// Which of the [window]'s children might be circulated and in which
@@ -741,6 +724,7 @@ derive_xrb! {
 	///
 	/// [`Drawable` error]: error::Drawable
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct GetGeometry: Request(14, error::Drawable) -> reply::GetGeometry {
 		/// The [drawable] for which this [request] gets its geometry.
 		///
@@ -778,6 +762,7 @@ derive_xrb! {
 	#[doc(alias("QueryParent", "QueryChildren", "QueryRoot"))]
 	#[doc(alias("GetParent", "GetChildren", "GetRoot"))]
 	#[derive(Debug, Hash, PartialEq, Eq, X11Size, Readable, Writable, ConstantX11Size)]
+	#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 	pub struct QueryWindowTree: Request(15, error::Window) -> reply::QueryWindowTree {
 		/// The [window] for which this [request] gets its root [window],
 		/// parent, and children.
@@ -794,3 +779,218 @@ derive_xrb! {
 		pub target: Window,
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{visual::ColorId, BitGravity, Colormap, EventMask};
+	use std::fmt::Debug;
+	use xrbk::{ConstantX11Size, Readable, Writable, X11Size};
+
+	const TARGET: Window = Window::new(0x0040_0001);
+
+	/// Writes the `request`, checks its length against its [`ConstantX11Size`],
+	/// and returns the bytes after checking that they read back to the same
+	/// `request`.
+	fn round_trip<Req>(request: &Req) -> Vec<u8>
+	where
+		Req: Request + ConstantX11Size + Readable + PartialEq + Debug,
+	{
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), Req::X11_SIZE);
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(bytes[0], Req::MAJOR_OPCODE);
+		assert_eq!(
+			usize::from(u16::from_be_bytes([bytes[2], bytes[3]])) * 4,
+			bytes.len(),
+		);
+
+		// The major opcode is not read by `read_from`.
+		assert_eq!(&Req::read_from(&mut &bytes[1..]).unwrap(), request);
+
+		bytes
+	}
+
+	#[test]
+	fn test_single_window_requests_round_trip() {
+		assert_eq!(
+			round_trip(&DestroyWindow { target: TARGET })[4..],
+			[0, 0x40, 0, 1]
+		);
+		round_trip(&DestroyChildren { target: TARGET });
+		round_trip(&MapWindow { target: TARGET });
+		round_trip(&MapChildren { target: TARGET });
+		round_trip(&UnmapWindow { target: TARGET });
+		round_trip(&UnmapChildren { target: TARGET });
+	}
+
+	#[test]
+	fn test_reparent_window_round_trip() {
+		let bytes = round_trip(&ReparentWindow {
+			target: TARGET,
+			new_parent: Window::new(0x0040_0002),
+			coords: Coords::new(Px(-1), Px(2)),
+		});
+
+		assert_eq!(bytes[12..], [0xff, 0xff, 0, 2]);
+	}
+
+	#[test]
+	fn test_create_window_copy_class_from_parent() {
+		let request = CreateWindow {
+			depth: CopyableFromParent::CopyFromParent,
+			window_id: Window::new(0x0040_0002),
+			parent: TARGET,
+			geometry: Rectangle::new(Px(0), Px(0), Px(100), Px(100)),
+			border_width: Px(0),
+			class: CopyableFromParent::CopyFromParent,
+			visual: CopyableFromParent::CopyFromParent,
+			attributes: Attributes::builder().build(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), 32);
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(bytes[2..4], [0, 8]);
+		// `class` is 2 bytes, even when it is copied from the parent.
+		assert_eq!(bytes[22..28], [0, 0, 0, 0, 0, 0]);
+
+		assert_eq!(CreateWindow::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	/// Attributes like those xterm gives its top-level window.
+	fn xterm_attributes() -> Attributes {
+		let mut attributes = Attributes::builder();
+		attributes
+			.background_color(ColorId::new(0x00ff_ffff))
+			.border_color(ColorId::ZERO)
+			.bit_gravity(BitGravity::NorthWest)
+			.event_mask(
+				EventMask::KEY_PRESS
+					| EventMask::EXPOSURE
+					| EventMask::STRUCTURE_NOTIFY
+					| EventMask::FOCUS_CHANGE
+					| EventMask::PROPERTY_CHANGE,
+			)
+			.colormap(CopyableFromParent::Other(Colormap::new(0x0000_0020)));
+
+		attributes.build()
+	}
+
+	#[test]
+	fn test_create_window_layout() {
+		let request = CreateWindow {
+			depth: CopyableFromParent::Other(24),
+			window_id: Window::new(0x0120_0009),
+			parent: Window::new(0x0000_0279),
+			geometry: Rectangle::new(Px(0), Px(0), Px(484), Px(316)),
+			border_width: Px(1),
+			class: CopyableFromParent::Other(WindowClass::InputOutput),
+			visual: CopyableFromParent::Other(VisualId::new(0x0000_0021)),
+			attributes: xterm_attributes(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(bytes, [
+			1, 24, 0, 13,
+			0x01, 0x20, 0, 0x09, // window_id
+			0, 0, 0x02, 0x79, // parent
+			0, 0, 0, 0, // x, y
+			0x01, 0xe4, 0x01, 0x3c, // width, height
+			0, 1, 0, 1, // border_width, class
+			0, 0, 0, 0x21, // visual
+			0, 0, 0x28, 0x1a, // attribute mask
+			0, 0xff, 0xff, 0xff, // background_color
+			0, 0, 0, 0, // border_color
+			0, 0, 0, 1, // bit_gravity
+			0, 0x62, 0x80, 0x01, // event_mask
+			0, 0, 0, 0x20, // colormap
+		]);
+		assert_eq!(bytes.len(), request.x11_size());
+
+		assert_eq!(CreateWindow::read_from(&mut &bytes[1..]).unwrap(), request);
+	}
+
+	#[test]
+	fn test_change_window_attributes_shares_value_list() {
+		let attributes = xterm_attributes();
+
+		let mut create_window = vec![];
+		CreateWindow {
+			depth: CopyableFromParent::CopyFromParent,
+			window_id: Window::new(0x0040_0002),
+			parent: TARGET,
+			geometry: Rectangle::new(Px(0), Px(0), Px(1), Px(1)),
+			border_width: Px(0),
+			class: CopyableFromParent::CopyFromParent,
+			visual: CopyableFromParent::CopyFromParent,
+			attributes: attributes.clone(),
+		}
+		.write_to(&mut create_window)
+		.unwrap();
+
+		let request = ChangeWindowAttributes {
+			target: TARGET,
+			attributes,
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(bytes[2..4], [0, 8]);
+		assert_eq!(bytes[8..], create_window[28..]);
+
+		assert_eq!(
+			ChangeWindowAttributes::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+	}
+
+	#[test]
+	fn test_circulate_window_direction_is_metabyte() {
+		for (direction, metabyte) in [
+			(CirculateDirection::RaiseLowest, 0),
+			(CirculateDirection::LowerHighest, 1),
+		] {
+			let bytes = round_trip(&CirculateWindow {
+				direction,
+				target: TARGET,
+			});
+
+			assert_eq!(bytes[1], metabyte);
+		}
+	}
+
+	#[test]
+	fn test_configure_window_length() {
+		let mut config = WindowConfig::builder();
+		config.width(Px(640)).height(Px(480));
+
+		let request = ConfigureWindow {
+			target: TARGET,
+			config: config.build(),
+		};
+
+		let mut bytes = vec![];
+		request.write_to(&mut bytes).unwrap();
+
+		// The header, target, mask, 2 unused bytes, and 2 values.
+		assert_eq!(bytes.len(), 20);
+		assert_eq!(bytes.len(), request.x11_size());
+		assert_eq!(bytes[2..4], [0, 5]);
+		assert_eq!(bytes[8..10], [0, 0x0c]);
+
+		assert_eq!(
+			ConfigureWindow::read_from(&mut &bytes[1..]).unwrap(),
+			request
+		);
+	}
+}
@@ -0,0 +1,217 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Helpers for common patterns of [requests] and [replies] defined in the core
+//! X11 protocol.
+//!
+//! [requests]: crate::message::Request
+//! [replies]: crate::message::Reply
+
+pub use window_tree::WindowTree;
+
+mod window_tree;
+
+use std::mem::ManuallyDrop;
+
+use crate::{
+	message::{Request, SendRequest},
+	x11::{
+		reply::MapState,
+		request::{GrabServer, MapWindow, ReparentWindow, UngrabServer},
+	},
+	Coords,
+	Window,
+};
+
+/// Sends a [`GrabServer` request], returning a guard which sends an
+/// [`UngrabServer` request] when it is dropped.
+///
+/// While the server is grabbed, [requests] may be sent with the returned
+/// guard. The server is ungrabbed when the guard is dropped, even if the
+/// current thread panics. If an error should be handled when ungrabbing the
+/// server, use [`ServerGrabGuard::ungrab`].
+///
+/// # Errors
+/// Returns the `sink`'s [error] if the [`GrabServer` request] could not be
+/// sent.
+///
+/// [requests]: Request
+/// [error]: SendRequest::Error
+///
+/// [`GrabServer` request]: GrabServer
+/// [`UngrabServer` request]: UngrabServer
+pub fn grab_server<S: SendRequest>(sink: &mut S) -> Result<ServerGrabGuard<'_, S>, S::Error> {
+	sink.send_request(&GrabServer)?;
+
+	Ok(ServerGrabGuard { sink })
+}
+
+/// A guard which sends an [`UngrabServer` request] when it is dropped.
+///
+/// This is created with [`grab_server`].
+///
+/// [`UngrabServer` request]: UngrabServer
+#[must_use = "the server is ungrabbed as soon as the guard is dropped"]
+pub struct ServerGrabGuard<'sink, S: SendRequest> {
+	sink: &'sink mut S,
+}
+
+impl<S: SendRequest> ServerGrabGuard<'_, S> {
+	/// Ungrabs the server, returning any error encountered when sending the
+	/// [`UngrabServer` request].
+	///
+	/// # Errors
+	/// Returns the `sink`'s [error] if the [`UngrabServer` request] could not
+	/// be sent.
+	///
+	/// [error]: SendRequest::Error
+	///
+	/// [`UngrabServer` request]: UngrabServer
+	pub fn ungrab(self) -> Result<(), S::Error> {
+		// The `UngrabServer` request is sent here, so it must not be sent again
+		// on drop.
+		let mut this = ManuallyDrop::new(self);
+
+		this.sink.send_request(&UngrabServer)
+	}
+}
+
+impl<S: SendRequest> SendRequest for ServerGrabGuard<'_, S> {
+	type Error = S::Error;
+
+	fn send_request<Req: Request>(&mut self, request: &Req) -> Result<(), Self::Error> {
+		self.sink.send_request(request)
+	}
+}
+
+impl<S: SendRequest> Drop for ServerGrabGuard<'_, S> {
+	fn drop(&mut self) {
+		// Errors cannot be returned from `drop`; `ungrab` can be used instead
+		// if they need to be handled.
+		let _ = self.sink.send_request(&UngrabServer);
+	}
+}
+
+/// Sends a [`ReparentWindow` request] to move the `target` [window] into the
+/// `new_parent` at the given `coords`, followed by a [`MapWindow` request] if
+/// the `target` needs to be mapped.
+///
+/// If the `target` is mapped when it is reparented, the X server maps it again
+/// automatically once it has been moved, so the [`MapWindow` request] is only
+/// sent if the `target`'s `map_state` is [`MapState::Unmapped`]. The
+/// `map_state` is typically taken from a [`GetWindowAttributes` reply].
+///
+/// # Errors
+/// Returns the `sink`'s [error] if either [request] could not be sent.
+///
+/// [window]: Window
+/// [request]: Request
+/// [error]: SendRequest::Error
+///
+/// [`ReparentWindow` request]: ReparentWindow
+/// [`MapWindow` request]: MapWindow
+/// [`GetWindowAttributes` reply]: crate::x11::reply::GetWindowAttributes
+pub fn reparent_and_map<S: SendRequest>(
+	sink: &mut S, target: Window, new_parent: Window, coords: Coords, map_state: MapState,
+) -> Result<(), S::Error> {
+	sink.send_request(&ReparentWindow {
+		target,
+		new_parent,
+		coords,
+	})?;
+
+	if map_state == MapState::Unmapped {
+		sink.send_request(&MapWindow { target })?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{io::RequestBuffer, unit::Px};
+	use std::{
+		convert::Infallible,
+		panic::{self, AssertUnwindSafe},
+	};
+	use xrbk::{ByteOrder, Writable};
+
+	const GRAB_SERVER: [u8; 4] = [36, 0, 0, 1];
+	const UNGRAB_SERVER: [u8; 4] = [37, 0, 0, 1];
+
+	/// Records the serialized bytes of every request sent.
+	#[derive(Default)]
+	struct Recorder(Vec<u8>);
+
+	impl SendRequest for Recorder {
+		type Error = Infallible;
+
+		fn send_request<Req: Request>(&mut self, request: &Req) -> Result<(), Self::Error> {
+			request.write_to(&mut self.0).unwrap();
+
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn test_guard_ungrabs_on_drop() {
+		let mut recorder = Recorder::default();
+
+		drop(grab_server(&mut recorder).unwrap());
+
+		assert_eq!(recorder.0, [GRAB_SERVER, UNGRAB_SERVER].concat());
+	}
+
+	#[test]
+	fn test_guard_ungrabs_on_panic() {
+		let mut recorder = Recorder::default();
+
+		let result = panic::catch_unwind(AssertUnwindSafe(|| {
+			let _guard = grab_server(&mut recorder).unwrap();
+
+			panic!("panicked while the server was grabbed");
+		}));
+
+		assert!(result.is_err());
+		assert_eq!(recorder.0, [GRAB_SERVER, UNGRAB_SERVER].concat());
+	}
+
+	#[test]
+	fn test_reparent_and_map() {
+		let (target, new_parent) = (Window::new(0x0040_0001), Window::new(0x0000_0100));
+		let coords = Coords::new(Px(10), Px(20));
+
+		let mut reparent = vec![];
+		ReparentWindow {
+			target,
+			new_parent,
+			coords,
+		}
+		.write_to(&mut reparent)
+		.unwrap();
+		let mut map = vec![];
+		MapWindow { target }.write_to(&mut map).unwrap();
+
+		let mut buffer = RequestBuffer::new(ByteOrder::BigEndian);
+
+		reparent_and_map(&mut buffer, target, new_parent, coords, MapState::Unmapped).unwrap();
+		assert_eq!(buffer.flush(), [reparent.clone(), map].concat());
+
+		// A mapped window is mapped again by the server once reparented.
+		for map_state in [MapState::Unviewable, MapState::Viewable] {
+			reparent_and_map(&mut buffer, target, new_parent, coords, map_state).unwrap();
+			assert_eq!(buffer.flush(), reparent);
+		}
+	}
+
+	#[test]
+	fn test_explicit_ungrab_only_ungrabs_once() {
+		let mut recorder = Recorder::default();
+
+		grab_server(&mut recorder).unwrap().ungrab().unwrap();
+
+		assert_eq!(recorder.0, [GRAB_SERVER, UNGRAB_SERVER].concat());
+	}
+}
@@ -0,0 +1,206 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::{collections::HashMap, iter};
+
+use crate::{x11::reply::QueryWindowTree, Window};
+
+/// A cache of the [window] hierarchy, built from [`QueryWindowTree` replies].
+///
+/// The `WindowTree` does not send requests itself: [`QueryWindowTree`
+/// replies] are given to it with [`insert_reply`], and it answers queries about
+/// the parents, children, and ancestors of [windows] from the replies it has
+/// been given.
+///
+/// Replies may be inserted in any order. The parent of a [window] is known as
+/// soon as either its own reply or its parent's reply has been inserted, while
+/// its children are only known once its own reply has been inserted. If a
+/// [window] is found to have been reparented, the most recently inserted reply
+/// is taken to be correct.
+///
+/// [window]: Window
+/// [windows]: Window
+///
+/// [`QueryWindowTree` replies]: QueryWindowTree
+/// [`insert_reply`]: WindowTree::insert_reply
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowTree {
+	parents: HashMap<Window, Window>,
+	children: HashMap<Window, Vec<Window>>,
+}
+
+impl WindowTree {
+	/// Creates a new, empty `WindowTree`.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds the `reply` to a [`QueryWindowTree` request] for the given
+	/// [window] to the tree.
+	///
+	/// This replaces anything previously known about the [window]'s parent
+	/// and children. If the [window] or any of its children were previously
+	/// known to have a different parent, they are removed from that parent's
+	/// children.
+	///
+	/// If the [window]'s parent's children are known but do not include the
+	/// [window], the [window] is added to the top of their stacking order, as
+	/// that is where a reparented [window] is placed.
+	///
+	/// [window]: Window
+	///
+	/// [`QueryWindowTree` request]: crate::x11::request::QueryWindowTree
+	pub fn insert_reply(&mut self, window: Window, reply: &QueryWindowTree) {
+		match reply.parent {
+			Some(parent) => {
+				self.set_parent(window, parent);
+
+				if let Some(siblings) = self.children.get_mut(&parent) {
+					if !siblings.contains(&window) {
+						siblings.push(window);
+					}
+				}
+			},
+
+			None => {
+				if let Some(old_parent) = self.parents.remove(&window) {
+					self.remove_child(old_parent, window);
+				}
+			},
+		}
+
+		// Children which are no longer listed have been reparented elsewhere,
+		// but we don't know where yet.
+		if let Some(old_children) = self.children.remove(&window) {
+			for child in old_children {
+				if self.parents.get(&child) == Some(&window) {
+					self.parents.remove(&child);
+				}
+			}
+		}
+
+		for &child in &reply.children {
+			self.set_parent(child, window);
+		}
+
+		self.children.insert(window, reply.children.clone());
+	}
+
+	/// Returns the children of the given [window], if they are known.
+	///
+	/// The children are listed in stacking order, from bottom to top.
+	///
+	/// [window]: Window
+	#[must_use]
+	pub fn children(&self, window: Window) -> Option<&[Window]> {
+		self.children.get(&window).map(Vec::as_slice)
+	}
+
+	/// Returns the parent of the given [window], if it is known.
+	///
+	/// Root [windows] have no parent.
+	///
+	/// [window]: Window
+	/// [windows]: Window
+	#[must_use]
+	pub fn parent(&self, window: Window) -> Option<Window> {
+		self.parents.get(&window).copied()
+	}
+
+	/// Returns an iterator over the known ancestors of the given [window],
+	/// starting with its parent and ending with its root [window], if known.
+	///
+	/// [window]: Window
+	pub fn ancestors(&self, window: Window) -> impl Iterator<Item = Window> + '_ {
+		iter::successors(self.parent(window), |&ancestor| self.parent(ancestor))
+			// If inconsistent replies have been inserted, the parents may form a
+			// cycle.
+			.take(self.parents.len())
+	}
+
+	/// Records the `parent` of the given `window`, removing it from the
+	/// children of its previous parent if that was different.
+	fn set_parent(&mut self, window: Window, parent: Window) {
+		match self.parents.insert(window, parent) {
+			Some(old_parent) if old_parent != parent => self.remove_child(old_parent, window),
+
+			_ => {},
+		}
+	}
+
+	fn remove_child(&mut self, parent: Window, child: Window) {
+		if let Some(children) = self.children.get_mut(&parent) {
+			children.retain(|&window| window != child);
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const ROOT: Window = Window::new(0x0000_0100);
+	const FRAME_A: Window = Window::new(0x0040_0001);
+	const FRAME_B: Window = Window::new(0x0040_0002);
+	const CLIENT_A: Window = Window::new(0x0060_0001);
+	const CLIENT_B: Window = Window::new(0x0060_0002);
+
+	fn reply(parent: Option<Window>, children: Vec<Window>) -> QueryWindowTree {
+		QueryWindowTree {
+			sequence: 0,
+			root: ROOT,
+			parent,
+			children,
+		}
+	}
+
+	#[test]
+	fn test_three_levels() {
+		let mut tree = WindowTree::new();
+
+		// The child's reply arrives before its parent's.
+		tree.insert_reply(FRAME_A, &reply(Some(ROOT), vec![CLIENT_A, CLIENT_B]));
+		assert_eq!(tree.parent(FRAME_A), Some(ROOT));
+		assert_eq!(tree.children(ROOT), None);
+
+		tree.insert_reply(ROOT, &reply(None, vec![FRAME_A, FRAME_B]));
+		tree.insert_reply(FRAME_B, &reply(Some(ROOT), vec![]));
+
+		// Bottom to top.
+		assert_eq!(tree.children(ROOT), Some(&[FRAME_A, FRAME_B][..]));
+		assert_eq!(tree.children(FRAME_A), Some(&[CLIENT_A, CLIENT_B][..]));
+		assert_eq!(tree.children(FRAME_B), Some(&[][..]));
+		assert_eq!(tree.children(CLIENT_A), None);
+
+		assert_eq!(tree.parent(ROOT), None);
+		assert_eq!(tree.parent(CLIENT_B), Some(FRAME_A));
+
+		assert!(tree.ancestors(CLIENT_B).eq([FRAME_A, ROOT]));
+		assert!(tree.ancestors(ROOT).eq([]));
+	}
+
+	#[test]
+	fn test_reparent() {
+		let mut tree = WindowTree::new();
+
+		tree.insert_reply(ROOT, &reply(None, vec![FRAME_A, FRAME_B]));
+		tree.insert_reply(FRAME_A, &reply(Some(ROOT), vec![CLIENT_A, CLIENT_B]));
+		tree.insert_reply(FRAME_B, &reply(Some(ROOT), vec![]));
+
+		// `CLIENT_A` is reparented to `FRAME_B`.
+		tree.insert_reply(CLIENT_A, &reply(Some(FRAME_B), vec![]));
+
+		assert_eq!(tree.children(FRAME_A), Some(&[CLIENT_B][..]));
+		assert_eq!(tree.children(FRAME_B), Some(&[CLIENT_A][..]));
+		assert!(tree.ancestors(CLIENT_A).eq([FRAME_B, ROOT]));
+
+		// `FRAME_A` no longer lists `CLIENT_B`, whose new parent is not yet
+		// known.
+		tree.insert_reply(FRAME_A, &reply(Some(ROOT), vec![]));
+
+		assert_eq!(tree.parent(CLIENT_B), None);
+		assert_eq!(tree.children(FRAME_A), Some(&[][..]));
+	}
+}
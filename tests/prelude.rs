@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Checks that requests can be written and replies and events read with only
+//! the traits and types exported by `xrb::prelude`.
+
+use xrb::prelude::*;
+
+#[test]
+fn test_write_request() -> Result<(), WriteError> {
+	let request = xrb::x11::request::RingBell {
+		volume: xrb::unit::SignedPercentage::new(-50).unwrap(),
+	};
+
+	let mut bytes = vec![];
+	request.write_to(&mut bytes)?;
+
+	assert_eq!(bytes.len(), request.x11_size());
+	assert_eq!(bytes.len(), usize::from(request.length().unwrap()) * 4);
+	assert_eq!(bytes[0], xrb::x11::request::RingBell::MAJOR_OPCODE);
+
+	Ok(())
+}
+
+#[test]
+fn test_read_reply() -> Result<(), Box<dyn std::error::Error>> {
+	let reply = xrb::x11::reply::GetFocus {
+		sequence: 7,
+		revert_to: xrb::x11::request::RevertFocus::Parent,
+		focus: xrb::FocusWindow::Other(xrb::Window::new(0x0040_0001)),
+	};
+
+	let mut bytes = vec![];
+	reply.write_to(&mut bytes)?;
+
+	// The first byte of a message is read to tell which message it is.
+	let read = xrb::x11::reply::GetFocus::read_with_request(
+		&mut &bytes[1..],
+		&xrb::x11::request::GetFocus,
+	)?;
+
+	assert_eq!(read.sequence(), 7);
+	assert_eq!(read.length(), 0);
+	assert_eq!(read.focus, reply.focus);
+
+	Ok(())
+}
+
+#[test]
+fn test_read_event() -> Result<(), Box<dyn std::error::Error>> {
+	let event = xrb::x11::event::Destroy {
+		sequence: 3,
+		event_window: xrb::Window::new(0x0040_0001),
+		window: xrb::Window::new(0x0040_0002),
+	};
+
+	let mut bytes = vec![];
+	event.write_to(&mut bytes)?;
+
+	assert_eq!(bytes.len(), 32);
+	assert_eq!(bytes[0], xrb::x11::event::Destroy::CODE);
+
+	let read = xrb::x11::event::Destroy::read_from(&mut &bytes[1..])?;
+
+	assert_eq!(read.sequence(), Some(3));
+	assert_eq!(read, event);
+
+	Ok(())
+}
@@ -0,0 +1,35 @@
+use xrb::prelude::*;
+
+struct Manual;
+
+impl X11Size for Manual {
+	fn x11_size(&self) -> usize {
+		32
+	}
+}
+
+impl Readable for Manual {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		buf.advance(32);
+
+		Ok(Self)
+	}
+}
+
+impl Writable for Manual {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		buf.put_bytes(0, 32);
+
+		Ok(())
+	}
+}
+
+impl Event for Manual {
+	const CODE: u8 = 64;
+
+	fn sequence(&self) -> Option<u16> {
+		None
+	}
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use xrb::{Font, Window};
+
+fn main() {
+	// Resource IDs can't be implicitly converted from a `u32`...
+	let window: Window = 1_u32.into();
+
+	// ...even fallibly...
+	let _: Result<Window, _> = 1_u32.try_into();
+
+	// ...nor from another kind of resource ID.
+	let _: Window = Font::new(window.unwrap()).into();
+}
@@ -0,0 +1,342 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::color`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	visual::{ColorId, RgbColor, VisualId},
+	x11::request::{
+		AllocateColor,
+		AllocateColorCells,
+		AllocateColorPlanes,
+		AllocateNamedColor,
+		ColormapEntryChange,
+		CreateColormap,
+		DestroyColormap,
+		DestroyColormapEntries,
+		GetNamedColor,
+		InitialColormapAllocation,
+		InstallColormap,
+		ListInstalledColormaps,
+		MoveColormap,
+		QueryColors,
+		StoreColors,
+		StoreNamedColor,
+		UninstallColormap,
+	},
+	ColorChannelMask,
+	Colormap,
+	String8,
+};
+
+use crate::{assert_compat, WINDOW};
+
+const COLORMAP: Colormap = Colormap::new(0x0020_0001);
+const OTHER_COLORMAP: Colormap = Colormap::new(0x0020_0002);
+
+/// `CreateColormap` is x11rb's `CreateColormapRequest`.
+///
+/// - `initial_allocation` is `alloc`: `InitialColormapAllocation` is x11rb's
+///   `ColormapAlloc`.
+/// - `colormap_id` is `mid`.
+#[test]
+fn test_create_colormap() {
+	for (initial_allocation, alloc) in [
+		(InitialColormapAllocation::None, xproto::ColormapAlloc::NONE),
+		(InitialColormapAllocation::All, xproto::ColormapAlloc::ALL),
+	] {
+		assert_compat(
+			&CreateColormap {
+				initial_allocation,
+				colormap_id: COLORMAP,
+				window: WINDOW,
+				visual: VisualId::new(0x21),
+			},
+			xproto::CreateColormapRequest {
+				alloc,
+				mid: COLORMAP.unwrap(),
+				window: WINDOW.unwrap(),
+				visual: 0x21,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// Requests whose only field is the `target` [colormap], which is x11rb's
+/// `cmap`.
+///
+/// - `DestroyColormap` is x11rb's `FreeColormapRequest`.
+/// - `InstallColormap` is x11rb's `InstallColormapRequest`.
+/// - `UninstallColormap` is x11rb's `UninstallColormapRequest`.
+///
+/// [colormap]: Colormap
+#[test]
+fn test_colormap_target_requests() {
+	let cmap = COLORMAP.unwrap();
+
+	assert_compat(
+		&DestroyColormap { target: COLORMAP },
+		xproto::FreeColormapRequest { cmap }.serialize(),
+	);
+	assert_compat(
+		&InstallColormap { target: COLORMAP },
+		xproto::InstallColormapRequest { cmap }.serialize(),
+	);
+	assert_compat(
+		&UninstallColormap { target: COLORMAP },
+		xproto::UninstallColormapRequest { cmap }.serialize(),
+	);
+}
+
+/// `MoveColormap` is x11rb's `CopyColormapAndFreeRequest`.
+///
+/// - `colormap_id` is `mid`.
+/// - `source` is `src_cmap`.
+#[test]
+fn test_move_colormap() {
+	assert_compat(
+		&MoveColormap {
+			colormap_id: OTHER_COLORMAP,
+			source: COLORMAP,
+		},
+		xproto::CopyColormapAndFreeRequest {
+			mid: OTHER_COLORMAP.unwrap(),
+			src_cmap: COLORMAP.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `ListInstalledColormaps` is x11rb's `ListInstalledColormapsRequest`.
+///
+/// - `target` is `window`.
+#[test]
+fn test_list_installed_colormaps() {
+	assert_compat(
+		&ListInstalledColormaps { target: WINDOW },
+		xproto::ListInstalledColormapsRequest {
+			window: WINDOW.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `AllocateColor` is x11rb's `AllocColorRequest`.
+///
+/// - `target` is `cmap`.
+/// - `color` is `red`, `green`, and `blue`.
+#[test]
+fn test_allocate_color() {
+	assert_compat(
+		&AllocateColor {
+			target: COLORMAP,
+			color: RgbColor(0xffff, 0x8000, 0x0001),
+		},
+		xproto::AllocColorRequest {
+			cmap: COLORMAP.unwrap(),
+			red: 0xffff,
+			green: 0x8000,
+			blue: 0x0001,
+		}
+		.serialize(),
+	);
+}
+
+/// `AllocateNamedColor` and `GetNamedColor` are x11rb's
+/// `AllocNamedColorRequest` and `LookupColorRequest`.
+///
+/// - `target` is `cmap`.
+#[test]
+fn test_named_colors() {
+	for name in ["red", "cornflower blue"] {
+		assert_compat(
+			&AllocateNamedColor {
+				target: COLORMAP,
+				name: String8::from_latin1(name).unwrap(),
+			},
+			xproto::AllocNamedColorRequest {
+				cmap: COLORMAP.unwrap(),
+				name: Cow::Borrowed(name.as_bytes()),
+			}
+			.serialize(),
+		);
+
+		assert_compat(
+			&GetNamedColor {
+				target: COLORMAP,
+				name: String8::from_latin1(name).unwrap(),
+			},
+			xproto::LookupColorRequest {
+				cmap: COLORMAP.unwrap(),
+				name: Cow::Borrowed(name.as_bytes()),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `AllocateColorCells` is x11rb's `AllocColorCellsRequest`.
+///
+/// - `target` is `cmap`.
+/// - `color_count` is `colors`.
+/// - `plane_count` is `planes`.
+#[test]
+fn test_allocate_color_cells() {
+	assert_compat(
+		&AllocateColorCells {
+			contiguous: true,
+			target: COLORMAP,
+			color_count: 16,
+			plane_count: 2,
+		},
+		xproto::AllocColorCellsRequest {
+			contiguous: true,
+			cmap: COLORMAP.unwrap(),
+			colors: 16,
+			planes: 2,
+		}
+		.serialize(),
+	);
+}
+
+/// `AllocateColorPlanes` is x11rb's `AllocColorPlanesRequest`.
+///
+/// - `target` is `cmap`.
+/// - `color_count` is `colors`.
+/// - `red_plane_count`, `green_plane_count`, and `blue_plane_count` are `reds`,
+///   `greens`, and `blues`.
+#[test]
+fn test_allocate_color_planes() {
+	assert_compat(
+		&AllocateColorPlanes {
+			contiguous: false,
+			target: COLORMAP,
+			color_count: 4,
+			red_plane_count: 1,
+			green_plane_count: 2,
+			blue_plane_count: 3,
+		},
+		xproto::AllocColorPlanesRequest {
+			contiguous: false,
+			cmap: COLORMAP.unwrap(),
+			colors: 4,
+			reds: 1,
+			greens: 2,
+			blues: 3,
+		}
+		.serialize(),
+	);
+}
+
+/// `DestroyColormapEntries` and `QueryColors` are x11rb's `FreeColorsRequest`
+/// and `QueryColorsRequest`.
+///
+/// - `target` is `cmap`.
+/// - `colors` is `pixels`.
+#[test]
+fn test_color_list_requests() {
+	let colors = vec![ColorId::ZERO, ColorId::ONE, ColorId::new(0x00ab_cdef)];
+	let pixels = vec![0, 1, 0x00ab_cdef];
+
+	assert_compat(
+		&DestroyColormapEntries {
+			target: COLORMAP,
+			plane_mask: 0x0000_00f0,
+			colors: colors.clone(),
+		},
+		xproto::FreeColorsRequest {
+			cmap: COLORMAP.unwrap(),
+			plane_mask: 0x0000_00f0,
+			pixels: Cow::Borrowed(&pixels),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&QueryColors {
+			target: COLORMAP,
+			colors,
+		},
+		xproto::QueryColorsRequest {
+			cmap: COLORMAP.unwrap(),
+			pixels: Cow::Borrowed(&pixels),
+		}
+		.serialize(),
+	);
+}
+
+/// `StoreColors` is x11rb's `StoreColorsRequest`.
+///
+/// - `target` is `cmap`.
+/// - `changes` is `items`: `ColormapEntryChange` is x11rb's `Coloritem`, where
+///   `id` is `pixel`, `color` is `red`, `green`, and `blue`, and `mask` is
+///   `flags`.
+#[test]
+fn test_store_colors() {
+	assert_compat(
+		&StoreColors {
+			target: COLORMAP,
+			changes: vec![
+				ColormapEntryChange {
+					id: ColorId::ONE,
+					color: RgbColor(0x1111, 0x2222, 0x3333),
+					mask: ColorChannelMask::RED | ColorChannelMask::BLUE,
+				},
+				ColormapEntryChange {
+					id: ColorId::new(2),
+					color: RgbColor::BLACK,
+					mask: ColorChannelMask::GREEN,
+				},
+			],
+		},
+		xproto::StoreColorsRequest {
+			cmap: COLORMAP.unwrap(),
+			items: Cow::Owned(vec![
+				xproto::Coloritem {
+					pixel: 1,
+					red: 0x1111,
+					green: 0x2222,
+					blue: 0x3333,
+					flags: xproto::ColorFlag::RED | xproto::ColorFlag::BLUE,
+				},
+				xproto::Coloritem {
+					pixel: 2,
+					red: 0,
+					green: 0,
+					blue: 0,
+					flags: xproto::ColorFlag::GREEN,
+				},
+			]),
+		}
+		.serialize(),
+	);
+}
+
+/// `StoreNamedColor` is x11rb's `StoreNamedColorRequest`.
+///
+/// - `mask` is `flags`: `ColorChannelMask` is x11rb's `ColorFlag`.
+/// - `target` is `cmap`.
+/// - `id` is `pixel`.
+#[test]
+fn test_store_named_color() {
+	assert_compat(
+		&StoreNamedColor {
+			mask: ColorChannelMask::RED | ColorChannelMask::GREEN | ColorChannelMask::BLUE,
+			target: COLORMAP,
+			id: ColorId::new(7),
+			name: String8::from_latin1("navy").unwrap(),
+		},
+		xproto::StoreNamedColorRequest {
+			flags: xproto::ColorFlag::RED | xproto::ColorFlag::GREEN | xproto::ColorFlag::BLUE,
+			cmap: COLORMAP.unwrap(),
+			pixel: 7,
+			name: Cow::Borrowed(b"navy"),
+		}
+		.serialize(),
+	);
+}
@@ -0,0 +1,187 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::font`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	x11::request::{
+		AssignFont,
+		GetFontSearchDirectories,
+		ListFonts,
+		ListFontsWithInfo,
+		QueryFont,
+		QueryTextExtents,
+		SetFontSearchDirectories,
+		UnassignFont,
+	},
+	Char16,
+	Font,
+	Fontable,
+	LengthString8,
+	String16,
+	String8,
+};
+
+use crate::assert_compat;
+
+const FONT: Font = Font::new(0x0040_0005);
+
+/// `AssignFont` is x11rb's `OpenFontRequest`.
+///
+/// - `font_id` is `fid`.
+#[test]
+fn test_assign_font() {
+	for name in [
+		"fixed",
+		"-misc-fixed-medium-r-normal--13-120-75-75-c-70-iso10646-1",
+	] {
+		assert_compat(
+			&AssignFont {
+				font_id: FONT,
+				name: String8::from_latin1(name).unwrap(),
+			},
+			xproto::OpenFontRequest {
+				fid: FONT.unwrap(),
+				name: Cow::Borrowed(name.as_bytes()),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `UnassignFont` is x11rb's `CloseFontRequest`.
+///
+/// - `target` is `font`.
+#[test]
+fn test_unassign_font() {
+	assert_compat(
+		&UnassignFont { target: FONT },
+		xproto::CloseFontRequest {
+			font: FONT.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `QueryFont` is x11rb's `QueryFontRequest`.
+///
+/// - `target` is `font`.
+#[test]
+fn test_query_font() {
+	assert_compat(
+		&QueryFont {
+			target: Fontable::from(FONT),
+		},
+		xproto::QueryFontRequest {
+			font: FONT.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `QueryTextExtents` is x11rb's `QueryTextExtentsRequest`.
+///
+/// - `text` is `string`: `Char16` is x11rb's `Char2b`.
+#[test]
+fn test_query_text_extents() {
+	// Texts of odd and even lengths, the former of which is padded.
+	for chars in [
+		&[(0, b'a'), (0, b'b'), (1, 0x02)][..],
+		&[(0, b'c'), (0, b'd')],
+	] {
+		let text: Vec<Char16> = chars
+			.iter()
+			.map(|&(byte1, byte2)| Char16::new(byte1, byte2))
+			.collect();
+		let string: Vec<xproto::Char2b> = chars
+			.iter()
+			.map(|&(byte1, byte2)| xproto::Char2b { byte1, byte2 })
+			.collect();
+
+		assert_compat(
+			&QueryTextExtents {
+				font: Fontable::from(FONT),
+				text: String16::from(text),
+			},
+			xproto::QueryTextExtentsRequest {
+				font: FONT.unwrap(),
+				string: Cow::Owned(string),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `ListFonts` and `ListFontsWithInfo` are x11rb's `ListFontsRequest` and
+/// `ListFontsWithInfoRequest`.
+///
+/// - `max_names_count` and `max_fonts_count` are `max_names`.
+#[test]
+fn test_list_fonts() {
+	for pattern in ["*", "-*-fixed-*"] {
+		assert_compat(
+			&ListFonts {
+				max_names_count: 100,
+				pattern: String8::from_latin1(pattern).unwrap(),
+			},
+			xproto::ListFontsRequest {
+				max_names: 100,
+				pattern: Cow::Borrowed(pattern.as_bytes()),
+			}
+			.serialize(),
+		);
+
+		assert_compat(
+			&ListFontsWithInfo {
+				max_fonts_count: 10,
+				pattern: String8::from_latin1(pattern).unwrap(),
+			},
+			xproto::ListFontsWithInfoRequest {
+				max_names: 10,
+				pattern: Cow::Borrowed(pattern.as_bytes()),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SetFontSearchDirectories` is x11rb's `SetFontPathRequest`.
+///
+/// - `directories` is `font`: `LengthString8` is x11rb's `Str`.
+#[test]
+fn test_set_font_search_directories() {
+	let directories = ["/usr/share/fonts/X11/75dpi", "built-ins"];
+
+	assert_compat(
+		&SetFontSearchDirectories {
+			directories: directories
+				.iter()
+				.map(|&directory| LengthString8::try_from(directory).unwrap())
+				.collect(),
+		},
+		xproto::SetFontPathRequest {
+			font: Cow::Owned(
+				directories
+					.iter()
+					.map(|directory| xproto::Str {
+						name: directory.as_bytes().to_vec(),
+					})
+					.collect(),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `GetFontSearchDirectories` is x11rb's `GetFontPathRequest`.
+#[test]
+fn test_get_font_search_directories() {
+	assert_compat(
+		&GetFontSearchDirectories,
+		xproto::GetFontPathRequest.serialize(),
+	);
+}
@@ -0,0 +1,673 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::graphics`].
+
+mod config;
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	unit::Px,
+	x11::request::{
+		BitPlane,
+		CaptureImage,
+		CaptureImageFormat,
+		ClearArea,
+		CoordinateMode,
+		CopyArea,
+		CopyBitPlane,
+		DrawArcs,
+		DrawLines,
+		DrawPath,
+		DrawPoints,
+		DrawRectangles,
+		DrawText16,
+		DrawText8,
+		FillArcs,
+		FillPolygon,
+		FillRectangles,
+		ImageText16,
+		ImageText8,
+		Line,
+		PlaceImage,
+		PlaceImageFormat,
+		ShapeMode,
+		Text16,
+		Text8,
+		TextItem16,
+		TextItem8,
+	},
+	Arc,
+	Char16,
+	Coords,
+	Dimensions,
+	Drawable,
+	Font,
+	GraphicsContext,
+	Rectangle,
+	String16,
+	String8,
+};
+
+use crate::{assert_compat, WINDOW};
+
+const DRAWABLE: Drawable = Drawable::new(0x0040_0006);
+const OTHER_DRAWABLE: Drawable = Drawable::new(0x0060_0007);
+const GRAPHICS_CONTEXT: GraphicsContext = GraphicsContext::new(0x0040_0008);
+
+/// Returns the same points for XRB and x11rb.
+fn points() -> (Vec<Coords>, Vec<xproto::Point>) {
+	let points = [(0, 0), (-10, 20), (300, -400)];
+
+	(
+		points
+			.iter()
+			.map(|&(x, y)| Coords::new(Px(x), Px(y)))
+			.collect(),
+		points
+			.iter()
+			.map(|&(x, y)| xproto::Point { x, y })
+			.collect(),
+	)
+}
+
+/// Returns the same rectangles for XRB and x11rb.
+fn rectangles() -> (Vec<Rectangle>, Vec<xproto::Rectangle>) {
+	let rectangles = [(0, 0, 10, 20), (-5, 15, 640, 480)];
+
+	(
+		rectangles
+			.iter()
+			.map(|&(x, y, width, height)| Rectangle::new(Px(x), Px(y), Px(width), Px(height)))
+			.collect(),
+		rectangles
+			.iter()
+			.map(|&(x, y, width, height)| xproto::Rectangle {
+				x,
+				y,
+				width,
+				height,
+			})
+			.collect(),
+	)
+}
+
+/// Returns the same arcs for XRB and x11rb.
+///
+/// - `bounds` is `x`, `y`, `width`, and `height`.
+/// - `start_angle` and `end_angle` are `angle1` and `angle2`.
+fn arcs() -> (Vec<Arc>, Vec<xproto::Arc>) {
+	let arcs = [
+		(0, 0, 100, 100, 0, 360 * 64),
+		(-20, 30, 40, 60, 90 * 64, -45 * 64),
+	];
+
+	(
+		arcs.iter()
+			.map(|&(x, y, width, height, angle1, angle2)| {
+				Arc::new(
+					Rectangle::new(Px(x), Px(y), Px(width), Px(height)),
+					angle1,
+					angle2,
+				)
+			})
+			.collect(),
+		arcs.iter()
+			.map(|&(x, y, width, height, angle1, angle2)| xproto::Arc {
+				x,
+				y,
+				width,
+				height,
+				angle1,
+				angle2,
+			})
+			.collect(),
+	)
+}
+
+/// `ClearArea` is x11rb's `ClearAreaRequest`.
+///
+/// - `graphics_exposure` is `exposures`.
+/// - `target` is `window`.
+/// - `area` is `x`, `y`, `width`, and `height`.
+#[test]
+fn test_clear_area() {
+	for graphics_exposure in [false, true] {
+		assert_compat(
+			&ClearArea {
+				graphics_exposure,
+				target: WINDOW,
+				area: Rectangle::new(Px(-1), Px(2), Px(30), Px(40)),
+			},
+			xproto::ClearAreaRequest {
+				exposures: graphics_exposure,
+				window: WINDOW.unwrap(),
+				x: -1,
+				y: 2,
+				width: 30,
+				height: 40,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `CopyArea` and `CopyBitPlane` are x11rb's `CopyAreaRequest` and
+/// `CopyPlaneRequest`.
+///
+/// - `source` and `destination` are `src_drawable` and `dst_drawable`.
+/// - `graphics_context` is `gc`.
+/// - `source_coords` and `destination_coords` are `src_x`, `src_y`, `dst_x`,
+///   and `dst_y`.
+/// - `dimensions` is `width` and `height`.
+#[test]
+fn test_copy_requests() {
+	assert_compat(
+		&CopyArea {
+			source: DRAWABLE,
+			destination: OTHER_DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			source_coords: Coords::new(Px(1), Px(-2)),
+			destination_coords: Coords::new(Px(-3), Px(4)),
+			dimensions: Dimensions::new(Px(50), Px(60)),
+		},
+		xproto::CopyAreaRequest {
+			src_drawable: DRAWABLE.unwrap(),
+			dst_drawable: OTHER_DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			src_x: 1,
+			src_y: -2,
+			dst_x: -3,
+			dst_y: 4,
+			width: 50,
+			height: 60,
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&CopyBitPlane {
+			source: DRAWABLE,
+			destination: OTHER_DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			source_coords: Coords::new(Px(1), Px(-2)),
+			destination_coords: Coords::new(Px(-3), Px(4)),
+			dimensions: Dimensions::new(Px(50), Px(60)),
+			bit_plane: BitPlane::nth(5).unwrap(),
+		},
+		xproto::CopyPlaneRequest {
+			src_drawable: DRAWABLE.unwrap(),
+			dst_drawable: OTHER_DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			src_x: 1,
+			src_y: -2,
+			dst_x: -3,
+			dst_y: 4,
+			width: 50,
+			height: 60,
+			bit_plane: 1 << 5,
+		}
+		.serialize(),
+	);
+}
+
+/// Returns each `CoordinateMode` with its x11rb `CoordMode`.
+fn coordinate_modes() -> [(CoordinateMode, xproto::CoordMode); 2] {
+	[
+		(CoordinateMode::Drawable, xproto::CoordMode::ORIGIN),
+		(CoordinateMode::Previous, xproto::CoordMode::PREVIOUS),
+	]
+}
+
+/// `DrawPoints` and `DrawPath` are x11rb's `PolyPointRequest` and
+/// `PolyLineRequest`.
+///
+/// - `coordinate_mode` is `coordinate_mode`: `CoordinateMode` is x11rb's
+///   `CoordMode`.
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+#[test]
+fn test_point_requests() {
+	let (points, xproto_points) = points();
+
+	for (mode, coordinate_mode) in coordinate_modes() {
+		assert_compat(
+			&DrawPoints {
+				coordinate_mode: mode,
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				points: points.clone(),
+			},
+			xproto::PolyPointRequest {
+				coordinate_mode,
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				points: Cow::Borrowed(&xproto_points),
+			}
+			.serialize(),
+		);
+	}
+
+	for (mode, coordinate_mode) in coordinate_modes() {
+		assert_compat(
+			&DrawPath {
+				coordinate_mode: mode,
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				points: points.clone(),
+			},
+			xproto::PolyLineRequest {
+				coordinate_mode,
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				points: Cow::Borrowed(&xproto_points),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `FillPolygon` is x11rb's `FillPolyRequest`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `shape` is `shape`: `ShapeMode` is x11rb's `PolyShape`.
+/// - `coordinate_mode` is `coordinate_mode`: `CoordinateMode` is x11rb's
+///   `CoordMode`.
+#[test]
+fn test_fill_polygon() {
+	let (points, xproto_points) = points();
+
+	for (shape_mode, shape, mode, coordinate_mode) in [
+		(
+			ShapeMode::Complex,
+			xproto::PolyShape::COMPLEX,
+			CoordinateMode::Drawable,
+			xproto::CoordMode::ORIGIN,
+		),
+		(
+			ShapeMode::Nonconvex,
+			xproto::PolyShape::NONCONVEX,
+			CoordinateMode::Previous,
+			xproto::CoordMode::PREVIOUS,
+		),
+		(
+			ShapeMode::Convex,
+			xproto::PolyShape::CONVEX,
+			CoordinateMode::Drawable,
+			xproto::CoordMode::ORIGIN,
+		),
+	] {
+		assert_compat(
+			&FillPolygon {
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				shape: shape_mode,
+				coordinate_mode: mode,
+				points: points.clone(),
+			},
+			xproto::FillPolyRequest {
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				shape,
+				coordinate_mode,
+				points: Cow::Borrowed(&xproto_points),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `DrawLines` is x11rb's `PolySegmentRequest`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `lines` is `segments`: `Line` is x11rb's `Segment`, where `start` and
+///   `end` are `x1`, `y1`, `x2`, and `y2`.
+#[test]
+fn test_draw_lines() {
+	assert_compat(
+		&DrawLines {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			lines: vec![
+				Line {
+					start: Coords::new(Px(0), Px(0)),
+					end: Coords::new(Px(100), Px(-100)),
+				},
+				Line {
+					start: Coords::new(Px(-7), Px(8)),
+					end: Coords::new(Px(9), Px(-10)),
+				},
+			],
+		},
+		xproto::PolySegmentRequest {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			segments: Cow::Owned(vec![
+				xproto::Segment {
+					x1: 0,
+					y1: 0,
+					x2: 100,
+					y2: -100,
+				},
+				xproto::Segment {
+					x1: -7,
+					y1: 8,
+					x2: 9,
+					y2: -10,
+				},
+			]),
+		}
+		.serialize(),
+	);
+}
+
+/// `DrawRectangles` and `FillRectangles` are x11rb's `PolyRectangleRequest`
+/// and `PolyFillRectangleRequest`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+#[test]
+fn test_rectangle_requests() {
+	let (rectangles, xproto_rectangles) = rectangles();
+
+	assert_compat(
+		&DrawRectangles {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			rectangles: rectangles.clone(),
+		},
+		xproto::PolyRectangleRequest {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			rectangles: Cow::Borrowed(&xproto_rectangles),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&FillRectangles {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			rectangles,
+		},
+		xproto::PolyFillRectangleRequest {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			rectangles: Cow::Borrowed(&xproto_rectangles),
+		}
+		.serialize(),
+	);
+}
+
+/// `DrawArcs` and `FillArcs` are x11rb's `PolyArcRequest` and
+/// `PolyFillArcRequest`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+#[test]
+fn test_arc_requests() {
+	let (arcs, xproto_arcs) = arcs();
+
+	assert_compat(
+		&DrawArcs {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			arcs: arcs.clone(),
+		},
+		xproto::PolyArcRequest {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			arcs: Cow::Borrowed(&xproto_arcs),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&FillArcs {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			arcs,
+		},
+		xproto::PolyFillArcRequest {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			arcs: Cow::Borrowed(&xproto_arcs),
+		}
+		.serialize(),
+	);
+}
+
+/// `PlaceImage` is x11rb's `PutImageRequest`.
+///
+/// - `format` is `format`: `PlaceImageFormat` is x11rb's `ImageFormat`.
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `dimensions` is `width` and `height`.
+/// - `coordinates` is `dst_x` and `dst_y`.
+/// - `left_padding` is `left_pad`.
+#[test]
+fn test_place_image() {
+	for (image_format, format, data) in [
+		(
+			PlaceImageFormat::Bitmap,
+			xproto::ImageFormat::XY_BITMAP,
+			&[0b1010_0101_u8, 0b0101_1010][..],
+		),
+		(
+			PlaceImageFormat::XyPixmap,
+			xproto::ImageFormat::XY_PIXMAP,
+			&[1, 2, 3, 4, 5][..],
+		),
+		(
+			PlaceImageFormat::Zpixmap,
+			xproto::ImageFormat::Z_PIXMAP,
+			&[0xff; 16][..],
+		),
+	] {
+		assert_compat(
+			&PlaceImage {
+				format: image_format,
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				dimensions: Dimensions::new(Px(4), Px(2)),
+				coordinates: Coords::new(Px(-1), Px(1)),
+				left_padding: 3,
+				depth: 1,
+				data: data.to_vec(),
+			},
+			xproto::PutImageRequest {
+				format,
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				width: 4,
+				height: 2,
+				dst_x: -1,
+				dst_y: 1,
+				left_pad: 3,
+				depth: 1,
+				data: Cow::Borrowed(data),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `CaptureImage` is x11rb's `GetImageRequest`.
+///
+/// - `format` is `format`: `CaptureImageFormat` is x11rb's `ImageFormat`.
+/// - `target` is `drawable`.
+/// - `area` is `x`, `y`, `width`, and `height`.
+#[test]
+fn test_capture_image() {
+	for (image_format, format) in [
+		(CaptureImageFormat::XyPixmap, xproto::ImageFormat::XY_PIXMAP),
+		(CaptureImageFormat::Zpixmap, xproto::ImageFormat::Z_PIXMAP),
+	] {
+		assert_compat(
+			&CaptureImage {
+				format: image_format,
+				target: DRAWABLE,
+				area: Rectangle::new(Px(10), Px(-20), Px(30), Px(40)),
+				plane_mask: 0x00ff_ffff,
+			},
+			xproto::GetImageRequest {
+				format,
+				drawable: DRAWABLE.unwrap(),
+				x: 10,
+				y: -20,
+				width: 30,
+				height: 40,
+				plane_mask: 0x00ff_ffff,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `DrawText8` is x11rb's `PolyText8Request`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `coordinates` is `x` and `y`.
+/// - `text_items` is `items`, which x11rb leaves encoded: a text item is its
+///   length, its horizontal offset, then its string, while a font item is `255`
+///   followed by the big-endian font.
+#[test]
+fn test_draw_text8() {
+	let font = Font::new(0x0102_0304);
+
+	assert_compat(
+		&DrawText8 {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			coordinates: Coords::new(Px(5), Px(15)),
+			text_items: vec![
+				TextItem8::Text(Box::new(
+					Text8::new(Px(2), String8::from_latin1("abc").unwrap()).unwrap(),
+				)),
+				TextItem8::Font(font),
+				TextItem8::Text(Box::new(
+					Text8::new(Px(-1), String8::from_latin1("de").unwrap()).unwrap(),
+				)),
+			],
+		},
+		xproto::PolyText8Request {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			x: 5,
+			y: 15,
+			#[rustfmt::skip]
+			items: Cow::Borrowed(&[
+				3, 2, b'a', b'b', b'c',
+				255, 1, 2, 3, 4,
+				2, 0xff, b'd', b'e',
+			]),
+		}
+		.serialize(),
+	);
+}
+
+/// `DrawText16` is x11rb's `PolyText16Request`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `coordinates` is `x` and `y`.
+/// - `text_items` is `items`, which x11rb leaves encoded: a text item is its
+///   length in characters, its horizontal offset, then its string, while a font
+///   item is `255` followed by the big-endian font.
+#[test]
+fn test_draw_text16() {
+	let font = Font::new(0x0102_0304);
+
+	assert_compat(
+		&DrawText16 {
+			target: DRAWABLE,
+			graphics_context: GRAPHICS_CONTEXT,
+			coordinates: Coords::new(Px(5), Px(15)),
+			text_items: vec![
+				TextItem16::Font(font),
+				TextItem16::Text(Box::new(
+					Text16::new(
+						Px(4),
+						String16::from(vec![Char16::new(0x12, 0x34), Char16::new(0, b'x')]),
+					)
+					.unwrap(),
+				)),
+			],
+		},
+		xproto::PolyText16Request {
+			drawable: DRAWABLE.unwrap(),
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			x: 5,
+			y: 15,
+			#[rustfmt::skip]
+			items: Cow::Borrowed(&[
+				255, 1, 2, 3, 4,
+				2, 4, 0x12, 0x34, 0, b'x',
+			]),
+		}
+		.serialize(),
+	);
+}
+
+/// `ImageText8` and `ImageText16` are x11rb's `ImageText8Request` and
+/// `ImageText16Request`.
+///
+/// - `target` is `drawable`.
+/// - `graphics_context` is `gc`.
+/// - `coordinates` is `x` and `y`.
+/// - `string` is `string`: `Char16` is x11rb's `Char2b`.
+#[test]
+fn test_image_text() {
+	for text in ["", "a", "hello", "wire compat"] {
+		assert_compat(
+			&ImageText8 {
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				coordinates: Coords::new(Px(-8), Px(16)),
+				string: String8::from_latin1(text).unwrap(),
+			},
+			xproto::ImageText8Request {
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				x: -8,
+				y: 16,
+				string: Cow::Borrowed(text.as_bytes()),
+			}
+			.serialize(),
+		);
+
+		assert_compat(
+			&ImageText16 {
+				target: DRAWABLE,
+				graphics_context: GRAPHICS_CONTEXT,
+				coordinates: Coords::new(Px(-8), Px(16)),
+				string: String16::from(
+					text.bytes()
+						.map(|byte| Char16::new(0, byte))
+						.collect::<Vec<_>>(),
+				),
+			},
+			xproto::ImageText16Request {
+				drawable: DRAWABLE.unwrap(),
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				x: -8,
+				y: 16,
+				string: Cow::Owned(
+					text.bytes()
+						.map(|byte| xproto::Char2b {
+							byte1: 0,
+							byte2: byte,
+						})
+						.collect(),
+				),
+			}
+			.serialize(),
+		);
+	}
+}
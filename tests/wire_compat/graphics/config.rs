@@ -0,0 +1,462 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::graphics::config`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	set::{
+		ArcMode,
+		CapStyle,
+		ChildMode,
+		FillRule,
+		FillStyle,
+		Function,
+		GraphicsOptions,
+		GraphicsOptionsMask,
+		JoinStyle,
+		LineStyle,
+		LineWidth,
+	},
+	unit::Px,
+	visual::{ColorId, RgbColor},
+	x11::request::{
+		ChangeGraphicsOptions,
+		ClipRectanglesOrdering,
+		CopyGraphicsOptions,
+		CreateCursorAppearance,
+		CreateGlyphCursorAppearance,
+		CreateGraphicsContext,
+		CreatePixmap,
+		DestroyCursorAppearance,
+		DestroyGraphicsContext,
+		DimensionClass,
+		FreePixmap,
+		QueryIdealDimensions,
+		RecolorCursorAppearance,
+		SetClipRectangles,
+		SetDashes,
+	},
+	Dimensions,
+	Font,
+	GraphicsContext,
+	Pixmap,
+};
+
+use super::{DRAWABLE, GRAPHICS_CONTEXT};
+use crate::{assert_compat, CURSOR_APPEARANCE};
+
+const PIXMAP: Pixmap = Pixmap::new(0x0040_0009);
+const OTHER_PIXMAP: Pixmap = Pixmap::new(0x0040_000a);
+const FONT: Font = Font::new(0x0040_000b);
+
+const FOREGROUND: RgbColor = RgbColor(0xffff, 0x8000, 0);
+const BACKGROUND: RgbColor = RgbColor(0x0101, 0x0202, 0x0303);
+
+/// `CreatePixmap` is x11rb's `CreatePixmapRequest`.
+///
+/// - `pixmap_id` is `pid`.
+#[test]
+fn test_create_pixmap() {
+	assert_compat(
+		&CreatePixmap {
+			depth: 24,
+			pixmap_id: PIXMAP,
+			drawable: DRAWABLE,
+			width: Px(640),
+			height: Px(480),
+		},
+		xproto::CreatePixmapRequest {
+			depth: 24,
+			pid: PIXMAP.unwrap(),
+			drawable: DRAWABLE.unwrap(),
+			width: 640,
+			height: 480,
+		}
+		.serialize(),
+	);
+}
+
+/// Requests whose only field is the `target` resource.
+///
+/// - `FreePixmap` is x11rb's `FreePixmapRequest`, where `target` is `pixmap`.
+/// - `DestroyGraphicsContext` is x11rb's `FreeGCRequest`, where `target` is
+///   `gc`.
+/// - `DestroyCursorAppearance` is x11rb's `FreeCursorRequest`, where `target`
+///   is `cursor`.
+#[test]
+fn test_target_requests() {
+	assert_compat(
+		&FreePixmap { target: PIXMAP },
+		xproto::FreePixmapRequest {
+			pixmap: PIXMAP.unwrap(),
+		}
+		.serialize(),
+	);
+	assert_compat(
+		&DestroyGraphicsContext {
+			target: GRAPHICS_CONTEXT,
+		},
+		xproto::FreeGCRequest {
+			gc: GRAPHICS_CONTEXT.unwrap(),
+		}
+		.serialize(),
+	);
+	assert_compat(
+		&DestroyCursorAppearance {
+			target: CURSOR_APPEARANCE,
+		},
+		xproto::FreeCursorRequest {
+			cursor: CURSOR_APPEARANCE.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `CreateGraphicsContext` is x11rb's `CreateGCRequest`.
+///
+/// - `graphics_context_id` is `cid`.
+/// - `graphics_options` is `value_list`; see [`test_change_graphics_options`]
+///   for the options.
+#[test]
+fn test_create_graphics_context() {
+	let mut options = GraphicsOptions::builder();
+	options
+		.function(Function::Xor)
+		.foreground_color(ColorId::new(0x00ff_0000))
+		.line_width(LineWidth::new(3))
+		.graphics_exposure(false);
+
+	assert_compat(
+		&CreateGraphicsContext {
+			graphics_context_id: GRAPHICS_CONTEXT,
+			drawable: DRAWABLE,
+			graphics_options: options.build(),
+		},
+		xproto::CreateGCRequest {
+			cid: GRAPHICS_CONTEXT.unwrap(),
+			drawable: DRAWABLE.unwrap(),
+			value_list: Cow::Owned(
+				xproto::CreateGCAux::new()
+					.function(xproto::GX::XOR)
+					.foreground(0x00ff_0000)
+					.line_width(3)
+					.graphics_exposures(0),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `ChangeGraphicsOptions` is x11rb's `ChangeGCRequest`.
+///
+/// - `target` is `gc`.
+/// - `changed_options` is `value_list`:
+///   - `function` is `function`: `Function` is x11rb's `GX`.
+///   - `foreground_color` and `background_color` are `foreground` and
+///     `background`.
+///   - `tile_stipple_x` and `tile_stipple_y` are `tile_stipple_x_origin` and
+///     `tile_stipple_y_origin`.
+///   - `child_mode` is `subwindow_mode`: `ChildMode` is x11rb's
+///     `SubwindowMode`.
+///   - `graphics_exposure` is `graphics_exposures`.
+///   - `clip_x` and `clip_y` are `clip_x_origin` and `clip_y_origin`.
+#[test]
+fn test_change_graphics_options() {
+	let mut options = GraphicsOptions::builder();
+	options
+		.function(Function::Or)
+		.plane_mask(0x0000_ffff)
+		.foreground_color(ColorId::ONE)
+		.background_color(ColorId::ZERO)
+		.line_width(LineWidth::new(2))
+		.line_style(LineStyle::OnOffDash)
+		.cap_style(CapStyle::Round)
+		.join_style(JoinStyle::Bevel)
+		.fill_style(FillStyle::Tiled)
+		.fill_rule(FillRule::Winding)
+		.tile(PIXMAP)
+		.stipple(OTHER_PIXMAP)
+		.tile_stipple_x(Px(-3))
+		.tile_stipple_y(Px(4))
+		.font(FONT)
+		.child_mode(ChildMode::IncludeDescendents)
+		.graphics_exposure(true)
+		.clip_x(Px(-5))
+		.clip_y(Px(6))
+		.clip_mask(Some(OTHER_PIXMAP))
+		.dash_offset(Px(7))
+		.dashes(8)
+		.arc_mode(ArcMode::PieSlice);
+
+	assert_compat(
+		&ChangeGraphicsOptions {
+			target: GRAPHICS_CONTEXT,
+			changed_options: options.build(),
+		},
+		xproto::ChangeGCRequest {
+			gc: GRAPHICS_CONTEXT.unwrap(),
+			value_list: Cow::Owned(
+				xproto::ChangeGCAux::new()
+					.function(xproto::GX::OR)
+					.plane_mask(0x0000_ffff)
+					.foreground(1)
+					.background(0)
+					.line_width(2)
+					.line_style(xproto::LineStyle::ON_OFF_DASH)
+					.cap_style(xproto::CapStyle::ROUND)
+					.join_style(xproto::JoinStyle::BEVEL)
+					.fill_style(xproto::FillStyle::TILED)
+					.fill_rule(xproto::FillRule::WINDING)
+					.tile(PIXMAP.unwrap())
+					.stipple(OTHER_PIXMAP.unwrap())
+					.tile_stipple_x_origin(-3)
+					.tile_stipple_y_origin(4)
+					.font(FONT.unwrap())
+					.subwindow_mode(xproto::SubwindowMode::INCLUDE_INFERIORS)
+					.graphics_exposures(1)
+					.clip_x_origin(-5)
+					.clip_y_origin(6)
+					.clip_mask(OTHER_PIXMAP.unwrap())
+					.dash_offset(7)
+					.dashes(8)
+					.arc_mode(xproto::ArcMode::PIE_SLICE),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `CopyGraphicsOptions` is x11rb's `CopyGCRequest`.
+///
+/// - `source` and `destination` are `src_gc` and `dst_gc`.
+/// - `options_mask` is `value_mask`: `GraphicsOptionsMask` is x11rb's `GC`.
+#[test]
+fn test_copy_graphics_options() {
+	let destination = GraphicsContext::new(0x0040_000c);
+
+	assert_compat(
+		&CopyGraphicsOptions {
+			source: GRAPHICS_CONTEXT,
+			destination,
+			options_mask: GraphicsOptionsMask::FUNCTION
+				| GraphicsOptionsMask::LINE_WIDTH
+				| GraphicsOptionsMask::ARC_MODE,
+		},
+		xproto::CopyGCRequest {
+			src_gc: GRAPHICS_CONTEXT.unwrap(),
+			dst_gc: destination.unwrap(),
+			value_mask: xproto::GC::FUNCTION | xproto::GC::LINE_WIDTH | xproto::GC::ARC_MODE,
+		}
+		.serialize(),
+	);
+}
+
+/// `SetDashes` is x11rb's `SetDashesRequest`.
+///
+/// - `target` is `gc`.
+#[test]
+fn test_set_dashes() {
+	for dashes in [&[4_u8][..], &[1, 2, 3, 4], &[5, 6, 7, 8, 9]] {
+		assert_compat(
+			&SetDashes {
+				target: GRAPHICS_CONTEXT,
+				dash_offset: Px(2),
+				dashes: dashes.iter().map(|&dash| Px(dash)).collect(),
+			},
+			xproto::SetDashesRequest {
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				dash_offset: 2,
+				dashes: Cow::Borrowed(dashes),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SetClipRectangles` is x11rb's `SetClipRectanglesRequest`.
+///
+/// - `ordering` is `ordering`: `ClipRectanglesOrdering` is x11rb's
+///   `ClipOrdering`.
+/// - `target` is `gc`.
+/// - `clip_x` and `clip_y` are `clip_x_origin` and `clip_y_origin`.
+/// - `clip_rectangles` is `rectangles`.
+#[test]
+fn test_set_clip_rectangles() {
+	let (rectangles, xproto_rectangles) = super::rectangles();
+
+	for (clip_ordering, ordering) in [
+		(
+			ClipRectanglesOrdering::Unsorted,
+			xproto::ClipOrdering::UNSORTED,
+		),
+		(
+			ClipRectanglesOrdering::SortedByY,
+			xproto::ClipOrdering::Y_SORTED,
+		),
+		(
+			ClipRectanglesOrdering::SortedByYx,
+			xproto::ClipOrdering::YX_SORTED,
+		),
+		(
+			ClipRectanglesOrdering::BandedByYx,
+			xproto::ClipOrdering::YX_BANDED,
+		),
+	] {
+		assert_compat(
+			&SetClipRectangles {
+				ordering: clip_ordering,
+				target: GRAPHICS_CONTEXT,
+				clip_x: Px(-1),
+				clip_y: Px(2),
+				clip_rectangles: rectangles.clone(),
+			},
+			xproto::SetClipRectanglesRequest {
+				ordering,
+				gc: GRAPHICS_CONTEXT.unwrap(),
+				clip_x_origin: -1,
+				clip_y_origin: 2,
+				rectangles: Cow::Borrowed(&xproto_rectangles),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `CreateCursorAppearance` is x11rb's `CreateCursorRequest`.
+///
+/// - `cursor_appearance_id` is `cid`.
+/// - `mask` is `mask`, where [`None`] is `0`.
+/// - `foreground_color` and `background_color` are `fore_red`, `fore_green`,
+///   `fore_blue`, `back_red`, `back_green`, and `back_blue`.
+/// - `hotspot_x` and `hotspot_y` are `x` and `y`.
+#[test]
+fn test_create_cursor_appearance() {
+	for (mask, xproto_mask) in [(None, 0), (Some(OTHER_PIXMAP), OTHER_PIXMAP.unwrap())] {
+		assert_compat(
+			&CreateCursorAppearance {
+				cursor_appearance_id: CURSOR_APPEARANCE,
+				source: PIXMAP,
+				mask,
+				foreground_color: FOREGROUND,
+				background_color: BACKGROUND,
+				hotspot_x: Px(8),
+				hotspot_y: Px(9),
+			},
+			xproto::CreateCursorRequest {
+				cid: CURSOR_APPEARANCE.unwrap(),
+				source: PIXMAP.unwrap(),
+				mask: xproto_mask,
+				fore_red: 0xffff,
+				fore_green: 0x8000,
+				fore_blue: 0,
+				back_red: 0x0101,
+				back_green: 0x0202,
+				back_blue: 0x0303,
+				x: 8,
+				y: 9,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `CreateGlyphCursorAppearance` is x11rb's `CreateGlyphCursorRequest`.
+///
+/// - `cursor_appearance_id` is `cid`.
+/// - `mask_font` is `mask_font`, where [`None`] is `0`.
+/// - `foreground_color` and `background_color` are `fore_red`, `fore_green`,
+///   `fore_blue`, `back_red`, `back_green`, and `back_blue`.
+#[test]
+fn test_create_glyph_cursor_appearance() {
+	for (mask_font, xproto_mask_font) in [(None, 0), (Some(FONT), FONT.unwrap())] {
+		assert_compat(
+			&CreateGlyphCursorAppearance {
+				cursor_appearance_id: CURSOR_APPEARANCE,
+				source_font: FONT,
+				mask_font,
+				source_char: 68,
+				mask_char: 69,
+				foreground_color: FOREGROUND,
+				background_color: BACKGROUND,
+			},
+			xproto::CreateGlyphCursorRequest {
+				cid: CURSOR_APPEARANCE.unwrap(),
+				source_font: FONT.unwrap(),
+				mask_font: xproto_mask_font,
+				source_char: 68,
+				mask_char: 69,
+				fore_red: 0xffff,
+				fore_green: 0x8000,
+				fore_blue: 0,
+				back_red: 0x0101,
+				back_green: 0x0202,
+				back_blue: 0x0303,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `RecolorCursorAppearance` is x11rb's `RecolorCursorRequest`.
+///
+/// - `target` is `cursor`.
+/// - `foreground_color` and `background_color` are `fore_red`, `fore_green`,
+///   `fore_blue`, `back_red`, `back_green`, and `back_blue`.
+#[test]
+fn test_recolor_cursor_appearance() {
+	assert_compat(
+		&RecolorCursorAppearance {
+			target: CURSOR_APPEARANCE,
+			foreground_color: FOREGROUND,
+			background_color: BACKGROUND,
+		},
+		xproto::RecolorCursorRequest {
+			cursor: CURSOR_APPEARANCE.unwrap(),
+			fore_red: 0xffff,
+			fore_green: 0x8000,
+			fore_blue: 0,
+			back_red: 0x0101,
+			back_green: 0x0202,
+			back_blue: 0x0303,
+		}
+		.serialize(),
+	);
+}
+
+/// `QueryIdealDimensions` is x11rb's `QueryBestSizeRequest`.
+///
+/// - `class` is `class`: `DimensionClass` is x11rb's `QueryShapeOf`.
+/// - `dimensions` is `width` and `height`.
+#[test]
+fn test_query_ideal_dimensions() {
+	for (dimension_class, class) in [
+		(
+			DimensionClass::CursorAppearance,
+			xproto::QueryShapeOf::LARGEST_CURSOR,
+		),
+		(DimensionClass::Tile, xproto::QueryShapeOf::FASTEST_TILE),
+		(
+			DimensionClass::Stipple,
+			xproto::QueryShapeOf::FASTEST_STIPPLE,
+		),
+	] {
+		assert_compat(
+			&QueryIdealDimensions {
+				class: dimension_class,
+				drawable: DRAWABLE,
+				dimensions: Dimensions::new(Px(16), Px(32)),
+			},
+			xproto::QueryBestSizeRequest {
+				class,
+				drawable: DRAWABLE.unwrap(),
+				width: 16,
+				height: 32,
+			}
+			.serialize(),
+		);
+	}
+}
@@ -0,0 +1,659 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::input`].
+
+use std::{borrow::Cow, num::NonZeroU16};
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	set::{DurationOrDefault, KeyboardOptions, Led, LedMode, PercentOrDefault, PitchOrDefault},
+	unit::{Hz, Ms, Px},
+	x11::request::{
+		AllowEvents,
+		AllowEventsMode,
+		ChangeActiveCursorGrab,
+		ChangeCursorOptions,
+		ChangeKeyboardMapping,
+		ChangeKeyboardOptions,
+		ConvertCoordinates,
+		Fraction,
+		GetButtonMapping,
+		GetCursorOptions,
+		GetFocus,
+		GetKeyboardMapping,
+		GetKeyboardOptions,
+		GetModifierMapping,
+		GetMotionHistory,
+		GrabButton,
+		GrabCursor,
+		GrabKey,
+		GrabKeyboard,
+		GrabServer,
+		OrDefault,
+		QueryCursorLocation,
+		QueryKeyboard,
+		RevertFocus,
+		RingBell,
+		SetButtonMapping,
+		SetFocus,
+		SetModifierMapping,
+		UngrabButton,
+		UngrabCursor,
+		UngrabKey,
+		UngrabKeyboard,
+		UngrabServer,
+		WarpCursor,
+		WarpSourceDimension,
+	},
+	Any,
+	AnyModifierKeyMask,
+	Button,
+	Coords,
+	CurrentableTime,
+	CursorEventMask,
+	FocusWindow,
+	FreezeMode,
+	Keycode,
+	Keysym,
+	Modifier,
+	ModifierMap,
+	ToggleOrDefault,
+};
+
+use crate::{assert_compat, CURRENT_TIME, CURSOR_APPEARANCE, NONE, OTHER_WINDOW, TIME, WINDOW};
+
+/// `GrabCursor` is x11rb's `GrabPointerRequest`.
+///
+/// - `cursor_freeze` is `pointer_mode`: `Frozen` is `SYNC`, `Unfrozen` is
+///   `ASYNC`.
+/// - `keyboard_freeze` is `keyboard_mode`.
+/// - `cursor_appearance` is `cursor`.
+#[test]
+fn test_grab_cursor() {
+	assert_compat(
+		&GrabCursor {
+			owner_events: true,
+			grab_window: WINDOW,
+			event_mask: CursorEventMask::BUTTON_PRESS | CursorEventMask::ANY_MOTION,
+			cursor_freeze: FreezeMode::Frozen,
+			keyboard_freeze: FreezeMode::Unfrozen,
+			confine_to: Some(OTHER_WINDOW),
+			cursor_appearance: Some(CURSOR_APPEARANCE),
+			time: CurrentableTime::Other(TIME),
+		},
+		xproto::GrabPointerRequest {
+			owner_events: true,
+			grab_window: WINDOW.unwrap(),
+			event_mask: xproto::EventMask::BUTTON_PRESS | xproto::EventMask::POINTER_MOTION,
+			pointer_mode: xproto::GrabMode::SYNC,
+			keyboard_mode: xproto::GrabMode::ASYNC,
+			confine_to: OTHER_WINDOW.unwrap(),
+			cursor: CURSOR_APPEARANCE.unwrap(),
+			time: TIME.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `UngrabCursor` is x11rb's `UngrabPointerRequest`.
+#[test]
+fn test_ungrab_cursor() {
+	assert_compat(
+		&UngrabCursor {
+			time: CurrentableTime::CurrentTime,
+		},
+		xproto::UngrabPointerRequest { time: CURRENT_TIME }.serialize(),
+	);
+}
+
+/// `GrabButton` is x11rb's `GrabButtonRequest`.
+///
+/// - `cursor_freeze` is `pointer_mode`.
+/// - `keyboard_freeze` is `keyboard_mode`.
+/// - `cursor_appearance` is `cursor`.
+#[test]
+fn test_grab_button() {
+	assert_compat(
+		&GrabButton {
+			owner_events: false,
+			grab_window: WINDOW,
+			event_mask: CursorEventMask::BUTTON_RELEASE,
+			cursor_freeze: FreezeMode::Unfrozen,
+			keyboard_freeze: FreezeMode::Frozen,
+			confine_to: None,
+			cursor_appearance: None,
+			button: Any::Other(Button::new(3)),
+			modifiers: AnyModifierKeyMask::SHIFT | AnyModifierKeyMask::MOD_4,
+		},
+		xproto::GrabButtonRequest {
+			owner_events: false,
+			grab_window: WINDOW.unwrap(),
+			event_mask: xproto::EventMask::BUTTON_RELEASE,
+			pointer_mode: xproto::GrabMode::ASYNC,
+			keyboard_mode: xproto::GrabMode::SYNC,
+			confine_to: NONE,
+			cursor: NONE,
+			button: xproto::ButtonIndex::M3,
+			modifiers: xproto::ModMask::SHIFT | xproto::ModMask::M4,
+		}
+		.serialize(),
+	);
+}
+
+/// `UngrabButton` is x11rb's `UngrabButtonRequest`.
+#[test]
+fn test_ungrab_button() {
+	assert_compat(
+		&UngrabButton {
+			button: Any::Any,
+			grab_window: WINDOW,
+			modifiers: AnyModifierKeyMask::ANY_MODIFIER,
+		},
+		xproto::UngrabButtonRequest {
+			button: xproto::ButtonIndex::ANY,
+			grab_window: WINDOW.unwrap(),
+			modifiers: xproto::ModMask::ANY,
+		}
+		.serialize(),
+	);
+}
+
+/// `ChangeActiveCursorGrab` is x11rb's `ChangeActivePointerGrabRequest`.
+///
+/// - `cursor_appearance` is `cursor`.
+#[test]
+fn test_change_active_cursor_grab() {
+	assert_compat(
+		&ChangeActiveCursorGrab {
+			cursor_appearance: Some(CURSOR_APPEARANCE),
+			time: CurrentableTime::Other(TIME),
+			event_mask: CursorEventMask::ENTER_WINDOW | CursorEventMask::LEAVE_WINDOW,
+		},
+		xproto::ChangeActivePointerGrabRequest {
+			cursor: CURSOR_APPEARANCE.unwrap(),
+			time: TIME.unwrap(),
+			event_mask: xproto::EventMask::ENTER_WINDOW | xproto::EventMask::LEAVE_WINDOW,
+		}
+		.serialize(),
+	);
+}
+
+/// `GrabKeyboard` is x11rb's `GrabKeyboardRequest`.
+///
+/// - `cursor_freeze` is `pointer_mode`.
+/// - `keyboard_freeze` is `keyboard_mode`.
+#[test]
+fn test_grab_keyboard() {
+	assert_compat(
+		&GrabKeyboard {
+			owner_events: true,
+			grab_window: WINDOW,
+			time: CurrentableTime::Other(TIME),
+			cursor_freeze: FreezeMode::Unfrozen,
+			keyboard_freeze: FreezeMode::Frozen,
+		},
+		xproto::GrabKeyboardRequest {
+			owner_events: true,
+			grab_window: WINDOW.unwrap(),
+			time: TIME.unwrap(),
+			pointer_mode: xproto::GrabMode::ASYNC,
+			keyboard_mode: xproto::GrabMode::SYNC,
+		}
+		.serialize(),
+	);
+}
+
+/// `UngrabKeyboard` is x11rb's `UngrabKeyboardRequest`.
+#[test]
+fn test_ungrab_keyboard() {
+	assert_compat(
+		&UngrabKeyboard {
+			time: CurrentableTime::Other(TIME),
+		},
+		xproto::UngrabKeyboardRequest {
+			time: TIME.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `GrabKey` is x11rb's `GrabKeyRequest`.
+///
+/// - `cursor_freeze` is `pointer_mode`.
+/// - `keyboard_freeze` is `keyboard_mode`.
+#[test]
+fn test_grab_key() {
+	assert_compat(
+		&GrabKey {
+			owner_events: false,
+			grab_window: WINDOW,
+			modifiers: AnyModifierKeyMask::CONTROL,
+			key: Any::Other(Keycode::new(38)),
+			cursor_freeze: FreezeMode::Frozen,
+			keyboard_freeze: FreezeMode::Unfrozen,
+		},
+		xproto::GrabKeyRequest {
+			owner_events: false,
+			grab_window: WINDOW.unwrap(),
+			modifiers: xproto::ModMask::CONTROL,
+			key: 38,
+			pointer_mode: xproto::GrabMode::SYNC,
+			keyboard_mode: xproto::GrabMode::ASYNC,
+		}
+		.serialize(),
+	);
+}
+
+/// `UngrabKey` is x11rb's `UngrabKeyRequest`.
+#[test]
+fn test_ungrab_key() {
+	assert_compat(
+		&UngrabKey {
+			key: Any::Any,
+			grab_window: WINDOW,
+			modifiers: AnyModifierKeyMask::LOCK | AnyModifierKeyMask::MOD_1,
+		},
+		xproto::UngrabKeyRequest {
+			key: xproto::Grab::ANY.into(),
+			grab_window: WINDOW.unwrap(),
+			modifiers: xproto::ModMask::LOCK | xproto::ModMask::M1,
+		}
+		.serialize(),
+	);
+}
+
+/// `AllowEvents` is x11rb's `AllowEventsRequest`.
+///
+/// - `AllowEventsMode` is x11rb's `Allow`: `Unfreeze` is `ASYNC`, `Refreeze` is
+///   `SYNC`, and `Cursor` is `POINTER`.
+#[test]
+fn test_allow_events() {
+	for (mode, allow) in [
+		(
+			AllowEventsMode::UnfreezeCursor,
+			xproto::Allow::ASYNC_POINTER,
+		),
+		(AllowEventsMode::RefreezeCursor, xproto::Allow::SYNC_POINTER),
+		(AllowEventsMode::ReplayCursor, xproto::Allow::REPLAY_POINTER),
+		(
+			AllowEventsMode::UnfreezeKeyboard,
+			xproto::Allow::ASYNC_KEYBOARD,
+		),
+		(
+			AllowEventsMode::RefreezeKeyboard,
+			xproto::Allow::SYNC_KEYBOARD,
+		),
+		(
+			AllowEventsMode::ReplayKeyboard,
+			xproto::Allow::REPLAY_KEYBOARD,
+		),
+		(AllowEventsMode::UnfreezeBoth, xproto::Allow::ASYNC_BOTH),
+		(AllowEventsMode::RefreezeBoth, xproto::Allow::SYNC_BOTH),
+	] {
+		assert_compat(
+			&AllowEvents {
+				mode,
+				time: CurrentableTime::Other(TIME),
+			},
+			xproto::AllowEventsRequest {
+				mode: allow,
+				time: TIME.unwrap(),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `GrabServer` and `UngrabServer` are x11rb's `GrabServerRequest` and
+/// `UngrabServerRequest`.
+#[test]
+fn test_grab_server() {
+	assert_compat(&GrabServer, xproto::GrabServerRequest.serialize());
+	assert_compat(&UngrabServer, xproto::UngrabServerRequest.serialize());
+}
+
+/// `QueryCursorLocation` is x11rb's `QueryPointerRequest`.
+///
+/// - `target` is `window`.
+#[test]
+fn test_query_cursor_location() {
+	assert_compat(
+		&QueryCursorLocation { target: WINDOW },
+		xproto::QueryPointerRequest {
+			window: WINDOW.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `GetMotionHistory` is x11rb's `GetMotionEventsRequest`.
+///
+/// - `target` is `window`.
+/// - `end` is `stop`.
+#[test]
+fn test_get_motion_history() {
+	assert_compat(
+		&GetMotionHistory {
+			target: WINDOW,
+			start: CurrentableTime::Other(TIME),
+			end: CurrentableTime::CurrentTime,
+		},
+		xproto::GetMotionEventsRequest {
+			window: WINDOW.unwrap(),
+			start: TIME.unwrap(),
+			stop: CURRENT_TIME,
+		}
+		.serialize(),
+	);
+}
+
+/// `ConvertCoordinates` is x11rb's `TranslateCoordinatesRequest`.
+///
+/// - `original` is `src_window`.
+/// - `output` is `dst_window`.
+/// - `original_coords` is `src_x` and `src_y`.
+#[test]
+fn test_convert_coordinates() {
+	assert_compat(
+		&ConvertCoordinates {
+			original: WINDOW,
+			output: OTHER_WINDOW,
+			original_coords: Coords::new(Px(-20), Px(300)),
+		},
+		xproto::TranslateCoordinatesRequest {
+			src_window: WINDOW.unwrap(),
+			dst_window: OTHER_WINDOW.unwrap(),
+			src_x: -20,
+			src_y: 300,
+		}
+		.serialize(),
+	);
+}
+
+/// `WarpCursor` is x11rb's `WarpPointerRequest`.
+///
+/// - `source` is `src_window`.
+/// - `destination` is `dst_window`.
+/// - `source_coords` is `src_x` and `src_y`.
+/// - `source_width` and `source_height` are `src_width` and `src_height`:
+///   `FillRemaining` is `0`.
+/// - `coords` is `dst_x` and `dst_y`.
+#[test]
+fn test_warp_cursor() {
+	assert_compat(
+		&WarpCursor {
+			source: Some(WINDOW),
+			destination: None,
+			source_coords: Coords::new(Px(1), Px(-2)),
+			source_width: WarpSourceDimension::Other(NonZeroU16::new(640).unwrap()),
+			source_height: WarpSourceDimension::FillRemaining,
+			coords: Coords::new(Px(-30), Px(40)),
+		},
+		xproto::WarpPointerRequest {
+			src_window: WINDOW.unwrap(),
+			dst_window: NONE,
+			src_x: 1,
+			src_y: -2,
+			src_width: 640,
+			src_height: 0,
+			dst_x: -30,
+			dst_y: 40,
+		}
+		.serialize(),
+	);
+}
+
+/// `SetFocus` is x11rb's `SetInputFocusRequest`.
+///
+/// - `RevertFocus` is x11rb's `InputFocus`: `CursorRoot` is `POINTER_ROOT`.
+/// - `new_focus` is `focus`: `FocusWindow::CursorRoot` is `POINTER_ROOT`.
+#[test]
+fn test_set_focus() {
+	assert_compat(
+		&SetFocus {
+			revert_to: RevertFocus::Parent,
+			new_focus: FocusWindow::Other(WINDOW),
+			time: CurrentableTime::Other(TIME),
+		},
+		xproto::SetInputFocusRequest {
+			revert_to: xproto::InputFocus::PARENT,
+			focus: WINDOW.unwrap(),
+			time: TIME.unwrap(),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&SetFocus {
+			revert_to: RevertFocus::CursorRoot,
+			new_focus: FocusWindow::CursorRoot,
+			time: CurrentableTime::CurrentTime,
+		},
+		xproto::SetInputFocusRequest {
+			revert_to: xproto::InputFocus::POINTER_ROOT,
+			focus: u32::from(xproto::InputFocus::POINTER_ROOT),
+			time: CURRENT_TIME,
+		}
+		.serialize(),
+	);
+}
+
+/// Requests without any fields.
+///
+/// - `GetFocus` is x11rb's `GetInputFocusRequest`.
+/// - `QueryKeyboard` is x11rb's `QueryKeymapRequest`.
+/// - `GetKeyboardOptions` is x11rb's `GetKeyboardControlRequest`.
+/// - `GetCursorOptions` is x11rb's `GetPointerControlRequest`.
+/// - `GetButtonMapping` is x11rb's `GetPointerMappingRequest`.
+/// - `GetModifierMapping` is x11rb's `GetModifierMappingRequest`.
+#[test]
+fn test_empty_input_requests() {
+	assert_compat(&GetFocus, xproto::GetInputFocusRequest.serialize());
+	assert_compat(&QueryKeyboard, xproto::QueryKeymapRequest.serialize());
+	assert_compat(
+		&GetKeyboardOptions,
+		xproto::GetKeyboardControlRequest.serialize(),
+	);
+	assert_compat(
+		&GetCursorOptions,
+		xproto::GetPointerControlRequest.serialize(),
+	);
+	assert_compat(
+		&GetButtonMapping,
+		xproto::GetPointerMappingRequest.serialize(),
+	);
+	assert_compat(
+		&GetModifierMapping,
+		xproto::GetModifierMappingRequest.serialize(),
+	);
+}
+
+/// `ChangeKeyboardOptions` is x11rb's `ChangeKeyboardControlRequest`.
+///
+/// - `changed_options` is `value_list`.
+/// - `key_click_volume` is `key_click_percent`.
+/// - `bell_volume` is `bell_percent`.
+/// - `auto_repeated_key` is `key`.
+#[test]
+fn test_change_keyboard_options() {
+	let mut options = KeyboardOptions::builder();
+	options
+		.key_click_volume(PercentOrDefault::new_percent(50).unwrap())
+		.bell_volume(PercentOrDefault::new_default())
+		.bell_pitch(PitchOrDefault::new_pitch(Hz(200)))
+		.bell_duration(DurationOrDefault::new_duration(Ms(100)))
+		.led(Led::new(3).unwrap())
+		.led_mode(LedMode::On)
+		.auto_repeated_key(Keycode::new(38))
+		.auto_repeat_mode(ToggleOrDefault::Enabled);
+
+	assert_compat(
+		&ChangeKeyboardOptions {
+			changed_options: options.build(),
+		},
+		xproto::ChangeKeyboardControlRequest {
+			value_list: Cow::Owned(
+				xproto::ChangeKeyboardControlAux::new()
+					.key_click_percent(50)
+					.bell_percent(-1)
+					.bell_pitch(200)
+					.bell_duration(100)
+					.led(3)
+					.led_mode(xproto::LedMode::ON)
+					.key(38)
+					.auto_repeat_mode(xproto::AutoRepeatMode::ON),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `RingBell` is x11rb's `BellRequest`.
+///
+/// - `volume` is `percent`.
+#[test]
+fn test_ring_bell() {
+	assert_compat(
+		&RingBell::new(-50).unwrap(),
+		xproto::BellRequest { percent: -50 }.serialize(),
+	);
+}
+
+/// `ChangeCursorOptions` is x11rb's `ChangePointerControlRequest`.
+///
+/// - `acceleration` is `acceleration_numerator` and `acceleration_denominator`:
+///   `OrDefault::Default` is `-1`.
+#[test]
+fn test_change_cursor_options() {
+	assert_compat(
+		&ChangeCursorOptions {
+			acceleration: Fraction::<OrDefault<Px<u8>>>::new(
+				OrDefault::Other(Px(3)),
+				OrDefault::Default,
+			)
+			.unwrap(),
+			threshold: OrDefault::Other(Px(4)),
+			do_acceleration: true,
+			do_threshold: false,
+		},
+		xproto::ChangePointerControlRequest {
+			acceleration_numerator: 3,
+			acceleration_denominator: -1,
+			threshold: 4,
+			do_acceleration: true,
+			do_threshold: false,
+		}
+		.serialize(),
+	);
+}
+
+/// `SetButtonMapping` is x11rb's `SetPointerMappingRequest`.
+///
+/// - `mappings` is `map`: a mapping of [`None`] is `0`.
+#[test]
+fn test_set_button_mapping() {
+	for mappings in [
+		vec![],
+		vec![Some(Button::new(3)), None, Some(Button::new(1))],
+		vec![Some(Button::new(1)); 5],
+	] {
+		let map: Vec<u8> = mappings
+			.iter()
+			.map(|mapping| mapping.map_or(0, |button: Button| button.unwrap()))
+			.collect();
+
+		assert_compat(
+			&SetButtonMapping { mappings },
+			xproto::SetPointerMappingRequest {
+				map: Cow::Owned(map),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `GetKeyboardMapping` is x11rb's `GetKeyboardMappingRequest`.
+///
+/// - `range` is `first_keycode` and `count`.
+#[test]
+fn test_get_keyboard_mapping() {
+	assert_compat(
+		&GetKeyboardMapping {
+			range: Keycode::new(8)..=Keycode::new(255),
+		},
+		xproto::GetKeyboardMappingRequest {
+			first_keycode: 8,
+			count: 248,
+		}
+		.serialize(),
+	);
+}
+
+/// `ChangeKeyboardMapping` is x11rb's `ChangeKeyboardMappingRequest`.
+///
+/// - `mappings` is `keycode_count`, `keysyms_per_keycode`, and `keysyms`: the
+///   number of keysyms per keycode is the length of each mapping.
+#[test]
+fn test_change_keyboard_mapping() {
+	let a = Keysym::new(0x0061);
+	let shift_a = Keysym::new(0x0041);
+	let b = Keysym::new(0x0062);
+
+	assert_compat(
+		&ChangeKeyboardMapping {
+			first_keycode: Keycode::new(38),
+			mappings: vec![[a, shift_a, Keysym::NO_SYMBOL], [b, Keysym::NO_SYMBOL, b]],
+		},
+		xproto::ChangeKeyboardMappingRequest {
+			keycode_count: 2,
+			first_keycode: 38,
+			keysyms_per_keycode: 3,
+			keysyms: Cow::Owned(vec![
+				a.unwrap(),
+				shift_a.unwrap(),
+				0,
+				b.unwrap(),
+				0,
+				b.unwrap(),
+			]),
+		}
+		.serialize(),
+	);
+}
+
+/// `SetModifierMapping` is x11rb's `SetModifierMappingRequest`.
+///
+/// - `modifier_map` is `keycodes`: each of the eight rows is padded with zeros
+///   to the length of the longest.
+#[test]
+fn test_set_modifier_mapping() {
+	let mut modifier_map = ModifierMap::builder();
+	modifier_map
+		.keycodes(Modifier::Shift, [Keycode::new(50), Keycode::new(62)])
+		.keycodes(Modifier::Ctrl, [Keycode::new(37)])
+		.keycodes(Modifier::Mod4, [Keycode::new(133), Keycode::new(134)]);
+
+	#[rustfmt::skip]
+	let keycodes = vec![
+		50, 62, // Shift
+		0, 0, // Lock
+		37, 0, // Control
+		0, 0, // Mod1
+		0, 0, // Mod2
+		0, 0, // Mod3
+		133, 134, // Mod4
+		0, 0, // Mod5
+	];
+
+	assert_compat(
+		&SetModifierMapping {
+			modifier_map: modifier_map.build(),
+		},
+		xproto::SetModifierMappingRequest {
+			keycodes: Cow::Owned(keycodes),
+		}
+		.serialize(),
+	);
+}
@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::meta`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	x11::request::{
+		self,
+		AddOrRemove,
+		ChangeSavedWindows,
+		ForceScreenSaver,
+		ForceScreenSaverMode,
+		GetScreenSaver,
+		KillClient,
+		ListExtensions,
+		NoOp,
+		QueryExtension,
+		RetainResourcesMode,
+		SetRetainResourcesMode,
+		SetScreenSaver,
+	},
+	AsciiString,
+	Host,
+	HostAddress,
+	KillClientTarget,
+	String8,
+	Toggle,
+	ToggleOrDefault,
+};
+
+use crate::{assert_compat, WINDOW};
+
+/// `ChangeSavedWindows` is x11rb's `ChangeSaveSetRequest`.
+///
+/// - `change_mode` is `mode`: `AddOrRemove` is x11rb's `SetMode`, where `Add`
+///   is `INSERT` and `Remove` is `DELETE`.
+#[test]
+fn test_change_saved_windows() {
+	for (change_mode, mode) in [
+		(AddOrRemove::Add, xproto::SetMode::INSERT),
+		(AddOrRemove::Remove, xproto::SetMode::DELETE),
+	] {
+		assert_compat(
+			&ChangeSavedWindows {
+				change_mode,
+				window: WINDOW,
+			},
+			xproto::ChangeSaveSetRequest {
+				mode,
+				window: WINDOW.unwrap(),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `QueryExtension` is x11rb's `QueryExtensionRequest`.
+#[test]
+fn test_query_extension() {
+	// Names with and without padding.
+	for name in ["BIG-REQUESTS", "XKEYBOARD"] {
+		assert_compat(
+			&QueryExtension {
+				name: String8::from_latin1(name).unwrap(),
+			},
+			xproto::QueryExtensionRequest {
+				name: Cow::Borrowed(name.as_bytes()),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SetScreenSaver` is x11rb's `SetScreenSaverRequest`.
+///
+/// - `timeout` and `interval` are `-1` for `Delay::Default` and `0` for
+///   `Delay::Disabled`.
+/// - `prefer_blanking` is a `Blanking`: `Disabled` is `NOT_PREFERRED` and
+///   `Enabled` is `PREFERRED`.
+/// - `allow_expose_events` is `allow_exposures`, an `Exposures`: `Disabled` is
+///   `NOT_ALLOWED` and `Enabled` is `ALLOWED`.
+#[test]
+fn test_set_screen_saver() {
+	assert_compat(
+		&SetScreenSaver::new(600, -1, ToggleOrDefault::Enabled, ToggleOrDefault::Default).unwrap(),
+		xproto::SetScreenSaverRequest {
+			timeout: 600,
+			interval: -1,
+			prefer_blanking: xproto::Blanking::PREFERRED,
+			allow_exposures: xproto::Exposures::DEFAULT,
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&SetScreenSaver::new(0, 30, ToggleOrDefault::Default, ToggleOrDefault::Disabled).unwrap(),
+		xproto::SetScreenSaverRequest {
+			timeout: 0,
+			interval: 30,
+			prefer_blanking: xproto::Blanking::DEFAULT,
+			allow_exposures: xproto::Exposures::NOT_ALLOWED,
+		}
+		.serialize(),
+	);
+}
+
+/// `ChangeHosts` is x11rb's `ChangeHostsRequest`.
+///
+/// - `mode` is a `HostMode`: `Add` is `INSERT` and `Remove` is `DELETE`.
+/// - `host` is `family` and `address`: `HostAddress::Ipv4` is `INTERNET`,
+///   `HostAddress::Ipv6` is `INTERNET6`, and a `ServerInterpreted` address is
+///   its type and value separated by a zero byte.
+#[test]
+#[allow(deprecated)]
+fn test_change_hosts() {
+	let ipv6 = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+	for (mode, host_mode, address, family, bytes) in [
+		(
+			AddOrRemove::Add,
+			xproto::HostMode::INSERT,
+			HostAddress::Ipv4([192, 168, 0, 1]),
+			xproto::Family::INTERNET,
+			&[192, 168, 0, 1][..],
+		),
+		(
+			AddOrRemove::Remove,
+			xproto::HostMode::DELETE,
+			HostAddress::Ipv6(ipv6),
+			xproto::Family::INTERNET6,
+			&ipv6,
+		),
+		(
+			AddOrRemove::Add,
+			xproto::HostMode::INSERT,
+			HostAddress::ServerInterpreted {
+				address_type: AsciiString::new(b"localuser".to_vec()).unwrap(),
+				address_value: AsciiString::new(b"root".to_vec()).unwrap(),
+			},
+			xproto::Family::SERVER_INTERPRETED,
+			b"localuser\0root",
+		),
+	] {
+		assert_compat(
+			&request::ChangeHosts {
+				mode,
+				host: Host::new(address),
+			},
+			xproto::ChangeHostsRequest {
+				mode: host_mode,
+				family,
+				address: Cow::Borrowed(bytes),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SetAccessControl` is x11rb's `SetAccessControlRequest`.
+///
+/// - `mode` is an `AccessControl`: `Disabled` is `DISABLE` and `Enabled` is
+///   `ENABLE`.
+#[test]
+#[allow(deprecated)]
+fn test_set_access_control() {
+	for (mode, access_control) in [
+		(Toggle::Disabled, xproto::AccessControl::DISABLE),
+		(Toggle::Enabled, xproto::AccessControl::ENABLE),
+	] {
+		assert_compat(
+			&request::SetAccessControl { mode },
+			xproto::SetAccessControlRequest {
+				mode: access_control,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SetRetainResourcesMode` is x11rb's `SetCloseDownModeRequest`.
+///
+/// - `RetainResourcesMode` is x11rb's `CloseDown`: `Destroy` is `DESTROY_ALL`,
+///   `RetainPermanently` is `RETAIN_PERMANENT`, and `RetainTemporarily` is
+///   `RETAIN_TEMPORARY`.
+#[test]
+fn test_set_retain_resources_mode() {
+	for (mode, close_down) in [
+		(RetainResourcesMode::Destroy, xproto::CloseDown::DESTROY_ALL),
+		(
+			RetainResourcesMode::RetainPermanently,
+			xproto::CloseDown::RETAIN_PERMANENT,
+		),
+		(
+			RetainResourcesMode::RetainTemporarily,
+			xproto::CloseDown::RETAIN_TEMPORARY,
+		),
+	] {
+		assert_compat(
+			&SetRetainResourcesMode { mode },
+			xproto::SetCloseDownModeRequest { mode: close_down }.serialize(),
+		);
+	}
+}
+
+/// `KillClient` is x11rb's `KillClientRequest`.
+///
+/// - `target` is `resource`:
+///   `KillClientTarget::DestroyTemporarilyRetainedResources` is
+///   `Kill::ALL_TEMPORARY`.
+#[test]
+fn test_kill_client() {
+	assert_compat(
+		&KillClient {
+			target: KillClientTarget::KillClient {
+				resource: WINDOW.unwrap(),
+			},
+		},
+		xproto::KillClientRequest {
+			resource: WINDOW.unwrap(),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&KillClient {
+			target: KillClientTarget::DestroyTemporarilyRetainedResources,
+		},
+		xproto::KillClientRequest {
+			resource: u32::from(xproto::Kill::ALL_TEMPORARY),
+		}
+		.serialize(),
+	);
+}
+
+/// `ForceScreenSaver` is x11rb's `ForceScreenSaverRequest`.
+///
+/// - `ForceScreenSaverMode` is x11rb's `ScreenSaver`: `Reset` is `RESET` and
+///   `Activate` is `ACTIVE`.
+#[test]
+fn test_force_screen_saver() {
+	for (mode, screen_saver) in [
+		(ForceScreenSaverMode::Reset, xproto::ScreenSaver::RESET),
+		(ForceScreenSaverMode::Activate, xproto::ScreenSaver::ACTIVE),
+	] {
+		assert_compat(
+			&ForceScreenSaver { mode },
+			xproto::ForceScreenSaverRequest { mode: screen_saver }.serialize(),
+		);
+	}
+}
+
+/// Requests without any fields.
+///
+/// - `ListExtensions` is x11rb's `ListExtensionsRequest`.
+/// - `GetScreenSaver` is x11rb's `GetScreenSaverRequest`.
+/// - `QueryAccessControl` is x11rb's `ListHostsRequest`.
+/// - `NoOp` is x11rb's `NoOperationRequest`, which has no `unused_units`.
+#[test]
+#[allow(deprecated)]
+fn test_empty_meta_requests() {
+	assert_compat(&ListExtensions, xproto::ListExtensionsRequest.serialize());
+	assert_compat(&GetScreenSaver, xproto::GetScreenSaverRequest.serialize());
+	assert_compat(
+		&request::QueryAccessControl,
+		xproto::ListHostsRequest.serialize(),
+	);
+	assert_compat(
+		&NoOp { unused_units: 0 },
+		xproto::NoOperationRequest.serialize(),
+	);
+}
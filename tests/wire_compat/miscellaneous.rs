@@ -0,0 +1,368 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::miscellaneous`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	unit::Px,
+	x11::{
+		event::Expose,
+		request::{
+			ConvertSelection,
+			DataList,
+			DeleteProperty,
+			GetAtom,
+			GetAtomName,
+			GetProperty,
+			GetSelectionOwner,
+			ListProperties,
+			ModifyProperty,
+			ModifyPropertyMode,
+			RotateProperties,
+			SendEvent,
+			SetSelectionOwner,
+		},
+	},
+	Any,
+	Atom,
+	CurrentableTime,
+	DestinationWindow,
+	EventMask,
+	Region,
+	String8,
+};
+
+use crate::{assert_compat, CURRENT_TIME, NONE, OTHER_WINDOW, TIME, WINDOW};
+
+const PROPERTY: Atom = Atom::new(0x0000_0120);
+const OTHER_PROPERTY: Atom = Atom::new(0x0000_0121);
+const TYPE: Atom = Atom::new(0x0000_0122);
+
+/// `GetAtom` is x11rb's `InternAtomRequest`.
+///
+/// - `no_creation` is `only_if_exists`.
+#[test]
+fn test_get_atom() {
+	for (no_creation, name) in [(false, "WM_PROTOCOLS"), (true, "_NET_WM_NAME")] {
+		assert_compat(
+			&GetAtom {
+				no_creation,
+				name: String8::from_latin1(name).unwrap(),
+			},
+			xproto::InternAtomRequest {
+				only_if_exists: no_creation,
+				name: Cow::Borrowed(name.as_bytes()),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `GetAtomName` is x11rb's `GetAtomNameRequest`.
+///
+/// - `target` is `atom`.
+#[test]
+fn test_get_atom_name() {
+	assert_compat(
+		&GetAtomName { target: PROPERTY },
+		xproto::GetAtomNameRequest {
+			atom: PROPERTY.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `ModifyProperty` is x11rb's `ChangePropertyRequest`.
+///
+/// - `modify_mode` is `mode`: `ModifyPropertyMode` is x11rb's `PropMode`.
+/// - `target` is `window`.
+/// - `type` is `type_`.
+/// - `data` is `format`, `data_len`, and `data`: x11rb takes the raw bytes of
+///   the data.
+#[test]
+fn test_modify_property() {
+	let i8s = vec![1, -2, 3];
+	let i16s = vec![-300, 400, 0x0102];
+	let i32s = vec![0x0102_0304, -5];
+
+	for (modify_mode, mode, data, format, data_len, bytes) in [
+		(
+			ModifyPropertyMode::Replace,
+			xproto::PropMode::REPLACE,
+			DataList::I8(i8s.clone()),
+			8,
+			3,
+			i8s.iter()
+				.flat_map(|value| value.to_ne_bytes())
+				.collect::<Vec<_>>(),
+		),
+		(
+			ModifyPropertyMode::Prepend,
+			xproto::PropMode::PREPEND,
+			DataList::I16(i16s.clone()),
+			16,
+			3,
+			i16s.iter().flat_map(|value| value.to_ne_bytes()).collect(),
+		),
+		(
+			ModifyPropertyMode::Append,
+			xproto::PropMode::APPEND,
+			DataList::I32(i32s.clone()),
+			32,
+			2,
+			i32s.iter().flat_map(|value| value.to_ne_bytes()).collect(),
+		),
+	] {
+		assert_compat(
+			&ModifyProperty {
+				modify_mode,
+				target: WINDOW,
+				property: PROPERTY,
+				r#type: TYPE,
+				data,
+			},
+			xproto::ChangePropertyRequest {
+				mode,
+				window: WINDOW.unwrap(),
+				property: PROPERTY.unwrap(),
+				type_: TYPE.unwrap(),
+				format,
+				data_len,
+				data: Cow::Owned(bytes),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `DeleteProperty` is x11rb's `DeletePropertyRequest`.
+///
+/// - `target` is `window`.
+#[test]
+fn test_delete_property() {
+	assert_compat(
+		&DeleteProperty {
+			target: WINDOW,
+			property: PROPERTY,
+		},
+		xproto::DeletePropertyRequest {
+			window: WINDOW.unwrap(),
+			property: PROPERTY.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `GetProperty` is x11rb's `GetPropertyRequest`.
+///
+/// - `target` is `window`.
+/// - `type` is `type_`: `Any::Any` is `AtomEnum::ANY`.
+/// - `offset` is `long_offset`.
+/// - `length` is `long_length`.
+#[test]
+fn test_get_property() {
+	for (delete, r#type, type_) in [
+		(false, Any::Any, u32::from(xproto::AtomEnum::ANY)),
+		(true, Any::Other(TYPE), TYPE.unwrap()),
+	] {
+		assert_compat(
+			&GetProperty {
+				delete,
+				target: WINDOW,
+				property: PROPERTY,
+				r#type,
+				offset: 2,
+				length: 1024,
+			},
+			xproto::GetPropertyRequest {
+				delete,
+				window: WINDOW.unwrap(),
+				property: PROPERTY.unwrap(),
+				type_,
+				long_offset: 2,
+				long_length: 1024,
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `ListProperties` is x11rb's `ListPropertiesRequest`.
+///
+/// - `target` is `window`.
+#[test]
+fn test_list_properties() {
+	assert_compat(
+		&ListProperties { target: WINDOW },
+		xproto::ListPropertiesRequest {
+			window: WINDOW.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `SetSelectionOwner` is x11rb's `SetSelectionOwnerRequest`.
+///
+/// - `new_owner` is `owner`.
+#[test]
+fn test_set_selection_owner() {
+	assert_compat(
+		&SetSelectionOwner {
+			new_owner: Some(WINDOW),
+			selection: PROPERTY,
+			time: CurrentableTime::Other(TIME),
+		},
+		xproto::SetSelectionOwnerRequest {
+			owner: WINDOW.unwrap(),
+			selection: PROPERTY.unwrap(),
+			time: TIME.unwrap(),
+		}
+		.serialize(),
+	);
+
+	assert_compat(
+		&SetSelectionOwner {
+			new_owner: None,
+			selection: PROPERTY,
+			time: CurrentableTime::CurrentTime,
+		},
+		xproto::SetSelectionOwnerRequest {
+			owner: NONE,
+			selection: PROPERTY.unwrap(),
+			time: CURRENT_TIME,
+		}
+		.serialize(),
+	);
+}
+
+/// `GetSelectionOwner` is x11rb's `GetSelectionOwnerRequest`.
+///
+/// - `target` is `selection`.
+#[test]
+fn test_get_selection_owner() {
+	assert_compat(
+		&GetSelectionOwner { target: PROPERTY },
+		xproto::GetSelectionOwnerRequest {
+			selection: PROPERTY.unwrap(),
+		}
+		.serialize(),
+	);
+}
+
+/// `ConvertSelection` is x11rb's `ConvertSelectionRequest`.
+///
+/// - `requester` is `requestor`.
+/// - `target_type` is `target`.
+#[test]
+fn test_convert_selection() {
+	for (property, x11rb_property) in [
+		(Some(OTHER_PROPERTY), OTHER_PROPERTY.unwrap()),
+		(None, NONE),
+	] {
+		assert_compat(
+			&ConvertSelection {
+				requester: WINDOW,
+				selection: PROPERTY,
+				target_type: TYPE,
+				property,
+				time: CurrentableTime::Other(TIME),
+			},
+			xproto::ConvertSelectionRequest {
+				requestor: WINDOW.unwrap(),
+				selection: PROPERTY.unwrap(),
+				target: TYPE.unwrap(),
+				property: x11rb_property,
+				time: TIME.unwrap(),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `SendEvent` is x11rb's `SendEventRequest`.
+///
+/// - `destination` is a `SendEventDest`: `Cursor` is `POINTER_WINDOW` and
+///   `Focus` is `ITEM_FOCUS`.
+/// - `event` is the 32 bytes of the event: here, x11rb's `ExposeEvent`, where
+///   `region` is `x`, `y`, `width`, and `height`.
+#[test]
+fn test_send_event() {
+	let event = || Expose {
+		sequence: 0,
+		window: WINDOW,
+		region: Region::new(Px(10), Px(20), Px(300), Px(400)),
+		count: 2,
+	};
+	let x11rb_event: [u8; 32] = xproto::ExposeEvent {
+		response_type: xproto::EXPOSE_EVENT,
+		sequence: 0,
+		window: WINDOW.unwrap(),
+		x: 10,
+		y: 20,
+		width: 300,
+		height: 400,
+		count: 2,
+	}
+	.into();
+
+	for (destination, x11rb_destination) in [
+		(
+			DestinationWindow::Cursor,
+			u32::from(xproto::SendEventDest::POINTER_WINDOW),
+		),
+		(
+			DestinationWindow::Focus,
+			u32::from(xproto::SendEventDest::ITEM_FOCUS),
+		),
+		(
+			DestinationWindow::Other(OTHER_WINDOW),
+			OTHER_WINDOW.unwrap(),
+		),
+	] {
+		assert_compat(
+			&SendEvent {
+				propagate: true,
+				destination,
+				event_mask: EventMask::EXPOSURE,
+				event: event(),
+			},
+			xproto::SendEventRequest {
+				propagate: true,
+				destination: x11rb_destination,
+				event_mask: xproto::EventMask::EXPOSURE,
+				event: Cow::Borrowed(&x11rb_event),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `RotateProperties` is x11rb's `RotatePropertiesRequest`.
+///
+/// - `target` is `window`.
+/// - `shift` is `delta`.
+/// - `properties` is `atoms`.
+#[test]
+fn test_rotate_properties() {
+	assert_compat(
+		&RotateProperties {
+			target: WINDOW,
+			shift: -1,
+			properties: vec![PROPERTY, OTHER_PROPERTY, TYPE],
+		},
+		xproto::RotatePropertiesRequest {
+			window: WINDOW.unwrap(),
+			delta: -1,
+			atoms: Cow::Owned(vec![
+				PROPERTY.unwrap(),
+				OTHER_PROPERTY.unwrap(),
+				TYPE.unwrap(),
+			]),
+		}
+		.serialize(),
+	);
+}
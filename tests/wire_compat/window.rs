@@ -0,0 +1,335 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tests for the requests in [`xrb::x11::request::window`].
+
+use std::borrow::Cow;
+
+use x11rb_protocol::protocol::xproto;
+use xrb::{
+	set::{Attributes, WindowConfig},
+	unit::Px,
+	visual::{ColorId, VisualId},
+	x11::request::{
+		ChangeWindowAttributes,
+		CirculateDirection,
+		CirculateWindow,
+		ConfigureWindow,
+		CreateWindow,
+		DestroyChildren,
+		DestroyWindow,
+		GetGeometry,
+		GetWindowAttributes,
+		MapChildren,
+		MapWindow,
+		QueryWindowTree,
+		ReparentWindow,
+		UnmapChildren,
+		UnmapWindow,
+	},
+	BitGravity,
+	Colormap,
+	Coords,
+	CopyableFromParent,
+	DeviceEventMask,
+	Drawable,
+	EventMask,
+	MaintainContents,
+	ParentRelatable,
+	Pixmap,
+	Rectangle,
+	StackMode,
+	WindowClass,
+	WindowGravity,
+};
+
+use crate::{assert_compat, CURSOR_APPEARANCE, OTHER_WINDOW, WINDOW};
+
+/// `CreateWindow` is x11rb's `CreateWindowRequest`.
+///
+/// - `window_id` is `wid`.
+/// - `geometry` is `x`, `y`, `width`, and `height`.
+/// - `attributes` is `value_list`; see [`test_change_window_attributes`] for
+///   the attributes.
+#[test]
+fn test_create_window() {
+	let mut attributes = Attributes::builder();
+	attributes
+		.background_color(ColorId::new(0x00ff_ffff))
+		.bit_gravity(BitGravity::NorthWest)
+		.event_mask(EventMask::EXPOSURE);
+
+	assert_compat(
+		&CreateWindow {
+			depth: CopyableFromParent::Other(24),
+			window_id: OTHER_WINDOW,
+			parent: WINDOW,
+			geometry: Rectangle::new(Px(-10), Px(20), Px(484), Px(316)),
+			border_width: Px(1),
+			class: CopyableFromParent::Other(WindowClass::InputOutput),
+			visual: CopyableFromParent::Other(VisualId::new(0x21)),
+			attributes: attributes.build(),
+		},
+		xproto::CreateWindowRequest {
+			depth: 24,
+			wid: OTHER_WINDOW.unwrap(),
+			parent: WINDOW.unwrap(),
+			x: -10,
+			y: 20,
+			width: 484,
+			height: 316,
+			border_width: 1,
+			class: xproto::WindowClass::INPUT_OUTPUT,
+			visual: 0x21,
+			value_list: Cow::Owned(
+				xproto::CreateWindowAux::new()
+					.background_pixel(0x00ff_ffff)
+					.bit_gravity(xproto::Gravity::NORTH_WEST)
+					.event_mask(xproto::EventMask::EXPOSURE),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `ChangeWindowAttributes` is x11rb's `ChangeWindowAttributesRequest`.
+///
+/// - `target` is `window`.
+/// - `attributes` is `value_list`:
+///   - `background_color` and `border_color` are `background_pixel` and
+///     `border_pixel`.
+///   - `window_gravity` is `win_gravity`.
+///   - `maintain_contents` is `backing_store`.
+///   - `maintained_planes` is `backing_planes`.
+///   - `maintenance_fallback_color` is `backing_pixel`.
+///   - `maintain_windows_under` is `save_under`.
+///   - `do_not_propagate_mask` is `do_not_propogate_mask`.
+///   - `cursor_appearance` is `cursor`.
+#[test]
+fn test_change_window_attributes() {
+	let mut attributes = Attributes::builder();
+	attributes
+		.background_pixmap(ParentRelatable::ParentRelative)
+		.background_color(ColorId::new(0x00ff_ffff))
+		.border_pixmap(CopyableFromParent::Other(Pixmap::new(0x0040_0004)))
+		.border_color(ColorId::ZERO)
+		.bit_gravity(BitGravity::Static)
+		.window_gravity(WindowGravity::SouthEast)
+		.maintain_contents(MaintainContents::WhenMapped)
+		.maintained_planes(0x0000_00ff)
+		.maintenance_fallback_color(ColorId::ONE)
+		.override_redirect(true)
+		.maintain_windows_under(false)
+		.event_mask(EventMask::STRUCTURE_NOTIFY | EventMask::PROPERTY_CHANGE)
+		.do_not_propagate_mask(DeviceEventMask::BUTTON_PRESS)
+		.colormap(CopyableFromParent::Other(Colormap::new(0x20)))
+		.cursor_appearance(Some(CURSOR_APPEARANCE));
+
+	assert_compat(
+		&ChangeWindowAttributes {
+			target: WINDOW,
+			attributes: attributes.build(),
+		},
+		xproto::ChangeWindowAttributesRequest {
+			window: WINDOW.unwrap(),
+			value_list: Cow::Owned(
+				xproto::ChangeWindowAttributesAux::new()
+					.background_pixmap(xproto::BackPixmap::PARENT_RELATIVE)
+					.background_pixel(0x00ff_ffff)
+					.border_pixmap(0x0040_0004)
+					.border_pixel(0)
+					.bit_gravity(xproto::Gravity::STATIC)
+					.win_gravity(xproto::Gravity::SOUTH_EAST)
+					.backing_store(xproto::BackingStore::WHEN_MAPPED)
+					.backing_planes(0x0000_00ff)
+					.backing_pixel(1)
+					.override_redirect(1)
+					.save_under(0)
+					.event_mask(
+						xproto::EventMask::STRUCTURE_NOTIFY | xproto::EventMask::PROPERTY_CHANGE,
+					)
+					.do_not_propogate_mask(xproto::EventMask::BUTTON_PRESS)
+					.colormap(0x20)
+					.cursor(CURSOR_APPEARANCE.unwrap()),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// Requests whose only field is the `target` [window], which is x11rb's
+/// `window`.
+///
+/// - `GetWindowAttributes` is x11rb's `GetWindowAttributesRequest`.
+/// - `DestroyWindow` is x11rb's `DestroyWindowRequest`.
+/// - `DestroyChildren` is x11rb's `DestroySubwindowsRequest`.
+/// - `MapWindow` is x11rb's `MapWindowRequest`.
+/// - `MapChildren` is x11rb's `MapSubwindowsRequest`.
+/// - `UnmapWindow` is x11rb's `UnmapWindowRequest`.
+/// - `UnmapChildren` is x11rb's `UnmapSubwindowsRequest`.
+/// - `QueryWindowTree` is x11rb's `QueryTreeRequest`.
+///
+/// [window]: xrb::Window
+#[test]
+fn test_window_target_requests() {
+	let window = WINDOW.unwrap();
+
+	assert_compat(
+		&GetWindowAttributes { target: WINDOW },
+		xproto::GetWindowAttributesRequest { window }.serialize(),
+	);
+	assert_compat(
+		&DestroyWindow { target: WINDOW },
+		xproto::DestroyWindowRequest { window }.serialize(),
+	);
+	assert_compat(
+		&DestroyChildren { target: WINDOW },
+		xproto::DestroySubwindowsRequest { window }.serialize(),
+	);
+	assert_compat(
+		&MapWindow { target: WINDOW },
+		xproto::MapWindowRequest { window }.serialize(),
+	);
+	assert_compat(
+		&MapChildren { target: WINDOW },
+		xproto::MapSubwindowsRequest { window }.serialize(),
+	);
+	assert_compat(
+		&UnmapWindow { target: WINDOW },
+		xproto::UnmapWindowRequest { window }.serialize(),
+	);
+	assert_compat(
+		&UnmapChildren { target: WINDOW },
+		xproto::UnmapSubwindowsRequest { window }.serialize(),
+	);
+	assert_compat(
+		&QueryWindowTree { target: WINDOW },
+		xproto::QueryTreeRequest { window }.serialize(),
+	);
+}
+
+/// `ReparentWindow` is x11rb's `ReparentWindowRequest`.
+///
+/// - `target` is `window`.
+/// - `new_parent` is `parent`.
+/// - `coords` is `x` and `y`.
+#[test]
+fn test_reparent_window() {
+	assert_compat(
+		&ReparentWindow {
+			target: WINDOW,
+			new_parent: OTHER_WINDOW,
+			coords: Coords::new(Px(-5), Px(12)),
+		},
+		xproto::ReparentWindowRequest {
+			window: WINDOW.unwrap(),
+			parent: OTHER_WINDOW.unwrap(),
+			x: -5,
+			y: 12,
+		}
+		.serialize(),
+	);
+}
+
+/// `ConfigureWindow` is x11rb's `ConfigureWindowRequest`.
+///
+/// - `target` is `window`.
+/// - `config` is `value_list`.
+#[test]
+fn test_configure_window() {
+	let mut config = WindowConfig::builder();
+	config
+		.x(Px(-100))
+		.y(Px(200))
+		.width(Px(640))
+		.height(Px(480))
+		.border_width(Px(2))
+		.sibling(OTHER_WINDOW)
+		.stack_mode(StackMode::Opposite);
+
+	assert_compat(
+		&ConfigureWindow {
+			target: WINDOW,
+			config: config.build(),
+		},
+		xproto::ConfigureWindowRequest {
+			window: WINDOW.unwrap(),
+			value_list: Cow::Owned(
+				xproto::ConfigureWindowAux::new()
+					.x(-100)
+					.y(200)
+					.width(640)
+					.height(480)
+					.border_width(2)
+					.sibling(OTHER_WINDOW.unwrap())
+					.stack_mode(xproto::StackMode::OPPOSITE),
+			),
+		}
+		.serialize(),
+	);
+
+	// Only the options which are set are written.
+	let mut config = WindowConfig::builder();
+	config.stack_mode(StackMode::Below);
+
+	assert_compat(
+		&ConfigureWindow {
+			target: WINDOW,
+			config: config.build(),
+		},
+		xproto::ConfigureWindowRequest {
+			window: WINDOW.unwrap(),
+			value_list: Cow::Owned(
+				xproto::ConfigureWindowAux::new().stack_mode(xproto::StackMode::BELOW),
+			),
+		}
+		.serialize(),
+	);
+}
+
+/// `CirculateWindow` is x11rb's `CirculateWindowRequest`.
+///
+/// - `CirculateDirection` is x11rb's `Circulate`.
+/// - `target` is `window`.
+#[test]
+fn test_circulate_window() {
+	for (direction, circulate) in [
+		(
+			CirculateDirection::RaiseLowest,
+			xproto::Circulate::RAISE_LOWEST,
+		),
+		(
+			CirculateDirection::LowerHighest,
+			xproto::Circulate::LOWER_HIGHEST,
+		),
+	] {
+		assert_compat(
+			&CirculateWindow {
+				direction,
+				target: WINDOW,
+			},
+			xproto::CirculateWindowRequest {
+				direction: circulate,
+				window: WINDOW.unwrap(),
+			}
+			.serialize(),
+		);
+	}
+}
+
+/// `GetGeometry` is x11rb's `GetGeometryRequest`.
+///
+/// - `target` is `drawable`.
+#[test]
+fn test_get_geometry() {
+	assert_compat(
+		&GetGeometry {
+			target: Drawable::from(WINDOW),
+		},
+		xproto::GetGeometryRequest {
+			drawable: WINDOW.unwrap(),
+		}
+		.serialize(),
+	);
+}
@@ -0,0 +1,597 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Support for reading and writing in either byte order.
+//!
+//! Every [`Readable`] and [`Writable`] implementation reads and writes
+//! multi-byte integers through the [`Buf`] and [`BufMut`] methods `get_u16`,
+//! `put_u32`, and so on, which are big endian. [`Ordered`] overrides those
+//! methods to use a [`ByteOrder`] chosen at runtime, so that any existing
+//! implementation can be used with a little endian connection without
+//! modification.
+//!
+//! Wrappers such as [`bytes::buf::Take`] do not forward those methods to
+//! the buffer they wrap, and so would lose the chosen [`ByteOrder`]. [`take`]
+//! and [`limit`] should be used instead: `Buf::take`, `BufMut::limit`,
+//! `Buf::chain`, and `BufMut::chain_mut` are disallowed in this workspace's
+//! `clippy.toml`.
+//!
+//! For the same reason, bytes must not be copied out of a buffer into a slice
+//! and then read from that slice: a slice is always read as big endian. Read
+//! from the original buffer instead, using [`take`] if the number of bytes
+//! must be limited.
+//!
+//! [`Readable`]: crate::Readable
+//! [`Writable`]: crate::Writable
+
+use bytes::{buf::UninitSlice, Buf, BufMut};
+
+/// The order in which the bytes of multi-byte integers are sent.
+///
+/// The byte order used for a connection is chosen by the client when it first
+/// connects, and applies to every message sent in either direction for that
+/// connection.
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub enum ByteOrder {
+	/// The most significant byte is sent first.
+	#[default]
+	BigEndian,
+	/// The least significant byte is sent first.
+	LittleEndian,
+}
+
+/// A [`Buf`] or [`BufMut`] which reads and writes multi-byte integers in the
+/// given [`ByteOrder`].
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct Ordered<B> {
+	inner: B,
+	order: ByteOrder,
+}
+
+impl<B> Ordered<B> {
+	/// Wraps the given `inner` buffer so that multi-byte integers are read and
+	/// written in the given byte `order`.
+	pub const fn new(inner: B, order: ByteOrder) -> Self {
+		Self { inner, order }
+	}
+
+	/// Returns the [`ByteOrder`] used by this buffer.
+	#[must_use]
+	pub const fn order(&self) -> ByteOrder {
+		self.order
+	}
+
+	/// Returns a reference to the wrapped buffer.
+	#[must_use]
+	pub const fn get_ref(&self) -> &B {
+		&self.inner
+	}
+
+	/// Returns a mutable reference to the wrapped buffer.
+	pub const fn get_mut(&mut self) -> &mut B {
+		&mut self.inner
+	}
+
+	/// Consumes this `Ordered`, returning the wrapped buffer.
+	pub fn into_inner(self) -> B {
+		self.inner
+	}
+}
+
+/// Generates overrides of [`Buf`] methods which dispatch to either the big
+/// endian or little endian method according to `self.order`.
+macro_rules! ordered_gets {
+	($($get:ident, $get_le:ident -> $ty:ty;)*) => {
+		$(
+			fn $get(&mut self) -> $ty {
+				match self.order {
+					ByteOrder::BigEndian => self.inner.$get(),
+					ByteOrder::LittleEndian => self.inner.$get_le(),
+				}
+			}
+		)*
+	};
+}
+
+/// Generates overrides of [`BufMut`] methods which dispatch to either the big
+/// endian or little endian method according to `self.order`.
+macro_rules! ordered_puts {
+	($($put:ident, $put_le:ident($ty:ty);)*) => {
+		$(
+			fn $put(&mut self, n: $ty) {
+				match self.order {
+					ByteOrder::BigEndian => self.inner.$put(n),
+					ByteOrder::LittleEndian => self.inner.$put_le(n),
+				}
+			}
+		)*
+	};
+}
+
+impl<B: Buf> Buf for Ordered<B> {
+	fn remaining(&self) -> usize {
+		self.inner.remaining()
+	}
+
+	fn chunk(&self) -> &[u8] {
+		self.inner.chunk()
+	}
+
+	fn advance(&mut self, cnt: usize) {
+		self.inner.advance(cnt);
+	}
+
+	ordered_gets! {
+		get_u16, get_u16_le -> u16;
+		get_i16, get_i16_le -> i16;
+		get_u32, get_u32_le -> u32;
+		get_i32, get_i32_le -> i32;
+		get_u64, get_u64_le -> u64;
+		get_i64, get_i64_le -> i64;
+		get_u128, get_u128_le -> u128;
+		get_i128, get_i128_le -> i128;
+		get_f32, get_f32_le -> f32;
+		get_f64, get_f64_le -> f64;
+	}
+}
+
+// SAFETY: every method which affects the initialized bytes or the cursor is
+//         forwarded directly to `inner`.
+unsafe impl<B: BufMut> BufMut for Ordered<B> {
+	fn remaining_mut(&self) -> usize {
+		self.inner.remaining_mut()
+	}
+
+	unsafe fn advance_mut(&mut self, cnt: usize) {
+		// SAFETY: the caller upholds the same contract for `inner`.
+		unsafe { self.inner.advance_mut(cnt) }
+	}
+
+	fn chunk_mut(&mut self) -> &mut UninitSlice {
+		self.inner.chunk_mut()
+	}
+
+	ordered_puts! {
+		put_u16, put_u16_le(u16);
+		put_i16, put_i16_le(i16);
+		put_u32, put_u32_le(u32);
+		put_i32, put_i32_le(i32);
+		put_u64, put_u64_le(u64);
+		put_i64, put_i64_le(i64);
+		put_u128, put_u128_le(u128);
+		put_i128, put_i128_le(i128);
+		put_f32, put_f32_le(f32);
+		put_f64, put_f64_le(f64);
+	}
+}
+
+/// A [`Buf`] which reads no more than a limited number of bytes from the
+/// buffer it wraps.
+///
+/// Unlike [`bytes::buf::Take`], this forwards the reading of multi-byte
+/// integers to the wrapped buffer, so that an [`Ordered`] buffer's
+/// [`ByteOrder`] is respected.
+///
+/// This is created with [`take`].
+#[derive(Debug)]
+pub struct Take<'a, B> {
+	inner: &'a mut B,
+	limit: usize,
+}
+
+/// Returns a [`Take`] which reads no more than `limit` bytes from `buf`.
+#[must_use]
+pub const fn take<B: Buf>(buf: &mut B, limit: usize) -> Take<'_, B> {
+	Take { inner: buf, limit }
+}
+
+impl<B> Take<'_, B> {
+	/// Returns the maximum number of bytes which can still be read.
+	#[must_use]
+	pub const fn limit(&self) -> usize {
+		self.limit
+	}
+}
+
+/// Generates overrides of [`Buf`] methods which forward to the inner buffer,
+/// panicking if the read would exceed the limit.
+macro_rules! forwarded_gets {
+	($($get:ident -> $ty:ty;)*) => {
+		$(
+			fn $get(&mut self) -> $ty {
+				let size = core::mem::size_of::<$ty>();
+				assert!(size <= self.limit, "attempted to read past the limit");
+
+				self.limit -= size;
+				self.inner.$get()
+			}
+		)*
+	};
+}
+
+impl<B: Buf> Buf for Take<'_, B> {
+	fn remaining(&self) -> usize {
+		self.inner.remaining().min(self.limit)
+	}
+
+	fn chunk(&self) -> &[u8] {
+		let chunk = self.inner.chunk();
+		&chunk[..chunk.len().min(self.limit)]
+	}
+
+	fn advance(&mut self, cnt: usize) {
+		assert!(cnt <= self.limit, "attempted to advance past the limit");
+
+		self.inner.advance(cnt);
+		self.limit -= cnt;
+	}
+
+	forwarded_gets! {
+		get_u16 -> u16;
+		get_i16 -> i16;
+		get_u32 -> u32;
+		get_i32 -> i32;
+		get_u64 -> u64;
+		get_i64 -> i64;
+		get_u128 -> u128;
+		get_i128 -> i128;
+		get_f32 -> f32;
+		get_f64 -> f64;
+	}
+}
+
+/// A [`BufMut`] which writes no more than a limited number of bytes to the
+/// buffer it wraps.
+///
+/// Unlike [`bytes::buf::Limit`], this forwards the writing of multi-byte
+/// integers to the wrapped buffer, so that an [`Ordered`] buffer's
+/// [`ByteOrder`] is respected.
+///
+/// This is created with [`limit`].
+#[derive(Debug)]
+pub struct Limit<'a, B> {
+	inner: &'a mut B,
+	limit: usize,
+}
+
+/// Returns a [`Limit`] which writes no more than `limit` bytes to `buf`.
+#[must_use]
+pub const fn limit<B: BufMut>(buf: &mut B, limit: usize) -> Limit<'_, B> {
+	Limit { inner: buf, limit }
+}
+
+impl<B> Limit<'_, B> {
+	/// Returns the maximum number of bytes which can still be written.
+	#[must_use]
+	pub const fn limit(&self) -> usize {
+		self.limit
+	}
+}
+
+/// Generates overrides of [`BufMut`] methods which forward to the inner
+/// buffer, panicking if the write would exceed the limit.
+macro_rules! forwarded_puts {
+	($($put:ident($ty:ty);)*) => {
+		$(
+			fn $put(&mut self, n: $ty) {
+				let size = core::mem::size_of::<$ty>();
+				assert!(size <= self.limit, "attempted to write past the limit");
+
+				self.limit -= size;
+				self.inner.$put(n);
+			}
+		)*
+	};
+}
+
+// SAFETY: `chunk_mut` never returns more than `limit` bytes, and `advance_mut`
+//         is forwarded directly to `inner`.
+unsafe impl<B: BufMut> BufMut for Limit<'_, B> {
+	fn remaining_mut(&self) -> usize {
+		self.inner.remaining_mut().min(self.limit)
+	}
+
+	unsafe fn advance_mut(&mut self, cnt: usize) {
+		assert!(cnt <= self.limit, "attempted to advance past the limit");
+
+		// SAFETY: the caller upholds the same contract for `inner`.
+		unsafe { self.inner.advance_mut(cnt) };
+		self.limit -= cnt;
+	}
+
+	fn chunk_mut(&mut self) -> &mut UninitSlice {
+		let chunk = self.inner.chunk_mut();
+		let end = chunk.len().min(self.limit);
+
+		&mut chunk[..end]
+	}
+
+	forwarded_puts! {
+		put_u16(u16);
+		put_i16(i16);
+		put_u32(u32);
+		put_i32(i32);
+		put_u64(u64);
+		put_i64(i64);
+		put_u128(u128);
+		put_i128(i128);
+		put_f32(f32);
+		put_f64(f64);
+	}
+}
+
+/// A [`BufMut`] which counts the number of bytes written to the buffer it
+/// wraps.
+///
+/// Like [`Limit`], this forwards the writing of multi-byte integers to the
+/// wrapped buffer, so that an [`Ordered`] buffer's [`ByteOrder`] is respected.
+#[derive(Debug)]
+pub struct CountingBufMut<'a, B> {
+	inner: &'a mut B,
+	written: usize,
+}
+
+impl<'a, B> CountingBufMut<'a, B> {
+	/// Wraps the given `buf` so that the number of bytes written to it is
+	/// counted.
+	#[must_use]
+	pub const fn new(buf: &'a mut B) -> Self {
+		Self {
+			inner: buf,
+			written: 0,
+		}
+	}
+
+	/// Returns the number of bytes which have been written.
+	#[must_use]
+	pub const fn written(&self) -> usize {
+		self.written
+	}
+}
+
+/// Generates overrides of [`BufMut`] methods which forward to the inner
+/// buffer, counting the bytes written.
+macro_rules! counted_puts {
+	($($put:ident($ty:ty);)*) => {
+		$(
+			fn $put(&mut self, n: $ty) {
+				self.written += core::mem::size_of::<$ty>();
+				self.inner.$put(n);
+			}
+		)*
+	};
+}
+
+// SAFETY: every method which affects the initialized bytes or the cursor is
+//         forwarded directly to `inner`.
+unsafe impl<B: BufMut> BufMut for CountingBufMut<'_, B> {
+	fn remaining_mut(&self) -> usize {
+		self.inner.remaining_mut()
+	}
+
+	unsafe fn advance_mut(&mut self, cnt: usize) {
+		// SAFETY: the caller upholds the same contract for `inner`.
+		unsafe { self.inner.advance_mut(cnt) };
+		self.written += cnt;
+	}
+
+	fn chunk_mut(&mut self) -> &mut UninitSlice {
+		self.inner.chunk_mut()
+	}
+
+	counted_puts! {
+		put_u16(u16);
+		put_i16(i16);
+		put_u32(u32);
+		put_i32(i32);
+		put_u64(u64);
+		put_i64(i64);
+		put_u128(u128);
+		put_i128(i128);
+		put_f32(f32);
+		put_f64(f64);
+	}
+}
+
+/// Determines the [`ByteOrder`] in which a buffer reads and writes multi-byte
+/// integers, so that lists of integers can be converted and copied in bulk
+/// rather than one element at a time.
+///
+/// This returns [`None`] for buffers which are not known to use the default
+/// big endian implementations of `get_u16`, `put_u32`, and so on: those
+/// buffers might override them, so each element must be read or written
+/// through them individually.
+pub trait KnownByteOrder {
+	fn known_byte_order(&self) -> Option<ByteOrder>;
+}
+
+impl<B: ?Sized> KnownByteOrder for B {
+	default fn known_byte_order(&self) -> Option<ByteOrder> {
+		None
+	}
+}
+
+/// Implements [`KnownByteOrder`] for buffers which use the default big endian
+/// implementations of `get_u16`, `put_u32`, and so on.
+macro_rules! big_endian {
+	($($ty:ty),*$(,)?) => {
+		$(
+			impl KnownByteOrder for $ty {
+				fn known_byte_order(&self) -> Option<ByteOrder> {
+					Some(ByteOrder::BigEndian)
+				}
+			}
+		)*
+	};
+}
+
+big_endian! {
+	alloc::vec::Vec<u8>,
+	bytes::Bytes,
+	bytes::BytesMut,
+	&[u8],
+	// `&mut [u8]` is handled by the implementation for `&mut B`.
+	[u8],
+	crate::testing::FragmentedBuf<'_>,
+}
+
+impl<B: ?Sized> KnownByteOrder for &mut B {
+	fn known_byte_order(&self) -> Option<ByteOrder> {
+		(**self).known_byte_order()
+	}
+}
+
+impl<B> KnownByteOrder for Ordered<B> {
+	fn known_byte_order(&self) -> Option<ByteOrder> {
+		match self.order {
+			// Big endian integers are read and written through the same
+			// methods of `inner`, which might themselves be overridden.
+			ByteOrder::BigEndian => self.inner.known_byte_order(),
+			// Little endian integers are read and written through the `_le`
+			// methods of `inner`, which are only known not to be overridden if
+			// `inner` is known.
+			ByteOrder::LittleEndian => self
+				.inner
+				.known_byte_order()
+				.map(|_| ByteOrder::LittleEndian),
+		}
+	}
+}
+
+/// Implements [`KnownByteOrder`] for wrappers which forward the reading and
+/// writing of multi-byte integers to the buffer they wrap.
+macro_rules! forwarded_byte_order {
+	($($ty:ident),*$(,)?) => {
+		$(
+			impl<B> KnownByteOrder for $ty<'_, B> {
+				fn known_byte_order(&self) -> Option<ByteOrder> {
+					self.inner.known_byte_order()
+				}
+			}
+		)*
+	};
+}
+
+forwarded_byte_order!(Take, Limit, CountingBufMut);
+
+/// A [`Buf`] which can be used as a trait object.
+///
+/// This is implemented for every [`Buf`]. Unlike a `dyn Buf`, a `dyn DynBuf`
+/// keeps the [`ByteOrder`] of the buffer it was created from if that is known,
+/// so that lists of integers can still be read in bulk through it.
+///
+/// See [`Readable::read_from_dyn`](crate::Readable::read_from_dyn).
+pub trait DynBuf: Buf + KnownByteOrder {}
+
+impl<B: Buf + ?Sized> DynBuf for B {}
+
+/// A [`BufMut`] which can be used as a trait object.
+///
+/// This is implemented for every [`BufMut`]. Unlike a `dyn BufMut`, a
+/// `dyn DynBufMut` keeps the [`ByteOrder`] of the buffer it was created from if
+/// that is known, so that lists of integers can still be written in bulk
+/// through it.
+///
+/// See [`Writable::write_to_dyn`](crate::Writable::write_to_dyn).
+pub trait DynBufMut: BufMut + KnownByteOrder {}
+
+impl<B: BufMut + ?Sized> DynBufMut for B {}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{Readable, ReadableWithContext, Writable};
+	use alloc::{vec, vec::Vec};
+
+	#[test]
+	fn test_ordered_writes_little_endian() {
+		let mut bytes = vec![];
+		0x0102_u16
+			.write_to_ordered(&mut bytes, ByteOrder::LittleEndian)
+			.unwrap();
+		0x0304_0506_u32
+			.write_to_ordered(&mut bytes, ByteOrder::BigEndian)
+			.unwrap();
+
+		assert_eq!(bytes, [0x02, 0x01, 0x03, 0x04, 0x05, 0x06]);
+	}
+
+	#[test]
+	fn test_take_preserves_order() {
+		let bytes = [0x02, 0x01, 0x04, 0x03];
+		let mut buf = Ordered::new(&bytes[..], ByteOrder::LittleEndian);
+		let buf = &mut take(&mut buf, 2);
+
+		assert_eq!(u16::read_from(buf).unwrap(), 0x0102);
+		assert_eq!(buf.remaining(), 0);
+	}
+
+	#[test]
+	fn test_limit_preserves_order() {
+		let mut bytes = vec![];
+		let mut buf = Ordered::new(&mut bytes, ByteOrder::LittleEndian);
+		0x0102_u16.write_to(&mut limit(&mut buf, 2)).unwrap();
+
+		assert_eq!(bytes, [0x02, 0x01]);
+	}
+
+	#[test]
+	fn test_counting_buf_mut_preserves_order() {
+		let mut bytes = vec![];
+		let mut buf = Ordered::new(&mut bytes, ByteOrder::LittleEndian);
+		let buf = &mut CountingBufMut::new(&mut buf);
+		0x0102_u16.write_to(buf).unwrap();
+		[3_u8, 4, 5].write_to(buf).unwrap();
+
+		assert_eq!(buf.written(), 5);
+		assert_eq!(bytes, [0x02, 0x01, 3, 4, 5]);
+	}
+
+	#[test]
+	fn test_lists_respect_order() {
+		// More than one chunk's worth of elements.
+		let list: Vec<u16> = (0..300_u16).map(|i| i.wrapping_mul(0x0101)).collect();
+		let little_endian: Vec<u8> = list.iter().flat_map(|x| x.to_le_bytes()).collect();
+		let big_endian: Vec<u8> = list.iter().flat_map(|x| x.to_be_bytes()).collect();
+
+		let mut bytes = vec![];
+		let mut buf = Ordered::new(&mut bytes, ByteOrder::LittleEndian);
+		list.write_to(&mut limit(&mut buf, 600)).unwrap();
+		assert_eq!(bytes, little_endian);
+
+		// The byte order of a `CountingBufMut` is forwarded from the buffer it
+		// wraps, so the list is still written in bulk.
+		let mut bytes = vec![];
+		let mut buf = Ordered::new(&mut bytes, ByteOrder::LittleEndian);
+		let buf = &mut CountingBufMut::new(&mut buf);
+		assert_eq!(buf.known_byte_order(), Some(ByteOrder::LittleEndian));
+		list.write_to(buf).unwrap();
+		assert_eq!(buf.written(), 600);
+		assert_eq!(bytes, little_endian);
+
+		let mut buf = Ordered::new(&little_endian[..], ByteOrder::LittleEndian);
+		assert_eq!(Vec::<u16>::read_with(&mut buf, &300).unwrap(), list);
+		assert!(Vec::<u16>::read_with(&mut &big_endian[..599], &300).is_err());
+	}
+
+	#[test]
+	fn test_dyn_buffers_preserve_order() {
+		let list: Vec<u16> = (0..300_u16).map(|i| i.wrapping_mul(0x0101)).collect();
+		let little_endian: Vec<u8> = list.iter().flat_map(|x| x.to_le_bytes()).collect();
+
+		let mut bytes = vec![];
+		let mut buf = Ordered::new(&mut bytes, ByteOrder::LittleEndian);
+		let buf: &mut dyn DynBufMut = &mut buf;
+		assert_eq!(buf.known_byte_order(), Some(ByteOrder::LittleEndian));
+		list.write_to_dyn(buf).unwrap();
+		assert_eq!(bytes, little_endian);
+
+		let mut buf = Ordered::new(&little_endian[..], ByteOrder::LittleEndian);
+		let buf: &mut dyn DynBuf = &mut buf;
+		assert_eq!(buf.known_byte_order(), Some(ByteOrder::LittleEndian));
+		assert_eq!(<[u16; 300]>::read_from_dyn(buf).unwrap().to_vec(), list);
+	}
+}
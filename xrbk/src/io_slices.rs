@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A [`Buf`] over the buffers filled by a vectored read.
+
+use std::io::IoSlice;
+
+use bytes::Buf;
+
+/// A [`Buf`] which reads from each of a list of [`IoSlice`]s in turn, such
+/// as the buffers filled by a vectored read with `readv`.
+///
+/// Messages can be read from an `IoSlices` without first copying the bytes
+/// into one contiguous buffer, even if they straddle two or more of the
+/// slices. Empty slices are skipped.
+///
+/// ```
+/// use std::io::IoSlice;
+///
+/// use xrbk::{Buf, IoSlices, Readable};
+///
+/// let slices = [
+///     IoSlice::new(&[0x00, 0x40]),
+///     IoSlice::new(&[]),
+///     IoSlice::new(&[0x00, 0x01, 0x02]),
+/// ];
+/// let mut buf = IoSlices::new(&slices);
+///
+/// assert_eq!(buf.remaining(), 5);
+/// assert_eq!(u32::read_from(&mut buf).unwrap(), 0x0040_0001);
+/// assert_eq!(buf.chunk(), [0x02]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct IoSlices<'a, 'b> {
+	/// The slices which have not been completely read.
+	slices: &'a [IoSlice<'b>],
+	/// The number of bytes of the first of the `slices` which have been read.
+	offset: usize,
+
+	/// The number of bytes which have not been read.
+	remaining: usize,
+}
+
+impl<'a, 'b> IoSlices<'a, 'b> {
+	/// Creates a new `IoSlices` which reads from each of the given `slices` in
+	/// turn.
+	#[must_use]
+	pub fn new(slices: &'a [IoSlice<'b>]) -> Self {
+		let mut buf = Self {
+			slices,
+			offset: 0,
+
+			remaining: slices.iter().map(|slice| slice.len()).sum(),
+		};
+		buf.skip_read_slices();
+
+		buf
+	}
+
+	/// Moves past any slices at the start of the `slices` which have been
+	/// completely read or are empty, so that [`chunk`] is only empty once
+	/// every byte has been read.
+	///
+	/// [`chunk`]: Buf::chunk
+	fn skip_read_slices(&mut self) {
+		while let Some((first, rest)) = self.slices.split_first() {
+			if self.offset < first.len() {
+				break;
+			}
+
+			self.offset -= first.len();
+			self.slices = rest;
+		}
+	}
+}
+
+impl Buf for IoSlices<'_, '_> {
+	fn remaining(&self) -> usize {
+		self.remaining
+	}
+
+	fn chunk(&self) -> &[u8] {
+		self.slices
+			.first()
+			.map_or(&[], |first| &first[self.offset..])
+	}
+
+	fn advance(&mut self, cnt: usize) {
+		assert!(
+			cnt <= self.remaining,
+			"attempted to advance past the end of the buffer"
+		);
+
+		self.remaining -= cnt;
+		self.offset += cnt;
+
+		self.skip_read_slices();
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{ByteOrder, Ordered, Readable, ReadableWithContext};
+	use alloc::vec::Vec;
+
+	#[test]
+	fn test_read_across_slices() {
+		let slices = [
+			IoSlice::new(&[]),
+			IoSlice::new(&[1]),
+			IoSlice::new(&[2, 3]),
+			IoSlice::new(&[]),
+			IoSlice::new(&[4, 5, 6, 7]),
+			IoSlice::new(&[8]),
+		];
+		let mut buf = IoSlices::new(&slices);
+
+		assert_eq!(buf.remaining(), 8);
+		assert_eq!(buf.chunk(), [1]);
+
+		assert_eq!(u16::read_from(&mut buf).unwrap(), 0x0102);
+		assert_eq!(buf.chunk(), [3]);
+		assert_eq!(u32::read_from(&mut buf).unwrap(), 0x0304_0506);
+		assert_eq!(buf.chunk(), [7]);
+
+		let rest = Vec::<u8>::read_with(&mut buf, &2).unwrap();
+		assert_eq!(rest, [7, 8]);
+		assert!(!buf.has_remaining());
+		assert!(buf.chunk().is_empty());
+	}
+
+	#[test]
+	fn test_read_across_slices_little_endian() {
+		let slices = [IoSlice::new(&[1, 2, 3]), IoSlice::new(&[4])];
+		let mut buf = Ordered::new(IoSlices::new(&slices), ByteOrder::LittleEndian);
+
+		assert_eq!(u32::read_from(&mut buf).unwrap(), 0x0403_0201);
+	}
+
+	#[test]
+	fn test_only_empty_slices() {
+		let slices = [IoSlice::new(&[]), IoSlice::new(&[])];
+		let buf = IoSlices::new(&slices);
+
+		assert_eq!(buf.remaining(), 0);
+		assert!(buf.chunk().is_empty());
+	}
+}
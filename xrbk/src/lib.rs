@@ -2,10 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+// `std` is only needed for the `std` features of our dependencies: everything
+// else is available in `core` and `alloc`.
+#![no_std]
 // We need specialization to implement DataSize for types with generics like
 // Option<T>
 #![allow(incomplete_features)]
 #![feature(specialization)]
+// Used to read arrays without requiring `T: Default` or leaking on failure.
+#![feature(array_try_from_fn)]
 // Deny the following clippy lints to enforce them:
 #![deny(clippy::complexity)]
 #![deny(clippy::correctness)]
@@ -27,15 +32,54 @@
 //!
 //! The XRB Kit, a collection of traits and types to help with
 //! (de)serialization of types in XRB.
+//!
+//! # Features
+//! XRBK only requires `alloc`. The `std` feature, enabled by default, enables
+//! the `std` features of its dependencies, such as the [`Buf`] and [`BufMut`]
+//! implementations for `std::io` types, and provides [`IoSlices`] for reading
+//! from the buffers filled by a vectored read.
+//!
+//! # Prelude
+//! [`prelude`] exports the traits and types which are needed to read and write
+//! values, including [`Buf`] and [`BufMut`], so that crates using XRBK don't
+//! need to depend on `bytes` themselves.
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use std::{
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{
 	any::Any,
-	fmt::{Debug, Display},
+	fmt::{self, Debug, Display, Formatter},
 };
 
-pub use bytes::{Buf, BufMut};
+pub use byte_order::{
+	limit,
+	take,
+	ByteOrder,
+	CountingBufMut,
+	DynBuf,
+	DynBufMut,
+	KnownByteOrder,
+	Limit,
+	Ordered,
+	Take,
+};
+pub use bytes::{Buf, BufMut, BytesMut};
+#[cfg(feature = "std")]
+pub use io_slices::IoSlices;
+pub use readable_ref::{read_ref, read_ref_with};
+pub use wire::WireField;
 
-use thiserror::Error;
+/// Items used by the code generated by `derive_xrb!`.
+///
+/// The generated code cannot name `alloc` itself, as the crate it is used in
+/// may not declare `extern crate alloc`.
+#[doc(hidden)]
+pub mod __private {
+	pub use alloc::vec::Vec;
+}
 
 /// Determines the number of unused bytes required to be inserted after the
 /// given `value` to reach a multiple of four bytes in size.
@@ -51,6 +95,61 @@ pub fn pad<T: X11Size>(value: &T) -> usize {
 	(ALIGNMENT - (x11_size % ALIGNMENT)) % ALIGNMENT
 }
 
+/// Returns [`ReadError::UnexpectedEndOfData`] if fewer than `expected` bytes
+/// remain in the given `buf`.
+///
+/// This allows a [`Readable`] implementation to check that there are enough
+/// bytes once, before reading a value of a known size, rather than before
+/// each of its parts.
+///
+/// # Errors
+/// Returns [`ReadError::UnexpectedEndOfData`] if fewer than `expected` bytes
+/// remain.
+pub fn ensure_remaining(buf: &impl Buf, expected: usize) -> ReadResult<()> {
+	let found = buf.remaining();
+
+	if found < expected {
+		return Err(ReadError::UnexpectedEndOfData { expected, found });
+	}
+
+	Ok(())
+}
+
+/// Skips `count` unused bytes in the given `buf`.
+///
+/// Unlike [`Buf::advance`], this does not panic if fewer than `count` bytes
+/// remain.
+///
+/// # Errors
+/// Returns [`ReadError::UnexpectedEndOfData`] if fewer than `count` bytes
+/// remain.
+pub fn skip(buf: &mut impl Buf, count: usize) -> ReadResult<()> {
+	ensure_remaining(buf, count)?;
+	buf.advance(count);
+
+	Ok(())
+}
+
+/// Panics if the number of bytes `written` for a message of the given
+/// `type_name` differs from either its `x11_size` or the number of bytes given
+/// by its `length` field.
+///
+/// `derive_xrb!` calls this at the end of the [`Writable`] implementations of
+/// requests, replies, and events in debug builds. A message whose length is
+/// calculated incorrectly would otherwise cause the X server or client to
+/// misread every message which follows it.
+///
+/// # Panics
+/// Panics if `written`, `x11_size`, and `length` are not all equal.
+#[track_caller]
+pub fn assert_written_size(type_name: &str, written: usize, x11_size: usize, length: usize) {
+	assert!(
+		written == x11_size && written == length,
+		"{type_name} wrote {written} bytes, but its X11 size is {x11_size} bytes and its length \
+		 is {length} bytes",
+	);
+}
+
 pub type ReadResult<T> = Result<T, ReadError>;
 pub type WriteResult = Result<(), WriteError>;
 
@@ -58,31 +157,239 @@ pub trait DebugDisplay: Debug + Display {}
 impl<T: Debug + Display> DebugDisplay for T {}
 
 #[non_exhaustive]
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum ReadError {
-	#[error("unrecognized variant discriminant: {0}")]
-	UnrecognizedDiscriminant(usize),
+	UnrecognizedDiscriminant {
+		/// The name of the type which was being read.
+		type_name: &'static str,
+		/// The discriminant which was read.
+		discriminant: usize,
+	},
+
+	TrailingData {
+		remaining: usize,
+	},
+
+	UnexpectedEndOfData {
+		/// The number of bytes which were required.
+		expected: usize,
+		/// The number of bytes which remained.
+		found: usize,
+	},
 
-	#[error("a conversion failed")]
 	FailedConversion(Box<dyn Any>),
-	#[error("{0}")]
 	Other(Box<dyn DebugDisplay>),
+
+	/// An error generated while reading a field of a type.
+	///
+	/// This is added by the `Readable` implementations generated by
+	/// `derive_xrb!`, so that the path to the field which could not be read
+	/// is known. It is displayed as that path followed by the
+	/// [`root_cause`](ReadError::root_cause), such as
+	/// `GrabCursor.cursor_freeze: unrecognized FreezeMode discriminant: 2`.
+	InField {
+		/// The name of the type which was being read.
+		parent: &'static str,
+		/// The name of the field which could not be read.
+		field: &'static str,
+
+		/// The error generated while reading the field.
+		source: Box<Self>,
+	},
+}
+
+impl ReadError {
+	/// Wraps this error in [`ReadError::InField`] to record that it was
+	/// generated while reading the given `field` of the given `parent` type.
+	#[must_use]
+	pub fn in_field(self, parent: &'static str, field: &'static str) -> Self {
+		Self::InField {
+			parent,
+			field,
+
+			source: Box::new(self),
+		}
+	}
+
+	/// Returns the error which was generated by the field at the end of the
+	/// path of [`ReadError::InField`]s, or this error if it is not a
+	/// [`ReadError::InField`].
+	#[must_use]
+	pub fn root_cause(&self) -> &Self {
+		match self {
+			Self::InField { source, .. } => source.root_cause(),
+			other => other,
+		}
+	}
+
+	/// Formats the path from the given `field` through any nested
+	/// [`ReadError::InField`]s, followed by the error at the end of that path.
+	fn field_path(field: &str, source: &Self) -> String {
+		match source {
+			Self::InField {
+				field: inner,
+				source,
+				..
+			} => {
+				format!("{field}.{}", Self::field_path(inner, source))
+			},
+
+			other => format!("{field}: {other}"),
+		}
+	}
+}
+
+impl Display for ReadError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::UnrecognizedDiscriminant {
+				type_name,
+				discriminant,
+			} => write!(f, "unrecognized {type_name} discriminant: {discriminant}"),
+
+			Self::TrailingData { remaining } => {
+				write!(f, "{remaining} bytes remained after reading")
+			},
+
+			Self::UnexpectedEndOfData { expected, found } => {
+				write!(f, "expected at least {expected} bytes, found {found}")
+			},
+
+			Self::FailedConversion(_) => f.write_str("a conversion failed"),
+			Self::Other(error) => Display::fmt(error, f),
+
+			Self::InField {
+				parent,
+				field,
+				source,
+			} => write!(f, "{parent}.{}", Self::field_path(field, source)),
+		}
+	}
+}
+
+impl core::error::Error for ReadError {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		match self {
+			Self::InField { source, .. } => Some(&**source),
+			_ => None,
+		}
+	}
 }
 
 #[non_exhaustive]
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum WriteError {
-	#[error("a conversion failed")]
+	/// A value did not match the discriminant written separately from it.
+	///
+	/// This is returned by [`Discriminated::write_discriminated`], rather than
+	/// writing bytes which would be read back as a different variant.
+	MismatchedDiscriminant {
+		/// The name of the type which was being written.
+		type_name: &'static str,
+	},
+
 	FailedConversion(Box<dyn Any>),
-	#[error("{0}")]
 	Other(Box<dyn DebugDisplay>),
+
+	/// An error generated while writing a field of a type.
+	///
+	/// This is added by the `Writable` implementations generated by
+	/// `derive_xrb!`, so that the path to the field which could not be
+	/// written is known. It is displayed as that path followed by the
+	/// [`root_cause`](WriteError::root_cause).
+	InField {
+		/// The name of the type which was being written.
+		parent: &'static str,
+		/// The name of the field which could not be written.
+		field: &'static str,
+
+		/// The error generated while writing the field.
+		source: Box<Self>,
+	},
+}
+
+impl WriteError {
+	/// Wraps this error in [`WriteError::InField`] to record that it was
+	/// generated while writing the given `field` of the given `parent` type.
+	#[must_use]
+	pub fn in_field(self, parent: &'static str, field: &'static str) -> Self {
+		Self::InField {
+			parent,
+			field,
+
+			source: Box::new(self),
+		}
+	}
+
+	/// Returns the error which was generated by the field at the end of the
+	/// path of [`WriteError::InField`]s, or this error if it is not a
+	/// [`WriteError::InField`].
+	#[must_use]
+	pub fn root_cause(&self) -> &Self {
+		match self {
+			Self::InField { source, .. } => source.root_cause(),
+			other => other,
+		}
+	}
+
+	/// Formats the path from the given `field` through any nested
+	/// [`WriteError::InField`]s, followed by the error at the end of that path.
+	fn field_path(field: &str, source: &Self) -> String {
+		match source {
+			Self::InField {
+				field: inner,
+				source,
+				..
+			} => {
+				format!("{field}.{}", Self::field_path(inner, source))
+			},
+
+			other => format!("{field}: {other}"),
+		}
+	}
 }
 
+impl Display for WriteError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::MismatchedDiscriminant { type_name } => {
+				write!(f, "{type_name} does not match its discriminant")
+			},
+
+			Self::FailedConversion(_) => f.write_str("a conversion failed"),
+			Self::Other(error) => Display::fmt(error, f),
+
+			Self::InField {
+				parent,
+				field,
+				source,
+			} => write!(f, "{parent}.{}", Self::field_path(field, source)),
+		}
+	}
+}
+
+impl core::error::Error for WriteError {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		match self {
+			Self::InField { source, .. } => Some(&**source),
+			_ => None,
+		}
+	}
+}
+
+mod byte_order;
+#[cfg(feature = "std")]
+mod io_slices;
 mod readable;
+mod readable_ref;
+mod wire;
 mod wrap;
 mod writable;
 mod x11_size;
 
+pub mod prelude;
+pub mod testing;
+
 /// Gives the type size in bytes.
 /// The size can vary depending on the quantity of data it contains
 pub trait X11Size {
@@ -109,6 +416,11 @@ pub trait X11Size {
 ///     }
 /// }
 /// ```
+#[diagnostic::on_unimplemented(
+	message = "`{Self}` does not have a constant size",
+	label = "this does not have a constant size",
+	note = "`ConstantX11Size` can only be derived if every element has a constant size"
+)]
 pub trait ConstantX11Size: X11Size {
 	/// The size of this type when serialized according to the the X11 protocol,
 	/// measured in bytes.
@@ -129,6 +441,83 @@ pub trait Readable: X11Size {
 	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
 	where
 		Self: Sized;
+
+	/// Reads [`Self`] from a [`DynBuf`] trait object.
+	///
+	/// [`read_from`] is generic over the type of buffer, so its code is
+	/// compiled again for every type of buffer that [`Self`] is read from. If
+	/// the `dyn-buffers` feature is enabled in a crate which uses
+	/// `xrbk_macro`, the implementations generated in it put the code which
+	/// reads [`Self`] here instead, so that it is only compiled once, and
+	/// [`read_from`] only forwards to it.
+	///
+	/// By default, this forwards to [`read_from`].
+	///
+	/// # Errors
+	///
+	/// See [`read_from`].
+	///
+	/// [`read_from`]: Self::read_from
+	fn read_from_dyn(mut buf: &mut dyn DynBuf) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		Self::read_from(&mut buf)
+	}
+
+	/// Reads [`Self`] from a [`Buf`] of bytes, reading multi-byte integers in
+	/// the given byte `order`.
+	///
+	/// # Errors
+	///
+	/// See [`read_from`](Self::read_from).
+	///
+	/// [`Buf`]: Buf
+	fn read_from_ordered(buf: &mut impl Buf, order: ByteOrder) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		Self::read_from(&mut Ordered::new(buf, order))
+	}
+
+	/// Reads [`Self`] from the start of the given `bytes`, returning the
+	/// number of bytes which were read.
+	///
+	/// # Errors
+	///
+	/// See [`read_from`](Self::read_from).
+	fn read_from_bytes(bytes: &[u8]) -> ReadResult<(Self, usize)>
+	where
+		Self: Sized,
+	{
+		let mut buf = bytes;
+		let value = Self::read_from(&mut buf)?;
+
+		Ok((value, bytes.len() - buf.len()))
+	}
+
+	/// Reads [`Self`] from the given `bytes`, which must be exactly the size of
+	/// [`Self`].
+	///
+	/// This is useful to check that a message's declared length matches the
+	/// elements which are actually read.
+	///
+	/// # Errors
+	///
+	/// - [`ReadError::TrailingData`]: Not all of the `bytes` were read.
+	///
+	/// See also [`read_from`](Self::read_from).
+	fn read_exact(bytes: &[u8]) -> ReadResult<Self>
+	where
+		Self: Sized,
+	{
+		let (value, read) = Self::read_from_bytes(bytes)?;
+
+		match bytes.len() - read {
+			0 => Ok(value),
+			remaining => Err(ReadError::TrailingData { remaining }),
+		}
+	}
 }
 
 /// Allows the reading of a type from bytes given some additional
@@ -155,6 +544,53 @@ pub trait ReadableWithContext: X11Size {
 		Self: Sized;
 }
 
+/// Reads a type from a slice of bytes, borrowing from that slice where
+/// possible rather than copying.
+///
+/// This is intended for types which carry large amounts of uninterpreted data,
+/// such as property values or image data, where copying every byte into a
+/// freshly allocated [`Vec`] is wasteful. Any type which implements
+/// [`Readable`] also implements `ReadableRef`, simply by copying.
+///
+/// `ReadableRef` can be derived with `derive_xrb!` for a struct with a
+/// lifetime parameter, so long as each of its fields implements
+/// `ReadableRef`, or [`ReadableRefWithContext`] if it has a context.
+pub trait ReadableRef<'a>: Sized {
+	/// Reads [`Self`] from the start of the given `bytes`, returning the
+	/// remaining bytes following [`Self`].
+	///
+	/// # Errors
+	///
+	/// - [`ReadError::UnrecognizedDiscriminant`]: The value encountered is not
+	///   matching any enum's variants discriminant.
+	/// - [`ReadError::Other`]: Any other error when parsing.
+	fn read_ref(bytes: &'a [u8]) -> ReadResult<(Self, &'a [u8])>;
+}
+
+/// Allows the reading of a type from a slice of bytes given some additional
+/// [`Context`](Self::Context), borrowing from that slice where possible rather
+/// than copying.
+///
+/// See [`ReadableRef`] for more information.
+pub trait ReadableRefWithContext<'a>: Sized {
+	/// The type of context with which this type can be read from bytes.
+	///
+	/// For example, this might be `usize` for a slice, where that `usize`
+	/// context represents the length of the slice with which to read.
+	type Context;
+
+	/// Reads [`Self`] from the start of the given `bytes`, given some
+	/// additional [`Context`](Self::Context), returning the remaining bytes
+	/// following [`Self`].
+	///
+	/// # Errors
+	///
+	/// - [`ReadError::UnrecognizedDiscriminant`]: The value encountered is not
+	///   matching any enum's variants discriminant.
+	/// - [`ReadError::Other`]: Any other error when parsing.
+	fn read_ref_with(bytes: &'a [u8], context: &Self::Context) -> ReadResult<(Self, &'a [u8])>;
+}
+
 /// Allows a type to be written as bytes.
 pub trait Writable: X11Size {
 	/// Writes [`self`](Self) as bytes to a [`BufMut`].
@@ -166,15 +602,129 @@ pub trait Writable: X11Size {
 	///
 	/// [`BufMut`]: BufMut
 	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult;
+
+	/// Writes [`self`](Self) as bytes to a [`DynBufMut`] trait object.
+	///
+	/// [`write_to`] is generic over the type of buffer, so its code is
+	/// compiled again for every type of buffer that [`self`](Self) is written
+	/// to. If the `dyn-buffers` feature is enabled in a crate which uses
+	/// `xrbk_macro`, the implementations generated in it put the code which
+	/// writes [`self`](Self) here instead, so that it is only compiled once,
+	/// and [`write_to`] only forwards to it.
+	///
+	/// By default, this forwards to [`write_to`].
+	///
+	/// # Errors
+	///
+	/// See [`write_to`].
+	///
+	/// [`write_to`]: Self::write_to
+	fn write_to_dyn(&self, mut buf: &mut dyn DynBufMut) -> WriteResult {
+		self.write_to(&mut buf)
+	}
+
+	/// Writes [`self`](Self) as bytes to a [`BufMut`], writing multi-byte
+	/// integers in the given byte `order`.
+	///
+	/// # Errors
+	///
+	/// See [`write_to`](Self::write_to).
+	///
+	/// [`BufMut`]: BufMut
+	fn write_to_ordered(&self, buf: &mut impl BufMut, order: ByteOrder) -> WriteResult {
+		self.write_to(&mut Ordered::new(buf, order))
+	}
+
+	/// Writes [`self`](Self) as bytes to the end of a [`BytesMut`], reserving
+	/// its [`X11Size`] up front.
+	///
+	/// Reserving the exact size means the buffer is grown at most once, so
+	/// many values can be written into a single (possibly reused) buffer
+	/// without serializing each of them into a buffer of its own first.
+	///
+	/// # Errors
+	///
+	/// See [`write_to`](Self::write_to).
+	fn write_into_exact(&self, buf: &mut BytesMut) -> WriteResult {
+		buf.reserve(self.x11_size());
+
+		self.write_to(buf)
+	}
+}
+
+/// Describes where each element of a type is written in its serialized bytes.
+///
+/// This is useful for debugging: comparing [`fmt_wire`] with a capture of the
+/// bytes actually exchanged with the X server shows which element a
+/// mismatched byte belongs to.
+///
+/// `WireLayout` is implemented for requests, replies, events, and errors
+/// defined with `derive_xrb!` which derive [`Writable`] if the `trace-wire`
+/// feature of the crate in which they are defined is enabled.
+///
+/// [`fmt_wire`]: WireLayout::fmt_wire
+pub trait WireLayout: Writable {
+	/// Writes [`self`](Self) as bytes to the end of `bytes`, returning a
+	/// [`WireField`] for each element in the order they were written.
+	///
+	/// The offsets of the [`WireField`]s are relative to the first byte
+	/// written.
+	///
+	/// # Errors
+	///
+	/// Returns a [`WriteError`] if [`self`](Self) could not be written.
+	fn write_layout(&self, bytes: &mut Vec<u8>) -> Result<Vec<WireField>, WriteError>;
+
+	/// Returns a [`WireField`] for each element of [`self`](Self), in the
+	/// order they are written.
+	///
+	/// The sizes of variable-size elements are those of this particular value.
+	///
+	/// # Errors
+	///
+	/// Returns a [`WriteError`] if [`self`](Self) could not be written.
+	fn wire_layout(&self) -> Result<Vec<WireField>, WriteError> {
+		self.write_layout(&mut Vec::new())
+	}
+
+	/// Writes [`self`](Self) as bytes and formats them as a hex dump, with
+	/// each element starting on a new line labelled with its offset and name.
+	///
+	/// # Errors
+	///
+	/// Returns a [`WriteError`] if [`self`](Self) could not be written.
+	fn fmt_wire(&self) -> Result<String, WriteError> {
+		let mut bytes = Vec::new();
+		let fields = self.write_layout(&mut bytes)?;
+
+		Ok(wire::format(&bytes, &fields))
+	}
 }
 
 /// A trait implemented for types which 'wrap' some primitive integer type.
 ///
 /// This trait is used so that XRBK traits may be implemented for
-/// <code>[Option]<T: [Wrap]></code>.
-pub trait Wrap: Clone + TryFrom<Self::Integer> + Into<Self::Integer> + ConstantX11Size {
+/// <code>[Option]<T: [ZeroIsNone]></code>.
+pub trait Wrap: Clone + Into<Self::Integer> + ConstantX11Size {
 	type Integer: Copy + TryFrom<u64> + Into<u64> + ConstantX11Size + Readable + Writable;
 
+	/// The error returned by [`try_wrap`] if an [`Integer`] cannot be wrapped.
+	///
+	/// [`try_wrap`]: Wrap::try_wrap
+	/// [`Integer`]: Wrap::Integer
+	type Error;
+
+	/// Wraps the given `integer`.
+	///
+	/// This is used instead of [`TryFrom`] so that types, such as resource
+	/// IDs, can be wrapped without being convertible from any integer with
+	/// `try_into()`.
+	///
+	/// # Errors
+	///
+	/// Returns an error if `integer` is not a valid value of this type.
+	fn try_wrap(integer: Self::Integer) -> Result<Self, Self::Error>;
+
 	/// Referencing this associated `const` causes a compilation error if
 	/// `Self::X11_SIZE` does not equal `Self::Integer::X11_SIZE`.
 	const WRAPS_X11_SIZE: () = {
@@ -185,17 +735,144 @@ pub trait Wrap: Clone + TryFrom<Self::Integer> + Into<Self::Integer> + ConstantX
 	};
 }
 
-impl<T: Wrap> Readable for Option<T>
+/// A marker trait for [`Wrap`] types which are never zero, such as resource
+/// IDs.
+///
+/// The X11 protocol encodes the absence of a resource ID or atom as zero, so
+/// <code>[Option]<T: [ZeroIsNone]></code> is read as [`None`] if its wrapped
+/// integer is zero, and [`None`] is written as zero.
+///
+/// This must not be implemented for types for which zero is a valid value,
+/// such as a plain `u8`: a [`Some`] value of zero would be read back as
+/// [`None`]. Optional values of such types are read and written with
+/// [`PresentIf`] instead.
+pub trait ZeroIsNone: Wrap {}
+
+/// Converts a raw `integer` to <code>[Option]<T: [ZeroIsNone]></code> in the
+/// same way as it is read: zero is [`None`].
+///
+/// This is used by the `x11_compat` constructors generated by `derive_xrb!`,
+/// which accept the raw encodings of optional values. If `integer` cannot be
+/// converted to `T`, [`None`] is returned.
+pub fn zero_is_none<T>(integer: T::Integer) -> Option<T>
+where
+	T: ZeroIsNone,
+{
+	if integer.into() == 0 {
+		None
+	} else {
+		T::try_wrap(integer).ok()
+	}
+}
+
+/// Reading and writing for optional values whose presence is indicated by
+/// some other value, rather than by a value of zero.
+///
+/// Some messages contain values which are only meaningful if some other field
+/// is `true`, yet for which zero is a valid value. Such values cannot be read
+/// as <code>[Option]<T: [ZeroIsNone]></code>, which treats zero as [`None`].
+///
+/// This is used by `#[present_if(...)]` fields in `derive_xrb!`.
+pub trait PresentIf: Sized {
+	/// Reads [`Self`] from a [`Buf`] of bytes, where `present` indicates
+	/// whether the value is present.
+	///
+	/// If the value is not present, its bytes are skipped without being
+	/// interpreted.
+	///
+	/// # Errors
+	///
+	/// See [`Readable::read_from`].
+	///
+	/// [`Buf`]: Buf
+	fn read_present_if(buf: &mut impl Buf, present: bool) -> ReadResult<Self>;
+
+	/// Writes [`self`](Self) as bytes to a [`BufMut`], writing zeros if the
+	/// value is not present.
+	///
+	/// # Errors
+	///
+	/// See [`Writable::write_to`].
+	///
+	/// [`BufMut`]: BufMut
+	fn write_present_if(&self, buf: &mut impl BufMut) -> WriteResult;
+}
+
+impl<T: ConstantX11Size + Readable + Writable> PresentIf for Option<T> {
+	fn read_present_if(buf: &mut impl Buf, present: bool) -> ReadResult<Self> {
+		Ok(if present {
+			Some(T::read_from(buf)?)
+		} else {
+			buf.advance(T::X11_SIZE);
+
+			None
+		})
+	}
+
+	fn write_present_if(&self, buf: &mut impl BufMut) -> WriteResult {
+		match self {
+			Some(value) => value.write_to(buf)?,
+			None => buf.put_bytes(0, T::X11_SIZE),
+		}
+
+		Ok(())
+	}
+}
+
+/// Reading and writing for types whose variant is selected by a discriminant
+/// which is written separately, such as in an earlier field.
+///
+/// Some messages contain a value whose interpretation depends on some other
+/// field: the `data` of a `ClientMessage` event, for example, is a list of 8,
+/// 16, or 32-bit values depending on its `format` field. The discriminant is
+/// the [`Context`] with which such a type is read. As it is written separately,
+/// it is checked to match the value's variant when the value is written.
+///
+/// This is used by `#[discriminated_by(...)]` fields in `derive_xrb!`.
+///
+/// [`Context`]: ReadableWithContext::Context
+pub trait Discriminated: ReadableWithContext + Writable {
+	/// Returns the discriminant which selects this value's variant.
+	fn discriminant(&self) -> Self::Context;
+
+	/// Writes [`self`](Self) as bytes to a [`BufMut`], after checking that it
+	/// matches the `discriminant` written for it.
+	///
+	/// # Errors
+	///
+	/// Returns [`WriteError::MismatchedDiscriminant`] if the `discriminant`
+	/// does not select this value's variant. See [`Writable::write_to`] for
+	/// other errors.
+	///
+	/// [`BufMut`]: BufMut
+	fn write_discriminated(
+		&self, buf: &mut impl BufMut, discriminant: &Self::Context,
+	) -> WriteResult
+	where
+		Self::Context: PartialEq,
+	{
+		if self.discriminant() != *discriminant {
+			return Err(WriteError::MismatchedDiscriminant {
+				type_name: core::any::type_name::<Self>(),
+			});
+		}
+
+		self.write_to(buf)
+	}
+}
+
+/// Reads [`None`] if the wrapped integer is zero.
+impl<T: ZeroIsNone> Readable for Option<T>
 where
-	<T as TryFrom<T::Integer>>::Error: 'static,
+	T::Error: 'static,
 {
 	fn read_from(buf: &mut impl Buf) -> ReadResult<Self>
 	where
 		Self: Sized,
 	{
-		Ok(match <T::Integer>::read_from(buf).unwrap() {
+		Ok(match <T::Integer>::read_from(buf)? {
 			discrim if discrim.into() == 0_u64 => None,
-			value => Some(match T::try_from(value) {
+			value => Some(match T::try_wrap(value) {
 				Ok(value) => value,
 				Err(error) => return Err(ReadError::FailedConversion(Box::new(error))),
 			}),
@@ -203,7 +880,11 @@ where
 	}
 }
 
-impl<T: Wrap> Writable for Option<T>
+/// Writes [`None`] as zero.
+///
+/// [`Some`] values must therefore never be zero, or they would be read back as
+/// [`None`]; this is checked in debug builds.
+impl<T: ZeroIsNone> Writable for Option<T>
 where
 	<T::Integer as TryFrom<u64>>::Error: 'static,
 {
@@ -215,11 +896,19 @@ where
 			}
 			.write_to(buf)?,
 
-			Some(val) => match <T::Integer as TryFrom<T>>::try_from(val.clone()) {
-				Ok(val) => val,
-				Err(error) => return Err(WriteError::FailedConversion(Box::new(error))),
-			}
-			.write_to(buf)?,
+			Some(val) => {
+				let val = match <T::Integer as TryFrom<T>>::try_from(val.clone()) {
+					Ok(val) => val,
+					Err(error) => return Err(WriteError::FailedConversion(Box::new(error))),
+				};
+
+				debug_assert!(
+					Into::<u64>::into(val) != 0,
+					"`Some` cannot be written with a value of zero, as zero means `None`"
+				);
+
+				val.write_to(buf)?;
+			},
 		}
 
 		Ok(())
@@ -0,0 +1,30 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The traits and types which are needed to read and write values with XRBK.
+//!
+//! ```
+//! use xrbk::prelude::*;
+//!
+//! let mut bytes = vec![];
+//! 0x1234_u16.write_to(&mut bytes)?;
+//!
+//! assert_eq!(u16::read_from(&mut &bytes[..])?, 0x1234);
+//! #
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub use crate::{
+	Buf,
+	BufMut,
+	ConstantX11Size,
+	ReadError,
+	ReadResult,
+	Readable,
+	ReadableWithContext,
+	Writable,
+	WriteError,
+	WriteResult,
+	X11Size,
+};
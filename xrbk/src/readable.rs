@@ -4,15 +4,26 @@
 
 //! [`Readable`] implementations for primitive types
 
-use crate::{ReadResult, Readable, ReadableWithContext, X11Size};
+use crate::{
+	byte_order::KnownByteOrder,
+	ensure_remaining,
+	ByteOrder,
+	ReadResult,
+	Readable,
+	ReadableWithContext,
+	X11Size,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
 use bytes::Buf;
-use std::ops::{Range, RangeInclusive};
+use core::ops::{Range, RangeInclusive};
 
 macro_rules! implement {
 	($($reader:ident, $ty:ty => $expr:expr),*$(,)?) => {
 		$(
 			impl $crate::Readable for $ty {
 				fn read_from($reader: &mut impl bytes::Buf) -> Result<Self, $crate::ReadError> {
+					$crate::ensure_remaining($reader, ::core::mem::size_of::<$ty>())?;
+
 					Ok($expr)
 				}
 			}
@@ -44,16 +55,34 @@ impl<T: Readable, const N: usize> Readable for [T; N] {
 	where
 		Self: Sized,
 	{
-		let mut vec = Vec::new();
+		// If reading an element fails, the elements which have already been
+		// read are dropped and the error is returned.
+		core::array::try_from_fn(|_| T::read_from(reader))
+	}
+}
 
-		for _ in 0..N {
-			vec.push(T::read_from(reader)?);
-		}
+/// Implements [`Readable`] for tuples of [`Readable`] types, which are read in
+/// order.
+macro_rules! implement_tuple {
+	($(($($T:ident),+)),+$(,)?) => {
+		$(
+			impl<$($T: Readable),+> Readable for ($($T,)+) {
+				fn read_from(reader: &mut impl Buf) -> ReadResult<Self>
+				where
+					Self: Sized,
+				{
+					Ok(($($T::read_from(reader)?,)+))
+				}
+			}
+		)+
+	};
+}
 
-		Ok(vec
-			.try_into()
-			.unwrap_or_else(|_| unreachable!("we know the length of this vec is `N`")))
-	}
+implement_tuple! {
+	(A),
+	(A, B),
+	(A, B, C),
+	(A, B, C, D),
 }
 
 impl<T: Readable> Readable for Box<T> {
@@ -65,6 +94,94 @@ impl<T: Readable> Readable for Box<T> {
 	}
 }
 
+/// Reads a list of `len` values one after the other.
+///
+/// This is implemented for every [`Readable`] type, and specialized for
+/// integers so that lists of them are read in bulk.
+trait ReadVec: Readable + Sized {
+	fn read_vec(reader: &mut impl Buf, len: usize) -> ReadResult<Vec<Self>>;
+}
+
+impl<T: Readable> ReadVec for T {
+	default fn read_vec(reader: &mut impl Buf, len: usize) -> ReadResult<Vec<Self>> {
+		let mut vec = Vec::new();
+
+		for _ in 0..len {
+			vec.push(T::read_from(reader)?);
+		}
+
+		Ok(vec)
+	}
+}
+
+impl ReadVec for u8 {
+	fn read_vec(reader: &mut impl Buf, len: usize) -> ReadResult<Vec<Self>> {
+		ensure_remaining(reader, len)?;
+
+		let mut vec = vec![0; len];
+		reader.copy_to_slice(&mut vec);
+
+		Ok(vec)
+	}
+}
+
+/// The number of bytes copied at a time when reading lists of multi-byte
+/// integers.
+const CHUNK_SIZE: usize = 256;
+
+/// Implements [`ReadVec`] for integers by copying their bytes in chunks with
+/// [`Buf::copy_to_slice`] and converting each chunk.
+///
+/// If the byte order of the buffer is not known, each integer is read with its
+/// [`Readable`] implementation instead.
+macro_rules! implement_bulk {
+	($($ty:ty),*$(,)?) => {
+		$(
+			impl ReadVec for $ty {
+				fn read_vec(reader: &mut impl Buf, len: usize) -> ReadResult<Vec<Self>> {
+					const SIZE: usize = core::mem::size_of::<$ty>();
+
+					let Some(order) = reader.known_byte_order() else {
+						let mut vec = Vec::new();
+
+						for _ in 0..len {
+							vec.push(<$ty>::read_from(reader)?);
+						}
+
+						return Ok(vec);
+					};
+
+					ensure_remaining(reader, len.saturating_mul(SIZE))?;
+
+					let mut vec = Vec::with_capacity(len);
+					let mut chunk = [0; CHUNK_SIZE];
+
+					while vec.len() < len {
+						let bytes = &mut chunk[..(len - vec.len()).min(CHUNK_SIZE / SIZE) * SIZE];
+						reader.copy_to_slice(bytes);
+
+						vec.extend(bytes.chunks_exact(SIZE).map(|x| {
+							let x = x.try_into().expect("chunks are exactly `SIZE` bytes");
+
+							match order {
+								ByteOrder::BigEndian => <$ty>::from_be_bytes(x),
+								ByteOrder::LittleEndian => <$ty>::from_le_bytes(x),
+							}
+						}));
+					}
+
+					Ok(vec)
+				}
+			}
+		)*
+	};
+}
+
+implement_bulk! {
+	i8, i16, i32, i64,
+	u16, u32, u64,
+}
+
 impl<T: Readable> ReadableWithContext for Vec<T> {
 	type Context = usize;
 
@@ -72,13 +189,7 @@ impl<T: Readable> ReadableWithContext for Vec<T> {
 	where
 		Self: Sized,
 	{
-		let mut vec = Self::new();
-
-		for _ in 0..*context {
-			vec.push(T::read_from(reader)?);
-		}
-
-		Ok(vec)
+		T::read_vec(reader, *context)
 	}
 }
 
@@ -106,3 +217,97 @@ impl<T: X11Size + Clone> ReadableWithContext for RangeInclusive<T> {
 		Ok(Self::new(start.clone(), end.clone()))
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::ReadError;
+	use alloc::string::ToString;
+	use core::sync::atomic::{AtomicUsize, Ordering};
+
+	#[test]
+	fn test_read_exact_with_trailing_data() {
+		let bytes = [0x01, 0x02, 0x03];
+
+		assert_eq!(u16::read_from_bytes(&bytes).unwrap(), (0x0102, 2));
+		assert!(matches!(
+			u16::read_exact(&bytes),
+			Err(ReadError::TrailingData { remaining: 1 }),
+		));
+
+		assert_eq!(u16::read_exact(&bytes[..2]).unwrap(), 0x0102);
+	}
+
+	#[test]
+	fn test_read_exact_too_short() {
+		assert!(matches!(
+			<[u16; 2]>::read_exact(&[0x01, 0x02, 0x03]),
+			Err(ReadError::UnexpectedEndOfData {
+				expected: 2,
+				found: 1,
+			}),
+		));
+	}
+
+	#[test]
+	fn test_nested_field_path() {
+		let error = u8::read_exact(&[])
+			.unwrap_err()
+			.in_field("Point", "x")
+			.in_field("Rectangle", "position");
+
+		assert_eq!(
+			error.to_string(),
+			"Rectangle.position.x: expected at least 1 bytes, found 0"
+		);
+		assert!(matches!(
+			error.root_cause(),
+			ReadError::UnexpectedEndOfData { .. },
+		));
+	}
+
+	#[test]
+	fn test_read_tuple() {
+		let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+		assert_eq!(
+			<(u8, u16, [u8; 2], bool)>::read_exact(&bytes).unwrap(),
+			(0x01, 0x0203, [0x04, 0x05], true),
+		);
+		assert!(<(u8, u16, [u8; 2], bool)>::read_exact(&bytes[..5]).is_err());
+	}
+
+	/// The number of [`Counted`] values which have been dropped.
+	static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+	/// A byte which counts how many times it is dropped.
+	struct Counted;
+
+	impl X11Size for Counted {
+		fn x11_size(&self) -> usize {
+			1
+		}
+	}
+
+	impl Readable for Counted {
+		fn read_from(reader: &mut impl Buf) -> ReadResult<Self> {
+			u8::read_from(reader)?;
+
+			Ok(Self)
+		}
+	}
+
+	impl Drop for Counted {
+		fn drop(&mut self) {
+			DROPPED.fetch_add(1, Ordering::SeqCst);
+		}
+	}
+
+	#[test]
+	fn test_read_array_partial_failure() {
+		// Only three of the four elements can be read.
+		assert!(<[Counted; 4]>::read_from(&mut &[0_u8; 3][..]).is_err());
+		// The three elements which were read must have been dropped exactly once.
+		assert_eq!(DROPPED.load(Ordering::SeqCst), 3);
+	}
+}
@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`ReadableRef`] and [`ReadableRefWithContext`] implementations.
+
+use alloc::vec::Vec;
+
+use crate::{ensure_remaining, ReadResult, Readable, ReadableRef, ReadableRefWithContext};
+
+impl<'a, T: Readable> ReadableRef<'a> for T {
+	fn read_ref(mut bytes: &'a [u8]) -> ReadResult<(Self, &'a [u8])> {
+		let value = T::read_from(&mut bytes)?;
+
+		Ok((value, bytes))
+	}
+}
+
+impl<'a> ReadableRefWithContext<'a> for &'a [u8] {
+	type Context = usize;
+
+	fn read_ref_with(bytes: &'a [u8], length: &usize) -> ReadResult<(Self, &'a [u8])> {
+		ensure_remaining(&bytes, *length)?;
+
+		Ok(bytes.split_at(*length))
+	}
+}
+
+impl<'a, T: ReadableRef<'a>> ReadableRefWithContext<'a> for Vec<T> {
+	type Context = usize;
+
+	fn read_ref_with(mut bytes: &'a [u8], count: &usize) -> ReadResult<(Self, &'a [u8])> {
+		// Every element is at least one byte, so a `count` sent by the other
+		// end of the connection cannot make this allocate more than `bytes`.
+		let mut values = Vec::with_capacity((*count).min(bytes.len()));
+
+		for _ in 0..*count {
+			values.push(read_ref(&mut bytes)?);
+		}
+
+		Ok((values, bytes))
+	}
+}
+
+/// Reads a `T` from the start of the given `bytes` with [`ReadableRef`], and
+/// advances `bytes` past it.
+///
+/// This is used by the [`ReadableRef`] implementations generated by
+/// `derive_xrb!`.
+///
+/// # Errors
+/// Returns any error returned by [`ReadableRef::read_ref`].
+pub fn read_ref<'a, T: ReadableRef<'a>>(bytes: &mut &'a [u8]) -> ReadResult<T> {
+	let (value, remaining) = T::read_ref(bytes)?;
+	*bytes = remaining;
+
+	Ok(value)
+}
+
+/// Reads a `T` from the start of the given `bytes` with
+/// [`ReadableRefWithContext`], and advances `bytes` past it.
+///
+/// This is used by the [`ReadableRef`] implementations generated by
+/// `derive_xrb!`.
+///
+/// # Errors
+/// Returns any error returned by [`ReadableRefWithContext::read_ref_with`].
+pub fn read_ref_with<'a, T: ReadableRefWithContext<'a>>(
+	bytes: &mut &'a [u8], context: &T::Context,
+) -> ReadResult<T> {
+	let (value, remaining) = T::read_ref_with(bytes, context)?;
+	*bytes = remaining;
+
+	Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_read_vec_of_slices() {
+		#[derive(Debug, PartialEq)]
+		struct Name<'a>(&'a [u8]);
+
+		impl<'a> ReadableRef<'a> for Name<'a> {
+			fn read_ref(mut bytes: &'a [u8]) -> ReadResult<(Self, &'a [u8])> {
+				let len: u8 = read_ref(&mut bytes)?;
+				let name = read_ref_with(&mut bytes, &usize::from(len))?;
+
+				Ok((Self(name), bytes))
+			}
+		}
+
+		let bytes = [2, b'a', b'b', 0, 1, b'c', 0xff];
+		let (names, remaining) = Vec::<Name>::read_ref_with(&bytes, &3).unwrap();
+
+		assert_eq!(names, [Name(b"ab"), Name(b""), Name(b"c")]);
+		assert_eq!(remaining, [0xff]);
+	}
+
+	#[test]
+	fn test_read_vec_too_short() {
+		assert!(Vec::<u16>::read_ref_with(&[0, 1, 2], &2).is_err());
+	}
+}
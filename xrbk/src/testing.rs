@@ -0,0 +1,142 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Utilities for testing [`Readable`] implementations.
+//!
+//! [`Readable`]: crate::Readable
+
+use bytes::Buf;
+
+/// A [`Buf`] which splits the bytes it wraps into fragments of `1` to `max`
+/// bytes, as a vectored read into a ring of small buffers might.
+///
+/// [`chunk`] never returns more than the current fragment, so reading from a
+/// `FragmentedBuf` fails, or reads the wrong values, if a [`Readable`]
+/// implementation assumes that the rest of a message is contiguous.
+///
+/// The fragments are `1`, `2`, and so on up to `max` bytes long, repeating
+/// from `1` after `max`.
+///
+/// ```
+/// use xrbk::{testing::FragmentedBuf, Buf};
+///
+/// let mut buf = FragmentedBuf::new(&[1, 2, 3, 4, 5, 6, 7], 3);
+///
+/// assert_eq!(buf.chunk(), [1]);
+/// buf.advance(1);
+/// assert_eq!(buf.chunk(), [2, 3]);
+/// buf.advance(2);
+/// assert_eq!(buf.chunk(), [4, 5, 6]);
+/// buf.advance(2);
+///
+/// // Reading past the end of a fragment continues into the next one.
+/// assert_eq!(buf.get_u16(), 0x0607);
+/// assert!(!buf.has_remaining());
+/// ```
+///
+/// [`chunk`]: Buf::chunk
+/// [`Readable`]: crate::Readable
+#[derive(Clone, Debug)]
+pub struct FragmentedBuf<'a> {
+	bytes: &'a [u8],
+	/// The number of bytes which have been read.
+	position: usize,
+
+	/// The position at which the current fragment ends.
+	fragment_end: usize,
+	/// The size of the fragment after the current one.
+	next_size: usize,
+	/// The maximum size of a fragment.
+	max: usize,
+}
+
+impl<'a> FragmentedBuf<'a> {
+	/// Creates a new `FragmentedBuf` which splits the given `bytes` into
+	/// fragments of no more than `max` bytes.
+	///
+	/// # Panics
+	/// Panics if `max` is `0`.
+	#[must_use]
+	pub fn new(bytes: &'a [u8], max: usize) -> Self {
+		assert_ne!(max, 0, "fragments must be at least one byte long");
+
+		Self {
+			bytes,
+			position: 0,
+
+			fragment_end: 1.min(bytes.len()),
+			next_size: 2.min(max),
+			max,
+		}
+	}
+}
+
+impl Buf for FragmentedBuf<'_> {
+	fn remaining(&self) -> usize {
+		self.bytes.len() - self.position
+	}
+
+	fn chunk(&self) -> &[u8] {
+		&self.bytes[self.position..self.fragment_end]
+	}
+
+	fn advance(&mut self, cnt: usize) {
+		assert!(
+			cnt <= self.remaining(),
+			"attempted to advance past the end of the buffer"
+		);
+
+		self.position += cnt;
+
+		// Move on to the fragment containing the new position.
+		while self.fragment_end <= self.position && self.fragment_end < self.bytes.len() {
+			self.fragment_end = (self.fragment_end + self.next_size).min(self.bytes.len());
+			self.next_size = self.next_size % self.max + 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use alloc::vec::Vec;
+
+	/// Returns the sizes of the fragments which the `bytes` are split into.
+	fn fragment_sizes(bytes: &[u8], max: usize) -> Vec<usize> {
+		let mut buf = FragmentedBuf::new(bytes, max);
+		let mut sizes = Vec::new();
+
+		while buf.has_remaining() {
+			let size = buf.chunk().len();
+
+			sizes.push(size);
+			buf.advance(size);
+		}
+
+		sizes
+	}
+
+	#[test]
+	fn test_fragment_sizes() {
+		assert_eq!(fragment_sizes(&[0; 12], 3), [1, 2, 3, 1, 2, 3]);
+		assert_eq!(fragment_sizes(&[0; 5], 1), [1, 1, 1, 1, 1]);
+		// The last fragment is cut short by the end of the bytes.
+		assert_eq!(fragment_sizes(&[0; 8], 4), [1, 2, 3, 2]);
+		assert_eq!(fragment_sizes(&[], 4), [0; 0]);
+	}
+
+	#[test]
+	fn test_advance_across_fragments() {
+		let bytes: Vec<u8> = (0..10).collect();
+		let mut buf = FragmentedBuf::new(&bytes, 3);
+
+		buf.advance(4);
+		assert_eq!(buf.chunk(), [4, 5]);
+
+		let mut rest = [0; 6];
+		buf.copy_to_slice(&mut rest);
+		assert_eq!(rest, [4, 5, 6, 7, 8, 9]);
+		assert!(!buf.has_remaining());
+	}
+}
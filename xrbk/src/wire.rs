@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! [`WireField`] and the hex dumps produced by [`WireLayout::fmt_wire`].
+//!
+//! [`WireLayout::fmt_wire`]: crate::WireLayout::fmt_wire
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+/// The number of bytes shown on each line of a hex dump.
+const BYTES_PER_LINE: usize = 16;
+
+/// Where one element of a type was written in its serialized bytes.
+///
+/// See [`WireLayout`] for more information.
+///
+/// [`WireLayout`]: crate::WireLayout
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WireField {
+	/// The name of the element.
+	///
+	/// This is the name of the field or `let` element, `_` for unused bytes,
+	/// or the name of a part of a message's header (such as `major_opcode` or
+	/// `length`).
+	pub name: &'static str,
+
+	/// The offset of the element's first byte from the start of the type's
+	/// serialized bytes.
+	pub offset: usize,
+	/// The number of bytes which the element was written as.
+	pub size: usize,
+}
+
+/// Formats the given `bytes` as a hex dump, annotated with the names of the
+/// given `fields`.
+///
+/// Each field starts on a new line, beginning with the offset of its first
+/// byte. Fields of more than 16 bytes continue onto following lines.
+pub fn format(bytes: &[u8], fields: &[WireField]) -> String {
+	let name_width = fields
+		.iter()
+		.map(|field| field.name.len())
+		.max()
+		.unwrap_or(0);
+	let mut dump = String::new();
+
+	for field in fields {
+		let field_bytes = &bytes[field.offset..field.offset + field.size];
+
+		if field_bytes.is_empty() {
+			writeln!(dump, "{:>6}  {}", field.offset, field.name).unwrap();
+		}
+
+		for (i, line) in field_bytes.chunks(BYTES_PER_LINE).enumerate() {
+			let name = if i == 0 { field.name } else { "" };
+			let hex: Vec<_> = line.iter().map(|byte| format!("{byte:02x}")).collect();
+
+			writeln!(
+				dump,
+				"{:>6}  {name:<name_width$}  {}",
+				field.offset + i * BYTES_PER_LINE,
+				hex.join(" "),
+			)
+			.unwrap();
+		}
+	}
+
+	dump
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_format() {
+		let bytes: Vec<u8> = (0..24).collect();
+		let fields = [
+			WireField {
+				name: "code",
+				offset: 0,
+				size: 1,
+			},
+			WireField {
+				name: "_",
+				offset: 1,
+				size: 0,
+			},
+			WireField {
+				name: "data",
+				offset: 1,
+				size: 23,
+			},
+		];
+
+		assert_eq!(
+			format(&bytes, &fields),
+			"     0  code  00\n     1  _\n     1  data  01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e \
+			 0f 10\n    17        11 12 13 14 15 16 17\n",
+		);
+	}
+}
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use core::convert::Infallible;
+
 use crate::Wrap;
 
 macro_rules! impl_wrap {
@@ -9,6 +11,11 @@ macro_rules! impl_wrap {
 		$($(
 			impl Wrap for $type {
 				type Integer = Self;
+				type Error = Infallible;
+
+				fn try_wrap(integer: Self) -> Result<Self, Infallible> {
+					Ok(integer)
+				}
 			}
 		)+)?
 	}
@@ -4,7 +4,8 @@
 
 //! [`Writable`] implementations for primitive types
 
-use crate::{Writable, WriteResult};
+use crate::{byte_order::KnownByteOrder, ByteOrder, Writable, WriteResult};
+use alloc::{boxed::Box, vec::Vec};
 use bytes::BufMut;
 
 macro_rules! implement {
@@ -44,9 +45,17 @@ implement! {
 	b: &bool => BufMut::put_u8(u8::from(*b)),
 }
 
-impl<T: Writable> Writable for &[T] {
-	fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
-		for x in *self {
+/// Writes a slice of values one after the other.
+///
+/// This is implemented for every [`Writable`] type, and specialized for
+/// integers so that lists of them are written in bulk.
+trait WriteSlice: Writable + Sized {
+	fn write_slice(slice: &[Self], writer: &mut impl BufMut) -> WriteResult;
+}
+
+impl<T: Writable> WriteSlice for T {
+	default fn write_slice(slice: &[Self], writer: &mut impl BufMut) -> WriteResult {
+		for x in slice {
 			x.write_to(writer)?;
 		}
 
@@ -54,23 +63,101 @@ impl<T: Writable> Writable for &[T] {
 	}
 }
 
-impl<T: Writable, const N: usize> Writable for [T; N] {
-	fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
-		for x in self {
-			x.write_to(writer)?;
-		}
+impl WriteSlice for u8 {
+	fn write_slice(slice: &[Self], writer: &mut impl BufMut) -> WriteResult {
+		writer.put_slice(slice);
 
 		Ok(())
 	}
 }
 
-impl<T: Writable> Writable for Vec<T> {
+/// The number of bytes converted at a time when writing lists of multi-byte
+/// integers.
+const CHUNK_SIZE: usize = 256;
+
+/// Implements [`WriteSlice`] for integers by converting them to bytes in
+/// chunks and writing each chunk with [`BufMut::put_slice`].
+///
+/// If the byte order of the buffer is not known, each integer is written with
+/// its [`Writable`] implementation instead.
+macro_rules! implement_bulk {
+	($($ty:ty),*$(,)?) => {
+		$(
+			impl WriteSlice for $ty {
+				fn write_slice(slice: &[Self], writer: &mut impl BufMut) -> WriteResult {
+					const SIZE: usize = core::mem::size_of::<$ty>();
+
+					let Some(order) = writer.known_byte_order() else {
+						for x in slice {
+							x.write_to(writer)?;
+						}
+
+						return Ok(());
+					};
+
+					let mut chunk = [0; CHUNK_SIZE];
+
+					for elements in slice.chunks(CHUNK_SIZE / SIZE) {
+						for (bytes, x) in chunk.chunks_exact_mut(SIZE).zip(elements) {
+							bytes.copy_from_slice(&match order {
+								ByteOrder::BigEndian => x.to_be_bytes(),
+								ByteOrder::LittleEndian => x.to_le_bytes(),
+							});
+						}
+
+						writer.put_slice(&chunk[..elements.len() * SIZE]);
+					}
+
+					Ok(())
+				}
+			}
+		)*
+	};
+}
+
+implement_bulk! {
+	i8, i16, i32, i64,
+	u16, u32, u64,
+}
+
+impl<T: Writable> Writable for &[T] {
 	fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
-		for x in self {
-			x.write_to(writer)?;
-		}
+		T::write_slice(self, writer)
+	}
+}
 
-		Ok(())
+impl<T: Writable, const N: usize> Writable for [T; N] {
+	fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
+		T::write_slice(self, writer)
+	}
+}
+
+/// Implements [`Writable`] for tuples of [`Writable`] types, which are written
+/// in order.
+macro_rules! implement_tuple {
+	($(($($T:ident: $index:tt),+)),+$(,)?) => {
+		$(
+			impl<$($T: Writable),+> Writable for ($($T,)+) {
+				fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
+					$(self.$index.write_to(writer)?;)+
+
+					Ok(())
+				}
+			}
+		)+
+	};
+}
+
+implement_tuple! {
+	(A: 0),
+	(A: 0, B: 1),
+	(A: 0, B: 1, C: 2),
+	(A: 0, B: 1, C: 2, D: 3),
+}
+
+impl<T: Writable> Writable for Vec<T> {
+	fn write_to(&self, writer: &mut impl BufMut) -> WriteResult {
+		T::write_slice(self, writer)
 	}
 }
 
@@ -97,3 +184,30 @@ impl<T: Writable> Writable for Box<T> {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use bytes::BytesMut;
+
+	#[test]
+	fn test_write_into_exact_appends() {
+		let mut buf = BytesMut::new();
+
+		0x0102_u16.write_into_exact(&mut buf).unwrap();
+		[3_u8, 4, 5].write_into_exact(&mut buf).unwrap();
+
+		assert_eq!(buf[..], [1, 2, 3, 4, 5]);
+	}
+
+	#[test]
+	fn test_write_tuple() {
+		let mut buf = BytesMut::new();
+
+		(0x01_u8, 0x0203_u16, [4_u8, 5], true)
+			.write_into_exact(&mut buf)
+			.unwrap();
+
+		assert_eq!(buf[..], [1, 2, 3, 4, 5, 1]);
+	}
+}
@@ -5,14 +5,15 @@
 //! [`X11Size`] and [`ConstantX11Size`] implementations for primitive types
 
 use crate::{ConstantX11Size, X11Size};
-use std::ops::{Range, RangeInclusive};
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::{Range, RangeInclusive};
 
 /// Simple macro for easely defining size for primitive types
 macro_rules! constant_x11_size {
 	($($type:ty),+$(,)?) => {
 		$(
 			impl ConstantX11Size for $type {
-				const X11_SIZE: usize = std::mem::size_of::<Self>();
+				const X11_SIZE: usize = core::mem::size_of::<Self>();
 			}
 
 			impl X11Size for $type {
@@ -61,6 +62,35 @@ impl<T: X11Size, const N: usize> X11Size for [T; N] {
 	}
 }
 
+impl<T: ConstantX11Size, const N: usize> ConstantX11Size for [T; N] {
+	const X11_SIZE: usize = T::X11_SIZE * N;
+}
+
+/// Implements [`X11Size`] for tuples of [`X11Size`] types, and
+/// [`ConstantX11Size`] for tuples of [`ConstantX11Size`] types.
+macro_rules! implement_tuple {
+	($(($($T:ident: $index:tt),+)),+$(,)?) => {
+		$(
+			impl<$($T: X11Size),+> X11Size for ($($T,)+) {
+				fn x11_size(&self) -> usize {
+					0 $(+ self.$index.x11_size())+
+				}
+			}
+
+			impl<$($T: ConstantX11Size),+> ConstantX11Size for ($($T,)+) {
+				const X11_SIZE: usize = 0 $(+ $T::X11_SIZE)+;
+			}
+		)+
+	};
+}
+
+implement_tuple! {
+	(A: 0),
+	(A: 0, B: 1),
+	(A: 0, B: 1, C: 2),
+	(A: 0, B: 1, C: 2, D: 3),
+}
+
 impl<T: X11Size> X11Size for &[T] {
 	fn x11_size(&self) -> usize {
 		let mut x11_size: usize = 0;
@@ -156,6 +186,7 @@ impl<T: X11Size + ConstantX11Size> ConstantX11Size for RangeInclusive<T> {
 #[cfg(test)]
 mod test {
 	use super::X11Size;
+	use alloc::vec;
 
 	#[test]
 	fn test_x11_size_vec() {
@@ -5,9 +5,9 @@
 mod expansion;
 pub mod parsing;
 
-use syn::{punctuated::Punctuated, token, Path, Token};
+use syn::{punctuated::Punctuated, token, Expr, Ident, Path, Token};
 
-use crate::Source;
+use crate::{Source, SourceArg};
 
 /// An attribute which places an [`Element`] in the metabyte position.
 ///
@@ -33,6 +33,11 @@ pub struct MetabyteAttribute {
 /// > _SequenceAttribute_ :\
 /// > &nbsp;&nbsp; `#` `[` `sequence` `]`
 ///
+/// The sequence field is ignored by `PartialEq` and `Hash` implementations
+/// derived with `derivative`, and an `eq_including_sequence` method is
+/// generated which compares it too. Deriving `PartialEq` or `Hash` with a
+/// normal `#[derive(...)]` attribute is an error.
+///
 /// [`Field`]: crate::element::Field
 pub struct SequenceAttribute {
 	/// A hash token: `#`.
@@ -142,6 +147,139 @@ pub struct HideAttribute {
 	pub hidden_traits: Punctuated<Path, Token![,]>,
 }
 
+/// An attribute which indicates that an optional [`Field`] is only present if
+/// another boolean field is `true`.
+///
+/// When read, the [`Field`] is [`Some`] only if the `condition` is `true`; its
+/// bytes are skipped otherwise. When written, [`None`] is written as zero
+/// bytes.
+///
+/// > **<sup>Syntax</sup>**\
+/// > _PresentIfAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `present_if` `(` [IDENTIFIER] `)` `]`
+/// >
+/// > [IDENTIFIER]: https://doc.rust-lang.org/reference/identifiers.html
+///
+/// [`Field`]: crate::element::Field
+pub struct PresentIfAttribute {
+	/// A hash token: `#`.
+	pub hash_token: Token![#],
+	/// A pair of square brackets (`[` and `]`) surrounding the `path`.
+	pub bracket_token: token::Bracket,
+
+	/// The attribute path: `present_if` for a `PresentIfAttribute`.
+	pub path: Path,
+
+	/// A pair of normal brackets (`(` and `)`) surrounding the `condition`.
+	pub paren_token: token::Paren,
+
+	/// The boolean field or let element which determines whether the
+	/// [`Field`] is present.
+	///
+	/// [`Field`]: crate::element::Field
+	pub condition: SourceArg,
+}
+
+/// An attribute which indicates that the variant of a [`Field`] is selected by
+/// an earlier field, rather than by a discriminant written before it.
+///
+/// When read, the earlier field is given as the context with which the
+/// [`Field`]'s type is read. When written, the [`Field`] is checked to match
+/// the earlier field, returning an error if it does not, rather than writing
+/// bytes which would be read back as a different variant. The [`Field`]'s
+/// type must implement [`xrbk::Discriminated`].
+///
+/// > **<sup>Syntax</sup>**\
+/// > _DiscriminatedByAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `discriminated_by` `(` [IDENTIFIER] `)` `]`
+/// >
+/// > [IDENTIFIER]: https://doc.rust-lang.org/reference/identifiers.html
+///
+/// [`Field`]: crate::element::Field
+/// [`xrbk::Discriminated`]: https://docs.rs/xrbk/latest/xrbk/trait.Discriminated.html
+pub struct DiscriminatedByAttribute {
+	/// A hash token: `#`.
+	pub hash_token: Token![#],
+	/// A pair of square brackets (`[` and `]`) surrounding the `path`.
+	pub bracket_token: token::Bracket,
+
+	/// The attribute path: `discriminated_by` for a
+	/// `DiscriminatedByAttribute`.
+	pub path: Path,
+
+	/// A pair of normal brackets (`(` and `)`) surrounding the
+	/// `discriminant`.
+	pub paren_token: token::Paren,
+
+	/// The field or let element which selects the [`Field`]'s variant.
+	///
+	/// [`Field`]: crate::element::Field
+	pub discriminant: SourceArg,
+}
+
+/// An attribute which gives a [`Field`] a default value in the builder
+/// generated by an `#[builder]` item attribute.
+///
+/// Without an expression, the default value is [`Default::default()`].
+///
+/// > **<sup>Syntax</sup>**\
+/// > _DefaultAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `default` ( `(` [_Expression_] `)` )<sup>?</sup> `]`
+/// >
+/// > [_Expression_]: https://doc.rust-lang.org/reference/expressions.html
+///
+/// [`Field`]: crate::element::Field
+pub struct DefaultAttribute {
+	/// A hash token: `#`.
+	pub hash_token: Token![#],
+	/// A pair of square brackets (`[` and `]`) surrounding the `path`.
+	pub bracket_token: token::Bracket,
+
+	/// The attribute path: `default` for a `DefaultAttribute`.
+	pub path: Path,
+
+	/// An optional expression, surrounded by normal brackets (`(` and `)`),
+	/// giving the default value.
+	pub default: Option<(token::Paren, Expr)>,
+}
+
+/// An attribute which gives the function with which a [`Field`], or a whole
+/// item, is generated in the `arbitrary::Arbitrary` implementation generated by
+/// `derive_xrb!`.
+///
+/// The function takes a `&mut arbitrary::Unstructured` and returns an
+/// `arbitrary::Result` of the [`Field`]'s or item's type. It is used for values
+/// which are restricted in ways that `derive_xrb!` cannot infer.
+///
+/// > **<sup>Syntax</sup>**\
+/// > _ArbitraryAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `arbitrary` `(` `with` `=` [_Expression_] `)` `]`
+/// >
+/// > [_Expression_]: https://doc.rust-lang.org/reference/expressions.html
+///
+/// [`Field`]: crate::element::Field
+pub struct ArbitraryAttribute {
+	/// A hash token: `#`.
+	pub hash_token: Token![#],
+	/// A pair of square brackets (`[` and `]`) surrounding the `path`.
+	pub bracket_token: token::Bracket,
+
+	/// The attribute path: `arbitrary` for an `ArbitraryAttribute`.
+	pub path: Path,
+
+	/// A pair of normal brackets (`(` and `)`) surrounding the `function`.
+	pub paren_token: token::Paren,
+	/// The `with` keyword preceding the `function`.
+	pub with_token: Ident,
+	/// An equals token (`=`) preceding the `function`.
+	pub equals_token: Token![=],
+
+	/// The function which generates the [`Field`].
+	///
+	/// [`Field`]: crate::element::Field
+	pub function: Expr,
+}
+
 /// An attribute which provides the [`ContextualReadable::Context`] for a type
 /// implementing [`xrbk::ContextualReadable`].
 ///
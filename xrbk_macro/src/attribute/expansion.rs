@@ -76,6 +76,68 @@ impl ToTokens for HideAttribute {
 	}
 }
 
+impl ToTokens for PresentIfAttribute {
+	fn to_tokens(&self, tokens: &mut TokenStream) {
+		// `#`.
+		self.hash_token.to_tokens(tokens);
+		// Square brackets surrounding `present_if` and the condition.
+		self.bracket_token.surround(tokens, |tokens| {
+			self.path.to_tokens(tokens);
+			self.paren_token.surround(tokens, |tokens| {
+				self.condition.ident.to_tokens(tokens);
+			});
+		});
+	}
+}
+
+impl ToTokens for DiscriminatedByAttribute {
+	fn to_tokens(&self, tokens: &mut TokenStream) {
+		// `#`.
+		self.hash_token.to_tokens(tokens);
+		// Square brackets surrounding `discriminated_by` and the discriminant.
+		self.bracket_token.surround(tokens, |tokens| {
+			self.path.to_tokens(tokens);
+			self.paren_token.surround(tokens, |tokens| {
+				self.discriminant.ident.to_tokens(tokens);
+			});
+		});
+	}
+}
+
+impl ToTokens for DefaultAttribute {
+	fn to_tokens(&self, tokens: &mut TokenStream) {
+		// `#`.
+		self.hash_token.to_tokens(tokens);
+		// Square brackets surrounding `default` and the default value.
+		self.bracket_token.surround(tokens, |tokens| {
+			self.path.to_tokens(tokens);
+
+			if let Some((paren_token, default)) = &self.default {
+				paren_token.surround(tokens, |tokens| {
+					default.to_tokens(tokens);
+				});
+			}
+		});
+	}
+}
+
+impl ToTokens for ArbitraryAttribute {
+	fn to_tokens(&self, tokens: &mut TokenStream) {
+		// `#`.
+		self.hash_token.to_tokens(tokens);
+		// Square brackets surrounding `arbitrary` and the function.
+		self.bracket_token.surround(tokens, |tokens| {
+			self.path.to_tokens(tokens);
+
+			self.paren_token.surround(tokens, |tokens| {
+				self.with_token.to_tokens(tokens);
+				self.equals_token.to_tokens(tokens);
+				self.function.to_tokens(tokens);
+			});
+		});
+	}
+}
+
 impl ToTokens for ContextAttribute {
 	fn to_tokens(&self, tokens: &mut TokenStream) {
 		// `#`.
@@ -13,6 +13,8 @@ use syn::{
 	spanned::Spanned,
 	AttrStyle,
 	Attribute,
+	Meta,
+	NestedMeta,
 };
 
 use super::*;
@@ -37,6 +39,14 @@ pub struct ParsedAttributes {
 	pub error_data_attribute: Option<ErrorDataAttribute>,
 	/// A hide attribute, if one was parsed.
 	pub hide_attribute: Option<HideAttribute>,
+	/// A present if attribute, if one was parsed.
+	pub present_if_attribute: Option<PresentIfAttribute>,
+	/// A discriminated by attribute, if one was parsed.
+	pub discriminated_by_attribute: Option<DiscriminatedByAttribute>,
+	/// A default attribute, if one was parsed.
+	pub default_attribute: Option<DefaultAttribute>,
+	/// An arbitrary attribute, if one was parsed.
+	pub arbitrary_attribute: Option<ArbitraryAttribute>,
 }
 
 pub struct ParsedItemAttributes {
@@ -47,6 +57,18 @@ pub struct ParsedItemAttributes {
 	pub derive_writables: Punctuated<Path, Token![,]>,
 	pub derive_readables: Punctuated<Path, Token![,]>,
 	pub derive_readable_with_contexts: Punctuated<Path, Token![,]>,
+	pub derive_readable_refs: Punctuated<Path, Token![,]>,
+
+	/// `?ConstantX11Size` derives, which only implement `ConstantX11Size` if
+	/// every element has a constant size.
+	pub maybe_derive_constant_x11_sizes: Punctuated<Path, Token![,]>,
+
+	/// An `#[x11_compat]` attribute, if one was parsed.
+	pub x11_compat: Option<Path>,
+	/// A `#[builder]` attribute, if one was parsed.
+	pub builder: Option<Path>,
+	/// An `#[arbitrary(with = ...)]` attribute, if one was parsed.
+	pub arbitrary_attribute: Option<ArbitraryAttribute>,
 }
 
 impl ParsedItemAttributes {
@@ -56,6 +78,86 @@ impl ParsedItemAttributes {
 			|| !self.derive_writables.is_empty()
 			|| !self.derive_readables.is_empty()
 			|| !self.derive_readable_with_contexts.is_empty()
+			|| !self.derive_readable_refs.is_empty()
+			|| !self.maybe_derive_constant_x11_sizes.is_empty()
+			|| self.x11_compat.is_some()
+			|| self.builder.is_some()
+	}
+
+	/// The path with which to infer a `ConstantX11Size` implementation, if it
+	/// should be inferred.
+	///
+	/// `ConstantX11Size` is inferred if it is derived with `?ConstantX11Size`,
+	/// or if `X11Size` is derived but `ConstantX11Size` is not.
+	pub fn inferred_constant_x11_size(&self) -> Option<&Path> {
+		if !self.derive_constant_x11_sizes.is_empty() {
+			return None;
+		}
+
+		self.maybe_derive_constant_x11_sizes
+			.first()
+			.or_else(|| self.derive_x11_sizes.first())
+	}
+
+	/// The path of the given `trait` if it is derived with a normal
+	/// `#[derive(...)]` attribute.
+	pub fn derive(&self, r#trait: &str) -> Option<Path> {
+		listed_traits(&self.attributes, "derive").find(|path| path.is_ident(r#trait))
+	}
+
+	/// Whether the given `trait` is derived with a `#[derivative(...)]`
+	/// attribute.
+	pub fn derives_with_derivative(&self, r#trait: &str) -> bool {
+		listed_traits(&self.attributes, "derivative").any(|path| path.is_ident(r#trait))
+	}
+}
+
+/// The paths of the traits listed in any of the given `attributes` with the
+/// given `name`.
+///
+/// For example, `listed_traits(attributes, "derive")` returns `Debug` and
+/// `Clone` for `#[derive(Debug, Clone)]`, and `listed_traits(attributes,
+/// "derivative")` returns `Hash` for `#[derivative(Hash = "ignore")]`.
+pub fn listed_traits<'a>(
+	attributes: &'a [Attribute], name: &'a str,
+) -> impl Iterator<Item = Path> + 'a {
+	attributes
+		.iter()
+		.filter(move |attribute| attribute.path.is_ident(name))
+		.filter_map(|attribute| match attribute.parse_meta() {
+			Ok(Meta::List(list)) => Some(list.nested),
+			_ => None,
+		})
+		.flatten()
+		.filter_map(|nested| match nested {
+			NestedMeta::Meta(meta) => Some(meta.path().clone()),
+			NestedMeta::Lit(_) => None,
+		})
+}
+
+impl ArbitraryAttribute {
+	/// Parses the `(with = function)` which follows the `path` of an
+	/// `ArbitraryAttribute` in the given `content`.
+	fn parse_content(
+		hash_token: Token![#], bracket_token: token::Bracket, path: Path, content: ParseStream,
+	) -> Result<Self> {
+		let inner_content;
+		let paren_token = parenthesized!(inner_content in content);
+
+		let with_token: Ident = inner_content.parse()?;
+		if with_token != "with" {
+			return Err(syn::Error::new(with_token.span(), "expected `with`"));
+		}
+
+		Ok(Self {
+			hash_token,
+			bracket_token,
+			path,
+			paren_token,
+			with_token,
+			equals_token: inner_content.parse()?,
+			function: inner_content.parse()?,
+		})
 	}
 }
 
@@ -75,6 +177,10 @@ impl ParseWithContext for ParsedAttributes {
 		let mut major_opcode_attribute = None;
 		let mut error_data_attribute = None;
 		let mut hide_attribute = None;
+		let mut present_if_attribute = None;
+		let mut discriminated_by_attribute = None;
+		let mut default_attribute = None;
+		let mut arbitrary_attribute = None;
 
 		// While there are still attributes remaining...
 		while input.peek(Token![#]) && input.peek2(token::Bracket) {
@@ -196,6 +302,83 @@ impl ParseWithContext for ParsedAttributes {
 					paren_token: parenthesized!(inner_content in content),
 					hidden_traits: inner_content.parse_terminated(Path::parse)?,
 				});
+			// If the name is `present_if`, parse it as a present if attribute.
+			} else if path.is_ident("present_if") {
+				if present_if_attribute.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one present if attribute is allowed per element",
+					));
+				}
+
+				let ((let_map, field_map), _) = context;
+
+				let inner_content;
+				present_if_attribute = Some(PresentIfAttribute {
+					hash_token,
+					bracket_token,
+					path,
+					paren_token: parenthesized!(inner_content in content),
+					condition: inner_content.parse_with((let_map, Some(field_map)))?,
+				});
+			// If the name is `discriminated_by`, parse it as a discriminated by
+			// attribute.
+			} else if path.is_ident("discriminated_by") {
+				if discriminated_by_attribute.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one discriminated by attribute is allowed per element",
+					));
+				}
+
+				let ((let_map, field_map), _) = context;
+
+				let inner_content;
+				discriminated_by_attribute = Some(DiscriminatedByAttribute {
+					hash_token,
+					bracket_token,
+					path,
+					paren_token: parenthesized!(inner_content in content),
+					discriminant: inner_content.parse_with((let_map, Some(field_map)))?,
+				});
+			// If the name is `default`, parse it as a default attribute.
+			} else if path.is_ident("default") {
+				if default_attribute.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one default attribute is allowed per element",
+					));
+				}
+
+				let default = if content.peek(token::Paren) {
+					let inner_content;
+
+					Some((parenthesized!(inner_content in content), inner_content.parse()?))
+				} else {
+					None
+				};
+
+				default_attribute = Some(DefaultAttribute {
+					hash_token,
+					bracket_token,
+					path,
+					default,
+				});
+			// If the name is `arbitrary`, parse it as an arbitrary attribute.
+			} else if path.is_ident("arbitrary") {
+				if arbitrary_attribute.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one arbitrary attribute is allowed per element",
+					));
+				}
+
+				arbitrary_attribute = Some(ArbitraryAttribute::parse_content(
+					hash_token,
+					bracket_token,
+					path,
+					&content,
+				)?);
 			// Otherwise, if the name was not `context`, `metabyte`, nor
 			// `sequence`, parse the attribute as a normal attribute.
 			} else {
@@ -212,6 +395,7 @@ impl ParseWithContext for ParsedAttributes {
 
 		if let Some(hide_attribute) = &hide_attribute
 			&& context_attribute.is_none()
+			&& discriminated_by_attribute.is_none()
 			&& hide_attribute
 				.hidden_traits
 				.iter()
@@ -233,6 +417,10 @@ impl ParseWithContext for ParsedAttributes {
 			major_opcode_attribute,
 			error_data_attribute,
 			hide_attribute,
+			present_if_attribute,
+			discriminated_by_attribute,
+			default_attribute,
+			arbitrary_attribute,
 		})
 	}
 }
@@ -246,6 +434,12 @@ impl Parse for ParsedItemAttributes {
 		let mut derive_writables = Punctuated::new();
 		let mut derive_readables = Punctuated::new();
 		let mut derive_readable_with_contexts = Punctuated::new();
+		let mut derive_readable_refs = Punctuated::new();
+		let mut maybe_derive_constant_x11_sizes = Punctuated::new();
+
+		let mut x11_compat = None;
+		let mut builder = None;
+		let mut arbitrary_attribute = None;
 
 		while input.peek(Token![#]) && input.peek2(token::Bracket) {
 			let content;
@@ -261,6 +455,7 @@ impl Parse for ParsedItemAttributes {
 				let mut paths = Punctuated::new();
 
 				while !inner.is_empty() {
+					let question_token = inner.parse::<Option<Token![?]>>()?;
 					let path = inner.parse::<Path>()?;
 
 					let comma = if inner.peek(Token![,]) {
@@ -270,7 +465,20 @@ impl Parse for ParsedItemAttributes {
 					};
 					let is_comma = comma.is_some();
 
-					if path.is_ident("X11Size") {
+					if let Some(question_token) = question_token {
+						if !path.is_ident("ConstantX11Size") {
+							return Err(syn::Error::new(
+								question_token.span(),
+								"only ConstantX11Size can be derived with `?`",
+							));
+						}
+
+						maybe_derive_constant_x11_sizes.push_value(path);
+
+						if let Some(comma) = comma {
+							maybe_derive_constant_x11_sizes.push_punct(comma);
+						}
+					} else if path.is_ident("X11Size") {
 						derive_x11_sizes.push_value(path);
 
 						if let Some(comma) = comma {
@@ -300,6 +508,12 @@ impl Parse for ParsedItemAttributes {
 						if let Some(comma) = comma {
 							derive_readable_with_contexts.push_punct(comma);
 						}
+					} else if path.is_ident("ReadableRef") {
+						derive_readable_refs.push_value(path);
+
+						if let Some(comma) = comma {
+							derive_readable_refs.push_punct(comma);
+						}
 					} else {
 						paths.push(path);
 
@@ -313,18 +527,54 @@ impl Parse for ParsedItemAttributes {
 					}
 				}
 
-				attributes.push(Attribute {
-					pound_token: hash_token,
-					style: AttrStyle::Outer,
+				// If every derived trait was an XRBK trait, there is nothing
+				// left to derive.
+				if !paths.is_empty() {
+					attributes.push(Attribute {
+						pound_token: hash_token,
+						style: AttrStyle::Outer,
+						bracket_token,
+						path,
+
+						tokens: TokenStream2::with_tokens(|tokens| {
+							paren_token.surround(tokens, |tokens| {
+								paths.to_tokens(tokens);
+							});
+						}),
+					});
+				}
+			} else if path.is_ident("x11_compat") {
+				if x11_compat.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one x11_compat attribute is allowed per item",
+					));
+				}
+
+				x11_compat = Some(path);
+			} else if path.is_ident("builder") {
+				if builder.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one builder attribute is allowed per item",
+					));
+				}
+
+				builder = Some(path);
+			} else if path.is_ident("arbitrary") {
+				if arbitrary_attribute.is_some() {
+					return Err(syn::Error::new(
+						path.span(),
+						"no more than one arbitrary attribute is allowed per item",
+					));
+				}
+
+				arbitrary_attribute = Some(ArbitraryAttribute::parse_content(
+					hash_token,
 					bracket_token,
 					path,
-
-					tokens: TokenStream2::with_tokens(|tokens| {
-						paren_token.surround(tokens, |tokens| {
-							paths.to_tokens(tokens);
-						});
-					}),
-				})
+					&content,
+				)?);
 			} else {
 				attributes.push(Attribute {
 					pound_token: hash_token,
@@ -345,6 +595,12 @@ impl Parse for ParsedItemAttributes {
 			derive_writables,
 			derive_readables,
 			derive_readable_with_contexts,
+			derive_readable_refs,
+			maybe_derive_constant_x11_sizes,
+
+			x11_compat,
+			builder,
+			arbitrary_attribute,
 		})
 	}
 }
@@ -119,16 +119,25 @@ pub struct Struct {
 /// > &nbsp;&nbsp;
 /// > [_OuterAttribute_]<sup>\*</sup>&nbsp;[_Visibility_]<sup>?</sup>
 /// > [_StructMetadata_]\
-/// > &nbsp;&nbsp;
-/// > `:`&nbsp;`Request`&nbsp;`(`&nbsp;_Meta_&nbsp;`)`&nbsp;_ReplyType_<sup>?
-/// > </sup>\
+/// > &nbsp;&nbsp; `:`&nbsp;(&nbsp;_CoreRequest_&nbsp;|&nbsp;_ExtensionRequest_&nbsp;)
+/// > &nbsp;_ReplyType_<sup>?</sup>\
 /// > &nbsp;&nbsp; [_StructlikeContent_]
 /// >
+/// > _CoreRequest_ :\
+/// > &nbsp;&nbsp; `Request`&nbsp;`(`&nbsp;_Meta_&nbsp;`)`
+/// >
+/// > _ExtensionRequest_ :\
+/// > &nbsp;&nbsp; `ExtensionRequest`&nbsp;`(`&nbsp;_ExtensionMeta_&nbsp;`)`
+/// >
 /// > _Meta_ :\
 /// > &nbsp;&nbsp; _MajorOpcode_\
 /// > &nbsp;&nbsp; ( `,` _MinorOpcode_ )<sup>?</sup>\
 /// > &nbsp;&nbsp; ( `,` _OtherErrors_ )<sup>?</sup>
 /// >
+/// > _ExtensionMeta_ :\
+/// > &nbsp;&nbsp; _MinorOpcode_\
+/// > &nbsp;&nbsp; ( `,` _OtherErrors_ )<sup>?</sup>
+/// >
 /// > _MajorOpcode_, _MinorOpcode_ :\
 /// > &nbsp;&nbsp; [_Expression_]
 /// >
@@ -162,13 +171,17 @@ pub struct Request {
 
 	/// A colon token: `:`.
 	pub colon_token: Token![:],
-	/// A path representing the `Request` trait.
+	/// A path representing the `Request` trait, or the `ExtensionRequest`
+	/// trait for an extension request.
 	pub request_token: Path,
 
 	/// A pair of normal brackets surrounding the opcodes: `(` and `)`.
 	pub paren_token: token::Paren,
 	/// An expression representing the major opcode associated with the request.
-	pub major_opcode: Expr,
+	///
+	/// This is `None` for extension requests, whose major opcode is assigned
+	/// to their extension by the X server.
+	pub major_opcode: Option<Expr>,
 	/// A comma token: `,`. This is required before the `minor_opcode`.
 	pub comma1: Option<Token![,]>,
 	/// An expression representing the minor opcode associated with the request.
@@ -189,6 +202,14 @@ pub struct Request {
 	pub content: StructlikeContent,
 }
 
+impl Request {
+	/// Whether this is an extension request, which is written with the major
+	/// opcode of its extension rather than one of its own.
+	pub const fn is_extension(&self) -> bool {
+		self.major_opcode.is_none()
+	}
+}
+
 /// A struct with metadata for reply messages and support for [`Element`]s.
 ///
 /// > **<sup>Syntax</sup>**\
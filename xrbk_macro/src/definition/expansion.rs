@@ -2,15 +2,88 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+mod arbitrary;
+mod builder;
+mod constant_x11_size;
+mod dyn_buffers;
+mod eq_including_sequence;
 mod message_trait;
 mod readable;
+mod readable_ref;
+mod trace;
+mod wire;
 mod writable;
+mod x11_compat;
 mod x11_size;
 
 use super::*;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::spanned::Spanned;
+use syn::{spanned::Spanned, GenericArgument, PathArguments, Type};
+
+use crate::attribute::parsing::ParsedItemAttributes;
+
+/// Generates an error if the given attributes contain an `#[x11_compat]`
+/// attribute, which is only supported for requests.
+fn reject_x11_compat(attrs: &ParsedItemAttributes, tokens: &mut TokenStream) {
+	if let Some(path) = &attrs.x11_compat {
+		syn::Error::new(path.span(), "x11_compat is only supported for requests")
+			.to_compile_error()
+			.to_tokens(tokens);
+	}
+}
+
+/// Generates an error if the given attributes contain a `#[builder]`
+/// attribute, which is only supported for requests.
+fn reject_builder(attrs: &ParsedItemAttributes, tokens: &mut TokenStream) {
+	if let Some(path) = &attrs.builder {
+		syn::Error::new(path.span(), "builder is only supported for requests")
+			.to_compile_error()
+			.to_tokens(tokens);
+	}
+}
+
+/// Generates an error for each `ReadableRef` derive in the given attributes,
+/// as `ReadableRef` can only be derived for structs.
+fn reject_readable_ref(attrs: &ParsedItemAttributes, tokens: &mut TokenStream) {
+	for path in &attrs.derive_readable_refs {
+		syn::Error::new(path.span(), "ReadableRef can only be derived for structs")
+			.to_compile_error()
+			.to_tokens(tokens);
+	}
+}
+
+/// Returns `T` if the given `type` is `Option<T>`.
+fn option_type(r#type: &Type) -> Option<&Type> {
+	type_argument(r#type, "Option")
+}
+
+/// Returns `T` if the given `type` is `Vec<T>`.
+fn vec_type(r#type: &Type) -> Option<&Type> {
+	type_argument(r#type, "Vec")
+}
+
+/// Returns `T` if the given `type` is `Wrapper<T>`, where `Wrapper` is the
+/// given `wrapper`.
+fn type_argument<'a>(r#type: &'a Type, wrapper: &str) -> Option<&'a Type> {
+	let Type::Path(path) = r#type else {
+		return None;
+	};
+	let segment = path.path.segments.last()?;
+
+	if segment.ident != wrapper {
+		return None;
+	}
+
+	let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+		return None;
+	};
+
+	match arguments.args.first()? {
+		GenericArgument::Type(r#type) if arguments.args.len() == 1 => Some(r#type),
+		_ => None,
+	}
+}
 
 impl ToTokens for Definitions {
 	fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -29,6 +102,8 @@ impl ToTokens for Definition {
 				r#struct.to_tokens(tokens);
 
 				let attrs = &r#struct.item_attributes;
+				reject_x11_compat(attrs, tokens);
+				reject_builder(attrs, tokens);
 
 				for path in &attrs.derive_writables {
 					r#struct.impl_writable(tokens, path);
@@ -38,15 +113,34 @@ impl ToTokens for Definition {
 					r#struct.impl_readable(tokens, path);
 				}
 
+				for path in &attrs.derive_readable_refs {
+					r#struct.impl_readable_ref(tokens, path);
+				}
+
 				for path in &attrs.derive_x11_sizes {
 					r#struct.impl_x11_size(tokens, path);
 				}
+
+				for path in &attrs.derive_constant_x11_sizes {
+					r#struct.impl_constant_x11_size(tokens, path, false);
+				}
+
+				// Infer a `ConstantX11Size` implementation if it is not derived
+				// unconditionally.
+				if let Some(path) = attrs.inferred_constant_x11_size() {
+					r#struct.impl_constant_x11_size(tokens, path, true);
+				}
+
+				r#struct.impl_arbitrary(tokens);
 			},
 
 			Self::Enum(r#enum) => {
 				r#enum.to_tokens(tokens);
 
 				let attrs = &r#enum.item_attributes;
+				reject_x11_compat(attrs, tokens);
+				reject_builder(attrs, tokens);
+				reject_readable_ref(attrs, tokens);
 
 				for path in &attrs.derive_writables {
 					r#enum.impl_writable(tokens, path);
@@ -59,6 +153,23 @@ impl ToTokens for Definition {
 				for path in &attrs.derive_x11_sizes {
 					r#enum.impl_x11_size(tokens, path);
 				}
+
+				for path in &attrs.derive_constant_x11_sizes {
+					r#enum.impl_constant_x11_size(tokens, path);
+				}
+
+				// Whether an enum has a constant size can't be expressed in a
+				// `where` clause, so it can't be inferred.
+				for path in &attrs.maybe_derive_constant_x11_sizes {
+					syn::Error::new(
+						path.span(),
+						"?ConstantX11Size is not supported for enums: derive ConstantX11Size instead",
+					)
+					.to_compile_error()
+					.to_tokens(tokens);
+				}
+
+				r#enum.impl_arbitrary(tokens);
 			},
 
 			Self::Request(request) => {
@@ -66,6 +177,15 @@ impl ToTokens for Definition {
 				request.impl_trait(tokens);
 
 				let attrs = &request.item_attributes;
+				reject_readable_ref(attrs, tokens);
+
+				if let Some(path) = &attrs.x11_compat {
+					request.impl_x11_compat(tokens, path);
+				}
+
+				if let Some(path) = &attrs.builder {
+					request.impl_builder(tokens, path);
+				}
 
 				for path in &attrs.derive_writables {
 					request.impl_writable(tokens, path);
@@ -78,13 +198,29 @@ impl ToTokens for Definition {
 				for path in &attrs.derive_x11_sizes {
 					request.impl_x11_size(tokens, path);
 				}
+
+				for path in &attrs.derive_constant_x11_sizes {
+					request.impl_constant_x11_size(tokens, path, false);
+				}
+
+				// Infer a `ConstantX11Size` implementation if it is not derived
+				// unconditionally.
+				if let Some(path) = attrs.inferred_constant_x11_size() {
+					request.impl_constant_x11_size(tokens, path, true);
+				}
+
+				request.impl_arbitrary(tokens);
 			},
 
 			Self::Reply(reply) => {
 				reply.to_tokens(tokens);
 				reply.impl_trait(tokens);
+				reply.impl_eq_including_sequence(tokens);
 
 				let attrs = &reply.item_attributes;
+				reject_x11_compat(attrs, tokens);
+				reject_builder(attrs, tokens);
+				reject_readable_ref(attrs, tokens);
 
 				for path in &attrs.derive_writables {
 					reply.impl_writable(tokens, path);
@@ -97,13 +233,29 @@ impl ToTokens for Definition {
 				for path in &attrs.derive_x11_sizes {
 					reply.impl_x11_size(tokens, path);
 				}
+
+				for path in &attrs.derive_constant_x11_sizes {
+					reply.impl_constant_x11_size(tokens, path, false);
+				}
+
+				// Infer a `ConstantX11Size` implementation if it is not derived
+				// unconditionally.
+				if let Some(path) = attrs.inferred_constant_x11_size() {
+					reply.impl_constant_x11_size(tokens, path, true);
+				}
+
+				reply.impl_arbitrary(tokens);
 			},
 
 			Self::Event(event) => {
 				event.to_tokens(tokens);
 				event.impl_trait(tokens);
+				event.impl_eq_including_sequence(tokens);
 
 				let attrs = &event.item_attributes;
+				reject_x11_compat(attrs, tokens);
+				reject_builder(attrs, tokens);
+				reject_readable_ref(attrs, tokens);
 
 				for path in &attrs.derive_writables {
 					event.impl_writable(tokens, path);
@@ -116,13 +268,29 @@ impl ToTokens for Definition {
 				for path in &attrs.derive_x11_sizes {
 					event.impl_x11_size(tokens, path);
 				}
+
+				for path in &attrs.derive_constant_x11_sizes {
+					event.impl_constant_x11_size(tokens, path, false);
+				}
+
+				// Infer a `ConstantX11Size` implementation if it is not derived
+				// unconditionally.
+				if let Some(path) = attrs.inferred_constant_x11_size() {
+					event.impl_constant_x11_size(tokens, path, true);
+				}
+
+				event.impl_arbitrary(tokens);
 			},
 
 			Self::Error(error) => {
 				error.to_tokens(tokens);
 				error.impl_trait(tokens);
+				error.impl_eq_including_sequence(tokens);
 
 				let attrs = &error.item_attributes;
+				reject_x11_compat(attrs, tokens);
+				reject_builder(attrs, tokens);
+				reject_readable_ref(attrs, tokens);
 
 				for path in &attrs.derive_writables {
 					error.impl_writable(tokens, path);
@@ -135,6 +303,17 @@ impl ToTokens for Definition {
 				for path in &attrs.derive_x11_sizes {
 					error.impl_x11_size(tokens, path);
 				}
+
+				// Errors always have a constant size.
+				for path in attrs
+					.derive_constant_x11_sizes
+					.iter()
+					.chain(&attrs.maybe_derive_constant_x11_sizes)
+				{
+					error.impl_constant_x11_size(tokens, path);
+				}
+
+				error.impl_arbitrary(tokens);
 			},
 
 			Self::Other(item) => item.to_tokens(tokens),
@@ -200,3 +379,233 @@ impl ToTokens for Variant {
 		}
 	}
 }
+
+/// Snapshot tests for the code generated by [`derive_xrb!`].
+///
+/// Each test expands a representative definition and compares the
+/// pretty-printed output to the snapshot stored in `snapshots/`. If the
+/// generated code changes, the test fails until the new snapshot is reviewed
+/// and accepted with `cargo insta review`.
+///
+/// [`derive_xrb!`]: crate::derive_xrb
+#[cfg(test)]
+mod test {
+	use super::*;
+	use quote::quote;
+
+	/// Expands the given `derive_xrb!` input and pretty-prints the result.
+	fn expand(input: TokenStream) -> String {
+		let definitions: Definitions = syn::parse2(input).expect("failed to parse definitions");
+		let file: syn::File =
+			syn::parse2(definitions.into_token_stream()).expect("failed to parse expansion");
+
+		prettyplease::unparse(&file)
+	}
+
+	#[test]
+	fn test_struct_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct Point {
+				pub x: i16,
+				pub y: i16,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_request_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct ChangeName: Request(200) {
+				#[metabyte]
+				pub replace: bool,
+
+				pub window: u32,
+
+				#[allow(clippy::cast_possible_truncation)]
+				let name_len: u16 = name => name.len() as u16,
+				[_; 2],
+
+				#[context(name_len => usize::from(*name_len))]
+				pub name: Vec<u8>,
+				[_; name => pad(name)],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_extension_request_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct GetXidList: ExtensionRequest(2) -> reply::GetXidList {
+				pub count: u32,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_x11_compat_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[x11_compat]
+			pub struct WarpTo: Request(201) {
+				#[doc(alias("dst_window", "destination_window"))]
+				pub destination: Option<Window>,
+				#[doc(alias = "dst_x")]
+				pub x: i16,
+				pub y: i16,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_builder_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			#[builder]
+			pub struct Grab: Request(202) {
+				#[metabyte]
+				#[default]
+				pub owner_events: bool,
+
+				pub window: Window,
+				pub confine_to: Option<Window>,
+				#[default(Time::CurrentTime)]
+				pub time: Time,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_reply_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct ListNames: Reply for request::ListNames {
+				#[sequence]
+				pub sequence: u16,
+
+				#[metabyte]
+				#[allow(clippy::cast_possible_truncation)]
+				let names_len: u8 = names => names.len() as u8,
+				[_; 24],
+
+				#[context(names_len => usize::from(*names_len))]
+				pub names: Vec<u32>,
+				[_; ..],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_reply_with_request_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct GetCounts: Reply for request::GetCounts {
+				#[sequence]
+				pub sequence: u16,
+				[_; 24],
+
+				#[context(self::request => usize::from(request.count))]
+				pub counts: Vec<u32>,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_present_if_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct Lookup: Reply for request::Lookup {
+				#[sequence]
+				pub sequence: u16,
+
+				pub present: bool,
+				#[present_if(present)]
+				pub opcode: Option<u8>,
+				[_; ..],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_discriminated_by_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct Message: Event(33) {
+				#[sequence]
+				pub sequence: u16,
+
+				#[metabyte]
+				pub format: Format,
+
+				#[discriminated_by(format)]
+				pub data: Data,
+			}
+		}));
+	}
+
+	#[test]
+	fn test_readable_ref_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(ReadableRef)]
+			pub struct Name<'a> {
+				let len: u8 = name => name.len() as u8,
+				[_; 3],
+
+				#[context(len => usize::from(*len))]
+				pub name: &'a [u8],
+				[_; name => pad(name)],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_constant_x11_size_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, ConstantX11Size, Readable, Writable)]
+			pub struct Circulate: Request(13) {
+				#[metabyte]
+				pub direction: u8,
+
+				pub window: u32,
+				[_; ..],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_event_with_sequence_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct Moved: Event(100) {
+				#[sequence]
+				pub sequence: u16,
+
+				pub window: u32,
+				[_; ..],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_event_without_sequence_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub struct State: Event(101) {
+				pub keys: [u8; 31],
+			}
+		}));
+	}
+
+	#[test]
+	fn test_enum_expansion() {
+		insta::assert_snapshot!(expand(quote! {
+			#[derive(X11Size, Readable, Writable)]
+			pub enum Destination {
+				Cursor,
+				Window(u32),
+				Area { x: i16, y: i16 },
+			}
+		}));
+	}
+}
@@ -0,0 +1,317 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Generation of `arbitrary::Arbitrary` implementations.
+//!
+//! Implementations are generated behind a `#[cfg(feature = "arbitrary")]`
+//! attribute, so they are only compiled if the `arbitrary` feature is enabled
+//! in the crate in which the definitions are found, as with the `tracing`
+//! feature.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_quote, Generics, Type, WhereClause};
+
+use super::*;
+use crate::{
+	attribute::{ArbitraryAttribute, DiscriminatedByAttribute, PresentIfAttribute},
+	element::{Element, Field, Let},
+	TsExt,
+};
+
+/// The primitive integer types which the length of a list may be written as.
+const LENGTH_TYPES: [&str; 8] = ["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+/// Whether an `Arbitrary` implementation should be generated for a definition
+/// with the given attributes.
+///
+/// Only definitions which can be both written and read are generated
+/// arbitrarily, as they are generated in order to test that they are read
+/// back unchanged after being written.
+fn derives_arbitrary(attrs: &ParsedItemAttributes) -> bool {
+	!attrs.derive_writables.is_empty() && !attrs.derive_readables.is_empty()
+}
+
+/// Adds the `'arbitrary` lifetime to the given `generics`, and bounds each of
+/// their type parameters by `Arbitrary`.
+fn arbitrary_generics(generics: &Generics) -> Generics {
+	let mut generics = generics.clone();
+
+	for param in generics.type_params_mut() {
+		param
+			.bounds
+			.push(parse_quote!(::arbitrary::Arbitrary<'arbitrary>));
+	}
+
+	generics.params.insert(0, parse_quote!('arbitrary));
+
+	generics
+}
+
+/// Returns the type of the `Let` element which gives the length of the given
+/// list `field`, if that type is a primitive integer.
+fn length_type<'a>(field: &Field, lets: &[&'a Let]) -> Option<&'a Type> {
+	let context = field.context_attribute.as_ref()?;
+	let (args, _) = context.context.source().args.as_ref()?;
+
+	args.args.iter().find_map(|arg| {
+		let r#let = lets.iter().find(|r#let| r#let.ident == arg.ident)?;
+
+		match &r#let.r#type {
+			Type::Path(path) if LENGTH_TYPES.iter().any(|r#type| path.path.is_ident(r#type)) => {
+				Some(&r#let.r#type)
+			},
+
+			_ => None,
+		}
+	})
+}
+
+/// Generates a `let` statement for each field in the given `elements`,
+/// binding an arbitrary value to the field's formatted identifier.
+///
+/// Elements which are not fields are not generated, since their values are
+/// derived from the fields when they are written. A field with an
+/// `#[arbitrary(with = ...)]` attribute is generated with the given function.
+/// Other fields are generated so that they can be written:
+/// - a list whose length is written as a `Let` element of a primitive integer
+///   type is no longer than that type's maximum value;
+/// - a field with a `#[present_if(...)]` attribute is present only if its
+///   condition field is `true`;
+/// - the discriminant field of a field with a `#[discriminated_by(...)]`
+///   attribute is the discriminant of its value.
+fn arbitrary_fields<'a>(
+	tokens: &mut TokenStream2, elements: impl IntoIterator<Item = &'a Element>,
+) {
+	let mut fields = Vec::new();
+	let mut lets = Vec::new();
+
+	for element in elements {
+		match element {
+			Element::Field(field) => fields.push(&**field),
+			Element::Let(r#let) => lets.push(&**r#let),
+
+			Element::SingleUnused(_) | Element::ArrayUnused(_) => {},
+		}
+	}
+
+	let is_field = |formatted: &Option<Ident>| {
+		fields
+			.iter()
+			.any(|field| formatted.as_ref() == Some(&field.formatted))
+	};
+
+	for field in &fields {
+		let formatted = &field.formatted;
+		let r#type = &field.r#type;
+
+		let value = if let Some(ArbitraryAttribute { function, .. }) = &field.arbitrary_attribute {
+			quote!((#function)(u)?)
+		} else if let Some(PresentIfAttribute { condition, .. }) = &field.present_if_attribute
+			&& is_field(&condition.formatted)
+		{
+			let condition = &condition.formatted;
+
+			quote!(
+				if #condition {
+					::core::option::Option::Some(::arbitrary::Arbitrary::arbitrary(u)?)
+				} else {
+					::core::option::Option::None
+				}
+			)
+		} else if let Some(length_type) = length_type(field, &lets)
+			&& let Some(element_type) = vec_type(r#type)
+		{
+			quote!({
+				let max_len = usize::try_from(<#length_type>::MAX).unwrap_or(usize::MAX);
+				let len = u.arbitrary_len::<#element_type>()?.min(max_len);
+
+				::core::iter::repeat_with(|| ::arbitrary::Arbitrary::arbitrary(u))
+					.take(len)
+					.collect::<::arbitrary::Result<_>>()?
+			})
+		} else {
+			quote!(::arbitrary::Arbitrary::arbitrary(u)?)
+		};
+
+		tokens.append_tokens(quote!(
+			let #formatted: #r#type = #value;
+		));
+	}
+
+	// The discriminant of a discriminated field is chosen by its value.
+	for field in &fields {
+		if let Some(DiscriminatedByAttribute { discriminant, .. }) =
+			&field.discriminated_by_attribute
+			&& is_field(&discriminant.formatted)
+		{
+			let formatted = &field.formatted;
+			let discriminant = &discriminant.formatted;
+
+			tokens.append_tokens(quote!(
+				let #discriminant = ::xrbk::Discriminated::discriminant(&#formatted);
+			));
+		}
+	}
+}
+
+/// Generates an `Arbitrary` implementation for a structlike definition with
+/// the given `ident`, `generics`, and `content`.
+///
+/// If the definition has an `#[arbitrary(with = ...)]` attribute, it is
+/// generated with the given function instead.
+fn impl_arbitrary_structlike(
+	tokens: &mut TokenStream2, item_attributes: &ParsedItemAttributes, ident: &Ident,
+	generics: &Generics, content: &StructlikeContent,
+) {
+	let arbitrary_generics = arbitrary_generics(generics);
+	let (impl_generics, ..) = arbitrary_generics.split_for_impl();
+	let (_, type_generics, _) = generics.split_for_impl();
+	let where_clause = match content {
+		StructlikeContent::Regular { where_clause, .. } => where_clause,
+		StructlikeContent::Tuple { where_clause, .. } => where_clause,
+		StructlikeContent::Unit { where_clause, .. } => where_clause,
+	};
+
+	let body = if let Some(ArbitraryAttribute { function, .. }) = &item_attributes.arbitrary_attribute {
+		quote!((#function)(u))
+	} else {
+		let fields = TokenStream2::with_tokens(|tokens| {
+			arbitrary_fields(tokens, content);
+		});
+
+		let cons = TokenStream2::with_tokens(|tokens| {
+			content.pat_cons_to_tokens(tokens);
+		});
+
+		quote!(
+			#fields
+
+			Ok(Self #cons)
+		)
+	};
+
+	impl_arbitrary(
+		tokens,
+		&impl_generics.to_token_stream(),
+		&quote!(#ident #type_generics),
+		where_clause,
+		&body,
+	);
+}
+
+/// Generates an `Arbitrary` implementation with the given `body` for its
+/// `arbitrary` function, which is only compiled if the `arbitrary` feature is
+/// enabled.
+fn impl_arbitrary(
+	tokens: &mut TokenStream2, impl_generics: &TokenStream2, self_type: &TokenStream2,
+	where_clause: &Option<WhereClause>, body: &TokenStream2,
+) {
+	tokens.append_tokens(quote!(
+		#[cfg(feature = "arbitrary")]
+		#[automatically_derived]
+		impl #impl_generics ::arbitrary::Arbitrary<'arbitrary> for #self_type #where_clause {
+			#[allow(clippy::items_after_statements, clippy::cast_possible_truncation)]
+			fn arbitrary(
+				u: &mut ::arbitrary::Unstructured<'arbitrary>,
+			) -> ::arbitrary::Result<Self> {
+				#body
+			}
+		}
+	));
+}
+
+macro_rules! structlike_impl_arbitrary {
+	($($Definition:ty),+$(,)?) => {
+		$(
+			impl $Definition {
+				/// Generates an `Arbitrary` implementation if both `Readable`
+				/// and `Writable` are derived.
+				pub fn impl_arbitrary(&self, tokens: &mut TokenStream2) {
+					if derives_arbitrary(&self.item_attributes) {
+						impl_arbitrary_structlike(
+							tokens,
+							&self.item_attributes,
+							&self.ident,
+							&self.generics,
+							&self.content,
+						);
+					}
+				}
+			}
+		)+
+	};
+}
+
+structlike_impl_arbitrary!(Struct, Request, Reply, Event, Error);
+
+impl Enum {
+	/// Generates an `Arbitrary` implementation if both `Readable` and
+	/// `Writable` are derived.
+	///
+	/// Each variant is equally likely to be chosen, and only the variants'
+	/// fields are generated, so every value has a valid discriminant. If the
+	/// enum has an `#[arbitrary(with = ...)]` attribute, it is generated with
+	/// the given function instead.
+	pub fn impl_arbitrary(&self, tokens: &mut TokenStream2) {
+		if !derives_arbitrary(&self.item_attributes) {
+			return;
+		}
+
+		let arbitrary_generics = arbitrary_generics(&self.generics);
+		let (impl_generics, ..) = arbitrary_generics.split_for_impl();
+		let (_, type_generics, _) = self.generics.split_for_impl();
+		let ident = &self.ident;
+
+		if let Some(ArbitraryAttribute { function, .. }) = &self.item_attributes.arbitrary_attribute {
+			impl_arbitrary(
+				tokens,
+				&impl_generics.to_token_stream(),
+				&quote!(#ident #type_generics),
+				&self.where_clause,
+				&quote!((#function)(u)),
+			);
+
+			return;
+		}
+
+		let variant_count = self.variants.len();
+
+		let arms = TokenStream2::with_tokens(|tokens| {
+			for (index, variant) in self.variants.iter().enumerate() {
+				let variant_ident = &variant.ident;
+
+				let fields = TokenStream2::with_tokens(|tokens| {
+					arbitrary_fields(tokens, &variant.content);
+				});
+
+				let cons = TokenStream2::with_tokens(|tokens| {
+					variant.content.pat_cons_to_tokens(tokens);
+				});
+
+				tokens.append_tokens(quote!(
+					#index => {
+						#fields
+
+						Self::#variant_ident #cons
+					},
+				));
+			}
+		});
+
+		impl_arbitrary(
+			tokens,
+			&impl_generics.to_token_stream(),
+			&quote!(#ident #type_generics),
+			&self.where_clause,
+			&quote!(
+				Ok(match u.choose_index(#variant_count)? {
+					#arms
+
+					_ => ::core::unreachable!(),
+				})
+			),
+		);
+	}
+}
@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::Path;
+
+use super::*;
+use crate::{
+	attribute::DefaultAttribute,
+	element::{Element, FieldId},
+	TsExt,
+};
+
+impl Request {
+	/// Generates a `new` constructor taking the fields without a default value,
+	/// and a builder with which the other fields can be set.
+	///
+	/// Fields with a `#[default]` attribute and [`Option`] fields have default
+	/// values.
+	pub fn impl_builder(&self, tokens: &mut TokenStream2, attribute_path: &Path) {
+		let StructlikeContent::Regular { where_clause, .. } = &self.content else {
+			tokens.append_tokens(
+				syn::Error::new(
+					attribute_path.span(),
+					"builder is only supported for requests with named fields",
+				)
+				.to_compile_error(),
+			);
+
+			return;
+		};
+
+		let ident = &self.ident;
+		let vis = &self.visibility;
+		let builder = format_ident!("{}Builder", ident);
+
+		let generics = &self.generics;
+		let (impl_generics, type_generics, _) = generics.split_for_impl();
+
+		let mut params = TokenStream2::new();
+		let mut args = TokenStream2::new();
+
+		let mut builder_fields = TokenStream2::new();
+		let mut builder_inits = TokenStream2::new();
+		let mut setters = TokenStream2::new();
+		let mut build_inits = TokenStream2::new();
+
+		for element in &self.content {
+			let Element::Field(field) = element else {
+				continue;
+			};
+			let FieldId::Ident(name) = &field.id else {
+				continue;
+			};
+			let r#type = &field.r#type;
+
+			builder_fields.append_tokens(quote!(#name: #r#type,));
+			build_inits.append_tokens(quote!(#name: self.#name,));
+
+			let default = match &field.default_attribute {
+				Some(DefaultAttribute {
+					default: Some((_, default)),
+					..
+				}) => Some(quote!(#default)),
+				Some(DefaultAttribute { path, .. }) => {
+					Some(quote_spanned!(path.span()=> ::core::default::Default::default()))
+				},
+
+				None if option_type(r#type).is_some() => Some(quote!(::core::option::Option::None)),
+				None => None,
+			};
+
+			let Some(default) = default else {
+				// Fields without a default value are required.
+				params.append_tokens(quote!(#name: #r#type,));
+				args.append_tokens(quote!(#name,));
+				builder_inits.append_tokens(quote!(#name,));
+
+				continue;
+			};
+
+			builder_inits.append_tokens(quote!(#name: #default,));
+
+			let field_vis = &field.visibility;
+			let doc = format!("Sets the [`{name}`]({ident}::{name}) of the `{ident}`.");
+
+			// Optional fields are set to the given value, so that `Some` doesn't
+			// have to be written out.
+			if let Some(inner) = option_type(r#type) {
+				setters.append_tokens(quote!(
+					#[doc = #doc]
+					#[must_use]
+					#field_vis fn #name(mut self, #name: #inner) -> Self {
+						self.#name = ::core::option::Option::Some(#name);
+						self
+					}
+				));
+			} else {
+				setters.append_tokens(quote!(
+					#[doc = #doc]
+					#[must_use]
+					#field_vis fn #name(mut self, #name: #r#type) -> Self {
+						self.#name = #name;
+						self
+					}
+				));
+			}
+		}
+
+		let new_doc = format!(
+			"Creates a new `{ident}` from the fields which have no default value.\n\nThe other \
+			 fields are given their default values. Use [`builder`](Self::builder) to set them."
+		);
+		let builder_doc = format!(
+			"Returns a [`{builder}`] with the fields which have no default value, with which the \
+			 other fields of a `{ident}` can be set."
+		);
+		let struct_doc = format!(
+			"A builder used to construct a new [`{ident}` request].\n\nFields which are not set \
+			 are given their default values. When the builder is configured, \
+			 [`build()`]({builder}::build) can be used to construct the resulting \
+			 [`{ident}`].\n\n[`{ident}` request]: {ident}"
+		);
+		let build_doc = format!("Constructs the resulting [`{ident}`].");
+
+		tokens.append_tokens(quote_spanned!(attribute_path.span()=>
+			impl #impl_generics #ident #type_generics #where_clause {
+				#[doc = #new_doc]
+				#[allow(clippy::too_many_arguments)]
+				#[must_use]
+				pub fn new(#params) -> Self {
+					Self::builder(#args).build()
+				}
+
+				#[doc = #builder_doc]
+				#[allow(clippy::too_many_arguments)]
+				#[must_use]
+				pub fn builder(#params) -> #builder #type_generics {
+					#builder {
+						#builder_inits
+					}
+				}
+			}
+
+			#[doc = #struct_doc]
+			#vis struct #builder #generics #where_clause {
+				#builder_fields
+			}
+
+			impl #impl_generics #builder #type_generics #where_clause {
+				#setters
+
+				#[doc = #build_doc]
+				#[must_use]
+				pub fn build(self) -> #ident #type_generics {
+					#ident {
+						#build_inits
+					}
+				}
+			}
+		));
+	}
+}
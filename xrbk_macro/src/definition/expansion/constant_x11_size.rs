@@ -0,0 +1,478 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{ext::IdentExt, spanned::Spanned, Path, Type, WhereClause};
+
+use crate::{
+	element::{Element, UnusedContent},
+	TsExt,
+};
+
+use super::*;
+
+/// Generates the `where` clause of a `ConstantX11Size` implementation which is
+/// inferred, rather than explicitly derived.
+///
+/// The implementation only applies if every one of the `elements` implements
+/// `ConstantX11Size`. Each of those bounds is made higher-ranked so that it is
+/// not rejected when it does not hold for a concrete type.
+///
+/// Returns [`None`] if the number of unused bytes of an array-type unused bytes
+/// element depends on other elements, meaning that `ConstantX11Size` cannot be
+/// implemented.
+fn inferred_where_clause<'a>(
+	where_clause: &Option<WhereClause>, elements: impl Iterator<Item = &'a Element>,
+) -> Option<TokenStream2> {
+	let mut types = Vec::new();
+
+	for element in elements {
+		match element {
+			Element::Field(field) => {
+				if !field.is_ignoring_trait("X11Size") {
+					types.push(&field.r#type);
+				}
+			},
+			Element::Let(r#let) => types.push(&r#let.r#type),
+
+			Element::SingleUnused(_) => {},
+			Element::ArrayUnused(unused) => {
+				if let UnusedContent::Source(source) = &unused.content
+					&& source.args.is_some()
+				{
+					return None;
+				}
+			},
+		}
+	}
+
+	let predicates = where_clause
+		.iter()
+		.flat_map(|where_clause| where_clause.predicates.iter());
+
+	Some(quote!(
+		where
+			#(#predicates,)*
+			#(for<'__xrbk> #types: ::xrbk::ConstantX11Size,)*
+	))
+}
+
+/// Formats the given `type` for use in an error message.
+fn type_name(r#type: &Type) -> String {
+	r#type
+		.to_token_stream()
+		.to_string()
+		.replace(" <", "<")
+		.replace("< ", "<")
+		.replace(" >", ">")
+		.replace(" ::", "::")
+		.replace(":: ", "::")
+		.replace(" ,", ",")
+		.replace("& ", "&")
+		.replace("[ ", "[")
+		.replace(" ]", "]")
+		.replace(" ;", ";")
+}
+
+/// Generates a compile error for each of the `elements` which does not have a
+/// constant size.
+///
+/// A field read with a `#[context]` attribute cannot have a constant size, nor
+/// can unused bytes whose number depends on other elements.
+///
+/// Returns whether any errors were generated.
+fn reject_variable_elements<'a>(
+	tokens: &mut TokenStream2, elements: impl Iterator<Item = &'a Element>,
+) -> bool {
+	let mut rejected = false;
+
+	for element in elements {
+		let (span, message) = match element {
+			Element::Field(field)
+				if field.context_attribute.is_some() && !field.is_ignoring_trait("X11Size") =>
+			{
+				let message = format!(
+					"ConstantX11Size cannot be derived because `{}` (of type `{}`) is read with \
+					 context, so it does not have a constant size",
+					field.id.to_string(),
+					type_name(&field.r#type),
+				);
+
+				(field.span(), message)
+			},
+
+			Element::ArrayUnused(unused)
+				if let UnusedContent::Source(source) = &unused.content
+					&& let Some((args, _)) = &source.args =>
+			{
+				let mut names: Vec<_> = args
+					.args
+					.iter()
+					.map(|arg| format!("`{}`", arg.ident.unraw()))
+					.collect();
+
+				if args.remaining_arg.is_some() {
+					names.push("`self::remaining`".to_owned());
+				}
+
+				if args.length_arg.is_some() {
+					names.push("`self::length`".to_owned());
+				}
+
+				let message = format!(
+					"ConstantX11Size cannot be derived because the number of these unused bytes \
+					 depends on {}, so it is not constant",
+					names.join(", "),
+				);
+
+				(unused.span(), message)
+			},
+
+			_ => continue,
+		};
+
+		tokens.append_tokens(syn::Error::new(span, message).to_compile_error());
+		rejected = true;
+	}
+
+	rejected
+}
+
+impl Struct {
+	pub fn impl_constant_x11_size(
+		&self, tokens: &mut TokenStream2, trait_path: &Path, inferred: bool,
+	) {
+		let ident = &self.ident;
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+		let where_clause = if inferred {
+			match inferred_where_clause(where_clause, self.content.iter()) {
+				Some(where_clause) => where_clause,
+				None => return,
+			}
+		} else {
+			if reject_variable_elements(tokens, self.content.iter()) {
+				return;
+			}
+
+			where_clause.to_token_stream()
+		};
+
+		let sizes = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				element.constant_x11_size_tokens(tokens, DefinitionType::Basic);
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					let mut size: usize = 0;
+
+					// Add the size of each element.
+					#sizes
+
+					// Return the cumulative size.
+					size
+				};
+			}
+		));
+	}
+}
+
+impl Request {
+	pub fn impl_constant_x11_size(
+		&self, tokens: &mut TokenStream2, trait_path: &Path, inferred: bool,
+	) {
+		let ident = &self.ident;
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+		let where_clause = if inferred {
+			match inferred_where_clause(
+				where_clause,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				Some(where_clause) => where_clause,
+				None => return,
+			}
+		} else {
+			if reject_variable_elements(
+				tokens,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				return;
+			}
+
+			where_clause.to_token_stream()
+		};
+
+		let sizes = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				if element.is_normal() {
+					element.constant_x11_size_tokens(tokens, DefinitionType::Request);
+				}
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					// The size starts at `4` to account for the size of a
+					// request's header being 4 bytes.
+					let mut size: usize = 4;
+
+					// Add the size of each element.
+					#sizes
+
+					// Return the cumulative size.
+					size
+				};
+			}
+		));
+	}
+}
+
+impl Reply {
+	pub fn impl_constant_x11_size(
+		&self, tokens: &mut TokenStream2, trait_path: &Path, inferred: bool,
+	) {
+		let ident = &self.ident;
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+		let where_clause = if inferred {
+			match inferred_where_clause(
+				where_clause,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				Some(where_clause) => where_clause,
+				None => return,
+			}
+		} else {
+			if reject_variable_elements(
+				tokens,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				return;
+			}
+
+			where_clause.to_token_stream()
+		};
+
+		let sizes = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				if element.is_normal() {
+					element.constant_x11_size_tokens(tokens, DefinitionType::Reply);
+				}
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					// The size starts at `8` to account for the size of a
+					// reply's header being 8 bytes.
+					let mut size: usize = 8;
+
+					// Add the size of each element.
+					#sizes
+
+					// Return the cumulative size.
+					size
+				};
+			}
+		));
+	}
+}
+
+impl Event {
+	pub fn impl_constant_x11_size(
+		&self, tokens: &mut TokenStream2, trait_path: &Path, inferred: bool,
+	) {
+		let ident = &self.ident;
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+		let where_clause = if inferred {
+			match inferred_where_clause(
+				where_clause,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				Some(where_clause) => where_clause,
+				None => return,
+			}
+		} else {
+			if reject_variable_elements(
+				tokens,
+				self.content.iter().filter(|element| element.is_normal()),
+			) {
+				return;
+			}
+
+			where_clause.to_token_stream()
+		};
+
+		let size: usize = if self.content.sequence_element().is_some() {
+			4
+		} else {
+			1
+		};
+
+		let sizes = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				if element.is_normal() {
+					element.constant_x11_size_tokens(tokens, DefinitionType::Event);
+				}
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					// The size starts at either `4` or `1`, depending on
+					// whether there is a sequence field and metabyte position,
+					// to account for the size of the event's header.
+					let mut size: usize = #size;
+
+					// Add the size of each element.
+					#sizes
+
+					// Return the cumulative size.
+					size
+				};
+			}
+		));
+	}
+}
+
+impl Error {
+	pub fn impl_constant_x11_size(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let ident = &self.ident;
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+
+		let sizes = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				if element.is_normal() {
+					element.constant_x11_size_tokens(tokens, DefinitionType::Error);
+				}
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					// At least 11 bytes including all the required fields.
+					let mut size: usize = 11;
+
+					#sizes
+
+					size
+				};
+			}
+		));
+	}
+}
+
+impl Enum {
+	pub fn impl_constant_x11_size(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let ident = &self.ident;
+		let discrim_type = self.discriminant_type.as_ref().map_or_else(
+			|| quote_spanned!(trait_path.span()=> u8),
+			|(_, r#type)| r#type.to_token_stream(),
+		);
+
+		// TODO: add generic bounds
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = &self.where_clause;
+
+		let variant_sizes = TokenStream2::with_tokens(|tokens| {
+			for variant in &self.variants {
+				let sizes = TokenStream2::with_tokens(|tokens| {
+					for element in &variant.content {
+						element.constant_x11_size_tokens(tokens, DefinitionType::Basic);
+					}
+				});
+
+				tokens.append_tokens(quote_spanned!(trait_path.span()=>
+					{
+						let mut size: usize = 0;
+
+						// Add the size of each element.
+						#sizes
+
+						size
+					},
+				));
+			}
+		});
+
+		let discrim_type = quote_spanned!(discrim_type.span() =>
+			<#discrim_type as ::xrbk::ConstantX11Size>
+		);
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			#[allow(clippy::identity_op, unused_mut)]
+			impl #impl_generics ::xrbk::ConstantX11Size for #ident #type_generics #where_clause {
+				const X11_SIZE: usize = {
+					let sizes: &[usize] = &[#variant_sizes];
+
+					// Every variant must be the same size for the enum to have
+					// a constant size.
+					let mut i = 0;
+					while i < sizes.len() {
+						assert!(
+							sizes[i] == sizes[0],
+							"every variant must have the same size to implement ConstantX11Size",
+						);
+
+						i += 1;
+					}
+
+					#discrim_type::X11_SIZE + if sizes.is_empty() { 0 } else { sizes[0] }
+				};
+			}
+		));
+	}
+}
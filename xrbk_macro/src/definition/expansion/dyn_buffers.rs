@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Generation of the functions which read and write a type.
+//!
+//! `write_to` and `read_from` are generic over the type of buffer, so the code
+//! which reads or writes a type is compiled again for every type of buffer it
+//! is used with. If the `dyn-buffers` feature is enabled in the crate in which
+//! the type is defined, that code is instead compiled in `write_to_dyn` and
+//! `read_from_dyn`, which take a trait object and so are only compiled once,
+//! and `write_to` and `read_from` only forward to them.
+//!
+//! That makes for much less code to compile, but reading and writing through a
+//! trait object is slower, which is why it is not always done.
+//!
+//! Both versions are generated, each behind a `#[cfg]` attribute for the
+//! feature, as with the `tracing` feature.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+
+/// Generates the `write_to` function of a `Writable` implementation, which
+/// writes the given `body`, and its `write_to_dyn` function for when the
+/// `dyn-buffers` feature is enabled.
+///
+/// The `attributes` are applied to whichever function the `body` is compiled
+/// in.
+pub fn write_functions(span: Span, attributes: &TokenStream2, body: &TokenStream2) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg(not(feature = "dyn-buffers"))]
+		#attributes
+		fn write_to(
+			&self,
+			buf: &mut impl ::xrbk::BufMut,
+		) -> Result<(), ::xrbk::WriteError> {
+			#body
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		fn write_to(
+			&self,
+			buf: &mut impl ::xrbk::BufMut,
+		) -> Result<(), ::xrbk::WriteError> {
+			<Self as ::xrbk::Writable>::write_to_dyn(self, buf)
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		#attributes
+		fn write_to_dyn(
+			&self,
+			mut buf: &mut dyn ::xrbk::DynBufMut,
+		) -> Result<(), ::xrbk::WriteError> {
+			// `&mut dyn DynBufMut` implements `BufMut` itself.
+			let buf = &mut buf;
+
+			#body
+		}
+	)
+}
+
+/// Generates the `write_to_with` function of a `WritableRequest`
+/// implementation, which writes the given `body`, and its `write_to_with_dyn`
+/// function for when the `dyn-buffers` feature is enabled.
+///
+/// The `attributes` are applied to whichever function the `body` is compiled
+/// in.
+pub fn write_with_functions(
+	span: Span, attributes: &TokenStream2, body: &TokenStream2,
+) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg(not(feature = "dyn-buffers"))]
+		#attributes
+		fn write_to_with(
+			&self,
+			buf: &mut impl ::xrbk::BufMut,
+			mode: xrb::message::LengthMode,
+		) -> Result<(), ::xrbk::WriteError> {
+			#body
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		fn write_to_with(
+			&self,
+			buf: &mut impl ::xrbk::BufMut,
+			mode: xrb::message::LengthMode,
+		) -> Result<(), ::xrbk::WriteError> {
+			<Self as xrb::message::WritableRequest>::write_to_with_dyn(self, buf, mode)
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		#attributes
+		fn write_to_with_dyn(
+			&self,
+			mut buf: &mut dyn ::xrbk::DynBufMut,
+			mode: xrb::message::LengthMode,
+		) -> Result<(), ::xrbk::WriteError> {
+			// `&mut dyn DynBufMut` implements `BufMut` itself.
+			let buf = &mut buf;
+
+			#body
+		}
+	)
+}
+
+/// Generates the `read_from` function of a `Readable` implementation, which
+/// reads the given `body`, and its `read_from_dyn` function for when the
+/// `dyn-buffers` feature is enabled.
+///
+/// The `attributes` are applied to whichever function the `body` is compiled
+/// in.
+pub fn read_functions(span: Span, attributes: &TokenStream2, body: &TokenStream2) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg(not(feature = "dyn-buffers"))]
+		#attributes
+		fn read_from(
+			buf: &mut impl ::xrbk::Buf,
+		) -> Result<Self, ::xrbk::ReadError> {
+			#body
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		fn read_from(
+			buf: &mut impl ::xrbk::Buf,
+		) -> Result<Self, ::xrbk::ReadError> {
+			<Self as ::xrbk::Readable>::read_from_dyn(buf)
+		}
+
+		#[cfg(feature = "dyn-buffers")]
+		#attributes
+		fn read_from_dyn(
+			mut buf: &mut dyn ::xrbk::DynBuf,
+		) -> Result<Self, ::xrbk::ReadError> {
+			// `&mut dyn DynBuf` implements `Buf` itself.
+			let buf = &mut buf;
+
+			#body
+		}
+	)
+}
@@ -0,0 +1,101 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+use super::*;
+use crate::{
+	element::{Element, FieldId},
+	TsExt,
+};
+
+/// Generates an `eq_including_sequence` method for a message with the given
+/// `ident`, `generics`, and `content`, if it has a sequence field and derives
+/// `PartialEq`.
+///
+/// The derived `PartialEq` implementation ignores the sequence field, so this
+/// method is provided for when the sequence numbers should be compared too.
+fn impl_eq_including_sequence(
+	tokens: &mut TokenStream2, item_attributes: &ParsedItemAttributes, ident: &Ident,
+	generics: &Generics, content: &StructlikeContent,
+) {
+	if !item_attributes.derives_with_derivative("PartialEq") {
+		return;
+	}
+
+	let Some(Element::Field(field)) = content.sequence_element() else {
+		return;
+	};
+
+	let member = match &field.id {
+		FieldId::Ident(ident) => quote!(#ident),
+		FieldId::Index(index) => quote!(#index),
+	};
+
+	let (impl_generics, type_generics, _) = generics.split_for_impl();
+	let where_clause = match content {
+		StructlikeContent::Regular { where_clause, .. } => where_clause,
+		StructlikeContent::Tuple { where_clause, .. } => where_clause,
+		StructlikeContent::Unit { where_clause, .. } => where_clause,
+	};
+
+	let doc = format!(
+		"Whether this `{ident}` is equal to `other`, including its sequence number.\n\nThe \
+		 `PartialEq` implementation for `{ident}` ignores the sequence number, because the same \
+		 message may be sent with different sequence numbers."
+	);
+
+	tokens.append_tokens(quote!(
+		impl #impl_generics #ident #type_generics #where_clause {
+			#[doc = #doc]
+			#[must_use]
+			pub fn eq_including_sequence(&self, other: &Self) -> bool {
+				self == other && self.#member == other.#member
+			}
+		}
+	));
+}
+
+impl Reply {
+	/// Generates an `eq_including_sequence` method if this reply derives
+	/// `PartialEq`.
+	pub fn impl_eq_including_sequence(&self, tokens: &mut TokenStream2) {
+		impl_eq_including_sequence(
+			tokens,
+			&self.item_attributes,
+			&self.ident,
+			&self.generics,
+			&self.content,
+		);
+	}
+}
+
+impl Event {
+	/// Generates an `eq_including_sequence` method if this event has a
+	/// sequence field and derives `PartialEq`.
+	pub fn impl_eq_including_sequence(&self, tokens: &mut TokenStream2) {
+		impl_eq_including_sequence(
+			tokens,
+			&self.item_attributes,
+			&self.ident,
+			&self.generics,
+			&self.content,
+		);
+	}
+}
+
+impl Error {
+	/// Generates an `eq_including_sequence` method if this error derives
+	/// `PartialEq`.
+	pub fn impl_eq_including_sequence(&self, tokens: &mut TokenStream2) {
+		impl_eq_including_sequence(
+			tokens,
+			&self.item_attributes,
+			&self.ident,
+			&self.generics,
+			&self.content,
+		);
+	}
+}
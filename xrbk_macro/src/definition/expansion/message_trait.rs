@@ -4,11 +4,43 @@
 
 use crate::element::Element;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{Generics, WhereClause};
 
 use super::*;
 use crate::TsExt;
 
+/// Implements the supertrait which seals the message traits, so that messages
+/// can only implement them by being defined with `derive_xrb!`.
+fn impl_sealed(
+	tokens: &mut TokenStream2, name: &Ident, generics: &Generics,
+	where_clause: &Option<WhereClause>,
+) {
+	let (impl_generics, type_generics, _) = generics.split_for_impl();
+
+	tokens.append_tokens(quote!(
+		#[automatically_derived]
+		impl #impl_generics xrb::message::__private::Sealed for #name #type_generics #where_clause {}
+	));
+}
+
+/// Registers the message when testing, so that XRB can check that every
+/// message it defines is listed by `all_message_types!`.
+fn register(tokens: &mut TokenStream2, name: &Ident, kind: &str) {
+	let kind = format_ident!("{kind}");
+
+	tokens.append_tokens(quote!(
+		#[cfg(test)]
+		xrb::message::__private::inventory::submit! {
+			xrb::message::__private::RegisteredMessage {
+				kind: xrb::message::__private::MessageKind::#kind,
+				name: ::core::stringify!(#name),
+				module_path: ::core::module_path!(),
+			}
+		}
+	));
+}
+
 impl Request {
 	pub fn impl_trait(&self, tokens: &mut TokenStream2) {
 		let name = &self.ident;
@@ -20,26 +52,54 @@ impl Request {
 			StructlikeContent::Unit { where_clause, .. } => where_clause,
 		};
 
+		impl_sealed(tokens, name, &self.generics, where_clause);
+		register(tokens, name, "Request");
+
 		let reply = if let Some((_, r#type)) = &self.reply {
 			quote!(#r#type)
 		} else {
 			quote!(())
 		};
+		let has_reply = self.reply.is_some();
 
-		let major_opcode = &self.major_opcode;
-		let minor_opcode = if let Some(minor_opcode) = &self.minor_opcode {
-			quote!(Some(#minor_opcode))
-		} else {
-			quote!(None)
-		};
 		let other_errors = if let Some(other_errors) = &self.other_errors {
 			other_errors.to_token_stream()
 		} else {
-			quote!(::std::convert::Infallible)
+			quote!(::core::convert::Infallible)
 		};
 
 		let request_token = &self.request_token;
 
+		let Some(major_opcode) = &self.major_opcode else {
+			// Extension requests always have a minor opcode.
+			let minor_opcode = &self.minor_opcode;
+
+			tokens.append_tokens(quote_spanned!(self.request_token.span()=>
+				#[automatically_derived]
+				impl #impl_generics #request_token for #name #type_generics #where_clause {
+					type Reply = #reply;
+					type OtherErrors = #other_errors;
+
+					const MINOR_OPCODE: u8 = {
+						#minor_opcode
+					};
+
+					const HAS_REPLY: bool = #has_reply;
+
+					fn length(&self) -> Result<u16, xrb::message::RequestTooLong> {
+						xrb::message::request_length(<Self as ::xrbk::X11Size>::x11_size(self))
+					}
+				}
+			));
+
+			return;
+		};
+		let minor_opcode = if let Some(minor_opcode) = &self.minor_opcode {
+			quote!(Some(#minor_opcode))
+		} else {
+			quote!(None)
+		};
+
 		tokens.append_tokens({
 			quote_spanned!(self.request_token.span()=>
 				#[automatically_derived]
@@ -55,9 +115,10 @@ impl Request {
 						#minor_opcode
 					};
 
-					#[allow(clippy::cast_possible_truncation)]
-					fn length(&self) -> u16 {
-						(<Self as ::xrbk::X11Size>::x11_size(self) / 4) as u16
+					const HAS_REPLY: bool = #has_reply;
+
+					fn length(&self) -> Result<u16, xrb::message::RequestTooLong> {
+						xrb::message::request_length(<Self as ::xrbk::X11Size>::x11_size(self))
 					}
 				}
 			)
@@ -76,6 +137,9 @@ impl Reply {
 			StructlikeContent::Unit { where_clause, .. } => where_clause,
 		};
 
+		impl_sealed(tokens, name, &self.generics, where_clause);
+		register(tokens, name, "Reply");
+
 		let request = &self.request;
 		let sequence = match &self.content {
 			StructlikeContent::Regular {
@@ -93,6 +157,30 @@ impl Reply {
 
 		let reply_token = &self.reply_token;
 
+		// A reply which uses its request in a context attribute is read here,
+		// otherwise it is read with its `Readable` implementation.
+		let read_with_request = match (
+			self.content.request_arg(),
+			self.item_attributes.derive_readables.first(),
+		) {
+			(Some(_), Some(path)) => self.read_function_tokens(path.span()),
+
+			(Some(request_arg), None) => syn::Error::new(
+				request_arg.span(),
+				"`self::request` may only be used if `Readable` is derived",
+			)
+			.to_compile_error(),
+
+			(None, _) => quote_spanned!(self.reply_token.span()=>
+				fn read_with_request(
+					buf: &mut impl ::xrbk::Buf,
+					_request: &Self::Request,
+				) -> Result<Self, ::xrbk::ReadError> {
+					<Self as ::xrbk::Readable>::read_from(buf)
+				}
+			),
+		};
+
 		tokens.append_tokens({
 			quote_spanned!(self.reply_token.span()=>
 				#[automatically_derived]
@@ -107,6 +195,8 @@ impl Reply {
 					fn sequence(&self) -> u16 {
 						self.#sequence
 					}
+
+					#read_with_request
 				}
 			)
 		});
@@ -124,6 +214,9 @@ impl Event {
 			StructlikeContent::Unit { where_clause, .. } => where_clause,
 		};
 
+		impl_sealed(tokens, name, &self.generics, where_clause);
+		register(tokens, name, "Event");
+
 		let code = &self.event_code;
 		let sequence = match &self.content {
 			StructlikeContent::Regular {
@@ -175,6 +268,9 @@ impl Error {
 			StructlikeContent::Unit { where_clause, .. } => where_clause,
 		};
 
+		impl_sealed(tokens, name, &self.generics, where_clause);
+		register(tokens, name, "Error");
+
 		let error_path = &self.error_token;
 		let error_code = &self.error_code;
 
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::{format_ident, quote_spanned};
 use syn::Path;
 
@@ -13,6 +13,7 @@ use super::*;
 impl Struct {
 	pub fn impl_readable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -30,7 +31,7 @@ impl Struct {
 		// Expand the tokens to read each element.
 		let reads = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
-				element.read_tokens(tokens, DefinitionType::Basic);
+				element.read_tokens(tokens, DefinitionType::Basic, &parent);
 
 				// if self.content.contains_infer() {
 				element.add_x11_size_tokens(tokens);
@@ -38,29 +39,32 @@ impl Struct {
 			}
 		});
 
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+		);
+		let read_body = quote_spanned!(trait_path.span()=>
+			// Declare a x11_size variable if it is going to be
+			// used in an infer unused bytes element.
+			let mut size: usize = 0;
+
+			// Read each element.
+			#reads
+
+			// Construct and return `Self`.
+			Ok(Self #cons)
+		);
+		let functions = dyn_buffers::read_functions(trait_path.span(), &attributes, &read_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					// Declare a x11_size variable if it is going to be
-					// used in an infer unused bytes element.
-					let mut size: usize = 0;
-
-					// Read each element.
-					#reads
-
-					// Construct and return `Self`.
-					Ok(Self #cons)
-				}
+				#functions
 			}
 		));
 	}
@@ -69,6 +73,7 @@ impl Struct {
 impl Request {
 	pub fn impl_readable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -85,7 +90,7 @@ impl Request {
 		let reads = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
 				if !element.is_metabyte() && !element.is_sequence() {
-					element.read_tokens(tokens, DefinitionType::Request);
+					element.read_tokens(tokens, DefinitionType::Request, &parent);
 
 					// if self.content.contains_infer() {
 					element.add_x11_size_tokens(tokens);
@@ -101,45 +106,88 @@ impl Request {
 			None
 		} else if let Some(element) = self.content.metabyte_element() {
 			Some(TokenStream2::with_tokens(|tokens| {
-				element.read_tokens(tokens, DefinitionType::Request);
+				element.read_tokens(tokens, DefinitionType::Request, &parent);
 			}))
 		} else {
-			Some(quote_spanned!(trait_path.span()=> <_ as ::xrbk::Buf>::advance(buf, 1);))
+			Some(quote_spanned!(trait_path.span()=> ::xrbk::skip(buf, 1)?;))
 		};
 
+		let instrument = trace::instrument_read(
+			trait_path.span(),
+			ident,
+			if self.is_extension() {
+				quote_spanned!(trait_path.span()=>
+					minor_opcode = <Self as xrb::message::ExtensionRequest>::MINOR_OPCODE,
+					length = ::tracing::field::Empty,
+				)
+			} else {
+				quote_spanned!(trait_path.span()=>
+					major_opcode = <Self as xrb::message::Request>::MAJOR_OPCODE,
+					minor_opcode = ?<Self as xrb::message::Request>::MINOR_OPCODE,
+					length = ::tracing::field::Empty,
+				)
+			},
+		);
+		let record = trace::record_read(
+			trait_path.span(),
+			&[("length", quote_spanned!(trait_path.span()=> length * 4))],
+		);
+
+		let event = trace::event(
+			trait_path.span(),
+			ident,
+			"read",
+			quote_spanned!(trait_path.span()=> &message),
+		);
+
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let read_body = quote_spanned!(trait_path.span()=>
+			let mut size: usize = 4;
+
+			// If there is a metabyte element, read it, if not and
+			// there is no minor opcode, skip one byte. If there
+			// is a minor opcode, do nothing - it has already been
+			// read.
+			#metabyte
+			// Read the request's length. A length of `0` means that
+			// the length follows as a `u32`, as defined by the
+			// BIG-REQUESTS extension. That `u32` length includes its
+			// own 4 bytes, which are not part of the request's size.
+			let length: usize = match <u16 as ::xrbk::Readable>::read_from(buf)? {
+				0 => (<u32 as ::xrbk::Readable>::read_from(buf)? as usize).saturating_sub(1),
+				length => length as usize,
+			};
+			#record
+			::xrbk::ensure_remaining(buf, length.saturating_sub(1) * 4)?;
+			let buf = &mut ::xrbk::take(
+				buf,
+				length.saturating_sub(1) * 4,
+			);
+
+			// Read other elements.
+			#reads
+
+			// Construct and return Self.
+			let message = Self #cons;
+			#event
+
+			Ok(message)
+		);
+		let functions = dyn_buffers::read_functions(trait_path.span(), &attributes, &read_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					let mut size: usize = 4;
-
-					// If there is a metabyte element, read it, if not and
-					// there is no minor opcode, skip one byte. If there
-					// is a minor opcode, do nothing - it has already been
-					// read.
-					#metabyte
-					// Read the request's length.
-					let length = <_ as ::xrbk::Buf>::get_u16(buf);
-					let buf = &mut <_ as ::xrbk::Buf>::take(
-						buf,
-						((length - 1) as usize) * 4,
-					);
-
-					// Read other elements.
-					#reads
-
-					// Construct and return Self.
-					Ok(Self #cons)
-				}
+				#functions
 			}
 		));
 	}
@@ -147,6 +195,12 @@ impl Request {
 
 impl Reply {
 	pub fn impl_readable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		// A reply which uses its request is read by `Reply::read_with_request`
+		// instead.
+		if self.content.request_arg().is_some() {
+			return;
+		}
+
 		let ident = &self.ident;
 
 		// TODO: add generic bounds
@@ -157,6 +211,25 @@ impl Reply {
 			StructlikeContent::Unit { where_clause, .. } => where_clause,
 		};
 
+		let read_from = self.read_function_tokens(trait_path.span());
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
+				#read_from
+			}
+		));
+	}
+
+	/// Expands the function which reads this reply.
+	///
+	/// If the reply uses its request in a context attribute, this is
+	/// `Reply::read_with_request`, otherwise it is `Readable::read_from` (and
+	/// `Readable::read_from_dyn`).
+	pub fn read_function_tokens(&self, span: Span) -> TokenStream2 {
+		let ident = &self.ident;
+		let parent = ident.to_string();
+
 		let cons = TokenStream2::with_tokens(|tokens| {
 			self.content.pat_cons_to_tokens(tokens);
 		});
@@ -164,7 +237,7 @@ impl Reply {
 		let reads = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
 				if !element.is_metabyte() && !element.is_sequence() {
-					element.read_tokens(tokens, DefinitionType::Reply);
+					element.read_tokens(tokens, DefinitionType::Reply, &parent);
 
 					// if self.content.contains_infer() {
 					element.add_x11_size_tokens(tokens);
@@ -175,10 +248,10 @@ impl Reply {
 
 		let metabyte = if let Some(element) = self.content.metabyte_element() {
 			TokenStream2::with_tokens(|tokens| {
-				element.read_tokens(tokens, DefinitionType::Reply);
+				element.read_tokens(tokens, DefinitionType::Reply, &parent);
 			})
 		} else {
-			quote_spanned!(trait_path.span()=> <_ as ::xrbk::Buf>::advance(buf, 1);)
+			quote_spanned!(span=> ::xrbk::skip(buf, 1)?;)
 		};
 
 		let sequence = match self.content.sequence_element() {
@@ -186,46 +259,85 @@ impl Reply {
 			_ => panic!("replies must have a sequence field"),
 		};
 
-		tokens.append_tokens(quote_spanned!(trait_path.span()=>
-			#[automatically_derived]
-			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					let mut size: usize = 8;
-
-					// Metabyte position
-					#metabyte
-					// Sequence field
-					let #sequence = <_ as ::xrbk::Buf>::get_u16(buf);
-					// Length
-					let length = <_ as ::xrbk::Buf>::get_u32(buf);
-					let buf = &mut <_ as ::xrbk::Buf>::take(
-						buf,
-						(((length) as usize) * 4) + (32 - 8),
-					);
+		let instrument = trace::instrument_read(
+			span,
+			ident,
+			quote_spanned!(span=>
+				sequence = ::tracing::field::Empty,
+				length = ::tracing::field::Empty,
+			),
+		);
+		let record = trace::record_read(
+			span,
+			&[
+				("sequence", quote_spanned!(span=> #sequence)),
+				("length", quote_spanned!(span=> 32 + (length as usize) * 4)),
+			],
+		);
 
-					// Other elements
-					#reads
+		let event = trace::event(span, ident, "read", quote_spanned!(span=> &message));
+
+		let attributes = quote_spanned!(span=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let read_body = quote_spanned!(span=>
+			let mut size: usize = 8;
+
+			// Metabyte position
+			#metabyte
+			// Sequence field
+			let #sequence = <u16 as ::xrbk::Readable>::read_from(buf)?;
+			// Length
+			let length = <u32 as ::xrbk::Readable>::read_from(buf)?;
+			#record
+			::xrbk::ensure_remaining(buf, (((length) as usize) * 4) + (32 - 8))?;
+			let buf = &mut ::xrbk::take(
+				buf,
+				(((length) as usize) * 4) + (32 - 8),
+			);
+
+			// Other elements
+			#reads
+
+			// Construct and return Self.
+			let message = Self #cons;
+			#event
+
+			Ok(message)
+		);
 
-					// Construct and return Self.
-					Ok(Self #cons)
-				}
+		if self.content.request_arg().is_none() {
+			return dyn_buffers::read_functions(span, &attributes, &read_body);
+		}
+
+		let request = &self.request;
+
+		quote_spanned!(span=>
+			#attributes
+			fn read_with_request(
+				buf: &mut impl ::xrbk::Buf,
+				request: &Self::Request,
+			) -> Result<Self, ::xrbk::ReadError> {
+				// The request's type, for the parameters of context functions.
+				type __Request = #request;
+
+				#read_body
 			}
-		));
+		)
 	}
 }
 
 impl Event {
 	pub fn impl_readable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -248,7 +360,7 @@ impl Event {
 		let reads = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
 				if !element.is_metabyte() && !element.is_sequence() {
-					element.read_tokens(tokens, DefinitionType::Event);
+					element.read_tokens(tokens, DefinitionType::Event, &parent);
 
 					// if self.content.contains_infer() {
 					element.add_x11_size_tokens(tokens);
@@ -261,11 +373,11 @@ impl Event {
 			None
 		} else if let Some(element) = self.content.metabyte_element() {
 			Some(TokenStream2::with_tokens(|tokens| {
-				element.read_tokens(tokens, DefinitionType::Event);
+				element.read_tokens(tokens, DefinitionType::Event, &parent);
 			}))
 		} else {
 			Some(quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::Buf>::advance(buf, 1);
+				::xrbk::skip(buf, 1)?;
 			))
 		};
 
@@ -273,38 +385,84 @@ impl Event {
 			let formatted = &field.formatted;
 
 			Some(quote_spanned!(trait_path.span()=>
-				let #formatted = <_ as ::xrbk::Buf>::get_u16(buf);
+				let #formatted = <u16 as ::xrbk::Readable>::read_from(buf)?;
 			))
 		} else {
 			None
 		};
 
+		let instrument = trace::instrument_read(
+			trait_path.span(),
+			ident,
+			if self.content.sequence_element().is_some() {
+				quote_spanned!(trait_path.span()=>
+					code = <Self as xrb::message::Event>::CODE,
+					sequence = ::tracing::field::Empty,
+					length = 32_usize,
+				)
+			} else {
+				quote_spanned!(trait_path.span()=>
+					code = <Self as xrb::message::Event>::CODE,
+					length = 32_usize,
+				)
+			},
+		);
+		let record = if let Some(Element::Field(field)) = self.content.sequence_element() {
+			let formatted = &field.formatted;
+
+			Some(trace::record_read(
+				trait_path.span(),
+				&[("sequence", quote_spanned!(trait_path.span()=> #formatted))],
+			))
+		} else {
+			None
+		};
+
+		let event = trace::event(
+			trait_path.span(),
+			ident,
+			"read",
+			quote_spanned!(trait_path.span()=> &message),
+		);
+
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let read_body = quote_spanned!(trait_path.span()=>
+			let mut size: usize = #x11_size;
+
+			// Every event is 32 bytes, the first of which has already
+			// been read.
+			::xrbk::ensure_remaining(buf, 31)?;
+
+			// Metabyte position
+			#metabyte
+			// Sequence field
+			#sequence
+			#record
+
+			// Other elements
+			#reads
+
+			// Construct and return Self.
+			let message = Self #cons;
+			#event
+
+			Ok(message)
+		);
+		let functions = dyn_buffers::read_functions(trait_path.span(), &attributes, &read_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					let mut size: usize = #x11_size;
-
-					// Metabyte position
-					#metabyte
-					// Sequence field
-					#sequence
-
-					// Other elements
-					#reads
-
-					// Construct and return Self.
-					Ok(Self #cons)
-				}
+				#functions
 			}
 		));
 	}
@@ -313,6 +471,7 @@ impl Event {
 impl Error {
 	pub fn impl_readable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
 		let where_clause = match &self.content {
@@ -328,7 +487,7 @@ impl Error {
 		let reads = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
 				if element.is_normal() {
-					element.read_tokens(tokens, DefinitionType::Error);
+					element.read_tokens(tokens, DefinitionType::Error, &parent);
 
 					// if self.content.contains_infer() {
 					element.add_x11_size_tokens(tokens);
@@ -342,7 +501,7 @@ impl Error {
 				let formatted = &field.formatted;
 
 				quote_spanned!(trait_path.span()=>
-					let #formatted = <_ as ::xrbk::Buf>::get_u16(buf);
+					let #formatted = <u16 as ::xrbk::Readable>::read_from(buf)?;
 				)
 			},
 
@@ -354,7 +513,7 @@ impl Error {
 				let formatted = &field.formatted;
 
 				quote_spanned!(trait_path.span()=>
-					let #formatted = <_ as ::xrbk::Buf>::get_u16(buf);
+					let #formatted = <u16 as ::xrbk::Readable>::read_from(buf)?;
 				)
 			},
 
@@ -366,7 +525,7 @@ impl Error {
 				let formatted = &field.formatted;
 
 				quote_spanned!(trait_path.span()=>
-					let #formatted = <_ as ::xrbk::Buf>::get_u8(buf);
+					let #formatted = <u8 as ::xrbk::Readable>::read_from(buf)?;
 				)
 			},
 
@@ -374,44 +533,85 @@ impl Error {
 		};
 
 		let error_data = match self.content.error_data_element() {
+			Some(element @ Element::Field(field)) => TokenStream2::with_tokens(|tokens| {
+				field.read_tokens(tokens, &parent, &element.wire_name());
+			}),
+
+			_ => quote_spanned!(trait_path.span()=> ::xrbk::skip(buf, 4)?;),
+		};
+
+		let instrument = trace::instrument_read(
+			trait_path.span(),
+			ident,
+			quote_spanned!(trait_path.span()=>
+				code = <Self as xrb::message::Error>::CODE,
+				sequence = ::tracing::field::Empty,
+				length = 32_usize,
+			),
+		);
+		let record = match self.content.sequence_element() {
 			Some(Element::Field(field)) => {
-				TokenStream2::with_tokens(|tokens| field.read_tokens(tokens))
+				let formatted = &field.formatted;
+
+				trace::record_read(
+					trait_path.span(),
+					&[("sequence", quote_spanned!(trait_path.span()=> #formatted))],
+				)
 			},
 
-			_ => quote_spanned!(trait_path.span()=> <_ as ::xrbk::Buf>::advance(buf, 4);),
+			_ => panic!("errors must have sequence fields"),
 		};
 
+		let event = trace::event(
+			trait_path.span(),
+			ident,
+			"read",
+			quote_spanned!(trait_path.span()=> &message),
+		);
+
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let read_body = quote_spanned!(trait_path.span()=>
+			// 11 bytes includes:
+			// - 1 byte to say it's an error
+			// - 1 byte for its code
+			// - 2 bytes for its sequence number
+			// - 4 bytes for its (optional) error data
+			// - 2 bytes for the request's minor opcode
+			// - 1 byte for the request's major opcode
+			let mut size: usize = 11;
+
+			// Every error is 32 bytes, the first two of which have
+			// already been read.
+			::xrbk::ensure_remaining(buf, 30)?;
+
+			#sequence
+			#record
+			#error_data
+			#minor_opcode
+			#major_opcode
+
+			#reads
+
+			let message = Self #cons;
+			#event
+
+			Ok(message)
+		);
+		let functions = dyn_buffers::read_functions(trait_path.span(), &attributes, &read_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					// 11 bytes includes:
-					// - 1 byte to say it's an error
-					// - 1 byte for its code
-					// - 2 bytes for its sequence number
-					// - 4 bytes for its (optional) error data
-					// - 2 bytes for the request's minor opcode
-					// - 1 byte for the request's major opcode
-					let mut size: usize = 11;
-
-					#sequence
-					#error_data
-					#minor_opcode
-					#major_opcode
-
-					#reads
-
-					Ok(Self #cons)
-				}
+				#functions
 			}
 		));
 	}
@@ -458,6 +658,7 @@ impl Enum {
 
 			for variant in &self.variants {
 				let ident = &variant.ident;
+				let parent = format!("{}::{ident}", self.ident);
 
 				let declare_x11_size = {
 					let discrim_type = quote_spanned!(discrim_type.span()=>
@@ -481,7 +682,7 @@ impl Enum {
 
 				let reads = TokenStream2::with_tokens(|tokens| {
 					for element in &variant.content {
-						element.read_tokens(tokens, DefinitionType::Basic);
+						element.read_tokens(tokens, DefinitionType::Basic, &parent);
 
 						// if variant.content.contains_infer() {
 						element.add_x11_size_tokens(tokens);
@@ -507,32 +708,36 @@ impl Enum {
 			<#discrim_type as ::xrbk::Readable>
 		);
 
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				clippy::unnecessary_cast,
+				unused_mut,
+			)]
+		);
+		let read_body = quote_spanned!(trait_path.span()=>
+			// Define functions and variables for variants which
+			// have custom discriminant expressions.
+			#discriminants
+
+			match #discrim_type::read_from(buf)? {
+				#arms
+
+				other_discrim => Err(::xrbk::ReadError::UnrecognizedDiscriminant {
+					type_name: ::core::stringify!(#ident),
+					discriminant: other_discrim as usize,
+				}),
+			}
+		);
+		let functions = dyn_buffers::read_functions(trait_path.span(), &attributes, &read_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Readable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					clippy::unnecessary_cast,
-					unused_mut,
-				)]
-				fn read_from(
-					buf: &mut impl ::xrbk::Buf,
-				) -> Result<Self, ::xrbk::ReadError> {
-					// Define functions and variables for variants which
-					// have custom discriminant expressions.
-					#discriminants
-
-					match #discrim_type::read_from(buf)? {
-						#arms
-
-						other_discrim => Err(
-							::xrbk::ReadError::UnrecognizedDiscriminant(other_discrim as usize),
-						),
-					}
-				}
+				#functions
 			}
 		));
 	}
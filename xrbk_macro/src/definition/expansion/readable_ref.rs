@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote_spanned, ToTokens};
+use syn::{spanned::Spanned, Path};
+
+use crate::TsExt;
+
+use super::*;
+
+impl Struct {
+	pub fn impl_readable_ref(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let ident = &self.ident;
+		let parent = ident.to_string();
+
+		// The bytes are borrowed for the first lifetime parameter.
+		let Some(lifetime) = self.generics.lifetimes().next().map(|def| &def.lifetime) else {
+			syn::Error::new(
+				trait_path.span(),
+				"ReadableRef can only be derived for structs with a lifetime parameter",
+			)
+			.to_compile_error()
+			.to_tokens(tokens);
+
+			return;
+		};
+
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+
+		// Expand the tokens to call Self's constructor.
+		let cons = TokenStream2::with_tokens(|tokens| {
+			self.content.pat_cons_to_tokens(tokens);
+		});
+
+		// Expand the tokens to read each element.
+		let reads = TokenStream2::with_tokens(|tokens| {
+			for element in &self.content {
+				element.read_ref_tokens(
+					tokens,
+					DefinitionType::Basic,
+					&parent,
+					lifetime,
+					&self.generics,
+				);
+
+				element.add_x11_size_tokens(tokens);
+			}
+		});
+
+		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+			#[automatically_derived]
+			impl #impl_generics ::xrbk::ReadableRef<#lifetime> for #ident #type_generics
+			#where_clause
+			{
+				#[allow(
+					clippy::items_after_statements,
+					clippy::trivially_copy_pass_by_ref,
+					clippy::needless_borrow,
+					clippy::identity_op,
+					unused_mut,
+				)]
+				fn read_ref(
+					bytes: &#lifetime [u8],
+				) -> ::xrbk::ReadResult<(Self, &#lifetime [u8])> {
+					let mut bytes = bytes;
+					let buf = &mut bytes;
+
+					// Declare a x11_size variable if it is going to be
+					// used in an infer unused bytes element.
+					let mut size: usize = 0;
+
+					// Read each element.
+					#reads
+
+					// Construct and return `Self` with the bytes which
+					// haven't been read.
+					Ok((Self #cons, *buf))
+				}
+			}
+		));
+	}
+}
@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::Ident;
+
+/// Generates a `tracing::instrument` attribute for the `write_to` function of
+/// a message.
+///
+/// The attribute is only applied if the `tracing` feature is enabled in the
+/// crate in which the message is defined, so that nothing is generated
+/// otherwise.
+pub fn instrument_write(span: Span, ident: &Ident, fields: TokenStream2) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg_attr(
+			feature = "tracing",
+			::tracing::instrument(
+				name = "write",
+				level = "trace",
+				skip_all,
+				fields(
+					message = ::core::stringify!(#ident),
+					#fields
+					length = <Self as ::xrbk::X11Size>::x11_size(self),
+				),
+			),
+		)]
+	)
+}
+
+/// Generates a `tracing::instrument` attribute for the `read_from` function of
+/// a message.
+///
+/// A `debug` event is emitted if reading the message fails. Any `fields`
+/// which are not known until part of the message has been read should be
+/// [`Empty`] and recorded with [`record_read`].
+///
+/// As with [`instrument_write`], the attribute is only applied if the
+/// `tracing` feature is enabled.
+///
+/// [`Empty`]: https://docs.rs/tracing/latest/tracing/field/struct.Empty.html
+pub fn instrument_read(span: Span, ident: &Ident, fields: TokenStream2) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg_attr(
+			feature = "tracing",
+			::tracing::instrument(
+				name = "read",
+				level = "trace",
+				skip_all,
+				fields(
+					message = ::core::stringify!(#ident),
+					#fields
+				),
+				err(Display, level = "debug"),
+			),
+		)]
+	)
+}
+
+/// Generates a statement which records the values of the given `fields` of the
+/// span entered by [`instrument_read`], if the `tracing` feature is enabled.
+pub fn record_read(span: Span, fields: &[(&str, TokenStream2)]) -> TokenStream2 {
+	let records = fields.iter().map(|(name, value)| {
+		quote_spanned!(span=>
+			span.record(#name, #value);
+		)
+	});
+
+	quote_spanned!(span=>
+		#[cfg(feature = "tracing")]
+		{
+			let span = ::tracing::Span::current();
+			#(#records)*
+		}
+	)
+}
+
+/// Generates a `trace` event recording that a message has been written or
+/// read, if the `tracing` feature is enabled.
+///
+/// The event is emitted within the span entered by [`instrument_write`] or
+/// [`instrument_read`], which carries the message's opcodes or sequence
+/// number; many subscribers show nothing for a span which contains no events.
+///
+/// `direction` is either `"write"` or `"read"`, and `message` is a reference
+/// to the message which was written or read.
+pub fn event(span: Span, ident: &Ident, direction: &str, message: TokenStream2) -> TokenStream2 {
+	quote_spanned!(span=>
+		#[cfg(feature = "tracing")]
+		::tracing::trace!(
+			message = ::core::stringify!(#ident),
+			direction = #direction,
+			length = <Self as ::xrbk::X11Size>::x11_size(#message),
+		);
+	)
+}
@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote_spanned;
+use syn::{Generics, Ident, WhereClause};
+
+/// Wraps the given statements, which write one element of a type, so that the
+/// bytes they write are recorded as a `WireField` with the given `name` if
+/// `layout` is true.
+///
+/// This is used to generate the `write_layout` function of a `WireLayout`
+/// implementation from the same statements as its `Writable` implementation.
+/// If `layout` is false, the `statements` are returned unchanged.
+pub fn mark(layout: bool, span: Span, name: &str, statements: TokenStream2) -> TokenStream2 {
+	if !layout {
+		return statements;
+	}
+
+	quote_spanned!(span=>
+		let wire_start = buf.written();
+		#statements
+		wire_layout.push(::xrbk::WireField {
+			name: #name,
+			offset: wire_start,
+			size: buf.written() - wire_start,
+		});
+	)
+}
+
+/// Generates a `WireLayout` implementation which writes the given `body`,
+/// generated with [`mark`], to a counted buffer.
+///
+/// The implementation is only generated if the `trace-wire` feature is enabled
+/// in the crate in which the type is defined.
+pub fn impl_wire_layout(
+	span: Span, ident: &Ident, generics: &Generics, where_clause: &Option<WhereClause>,
+	body: TokenStream2,
+) -> TokenStream2 {
+	let (impl_generics, type_generics, _) = generics.split_for_impl();
+
+	quote_spanned!(span=>
+		#[cfg(feature = "trace-wire")]
+		#[automatically_derived]
+		impl #impl_generics ::xrbk::WireLayout for #ident #type_generics #where_clause {
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			fn write_layout(
+				&self,
+				bytes: &mut ::xrbk::__private::Vec<u8>,
+			) -> Result<::xrbk::__private::Vec<::xrbk::WireField>, ::xrbk::WriteError> {
+				let mut wire_layout = ::xrbk::__private::Vec::new();
+				let buf = &mut ::xrbk::CountingBufMut::new(bytes);
+
+				#body
+
+				Ok(wire_layout)
+			}
+		}
+	)
+}
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::*;
+use super::{dyn_buffers, wire, *};
 use crate::{element::Element, TsExt};
 
 use proc_macro2::TokenStream as TokenStream2;
@@ -12,6 +12,7 @@ use syn::Path;
 impl Struct {
 	pub fn impl_writable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -27,7 +28,7 @@ impl Struct {
 
 		let writes = TokenStream2::with_tokens(|tokens| {
 			for element in &self.content {
-				element.write_tokens(tokens, DefinitionType::Basic);
+				element.write_tokens(tokens, DefinitionType::Basic, &parent);
 
 				// if self.content.contains_infer() {
 				element.add_x11_size_tokens(tokens);
@@ -35,28 +36,30 @@ impl Struct {
 			}
 		});
 
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+		);
+		let write_body = quote_spanned!(trait_path.span()=>
+			let mut size: usize = 0;
+			// Destructure the struct's fields, if any.
+			let Self #pat = self;
+
+			#writes
+
+			Ok(())
+		);
+		let functions = dyn_buffers::write_functions(trait_path.span(), &attributes, &write_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn write_to(
-					&self,
-					buf: &mut impl ::xrbk::BufMut,
-				) -> Result<(), ::xrbk::WriteError> {
-					let mut size: usize = 0;
-					// Destructure the struct's fields, if any.
-					let Self #pat = self;
-
-					#writes
-
-					Ok(())
-				}
+				#functions
 			}
 		));
 	}
@@ -64,7 +67,9 @@ impl Struct {
 
 impl Request {
 	pub fn impl_writable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let span = trait_path.span();
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -78,83 +83,250 @@ impl Request {
 			self.content.pat_cons_to_tokens(tokens);
 		});
 
-		let writes = TokenStream2::with_tokens(|tokens| {
-			for element in &self.content {
-				if !element.is_metabyte() && !element.is_sequence() {
-					element.write_tokens(tokens, DefinitionType::Request);
-
-					// if self.content.contains_infer() {
-					element.add_x11_size_tokens(tokens);
-					// }
+		let writes = |layout: bool| {
+			TokenStream2::with_tokens(|tokens| {
+				for element in &self.content {
+					if !element.is_metabyte() && !element.is_sequence() {
+						let statements = TokenStream2::with_tokens(|tokens| {
+							element.write_tokens(tokens, DefinitionType::Request, &parent);
+
+							// if self.content.contains_infer() {
+							element.add_x11_size_tokens(tokens);
+							// }
+						});
+
+						tokens.append_tokens(wire::mark(
+							layout,
+							span,
+							&element.wire_name(),
+							statements,
+						));
+					}
 				}
+			})
+		};
+
+		let metabyte = |layout: bool| {
+			if self.is_extension() {
+				wire::mark(
+					layout,
+					span,
+					"minor_opcode",
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u8(
+							buf,
+							<Self as xrb::message::ExtensionRequest>::MINOR_OPCODE,
+						);
+					),
+				)
+			} else if self.minor_opcode.is_some() {
+				// TODO: can't be in metabyte, must check this in protocol!!
+				wire::mark(
+					layout,
+					span,
+					"minor_opcode",
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u16(
+							buf,
+							<Self as xrb::message::Request>::MINOR_OPCODE.unwrap(),
+						);
+					),
+				)
+			} else if let Some(element) = self.content.metabyte_element() {
+				wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					TokenStream2::with_tokens(|tokens| {
+						element.write_tokens(tokens, DefinitionType::Request, &parent);
+					}),
+				)
+			} else {
+				wire::mark(
+					layout,
+					span,
+					"_",
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u8(
+							buf,
+							0,
+						);
+					),
+				)
 			}
-		});
+		};
 
-		let metabyte = if self.minor_opcode.is_some() {
-			// TODO: can't be in metabyte, must check this in protocol!!
-			quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_u16(
-					buf,
-					<Self as xrb::message::Request>::MINOR_OPCODE.unwrap(),
-				);
-			)
-		} else if let Some(element) = self.content.metabyte_element() {
-			TokenStream2::with_tokens(|tokens| {
-				element.write_tokens(tokens, DefinitionType::Request);
-			})
+		// The major opcode of an extension request is given when it is written.
+		let major_opcode = if self.is_extension() {
+			quote_spanned!(span=> major_opcode)
 		} else {
-			quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_u8(
-					buf,
-					0,
-				);
+			quote_spanned!(span=> <Self as xrb::message::Request>::MAJOR_OPCODE)
+		};
+
+		let header = |layout: bool| {
+			let major_opcode = wire::mark(
+				layout,
+				span,
+				"major_opcode",
+				quote_spanned!(span=>
+					<_ as ::xrbk::BufMut>::put_u8(
+						buf,
+						#major_opcode
+					);
+				),
+			);
+			let metabyte = metabyte(layout);
+			let length = wire::mark(
+				layout,
+				span,
+				"length",
+				quote_spanned!(span=>
+					xrb::message::write_request_length(
+						buf,
+						<Self as ::xrbk::X11Size>::x11_size(self),
+						mode,
+					)?;
+				),
+			);
+
+			quote_spanned!(span=>
+				let mut size: usize = 4;
+				// Destructure the request struct's fields, if any.
+				let Self #pat = self;
+
+				// Major opcode
+				#major_opcode
+				// Metabyte position
+				#metabyte
+				// Length
+				#length
 			)
 		};
 
-		tokens.append_tokens(quote_spanned!(trait_path.span()=>
+		let header_tokens = header(false);
+		let writes_tokens = writes(false);
+
+		let instrument = trace::instrument_write(
+			span,
+			ident,
+			if self.is_extension() {
+				quote_spanned!(span=>
+					major_opcode,
+					minor_opcode = <Self as xrb::message::ExtensionRequest>::MINOR_OPCODE,
+				)
+			} else {
+				quote_spanned!(span=>
+					major_opcode = <Self as xrb::message::Request>::MAJOR_OPCODE,
+					minor_opcode = ?<Self as xrb::message::Request>::MINOR_OPCODE,
+				)
+			},
+		);
+
+		let event = trace::event(span, ident, "write", quote_spanned!(span=> self));
+
+		let attributes = quote_spanned!(span=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let write_body = quote_spanned!(span=>
+			#header_tokens
+
+			// Count the bytes written after the header in debug builds,
+			// so that the request's size can be checked.
+			#[cfg(debug_assertions)]
+			let buf = &mut ::xrbk::CountingBufMut::new(buf);
+
+			// Other elements
+			#writes_tokens
+
+			#[cfg(debug_assertions)]
+			::xrbk::assert_written_size(
+				::core::any::type_name::<Self>(),
+				4 + buf.written(),
+				<Self as ::xrbk::X11Size>::x11_size(self),
+				// The length is given in 4-byte units, not including the
+				// extra 4 bytes of an extended length.
+				<Self as ::xrbk::X11Size>::x11_size(self) / 4 * 4,
+			);
+
+			#event
+
+			Ok(())
+		);
+
+		// Extension requests are only `Writable` once they are given a major
+		// opcode, so they have no `Writable` or `WireLayout` implementation.
+		if self.is_extension() {
+			tokens.append_tokens(quote_spanned!(span=>
+				#[automatically_derived]
+				impl #impl_generics xrb::message::WritableExtensionRequest for #ident #type_generics #where_clause {
+					#attributes
+					fn write_to_with_opcode(
+						&self,
+						major_opcode: u8,
+						buf: &mut impl ::xrbk::BufMut,
+						mode: xrb::message::LengthMode,
+					) -> Result<(), ::xrbk::WriteError> {
+						#write_body
+					}
+				}
+			));
+
+			return;
+		}
+
+		let functions = dyn_buffers::write_with_functions(span, &attributes, &write_body);
+
+		tokens.append_tokens(quote_spanned!(span=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
 				fn write_to(
 					&self,
 					buf: &mut impl ::xrbk::BufMut,
 				) -> Result<(), ::xrbk::WriteError> {
-					let mut size: usize = 4;
-					// Destructure the request struct's fields, if any.
-					let Self #pat = self;
-
-					// Major opcode
-					<_ as ::xrbk::BufMut>::put_u8(
+					<Self as xrb::message::WritableRequest>::write_to_with(
+						self,
 						buf,
-						<Self as xrb::message::Request>::MAJOR_OPCODE
-					);
-					// Metabyte position
-					#metabyte
-					// Length
-					<_ as ::xrbk::BufMut>::put_u16(
-						buf,
-						<Self as xrb::message::Request>::length(&self),
-					);
-
-					// Other elements
-					#writes
-
-					Ok(())
+						xrb::message::LengthMode::Standard,
+					)
 				}
 			}
+
+			#[automatically_derived]
+			impl #impl_generics xrb::message::WritableRequest for #ident #type_generics #where_clause {
+				#functions
+			}
+		));
+
+		let header = header(true);
+		let writes = writes(true);
+
+		tokens.append_tokens(wire::impl_wire_layout(
+			span,
+			ident,
+			&self.generics,
+			where_clause,
+			quote_spanned!(span=>
+				let mode = xrb::message::LengthMode::Standard;
+
+				#header
+				#writes
+			),
 		));
 	}
 }
 
 impl Reply {
 	pub fn impl_writable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let span = trait_path.span();
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -168,79 +340,189 @@ impl Reply {
 			self.content.pat_cons_to_tokens(tokens);
 		});
 
-		let writes = TokenStream2::with_tokens(|tokens| {
-			for element in &self.content {
-				if !element.is_metabyte() && !element.is_sequence() {
-					element.write_tokens(tokens, DefinitionType::Reply);
-
-					// if self.content.contains_infer() {
-					element.add_x11_size_tokens(tokens);
-					// }
-				}
-			}
-		});
-
-		let metabyte = if let Some(element) = self.content.metabyte_element() {
+		let writes = |layout: bool| {
 			TokenStream2::with_tokens(|tokens| {
-				element.write_tokens(tokens, DefinitionType::Reply);
+				for element in &self.content {
+					if !element.is_metabyte() && !element.is_sequence() {
+						let statements = TokenStream2::with_tokens(|tokens| {
+							element.write_tokens(tokens, DefinitionType::Reply, &parent);
+
+							// if self.content.contains_infer() {
+							element.add_x11_size_tokens(tokens);
+							// }
+						});
+
+						tokens.append_tokens(wire::mark(
+							layout,
+							span,
+							&element.wire_name(),
+							statements,
+						));
+					}
+				}
 			})
-		} else {
-			quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_u8(buf, 0);
-			)
+		};
+
+		let metabyte = |layout: bool| {
+			if let Some(element) = self.content.metabyte_element() {
+				wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					TokenStream2::with_tokens(|tokens| {
+						element.write_tokens(tokens, DefinitionType::Reply, &parent);
+					}),
+				)
+			} else {
+				wire::mark(
+					layout,
+					span,
+					"_",
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u8(buf, 0);
+					),
+				)
+			}
 		};
 
 		let sequence = match self.content.sequence_element() {
-			Some(Element::Field(field)) => &field.formatted,
+			Some(element @ Element::Field(field)) => (element.wire_name(), &field.formatted),
 			_ => panic!("replies must have a sequence field"),
 		};
 
-		tokens.append_tokens(quote_spanned!(trait_path.span()=>
-			#[automatically_derived]
-			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn write_to(
-					&self,
-					buf: &mut impl ::xrbk::BufMut,
-				) -> Result<(), ::xrbk::WriteError> {
-					let mut size: usize = 8;
-					// Destructure the reply struct's fields, if any.
-					let Self #pat = self;
-
-					// `1` - indicates this is a reply
-					buf.put_u8(1);
-					// Metabyte position
-					#metabyte
-					// Sequence field
-					<_ as ::xrbk::BufMut>::put_u16(
-						buf,
-						*#sequence,
-					);
-					// Length
+		let body = |layout: bool| {
+			let reply = wire::mark(
+				layout,
+				span,
+				"reply",
+				quote_spanned!(span=>
+					<_ as ::xrbk::BufMut>::put_u8(buf, 1);
+				),
+			);
+			let metabyte = metabyte(layout);
+			let sequence = {
+				let (name, formatted) = &sequence;
+
+				wire::mark(
+					layout,
+					span,
+					name,
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u16(
+							buf,
+							*#formatted,
+						);
+					),
+				)
+			};
+			let length = wire::mark(
+				layout,
+				span,
+				"length",
+				quote_spanned!(span=>
 					<_ as ::xrbk::BufMut>::put_u32(
 						buf,
 						<Self as xrb::message::Reply>::length(&self),
 					);
+				),
+			);
+			let writes = writes(layout);
+
+			quote_spanned!(span=>
+				// `1` - indicates this is a reply
+				#reply
+				// Metabyte position
+				#metabyte
+				// Sequence field
+				#sequence
+				// Length
+				#length
+
+				// Other elements
+				#writes
+			)
+		};
 
-					// Other elements
-					#writes
+		let body_tokens = body(false);
 
-					Ok(())
-				}
+		let instrument = trace::instrument_write(
+			span,
+			ident,
+			quote_spanned!(span=>
+				sequence = <Self as xrb::message::Reply>::sequence(self),
+			),
+		);
+
+		let event = trace::event(span, ident, "write", quote_spanned!(span=> self));
+
+		let attributes = quote_spanned!(span=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let write_body = quote_spanned!(span=>
+			let mut size: usize = 8;
+			// Destructure the reply struct's fields, if any.
+			let Self #pat = self;
+
+			// Count the bytes written in debug builds, so that the
+			// reply's size can be checked.
+			#[cfg(debug_assertions)]
+			let buf = &mut ::xrbk::CountingBufMut::new(buf);
+
+			#body_tokens
+
+			#[cfg(debug_assertions)]
+			::xrbk::assert_written_size(
+				::core::any::type_name::<Self>(),
+				buf.written(),
+				<Self as ::xrbk::X11Size>::x11_size(self),
+				// The length is given in 4-byte units, not including the
+				// first 32 bytes of the reply.
+				32 + (<Self as xrb::message::Reply>::length(&self) as usize) * 4,
+			);
+
+			#event
+
+			Ok(())
+		);
+		let functions = dyn_buffers::write_functions(span, &attributes, &write_body);
+
+		tokens.append_tokens(quote_spanned!(span=>
+			#[automatically_derived]
+			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
+				#functions
 			}
 		));
+
+		let body = body(true);
+
+		tokens.append_tokens(wire::impl_wire_layout(
+			span,
+			ident,
+			&self.generics,
+			where_clause,
+			quote_spanned!(span=>
+				let mut size: usize = 8;
+				// Destructure the reply struct's fields, if any.
+				let Self #pat = self;
+
+				#body
+			),
+		));
 	}
 }
 
 impl Event {
 	pub fn impl_writable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let span = trait_path.span();
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -260,81 +542,179 @@ impl Event {
 			self.content.pat_cons_to_tokens(tokens);
 		});
 
-		let writes = TokenStream2::with_tokens(|tokens| {
-			for element in &self.content {
-				if element.is_normal() {
-					element.write_tokens(tokens, DefinitionType::Event);
-
-					// if self.content.contains_infer() {
-					element.add_x11_size_tokens(tokens);
-					// }
+		let writes = |layout: bool| {
+			TokenStream2::with_tokens(|tokens| {
+				for element in &self.content {
+					if element.is_normal() {
+						let statements = TokenStream2::with_tokens(|tokens| {
+							element.write_tokens(tokens, DefinitionType::Event, &parent);
+
+							// if self.content.contains_infer() {
+							element.add_x11_size_tokens(tokens);
+							// }
+						});
+
+						tokens.append_tokens(wire::mark(
+							layout,
+							span,
+							&element.wire_name(),
+							statements,
+						));
+					}
 				}
-			}
-		});
+			})
+		};
 
-		let metabyte = if self.content.sequence_element().is_none() {
-			None
-		} else if let Some(element) = self.content.metabyte_element() {
-			Some(TokenStream2::with_tokens(|tokens| {
-				element.write_tokens(tokens, DefinitionType::Event);
-			}))
-		} else {
-			Some(quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_u8(buf, 0);
-			))
+		let metabyte = |layout: bool| {
+			if self.content.sequence_element().is_none() {
+				None
+			} else if let Some(element) = self.content.metabyte_element() {
+				Some(wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					TokenStream2::with_tokens(|tokens| {
+						element.write_tokens(tokens, DefinitionType::Event, &parent);
+					}),
+				))
+			} else {
+				Some(wire::mark(
+					layout,
+					span,
+					"_",
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u8(buf, 0);
+					),
+				))
+			}
 		};
 
-		let sequence = if let Some(Element::Field(field)) = self.content.sequence_element() {
-			let formatted = &field.formatted;
+		let sequence = |layout: bool| {
+			if let Some(element @ Element::Field(field)) = self.content.sequence_element() {
+				let formatted = &field.formatted;
 
-			Some(quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_u16(buf, *#formatted);
-			))
-		} else {
-			None
+				Some(wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u16(buf, *#formatted);
+					),
+				))
+			} else {
+				None
+			}
 		};
 
-		tokens.append_tokens(quote_spanned!(trait_path.span()=>
-			#[automatically_derived]
-			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn write_to(
-					&self,
-					buf: &mut impl ::xrbk::BufMut,
-				) -> Result<(), ::xrbk::WriteError> {
-					let mut size: usize = #x11_size;
-					// Destructure the event struct's fields, if any.
-					let Self #pat = self;
-
-					// Event code
+		let body = |layout: bool| {
+			let code = wire::mark(
+				layout,
+				span,
+				"code",
+				quote_spanned!(span=>
 					<_ as ::xrbk::BufMut>::put_u8(
 						buf,
 						<Self as xrb::message::Event>::CODE,
 					);
-					// Metabyte position
-					#metabyte
-					// Sequence field
-					#sequence
+				),
+			);
+			let metabyte = metabyte(layout);
+			let sequence = sequence(layout);
+			let writes = writes(layout);
+
+			quote_spanned!(span=>
+				// Event code
+				#code
+				// Metabyte position
+				#metabyte
+				// Sequence field
+				#sequence
+
+				// Other elements
+				#writes
+			)
+		};
 
-					// Other elements
-					#writes
+		let body_tokens = body(false);
 
-					Ok(())
-				}
+		let instrument = trace::instrument_write(
+			span,
+			ident,
+			quote_spanned!(span=>
+				code = <Self as xrb::message::Event>::CODE,
+				sequence = ?<Self as xrb::message::Event>::sequence(self),
+			),
+		);
+
+		let event = trace::event(span, ident, "write", quote_spanned!(span=> self));
+
+		let attributes = quote_spanned!(span=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let write_body = quote_spanned!(span=>
+			let mut size: usize = #x11_size;
+			// Destructure the event struct's fields, if any.
+			let Self #pat = self;
+
+			// Count the bytes written in debug builds, so that the
+			// event's size can be checked.
+			#[cfg(debug_assertions)]
+			let buf = &mut ::xrbk::CountingBufMut::new(buf);
+
+			#body_tokens
+
+			#[cfg(debug_assertions)]
+			::xrbk::assert_written_size(
+				::core::any::type_name::<Self>(),
+				buf.written(),
+				<Self as ::xrbk::X11Size>::x11_size(self),
+				// Events are always 32 bytes long.
+				32,
+			);
+
+			#event
+
+			Ok(())
+		);
+		let functions = dyn_buffers::write_functions(span, &attributes, &write_body);
+
+		tokens.append_tokens(quote_spanned!(span=>
+			#[automatically_derived]
+			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
+				#functions
 			}
 		));
+
+		let body = body(true);
+
+		tokens.append_tokens(wire::impl_wire_layout(
+			span,
+			ident,
+			&self.generics,
+			where_clause,
+			quote_spanned!(span=>
+				let mut size: usize = #x11_size;
+				// Destructure the event struct's fields, if any.
+				let Self #pat = self;
+
+				#body
+			),
+		));
 	}
 }
 
 impl Error {
 	pub fn impl_writable(&self, tokens: &mut TokenStream2, trait_path: &Path) {
+		let span = trait_path.span();
 		let ident = &self.ident;
+		let parent = ident.to_string();
 
 		// TODO: add generic bounds
 		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
@@ -348,115 +728,205 @@ impl Error {
 			self.content.pat_cons_to_tokens(tokens);
 		});
 
-		let writes = TokenStream2::with_tokens(|tokens| {
-			for element in &self.content {
-				if element.is_normal() {
-					element.write_tokens(tokens, DefinitionType::Error);
-
-					if self.content.contains_infer() {
-						element.add_x11_size_tokens(tokens);
+		let writes = |layout: bool| {
+			TokenStream2::with_tokens(|tokens| {
+				for element in &self.content {
+					if element.is_normal() {
+						let statements = TokenStream2::with_tokens(|tokens| {
+							element.write_tokens(tokens, DefinitionType::Error, &parent);
+
+							if self.content.contains_infer() {
+								element.add_x11_size_tokens(tokens);
+							}
+						});
+
+						tokens.append_tokens(wire::mark(
+							layout,
+							span,
+							&element.wire_name(),
+							statements,
+						));
 					}
 				}
-			}
-		});
+			})
+		};
 
-		let sequence = match self.content.sequence_element() {
-			Some(Element::Field(field)) => {
+		let sequence = |layout: bool| match self.content.sequence_element() {
+			Some(element @ Element::Field(field)) => {
 				let formatted = &field.formatted;
 
-				quote_spanned!(trait_path.span()=>
-					<_ as ::xrbk::BufMut>::put_u16(
-						buf,
-						*#formatted,
-					);
+				wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u16(
+							buf,
+							*#formatted,
+						);
+					),
 				)
 			},
 
 			_ => panic!("errors must have sequence fields"),
 		};
 
-		let minor_opcode = match self.content.minor_opcode_element() {
-			Some(Element::Field(field)) => {
+		let minor_opcode = |layout: bool| match self.content.minor_opcode_element() {
+			Some(element @ Element::Field(field)) => {
 				let formatted = &field.formatted;
 
-				quote_spanned!(trait_path.span()=>
-					<_ as ::xrbk::BufMut>::put_u16(buf, *#formatted);
+				wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u16(buf, *#formatted);
+					),
 				)
 			},
 
 			_ => panic!("errors must have minor opcode fields"),
 		};
 
-		let major_opcode = match self.content.major_opcode_element() {
-			Some(Element::Field(field)) => {
+		let major_opcode = |layout: bool| match self.content.major_opcode_element() {
+			Some(element @ Element::Field(field)) => {
 				let formatted = &field.formatted;
 
-				quote_spanned!(trait_path.span()=>
-					<_ as ::xrbk::BufMut>::put_u8(buf, *#formatted);
+				wire::mark(
+					layout,
+					span,
+					&element.wire_name(),
+					quote_spanned!(span=>
+						<_ as ::xrbk::BufMut>::put_u8(buf, *#formatted);
+					),
 				)
 			},
 
 			_ => panic!("errors must have major opcode fields"),
 		};
 
-		let error_data = match self.content.error_data_element() {
-			Some(Element::Field(field)) => {
-				TokenStream2::with_tokens(|tokens| field.write_tokens(tokens))
-			},
+		let error_data = |layout: bool| match self.content.error_data_element() {
+			Some(element @ Element::Field(field)) => wire::mark(
+				layout,
+				span,
+				&element.wire_name(),
+				TokenStream2::with_tokens(|tokens| {
+					field.write_tokens(tokens, &parent, &element.wire_name());
+				}),
+			),
 
-			_ => quote_spanned!(trait_path.span()=>
-				<_ as ::xrbk::BufMut>::put_bytes(buf, 0, 4);
+			_ => wire::mark(
+				layout,
+				span,
+				"_",
+				quote_spanned!(span=>
+					<_ as ::xrbk::BufMut>::put_bytes(buf, 0, 4);
+				),
 			),
 		};
 
-		tokens.append_tokens(quote_spanned!(trait_path.span()=>
-			#[automatically_derived]
-			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					unused_mut,
-				)]
-				fn write_to(
-					&self,
-					buf: &mut impl ::xrbk::BufMut,
-				) -> Result<(), ::xrbk::WriteError> {
-					// 11 bytes includes:
-					// - 1 byte to say it's an error
-					// - 1 byte for its code
-					// - 2 bytes for its sequence number
-					// - 4 bytes for its (optional) error data
-					// - 2 bytes for the request's minor opcode
-					// - 1 byte for the request's major opcode
-					let mut size: usize = 11;
-					// Destructure the error struct's fields, if any.
-					let Self #pat = self;
-
-					// A first byte of `0` means that this is an error.
+		let body = |layout: bool| {
+			let error = wire::mark(
+				layout,
+				span,
+				"error",
+				quote_spanned!(span=>
 					<_ as ::xrbk::BufMut>::put_u8(buf, 0);
-					// Error code, uniquely identifying the error.
+				),
+			);
+			let code = wire::mark(
+				layout,
+				span,
+				"code",
+				quote_spanned!(span=>
 					<_ as ::xrbk::BufMut>::put_u8(
 						buf,
 						<Self as xrb::message::Error>::CODE,
 					);
-					// Sequence number.
-					#sequence
-					// An optional 4-byte data field.
-					#error_data
-					// The minor opcode of the request generating the error.
-					#minor_opcode
-					// The major opcode of the request generating the error.
-					#major_opcode
-
-					// Other elements.
-					#writes
-
-					Ok(())
-				}
+				),
+			);
+			let sequence = sequence(layout);
+			let error_data = error_data(layout);
+			let minor_opcode = minor_opcode(layout);
+			let major_opcode = major_opcode(layout);
+			let writes = writes(layout);
+
+			quote_spanned!(span=>
+				// 11 bytes includes:
+				// - 1 byte to say it's an error
+				// - 1 byte for its code
+				// - 2 bytes for its sequence number
+				// - 4 bytes for its (optional) error data
+				// - 2 bytes for the request's minor opcode
+				// - 1 byte for the request's major opcode
+				let mut size: usize = 11;
+				// Destructure the error struct's fields, if any.
+				let Self #pat = self;
+
+				// A first byte of `0` means that this is an error.
+				#error
+				// Error code, uniquely identifying the error.
+				#code
+				// Sequence number.
+				#sequence
+				// An optional 4-byte data field.
+				#error_data
+				// The minor opcode of the request generating the error.
+				#minor_opcode
+				// The major opcode of the request generating the error.
+				#major_opcode
+
+				// Other elements.
+				#writes
+			)
+		};
+
+		let body_tokens = body(false);
+
+		let instrument = trace::instrument_write(
+			span,
+			ident,
+			quote_spanned!(span=>
+				code = <Self as xrb::message::Error>::CODE,
+				sequence = <Self as xrb::message::Error>::sequence(self),
+			),
+		);
+
+		let event = trace::event(span, ident, "write", quote_spanned!(span=> self));
+
+		let attributes = quote_spanned!(span=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				unused_mut,
+			)]
+			#instrument
+		);
+		let write_body = quote_spanned!(span=>
+			#body_tokens
+
+			#event
+
+			Ok(())
+		);
+		let functions = dyn_buffers::write_functions(span, &attributes, &write_body);
+
+		tokens.append_tokens(quote_spanned!(span=>
+			#[automatically_derived]
+			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
+				#functions
 			}
 		));
+
+		tokens.append_tokens(wire::impl_wire_layout(
+			span,
+			ident,
+			&self.generics,
+			where_clause,
+			body(true),
+		));
 	}
 }
 
@@ -501,6 +971,7 @@ impl Enum {
 
 			for variant in &self.variants {
 				let ident = &variant.ident;
+				let parent = format!("{}::{ident}", self.ident);
 
 				let declare_x11_size = {
 					let discrim_type = quote_spanned!(discrim_type.span()=>
@@ -524,7 +995,7 @@ impl Enum {
 
 				let writes = TokenStream2::with_tokens(|tokens| {
 					for element in &variant.content {
-						element.write_tokens(tokens, DefinitionType::Basic);
+						element.write_tokens(tokens, DefinitionType::Basic, &parent);
 
 						// if variant.content.contains_infer() {
 						element.add_x11_size_tokens(tokens);
@@ -549,32 +1020,34 @@ impl Enum {
 			}
 		});
 
+		let attributes = quote_spanned!(trait_path.span()=>
+			#[allow(
+				clippy::items_after_statements,
+				clippy::trivially_copy_pass_by_ref,
+				clippy::needless_borrow,
+				clippy::identity_op,
+				clippy::cast_possible_truncation,
+				clippy::unnecessary_cast,
+				unused_mut,
+			)]
+		);
+		let write_body = quote_spanned!(trait_path.span()=>
+			// Define functions and variables for variants which
+			// have custom discriminant expressions.
+			#discriminants
+
+			match self {
+				#arms
+			}
+
+			Ok(())
+		);
+		let functions = dyn_buffers::write_functions(trait_path.span(), &attributes, &write_body);
+
 		tokens.append_tokens(quote_spanned!(trait_path.span()=>
 			#[automatically_derived]
 			impl #impl_generics ::xrbk::Writable for #ident #type_generics #where_clause {
-				#[allow(
-					clippy::items_after_statements,
-					clippy::trivially_copy_pass_by_ref,
-					clippy::needless_borrow,
-					clippy::identity_op,
-					clippy::cast_possible_truncation,
-					clippy::unnecessary_cast,
-					unused_mut,
-				)]
-				fn write_to(
-					&self,
-					buf: &mut impl ::xrbk::BufMut,
-				) -> Result<(), ::xrbk::WriteError> {
-					// Define functions and variables for variants which
-					// have custom discriminant expressions.
-					#discriminants
-
-					match self {
-						#arms
-					}
-
-					Ok(())
-				}
+				#functions
 			}
 		));
 	}
@@ -0,0 +1,128 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{ext::IdentExt, Ident, Lit, Meta, NestedMeta, Path};
+
+use super::*;
+use crate::{
+	element::{Element, Field, FieldId},
+	TsExt,
+};
+
+impl Request {
+	/// Generates an `x11_compat` constructor which takes a parameter for each
+	/// field, named after the first `#[doc(alias(...))]` of that field.
+	pub fn impl_x11_compat(&self, tokens: &mut TokenStream2, attribute_path: &Path) {
+		let ident = &self.ident;
+
+		let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+		let where_clause = match &self.content {
+			StructlikeContent::Regular { where_clause, .. } => where_clause,
+			StructlikeContent::Tuple { where_clause, .. } => where_clause,
+			StructlikeContent::Unit { where_clause, .. } => where_clause,
+		};
+
+		let mut params = TokenStream2::new();
+		let mut inits = TokenStream2::new();
+
+		for element in &self.content {
+			let Element::Field(field) = element else {
+				continue;
+			};
+
+			let param = compat_name(field);
+			let member = match &field.id {
+				FieldId::Ident(ident) => quote!(#ident),
+				FieldId::Index(index) => quote!(#index),
+			};
+
+			// Optional values are accepted as their raw encodings, where zero
+			// means `None`.
+			if field.present_if_attribute.is_none()
+				&& let Some(r#type) = option_type(&field.r#type)
+			{
+				params.append_tokens(quote!(
+					#param: <#r#type as ::xrbk::Wrap>::Integer,
+				));
+				inits.append_tokens(quote!(
+					#member: ::xrbk::zero_is_none::<#r#type>(#param),
+				));
+			} else {
+				let r#type = &field.r#type;
+
+				params.append_tokens(quote!(#param: #r#type,));
+
+				// Field init shorthand is used where the parameter has the
+				// same name as the field, as clippy's `redundant_field_names`
+				// lint would otherwise be triggered in the calling crate.
+				if matches!(&field.id, FieldId::Ident(ident) if *ident == param) {
+					inits.append_tokens(quote!(#param,));
+				} else {
+					inits.append_tokens(quote!(#member: #param,));
+				}
+			}
+		}
+
+		let doc = format!(
+			"Creates a new `{ident}` from parameters named after the fields of the X11 protocol's \
+			 version of this request.\n\nOptional resource IDs and atoms are given as their raw \
+			 encodings, where `0` means `None`."
+		);
+
+		tokens.append_tokens(quote_spanned!(attribute_path.span()=>
+			impl #impl_generics #ident #type_generics #where_clause {
+				#[doc = #doc]
+				#[deprecated(note = "use the new field names")]
+				#[allow(clippy::too_many_arguments)]
+				#[must_use]
+				pub fn x11_compat(#params) -> Self {
+					Self { #inits }
+				}
+			}
+		));
+	}
+}
+
+/// The name of the `x11_compat` parameter for the given `field`.
+///
+/// This is the first `#[doc(alias(...))]` of the field which is a valid
+/// identifier, or the name of the field if there is none.
+fn compat_name(field: &Field) -> Ident {
+	let alias = field
+		.attributes
+		.iter()
+		.filter(|attribute| attribute.path.is_ident("doc"))
+		.filter_map(|attribute| match attribute.parse_meta() {
+			Ok(Meta::List(list)) => Some(list.nested),
+			_ => None,
+		})
+		.flatten()
+		.flat_map(|nested| match nested {
+			// `#[doc(alias = "name")]`
+			NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("alias") => {
+				vec![name_value.lit]
+			},
+			// `#[doc(alias("name", "other_name"))]`
+			NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("alias") => list
+				.nested
+				.into_iter()
+				.filter_map(|nested| match nested {
+					NestedMeta::Lit(lit) => Some(lit),
+					NestedMeta::Meta(_) => None,
+				})
+				.collect(),
+			_ => vec![],
+		})
+		.find_map(|lit| match lit {
+			Lit::Str(alias) => syn::parse_str::<Ident>(&alias.value()).ok(),
+			_ => None,
+		});
+
+	alias.unwrap_or_else(|| match &field.id {
+		FieldId::Ident(ident) => ident.unraw(),
+		FieldId::Index(index) => format_ident!("_{}", index.index),
+	})
+}
@@ -2,8 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use quote::format_ident;
 use syn::{
 	parse::{discouraged::Speculative, Parse, ParseStream},
+	parse_quote,
 	spanned::Spanned,
 	Attribute,
 	Token,
@@ -11,7 +13,13 @@ use syn::{
 	Visibility,
 };
 
-use crate::{definition::DefinitionType, ParseWithContext, PsExt};
+use crate::{
+	attribute::parsing::listed_traits,
+	definition::DefinitionType,
+	element::Element,
+	ParseWithContext,
+	PsExt,
+};
 
 use super::*;
 
@@ -34,7 +42,7 @@ impl Parse for Definition {
 		let item_attributes = fork.parse::<ParsedItemAttributes>()?;
 		let visibility = fork.parse::<Visibility>()?;
 
-		Ok(if item_attributes.contains_xrbk_derives() {
+		let mut definition = if item_attributes.contains_xrbk_derives() {
 			if fork.peek(Token![struct]) {
 				input.advance_to(fork);
 
@@ -59,14 +67,14 @@ impl Parse for Definition {
 						Some(ident) => ident,
 
 						None => {
-							return Err(
-								input.error("expected `Request`, `Reply`, `Event`, or `Error`")
-							);
+							return Err(input.error(
+								"expected `Request`, `ExtensionRequest`, `Reply`, `Event`, or `Error`",
+							));
 						},
 					};
 
 					match &*message_ident.to_string() {
-						"Request" => Self::Request(input.parse_with((
+						"Request" | "ExtensionRequest" => Self::Request(input.parse_with((
 							item_attributes,
 							visibility,
 							struct_token,
@@ -109,7 +117,8 @@ impl Parse for Definition {
 						_ => {
 							return Err(syn::Error::new(
 								message_path.span(),
-								"expected `Request`, `Reply`, `Event`, or `Error` message type",
+								"expected `Request`, `ExtensionRequest`, `Reply`, `Event`, or `Error` message \
+								 type",
 							))
 						},
 					}
@@ -123,7 +132,114 @@ impl Parse for Definition {
 			}
 		} else {
 			Self::Other(input.parse()?)
-		})
+		};
+
+		definition.reject_default_attributes()?;
+		definition.ignore_sequence()?;
+
+		Ok(definition)
+	}
+}
+
+impl Definition {
+	/// Generates an error if any field has a `#[default]` attribute, unless
+	/// this is a request with a `#[builder]` attribute.
+	fn reject_default_attributes(&self) -> Result<()> {
+		let elements: Vec<&Element> = match self {
+			Self::Request(request) if request.item_attributes.builder.is_some() => return Ok(()),
+
+			Self::Struct(Struct { content, .. })
+			| Self::Request(Request { content, .. })
+			| Self::Reply(Reply { content, .. })
+			| Self::Event(Event { content, .. })
+			| Self::Error(Error { content, .. }) => content.into_iter().collect(),
+
+			Self::Enum(r#enum) => r#enum
+				.variants
+				.iter()
+				.flat_map(|variant| &variant.content)
+				.collect(),
+
+			Self::Other(_) => return Ok(()),
+		};
+
+		for element in elements {
+			if let Element::Field(field) = element
+				&& let Some(attribute) = &field.default_attribute
+			{
+				return Err(syn::Error::new(
+					attribute.span(),
+					"default attributes are only allowed in requests with a #[builder] attribute",
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Ignores the sequence field of a reply, event, or error, if it has one,
+	/// in its derived `PartialEq` and `Hash` implementations.
+	///
+	/// The same message may be sent with different sequence numbers, so two
+	/// messages which differ only in their sequence numbers are equal. A
+	/// `#[derivative(PartialEq = "ignore", Hash = "ignore")]` attribute is
+	/// added to the sequence field for whichever of those traits are derived
+	/// with `derivative`. Deriving them with a normal `#[derive(...)]` attribute
+	/// is an error, because the sequence field could not be ignored.
+	fn ignore_sequence(&mut self) -> Result<()> {
+		let (item_attributes, content) = match self {
+			Self::Reply(Reply {
+				item_attributes,
+				content,
+				..
+			})
+			| Self::Event(Event {
+				item_attributes,
+				content,
+				..
+			})
+			| Self::Error(Error {
+				item_attributes,
+				content,
+				..
+			}) => (&*item_attributes, content),
+
+			_ => return Ok(()),
+		};
+
+		let Some(field) = content.sequence_field_mut() else {
+			return Ok(());
+		};
+
+		for r#trait in ["PartialEq", "Hash"] {
+			if let Some(path) = item_attributes.derive(r#trait) {
+				return Err(syn::Error::new(
+					path.span(),
+					format!(
+						"`{trait}` must be derived with `derivative` for messages with a sequence \
+						 field, so that the sequence field can be ignored"
+					),
+				));
+			}
+		}
+
+		let ignored: Vec<Ident> = ["PartialEq", "Hash"]
+			.into_iter()
+			.filter(|r#trait| item_attributes.derives_with_derivative(r#trait))
+			// Don't ignore traits which are already configured for the field.
+			.filter(|r#trait| {
+				!listed_traits(&field.attributes, "derivative").any(|path| path.is_ident(r#trait))
+			})
+			.map(|r#trait| format_ident!("{}", r#trait))
+			.collect();
+
+		if !ignored.is_empty() {
+			field
+				.attributes
+				.push(parse_quote!(#[derivative(#(#ignored = "ignore"),*)]));
+		}
+
+		Ok(())
 	}
 }
 
@@ -151,61 +267,96 @@ impl ParseWithContext for Request {
 
 		let paren_token = parenthesized!(content in input);
 
-		let major_opcode = content.parse()?;
+		let mut major_opcode = None;
 		let mut comma1 = None;
 		let mut minor_opcode = None;
 		let mut comma2 = None;
 		let mut other_errors = None;
 		let mut comma3 = None;
 
-		if content.peek(Token![,]) {
-			comma1 = content.parse()?;
-			let fork = &content.fork();
+		if request_token.is_ident("ExtensionRequest") {
+			// Extension requests have no major opcode: it is assigned to their
+			// extension by the X server.
+			minor_opcode = Some(content.parse::<Expr>()?);
 
-			if let Ok(r#type) = fork.parse::<Type>() {
-				if fork.peek(Token![,]) {
-					let comma = fork.parse()?;
+			if content.peek(Token![,]) {
+				comma2 = Some(content.parse()?);
 
-					if fork.is_empty() {
-						content.advance_to(fork);
-						other_errors = Some(r#type);
-						comma3 = Some(comma);
-					} else {
-						minor_opcode = Some(content.parse::<Expr>()?);
+				if !content.is_empty() {
+					other_errors = Some(content.parse::<Type>()?);
 
-						if content.peek(Token![,]) {
-							comma2 = Some(content.parse()?);
+					if content.peek(Token![,]) {
+						comma3 = Some(content.parse()?);
+					}
+				}
+			}
+		} else {
+			major_opcode = Some(content.parse()?);
+
+			if content.peek(Token![,]) {
+				comma1 = content.parse()?;
+				let fork = &content.fork();
 
-							if !content.is_empty() {
-								other_errors = Some(content.parse::<Type>()?);
+				if let Ok(r#type) = fork.parse::<Type>() {
+					if fork.peek(Token![,]) {
+						let comma = fork.parse()?;
 
-								if content.peek(Token![,]) {
-									comma3 = Some(content.parse()?);
+						if fork.is_empty() {
+							content.advance_to(fork);
+							other_errors = Some(r#type);
+							comma3 = Some(comma);
+						} else {
+							minor_opcode = Some(content.parse::<Expr>()?);
+
+							if content.peek(Token![,]) {
+								comma2 = Some(content.parse()?);
+
+								if !content.is_empty() {
+									other_errors = Some(content.parse::<Type>()?);
+
+									if content.peek(Token![,]) {
+										comma3 = Some(content.parse()?);
+									}
 								}
 							}
 						}
+					} else {
+						content.advance_to(fork);
+						other_errors = Some(r#type);
 					}
 				} else {
-					content.advance_to(fork);
-					other_errors = Some(r#type);
-				}
-			} else {
-				minor_opcode = Some(content.parse::<Expr>()?);
+					minor_opcode = Some(content.parse::<Expr>()?);
 
-				if content.peek(Token![,]) {
-					comma2 = Some(content.parse()?);
+					if content.peek(Token![,]) {
+						comma2 = Some(content.parse()?);
 
-					if !content.is_empty() {
-						other_errors = Some(content.parse::<Type>()?);
+						if !content.is_empty() {
+							other_errors = Some(content.parse::<Type>()?);
 
-						if content.peek(Token![,]) {
-							comma3 = Some(content.parse()?);
+							if content.peek(Token![,]) {
+								comma3 = Some(content.parse()?);
+							}
 						}
 					}
 				}
 			}
 		}
 
+		let reply = if input.peek(Token![->]) {
+			Some((input.parse()?, input.parse()?))
+		} else {
+			None
+		};
+		let content: StructlikeContent = input.parse_with(DefinitionType::Request)?;
+
+		if minor_opcode.is_some() && let Some(metabyte) = content.metabyte_element() {
+			return Err(syn::Error::new(
+				metabyte.metabyte_attribute().span(),
+				"metabyte elements are not allowed for requests with a minor opcode: the minor \
+				 opcode is written in the metabyte position",
+			));
+		}
+
 		Ok(Self {
 			item_attributes,
 
@@ -228,12 +379,8 @@ impl ParseWithContext for Request {
 			other_errors,
 			comma3,
 
-			reply: if input.peek(Token![->]) {
-				Some((input.parse()?, input.parse()?))
-			} else {
-				None
-			},
-			content: input.parse_with(DefinitionType::Request)?,
+			reply,
+			content,
 		})
 	}
 }
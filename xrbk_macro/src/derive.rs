@@ -4,7 +4,17 @@
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote, ToTokens};
-use syn::{punctuated::Pair, Attribute, Data, Fields, FieldsNamed, FieldsUnnamed, Index, Type};
+use syn::{
+	punctuated::Pair,
+	Attribute,
+	Data,
+	Fields,
+	FieldsNamed,
+	FieldsUnnamed,
+	Ident,
+	Index,
+	Type,
+};
 
 use crate::TsExt;
 
@@ -272,7 +282,7 @@ pub fn derive_writes(attributes: &[Attribute], data: &Data) -> TokenStream2 {
 	}
 }
 
-pub fn derive_reads(attributes: &[Attribute], data: &Data) -> TokenStream2 {
+pub fn derive_reads(ident: &Ident, attributes: &[Attribute], data: &Data) -> TokenStream2 {
 	for attribute in attributes {
 		if attribute.path.is_ident("no_discrim") {
 			panic!("found #[no_discrim]: cannot derive Readable without discriminants");
@@ -364,12 +374,13 @@ pub fn derive_reads(attributes: &[Attribute], data: &Data) -> TokenStream2 {
 			});
 
 			quote!(
-				match buf.get_u8() {
+				match <u8 as ::xrbk::Readable>::read_from(buf)? {
 					#(#arms)*
 
-					other_discrim => Err(
-						::xrbk::ReadError::UnrecognizedDiscriminant(other_discrim as usize),
-					),
+					other_discrim => Err(::xrbk::ReadError::UnrecognizedDiscriminant {
+						type_name: ::core::stringify!(#ident),
+						discriminant: other_discrim as usize,
+					}),
 				}
 			)
 		},
@@ -477,7 +488,7 @@ pub fn derive_x11_sizes(attributes: &[Attribute], data: &Data) -> TokenStream2 {
 	}
 }
 
-pub fn derive_constant_x11_sizes(_attributes: &[Attribute], data: &Data) -> TokenStream2 {
+pub fn derive_constant_x11_sizes(attributes: &[Attribute], data: &Data) -> TokenStream2 {
 	fn derive_for_fields(fields: &Fields) -> TokenStream2 {
 		TokenStream2::with_tokens(|tokens| match fields {
 			Fields::Named(FieldsNamed { named: fields, .. })
@@ -512,7 +523,44 @@ pub fn derive_constant_x11_sizes(_attributes: &[Attribute], data: &Data) -> Toke
 			)
 		},
 
-		// TODO: derive for enums if all variants are the same constant size
-		Data::Enum(_) | Data::Union(_) => unimplemented!(),
+		Data::Enum(r#enum) => {
+			let no_discrim = attributes
+				.iter()
+				.any(|attribute| attribute.path.is_ident("no_discrim"));
+
+			let discrim_size: usize = if no_discrim { 0 } else { 1 };
+
+			let variant_sizes = r#enum.variants.iter().map(|variant| {
+				let sizes = derive_for_fields(&variant.fields);
+
+				quote!({
+					let mut size = 0;
+
+					#sizes
+
+					size
+				},)
+			});
+
+			quote!(
+				let sizes: &[usize] = &[#(#variant_sizes)*];
+
+				// Every variant must be the same size for the enum to have a
+				// constant size.
+				let mut i = 0;
+				while i < sizes.len() {
+					assert!(
+						sizes[i] == sizes[0],
+						"every variant must have the same size to implement ConstantX11Size",
+					);
+
+					i += 1;
+				}
+
+				#discrim_size + if sizes.is_empty() { 0 } else { sizes[0] }
+			)
+		},
+
+		Data::Union(_) => unimplemented!(),
 	}
 }
@@ -8,6 +8,7 @@ mod parsing;
 
 use quote::format_ident;
 use syn::{
+	ext::IdentExt,
 	punctuated::Punctuated,
 	token,
 	Attribute,
@@ -21,15 +22,19 @@ use syn::{
 
 use crate::{
 	attribute::{
+		ArbitraryAttribute,
 		ContextAttribute,
+		DefaultAttribute,
+		DiscriminatedByAttribute,
 		ErrorDataAttribute,
 		HideAttribute,
 		MajorOpcodeAttribute,
 		MetabyteAttribute,
 		MinorOpcodeAttribute,
+		PresentIfAttribute,
 		SequenceAttribute,
 	},
-	source::Source,
+	source::{Source, SourceRequestArg},
 };
 
 /// > **<sup>Syntax</sup>**\
@@ -286,6 +291,22 @@ impl StructlikeContent {
 		}
 	}
 
+	/// A mutable reference to the [`Field`] contained within this
+	/// `StructlikeContent` which has a [`SequenceAttribute`], if there is one.
+	pub fn sequence_field_mut(&mut self) -> Option<&mut Field> {
+		let elements = match self {
+			Self::Regular { content, .. } => &mut content.elements,
+			Self::Tuple { content, .. } => &mut content.elements,
+
+			Self::Unit { .. } => return None,
+		};
+
+		match &mut elements.sequence_element {
+			Some(Element::Field(field)) => Some(field),
+			_ => None,
+		}
+	}
+
 	/// The [`Element`] contained within this `StructlikeContent` which has a
 	/// [`MinorOpcodeAttribute`], if there is one.
 	pub const fn minor_opcode_element(&self) -> &Option<Element> {
@@ -318,6 +339,18 @@ impl StructlikeContent {
 			Self::Unit { .. } => &None,
 		}
 	}
+
+	/// The first [`SourceRequestArg`] used in a [`ContextAttribute`] within
+	/// this `StructlikeContent`, if there is one.
+	///
+	/// If there is, the `Reply` can only be read with its request.
+	pub fn request_arg(&self) -> Option<&SourceRequestArg> {
+		self.into_iter().find_map(|element| {
+			let (args, _) = element.context_attribute()?.context.source().args.as_ref()?;
+
+			args.request_arg.as_ref()
+		})
+	}
 }
 
 enum ElementsItem {
@@ -438,6 +471,28 @@ impl Element {
 		}
 	}
 
+	/// This `Element`'s [`MetabyteAttribute`], if it has one.
+	pub const fn metabyte_attribute(&self) -> Option<&MetabyteAttribute> {
+		match self {
+			Self::Field(field) => field.metabyte_attribute.as_ref(),
+
+			Self::Let(r#let) => r#let.metabyte_attribute.as_ref(),
+
+			Self::SingleUnused(unused) => unused.attribute.as_ref(),
+			Self::ArrayUnused(_) => None,
+		}
+	}
+
+	/// This `Element`'s [`ContextAttribute`], if it has one.
+	pub const fn context_attribute(&self) -> Option<&ContextAttribute> {
+		match self {
+			Self::Field(field) => field.context_attribute.as_ref(),
+			Self::Let(r#let) => r#let.context_attribute.as_ref(),
+
+			Self::SingleUnused(_) | Self::ArrayUnused(_) => None,
+		}
+	}
+
 	/// Whether this `Element` has a [`SequenceAttribute`].
 	pub const fn is_sequence(&self) -> bool {
 		if let Element::Field(field) = self {
@@ -483,6 +538,23 @@ impl Element {
 			false
 		}
 	}
+
+	/// The name given to this `Element` in a generated `WireLayout`
+	/// implementation.
+	///
+	/// This is the field's or `let` element's name, without any `r#` prefix,
+	/// or `_` for unused bytes.
+	pub fn wire_name(&self) -> String {
+		match self {
+			Self::Field(field) => match &field.id {
+				FieldId::Ident(ident) => ident.unraw().to_string(),
+				FieldId::Index(index) => index.index.to_string(),
+			},
+			Self::Let(r#let) => r#let.ident.unraw().to_string(),
+
+			Self::SingleUnused(_) | Self::ArrayUnused(_) => "_".to_owned(),
+		}
+	}
 }
 
 // }}} Field {{{
@@ -557,6 +629,26 @@ pub struct Field {
 	///
 	/// See [`HideAttribute`] for more information.
 	pub hide_attribute: Option<HideAttribute>,
+	/// An optional [`PresentIfAttribute`] which indicates that this optional
+	/// field is only present if another boolean field is `true`.
+	///
+	/// See [`PresentIfAttribute`] for more information.
+	pub present_if_attribute: Option<PresentIfAttribute>,
+	/// An optional [`DiscriminatedByAttribute`] which indicates that this
+	/// field's variant is selected by an earlier field.
+	///
+	/// See [`DiscriminatedByAttribute`] for more information.
+	pub discriminated_by_attribute: Option<DiscriminatedByAttribute>,
+	/// An optional [`DefaultAttribute`] which gives this field a default value
+	/// in the builder generated by a `#[builder]` item attribute.
+	///
+	/// See [`DefaultAttribute`] for more information.
+	pub default_attribute: Option<DefaultAttribute>,
+	/// An optional [`ArbitraryAttribute`] which gives the function with which
+	/// this field is generated in a generated `Arbitrary` implementation.
+	///
+	/// See [`ArbitraryAttribute`] for more information.
+	pub arbitrary_attribute: Option<ArbitraryAttribute>,
 
 	/// The visibility of the `Field`.
 	pub visibility: Visibility,
@@ -3,21 +3,104 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use quote::quote_spanned;
-use syn::spanned::Spanned;
+use syn::{spanned::Spanned, Generics, Lifetime};
 
 use crate::{definition::DefinitionType, TsExt};
 
 use super::*;
 
+/// How an [`Element`] is read.
+#[derive(Clone, Copy)]
+enum ReadMode<'a> {
+	/// Read from a `buf` implementing `Buf` with `Readable` and
+	/// `ReadableWithContext`.
+	Owned,
+	/// Read from a `buf` of type `&mut &'a [u8]` with `ReadableRef` and
+	/// `ReadableRefWithContext`, borrowing from those bytes where possible.
+	///
+	/// The lifetime parameters of the `generics` are given to context
+	/// functions, so that they can name the `lifetime` of the bytes.
+	Borrowed {
+		lifetime: &'a Lifetime,
+		generics: &'a Generics,
+	},
+}
+
+impl ReadMode<'_> {
+	/// Expands the generic parameters of a context function.
+	fn context_generics(self) -> TokenStream2 {
+		match self {
+			Self::Owned => TokenStream2::new(),
+
+			Self::Borrowed { generics, .. } => {
+				let lifetimes = generics.lifetimes();
+
+				quote!(<#(#lifetimes),*>)
+			},
+		}
+	}
+
+	/// Expands the type of the context with which a value of the given `type`
+	/// is read.
+	fn context_type(self, r#type: &Type) -> TokenStream2 {
+		match self {
+			Self::Owned => quote_spanned!(r#type.span()=>
+				<#r#type as ::xrbk::ReadableWithContext>::Context
+			),
+
+			Self::Borrowed { lifetime, .. } => quote_spanned!(r#type.span()=>
+				<#r#type as ::xrbk::ReadableRefWithContext<#lifetime>>::Context
+			),
+		}
+	}
+
+	/// Expands a call reading a value of the given `type` from `buf` with the
+	/// given `context`.
+	fn read_with(self, r#type: &Type, context: TokenStream2) -> TokenStream2 {
+		match self {
+			Self::Owned => quote_spanned!(r#type.span()=>
+				<#r#type as ::xrbk::ReadableWithContext>::read_with(buf, #context)
+			),
+
+			Self::Borrowed { .. } => quote_spanned!(r#type.span()=>
+				::xrbk::read_ref_with::<#r#type>(buf, #context)
+			),
+		}
+	}
+
+	/// Expands a call reading a value of the given `type` from `buf`.
+	fn read_from(self, r#type: &Type) -> TokenStream2 {
+		match self {
+			Self::Owned => quote_spanned!(r#type.span()=>
+				<#r#type as ::xrbk::Readable>::read_from(buf)
+			),
+
+			Self::Borrowed { .. } => quote_spanned!(r#type.span()=>
+				::xrbk::read_ref::<#r#type>(buf)
+			),
+		}
+	}
+}
+
 impl Element {
-	pub fn write_tokens(&self, tokens: &mut TokenStream2, definition_type: DefinitionType) {
+	/// Expands the tokens to write this `Element`.
+	///
+	/// Errors generated while writing a field or `let` element are wrapped
+	/// in [`WriteError::InField`] with the given `parent` type name.
+	///
+	/// [`WriteError::InField`]: https://docs.rs/xrbk/latest/xrbk/enum.WriteError.html
+	pub fn write_tokens(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType, parent: &str,
+	) {
+		self.metabyte_size_tokens(tokens);
+
 		match self {
 			Self::Field(field) => {
 				if !field.is_ignoring_trait("Writable") {
-					field.write_tokens(tokens)
+					field.write_tokens(tokens, parent, &self.wire_name())
 				}
 			},
-			Self::Let(r#let) => r#let.write_tokens(tokens),
+			Self::Let(r#let) => r#let.write_tokens(tokens, parent, &self.wire_name()),
 
 			Self::SingleUnused(unused) => unused.write_tokens(tokens),
 			Self::ArrayUnused(unused) => unused.write_tokens(tokens, definition_type),
@@ -38,17 +121,101 @@ impl Element {
 		}
 	}
 
-	pub fn read_tokens(&self, tokens: &mut TokenStream2, definition_type: DefinitionType) {
+	pub fn constant_x11_size_tokens(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType,
+	) {
 		match self {
 			Self::Field(field) => {
-				if !field.is_ignoring_trait("Readable") || field.context_attribute.is_some() {
-					field.read_tokens(tokens)
+				if !field.is_ignoring_trait("X11Size") {
+					field.constant_x11_size_tokens(tokens)
 				}
 			},
-			Self::Let(r#let) => r#let.read_tokens(tokens),
+			Self::Let(r#let) => r#let.constant_x11_size_tokens(tokens),
+
+			Self::SingleUnused(unused) => unused.add_x11_size_tokens(tokens),
+			Self::ArrayUnused(unused) => unused.constant_x11_size_tokens(tokens, definition_type),
+		}
+	}
+
+	/// Expands the tokens to read this `Element`.
+	///
+	/// Errors generated while reading a field or `let` element are wrapped
+	/// in [`ReadError::InField`] with the given `parent` type name.
+	///
+	/// [`ReadError::InField`]: https://docs.rs/xrbk/latest/xrbk/enum.ReadError.html
+	pub fn read_tokens(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType, parent: &str,
+	) {
+		self.read_tokens_in(tokens, definition_type, parent, ReadMode::Owned);
+	}
+
+	/// Expands the tokens to read this `Element` from a `buf` of type
+	/// `&mut &'a [u8]`, borrowing from those bytes where possible.
+	///
+	/// Fields and `let` elements are read with `ReadableRef`, or with
+	/// `ReadableRefWithContext` if they have a context attribute. `'a` is the
+	/// given `lifetime`, which must be one of the lifetime parameters of the
+	/// given `generics`.
+	pub fn read_ref_tokens(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType, parent: &str,
+		lifetime: &Lifetime, generics: &Generics,
+	) {
+		self.read_tokens_in(
+			tokens,
+			definition_type,
+			parent,
+			ReadMode::Borrowed { lifetime, generics },
+		);
+	}
+
+	fn read_tokens_in(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType, parent: &str,
+		mode: ReadMode,
+	) {
+		self.metabyte_size_tokens(tokens);
+
+		match self {
+			Self::Field(field) => {
+				if !field.is_ignoring_trait("Readable")
+					|| field.context_attribute.is_some()
+					|| field.discriminated_by_attribute.is_some()
+				{
+					field.read_tokens_in(tokens, parent, &self.wire_name(), mode)
+				}
+			},
+			Self::Let(r#let) => r#let.read_tokens_in(tokens, parent, &self.wire_name(), mode),
 
 			Self::SingleUnused(unused) => unused.read_tokens(tokens),
-			Self::ArrayUnused(unused) => unused.read_tokens(tokens, definition_type),
+			Self::ArrayUnused(unused) => unused.read_tokens_in(tokens, definition_type, mode),
+		}
+	}
+
+	/// Expands a compile-time assertion that this `Element`'s type is exactly
+	/// one byte, if it is a metabyte field or `let` element.
+	///
+	/// The assertion is an unnamed constant, rather than an inline `const`
+	/// block, so that it is evaluated even if the generated function is never
+	/// used. It is spanned to the [`MetabyteAttribute`], so that the error
+	/// points to it.
+	fn metabyte_size_tokens(&self, tokens: &mut TokenStream2) {
+		let (attribute, r#type) = match self {
+			Self::Field(field) => (&field.metabyte_attribute, &field.r#type),
+			Self::Let(r#let) => (&r#let.metabyte_attribute, &r#let.r#type),
+
+			Self::SingleUnused(_) | Self::ArrayUnused(_) => return,
+		};
+
+		if let Some(attribute) = attribute {
+			tokens.append_tokens(quote_spanned!(attribute.span()=>
+				const _: () = assert!(
+					<#r#type as ::xrbk::ConstantX11Size>::X11_SIZE == 1,
+					concat!(
+						"metabyte elements must be exactly 1 byte, but `",
+						stringify!(#r#type),
+						"` is not",
+					),
+				);
+			));
 		}
 	}
 
@@ -70,17 +237,52 @@ impl Element {
 // Field {{{
 
 impl Field {
-	pub fn write_tokens(&self, tokens: &mut TokenStream2) {
+	pub fn write_tokens(&self, tokens: &mut TokenStream2, parent: &str, name: &str) {
 		let formatted = &self.formatted;
 		let r#type = &self.r#type;
 
+		if self.present_if_attribute.is_some() {
+			tokens.append_tokens({
+				let r#type = quote_spanned!(r#type.span()=>
+					<#r#type as ::xrbk::PresentIf>
+				);
+
+				quote_spanned!(self.span()=>
+					#r#type::write_present_if(&#formatted, buf)
+						.map_err(|error| error.in_field(#parent, #name))?;
+				)
+			});
+
+			return;
+		}
+
+		if let Some(DiscriminatedByAttribute { discriminant, .. }) =
+			&self.discriminated_by_attribute
+		{
+			let discriminant = &discriminant.formatted;
+
+			tokens.append_tokens({
+				let r#type = quote_spanned!(r#type.span()=>
+					<#r#type as ::xrbk::Discriminated>
+				);
+
+				quote_spanned!(self.span()=>
+					#r#type::write_discriminated(&#formatted, buf, &#discriminant)
+						.map_err(|error| error.in_field(#parent, #name))?;
+				)
+			});
+
+			return;
+		}
+
 		tokens.append_tokens({
 			let r#type = quote_spanned!(r#type.span()=>
 				<#r#type as ::xrbk::Writable>
 			);
 
 			quote_spanned!(self.span()=>
-				#r#type::write_to(&#formatted, buf)?;
+				#r#type::write_to(&#formatted, buf)
+					.map_err(|error| error.in_field(#parent, #name))?;
 			)
 		});
 	}
@@ -89,49 +291,81 @@ impl Field {
 		self.add_x11_size_tokens(tokens);
 	}
 
-	pub fn read_tokens(&self, tokens: &mut TokenStream2) {
+	pub fn read_tokens(&self, tokens: &mut TokenStream2, parent: &str, name: &str) {
+		self.read_tokens_in(tokens, parent, name, ReadMode::Owned);
+	}
+
+	fn read_tokens_in(&self, tokens: &mut TokenStream2, parent: &str, name: &str, mode: ReadMode) {
 		let formatted = &self.formatted;
 		let r#type = &self.r#type;
 
+		// Fields which are only present if a condition is met, or whose
+		// discriminant is another element, are always read with `Readable`
+		// and `ReadableWithContext`.
+		if let Some(PresentIfAttribute { condition, .. }) = &self.present_if_attribute {
+			let condition = &condition.formatted;
+
+			tokens.append_tokens({
+				let r#type = quote_spanned!(r#type.span()=>
+					<#r#type as ::xrbk::PresentIf>
+				);
+
+				quote_spanned!(self.span()=>
+					let #formatted = #r#type::read_present_if(buf, #condition)
+						.map_err(|error| error.in_field(#parent, #name))?;
+				)
+			});
+
+			return;
+		}
+
+		// The discriminant is the context with which the field is read.
+		if let Some(DiscriminatedByAttribute { discriminant, .. }) =
+			&self.discriminated_by_attribute
+		{
+			let discriminant = &discriminant.formatted;
+
+			tokens.append_tokens({
+				let r#type = quote_spanned!(r#type.span()=>
+					<#r#type as ::xrbk::ReadableWithContext>
+				);
+
+				quote_spanned!(self.span()=>
+					let #formatted = #r#type::read_with(buf, &#discriminant)
+						.map_err(|error| error.in_field(#parent, #name))?;
+				)
+			});
+
+			return;
+		}
+
 		match &self.context_attribute {
 			Some(ContextAttribute { context, .. }) => {
-				context.source().function_to_tokens(
+				context.source().generic_function_to_tokens(
 					tokens,
 					None,
 					formatted,
-					quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::ReadableWithContext>::Context
-					),
+					mode.context_generics(),
+					mode.context_type(r#type),
 				);
 
 				let function_call = TokenStream2::with_tokens(|tokens| {
 					context.source().call_to_tokens(tokens, formatted);
 				});
 
-				tokens.append_tokens({
-					let r#type = quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::ReadableWithContext>
-					);
+				let read = mode.read_with(r#type, quote!(&#function_call));
 
-					quote_spanned!(self.span()=>
-						let #formatted = #r#type::read_with(
-							buf,
-							&#function_call,
-						)?;
-					)
-				});
+				tokens.append_tokens(quote_spanned!(self.span()=>
+					let #formatted = #read.map_err(|error| error.in_field(#parent, #name))?;
+				));
 			},
 
 			None => {
-				tokens.append_tokens({
-					let r#type = quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::Readable>
-					);
+				let read = mode.read_from(r#type);
 
-					quote_spanned!(self.span()=>
-						let #formatted = #r#type::read_from(buf)?;
-					)
-				});
+				tokens.append_tokens(quote_spanned!(self.span()=>
+					let #formatted = #read.map_err(|error| error.in_field(#parent, #name))?;
+				));
 			},
 		}
 	}
@@ -146,12 +380,22 @@ impl Field {
 			)
 		});
 	}
+
+	pub fn constant_x11_size_tokens(&self, tokens: &mut TokenStream2) {
+		let r#type = &self.r#type;
+
+		tokens.append_tokens({
+			quote_spanned!(self.span()=>
+				size += <#r#type as ::xrbk::ConstantX11Size>::X11_SIZE;
+			)
+		});
+	}
 }
 
 // }}} Let {{{
 
 impl Let {
-	pub fn write_tokens(&self, tokens: &mut TokenStream2) {
+	pub fn write_tokens(&self, tokens: &mut TokenStream2, parent: &str, name: &str) {
 		let formatted = &self.formatted;
 		let r#type = &self.r#type;
 
@@ -163,7 +407,8 @@ impl Let {
 			);
 
 			quote_spanned!(self.span()=>
-				#r#type::write_to(&#formatted, buf)?;
+				#r#type::write_to(&#formatted, buf)
+					.map_err(|error| error.in_field(#parent, #name))?;
 			)
 		});
 	}
@@ -192,49 +437,37 @@ impl Let {
 		));
 	}
 
-	pub fn read_tokens(&self, tokens: &mut TokenStream2) {
+	fn read_tokens_in(&self, tokens: &mut TokenStream2, parent: &str, name: &str, mode: ReadMode) {
 		let formatted = &self.formatted;
 		let r#type = &self.r#type;
 
 		match &self.context_attribute {
 			Some(ContextAttribute { context, .. }) => {
-				context.source().function_to_tokens(
+				context.source().generic_function_to_tokens(
 					tokens,
 					None,
 					formatted,
-					quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::ReadableWithContext>::Context
-					),
+					mode.context_generics(),
+					mode.context_type(r#type),
 				);
 
 				let function_call = TokenStream2::with_tokens(|tokens| {
 					context.source().call_to_tokens(tokens, formatted);
 				});
 
-				tokens.append_tokens({
-					let r#type = quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::ReadableWithContext>
-					);
+				let read = mode.read_with(r#type, function_call);
 
-					quote_spanned!(self.span()=>
-						let #formatted = #r#type::read_with(
-							buf,
-							#function_call,
-						)?;
-					)
-				});
+				tokens.append_tokens(quote_spanned!(self.span()=>
+					let #formatted = #read.map_err(|error| error.in_field(#parent, #name))?;
+				));
 			},
 
 			None => {
-				tokens.append_tokens({
-					let r#type = quote_spanned!(r#type.span()=>
-						<#r#type as ::xrbk::Readable>
-					);
+				let read = mode.read_from(r#type);
 
-					quote_spanned!(self.span()=>
-						let #formatted = #r#type::read_from(buf)?;
-					)
-				});
+				tokens.append_tokens(quote_spanned!(self.span()=>
+					let #formatted = #read.map_err(|error| error.in_field(#parent, #name))?;
+				));
 			},
 		}
 	}
@@ -249,6 +482,16 @@ impl Let {
 			)
 		});
 	}
+
+	pub fn constant_x11_size_tokens(&self, tokens: &mut TokenStream2) {
+		let r#type = &self.r#type;
+
+		tokens.append_tokens({
+			quote_spanned!(self.span()=>
+				size += <#r#type as ::xrbk::ConstantX11Size>::X11_SIZE;
+			)
+		});
+	}
 }
 
 // }}} Single unused byte {{{
@@ -269,7 +512,7 @@ impl SingleUnused {
 	pub fn read_tokens(&self, tokens: &mut TokenStream2) {
 		tokens.append_tokens({
 			quote_spanned!(self.span()=>
-				<_ as ::xrbk::Buf>::advance(buf, 1);
+				::xrbk::skip(buf, 1)?;
 			)
 		});
 	}
@@ -287,6 +530,12 @@ impl SingleUnused {
 
 impl ArrayUnused {
 	fn r#impl(&self, tokens: &mut TokenStream2, definition_type: DefinitionType) {
+		self.impl_in(tokens, definition_type, ReadMode::Owned);
+	}
+
+	/// Expands the tokens to evaluate the number of unused bytes, giving the
+	/// `mode`'s generic parameters to its source's function.
+	fn impl_in(&self, tokens: &mut TokenStream2, definition_type: DefinitionType, mode: ReadMode) {
 		let formatted = &self.formatted;
 
 		match &self.content {
@@ -311,7 +560,13 @@ impl ArrayUnused {
 			},
 
 			UnusedContent::Source(source) => {
-				source.function_to_tokens(tokens, Some(&self.attributes), formatted, quote!(usize));
+				source.generic_function_to_tokens(
+					tokens,
+					Some(&self.attributes),
+					formatted,
+					mode.context_generics(),
+					quote!(usize),
+				);
 
 				let call =
 					TokenStream2::with_tokens(|tokens| source.call_to_tokens(tokens, formatted));
@@ -342,14 +597,53 @@ impl ArrayUnused {
 		self.add_x11_size_tokens(tokens);
 	}
 
-	pub fn read_tokens(&self, tokens: &mut TokenStream2, definition_type: DefinitionType) {
+	pub fn constant_x11_size_tokens(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType,
+	) {
 		let formatted = &self.formatted;
 
-		self.r#impl(tokens, definition_type);
+		match &self.content {
+			UnusedContent::Infer { .. } => self.r#impl(tokens, definition_type),
+
+			// A source without arguments can be evaluated in a `const` context,
+			// so long as its expression can.
+			UnusedContent::Source(source) if source.args.is_none() => {
+				let expr = &source.expr;
+
+				tokens.append_tokens({
+					quote_spanned!(self.span()=>
+						let #formatted: usize = #expr;
+					)
+				});
+			},
+
+			UnusedContent::Source(_) => {
+				tokens.append_tokens(
+					syn::Error::new(
+						self.span(),
+						"ConstantX11Size cannot be derived when the number of unused bytes \
+						 depends on other elements",
+					)
+					.to_compile_error(),
+				);
+
+				return;
+			},
+		}
+
+		self.add_x11_size_tokens(tokens);
+	}
+
+	fn read_tokens_in(
+		&self, tokens: &mut TokenStream2, definition_type: DefinitionType, mode: ReadMode,
+	) {
+		let formatted = &self.formatted;
+
+		self.impl_in(tokens, definition_type, mode);
 
 		tokens.append_tokens({
 			quote_spanned!(self.span()=>
-				<_ as ::xrbk::Buf>::advance(buf, #formatted);
+				::xrbk::skip(buf, #formatted)?;
 			)
 		})
 	}
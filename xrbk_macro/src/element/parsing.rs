@@ -22,6 +22,21 @@ use crate::{
 	PsExt,
 };
 
+/// Returns an error if the given `source` uses a `self::request` argument.
+///
+/// The request is only available while reading a reply, so it may only be
+/// used in context attributes.
+fn reject_request_arg(source: &Source) -> Result<()> {
+	if let Some((SourceArgs { request_arg: Some(request_arg), .. }, _)) = &source.args {
+		return Err(syn::Error::new(
+			request_arg.span(),
+			"`self::request` may only be used in context attributes",
+		));
+	}
+
+	Ok(())
+}
+
 impl ParseWithContext for RegularContent {
 	type Context<'a> = DefinitionType;
 
@@ -174,24 +189,44 @@ impl ParseWithContext for Elements {
 				_ => (),
 			}
 
-			if element.is_metabyte() {
+			if let Some(attribute) = element.metabyte_attribute() {
 				if metabyte_element.is_some() {
 					return Err(syn::Error::new(
-						element.span(),
-						"no more than one metabyte element is allowed per message",
+						attribute.span(),
+						"no more than one metabyte element is allowed per message: the metabyte \
+						 position is a single byte",
+					));
+				}
+
+				if let Element::Field(field) = &element && let Some(sequence) = &field.sequence_attribute {
+					return Err(syn::Error::new(
+						sequence.span(),
+						"a sequence field cannot also be a metabyte element: the sequence number \
+						 is two bytes following the metabyte position",
 					));
 				}
 
 				metabyte_element = Some(element);
 				elements.push_value(ElementsItem::Metabyte);
-			} else if let Element::Field(field) = &element && field.is_sequence() {
+			} else if let Element::Field(field) = &element && let Some(attribute) = &field.sequence_attribute {
 				if sequence_element.is_some() {
 					return Err(syn::Error::new(
-						field.span(),
+						attribute.span(),
 						"no more than one sequence field is allowed per message",
 					));
 				}
 
+				if !matches!(
+					&field.r#type,
+					Type::Path(path) if path.qself.is_none() && path.path.is_ident("u16")
+				) {
+					return Err(syn::Error::new(
+						field.r#type.span(),
+						"sequence fields must be of type `u16`: sequence numbers are always two \
+						 bytes",
+					));
+				}
+
 				sequence_element = Some(element);
 				elements.push_value(ElementsItem::Sequence);
 			} else if let Element::Field(field) = &element && field.is_minor_opcode() {
@@ -279,11 +314,21 @@ impl ParseWithContext for Elements {
 			}
 		});
 
+		if let (DefinitionType::Basic | DefinitionType::Error, Some(metabyte)) =
+			(&definition_type, &metabyte_element)
+		{
+			return Err(syn::Error::new(
+				metabyte.metabyte_attribute().span(),
+				"metabyte elements are only allowed for requests, replies, and events",
+			));
+		}
+
 		match (&definition_type, &sequence_element) {
-			(DefinitionType::Basic | DefinitionType::Request, Some(sequence)) => {
+			(DefinitionType::Basic | DefinitionType::Request, Some(Element::Field(sequence))) => {
 				return Err(syn::Error::new(
-					sequence.span(),
-					"sequence fields are only allowed for replies, events, and errors",
+					sequence.sequence_attribute.span(),
+					"sequence fields are only allowed for replies, events, and errors: requests \
+					 are not sent with a sequence number",
 				));
 			},
 
@@ -438,6 +483,10 @@ impl ParseWithContext for SingleUnused {
 			major_opcode_attribute,
 			error_data_attribute,
 			hide_attribute,
+			present_if_attribute,
+			discriminated_by_attribute,
+			default_attribute,
+			arbitrary_attribute,
 		}: ParsedAttributes,
 	) -> Result<Self> {
 		if let Some(attribute) = attributes.first() {
@@ -489,6 +538,34 @@ impl ParseWithContext for SingleUnused {
 			));
 		}
 
+		if let Some(attribute) = present_if_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"present if attributes are not allowed for singular unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = discriminated_by_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"discriminated by attributes are not allowed for singular unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = default_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"default attributes are not allowed for singular unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = arbitrary_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"arbitrary attributes are not allowed for singular unused bytes elements",
+			));
+		}
+
 		Ok(Self {
 			attribute: metabyte_attribute,
 			underscore_token: input.parse()?,
@@ -518,6 +595,10 @@ impl ParseWithContext for ArrayUnused {
 				major_opcode_attribute,
 				error_data_attribute,
 				hide_attribute,
+				present_if_attribute,
+				discriminated_by_attribute,
+				default_attribute,
+				arbitrary_attribute,
 			},
 			bracket_token,
 			maps,
@@ -576,6 +657,34 @@ impl ParseWithContext for ArrayUnused {
 			));
 		}
 
+		if let Some(attribute) = present_if_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"present if attributes are not allowed for array-type unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = discriminated_by_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"discriminated by attributes are not allowed for array-type unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = default_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"default attributes are not allowed for array-type unused bytes elements",
+			));
+		}
+
+		if let Some(attribute) = arbitrary_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"arbitrary attributes are not allowed for array-type unused bytes elements",
+			));
+		}
+
 		Ok(Self {
 			formatted: format_ident!("unused_{}", unused_index),
 
@@ -608,9 +717,10 @@ impl ParseWithContext for UnusedContent {
 				last_element: false,
 			}
 		} else {
-			Self::Source(Box::new(
-				input.parse_with(((let_map, Some(field_map)), definition_type))?,
-			))
+			let source = input.parse_with(((let_map, Some(field_map)), definition_type))?;
+			reject_request_arg(&source)?;
+
+			Self::Source(Box::new(source))
 		})
 	}
 }
@@ -630,6 +740,10 @@ impl ParseWithContext for Let {
 				major_opcode_attribute,
 				error_data_attribute,
 				hide_attribute,
+				present_if_attribute,
+				discriminated_by_attribute,
+				default_attribute,
+				arbitrary_attribute,
 			},
 			let_map,
 			definition_type,
@@ -673,6 +787,34 @@ impl ParseWithContext for Let {
 			));
 		}
 
+		if let Some(attribute) = present_if_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"present if attributes are not allowed for let elements",
+			));
+		}
+
+		if let Some(attribute) = discriminated_by_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"discriminated by attributes are not allowed for let elements",
+			));
+		}
+
+		if let Some(attribute) = default_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"default attributes are not allowed for let elements",
+			));
+		}
+
+		if let Some(attribute) = arbitrary_attribute {
+			return Err(syn::Error::new(
+				attribute.span(),
+				"arbitrary attributes are not allowed for let elements",
+			));
+		}
+
 		let let_token = input.parse()?;
 
 		let ident: Ident = input.parse()?;
@@ -682,6 +824,7 @@ impl ParseWithContext for Let {
 		let equals_token = input.parse()?;
 
 		let source = input.parse_with(((&*let_map, None), definition_type))?;
+		reject_request_arg(&source)?;
 
 		let_map.insert(ident.to_string(), r#type.to_owned());
 
@@ -722,6 +865,10 @@ impl ParseWithContext for Field {
 				major_opcode_attribute,
 				error_data_attribute,
 				hide_attribute,
+				present_if_attribute,
+				discriminated_by_attribute,
+				default_attribute,
+				arbitrary_attribute,
 			},
 			map,
 		): Self::Context<'_>,
@@ -729,6 +876,32 @@ impl ParseWithContext for Field {
 	where
 		Self: Sized,
 	{
+		if let Some(attribute) = &present_if_attribute
+			&& context_attribute.is_some()
+		{
+			return Err(syn::Error::new(
+				attribute.span(),
+				"present if attributes cannot be used together with context attributes",
+			));
+		}
+
+		if let Some(attribute) = &discriminated_by_attribute {
+			if context_attribute.is_some() {
+				return Err(syn::Error::new(
+					attribute.span(),
+					"discriminated by attributes cannot be used together with context attributes",
+				));
+			}
+
+			if present_if_attribute.is_some() {
+				return Err(syn::Error::new(
+					attribute.span(),
+					"discriminated by attributes cannot be used together with present if \
+					 attributes",
+				));
+			}
+		}
+
 		let visibility = input.parse()?;
 
 		let id = match element_type {
@@ -759,6 +932,10 @@ impl ParseWithContext for Field {
 			major_opcode_attribute,
 			error_data_attribute,
 			hide_attribute,
+			present_if_attribute,
+			discriminated_by_attribute,
+			default_attribute,
+			arbitrary_attribute,
 
 			visibility,
 			id,
@@ -110,10 +110,25 @@ pub fn derive_wrap(item: TokenStream) -> TokenStream {
 
 	let integer_type = integer_type(&item.data);
 
+	let fields = match &item.data {
+		Data::Struct(r#struct) => &r#struct.fields,
+		Data::Enum(_) | Data::Union(_) => unimplemented!("only structs are supported"),
+	};
+
+	let name = names(fields);
+	let cons = pat_cons(fields);
+
 	let expanded = quote! {
 		#[automatically_derived]
 		impl #impl_generics ::xrbk::Wrap for #ident	#type_generics #where_clause {
 			type Integer = #integer_type;
+			type Error = ::core::convert::Infallible;
+
+			fn try_wrap(integer: Self::Integer) -> Result<Self, Self::Error> {
+				let #name = integer;
+
+				Ok(Self #cons)
+			}
 		}
 	};
 
@@ -156,7 +171,7 @@ pub fn derive_readable(item: TokenStream) -> TokenStream {
 	// TODO: add generic bounds
 	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
 
-	let reads = derive_reads(&item.attrs, &item.data);
+	let reads = derive_reads(ident, &item.attrs, &item.data);
 
 	quote!(
 		#[automatically_derived]
@@ -215,6 +230,62 @@ pub fn derive_constant_x11_size(item: TokenStream) -> TokenStream {
 
 /// Derive XRB-related traits for structs and enums.
 ///
+/// If `X11Size` is derived for a struct, request, reply, or event, but
+/// `ConstantX11Size` is not, `ConstantX11Size` is implemented anyway whenever
+/// every element has a constant size. Deriving `?ConstantX11Size` does the
+/// same without requiring `X11Size` to be derived alongside it, so a single
+/// derive list can be shared by definitions of both constant and variable
+/// sizes. Deriving `ConstantX11Size` itself generates an error naming every
+/// element which does not have a constant size.
+///
+/// If `Writable` is derived for a request, `WritableRequest` is implemented
+/// too, so that the request's length can be written in the extended form
+/// defined by the BIG-REQUESTS extension. The `Readable` implementation of a
+/// request accepts either form.
+///
+/// `ReadableRef` can be derived for a struct with a lifetime parameter. The
+/// first lifetime parameter is the lifetime of the bytes it is read from:
+/// fields are read with `ReadableRef`, or with `ReadableRefWithContext` if
+/// they have a `#[context(...)]` attribute, so a `&'a [u8]` field borrows its
+/// bytes rather than copying them.
+///
+/// An `ExtensionRequest` is given only its minor opcode, since the major
+/// opcode of an extension is assigned by the X server. It implements
+/// `ExtensionRequest` rather than `Request`, and deriving `Writable`
+/// implements `WritableExtensionRequest`, which writes the request with a
+/// major opcode given at runtime. Its `Readable` implementation reads neither
+/// opcode.
+///
+/// The `Writable` and `Readable` implementations of requests, replies, events,
+/// and errors are instrumented with `tracing` spans, and emit a `trace` event
+/// once the message has been written or read, if the crate in which they are
+/// defined enables a `tracing` feature. Otherwise, no instrumentation is
+/// generated.
+///
+/// A request marked with `#[x11_compat]` gets a deprecated `x11_compat`
+/// constructor which takes its fields in order, each parameter named after
+/// the first `#[doc(alias(...))]` of its field. Optional values which are
+/// `None` when zero are taken as their raw integers, so code ported from
+/// the C bindings can pass `0` as it did before.
+///
+/// A request marked with `#[builder]` gets a `new` constructor which takes
+/// only the fields which have no default value, and a `builder` function
+/// returning a builder with which the other fields can be set. [`Option`]
+/// fields default to [`None`], and fields marked with `#[default]` default to
+/// [`Default::default()`]. `#[default(expression)]` gives a field any other
+/// default value.
+///
+/// Every definition which derives both `Readable` and `Writable` gets an
+/// `arbitrary::Arbitrary` implementation, which is only compiled if the
+/// `arbitrary` feature is enabled in the crate in which it is defined. Only
+/// fields are generated, so let elements such as lengths always match the
+/// fields they are derived from, and lists are no longer than the maximum of
+/// the let element giving their length. Fields marked with
+/// `#[present_if(...)]` are only present if their condition field is `true`,
+/// and the discriminant of a field marked with `#[discriminated_by(...)]` is
+/// chosen to match its value. `#[arbitrary(with = function)]` generates a
+/// field, or a whole definition, with the given function instead.
+///
 /// > **<sup>Syntax</sup>**\
 /// > _`derive_xrb!`_ :\
 /// > &nbsp;&nbsp; _Definition_<sup>\*</sup>
@@ -244,9 +315,13 @@ pub fn derive_constant_x11_size(item: TokenStream) -> TokenStream {
 /// > _Request_ :\
 /// > &nbsp;&nbsp; [_OuterAttribute_]<sup>\*</sup> [_Visibility_]<sup>?</sup>
 /// > _StructMetadata_\
-/// > &nbsp;&nbsp; `:` `Request` _Opcodes_ _ReplyType_<sup>?</sup>\
+/// > &nbsp;&nbsp; `:` _RequestKind_ _ReplyType_<sup>?</sup>\
 /// > &nbsp;&nbsp; _StructlikeContent_
 /// >
+/// > _RequestKind_ :\
+/// > &nbsp;&nbsp; &nbsp;&nbsp; ( `Request` _Opcodes_ )\
+/// > &nbsp;&nbsp; | ( `ExtensionRequest` `(` [_Expression_] `)` )
+/// >
 /// > _Opcodes_ :\
 /// > &nbsp;&nbsp; `(` [_Expression_] ( `,` [_Expression_] )<sup>?</sup> `)`
 /// >
@@ -326,7 +401,11 @@ pub fn derive_constant_x11_size(item: TokenStream) -> TokenStream {
 /// > &nbsp;&nbsp; | _ContextAttribute_[^attr-once]\
 /// > &nbsp;&nbsp; | _MetabyteAttribute_[^attr-once]\
 /// > &nbsp;&nbsp; | _SequenceAttribute_[^attr-once][^sequence]\
-/// > &nbsp;&nbsp; | _HideAttribute_[^attr-once] )<sup>\*</sup>\
+/// > &nbsp;&nbsp; | _HideAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _PresentIfAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _DiscriminatedByAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _DefaultAttribute_[^attr-once][^default]\
+/// > &nbsp;&nbsp; | _ArbitraryAttribute_[^attr-once] )<sup>\*</sup>\
 /// > &nbsp;&nbsp; [_Visibility_]<sup>?</sup> [IDENTIFIER] `:` [_Type_]
 /// >
 /// > _UnnamedField_ :\
@@ -334,7 +413,11 @@ pub fn derive_constant_x11_size(item: TokenStream) -> TokenStream {
 /// > &nbsp;&nbsp; | _ContextAttribute_[^attr-once]\
 /// > &nbsp;&nbsp; | _MetabyteAttribute_[^attr-once]\
 /// > &nbsp;&nbsp; | _SequenceAttribute_[^attr-once][^sequence]\
-/// > &nbsp;&nbsp; | _HideAttribute_[^attr-once] )<sup>\*</sup>\
+/// > &nbsp;&nbsp; | _HideAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _PresentIfAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _DiscriminatedByAttribute_[^attr-once]\
+/// > &nbsp;&nbsp; | _DefaultAttribute_[^attr-once][^default]\
+/// > &nbsp;&nbsp; | _ArbitraryAttribute_[^attr-once] )<sup>\*</sup>\
 /// > &nbsp;&nbsp; [_Visibility_]<sup>?</sup> [_Type_]
 /// >
 /// > _LetElement_ :\
@@ -373,6 +456,32 @@ pub fn derive_constant_x11_size(item: TokenStream) -> TokenStream {
 /// > _SequenceAttribute_ :\
 /// > &nbsp;&nbsp; `#` `[` `sequence` `]`
 /// >
+/// > _PresentIfAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `present_if` `(` [IDENTIFIER][^present-if] `)` `]`
+/// >
+/// > [^present-if]: *PresentIfAttribute*s may not be used together with
+/// > *ContextAttribute*s. The identifier must refer to a `bool` field or let
+/// > element which comes before the field.
+/// >
+/// > _DiscriminatedByAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `discriminated_by` `(` [IDENTIFIER][^discriminated-by]
+/// > `)` `]`
+/// >
+/// > [^discriminated-by]: *DiscriminatedByAttribute*s may not be used together
+/// > with *ContextAttribute*s or *PresentIfAttribute*s. The identifier must
+/// > refer to a field or let element which comes before the field, and whose
+/// > type is the field's type's `ReadableWithContext::Context`. The field's
+/// > type must implement `Discriminated`.
+/// >
+/// > _DefaultAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `default` ( `(` [_Expression_] `)` )<sup>?</sup> `]`
+/// >
+/// > [^default]: *DefaultAttribute*s may only be used on named fields in
+/// > requests with a `#[builder]` attribute.
+/// >
+/// > _ArbitraryAttribute_ :\
+/// > &nbsp;&nbsp; `#` `[` `arbitrary` `(` `with` `=` [_Expression_] `)` `]`
+/// >
 /// > _HideAttribute_ :\
 /// > &nbsp;&nbsp; `#` `[` `hide` `(` _HiddenTraits_ `)` `]`
 /// >
@@ -33,11 +33,12 @@ pub struct SourceArg {
 	pub formatted: Option<Ident>,
 }
 
-/// A [`Source`] argument referring to the length of a [`Request`] or [`Reply`].
+/// A [`Source`] argument referring to the number of bytes remaining in a
+/// [`Request`] or [`Reply`].
 ///
 /// > **<sup>Syntax</sup>**\
-/// > _SourceLengthArg_ :\
-/// > &nbsp;&nbsp; `self` `::` `length`
+/// > _SourceRemainingArg_ :\
+/// > &nbsp;&nbsp; `self` `::` `remaining`
 ///
 /// [`Request`]: crate::definition::Request
 /// [`Reply`]: crate::definition::Reply
@@ -47,6 +48,36 @@ pub struct SourceRemainingArg {
 	pub remaining_token: Ident,
 }
 
+/// A [`Source`] argument referring to the length of a [`Request`] or [`Reply`]
+/// as read from its header.
+///
+/// > **<sup>Syntax</sup>**\
+/// > _SourceLengthArg_ :\
+/// > &nbsp;&nbsp; `self` `::` `length`
+///
+/// [`Request`]: crate::definition::Request
+/// [`Reply`]: crate::definition::Reply
+pub struct SourceLengthArg {
+	pub self_token: Token![self],
+	pub double_colon_token: Token![::],
+	pub length_token: Ident,
+}
+
+/// A [`Source`] argument referring to the [`Request`] which generated a
+/// [`Reply`].
+///
+/// > **<sup>Syntax</sup>**\
+/// > _SourceRequestArg_ :\
+/// > &nbsp;&nbsp; `self` `::` `request`
+///
+/// [`Request`]: crate::definition::Request
+/// [`Reply`]: crate::definition::Reply
+pub struct SourceRequestArg {
+	pub self_token: Token![self],
+	pub double_colon_token: Token![::],
+	pub request_token: Ident,
+}
+
 /// Arguments for a [`Source`].
 ///
 /// > **<sup>Syntax</sup>**\
@@ -54,18 +85,29 @@ pub struct SourceRemainingArg {
 /// > &nbsp;&nbsp; _Arg_ ( `,` _Arg_ )<sup>\*</sup> `,`<sup>?</sup>
 /// >
 /// > _Arg_ :\
-/// > &nbsp;&nbsp; [_SourceArg_] | [_SourceRemainingArg_][^usage]
+/// > &nbsp;&nbsp; [_SourceArg_] | [_SourceRemainingArg_][^usage] |
+/// > [_SourceLengthArg_][^usage] | [_SourceRequestArg_][^request-usage]
+/// >
+/// > [^usage]: [_SourceRemainingArg_]s and [_SourceLengthArg_]s may only be
+/// > used within [`Request`]s and [`Reply`]s, and each may be used no more than
+/// > once per _SourceArgs_.
 /// >
-/// > [^usage]: [_SourceRemainingArg_]s may only be used within [`Request`]s and
-/// > [`Reply`]s, and they may be used no more than once per _SourceArgs_.
+/// > [^request-usage]: [_SourceRequestArg_]s may only be used in the
+/// > [`ContextAttribute`]s of [`Reply`]s, and no more than once per
+/// > _SourceArgs_.
 ///
 /// [_SourceArg_]: SourceArg
-/// [_SourceLengthArg_]: SourceRemainingArg
+/// [_SourceRemainingArg_]: SourceRemainingArg
+/// [_SourceLengthArg_]: SourceLengthArg
+/// [_SourceRequestArg_]: SourceRequestArg
 /// [`Request`]: crate::definition::Request
 /// [`Reply`]: crate::definition::Reply
+/// [`ContextAttribute`]: crate::attribute::ContextAttribute
 pub struct SourceArgs {
 	pub args: Punctuated<SourceArg, Token![,]>,
 	pub remaining_arg: Option<(SourceRemainingArg, DefinitionType)>,
+	pub length_arg: Option<SourceLengthArg>,
+	pub request_arg: Option<SourceRequestArg>,
 }
 
 /// An inline function.
@@ -78,7 +120,8 @@ pub struct SourceArgs {
 /// > &nbsp;&nbsp; [_Arg_] ( `,` [_Arg_] )<sup>\*</sup> `,`<sup>?</sup>
 /// >
 /// > [_Arg_] :\
-/// > &nbsp;&nbsp; [_SourceArg_] | [_SourceRemainingArg_]
+/// > &nbsp;&nbsp; [_SourceArg_] | [_SourceRemainingArg_] | [_SourceLengthArg_]
+/// > | [_SourceRequestArg_]
 /// >
 /// > [_SourceArg_] :\
 /// > &nbsp;&nbsp; [IDENTIFIER] ( `:` [_Pattern_] )<sup>?</sup>
@@ -86,10 +129,18 @@ pub struct SourceArgs {
 /// > [_SourceRemainingArg_] :\
 /// > &nbsp;&nbsp; `self` `::` `remaining`
 /// >
+/// > [_SourceLengthArg_] :\
+/// > &nbsp;&nbsp; `self` `::` `length`
+/// >
+/// > [_SourceRequestArg_] :\
+/// > &nbsp;&nbsp; `self` `::` `request`
+/// >
 /// > [_SourceArgs_]: SourceArgs
 /// > [_Arg_]: SourceArgs
 /// > [_SourceArg_]: SourceArg
 /// > [_SourceRemainingArg_]: SourceRemainingArg
+/// > [_SourceLengthArg_]: SourceLengthArg
+/// > [_SourceRequestArg_]: SourceRequestArg
 /// >
 /// > [_Expression_]: https://doc.rust-lang.org/reference/expressions.html
 /// > [IDENTIFIER]: https://doc.rust-lang.org/reference/identifiers.html
@@ -181,6 +232,23 @@ pub struct SourceArgs {
 /// special syntax may be used in any `Source` within that [`Request`] or
 /// [`Reply`].
 ///
+/// # Length arguments
+/// Similarly, the length read from the header of a [`Request`] or [`Reply`]
+/// may be used with `self::length`. It is given as a `usize` in units of 4
+/// bytes, exactly as it appears in the header: for a [`Reply`], that does not
+/// include its first 32 bytes. The sequence number and metabyte of a message
+/// need no special syntax - they are fields, so they may be used like any other
+/// field.
+///
+/// # Request arguments
+/// In the [`ContextAttribute`]s of a [`Reply`], the [`Request`] which generated
+/// that [`Reply`] may be used with `self::request`. It is given as a reference
+/// to the [`Request`] named in the [`Reply`]'s definition, and is named
+/// `request` in the `Source`'s expression.
+///
+/// A [`Reply`] which uses `self::request` is not `Readable` on its own: it is
+/// only read by `Reply::read_with_request`.
+///
 /// # Examples
 /// ```ignore
 /// # extern crate xrbk;
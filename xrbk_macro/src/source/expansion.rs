@@ -18,6 +18,22 @@ impl ToTokens for SourceRemainingArg {
 	}
 }
 
+impl ToTokens for SourceLengthArg {
+	fn to_tokens(&self, tokens: &mut TokenStream2) {
+		self.self_token.to_tokens(tokens);
+		self.double_colon_token.to_tokens(tokens);
+		self.length_token.to_tokens(tokens);
+	}
+}
+
+impl ToTokens for SourceRequestArg {
+	fn to_tokens(&self, tokens: &mut TokenStream2) {
+		self.self_token.to_tokens(tokens);
+		self.double_colon_token.to_tokens(tokens);
+		self.request_token.to_tokens(tokens);
+	}
+}
+
 impl ToTokens for SourceArg {
 	fn to_tokens(&self, tokens: &mut TokenStream2) {
 		let r#type = &self.r#type;
@@ -44,6 +60,16 @@ impl ToTokens for SourceArgs {
 			tokens.append_tokens(quote!(#remaining_token: usize, ));
 		}
 
+		if let Some(SourceLengthArg { length_token, .. }) = &self.length_arg {
+			tokens.append_tokens(quote!(#length_token: usize, ));
+		}
+
+		// `__Request` is a type alias for the reply's request, declared in the
+		// body of `Reply::read_with_request`.
+		if let Some(SourceRequestArg { request_token, .. }) = &self.request_arg {
+			tokens.append_tokens(quote!(#request_token: &__Request, ));
+		}
+
 		for pair in self.args.pairs() {
 			let (arg, comma) = match pair {
 				Pair::Punctuated(arg, comma) => (arg, Some(comma)),
@@ -60,15 +86,25 @@ impl SourceArgs {
 	pub fn formatted_tokens(&self, tokens: &mut TokenStream2) {
 		if let Some((_, definition_type)) = &self.remaining_arg {
 			match definition_type {
-				DefinitionType::Request => quote!((((length - 1) as usize) * 4) - size,),
-				DefinitionType::Reply => {
-					quote!(((length as usize) * 4) + (32 - 8) - size,)
-				},
+				// `size` includes the header, which is included in a request's
+				// `length`...
+				DefinitionType::Request => quote!(((length as usize) * 4) - size,),
+				// ...but only the bytes following the first 32 bytes are
+				// included in a reply's `length`.
+				DefinitionType::Reply => quote!(((length as usize) * 4) + 32 - size,),
 				_ => unreachable!(),
 			}
 			.to_tokens(tokens);
 		}
 
+		if self.length_arg.is_some() {
+			quote!(length as usize,).to_tokens(tokens);
+		}
+
+		if self.request_arg.is_some() {
+			quote!(request,).to_tokens(tokens);
+		}
+
 		for pair in self.args.pairs() {
 			let (arg, comma) = match pair {
 				Pair::Punctuated(arg, comma) => (arg, Some(comma)),
@@ -87,6 +123,23 @@ impl Source {
 	pub fn function_to_tokens(
 		&self, tokens: &mut TokenStream2, attributes: Option<&Vec<Attribute>>, ident: &Ident,
 		return_type: TokenStream2,
+	) {
+		self.generic_function_to_tokens(
+			tokens,
+			attributes,
+			ident,
+			TokenStream2::new(),
+			return_type,
+		);
+	}
+
+	/// Expands this `Source` as a function with the given generic parameters.
+	///
+	/// `generics` are the generic parameters of the function, including the
+	/// angle brackets, or nothing if it has none.
+	pub fn generic_function_to_tokens(
+		&self, tokens: &mut TokenStream2, attributes: Option<&Vec<Attribute>>, ident: &Ident,
+		generics: TokenStream2, return_type: TokenStream2,
 	) {
 		let args = self.args.as_ref().map(|(args, ..)| args);
 		let expr = &self.expr;
@@ -100,7 +153,7 @@ impl Source {
 		tokens.append_tokens({
 			quote_spanned!(ident.span()=>
 				#[inline]
-				fn #ident(#args) -> #return_type {
+				fn #ident #generics(#args) -> #return_type {
 					#expr
 				}
 			)
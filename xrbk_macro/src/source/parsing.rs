@@ -84,6 +84,62 @@ impl Parse for SourceRemainingArg {
 	}
 }
 
+impl Parse for SourceLengthArg {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let self_token = input.parse()?;
+		let double_colon_token = input.parse()?;
+
+		let length_token = {
+			let ident: Ident = input.parse()?;
+
+			if ident != "length" {
+				return Err(Error::new(ident.span(), "expected `length`"));
+			}
+
+			ident
+		};
+
+		Ok(Self {
+			self_token,
+			double_colon_token,
+			length_token,
+		})
+	}
+}
+
+impl Parse for SourceRequestArg {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let self_token = input.parse()?;
+		let double_colon_token = input.parse()?;
+
+		let request_token = {
+			let ident: Ident = input.parse()?;
+
+			if ident != "request" {
+				return Err(Error::new(ident.span(), "expected `request`"));
+			}
+
+			ident
+		};
+
+		Ok(Self {
+			self_token,
+			double_colon_token,
+			request_token,
+		})
+	}
+}
+
+/// Whether the argument at the start of the `input` is `self::` followed by
+/// the given `name`.
+fn peek_self_arg(input: ParseStream, name: &str) -> bool {
+	let fork = input.fork();
+
+	fork.parse::<Token![self]>().is_ok()
+		&& fork.parse::<Token![::]>().is_ok()
+		&& fork.parse::<Ident>().is_ok_and(|ident| ident == name)
+}
+
 impl ParseWithContext for SourceArgs {
 	type Context<'a> = ((IdentMap<'a>, Option<IdentMap<'a>>), DefinitionType);
 
@@ -95,10 +151,40 @@ impl ParseWithContext for SourceArgs {
 
 		let mut args = Punctuated::new();
 		let mut remaining_arg = None;
+		let mut length_arg = None;
+		let mut request_arg = None;
 
 		while input.peek(Ident) || (definition_type.remaining_syntax() && input.peek(Token![self]))
 		{
-			if definition_type.remaining_syntax() && input.peek(Token![self]) {
+			if matches!(definition_type, DefinitionType::Reply) && peek_self_arg(input, "request") {
+				if request_arg.is_some() {
+					let request_arg2: SourceRequestArg = input.parse()?;
+
+					return Err(Error::new(request_arg2.span(), "duplicate request argument"));
+				}
+
+				request_arg = Some(input.parse()?);
+
+				if input.peek(Token![,]) {
+					input.parse::<Token![,]>()?;
+				} else {
+					break;
+				}
+			} else if definition_type.remaining_syntax() && peek_self_arg(input, "length") {
+				if length_arg.is_some() {
+					let length_arg2: SourceLengthArg = input.parse()?;
+
+					return Err(Error::new(length_arg2.span(), "duplicate length argument"));
+				}
+
+				length_arg = Some(input.parse()?);
+
+				if input.peek(Token![,]) {
+					input.parse::<Token![,]>()?;
+				} else {
+					break;
+				}
+			} else if definition_type.remaining_syntax() && input.peek(Token![self]) {
 				if remaining_arg.is_some() {
 					let remaining_arg2: SourceRemainingArg = input.parse()?;
 
@@ -129,6 +215,8 @@ impl ParseWithContext for SourceArgs {
 		Ok(Self {
 			args,
 			remaining_arg,
+			length_arg,
+			request_arg,
 		})
 	}
 }
@@ -0,0 +1,30 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+#[test]
+fn ui() {
+	let tests = trybuild::TestCases::new();
+
+	tests.pass("tests/ui/inferred_constant_x11_size.rs");
+	tests.pass("tests/ui/maybe_constant_x11_size.rs");
+	tests.compile_fail("tests/ui/constant_x11_size_context_field.rs");
+	tests.compile_fail("tests/ui/constant_x11_size_variable_field.rs");
+	tests.compile_fail("tests/ui/constant_x11_size_unused_bytes.rs");
+
+	tests.compile_fail("tests/ui/metabyte_too_large.rs");
+	tests.compile_fail("tests/ui/metabyte_duplicate.rs");
+	tests.compile_fail("tests/ui/metabyte_basic_struct.rs");
+	tests.compile_fail("tests/ui/metabyte_minor_opcode.rs");
+	tests.compile_fail("tests/ui/sequence_duplicate.rs");
+	tests.compile_fail("tests/ui/sequence_on_request.rs");
+	tests.compile_fail("tests/ui/sequence_not_u16.rs");
+	tests.compile_fail("tests/ui/sequence_metabyte.rs");
+	tests.compile_fail("tests/ui/sequence_derive_partial_eq.rs");
+
+	tests.compile_fail("tests/ui/default_without_builder.rs");
+	tests.compile_fail("tests/ui/discriminated_by_with_context.rs");
+
+	tests.compile_fail("tests/ui/readable_ref_without_lifetime.rs");
+	tests.compile_fail("tests/ui/readable_ref_enum.rs");
+}
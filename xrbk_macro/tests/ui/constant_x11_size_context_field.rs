@@ -0,0 +1,17 @@
+// The generated code checks for the `arbitrary` and `dyn-buffers` features.
+#![allow(unexpected_cfgs)]
+
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, ConstantX11Size, Readable, Writable)]
+	pub struct Name {
+		#[allow(clippy::cast_possible_truncation)]
+		let name_len: u8 = name => name.len() as u8,
+
+		#[context(name_len => usize::from(*name_len))]
+		pub name: Vec<u8>,
+	}
+}
+
+fn main() {}
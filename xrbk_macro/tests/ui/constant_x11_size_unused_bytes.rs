@@ -0,0 +1,15 @@
+// The generated code checks for the `arbitrary` and `dyn-buffers` features.
+#![allow(unexpected_cfgs)]
+
+use xrbk::pad;
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, ConstantX11Size, Readable, Writable)]
+	pub struct Name {
+		pub name: [u8; 3],
+		[_; name => pad(name)],
+	}
+}
+
+fn main() {}
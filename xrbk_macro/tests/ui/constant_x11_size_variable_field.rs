@@ -0,0 +1,46 @@
+// The generated code checks for the `arbitrary` and `dyn-buffers` features.
+#![allow(unexpected_cfgs)]
+
+use xrbk::{Buf, BufMut, ReadResult, Readable, Writable, WriteResult, X11Size};
+use xrbk_macro::derive_xrb;
+
+/// A type with a variable size which is read without context.
+pub struct Terminated(Vec<u8>);
+
+impl X11Size for Terminated {
+	fn x11_size(&self) -> usize {
+		self.0.len() + 1
+	}
+}
+
+impl Readable for Terminated {
+	fn read_from(buf: &mut impl Buf) -> ReadResult<Self> {
+		let mut bytes = vec![];
+
+		loop {
+			match buf.get_u8() {
+				0 => break Ok(Self(bytes)),
+				byte => bytes.push(byte),
+			}
+		}
+	}
+}
+
+impl Writable for Terminated {
+	fn write_to(&self, buf: &mut impl BufMut) -> WriteResult {
+		buf.put_slice(&self.0);
+		buf.put_u8(0);
+
+		Ok(())
+	}
+}
+
+derive_xrb! {
+	#[derive(X11Size, ConstantX11Size, Readable, Writable)]
+	pub struct Message {
+		pub id: u32,
+		pub text: Terminated,
+	}
+}
+
+fn main() {}
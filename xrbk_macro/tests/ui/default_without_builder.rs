@@ -0,0 +1,12 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Bell: Request(104) {
+		#[metabyte]
+		#[default]
+		pub percent: i8,
+	}
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Message: Event(33) {
+		#[sequence]
+		pub sequence: u16,
+
+		#[metabyte]
+		pub format: u8,
+
+		#[discriminated_by(format)]
+		#[context(format => *format)]
+		pub data: [u8; 4],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use xrbk::{ConstantX11Size, X11Size};
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Point {
+		pub x: i16,
+		pub y: i16,
+		[_; 4],
+	}
+
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Points {
+		#[allow(clippy::cast_possible_truncation)]
+		let points_len: u8 = points => points.len() as u8,
+
+		#[context(points_len => usize::from(*points_len))]
+		pub points: Vec<Point>,
+	}
+}
+
+fn constant_size<T: ConstantX11Size>() -> usize {
+	T::X11_SIZE
+}
+
+fn main() {
+	assert_eq!(constant_size::<Point>(), 8);
+	assert_eq!(Point { x: 1, y: 2 }.x11_size(), 8);
+
+	// `Points` does not implement `ConstantX11Size`, but still has a size.
+	assert_eq!(Points { points: vec![] }.x11_size(), 1);
+}
@@ -0,0 +1,32 @@
+use xrbk::{pad, ConstantX11Size, X11Size};
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, ?ConstantX11Size, Readable, Writable)]
+	pub struct Point {
+		pub x: i16,
+		pub y: i16,
+	}
+
+	// `ConstantX11Size` is skipped because `names` is read with context and
+	// its padding depends on it.
+	#[derive(X11Size, ?ConstantX11Size, Readable, Writable)]
+	pub struct Names {
+		#[allow(clippy::cast_possible_truncation)]
+		let names_len: u8 = names => names.len() as u8,
+
+		#[context(names_len => usize::from(*names_len))]
+		pub names: Vec<u8>,
+		[_; names => pad(names)],
+	}
+}
+
+fn constant_size<T: ConstantX11Size>() -> usize {
+	T::X11_SIZE
+}
+
+fn main() {
+	assert_eq!(constant_size::<Point>(), 4);
+
+	assert_eq!(Names { names: vec![1, 2] }.x11_size(), 5);
+}
@@ -0,0 +1,14 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Point {
+		#[metabyte]
+		pub flags: u8,
+
+		pub x: i16,
+		pub y: i16,
+	}
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Motion: Event(6) {
+		#[sequence]
+		pub sequence: u16,
+
+		#[metabyte]
+		pub detail: u8,
+		#[metabyte]
+		pub same_screen: bool,
+
+		[_; ..],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct SelectInput: Request(130, 4) {
+		#[metabyte]
+		pub enable: bool,
+
+		pub mask: u32,
+	}
+}
+
+fn main() {}
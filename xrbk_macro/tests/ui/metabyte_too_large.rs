@@ -0,0 +1,37 @@
+// The generated code checks for XRB's `tracing` and `trace-wire` features.
+#![allow(unexpected_cfgs)]
+
+use xrb::message::Event;
+use xrbk_macro::derive_xrb;
+
+// A stand-in for the `Event` trait defined in XRB, which is sealed.
+mod xrb {
+	pub mod message {
+		pub mod __private {
+			pub trait Sealed {}
+		}
+
+		pub trait Event: __private::Sealed {
+			const CODE: u8;
+
+			fn sequence(&self) -> Option<u16>;
+		}
+	}
+}
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Motion: Event(6) {
+		#[sequence]
+		pub sequence: u16,
+
+		#[metabyte]
+		pub detail: u16,
+
+		pub x: i16,
+		pub y: i16,
+		[_; ..],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(ReadableRef)]
+	pub enum Name<'a> {
+		Empty,
+		Bytes(&'a [u8]),
+	}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(ReadableRef)]
+	pub struct Point {
+		pub x: i16,
+		pub y: i16,
+	}
+}
+
+fn main() {}
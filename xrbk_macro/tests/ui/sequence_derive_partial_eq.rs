@@ -0,0 +1,13 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(Debug, PartialEq, X11Size, Readable, Writable)]
+	pub struct Motion: Event(6) {
+		#[sequence]
+		pub sequence: u16,
+
+		[_; ..],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Motion: Event(6) {
+		#[sequence]
+		pub sequence: u16,
+		#[sequence]
+		pub other_sequence: u16,
+
+		[_; ..],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Motion: Event(6) {
+		#[metabyte]
+		#[sequence]
+		pub sequence: u16,
+
+		[_; ..],
+	}
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	#[derive(X11Size, Readable, Writable)]
+	pub struct Bell: Request(104) {
+		#[sequence]
+		pub sequence: u16,
+	}
+}
+
+fn main() {}
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Checks that XRBK and the code generated by `derive_xrb!` can be used by a
+//! `no_std` crate which has `alloc`.
+//!
+//! This crate depends on XRBK without its default `std` feature. The types
+//! below are shaped like the contents of X11 messages: a fixed-size structure,
+//! a list with a length written before it, and an enum.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use xrbk::pad;
+use xrbk_macro::derive_xrb;
+
+derive_xrb! {
+	/// A rectangle, as found in many X11 requests.
+	#[derive(Clone, Debug, PartialEq, Eq, X11Size, ConstantX11Size, Readable, Writable)]
+	pub struct Rectangle {
+		pub x: i16,
+		pub y: i16,
+		pub width: u16,
+		pub height: u16,
+	}
+
+	/// A list of rectangles and a name, each written after their length.
+	#[derive(Clone, Debug, PartialEq, Eq, X11Size, Readable, Writable)]
+	pub struct Rectangles {
+		#[allow(clippy::cast_possible_truncation)]
+		let rectangles_len: u16 = rectangles => rectangles.len() as u16,
+		#[allow(clippy::cast_possible_truncation)]
+		let name_len: u16 = name => name.len() as u16,
+
+		#[context(rectangles_len => usize::from(*rectangles_len))]
+		pub rectangles: Vec<Rectangle>,
+
+		#[context(name_len => usize::from(*name_len))]
+		pub name: Vec<u8>,
+		[_; name => pad(name)],
+	}
+
+	/// Either a single rectangle or a list of them.
+	#[derive(Clone, Debug, PartialEq, Eq, X11Size, Readable, Writable)]
+	pub enum Shape {
+		Rectangle(Rectangle),
+		Rectangles(Rectangles),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use alloc::vec;
+	use xrbk::{ReadError, Readable, Writable, X11Size};
+
+	/// Writes the given `value`, then checks that it is read back unchanged.
+	fn round_trip<T: Readable + Writable + X11Size + PartialEq + core::fmt::Debug>(value: &T) {
+		let mut bytes = vec![];
+		value.write_to(&mut bytes).unwrap();
+
+		assert_eq!(bytes.len(), value.x11_size());
+		assert_eq!(&T::read_from(&mut &bytes[..]).unwrap(), value);
+	}
+
+	fn rectangles() -> Rectangles {
+		Rectangles {
+			rectangles: vec![
+				Rectangle {
+					x: 0,
+					y: -1,
+					width: 2,
+					height: 3,
+				},
+				Rectangle {
+					x: 4,
+					y: 5,
+					width: 6,
+					height: 7,
+				},
+			],
+			name: b"shape".to_vec(),
+		}
+	}
+
+	#[test]
+	fn test_round_trip() {
+		round_trip(&rectangles());
+		round_trip(&Shape::Rectangles(rectangles()));
+		round_trip(&Shape::Rectangle(Rectangle {
+			x: -8,
+			y: 9,
+			width: 10,
+			height: 11,
+		}));
+	}
+
+	#[test]
+	fn test_layout() {
+		let mut bytes = vec![];
+		rectangles().write_to(&mut bytes).unwrap();
+
+		#[rustfmt::skip]
+		assert_eq!(
+			bytes,
+			[
+				0, 2, // rectangles_len
+				0, 5, // name_len
+				0, 0, 0xff, 0xff, 0, 2, 0, 3, // rectangles[0]
+				0, 4, 0, 5, 0, 6, 0, 7, // rectangles[1]
+				b's', b'h', b'a', b'p', b'e', // name
+				0, 0, 0, // padding
+			]
+		);
+	}
+
+	#[test]
+	fn test_errors() {
+		let error = Shape::read_from(&mut &[1, 0, 1][..]).unwrap_err();
+
+		assert!(matches!(
+			error.root_cause(),
+			ReadError::UnexpectedEndOfData { .. },
+		));
+		assert!(core::error::Error::source(&error).is_some());
+	}
+}